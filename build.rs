@@ -0,0 +1,245 @@
+//! Build script that checks the handlebars templates and embeds them in the
+//! binary.
+//!
+//! Every `.hbs` file under `templates` is parsed, and the build fails if any
+//! of them has a syntax error, calls a helper that is not registered, or
+//! includes a partial that does not exist. The templates are then written to
+//! `templates.rs` in the output directory as a list of names and sources,
+//! which release builds register instead of reading the templates directory.
+
+use handlebars::Handlebars;
+use std::collections::HashSet;
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The directory templates are read from.
+const TEMPLATES_DIR: &'static str = "templates";
+
+/// The file the custom helpers are registered in.
+const HELPERS_FILE: &'static str = "src/templates/helpers.rs";
+
+/// Helpers that come with handlebars.
+const BUILTIN_HELPERS: &[&'static str] = &[
+    "if", "unless", "each", "with", "lookup", "raw", "log", "eq", "ne", "gt", "gte", "lt", "lte",
+    "and", "or", "not", "len",
+];
+
+/// Find every template under a directory, named by its path from the
+/// templates directory without the extension, like `admin/index`.
+fn find_templates(dir: &Path, templates: &mut Vec<(String, PathBuf)>) {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("Could not read {}: {}", dir.display(), e))
+        .map(|entry| entry.expect("Could not read directory entry").path())
+        .collect();
+    // Keep the generated file the same from build to build.
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            find_templates(&path, templates);
+        } else if path.extension().map_or(false, |ext| ext == "hbs") {
+            let name: String = path
+                .strip_prefix(TEMPLATES_DIR)
+                .expect("Template outside of the templates directory")
+                .with_extension("")
+                .components()
+                .map(|part| part.as_os_str().to_string_lossy().into_owned())
+                .collect::<Vec<String>>()
+                .join("/");
+            templates.push((name, path));
+        }
+    }
+}
+
+/// The names of the helpers registered in the helpers module.
+fn custom_helpers() -> Vec<String> {
+    let source: String = fs::read_to_string(HELPERS_FILE)
+        .unwrap_or_else(|e| panic!("Could not read {}: {}", HELPERS_FILE, e));
+    return source
+        .split("register_helper(")
+        .skip(1)
+        .filter_map(|call| call.split('"').nth(1))
+        .map(String::from)
+        .collect();
+}
+
+/// Split the inside of a tag into words, strings, and parentheses.
+fn tokenize(tag: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut chars = tag.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        } else if c == '(' || c == ')' {
+            tokens.push(c.to_string());
+        } else if c == '"' || c == '\'' {
+            let mut string = String::from(c);
+            for next in chars.by_ref() {
+                string.push(next);
+                if next == c {
+                    break;
+                }
+            }
+            tokens.push(string);
+        } else {
+            let mut word = String::from(c);
+            while let Some(&next) = chars.peek() {
+                if next.is_whitespace() || next == '(' || next == ')' {
+                    break;
+                }
+                word.push(next);
+                chars.next();
+            }
+            tokens.push(word);
+        }
+    }
+    return tokens;
+}
+
+/// Check the helpers and partials used by one template. Problems are added to
+/// `problems`.
+fn check_template(
+    name: &str,
+    source: &str,
+    helpers: &HashSet<String>,
+    partials: &HashSet<String>,
+    problems: &mut Vec<String>,
+) {
+    // Partials defined inline in this template.
+    let mut inline: HashSet<String> = HashSet::new();
+    // Partial includes, checked once all inline partials are known.
+    let mut included: Vec<String> = Vec::new();
+
+    let mut rest: &str = source;
+    while let Some(start) = rest.find("{{") {
+        rest = &rest[start..];
+
+        // Skip comments, which may contain anything.
+        let close: &str = if rest.starts_with("{{!--") {
+            "--}}"
+        } else if rest.starts_with("{{!") {
+            "}}"
+        } else {
+            ""
+        };
+        if !close.is_empty() {
+            match rest.find(close) {
+                Some(end) => rest = &rest[end + close.len()..],
+                None => break,
+            }
+            continue;
+        }
+
+        let end: usize = match rest.find("}}") {
+            Some(end) => end,
+            None => break,
+        };
+        let tag: &str = rest[2..end]
+            .trim_start_matches('{')
+            .trim_matches('~')
+            .trim();
+        rest = &rest[end + 2..];
+
+        let (kind, body): (char, &str) = match tag.chars().next() {
+            Some(c @ '#') | Some(c @ '^') | Some(c @ '/') | Some(c @ '>') => (c, tag[1..].trim()),
+            _ => (' ', tag),
+        };
+        let tokens: Vec<String> = tokenize(body);
+        let first: &str = match tokens.first() {
+            Some(first) => first.as_str(),
+            None => continue,
+        };
+
+        match kind {
+            // Closing tags name a block that was already checked.
+            '/' => continue,
+            '>' => {
+                if first != "(" && first != "@partial-block" {
+                    included.push(first.trim_matches(|c| c == '"' || c == '\'').to_string());
+                }
+            }
+            '#' if first == "*inline" => {
+                if let Some(partial) = tokens.get(1) {
+                    inline.insert(partial.trim_matches(|c| c == '"' || c == '\'').to_string());
+                }
+                continue;
+            }
+            _ => {
+                // `else` may start the inverse of another block helper.
+                let (call, args): (&str, usize) = match (first, tokens.get(1)) {
+                    ("else", Some(helper)) => (helper.as_str(), tokens.len() - 2),
+                    _ => (first, tokens.len() - 1),
+                };
+                // Blocks and expressions with arguments call a helper. Ones
+                // without are looking up a field.
+                let is_call: bool = kind == '#' || args > 0;
+                if is_call && call != "(" && call != "else" && !helpers.contains(call) {
+                    problems.push(format!("{}: unknown helper `{}`", name, call));
+                }
+            }
+        }
+
+        // Every subexpression calls a helper.
+        for pair in tokens.windows(2) {
+            if pair[0] == "(" && !helpers.contains(pair[1].as_str()) {
+                problems.push(format!("{}: unknown helper `{}`", name, pair[1]));
+            }
+        }
+    }
+
+    for partial in included {
+        if !partials.contains(&partial) && !inline.contains(&partial) {
+            problems.push(format!("{}: unknown partial `{}`", name, partial));
+        }
+    }
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", TEMPLATES_DIR);
+    println!("cargo:rerun-if-changed={}", HELPERS_FILE);
+
+    let mut templates: Vec<(String, PathBuf)> = Vec::new();
+    find_templates(Path::new(TEMPLATES_DIR), &mut templates);
+
+    let helpers: HashSet<String> = BUILTIN_HELPERS
+        .iter()
+        .map(|helper| helper.to_string())
+        .chain(custom_helpers())
+        .collect();
+    let partials: HashSet<String> = templates.iter().map(|(name, _)| name.clone()).collect();
+
+    let mut registry = Handlebars::new();
+    let mut problems: Vec<String> = Vec::new();
+    let mut generated =
+        String::from("/// Templates checked and embedded at build time, by name.\n");
+    generated.push_str("pub const TEMPLATES: &[(&str, &str)] = &[\n");
+
+    for (name, path) in templates.iter() {
+        let source: String = fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Could not read {}: {}", path.display(), e));
+        if let Err(e) = registry.register_template_string(name, source.as_str()) {
+            problems.push(format!("{}: {}", name, e));
+        }
+        check_template(name, source.as_str(), &helpers, &partials, &mut problems);
+
+        let absolute: PathBuf = fs::canonicalize(path).expect("Could not resolve template path");
+        // Writing to a string cannot fail.
+        let _ = writeln!(
+            generated,
+            "    ({:?}, include_str!({:?})),",
+            name,
+            absolute.to_string_lossy()
+        );
+    }
+    generated.push_str("];\n");
+
+    if !problems.is_empty() {
+        panic!("Invalid templates:\n  {}", problems.join("\n  "));
+    }
+
+    let out_dir: String = env::var("OUT_DIR").expect("OUT_DIR is not set");
+    fs::write(Path::new(out_dir.as_str()).join("templates.rs"), generated)
+        .expect("Could not write embedded templates");
+}
@@ -0,0 +1,3 @@
+ALTER TABLE public.semesters DROP COLUMN org_id;
+ALTER TABLE public.projects DROP COLUMN org_id;
+DROP TABLE public.organizations;
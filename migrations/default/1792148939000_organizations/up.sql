@@ -0,0 +1,18 @@
+CREATE TABLE public.organizations (
+    org_id text NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    title text NOT NULL,
+    PRIMARY KEY (org_id)
+);
+
+ALTER TABLE public.projects ADD COLUMN org_id text
+    REFERENCES public.organizations (org_id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+ALTER TABLE public.semesters ADD COLUMN org_id text
+    REFERENCES public.organizations (org_id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+COMMENT ON TABLE public.organizations IS 'Organizations (clubs) that share this Telescope deployment.';
+COMMENT ON COLUMN public.organizations.org_id IS 'Short unique identifier (slug) of the organization.';
+COMMENT ON COLUMN public.organizations.title IS 'Full display name of the organization.';
+COMMENT ON COLUMN public.projects.org_id IS 'The organization this project belongs to.';
+COMMENT ON COLUMN public.semesters.org_id IS 'The organization this semester belongs to.';
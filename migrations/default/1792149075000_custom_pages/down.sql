@@ -0,0 +1,2 @@
+DROP TABLE public.custom_page_revisions;
+DROP TABLE public.custom_pages;
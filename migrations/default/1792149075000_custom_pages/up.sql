@@ -0,0 +1,32 @@
+CREATE TABLE public.custom_pages (
+    slug text NOT NULL,
+    body text NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    is_draft boolean NOT NULL DEFAULT false,
+    nav_order integer NOT NULL DEFAULT 0,
+    show_in_nav boolean NOT NULL DEFAULT false,
+    title text NOT NULL,
+    updated_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (slug)
+);
+
+CREATE TABLE public.custom_page_revisions (
+    revision_id serial NOT NULL,
+    author_id uuid,
+    body text NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    slug text NOT NULL,
+    title text NOT NULL,
+    PRIMARY KEY (revision_id),
+    FOREIGN KEY (author_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE SET NULL,
+    FOREIGN KEY (slug) REFERENCES public.custom_pages (slug) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+COMMENT ON TABLE public.custom_pages IS 'Markdown pages managed by coordinators through the admin panel.';
+COMMENT ON COLUMN public.custom_pages.body IS 'Markdown body of the page.';
+COMMENT ON COLUMN public.custom_pages.is_draft IS 'Drafts are only visible to admins.';
+COMMENT ON COLUMN public.custom_pages.nav_order IS 'Pages are ordered in the navbar by this value.';
+COMMENT ON COLUMN public.custom_pages.show_in_nav IS 'Should this page be linked in the navbar?';
+COMMENT ON COLUMN public.custom_pages.slug IS 'The path of the page, e.g. "faq" for "/faq".';
+COMMENT ON TABLE public.custom_page_revisions IS 'Revision history of custom pages. A revision is saved on every edit.';
+COMMENT ON COLUMN public.custom_page_revisions.author_id IS 'The user who made this revision.';
@@ -0,0 +1,28 @@
+CREATE TABLE public.sponsors (
+    sponsor_id serial NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    description text,
+    end_date date,
+    logo_url text NOT NULL,
+    name text NOT NULL,
+    start_date date NOT NULL,
+    tier integer NOT NULL,
+    website_url text,
+    PRIMARY KEY (sponsor_id)
+);
+
+COMMENT ON TABLE public.sponsors IS 'Organizations that sponsor RCOS. Shown on the sponsors page and in the footer while active.';
+COMMENT ON COLUMN public.sponsors.end_date IS 'The last day the sponsor is shown. Null for no end.';
+COMMENT ON COLUMN public.sponsors.logo_url IS 'URL of the sponsor logo. May be a path under /static.';
+COMMENT ON COLUMN public.sponsors.start_date IS 'The first day the sponsor is shown.';
+COMMENT ON COLUMN public.sponsors.tier IS 'Sponsors are ordered by tier, lowest first.';
+
+-- The sponsors that were listed on the static sponsors page, in the order
+-- they were listed there.
+INSERT INTO public.sponsors (name, description, logo_url, website_url, tier, start_date) VALUES
+    ('RedHat', 'RedHat has funded student projects and learning workshops, including Introduction to Open Source Software.', '/static/sponsors/red-hat.svg', 'https://redhat.com/about', 0, CURRENT_DATE),
+    ('Mozilla', 'Helped with leadership program for mentors.', '/static/sponsors/mozilla.svg', 'https://mozilla.org', 1, CURRENT_DATE),
+    ('Google', 'Donated MAGPis and Cardboard VR.', '/static/sponsors/google.svg', 'https://about.google', 2, CURRENT_DATE),
+    ('Open Source Initiative', 'Educational Institution Member that has provided opportunities for students.', '/static/sponsors/osi.webp', 'https://opensource.org', 3, CURRENT_DATE),
+    ('NSF HFOSS', 'Affliated member with Trinity, Connecticut College and Wesleyan University', '/static/sponsors/hfoss.webp', 'http://hfoss.org', 4, CURRENT_DATE),
+    ('Microsoft', 'Partially Funded Student projects through the Microsoft employee program.', '/static/sponsors/microsoft.svg', 'https://microsoft.com/about', 5, CURRENT_DATE);
@@ -0,0 +1,2 @@
+DROP TABLE public.user_badges;
+DROP TABLE public.badges;
@@ -0,0 +1,35 @@
+CREATE TABLE public.badges (
+    badge_id text NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    description text NOT NULL,
+    icon text NOT NULL,
+    is_automatic boolean NOT NULL DEFAULT false,
+    title text NOT NULL,
+    PRIMARY KEY (badge_id)
+);
+
+CREATE TABLE public.user_badges (
+    user_id uuid NOT NULL,
+    badge_id text NOT NULL,
+    awarded_at timestamptz NOT NULL DEFAULT now(),
+    awarded_by uuid,
+    PRIMARY KEY (user_id, badge_id),
+    FOREIGN KEY (user_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (badge_id) REFERENCES public.badges (badge_id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (awarded_by) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+COMMENT ON TABLE public.badges IS 'Achievements that can be awarded to users.';
+COMMENT ON COLUMN public.badges.badge_id IS 'Short slug identifying the badge.';
+COMMENT ON COLUMN public.badges.icon IS 'Name of the feather icon shown with the badge.';
+COMMENT ON COLUMN public.badges.is_automatic IS 'Whether this badge is awarded by scheduled rules rather than by hand.';
+COMMENT ON TABLE public.user_badges IS 'Badges awarded to users.';
+COMMENT ON COLUMN public.user_badges.awarded_by IS 'The user who awarded this badge by hand. Null for automatic awards.';
+
+-- The badges awarded by the daily badge job. Their IDs are referenced by
+-- Telescope (see src/api/rcos/badges/mod.rs).
+INSERT INTO public.badges (badge_id, title, description, icon, is_automatic) VALUES
+    ('first-commit', 'First Commit', 'Landed a commit on an RCOS project.', 'git-commit', true),
+    ('ten-meetings', 'Ten Meetings', 'Attended ten meetings.', 'calendar', true),
+    ('mentor', 'Mentor', 'Mentored a small group.', 'award', true),
+    ('coordinator-emeritus', 'Coordinator Emeritus', 'Coordinated a semester of RCOS.', 'star', true);
@@ -0,0 +1 @@
+ALTER TABLE public.users DROP COLUMN leaderboard_opt_in;
@@ -0,0 +1,3 @@
+ALTER TABLE public.users ADD COLUMN leaderboard_opt_in boolean NOT NULL DEFAULT false;
+
+COMMENT ON COLUMN public.users.leaderboard_opt_in IS 'Whether the user appears on the public attendance leaderboard.';
@@ -0,0 +1,21 @@
+CREATE TABLE public.presentation_slots (
+    slot_id serial NOT NULL,
+    claimed_by uuid,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    end_date_time timestamptz NOT NULL,
+    location text,
+    project_id integer,
+    reminder_sent boolean NOT NULL DEFAULT false,
+    semester_id text NOT NULL,
+    start_date_time timestamptz NOT NULL,
+    PRIMARY KEY (slot_id),
+    UNIQUE (semester_id, project_id),
+    FOREIGN KEY (claimed_by) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE SET NULL,
+    FOREIGN KEY (project_id) REFERENCES public.projects (project_id) ON UPDATE CASCADE ON DELETE SET NULL,
+    FOREIGN KEY (semester_id) REFERENCES public.semesters (semester_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+COMMENT ON TABLE public.presentation_slots IS 'Time slots for end of semester project presentations.';
+COMMENT ON COLUMN public.presentation_slots.claimed_by IS 'The project lead who claimed this slot.';
+COMMENT ON COLUMN public.presentation_slots.project_id IS 'The project presenting in this slot, if it has been claimed.';
+COMMENT ON COLUMN public.presentation_slots.reminder_sent IS 'Whether presenters have been reminded of this slot.';
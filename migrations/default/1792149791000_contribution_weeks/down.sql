@@ -0,0 +1 @@
+DROP TABLE public.contribution_weeks;
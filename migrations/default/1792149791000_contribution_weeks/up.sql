@@ -0,0 +1,16 @@
+CREATE TABLE public.contribution_weeks (
+    user_id uuid NOT NULL,
+    project_id integer NOT NULL,
+    week_start date NOT NULL,
+    commits integer NOT NULL DEFAULT 0,
+    pull_requests integer NOT NULL DEFAULT 0,
+    updated_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (user_id, project_id, week_start),
+    FOREIGN KEY (user_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES public.projects (project_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+COMMENT ON TABLE public.contribution_weeks IS 'Weekly tallies of repository contributions by project members.';
+COMMENT ON COLUMN public.contribution_weeks.commits IS 'Commits authored on the default branch.';
+COMMENT ON COLUMN public.contribution_weeks.pull_requests IS 'Pull requests merged.';
+COMMENT ON COLUMN public.contribution_weeks.week_start IS 'The Monday this week starts on.';
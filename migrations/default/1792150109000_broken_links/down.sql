@@ -0,0 +1 @@
+DROP TABLE public.broken_links;
@@ -0,0 +1,15 @@
+CREATE TABLE public.broken_links (
+    url text NOT NULL,
+    source_path text NOT NULL,
+    checked_at timestamptz NOT NULL DEFAULT now(),
+    error text,
+    label text NOT NULL,
+    status_code integer,
+    PRIMARY KEY (url, source_path)
+);
+
+COMMENT ON TABLE public.broken_links IS 'Stored links that failed their last health check.';
+COMMENT ON COLUMN public.broken_links.error IS 'Description of the failure.';
+COMMENT ON COLUMN public.broken_links.label IS 'Which link on the page this is, e.g. "Recording".';
+COMMENT ON COLUMN public.broken_links.source_path IS 'Telescope path of the page the link appears on.';
+COMMENT ON COLUMN public.broken_links.status_code IS 'HTTP status of the failed check, if there was a response.';
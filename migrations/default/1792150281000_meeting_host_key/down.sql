@@ -0,0 +1 @@
+ALTER TABLE public.meetings DROP COLUMN host_key;
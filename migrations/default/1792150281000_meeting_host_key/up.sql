@@ -0,0 +1,3 @@
+ALTER TABLE public.meetings ADD COLUMN host_key text;
+
+COMMENT ON COLUMN public.meetings.host_key IS 'Host key of a generated video call, if any.';
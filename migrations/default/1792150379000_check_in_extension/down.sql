@@ -0,0 +1 @@
+ALTER TABLE public.meetings DROP COLUMN check_in_extended_until;
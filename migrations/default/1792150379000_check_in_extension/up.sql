@@ -0,0 +1,3 @@
+ALTER TABLE public.meetings ADD COLUMN check_in_extended_until timestamptz;
+
+COMMENT ON COLUMN public.meetings.check_in_extended_until IS 'Set by the host to keep check-in open past the normal window.';
@@ -0,0 +1,2 @@
+ALTER TABLE public.meeting_attendances DROP COLUMN manual_note;
+ALTER TABLE public.meeting_attendances DROP COLUMN added_by;
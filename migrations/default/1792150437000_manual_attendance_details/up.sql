@@ -0,0 +1,7 @@
+ALTER TABLE public.meeting_attendances ADD COLUMN added_by uuid
+    REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+ALTER TABLE public.meeting_attendances ADD COLUMN manual_note text;
+
+COMMENT ON COLUMN public.meeting_attendances.added_by IS 'The host who added this attendance by hand, if any.';
+COMMENT ON COLUMN public.meeting_attendances.manual_note IS 'Why the attendance was added by hand.';
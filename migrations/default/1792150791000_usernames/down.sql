@@ -0,0 +1,3 @@
+ALTER TABLE public.users DROP COLUMN username_changed_at;
+ALTER TABLE public.users DROP COLUMN username;
+DROP TABLE public.username_redirects;
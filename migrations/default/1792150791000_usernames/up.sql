@@ -0,0 +1,17 @@
+CREATE TABLE public.username_redirects (
+    old_username text NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    user_id uuid NOT NULL,
+    PRIMARY KEY (old_username),
+    FOREIGN KEY (user_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+ALTER TABLE public.users ADD COLUMN username text;
+
+ALTER TABLE public.users ADD COLUMN username_changed_at timestamptz;
+
+ALTER TABLE public.users ADD UNIQUE (username);
+
+COMMENT ON TABLE public.username_redirects IS 'Old usernames that still point to the user who had them';
+COMMENT ON COLUMN public.users.username IS 'Optional unique handle used in profile URLs';
+COMMENT ON COLUMN public.users.username_changed_at IS 'When the username was last changed';
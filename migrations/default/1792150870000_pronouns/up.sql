@@ -0,0 +1,3 @@
+ALTER TABLE public.users ADD COLUMN pronouns text;
+
+COMMENT ON COLUMN public.users.pronouns IS 'Pronouns to show next to the user''s name, e.g. "they/them"';
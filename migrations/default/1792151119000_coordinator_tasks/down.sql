@@ -0,0 +1,2 @@
+DROP TABLE public.coordinator_tasks;
+DROP TYPE public.task_status;
@@ -0,0 +1,24 @@
+CREATE TYPE public.task_status AS ENUM ('todo', 'in_progress', 'done');
+
+CREATE TABLE public.coordinator_tasks (
+    task_id serial NOT NULL,
+    assignee_id uuid,
+    completed_at timestamptz,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    created_by uuid,
+    description text,
+    due_date date,
+    source text,
+    source_key text,
+    status public.task_status NOT NULL DEFAULT 'todo',
+    title text NOT NULL,
+    updated_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (task_id),
+    UNIQUE (source_key),
+    FOREIGN KEY (assignee_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE SET NULL,
+    FOREIGN KEY (created_by) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+COMMENT ON TABLE public.coordinator_tasks IS 'Coordinator to-dos tracked on the task board';
+COMMENT ON COLUMN public.coordinator_tasks.source IS 'What created this task automatically, if anything';
+COMMENT ON COLUMN public.coordinator_tasks.source_key IS 'Unique key of the event that created this task, to avoid duplicates';
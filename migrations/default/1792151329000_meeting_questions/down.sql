@@ -0,0 +1,2 @@
+DROP TABLE public.meeting_question_votes;
+DROP TABLE public.meeting_questions;
@@ -0,0 +1,24 @@
+CREATE TABLE public.meeting_questions (
+    question_id serial NOT NULL,
+    answered_at timestamptz,
+    body text NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    is_answered boolean NOT NULL DEFAULT false,
+    meeting_id integer NOT NULL,
+    user_id uuid NOT NULL,
+    PRIMARY KEY (question_id),
+    FOREIGN KEY (meeting_id) REFERENCES public.meetings (meeting_id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE TABLE public.meeting_question_votes (
+    question_id integer NOT NULL,
+    user_id uuid NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (question_id, user_id),
+    FOREIGN KEY (question_id) REFERENCES public.meeting_questions (question_id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+COMMENT ON TABLE public.meeting_questions IS 'Questions submitted during a meeting';
+COMMENT ON TABLE public.meeting_question_votes IS 'Upvotes on questions asked during meetings';
@@ -0,0 +1,3 @@
+DROP TABLE public.poll_votes;
+DROP TABLE public.poll_options;
+DROP TABLE public.polls;
@@ -0,0 +1,49 @@
+CREATE TABLE public.polls (
+    poll_id serial NOT NULL,
+    closes_at timestamptz,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    created_by uuid,
+    description text,
+    discord_message_id text,
+    is_anonymous boolean NOT NULL DEFAULT false,
+    is_multiple_choice boolean NOT NULL DEFAULT false,
+    meeting_id integer,
+    opens_at timestamptz,
+    title text NOT NULL,
+    PRIMARY KEY (poll_id),
+    FOREIGN KEY (created_by) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE SET NULL,
+    FOREIGN KEY (meeting_id) REFERENCES public.meetings (meeting_id) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+CREATE TABLE public.poll_options (
+    option_id serial NOT NULL,
+    poll_id integer NOT NULL,
+    position integer NOT NULL,
+    text text NOT NULL,
+    PRIMARY KEY (option_id),
+    FOREIGN KEY (poll_id) REFERENCES public.polls (poll_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE TABLE public.poll_votes (
+    option_id integer NOT NULL,
+    user_id uuid NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    poll_id integer NOT NULL,
+    PRIMARY KEY (option_id, user_id),
+    FOREIGN KEY (option_id) REFERENCES public.poll_options (option_id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (poll_id) REFERENCES public.polls (poll_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+COMMENT ON TABLE public.polls IS 'Polls created by coordinators, standalone or attached to a meeting.';
+COMMENT ON COLUMN public.polls.closes_at IS 'When voting closes. Open indefinitely if null.';
+COMMENT ON COLUMN public.polls.description IS 'Optional markdown description.';
+COMMENT ON COLUMN public.polls.discord_message_id IS 'The Discord message linking to this poll, if it was cross-posted.';
+COMMENT ON COLUMN public.polls.is_anonymous IS 'Whether voters are hidden from the results.';
+COMMENT ON COLUMN public.polls.is_multiple_choice IS 'Whether voters may pick more than one option.';
+COMMENT ON COLUMN public.polls.meeting_id IS 'The meeting this poll is attached to, if any.';
+COMMENT ON COLUMN public.polls.opens_at IS 'When voting opens. Open immediately if null.';
+COMMENT ON COLUMN public.polls.title IS 'The question being asked.';
+COMMENT ON TABLE public.poll_options IS 'Options of a poll.';
+COMMENT ON COLUMN public.poll_options.position IS 'Order of the option on the poll.';
+COMMENT ON TABLE public.poll_votes IS 'Votes on poll options. A user has one row per option they picked.';
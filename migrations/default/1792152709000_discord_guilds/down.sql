@@ -0,0 +1 @@
+DROP TABLE public.discord_guilds;
@@ -0,0 +1,17 @@
+CREATE TABLE public.discord_guilds (
+    guild_id text NOT NULL,
+    announcements_channel_id text,
+    announcements_enabled boolean NOT NULL DEFAULT true,
+    commands_enabled boolean NOT NULL DEFAULT true,
+    member_role_id text,
+    name text,
+    PRIMARY KEY (guild_id)
+);
+
+COMMENT ON TABLE public.discord_guilds IS 'Per-server settings for the Telescope Discord bot';
+COMMENT ON COLUMN public.discord_guilds.announcements_channel_id IS 'Channel to post announcements in';
+COMMENT ON COLUMN public.discord_guilds.announcements_enabled IS 'Whether announcements are posted on this server';
+COMMENT ON COLUMN public.discord_guilds.commands_enabled IS 'Whether slash commands are registered on this server';
+COMMENT ON COLUMN public.discord_guilds.guild_id IS 'The Discord server (guild) ID';
+COMMENT ON COLUMN public.discord_guilds.member_role_id IS 'Role given to members with a verified Telescope account';
+COMMENT ON COLUMN public.discord_guilds.name IS 'A name to recognize the server by';
@@ -0,0 +1,2 @@
+DROP TABLE public.meeting_discord_rsvps;
+DROP TABLE public.meeting_announcements;
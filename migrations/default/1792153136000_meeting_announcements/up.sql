@@ -0,0 +1,25 @@
+CREATE TABLE public.meeting_announcements (
+    message_id text NOT NULL,
+    channel_id text NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    meeting_id integer NOT NULL,
+    PRIMARY KEY (message_id),
+    FOREIGN KEY (meeting_id) REFERENCES public.meetings (meeting_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE TABLE public.meeting_discord_rsvps (
+    meeting_id integer NOT NULL,
+    discord_id text NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    user_id uuid,
+    PRIMARY KEY (meeting_id, discord_id),
+    FOREIGN KEY (meeting_id) REFERENCES public.meetings (meeting_id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+COMMENT ON TABLE public.meeting_announcements IS 'Discord messages announcing meetings.';
+COMMENT ON COLUMN public.meeting_announcements.channel_id IS 'The Discord channel the message was posted in.';
+COMMENT ON COLUMN public.meeting_announcements.message_id IS 'The Discord message ID.';
+COMMENT ON TABLE public.meeting_discord_rsvps IS 'Informal RSVPs collected from reactions on Discord meeting announcements.';
+COMMENT ON COLUMN public.meeting_discord_rsvps.discord_id IS 'The Discord user ID of the person who reacted.';
+COMMENT ON COLUMN public.meeting_discord_rsvps.user_id IS 'The Telescope user with this Discord account linked, if any.';
@@ -0,0 +1,3 @@
+ALTER TABLE public.meeting_announcements DROP COLUMN thread_id;
+ALTER TABLE public.meeting_announcements DROP COLUMN thread_archived;
+ALTER TABLE public.meeting_announcements DROP COLUMN slides_message_id;
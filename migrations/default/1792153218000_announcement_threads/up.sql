@@ -0,0 +1,9 @@
+ALTER TABLE public.meeting_announcements ADD COLUMN slides_message_id text;
+
+ALTER TABLE public.meeting_announcements ADD COLUMN thread_archived boolean NOT NULL DEFAULT false;
+
+ALTER TABLE public.meeting_announcements ADD COLUMN thread_id text;
+
+COMMENT ON COLUMN public.meeting_announcements.slides_message_id IS 'The message in the thread with the slides link, once posted.';
+COMMENT ON COLUMN public.meeting_announcements.thread_archived IS 'Whether the thread was archived after the meeting ended.';
+COMMENT ON COLUMN public.meeting_announcements.thread_id IS 'The Discord thread for questions started from the announcement, if any.';
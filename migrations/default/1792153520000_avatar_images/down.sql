@@ -0,0 +1 @@
+ALTER TABLE public.users DROP COLUMN avatar_image_id;
@@ -0,0 +1,3 @@
+ALTER TABLE public.users ADD COLUMN avatar_image_id uuid;
+
+COMMENT ON COLUMN public.users.avatar_image_id IS 'The uploaded profile picture, served under /images.';
@@ -0,0 +1,17 @@
+CREATE TABLE public.saved_searches (
+    saved_search_id serial NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    is_default boolean NOT NULL DEFAULT false,
+    name text NOT NULL,
+    page text NOT NULL,
+    query text NOT NULL,
+    user_id uuid NOT NULL,
+    PRIMARY KEY (saved_search_id),
+    FOREIGN KEY (user_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+COMMENT ON TABLE public.saved_searches IS 'Named filter sets users have saved on list pages.';
+COMMENT ON COLUMN public.saved_searches.is_default IS 'Whether the page opens with these filters for this user.';
+COMMENT ON COLUMN public.saved_searches.page IS 'The page the filters apply to, like "meetings" or "developers".';
+COMMENT ON COLUMN public.saved_searches.query IS 'The query string of the page with these filters.';
+COMMENT ON COLUMN public.saved_searches.user_id IS 'The user who saved this search.';
@@ -0,0 +1,7 @@
+ALTER TABLE public.users DROP COLUMN profile_hidden;
+ALTER TABLE public.status_update_submissions DROP COLUMN is_hidden;
+ALTER TABLE public.meeting_questions DROP COLUMN is_hidden;
+DROP TABLE public.moderation_reports;
+DROP TABLE public.moderation_log;
+DROP TYPE public.report_status;
+DROP TYPE public.reported_content;
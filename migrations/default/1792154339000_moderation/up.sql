@@ -0,0 +1,50 @@
+CREATE TYPE public.report_status AS ENUM ('open', 'hidden', 'removed', 'dismissed');
+
+CREATE TYPE public.reported_content AS ENUM ('status_update', 'profile', 'question');
+
+CREATE TABLE public.moderation_log (
+    log_id serial NOT NULL,
+    action public.report_status NOT NULL,
+    content_key text NOT NULL,
+    content_type public.reported_content NOT NULL,
+    content_url text NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    moderator_id uuid NOT NULL,
+    note text,
+    reports_closed integer NOT NULL DEFAULT 0,
+    PRIMARY KEY (log_id),
+    FOREIGN KEY (moderator_id) REFERENCES public.users (id) ON UPDATE CASCADE
+);
+
+CREATE TABLE public.moderation_reports (
+    report_id serial NOT NULL,
+    content_key text NOT NULL,
+    content_preview text NOT NULL,
+    content_type public.reported_content NOT NULL,
+    content_url text NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    reason text NOT NULL,
+    reporter_id uuid NOT NULL,
+    status public.report_status NOT NULL DEFAULT 'open',
+    PRIMARY KEY (report_id),
+    FOREIGN KEY (reporter_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+ALTER TABLE public.meeting_questions ADD COLUMN is_hidden boolean NOT NULL DEFAULT false;
+
+ALTER TABLE public.status_update_submissions ADD COLUMN is_hidden boolean NOT NULL DEFAULT false;
+
+ALTER TABLE public.users ADD COLUMN profile_hidden boolean NOT NULL DEFAULT false;
+
+COMMENT ON TABLE public.moderation_log IS 'Decisions made on reported content. Rows are never updated or deleted.';
+COMMENT ON COLUMN public.moderation_log.action IS 'hidden, removed, or dismissed.';
+COMMENT ON COLUMN public.moderation_log.reports_closed IS 'How many open reports the decision closed.';
+COMMENT ON TABLE public.moderation_reports IS 'Reports of user-generated content, filed by members for coordinators to review.';
+COMMENT ON COLUMN public.moderation_reports.content_key IS 'Identifies the reported content within its type.';
+COMMENT ON COLUMN public.moderation_reports.content_preview IS 'The reported content as it was when reported.';
+COMMENT ON COLUMN public.moderation_reports.content_type IS 'What kind of content was reported: status_update, profile, or question.';
+COMMENT ON COLUMN public.moderation_reports.content_url IS 'Where the reported content can be seen.';
+COMMENT ON COLUMN public.moderation_reports.status IS 'open, hidden, removed, or dismissed.';
+COMMENT ON COLUMN public.meeting_questions.is_hidden IS 'Hidden by a moderator.';
+COMMENT ON COLUMN public.status_update_submissions.is_hidden IS 'Hidden by a moderator.';
+COMMENT ON COLUMN public.users.profile_hidden IS 'Profile hidden from other members by a moderator.';
@@ -0,0 +1,2 @@
+DROP TABLE public.policy_acceptances;
+DROP TABLE public.policy_documents;
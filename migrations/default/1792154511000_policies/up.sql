@@ -0,0 +1,26 @@
+CREATE TABLE public.policy_documents (
+    policy_id serial NOT NULL,
+    body text NOT NULL,
+    kind text NOT NULL,
+    published_at timestamptz NOT NULL,
+    published_by uuid,
+    title text NOT NULL,
+    version integer NOT NULL,
+    PRIMARY KEY (policy_id),
+    UNIQUE (kind, version),
+    FOREIGN KEY (published_by) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+CREATE TABLE public.policy_acceptances (
+    user_id uuid NOT NULL,
+    policy_id integer NOT NULL,
+    accepted_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (user_id, policy_id),
+    FOREIGN KEY (user_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (policy_id) REFERENCES public.policy_documents (policy_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+COMMENT ON TABLE public.policy_documents IS 'Versioned policies members must accept, like the terms of service and code of conduct. Published versions are never edited.';
+COMMENT ON COLUMN public.policy_documents.body IS 'Markdown.';
+COMMENT ON COLUMN public.policy_documents.kind IS 'Which policy this is a version of, e.g. "terms" or "code_of_conduct".';
+COMMENT ON TABLE public.policy_acceptances IS 'When users accepted each policy version.';
@@ -0,0 +1,15 @@
+CREATE TABLE public.user_attestations (
+    user_id uuid NOT NULL,
+    key text NOT NULL,
+    accepted boolean NOT NULL DEFAULT false,
+    answered_at timestamptz NOT NULL DEFAULT now(),
+    label text NOT NULL,
+    PRIMARY KEY (user_id, key),
+    FOREIGN KEY (user_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+COMMENT ON TABLE public.user_attestations IS 'Answers users gave to the attestations asked for at registration, like being over 18.';
+COMMENT ON COLUMN public.user_attestations.accepted IS 'If the user checked the box.';
+COMMENT ON COLUMN public.user_attestations.key IS 'The key of the attestation in the Telescope config.';
+COMMENT ON COLUMN public.user_attestations.label IS 'The statement the user was shown when they answered.';
+COMMENT ON COLUMN public.user_attestations.user_id IS 'The user who answered.';
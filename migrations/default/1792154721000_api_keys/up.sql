@@ -0,0 +1,19 @@
+CREATE TABLE public.api_keys (
+    key_id serial NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    created_by uuid,
+    key_hash text NOT NULL,
+    key_prefix text NOT NULL,
+    name text NOT NULL,
+    revoked_at timestamptz,
+    tier text NOT NULL,
+    PRIMARY KEY (key_id),
+    UNIQUE (key_hash),
+    FOREIGN KEY (created_by) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+COMMENT ON TABLE public.api_keys IS 'Keys for the public JSON API, with the rate limit tier each is allowed.';
+COMMENT ON COLUMN public.api_keys.key_hash IS 'SHA-256 of the key, in hex. Keys themselves are not stored.';
+COMMENT ON COLUMN public.api_keys.key_prefix IS 'The first characters of the key, to tell keys apart.';
+COMMENT ON COLUMN public.api_keys.name IS 'Who or what the key is for.';
+COMMENT ON COLUMN public.api_keys.tier IS 'The rate limit tier: standard or elevated.';
@@ -0,0 +1,11 @@
+CREATE TABLE public.user_field_visibility (
+    user_id uuid NOT NULL,
+    field text NOT NULL,
+    audience text NOT NULL,
+    PRIMARY KEY (user_id, field),
+    FOREIGN KEY (user_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+COMMENT ON TABLE public.user_field_visibility IS 'Who can see optional fields of a user profile. Fields without a row use their default audience.';
+COMMENT ON COLUMN public.user_field_visibility.audience IS 'One of "public", "members", "coordinators", or "self".';
+COMMENT ON COLUMN public.user_field_visibility.field IS 'The profile field, like "email" or "pronouns".';
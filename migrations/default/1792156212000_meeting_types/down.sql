@@ -0,0 +1,2 @@
+ALTER TABLE public.meetings DROP COLUMN custom_type_id;
+DROP TABLE public.meeting_types;
@@ -0,0 +1,18 @@
+CREATE TABLE public.meeting_types (
+    meeting_type_id serial NOT NULL,
+    color text NOT NULL,
+    counts_for_attendance boolean NOT NULL DEFAULT true,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    default_duration_minutes integer NOT NULL DEFAULT 60,
+    name text NOT NULL,
+    PRIMARY KEY (meeting_type_id)
+);
+
+ALTER TABLE public.meetings ADD COLUMN custom_type_id integer
+    REFERENCES public.meeting_types (meeting_type_id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+COMMENT ON TABLE public.meeting_types IS 'Meeting types defined by coordinators, in addition to the built in ones.';
+COMMENT ON COLUMN public.meeting_types.color IS 'Background color of the type in hex, like "#3a6ea5".';
+COMMENT ON COLUMN public.meeting_types.counts_for_attendance IS 'Whether attending meetings of this type counts towards attendance.';
+COMMENT ON COLUMN public.meeting_types.default_duration_minutes IS 'How long meetings of this type usually last.';
+COMMENT ON COLUMN public.meetings.custom_type_id IS 'A coordinator defined type. Meetings with one have type "other".';
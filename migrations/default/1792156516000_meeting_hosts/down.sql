@@ -0,0 +1 @@
+DROP TABLE public.meeting_hosts;
@@ -0,0 +1,12 @@
+CREATE TABLE public.meeting_hosts (
+    meeting_id integer NOT NULL,
+    user_id uuid NOT NULL,
+    added_at timestamptz NOT NULL DEFAULT now(),
+    role text NOT NULL DEFAULT 'co_host',
+    PRIMARY KEY (meeting_id, user_id),
+    FOREIGN KEY (meeting_id) REFERENCES public.meetings (meeting_id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+COMMENT ON TABLE public.meeting_hosts IS 'People hosting a meeting besides its primary host, and their roles.';
+COMMENT ON COLUMN public.meeting_hosts.role IS 'host, co_host, or guest_speaker';
@@ -0,0 +1,2 @@
+DROP TABLE public.meeting_speakers;
+DROP TABLE public.external_speakers;
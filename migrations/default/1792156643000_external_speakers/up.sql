@@ -0,0 +1,22 @@
+CREATE TABLE public.external_speakers (
+    speaker_id serial NOT NULL,
+    affiliation text,
+    bio text,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    created_by uuid,
+    name text NOT NULL,
+    photo_url text,
+    PRIMARY KEY (speaker_id),
+    FOREIGN KEY (created_by) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+CREATE TABLE public.meeting_speakers (
+    meeting_id integer NOT NULL,
+    speaker_id integer NOT NULL,
+    PRIMARY KEY (meeting_id, speaker_id),
+    FOREIGN KEY (meeting_id) REFERENCES public.meetings (meeting_id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (speaker_id) REFERENCES public.external_speakers (speaker_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+COMMENT ON TABLE public.external_speakers IS 'Speakers from outside RCOS who do not have a Telescope account.';
+COMMENT ON TABLE public.meeting_speakers IS 'External speakers at each meeting.';
@@ -0,0 +1,2 @@
+ALTER TABLE public.meetings DROP COLUMN small_group_id;
+ALTER TABLE public.meetings DROP COLUMN group_reminder_sent;
@@ -0,0 +1,7 @@
+ALTER TABLE public.meetings ADD COLUMN group_reminder_sent boolean NOT NULL DEFAULT false;
+
+ALTER TABLE public.meetings ADD COLUMN small_group_id integer
+    REFERENCES public.small_groups (small_group_id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+COMMENT ON COLUMN public.meetings.group_reminder_sent IS 'Have the members of the small group been reminded of this meeting?';
+COMMENT ON COLUMN public.meetings.small_group_id IS 'The small group this meeting is for, if it is only for one small group.';
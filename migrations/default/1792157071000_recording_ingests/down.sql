@@ -0,0 +1,2 @@
+DROP TABLE public.recording_ingest_candidates;
+DROP TABLE public.recording_ingests;
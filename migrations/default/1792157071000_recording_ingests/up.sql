@@ -0,0 +1,32 @@
+CREATE TABLE public.recording_ingests (
+    url text NOT NULL,
+    decided_by uuid,
+    meeting_id integer,
+    published_at timestamptz NOT NULL,
+    received_at timestamptz NOT NULL DEFAULT now(),
+    source text NOT NULL,
+    status text NOT NULL DEFAULT 'pending',
+    title text NOT NULL,
+    PRIMARY KEY (url),
+    FOREIGN KEY (decided_by) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE SET NULL,
+    FOREIGN KEY (meeting_id) REFERENCES public.meetings (meeting_id) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+CREATE TABLE public.recording_ingest_candidates (
+    url text NOT NULL,
+    meeting_id integer NOT NULL,
+    score integer NOT NULL DEFAULT 0,
+    PRIMARY KEY (url, meeting_id),
+    FOREIGN KEY (url) REFERENCES public.recording_ingests (url) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (meeting_id) REFERENCES public.meetings (meeting_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+COMMENT ON TABLE public.recording_ingests IS 'Meeting recordings picked up from YouTube or the recording webhook';
+COMMENT ON COLUMN public.recording_ingests.decided_by IS 'The coordinator who confirmed or dismissed a pending recording';
+COMMENT ON COLUMN public.recording_ingests.meeting_id IS 'The meeting the recording was attached to';
+COMMENT ON COLUMN public.recording_ingests.source IS 'Where the recording was found: youtube or webhook';
+COMMENT ON COLUMN public.recording_ingests.status IS 'attached, pending, or dismissed';
+COMMENT ON COLUMN public.recording_ingests.title IS 'The title of the recording where it was found';
+COMMENT ON COLUMN public.recording_ingests.url IS 'The recording link';
+COMMENT ON TABLE public.recording_ingest_candidates IS 'Meetings a pending recording could belong to';
+COMMENT ON COLUMN public.recording_ingest_candidates.score IS 'How well the recording matches the meeting, out of 100';
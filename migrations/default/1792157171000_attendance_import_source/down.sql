@@ -0,0 +1 @@
+ALTER TABLE public.meeting_attendances DROP COLUMN import_source;
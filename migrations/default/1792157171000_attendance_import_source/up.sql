@@ -0,0 +1,3 @@
+ALTER TABLE public.meeting_attendances ADD COLUMN import_source text;
+
+COMMENT ON COLUMN public.meeting_attendances.import_source IS 'Where attendance imported from a spreadsheet came from, like the name of the file. Null for attendance that was not imported.';
@@ -0,0 +1,2 @@
+DROP TABLE public.excused_absences;
+DROP TABLE public.attendance_policies;
@@ -0,0 +1,39 @@
+CREATE TABLE public.attendance_policies (
+    semester_id text NOT NULL,
+    max_excused integer NOT NULL,
+    required_count integer NOT NULL,
+    updated_at timestamptz NOT NULL DEFAULT now(),
+    updated_by uuid,
+    PRIMARY KEY (semester_id),
+    FOREIGN KEY (semester_id) REFERENCES public.semesters (semester_id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (updated_by) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+CREATE TABLE public.excused_absences (
+    meeting_id integer NOT NULL,
+    user_id uuid NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    decided_at timestamptz,
+    decided_by uuid,
+    reason text NOT NULL,
+    status text NOT NULL DEFAULT 'pending',
+    PRIMARY KEY (meeting_id, user_id),
+    FOREIGN KEY (meeting_id) REFERENCES public.meetings (meeting_id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (decided_by) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+COMMENT ON TABLE public.attendance_policies IS 'How much attendance is required of members in a semester.';
+COMMENT ON COLUMN public.attendance_policies.max_excused IS 'How many approved excused absences can count towards the required attendance.';
+COMMENT ON COLUMN public.attendance_policies.required_count IS 'How many meetings that count towards attendance members have to attend.';
+COMMENT ON COLUMN public.attendance_policies.semester_id IS 'The semester this policy is for.';
+COMMENT ON COLUMN public.attendance_policies.updated_at IS 'When the policy was last changed.';
+COMMENT ON COLUMN public.attendance_policies.updated_by IS 'The coordinator who last changed the policy.';
+COMMENT ON TABLE public.excused_absences IS 'Requests by members to be excused from meetings they could not attend.';
+COMMENT ON COLUMN public.excused_absences.created_at IS 'When the request was made.';
+COMMENT ON COLUMN public.excused_absences.decided_at IS 'When the request was approved or denied.';
+COMMENT ON COLUMN public.excused_absences.decided_by IS 'The coordinator who approved or denied the request.';
+COMMENT ON COLUMN public.excused_absences.meeting_id IS 'The meeting that was missed.';
+COMMENT ON COLUMN public.excused_absences.reason IS 'Why the member could not attend.';
+COMMENT ON COLUMN public.excused_absences.status IS 'One of pending, approved, or denied.';
+COMMENT ON COLUMN public.excused_absences.user_id IS 'The member asking to be excused.';
@@ -0,0 +1,21 @@
+CREATE TABLE public.project_milestones (
+    milestone_id serial NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    created_by uuid,
+    due_date date NOT NULL,
+    project_id integer NOT NULL,
+    status text NOT NULL DEFAULT 'planned',
+    title text NOT NULL,
+    PRIMARY KEY (milestone_id),
+    FOREIGN KEY (created_by) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE SET NULL,
+    FOREIGN KEY (project_id) REFERENCES public.projects (project_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+COMMENT ON TABLE public.project_milestones IS 'Milestones project leads set for their projects.';
+COMMENT ON COLUMN public.project_milestones.created_at IS 'When the milestone was added.';
+COMMENT ON COLUMN public.project_milestones.created_by IS 'Who added the milestone.';
+COMMENT ON COLUMN public.project_milestones.due_date IS 'When the milestone should be done.';
+COMMENT ON COLUMN public.project_milestones.milestone_id IS 'Unique identifier of the milestone.';
+COMMENT ON COLUMN public.project_milestones.project_id IS 'The project this milestone is for.';
+COMMENT ON COLUMN public.project_milestones.status IS 'One of planned, in_progress, or done.';
+COMMENT ON COLUMN public.project_milestones.title IS 'What the milestone is.';
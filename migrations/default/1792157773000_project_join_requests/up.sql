@@ -0,0 +1,24 @@
+CREATE TABLE public.project_join_requests (
+    request_id serial NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    decided_at timestamptz,
+    decided_by uuid,
+    message text,
+    project_id integer NOT NULL,
+    semester_id text NOT NULL,
+    status text NOT NULL DEFAULT 'pending',
+    user_id uuid NOT NULL,
+    PRIMARY KEY (request_id),
+    FOREIGN KEY (decided_by) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE SET NULL,
+    FOREIGN KEY (project_id) REFERENCES public.projects (project_id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (semester_id) REFERENCES public.semesters (semester_id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+COMMENT ON TABLE public.project_join_requests IS 'Requests from members to join a project, decided by its leads.';
+COMMENT ON COLUMN public.project_join_requests.decided_by IS 'The lead or coordinator who decided on the request.';
+COMMENT ON COLUMN public.project_join_requests.message IS 'Why the member wants to join, shown to the leads.';
+COMMENT ON COLUMN public.project_join_requests.project_id IS 'The project the member asked to join.';
+COMMENT ON COLUMN public.project_join_requests.semester_id IS 'The semester the member would be on the project for.';
+COMMENT ON COLUMN public.project_join_requests.status IS 'One of pending, approved, or denied.';
+COMMENT ON COLUMN public.project_join_requests.user_id IS 'The member asking to join.';
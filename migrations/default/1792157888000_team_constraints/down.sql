@@ -0,0 +1,2 @@
+DROP TABLE public.team_constraints;
+DROP TABLE public.audit_log;
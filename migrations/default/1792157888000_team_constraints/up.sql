@@ -0,0 +1,30 @@
+CREATE TABLE public.audit_log (
+    log_id serial NOT NULL,
+    action text NOT NULL,
+    actor_id uuid NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    note text,
+    target_url text NOT NULL,
+    PRIMARY KEY (log_id),
+    FOREIGN KEY (actor_id) REFERENCES public.users (id) ON UPDATE CASCADE
+);
+
+CREATE TABLE public.team_constraints (
+    semester_id text NOT NULL,
+    max_team_size integer,
+    members_per_mentor integer,
+    updated_at timestamptz NOT NULL DEFAULT now(),
+    updated_by uuid,
+    PRIMARY KEY (semester_id),
+    FOREIGN KEY (semester_id) REFERENCES public.semesters (semester_id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (updated_by) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+COMMENT ON TABLE public.audit_log IS 'Actions coordinators took that overrode a rule. Rows are never updated or deleted.';
+COMMENT ON COLUMN public.audit_log.action IS 'What was done, e.g. join_request_override.';
+COMMENT ON COLUMN public.audit_log.actor_id IS 'Who took the action.';
+COMMENT ON COLUMN public.audit_log.note IS 'Why, or which rules were overridden.';
+COMMENT ON COLUMN public.audit_log.target_url IS 'The page of what the action was taken on.';
+COMMENT ON TABLE public.team_constraints IS 'Limits on project teams in a semester, checked when requests to join are approved.';
+COMMENT ON COLUMN public.team_constraints.max_team_size IS 'The most members a project may have. Null for no limit.';
+COMMENT ON COLUMN public.team_constraints.members_per_mentor IS 'Projects need at least one lead for every this many members. Null for no requirement.';
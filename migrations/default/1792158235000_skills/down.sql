@@ -0,0 +1,3 @@
+DROP TABLE public.user_skills;
+DROP TABLE public.project_skills;
+DROP TABLE public.skills;
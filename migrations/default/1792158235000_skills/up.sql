@@ -0,0 +1,32 @@
+CREATE TABLE public.skills (
+    skill_id serial NOT NULL,
+    category text NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    name text NOT NULL,
+    PRIMARY KEY (skill_id),
+    UNIQUE (name)
+);
+
+CREATE TABLE public.project_skills (
+    project_id integer NOT NULL,
+    skill_id integer NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (project_id, skill_id),
+    FOREIGN KEY (project_id) REFERENCES public.projects (project_id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (skill_id) REFERENCES public.skills (skill_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE TABLE public.user_skills (
+    user_id uuid NOT NULL,
+    skill_id integer NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (user_id, skill_id),
+    FOREIGN KEY (user_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (skill_id) REFERENCES public.skills (skill_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+COMMENT ON TABLE public.skills IS 'Languages, frameworks, and domains that members have and projects need. Managed by coordinators.';
+COMMENT ON COLUMN public.skills.category IS 'One of language, framework, or domain.';
+COMMENT ON COLUMN public.skills.name IS 'The name of the skill, e.g. Rust.';
+COMMENT ON TABLE public.project_skills IS 'Skills a project needs, set by its leads.';
+COMMENT ON TABLE public.user_skills IS 'Skills members have tagged themselves with.';
@@ -0,0 +1,4 @@
+ALTER TABLE public.discord_guilds DROP COLUMN student_role_id;
+ALTER TABLE public.discord_guilds DROP COLUMN mentor_role_id;
+ALTER TABLE public.discord_guilds DROP COLUMN faculty_role_id;
+ALTER TABLE public.discord_guilds DROP COLUMN coordinator_role_id;
@@ -0,0 +1,12 @@
+ALTER TABLE public.discord_guilds ADD COLUMN coordinator_role_id text;
+
+ALTER TABLE public.discord_guilds ADD COLUMN faculty_role_id text;
+
+ALTER TABLE public.discord_guilds ADD COLUMN mentor_role_id text;
+
+ALTER TABLE public.discord_guilds ADD COLUMN student_role_id text;
+
+COMMENT ON COLUMN public.discord_guilds.coordinator_role_id IS 'Role given to coordinators of a current semester.';
+COMMENT ON COLUMN public.discord_guilds.faculty_role_id IS 'Role given to faculty and faculty advisors.';
+COMMENT ON COLUMN public.discord_guilds.mentor_role_id IS 'Role given to members mentoring a small group this semester.';
+COMMENT ON COLUMN public.discord_guilds.student_role_id IS 'Role given to members enrolled in a current semester.';
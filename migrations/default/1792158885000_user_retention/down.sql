@@ -0,0 +1,2 @@
+ALTER TABLE public.users DROP COLUMN withdrawn_at;
+ALTER TABLE public.users DROP COLUMN anonymized_at;
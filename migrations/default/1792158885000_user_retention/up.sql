@@ -0,0 +1,6 @@
+ALTER TABLE public.users ADD COLUMN anonymized_at timestamptz;
+
+ALTER TABLE public.users ADD COLUMN withdrawn_at timestamptz;
+
+COMMENT ON COLUMN public.users.anonymized_at IS 'When the account was anonymized by the retention job.';
+COMMENT ON COLUMN public.users.withdrawn_at IS 'When the user was marked withdrawn. Their account is anonymized after the retention period.';
@@ -0,0 +1,24 @@
+CREATE TABLE public.error_reports (
+    report_id serial NOT NULL,
+    closed_at timestamptz,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    description text NOT NULL,
+    issue_url text,
+    occurred_at timestamptz NOT NULL,
+    reporter_id uuid,
+    request_id text NOT NULL,
+    route text NOT NULL,
+    status integer NOT NULL,
+    PRIMARY KEY (report_id),
+    FOREIGN KEY (reporter_id) REFERENCES public.users (id) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+COMMENT ON TABLE public.error_reports IS 'Reports users filed from server error pages.';
+COMMENT ON COLUMN public.error_reports.closed_at IS 'When an admin closed the report. Open reports have none.';
+COMMENT ON COLUMN public.error_reports.description IS 'What the user was doing.';
+COMMENT ON COLUMN public.error_reports.issue_url IS 'The GitHub issue made from this report, if any.';
+COMMENT ON COLUMN public.error_reports.occurred_at IS 'When the error page was shown.';
+COMMENT ON COLUMN public.error_reports.reporter_id IS 'The user who filed the report, if they were signed in.';
+COMMENT ON COLUMN public.error_reports.request_id IS 'The ID of the request that failed.';
+COMMENT ON COLUMN public.error_reports.route IS 'The path of the request that failed.';
+COMMENT ON COLUMN public.error_reports.status IS 'The HTTP status code of the error.';
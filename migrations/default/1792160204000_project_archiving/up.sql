@@ -0,0 +1,3 @@
+ALTER TABLE public.projects ADD COLUMN archived_at timestamptz;
+
+COMMENT ON COLUMN public.projects.archived_at IS 'When the project was archived. Archived projects take no new members.';
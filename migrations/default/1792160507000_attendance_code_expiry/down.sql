@@ -0,0 +1 @@
+ALTER TABLE public.meetings DROP COLUMN attendance_code_expires_at;
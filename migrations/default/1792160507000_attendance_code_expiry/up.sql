@@ -0,0 +1,3 @@
+ALTER TABLE public.meetings ADD COLUMN attendance_code_expires_at timestamptz;
+
+COMMENT ON COLUMN public.meetings.attendance_code_expires_at IS 'When the attendance code stops being accepted. Codes without one do not expire.';
@@ -0,0 +1,2 @@
+ALTER TABLE public.meetings DROP COLUMN publish_at;
+ALTER TABLE public.meetings DROP COLUMN announce_on_publish;
@@ -0,0 +1,6 @@
+ALTER TABLE public.meetings ADD COLUMN announce_on_publish boolean NOT NULL DEFAULT false;
+
+ALTER TABLE public.meetings ADD COLUMN publish_at timestamptz;
+
+COMMENT ON COLUMN public.meetings.announce_on_publish IS 'Whether to announce the meeting on Discord when it is published automatically.';
+COMMENT ON COLUMN public.meetings.publish_at IS 'When a draft meeting is published automatically, if it is scheduled to be.';
@@ -0,0 +1,4 @@
+DROP TABLE public.webhook_subscriptions;
+DROP TABLE public.webhook_delivery_attempts;
+DROP TABLE public.webhook_deliveries;
+DROP TABLE public.webhook_endpoints;
@@ -0,0 +1,55 @@
+CREATE TABLE public.webhook_endpoints (
+    endpoint_id serial NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    description text,
+    enabled boolean NOT NULL DEFAULT true,
+    secret text NOT NULL,
+    url text NOT NULL,
+    PRIMARY KEY (endpoint_id)
+);
+
+CREATE TABLE public.webhook_deliveries (
+    delivery_id serial NOT NULL,
+    attempts integer NOT NULL DEFAULT 0,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    delivered_at timestamptz,
+    endpoint_id integer NOT NULL,
+    event text NOT NULL,
+    next_attempt_at timestamptz,
+    payload text NOT NULL,
+    PRIMARY KEY (delivery_id),
+    FOREIGN KEY (endpoint_id) REFERENCES public.webhook_endpoints (endpoint_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE TABLE public.webhook_delivery_attempts (
+    attempt_id serial NOT NULL,
+    attempted_at timestamptz NOT NULL DEFAULT now(),
+    delivery_id integer NOT NULL,
+    duration_ms integer NOT NULL,
+    error text,
+    status_code integer,
+    PRIMARY KEY (attempt_id),
+    FOREIGN KEY (delivery_id) REFERENCES public.webhook_deliveries (delivery_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE TABLE public.webhook_subscriptions (
+    endpoint_id integer NOT NULL,
+    event text NOT NULL,
+    PRIMARY KEY (endpoint_id, event),
+    FOREIGN KEY (endpoint_id) REFERENCES public.webhook_endpoints (endpoint_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+COMMENT ON TABLE public.webhook_endpoints IS 'URLs that integrations receive webhooks at';
+COMMENT ON COLUMN public.webhook_endpoints.description IS 'What the integration is, for admins';
+COMMENT ON COLUMN public.webhook_endpoints.enabled IS 'Disabled endpoints are not sent new events';
+COMMENT ON COLUMN public.webhook_endpoints.secret IS 'Key the bodies of deliveries are signed with';
+COMMENT ON COLUMN public.webhook_endpoints.url IS 'Where events are posted';
+COMMENT ON TABLE public.webhook_deliveries IS 'Outbox of events to post to webhook endpoints';
+COMMENT ON COLUMN public.webhook_deliveries.attempts IS 'Number of times delivery was tried';
+COMMENT ON COLUMN public.webhook_deliveries.next_attempt_at IS 'When to try next. Null once delivered or given up on';
+COMMENT ON COLUMN public.webhook_deliveries.payload IS 'JSON body posted to the endpoint';
+COMMENT ON TABLE public.webhook_delivery_attempts IS 'Log of attempts to post webhook deliveries';
+COMMENT ON COLUMN public.webhook_delivery_attempts.error IS 'Why the attempt failed, if it did';
+COMMENT ON COLUMN public.webhook_delivery_attempts.status_code IS 'HTTP status of the response, if there was one';
+COMMENT ON TABLE public.webhook_subscriptions IS 'Events each webhook endpoint receives';
+COMMENT ON COLUMN public.webhook_subscriptions.event IS 'Event name, like meeting.published';
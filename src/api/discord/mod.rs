@@ -1,7 +1,10 @@
 //! Discord API interactions authenticated with the Telescope bot token.
 
+use crate::discord_bot::guilds;
 use crate::env::global_config;
 use crate::error::TelescopeError;
+use crate::web::deadline;
+use serenity::builder::CreateEmbed;
 use serenity::http::Http;
 use serenity::model::id::RoleId;
 
@@ -20,18 +23,45 @@ pub async fn rcos_discord_verified_role_id() -> Result<Option<RoleId>, Telescope
     // Get the RCOS Guild ID.
     let rcos_discord: u64 = global_config().discord_config.rcos_guild_id();
 
+    // Use the role set on the admin panel if there is one.
+    if let Some(role_id) = guilds::get(rcos_discord).and_then(|settings| settings.member_role_id) {
+        return Ok(Some(RoleId(role_id)));
+    }
+
     // Get role
-    Ok(global_discord_client()
-        .get_guild_roles(rcos_discord)
-        .await
-        .map_err(|err| {
-            error!("Could not get RCOS Discord Roles. Internal error: {}", err);
-            TelescopeError::serenity_error(err)
-        })?
-        .iter()
-        // We use a simple string comparison for now. We can change this to use
-        // something else later on if needed.
-        .find(|role| role.name.eq_ignore_ascii_case("Verified"))
-        // Extract the ID from the Discord Role.
-        .map(|role| role.id))
+    Ok(deadline::within(
+        "Discord",
+        global_discord_client().get_guild_roles(rcos_discord),
+    )
+    .await?
+    .map_err(|err| {
+        error!("Could not get RCOS Discord Roles. Internal error: {}", err);
+        TelescopeError::serenity_error(err)
+    })?
+    .iter()
+    // We use a simple string comparison for now. We can change this to use
+    // something else later on if needed.
+    .find(|role| role.name.eq_ignore_ascii_case("Verified"))
+    // Extract the ID from the Discord Role.
+    .map(|role| role.id))
+}
+
+/// Send a Discord DM to a user. Return whether it was delivered.
+pub async fn send_dm(discord_id: u64, embed: &CreateEmbed) -> bool {
+    let channel = global_discord_client()
+        .create_private_channel(&json!({ "recipient_id": discord_id }))
+        .await;
+
+    match channel {
+        Ok(channel) => channel
+            .send_message(global_discord_client(), |message| {
+                message.set_embed(embed.clone())
+            })
+            .await
+            .is_ok(),
+        Err(e) => {
+            warn!("Could not open DM with Discord user {}: {}", discord_id, e);
+            false
+        }
+    }
 }
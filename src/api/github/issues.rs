@@ -0,0 +1,66 @@
+//! Opening issues on a repository.
+
+use crate::api::github::send_query;
+use crate::error::TelescopeError;
+use oauth2::AccessToken;
+
+/// GitHub URL type.
+type URI = String;
+
+/// Query for the node ID of a repository.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/github/schema.json",
+    query_path = "graphql/github/issues.graphql",
+    response_derives = "Debug"
+)]
+pub struct RepositoryId;
+
+/// Mutation to open an issue.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/github/schema.json",
+    query_path = "graphql/github/issues.graphql",
+    response_derives = "Debug"
+)]
+pub struct CreateIssue;
+
+impl CreateIssue {
+    /// Open an issue on a repository. The token needs permission to write
+    /// issues there. Return the URL of the new issue.
+    pub async fn execute(
+        token: &AccessToken,
+        owner: &str,
+        name: &str,
+        title: String,
+        body: String,
+    ) -> Result<String, TelescopeError> {
+        let repository_id: String = send_query::<RepositoryId>(
+            token,
+            repository_id::Variables {
+                owner: owner.to_string(),
+                name: name.to_string(),
+            },
+        )
+        .await?
+        .repository
+        .ok_or_else(|| {
+            TelescopeError::GitHubApiError(format!("Repository {}/{} not found.", owner, name))
+        })?
+        .id;
+
+        send_query::<Self>(
+            token,
+            create_issue::Variables {
+                repository_id,
+                title,
+                body,
+            },
+        )
+        .await?
+        .create_issue
+        .and_then(|payload| payload.issue)
+        .map(|issue| issue.url)
+        .ok_or_else(|| TelescopeError::GitHubApiError("The issue was not created.".into()))
+    }
+}
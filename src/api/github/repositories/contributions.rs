@@ -0,0 +1,152 @@
+//! Queries for commits and merged pull requests in a repository.
+
+use crate::api::github::send_query;
+use crate::error::TelescopeError;
+use chrono::Utc;
+use oauth2::AccessToken;
+
+/// GitHub timestamp types.
+type DateTime = chrono::DateTime<Utc>;
+type GitTimestamp = chrono::DateTime<Utc>;
+
+/// The most pages of 100 items to read from a repository in one query.
+const MAX_PAGES: usize = 10;
+
+/// Query for commits on the default branch of a repository.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/github/schema.json",
+    query_path = "graphql/github/repositories/contributions.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RepositoryCommits;
+
+/// Query for merged pull requests in a repository.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/github/schema.json",
+    query_path = "graphql/github/repositories/contributions.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RepositoryPullRequests;
+
+impl RepositoryCommits {
+    /// Get the GitHub node ID of the author and the commit time of every
+    /// commit to the default branch since a given time. Commits by authors
+    /// without a GitHub account are skipped.
+    pub async fn get(
+        token: &AccessToken,
+        owner: &str,
+        name: &str,
+        since: DateTime,
+    ) -> Result<Vec<(String, DateTime)>, TelescopeError> {
+        use repository_commits::RepositoryCommitsRepositoryDefaultBranchRefTargetOn as TargetOn;
+
+        let mut commits = Vec::new();
+        let mut after: Option<String> = None;
+
+        for _ in 0..MAX_PAGES {
+            let data = send_query::<Self>(
+                token,
+                repository_commits::Variables {
+                    owner: owner.to_string(),
+                    name: name.to_string(),
+                    since,
+                    after: after.take(),
+                },
+            )
+            .await?;
+
+            // Find the commit history, if the repository and branch exist.
+            let history = match data
+                .repository
+                .and_then(|repo| repo.default_branch_ref)
+                .and_then(|branch| branch.target)
+                .map(|target| target.on)
+            {
+                Some(TargetOn::Commit(commit)) => commit.history,
+                _ => break,
+            };
+
+            commits.extend(
+                history
+                    .nodes
+                    .unwrap_or_default()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|node| {
+                        let id = node.author?.user?.id;
+                        Some((id, node.committed_date))
+                    }),
+            );
+
+            // Go to the next page if there is one.
+            if !history.page_info.has_next_page {
+                break;
+            }
+            after = history.page_info.end_cursor;
+        }
+
+        return Ok(commits);
+    }
+}
+
+impl RepositoryPullRequests {
+    /// Get the GitHub node ID of the author and the merge time of every pull
+    /// request merged since a given time.
+    pub async fn get(
+        token: &AccessToken,
+        owner: &str,
+        name: &str,
+        since: DateTime,
+    ) -> Result<Vec<(String, DateTime)>, TelescopeError> {
+        use repository_pull_requests::RepositoryPullRequestsRepositoryPullRequestsNodesAuthorOn as AuthorOn;
+
+        let mut pulls = Vec::new();
+        let mut after: Option<String> = None;
+
+        for _ in 0..MAX_PAGES {
+            let data = send_query::<Self>(
+                token,
+                repository_pull_requests::Variables {
+                    owner: owner.to_string(),
+                    name: name.to_string(),
+                    after: after.take(),
+                },
+            )
+            .await?;
+
+            let connection = match data.repository {
+                Some(repo) => repo.pull_requests,
+                None => break,
+            };
+
+            let nodes: Vec<_> = connection
+                .nodes
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .collect();
+
+            // Pull requests are ordered by last update, so once one was last
+            // updated before the window, none of the rest were merged in it.
+            let exhausted: bool = nodes.iter().any(|node| node.updated_at < since);
+
+            pulls.extend(nodes.into_iter().filter_map(|node| {
+                let merged_at = node.merged_at.filter(|merged| *merged >= since)?;
+                match node.author?.on {
+                    AuthorOn::User(user) => Some((user.id, merged_at)),
+                    _ => None,
+                }
+            }));
+
+            // Go to the next page if there is one.
+            if exhausted || !connection.page_info.has_next_page {
+                break;
+            }
+            after = connection.page_info.end_cursor;
+        }
+
+        return Ok(pulls);
+    }
+}
@@ -0,0 +1,3 @@
+//! GitHub repository queries.
+
+pub mod contributions;
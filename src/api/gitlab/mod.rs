@@ -0,0 +1,141 @@
+//! GitLab REST API (v4) queries. Unlike GitHub, GitLab is read through its
+//! REST API since telescope only needs a couple of list endpoints.
+
+use crate::api::http::{self, Integration};
+use crate::env::GitLabConfig;
+use crate::error::TelescopeError;
+use crate::web::telescope_ua;
+use crate::web::timing;
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderValue, ACCEPT, USER_AGENT};
+use serde::de::DeserializeOwned;
+use url::form_urlencoded::byte_serialize;
+
+/// Number of items to request per page.
+const PER_PAGE: usize = 100;
+
+/// The most pages to read from one endpoint in one query.
+const MAX_PAGES: usize = 10;
+
+/// An event on a GitLab project. Only push events are requested.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Event {
+    /// The GitLab user ID of the user who pushed.
+    pub author_id: i64,
+    pub created_at: DateTime<Utc>,
+    /// Details of the push.
+    pub push_data: Option<PushData>,
+}
+
+/// Details of a push event.
+#[derive(Deserialize, Debug, Clone)]
+pub struct PushData {
+    /// The number of commits in the push.
+    pub commit_count: i64,
+}
+
+/// A merge request on a GitLab project.
+#[derive(Deserialize, Debug, Clone)]
+pub struct MergeRequest {
+    pub merged_at: Option<DateTime<Utc>>,
+    pub author: MergeRequestAuthor,
+}
+
+/// The author of a merge request.
+#[derive(Deserialize, Debug, Clone)]
+pub struct MergeRequestAuthor {
+    /// The GitLab user ID of the author.
+    pub id: i64,
+}
+
+/// Send a GET request for every page of a list endpoint on a project.
+async fn get_pages<T: DeserializeOwned>(
+    config: &GitLabConfig,
+    project_path: &str,
+    endpoint: &str,
+    query: &[(&str, String)],
+) -> Result<Vec<T>, TelescopeError> {
+    // Project paths are URL encoded in place of the project ID.
+    let project: String = byte_serialize(project_path.as_bytes()).collect();
+    let url = format!("{}/api/v4/projects/{}/{}", config.url, project, endpoint);
+
+    let mut items: Vec<T> = Vec::new();
+    for page in 1..=MAX_PAGES {
+        let mut request = http::client(Integration::GitLab)
+            .get(url.as_str())
+            .query(query)
+            .query(&[("per_page", PER_PAGE), ("page", page)])
+            .header(ACCEPT, HeaderValue::from_static("application/json"))
+            .header(USER_AGENT, telescope_ua());
+
+        // Authenticate if there is a token.
+        if let Some(token) = config.api_token.as_ref() {
+            request = request.header("PRIVATE-TOKEN", token.secret().as_str());
+        }
+
+        let page_items: Vec<T> = timing::upstream(async {
+            request
+                .send()
+                .await
+                .map_err(TelescopeError::gitlab_api_error)?
+                .error_for_status()
+                .map_err(TelescopeError::gitlab_api_error)?
+                .json::<Vec<T>>()
+                .await
+                .map_err(TelescopeError::gitlab_api_error)
+        })
+        .await?;
+
+        // A short page is the last page.
+        let last_page: bool = page_items.len() < PER_PAGE;
+        items.extend(page_items);
+        if last_page {
+            break;
+        }
+    }
+
+    return Ok(items);
+}
+
+/// Get the push events on a project since a given time.
+pub async fn push_events(
+    config: &GitLabConfig,
+    project_path: &str,
+    since: DateTime<Utc>,
+) -> Result<Vec<Event>, TelescopeError> {
+    // The `after` filter is exclusive and by day, so filter precisely afterwards.
+    let after: String = (since.date().naive_utc() - chrono::Duration::days(1)).to_string();
+    let events: Vec<Event> = get_pages(
+        config,
+        project_path,
+        "events",
+        &[("action", "pushed".into()), ("after", after)],
+    )
+    .await?;
+    return Ok(events
+        .into_iter()
+        .filter(|event| event.created_at >= since)
+        .collect());
+}
+
+/// Get the merge requests on a project merged since a given time.
+pub async fn merged_requests(
+    config: &GitLabConfig,
+    project_path: &str,
+    since: DateTime<Utc>,
+) -> Result<Vec<MergeRequest>, TelescopeError> {
+    let merge_requests: Vec<MergeRequest> = get_pages(
+        config,
+        project_path,
+        "merge_requests",
+        &[
+            ("state", "merged".into()),
+            ("updated_after", since.to_rfc3339()),
+        ],
+    )
+    .await?;
+    return Ok(merge_requests
+        .into_iter()
+        .filter(|mr| mr.merged_at.map(|t| t >= since).unwrap_or(false))
+        .collect());
+}
@@ -0,0 +1,199 @@
+//! HTTP clients for the services Telescope talks to, set up with the
+//! configured outbound proxies.
+//!
+//! Each integration can have its own proxy (or none) through
+//! `proxy_overrides` in the config. Integrations without an override use
+//! `http_proxy` and `https_proxy`, and if neither is set the usual
+//! `HTTP_PROXY`/`HTTPS_PROXY`/`NO_PROXY` environment variables apply.
+
+use crate::env::global_config;
+use crate::web::deadline;
+use reqwest::{Client, ClientBuilder, Proxy};
+use std::collections::HashMap;
+
+/// The value of a proxy override that sends an integration's requests
+/// directly, without a proxy.
+pub const DIRECT: &'static str = "direct";
+
+/// Services Telescope sends requests to. Each can have its own proxy.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Integration {
+    /// The central RCOS API.
+    RcosApi,
+    /// The GitHub API.
+    GitHub,
+    /// The configured GitLab instance.
+    GitLab,
+    /// The Discord API, outside of the bot.
+    Discord,
+    /// The Zoom API.
+    Zoom,
+    /// The YouTube Data API.
+    YouTube,
+    /// RPI's CAS login service.
+    RpiCas,
+    /// Links checked by the link health job.
+    LinkHealth,
+    /// Endpoints registered for outgoing webhooks.
+    Webhooks,
+}
+
+impl Integration {
+    /// Every integration.
+    pub const ALL: [Integration; 9] = [
+        Integration::RcosApi,
+        Integration::GitHub,
+        Integration::GitLab,
+        Integration::Discord,
+        Integration::Zoom,
+        Integration::YouTube,
+        Integration::RpiCas,
+        Integration::LinkHealth,
+        Integration::Webhooks,
+    ];
+
+    /// The name of this integration in `proxy_overrides`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Integration::RcosApi => "rcos_api",
+            Integration::GitHub => "github",
+            Integration::GitLab => "gitlab",
+            Integration::Discord => "discord",
+            Integration::Zoom => "zoom",
+            Integration::YouTube => "youtube",
+            Integration::RpiCas => "rpi_cas",
+            Integration::LinkHealth => "link_health",
+            Integration::Webhooks => "webhooks",
+        }
+    }
+}
+
+/// The configured proxies, read from their URLs.
+struct Proxies {
+    /// Proxy for plain HTTP requests, if any.
+    http: Option<Proxy>,
+    /// Proxy for HTTPS requests, if any.
+    https: Option<Proxy>,
+    /// Proxies for single integrations by name. `None` for [`DIRECT`].
+    overrides: HashMap<String, Option<Proxy>>,
+}
+
+lazy_static! {
+    /// The configured proxies, or a message saying which one is malformed.
+    static ref PROXIES: Result<Proxies, String> = read_proxies();
+}
+
+/// Read a configured proxy URL, saying which setting it is from if it is
+/// malformed.
+fn read_proxy(
+    setting: &str,
+    url: &str,
+    read: fn(&str) -> reqwest::Result<Proxy>,
+) -> Result<Proxy, String> {
+    read(url).map_err(|e| format!("Malformed proxy URL {:?} in {}: {}", url, setting, e))
+}
+
+/// Read every configured proxy URL.
+fn read_proxies() -> Result<Proxies, String> {
+    let config = global_config();
+    let mut overrides: HashMap<String, Option<Proxy>> = HashMap::new();
+    for (name, url) in config.proxy_overrides.iter() {
+        let proxy: Option<Proxy> = if url == DIRECT {
+            None
+        } else {
+            let setting: String = format!("proxy_overrides.{}", name);
+            Some(read_proxy(setting.as_str(), url, |url| Proxy::all(url))?)
+        };
+        overrides.insert(name.clone(), proxy);
+    }
+
+    return Ok(Proxies {
+        http: config
+            .http_proxy
+            .as_deref()
+            .map(|url| read_proxy("http_proxy", url, |url| Proxy::http(url)))
+            .transpose()?,
+        https: config
+            .https_proxy
+            .as_deref()
+            .map(|url| read_proxy("https_proxy", url, |url| Proxy::https(url)))
+            .transpose()?,
+        overrides,
+    });
+}
+
+/// Get a client builder for an integration with its proxies set. Use this
+/// instead of [`Client::builder`] when other options are needed.
+pub fn client_builder(integration: Integration) -> ClientBuilder {
+    let mut builder: ClientBuilder = Client::builder();
+    // Malformed proxies stop Telescope in `init`, so there are none to set
+    // here.
+    let proxies: &Proxies = match PROXIES.as_ref() {
+        Ok(proxies) => proxies,
+        Err(_) => return builder,
+    };
+
+    // An override applies to both plain and secure requests.
+    if let Some(proxy) = proxies.overrides.get(integration.name()) {
+        return match proxy {
+            Some(proxy) => builder.proxy(proxy.clone()),
+            None => builder.no_proxy(),
+        };
+    }
+
+    // Setting either proxy stops the builder from reading the environment.
+    if let Some(proxy) = proxies.http.as_ref() {
+        builder = builder.proxy(proxy.clone());
+    }
+    if let Some(proxy) = proxies.https.as_ref() {
+        builder = builder.proxy(proxy.clone());
+    }
+    return builder;
+}
+
+/// Get a client for an integration. Use this instead of [`Client::new`].
+/// While handling a request, the client times out at the request's
+/// [deadline](crate::web::deadline).
+pub fn client(integration: Integration) -> Client {
+    let mut builder: ClientBuilder = client_builder(integration);
+    if let Some(deadline) = deadline::current() {
+        builder = builder.timeout(deadline.remaining());
+    }
+    return builder.build().expect("Could not build HTTP client");
+}
+
+/// Check the proxy config, and pass the global proxies on to the libraries
+/// that make their own HTTP clients (the Discord bot and OAuth2 token
+/// exchanges) through the environment. Those don't follow
+/// `proxy_overrides`. Exits if a proxy URL is malformed. Call this before
+/// starting anything that sends requests.
+pub fn init() {
+    let config = global_config();
+
+    if let Err(e) = PROXIES.as_ref() {
+        error!("{}", e);
+        std::process::exit(1);
+    }
+
+    for name in config.proxy_overrides.keys() {
+        if !Integration::ALL.iter().any(|i| i.name() == name) {
+            warn!(
+                "Proxy override for unknown integration {:?} is ignored.",
+                name
+            );
+        }
+    }
+
+    // Variables that are already set win over the config.
+    let exports = [
+        ("HTTP_PROXY", config.http_proxy.as_ref()),
+        ("HTTPS_PROXY", config.https_proxy.as_ref()),
+    ];
+    for (variable, url) in exports.iter() {
+        if let Some(url) = url {
+            if std::env::var_os(variable).is_none() {
+                std::env::set_var(variable, url.as_str());
+            }
+        }
+    }
+}
@@ -5,7 +5,12 @@ use graphql_client::Response;
 
 pub mod discord;
 pub mod github;
+pub mod gitlab;
+pub mod http;
 pub mod rcos;
+pub mod repository_hosts;
+pub mod video;
+pub mod youtube;
 
 /// Handle a response from a GraphQL API. Convert any errors as necessary and
 /// extract the returned data if possible.
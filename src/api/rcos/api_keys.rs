@@ -0,0 +1,119 @@
+//! Keys for the public JSON API. Only a hash of each key is stored.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL query to list API keys.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/api_keys.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ApiKeys;
+
+/// Type representing GraphQL query to look up a key by its hash.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/api_keys.graphql",
+    response_derives = "Debug,Clone"
+)]
+pub struct ApiKeyByHash;
+
+/// Type representing GraphQL mutation to add an API key.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/api_keys.graphql",
+    response_derives = "Debug"
+)]
+pub struct CreateApiKey;
+
+/// Type representing GraphQL mutation to change the tier of an API key.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/api_keys.graphql",
+    response_derives = "Debug"
+)]
+pub struct SetApiKeyTier;
+
+/// Type representing GraphQL mutation to revoke an API key.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/api_keys.graphql",
+    response_derives = "Debug"
+)]
+pub struct RevokeApiKey;
+
+impl ApiKeys {
+    /// Get every API key, including revoked ones.
+    pub async fn get() -> Result<Vec<api_keys::ApiKeysApiKeys>, TelescopeError> {
+        Ok(send_query::<Self>(api_keys::Variables {}).await?.api_keys)
+    }
+}
+
+impl ApiKeyByHash {
+    /// Get the ID and tier of the key with a hash, if it has not been
+    /// revoked.
+    pub async fn get(key_hash: String) -> Result<Option<(i64, String)>, TelescopeError> {
+        Ok(send_query::<Self>(api_key_by_hash::Variables { key_hash })
+            .await?
+            .api_keys
+            .pop()
+            .map(|key| (key.key_id, key.tier)))
+    }
+}
+
+impl CreateApiKey {
+    /// Add a key. Return its ID.
+    pub async fn execute(
+        name: String,
+        key_prefix: String,
+        key_hash: String,
+        tier: String,
+        created_by: uuid,
+    ) -> Result<i64, TelescopeError> {
+        send_query::<Self>(create_api_key::Variables {
+            name,
+            key_prefix,
+            key_hash,
+            tier,
+            created_by,
+            now: Utc::now(),
+        })
+        .await?
+        .insert_api_keys_one
+        .map(|key| key.key_id)
+        .ok_or_else(|| TelescopeError::ise("API key creation did not return an ID."))
+    }
+}
+
+impl SetApiKeyTier {
+    /// Change the tier of a key. Return false if there is no such key.
+    pub async fn execute(key_id: i64, tier: String) -> Result<bool, TelescopeError> {
+        Ok(
+            send_query::<Self>(set_api_key_tier::Variables { key_id, tier })
+                .await?
+                .update_api_keys_by_pk
+                .is_some(),
+        )
+    }
+}
+
+impl RevokeApiKey {
+    /// Revoke a key. Return false if there is no such key, or it was already
+    /// revoked.
+    pub async fn execute(key_id: i64) -> Result<bool, TelescopeError> {
+        Ok(send_query::<Self>(revoke_api_key::Variables {
+            key_id,
+            now: Utc::now(),
+        })
+        .await?
+        .update_api_keys
+        .map_or(false, |updated| updated.affected_rows > 0))
+    }
+}
@@ -0,0 +1,62 @@
+//! GraphQL queries to find the meeting an attendance code is for and to list
+//! a meeting's attendance.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+
+/// Type representing GraphQL query to look up a meeting by attendance code.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/attendance/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct MeetingByCode;
+
+/// Type representing GraphQL query to get a meeting's attendance list.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/attendance/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct MeetingAttendance;
+
+impl MeetingByCode {
+    /// Get the latest published meeting with an attendance code.
+    pub async fn get(
+        code: String,
+    ) -> Result<Option<meeting_by_code::MeetingByCodeMeetings>, TelescopeError> {
+        Ok(send_query::<Self>(meeting_by_code::Variables { code })
+            .await?
+            .meetings
+            .pop())
+    }
+}
+
+impl MeetingAttendance {
+    /// Get the attendance list for a meeting.
+    pub async fn get(
+        meeting_id: MeetingId,
+    ) -> Result<Option<meeting_attendance::MeetingAttendanceMeeting>, TelescopeError> {
+        Ok(send_query::<Self>(meeting_attendance::Variables {
+            meeting_id: meeting_id.into(),
+        })
+        .await?
+        .meeting)
+    }
+}
+
+impl meeting_attendance::MeetingAttendanceMeeting {
+    /// Get the title of this meeting, constructing one from the type and date
+    /// if it has no title of its own.
+    pub fn title(&self) -> String {
+        self.title.clone().unwrap_or_else(|| {
+            format!(
+                "RCOS {} - {}",
+                self.type_,
+                self.start_date_time.format("%B %_d, %Y")
+            )
+        })
+    }
+}
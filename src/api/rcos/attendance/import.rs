@@ -0,0 +1,146 @@
+//! GraphQL query and mutation to import attendance taken outside of Telescope,
+//! like on a paper sign-in sheet copied into a spreadsheet.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use std::collections::HashSet;
+
+/// Type representing GraphQL query to look up the RCS IDs in an import.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/attendance/import.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ImportLookup;
+
+/// Type representing GraphQL mutation to add imported attendance.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/attendance/import.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ImportAttendance;
+
+use self::import_lookup::ImportLookupAccountsUser;
+
+/// A user found by RCS ID in an import.
+#[derive(Clone, Debug, Serialize)]
+pub struct ImportedUser {
+    pub rcs_id: String,
+    pub user: ImportLookupAccountsUser,
+}
+
+/// What importing a list of RCS IDs for a meeting would do.
+#[derive(Clone, Debug, Serialize)]
+pub struct ImportPreview {
+    pub meeting_id: i64,
+    /// The meeting title, or its type and date if it has none.
+    pub title: String,
+    /// Enrolled users that would be marked as attending.
+    pub to_add: Vec<ImportedUser>,
+    /// Users that are already marked as attending.
+    pub already_attended: Vec<ImportedUser>,
+    /// Users that are not enrolled in the meeting's semester.
+    pub not_enrolled: Vec<ImportedUser>,
+    /// RCS IDs that no user has.
+    pub unknown: Vec<String>,
+}
+
+impl ImportLookup {
+    /// Sort the RCS IDs in an import by what importing them would do. Return
+    /// `None` if the meeting does not exist.
+    pub async fn get(
+        meeting_id: MeetingId,
+        rcs_ids: Vec<String>,
+    ) -> Result<Option<ImportPreview>, TelescopeError> {
+        let response = send_query::<Self>(import_lookup::Variables {
+            meeting_id: meeting_id.into(),
+            rcs_ids: rcs_ids.clone(),
+        })
+        .await?;
+
+        let meeting = match response.meeting {
+            Some(meeting) => meeting,
+            None => return Ok(None),
+        };
+
+        let enrolled: HashSet<uuid> = meeting
+            .semester
+            .enrollments
+            .iter()
+            .map(|enrollment| enrollment.user_id)
+            .collect();
+        let attended: HashSet<uuid> = meeting
+            .meeting_attendances
+            .iter()
+            .map(|attendance| attendance.user_id)
+            .collect();
+
+        let mut preview = ImportPreview {
+            meeting_id: meeting.meeting_id,
+            title: meeting.title.clone().unwrap_or_else(|| {
+                format!(
+                    "RCOS {} - {}",
+                    meeting.type_,
+                    meeting.start_date_time.format("%B %_d, %Y")
+                )
+            }),
+            to_add: Vec::new(),
+            already_attended: Vec::new(),
+            not_enrolled: Vec::new(),
+            unknown: Vec::new(),
+        };
+
+        // Keep the order of the import.
+        for rcs_id in rcs_ids {
+            let account = response
+                .accounts
+                .iter()
+                .find(|account| account.account_id == rcs_id);
+
+            let user = match account {
+                Some(account) => ImportedUser {
+                    rcs_id,
+                    user: account.user.clone(),
+                },
+                None => {
+                    preview.unknown.push(rcs_id);
+                    continue;
+                }
+            };
+
+            if attended.contains(&user.user.id) {
+                preview.already_attended.push(user);
+            } else if enrolled.contains(&user.user.id) {
+                preview.to_add.push(user);
+            } else {
+                preview.not_enrolled.push(user);
+            }
+        }
+
+        return Ok(Some(preview));
+    }
+}
+
+impl ImportAttendance {
+    /// Mark a user as attending a meeting from an import. Return false if they
+    /// were already marked as attending.
+    pub async fn execute(
+        meeting_id: MeetingId,
+        user_id: uuid,
+        added_by: uuid,
+        source: String,
+    ) -> Result<bool, TelescopeError> {
+        Ok(send_query::<Self>(import_attendance::Variables {
+            meeting_id: meeting_id.into(),
+            user_id,
+            added_by,
+            source,
+        })
+        .await?
+        .insert_meeting_attendances_one
+        .is_some())
+    }
+}
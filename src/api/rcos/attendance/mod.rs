@@ -0,0 +1,42 @@
+//! Meeting attendance and the window in which members can check in.
+
+use crate::api::rcos::meetings::MeetingType;
+use crate::env::global_config;
+use chrono::{DateTime, Duration, Utc};
+
+pub mod get;
+pub mod import;
+pub mod mutations;
+pub mod policy;
+
+/// When check-in for a meeting is open.
+#[derive(Copy, Clone, Debug, Serialize)]
+pub struct CheckInTimes {
+    /// When attendance codes start being accepted.
+    pub opens: DateTime<Utc>,
+    /// When attendance codes stop being accepted.
+    pub closes: DateTime<Utc>,
+}
+
+impl CheckInTimes {
+    /// Get the check-in times for a meeting from the configured window for its
+    /// type and any extension set by the host.
+    pub fn for_meeting(
+        kind: MeetingType,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        extended_until: Option<DateTime<Utc>>,
+    ) -> Self {
+        let window = global_config().check_in_window(kind);
+        let closes: DateTime<Utc> = end + Duration::minutes(window.closes_after);
+        CheckInTimes {
+            opens: start - Duration::minutes(window.opens_before),
+            closes: extended_until.map(|t| t.max(closes)).unwrap_or(closes),
+        }
+    }
+
+    /// Check if check-in is open at a given time.
+    pub fn is_open_at(&self, time: DateTime<Utc>) -> bool {
+        self.opens <= time && time <= self.closes
+    }
+}
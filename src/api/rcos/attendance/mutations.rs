@@ -0,0 +1,103 @@
+//! GraphQL mutations to record attendance, set attendance codes, and extend
+//! check-in.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+
+/// Type representing GraphQL mutation to record attendance.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/attendance/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RecordAttendance;
+
+/// Type representing GraphQL mutation to extend check-in for a meeting.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/attendance/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ExtendCheckIn;
+
+/// Type representing GraphQL mutation to set a meeting's attendance code.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/attendance/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SetAttendanceCode;
+
+/// Type representing GraphQL mutation for a host to add attendance by hand.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/attendance/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AddAttendance;
+
+impl RecordAttendance {
+    /// Record that a user attended a meeting.
+    pub async fn execute(meeting_id: MeetingId, user_id: uuid) -> Result<(), TelescopeError> {
+        send_query::<Self>(record_attendance::Variables {
+            meeting_id: meeting_id.into(),
+            user_id,
+        })
+        .await
+        .map(|_| ())
+    }
+}
+
+impl ExtendCheckIn {
+    /// Keep check-in for a meeting open until a given time.
+    pub async fn execute(meeting_id: MeetingId, until: timestamptz) -> Result<(), TelescopeError> {
+        send_query::<Self>(extend_check_in::Variables {
+            meeting_id: meeting_id.into(),
+            until,
+        })
+        .await
+        .map(|_| ())
+    }
+}
+
+impl SetAttendanceCode {
+    /// Set the code members check in to a meeting with, and when it expires.
+    pub async fn execute(
+        meeting_id: MeetingId,
+        code: String,
+        expires_at: timestamptz,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(set_attendance_code::Variables {
+            meeting_id: meeting_id.into(),
+            code,
+            expires_at,
+        })
+        .await
+        .map(|_| ())
+    }
+}
+
+impl AddAttendance {
+    /// Mark a user as attending a meeting on their behalf. Return false if
+    /// they were already marked as attending.
+    pub async fn execute(
+        meeting_id: MeetingId,
+        user_id: uuid,
+        added_by: uuid,
+        note: Option<String>,
+    ) -> Result<bool, TelescopeError> {
+        Ok(send_query::<Self>(add_attendance::Variables {
+            meeting_id: meeting_id.into(),
+            user_id,
+            added_by,
+            note,
+        })
+        .await?
+        .insert_meeting_attendances_one
+        .is_some())
+    }
+}
@@ -0,0 +1,253 @@
+//! Attendance policies and excused absences.
+//!
+//! Coordinators set how many meetings members have to attend each semester.
+//! Members can ask to be excused from meetings they missed, and a limited
+//! number of approved excuses count as attendance. Only meetings of types that
+//! count towards attendance are considered.
+
+use crate::api::rcos::semesters::current::info::CurrentSemesters;
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL mutation to set a semester's attendance policy.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/attendance/policy.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SetAttendancePolicy;
+
+/// Type representing GraphQL query to get a member's attendance in a semester.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/attendance/policy.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct MyAttendance;
+
+/// Type representing GraphQL mutation to ask to be excused from a meeting.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/attendance/policy.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RequestExcuse;
+
+/// Type representing GraphQL query to get everyone's attendance in a semester.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/attendance/policy.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AttendanceReport;
+
+/// Type representing GraphQL mutation to approve or deny an excuse request.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/attendance/policy.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct DecideExcuse;
+
+/// How much attendance a semester requires.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Policy {
+    /// How many meetings members have to attend.
+    pub required_count: i64,
+    /// How many approved excuses count as attendance.
+    pub max_excused: i64,
+}
+
+/// A member's attendance measured against a semester's policy.
+#[derive(Copy, Clone, Debug, Serialize)]
+pub struct Standing {
+    pub attended: i64,
+    /// Approved excused absences.
+    pub excused: i64,
+    /// The approved excuses that count as attendance.
+    pub counted_excused: i64,
+    /// The number of meetings to attend, if the semester has a policy.
+    pub required: Option<i64>,
+    /// How many more meetings have to be attended to meet the policy.
+    pub remaining: i64,
+    /// Does the member meet the policy? Always true without a policy.
+    pub meets_requirement: bool,
+    /// How far along the member is, out of 100.
+    pub percent: i64,
+}
+
+impl Standing {
+    /// Measure attendance against a policy.
+    pub fn new(policy: Option<Policy>, attended: i64, excused: i64) -> Self {
+        let counted_excused: i64 = policy.map_or(0, |p| excused.min(p.max_excused.max(0)));
+        let required: Option<i64> = policy.map(|p| p.required_count);
+        let remaining: i64 =
+            required.map_or(0, |required| (required - attended - counted_excused).max(0));
+        let percent: i64 = match required {
+            Some(required) if required > 0 => {
+                (100 * (attended + counted_excused) / required).min(100)
+            }
+            _ => 100,
+        };
+
+        Standing {
+            attended,
+            excused,
+            counted_excused,
+            required,
+            remaining,
+            meets_requirement: remaining == 0,
+            percent,
+        }
+    }
+}
+
+/// The status of an excuse request.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+pub enum ExcuseStatus {
+    #[display(fmt = "pending")]
+    Pending,
+    #[display(fmt = "approved")]
+    Approved,
+    #[display(fmt = "denied")]
+    Denied,
+}
+
+impl SetAttendancePolicy {
+    /// Set the attendance policy of a semester.
+    pub async fn execute(
+        semester_id: SemesterId,
+        policy: Policy,
+        updated_by: uuid,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(set_attendance_policy::Variables {
+            semester_id: semester_id.into(),
+            required_count: policy.required_count,
+            max_excused: policy.max_excused,
+            updated_by,
+            now: Utc::now(),
+        })
+        .await?;
+        return Ok(());
+    }
+}
+
+impl MyAttendance {
+    /// Get a member's attendance in a semester.
+    pub async fn get(
+        semester_id: SemesterId,
+        user_id: uuid,
+    ) -> Result<my_attendance::ResponseData, TelescopeError> {
+        send_query::<Self>(my_attendance::Variables {
+            semester_id: semester_id.into(),
+            user_id,
+            now: Utc::now(),
+        })
+        .await
+    }
+
+    /// Get a member's attendance in the current semester, if there is one.
+    pub async fn current(
+        user_id: uuid,
+    ) -> Result<Option<my_attendance::ResponseData>, TelescopeError> {
+        let semester = match CurrentSemesters::get().await?.into_iter().next() {
+            Some(semester) => semester,
+            None => return Ok(None),
+        };
+        return Self::get(SemesterId(semester.semester_id), user_id)
+            .await
+            .map(Some);
+    }
+}
+
+impl my_attendance::ResponseData {
+    /// The semester's policy, if it has one.
+    pub fn policy(&self) -> Option<Policy> {
+        self.semester
+            .as_ref()
+            .and_then(|semester| semester.attendance_policy.as_ref())
+            .map(|policy| Policy {
+                required_count: policy.required_count,
+                max_excused: policy.max_excused,
+            })
+    }
+
+    /// The member's standing against the semester's policy.
+    pub fn standing(&self) -> Standing {
+        let attended = self
+            .meetings
+            .iter()
+            .filter(|meeting| !meeting.attended.is_empty())
+            .count();
+        let excused = self
+            .meetings
+            .iter()
+            .filter(|meeting| meeting.attended.is_empty())
+            .flat_map(|meeting| meeting.excuse.iter())
+            .filter(|excuse| excuse.status == ExcuseStatus::Approved.to_string())
+            .count();
+        return Standing::new(self.policy(), attended as i64, excused as i64);
+    }
+}
+
+impl RequestExcuse {
+    /// Ask to be excused from a meeting. Return false if the request was
+    /// already decided, so it could not be changed.
+    pub async fn execute(
+        meeting_id: MeetingId,
+        user_id: uuid,
+        reason: String,
+    ) -> Result<bool, TelescopeError> {
+        Ok(send_query::<Self>(request_excuse::Variables {
+            meeting_id: meeting_id.into(),
+            user_id,
+            reason,
+            now: Utc::now(),
+        })
+        .await?
+        .insert_excused_absences_one
+        .is_some())
+    }
+}
+
+impl AttendanceReport {
+    /// Get everyone's attendance in a semester.
+    pub async fn get(
+        semester_id: SemesterId,
+    ) -> Result<attendance_report::ResponseData, TelescopeError> {
+        send_query::<Self>(attendance_report::Variables {
+            semester_id: semester_id.into(),
+            now: Utc::now(),
+        })
+        .await
+    }
+}
+
+impl DecideExcuse {
+    /// Approve or deny an excuse request. Return false if there is no such
+    /// request.
+    pub async fn execute(
+        meeting_id: MeetingId,
+        user_id: uuid,
+        status: ExcuseStatus,
+        decided_by: uuid,
+    ) -> Result<bool, TelescopeError> {
+        Ok(send_query::<Self>(decide_excuse::Variables {
+            meeting_id: meeting_id.into(),
+            user_id,
+            status: status.to_string(),
+            decided_by,
+            now: Utc::now(),
+        })
+        .await?
+        .update_excused_absences_by_pk
+        .is_some())
+    }
+}
@@ -0,0 +1,71 @@
+//! The audit log, a record of coordinators overriding rules.
+//!
+//! Entries are only ever added. Each has the kind of action, who took it, a
+//! link to what it was taken on, and an optional note.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL mutation to add an entry to the audit log.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/audit_log.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RecordAudit;
+
+/// Type representing GraphQL query to get the latest entries of the audit log.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/audit_log.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RecentAudits;
+
+/// The kinds of action recorded in the audit log.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Display)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// A request to join a project was approved even though the team would
+    /// break the semester's team constraints.
+    #[display(fmt = "join_request_override")]
+    JoinRequestOverride,
+}
+
+impl RecordAudit {
+    /// Add an entry to the audit log.
+    pub async fn execute(
+        actor_id: uuid,
+        action: AuditAction,
+        target_url: String,
+        note: Option<String>,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(record_audit::Variables {
+            actor_id,
+            action: action.to_string(),
+            target_url,
+            note,
+            now: Utc::now(),
+        })
+        .await?;
+        return Ok(());
+    }
+}
+
+impl RecentAudits {
+    /// Get the latest entries of the audit log for one kind of action.
+    pub async fn get(
+        action: AuditAction,
+        limit: i64,
+    ) -> Result<Vec<recent_audits::RecentAuditsAuditLog>, TelescopeError> {
+        send_query::<Self>(recent_audits::Variables {
+            action: action.to_string(),
+            limit,
+        })
+        .await
+        .map(|response| response.audit_log)
+    }
+}
@@ -0,0 +1,81 @@
+//! GraphQL queries to get badges and the users eligible for them.
+
+use crate::api::rcos::badges::{
+    COORDINATOR_EMERITUS, FIRST_COMMIT, MEETING_THRESHOLD, MENTOR, TEN_MEETINGS,
+};
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL query to get the badge catalog.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/badges/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct BadgeCatalog;
+
+/// Type representing GraphQL query to find users who qualify for an
+/// automatic badge they do not hold yet.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/badges/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct BadgeEligibility;
+
+impl BadgeCatalog {
+    /// Get every badge, automatic badges first.
+    pub async fn get() -> Result<Vec<badge_catalog::BadgeCatalogBadges>, TelescopeError> {
+        Ok(send_query::<Self>(badge_catalog::Variables {})
+            .await?
+            .badges)
+    }
+}
+
+impl BadgeEligibility {
+    /// Get a list of `(user_id, badge_id)` pairs for every automatic badge
+    /// that should be awarded.
+    pub async fn get() -> Result<Vec<(uuid, &'static str)>, TelescopeError> {
+        let data = send_query::<Self>(badge_eligibility::Variables {
+            now: Utc::today().naive_utc(),
+            attendance_badge: TEN_MEETINGS.to_string(),
+            mentor_badge: MENTOR.to_string(),
+            emeritus_badge: COORDINATOR_EMERITUS.to_string(),
+            first_commit_badge: FIRST_COMMIT.to_string(),
+        })
+        .await?;
+
+        // Filter attendees down to those who have reached the threshold.
+        let attendees = data
+            .attendees
+            .into_iter()
+            .filter(|user| {
+                user.meeting_attendances_aggregate
+                    .aggregate
+                    .as_ref()
+                    .map(|agg| agg.count)
+                    .unwrap_or(0)
+                    >= MEETING_THRESHOLD
+            })
+            .map(|user| (user.id, TEN_MEETINGS));
+
+        let mentors = data.mentors.into_iter().map(|user| (user.id, MENTOR));
+        let emeriti = data
+            .emeriti
+            .into_iter()
+            .map(|user| (user.id, COORDINATOR_EMERITUS));
+        let committers = data
+            .committers
+            .into_iter()
+            .map(|user| (user.id, FIRST_COMMIT));
+
+        return Ok(attendees
+            .chain(mentors)
+            .chain(emeriti)
+            .chain(committers)
+            .collect());
+    }
+}
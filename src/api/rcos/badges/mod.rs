@@ -0,0 +1,20 @@
+//! GraphQL queries and mutations on badges and badge awards.
+
+pub mod get;
+pub mod mutations;
+
+/// Badge awarded to users who land their first commit on an RCOS project.
+pub const FIRST_COMMIT: &'static str = "first-commit";
+
+/// Badge awarded to users who have attended enough meetings.
+pub const TEN_MEETINGS: &'static str = "ten-meetings";
+
+/// Badge awarded to users who have mentored a small group.
+pub const MENTOR: &'static str = "mentor";
+
+/// Badge awarded to users who coordinated a semester that has since ended.
+pub const COORDINATOR_EMERITUS: &'static str = "coordinator-emeritus";
+
+/// The number of (non-draft) meetings a user needs to attend to earn the
+/// [`TEN_MEETINGS`] badge.
+pub const MEETING_THRESHOLD: i64 = 10;
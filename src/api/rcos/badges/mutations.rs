@@ -0,0 +1,49 @@
+//! GraphQL mutations to award and revoke badges.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+
+/// Type representing GraphQL mutation to award a badge to a user.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/badges/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AwardBadge;
+
+/// Type representing GraphQL mutation to revoke a badge from a user.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/badges/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RevokeBadge;
+
+impl AwardBadge {
+    /// Award a badge to a user. The awarding user should be `None` for
+    /// automatic awards. Return `true` if the badge was newly awarded.
+    pub async fn execute(
+        user_id: uuid,
+        badge_id: String,
+        awarded_by: Option<uuid>,
+    ) -> Result<bool, TelescopeError> {
+        send_query::<Self>(award_badge::Variables {
+            user_id,
+            badge_id,
+            awarded_by,
+        })
+        .await
+        .map(|data| data.insert_user_badges_one.is_some())
+    }
+}
+
+impl RevokeBadge {
+    /// Revoke a badge from a user. Return `true` if the user held the badge.
+    pub async fn execute(user_id: uuid, badge_id: String) -> Result<bool, TelescopeError> {
+        send_query::<Self>(revoke_badge::Variables { user_id, badge_id })
+            .await
+            .map(|data| data.delete_user_badges_by_pk.is_some())
+    }
+}
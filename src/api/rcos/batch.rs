@@ -0,0 +1,81 @@
+//! Run many RCOS API mutations at once and report which ones failed.
+//!
+//! Hasura runs each mutation in its own transaction, so a batch can partially
+//! succeed. Rather than stopping at the first error, every item is run and
+//! the outcome of each is collected into a [`BatchReport`], which serializes
+//! into the shape expected by the `batch/report` template partial.
+
+use crate::error::TelescopeError;
+use futures::stream::{self, StreamExt};
+use std::future::Future;
+
+/// The default number of mutations in flight at once. This is kept low so a
+/// large batch does not crowd out other requests to the API.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// The outcome of one item of a batch.
+#[derive(Clone, Debug, Serialize)]
+pub struct BatchOutcome<T> {
+    /// Describes the item to the user, e.g. the title of a meeting.
+    pub label: String,
+    /// The value the item produced, if it succeeded.
+    pub value: Option<T>,
+    /// The error message, if it failed.
+    pub error: Option<String>,
+}
+
+/// The outcomes of every item of a batch, in the order the items were given.
+#[derive(Clone, Debug, Serialize)]
+pub struct BatchReport<T> {
+    pub outcomes: Vec<BatchOutcome<T>>,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+impl<T> BatchReport<T> {
+    /// Did every item succeed?
+    pub fn is_complete(&self) -> bool {
+        self.failed == 0
+    }
+}
+
+/// Run a batch of labeled API calls with at most `concurrency` in flight at
+/// once. Every call is run even if some fail.
+pub async fn batch_execute<T, F, I>(items: I, concurrency: usize) -> BatchReport<T>
+where
+    I: IntoIterator<Item = (String, F)>,
+    F: Future<Output = Result<T, TelescopeError>>,
+{
+    let outcomes: Vec<BatchOutcome<T>> = stream::iter(items)
+        .map(|(label, call)| async move {
+            match call.await {
+                Ok(value) => BatchOutcome {
+                    label,
+                    value: Some(value),
+                    error: None,
+                },
+                Err(e) => {
+                    warn!("Batch item {} failed: {}", label, e);
+                    BatchOutcome {
+                        label,
+                        value: None,
+                        error: Some(e.to_string()),
+                    }
+                }
+            }
+        })
+        // Buffered keeps the outcomes in the same order as the items.
+        .buffered(concurrency.max(1))
+        .collect()
+        .await;
+
+    let failed: usize = outcomes
+        .iter()
+        .filter(|outcome| outcome.error.is_some())
+        .count();
+    return BatchReport {
+        succeeded: outcomes.len() - failed,
+        failed,
+        outcomes,
+    };
+}
@@ -0,0 +1,26 @@
+//! GraphQL query for the projects whose contributions are tracked.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL query to get projects with members in an
+/// ongoing semester and those members' repository host accounts.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/contributions/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct TrackedProjects;
+
+impl TrackedProjects {
+    /// Get the projects to track contributions for.
+    pub async fn get() -> Result<Vec<tracked_projects::TrackedProjectsProjects>, TelescopeError> {
+        Ok(send_query::<Self>(tracked_projects::Variables {
+            now: Utc::today().naive_utc(),
+        })
+        .await?
+        .projects)
+    }
+}
@@ -0,0 +1,37 @@
+//! Weekly repository contribution tallies for project members.
+
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use std::collections::HashMap;
+
+pub mod get;
+pub mod mutations;
+
+/// The number of weeks shown in contribution sparklines.
+pub const SPARKLINE_WEEKS: i64 = 12;
+
+/// Get the Monday starting the week a date is in.
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// Get the first day of the earliest week shown in contribution sparklines.
+pub fn sparkline_since() -> NaiveDate {
+    week_start(Local::today().naive_local()) - Duration::weeks(SPARKLINE_WEEKS - 1)
+}
+
+/// Sum weekly tallies into one total per week for the sparkline window,
+/// oldest first. Weeks without any tallies are zero.
+pub fn weekly_series(tallies: impl IntoIterator<Item = (NaiveDate, i64)>) -> Vec<i64> {
+    let since: NaiveDate = sparkline_since();
+
+    // Sum the tallies for each week.
+    let mut totals: HashMap<NaiveDate, i64> = HashMap::new();
+    for (week, count) in tallies {
+        *totals.entry(week).or_insert(0) += count;
+    }
+
+    return (0..SPARKLINE_WEEKS)
+        .map(|offset| since + Duration::weeks(offset))
+        .map(|week| totals.get(&week).copied().unwrap_or(0))
+        .collect();
+}
@@ -0,0 +1,36 @@
+//! GraphQL mutation to save weekly contribution tallies.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL mutation to save a contribution tally.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/contributions/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SaveContributionWeek;
+
+impl SaveContributionWeek {
+    /// Save a member's tally for a week of work on a project.
+    pub async fn execute(
+        user_id: uuid,
+        project_id: i64,
+        week_start: date,
+        commits: i64,
+        pull_requests: i64,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(save_contribution_week::Variables {
+            user_id,
+            project_id,
+            week_start,
+            commits,
+            pull_requests,
+            now: Utc::now(),
+        })
+        .await
+        .map(|_| ())
+    }
+}
@@ -0,0 +1,75 @@
+//! GraphQL queries to get custom pages.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+
+/// Type representing GraphQL query to get a custom page by its slug.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/custom_pages/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct CustomPage;
+
+/// Type representing GraphQL query to get the pages linked in the navbar.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/custom_pages/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct NavigationPages;
+
+/// Type representing GraphQL query to list all custom pages for the admin panel.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/custom_pages/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AllCustomPages;
+
+/// Type representing GraphQL query to get the revision history of a custom page.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/custom_pages/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct CustomPageRevisions;
+
+impl CustomPage {
+    /// Get a custom page by its slug.
+    pub async fn get(
+        slug: String,
+    ) -> Result<Option<custom_page::CustomPageCustomPagesByPk>, TelescopeError> {
+        Ok(send_query::<Self>(custom_page::Variables { slug })
+            .await?
+            .custom_pages_by_pk)
+    }
+}
+
+impl NavigationPages {
+    /// Get the published pages to link in the navbar, in order.
+    pub async fn get() -> Result<Vec<navigation_pages::NavigationPagesCustomPages>, TelescopeError>
+    {
+        Ok(send_query::<Self>(navigation_pages::Variables {})
+            .await?
+            .custom_pages)
+    }
+}
+
+impl AllCustomPages {
+    /// Get every custom page including drafts.
+    pub async fn get() -> Result<all_custom_pages::ResponseData, TelescopeError> {
+        send_query::<Self>(all_custom_pages::Variables {}).await
+    }
+}
+
+impl CustomPageRevisions {
+    /// Get the revision history of a custom page.
+    pub async fn get(slug: String) -> Result<custom_page_revisions::ResponseData, TelescopeError> {
+        send_query::<Self>(custom_page_revisions::Variables { slug }).await
+    }
+}
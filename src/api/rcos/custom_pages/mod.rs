@@ -0,0 +1,4 @@
+//! GraphQL queries and mutations for coordinator managed markdown pages.
+
+pub mod get;
+pub mod mutations;
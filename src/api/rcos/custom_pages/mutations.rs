@@ -0,0 +1,100 @@
+//! GraphQL mutations to create, edit, and delete custom pages.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL mutation to create a custom page.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/custom_pages/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct CreateCustomPage;
+
+/// Type representing GraphQL mutation to edit a custom page and save a revision.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/custom_pages/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct EditCustomPage;
+
+/// Type representing GraphQL mutation to delete a custom page and its history.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/custom_pages/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct DeleteCustomPage;
+
+/// The user editable content of a custom page.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CustomPageContent {
+    /// The title of the page.
+    pub title: String,
+    /// The markdown body of the page.
+    pub body: String,
+    /// Is this page hidden from non-admins?
+    pub is_draft: bool,
+    /// Should this page be linked in the navbar?
+    pub show_in_nav: bool,
+    /// The position of the page in the navbar.
+    pub nav_order: i64,
+}
+
+impl CreateCustomPage {
+    /// Create a custom page. Return the slug of the created page.
+    pub async fn execute(
+        slug: String,
+        content: CustomPageContent,
+        author: uuid,
+    ) -> Result<Option<String>, TelescopeError> {
+        send_query::<Self>(create_custom_page::Variables {
+            slug,
+            title: content.title,
+            body: content.body,
+            is_draft: content.is_draft,
+            show_in_nav: content.show_in_nav,
+            nav_order: content.nav_order,
+            author,
+        })
+        .await
+        .map(|data| data.insert_custom_pages_one.map(|page| page.slug))
+    }
+}
+
+impl EditCustomPage {
+    /// Edit a custom page and save the new content as a revision. Return the slug
+    /// of the page if it exists.
+    pub async fn execute(
+        slug: String,
+        content: CustomPageContent,
+        author: uuid,
+    ) -> Result<Option<String>, TelescopeError> {
+        send_query::<Self>(edit_custom_page::Variables {
+            slug,
+            title: content.title,
+            body: content.body,
+            is_draft: content.is_draft,
+            show_in_nav: content.show_in_nav,
+            nav_order: content.nav_order,
+            author,
+            now: Utc::now(),
+        })
+        .await
+        .map(|data| data.update_custom_pages_by_pk.map(|page| page.slug))
+    }
+}
+
+impl DeleteCustomPage {
+    /// Delete a custom page. Return the slug of the deleted page if it existed.
+    pub async fn execute(slug: String) -> Result<Option<String>, TelescopeError> {
+        send_query::<Self>(delete_custom_page::Variables { slug })
+            .await
+            .map(|data| data.delete_custom_pages_by_pk.map(|page| page.slug))
+    }
+}
@@ -0,0 +1,34 @@
+//! RCOS API mutation to forget a Discord category or channel that no longer
+//! exists.
+
+use crate::api::rcos::send_query;
+use crate::error::TelescopeError;
+
+/// Type representing GraphQL mutation to delete every association with a
+/// Discord category or channel.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/discord_associations/forget_channel.graphql"
+)]
+pub struct ForgetDiscordChannel;
+
+impl ForgetDiscordChannel {
+    /// Delete every association with a category or channel. Return how many
+    /// were deleted.
+    pub async fn execute(channel_id: String) -> Result<i64, TelescopeError> {
+        send_query::<Self>(forget_discord_channel::Variables { channel_id })
+            .await
+            .map(|response| {
+                response
+                    .delete_small_group_categories
+                    .map_or(0, |r| r.affected_rows)
+                    + response
+                        .delete_small_group_channels
+                        .map_or(0, |r| r.affected_rows)
+                    + response
+                        .delete_project_channels
+                        .map_or(0, |r| r.affected_rows)
+            })
+    }
+}
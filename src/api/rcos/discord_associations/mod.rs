@@ -1,4 +1,5 @@
 //! GraphQL types queries and mutations related to entities on the RCOS discord server.
+pub mod forget_channel;
 /// Type representing the different kinds of channels that can be associated with a small
 /// group or a project.
 pub mod project;
@@ -0,0 +1,44 @@
+//! GraphQL queries to get Discord server settings.
+
+use crate::api::rcos::send_query;
+use crate::error::TelescopeError;
+
+/// Type representing GraphQL query to get the settings of every configured
+/// Discord server.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/discord_guilds/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AllDiscordGuilds;
+
+/// Type representing GraphQL query to get the settings of a Discord server.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/discord_guilds/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct DiscordGuild;
+
+impl AllDiscordGuilds {
+    /// Get the settings of every configured Discord server.
+    pub async fn get(
+    ) -> Result<Vec<all_discord_guilds::AllDiscordGuildsDiscordGuilds>, TelescopeError> {
+        Ok(send_query::<Self>(all_discord_guilds::Variables {})
+            .await?
+            .discord_guilds)
+    }
+}
+
+impl DiscordGuild {
+    /// Get the settings of a Discord server by ID.
+    pub async fn get(
+        guild_id: String,
+    ) -> Result<Option<discord_guild::DiscordGuildDiscordGuildsByPk>, TelescopeError> {
+        Ok(send_query::<Self>(discord_guild::Variables { guild_id })
+            .await?
+            .discord_guilds_by_pk)
+    }
+}
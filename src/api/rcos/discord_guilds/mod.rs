@@ -0,0 +1,4 @@
+//! GraphQL queries and mutations on per-server Discord bot settings.
+
+pub mod get;
+pub mod mutations;
@@ -0,0 +1,78 @@
+//! GraphQL mutations to save and delete Discord server settings.
+
+use crate::api::rcos::send_query;
+use crate::error::TelescopeError;
+
+/// Type representing GraphQL mutation to create or replace the settings of a
+/// Discord server.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/discord_guilds/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SaveDiscordGuild;
+
+/// Type representing GraphQL mutation to delete the settings of a Discord
+/// server.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/discord_guilds/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct DeleteDiscordGuild;
+
+/// The editable settings of a Discord server.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DiscordGuildFields {
+    /// The Discord server ID.
+    pub guild_id: String,
+    /// A name to recognize the server by on the admin panel.
+    pub name: Option<String>,
+    /// The channel announcements are posted in.
+    pub announcements_channel_id: Option<String>,
+    /// The role given to members with a verified Telescope account.
+    pub member_role_id: Option<String>,
+    /// The role given to members enrolled in a current semester.
+    pub student_role_id: Option<String>,
+    /// The role given to members mentoring a small group this semester.
+    pub mentor_role_id: Option<String>,
+    /// The role given to coordinators of a current semester.
+    pub coordinator_role_id: Option<String>,
+    /// The role given to faculty and faculty advisors.
+    pub faculty_role_id: Option<String>,
+    /// Whether Telescope's slash commands are registered on this server.
+    pub commands_enabled: bool,
+    /// Whether announcements are posted on this server.
+    pub announcements_enabled: bool,
+}
+
+impl SaveDiscordGuild {
+    /// Create or replace the settings of a Discord server. Return its ID.
+    pub async fn execute(fields: DiscordGuildFields) -> Result<Option<String>, TelescopeError> {
+        send_query::<Self>(save_discord_guild::Variables {
+            guild_id: fields.guild_id,
+            name: fields.name,
+            announcements_channel_id: fields.announcements_channel_id,
+            member_role_id: fields.member_role_id,
+            student_role_id: fields.student_role_id,
+            mentor_role_id: fields.mentor_role_id,
+            coordinator_role_id: fields.coordinator_role_id,
+            faculty_role_id: fields.faculty_role_id,
+            commands_enabled: fields.commands_enabled,
+            announcements_enabled: fields.announcements_enabled,
+        })
+        .await
+        .map(|data| data.insert_discord_guilds_one.map(|obj| obj.guild_id))
+    }
+}
+
+impl DeleteDiscordGuild {
+    /// Delete the settings of a Discord server. Return the ID if it existed.
+    pub async fn execute(guild_id: String) -> Result<Option<String>, TelescopeError> {
+        send_query::<Self>(delete_discord_guild::Variables { guild_id })
+            .await
+            .map(|data| data.delete_discord_guilds_by_pk.map(|obj| obj.guild_id))
+    }
+}
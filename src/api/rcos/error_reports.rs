@@ -0,0 +1,127 @@
+//! Reports filed from server error pages.
+//!
+//! Each report records the request that failed, so it can be found in the logs
+//! by its request ID, along with what the user says they were doing.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL mutation to file an error report.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/error_reports.graphql",
+    response_derives = "Debug"
+)]
+pub struct FileErrorReport;
+
+/// Type representing GraphQL query to get the error report queue.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/error_reports.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ErrorReportQueue;
+
+/// Type representing GraphQL query to get an error report.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/error_reports.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ErrorReportById;
+
+/// Type representing GraphQL mutation to close an error report.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/error_reports.graphql",
+    response_derives = "Debug"
+)]
+pub struct CloseErrorReport;
+
+/// Type representing GraphQL mutation to link an error report to a GitHub
+/// issue.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/error_reports.graphql",
+    response_derives = "Debug"
+)]
+pub struct SetErrorReportIssue;
+
+/// An error report, as shown in the queue.
+pub type ErrorReport = error_report_by_id::ErrorReportByIdReport;
+
+/// A report to file, as submitted from an error page.
+#[derive(Clone, Debug)]
+pub struct NewErrorReport {
+    pub request_id: String,
+    pub route: String,
+    pub status: i64,
+    pub occurred_at: timestamptz,
+    pub description: String,
+    pub reporter_id: Option<uuid>,
+}
+
+impl FileErrorReport {
+    /// File an error report. Return the new report's ID.
+    pub async fn execute(report: NewErrorReport) -> Result<i64, TelescopeError> {
+        send_query::<Self>(file_error_report::Variables {
+            request_id: report.request_id,
+            route: report.route,
+            status: report.status,
+            occurred_at: report.occurred_at,
+            description: report.description,
+            reporter_id: report.reporter_id,
+            now: Utc::now(),
+        })
+        .await?
+        .insert_error_reports_one
+        .map(|report| report.report_id)
+        .ok_or(TelescopeError::ise("Error report was not filed."))
+    }
+}
+
+impl ErrorReportQueue {
+    /// Get the open error reports and the most recently closed ones.
+    pub async fn get() -> Result<error_report_queue::ResponseData, TelescopeError> {
+        send_query::<Self>(error_report_queue::Variables {}).await
+    }
+}
+
+impl ErrorReportById {
+    /// Get an error report by its ID.
+    pub async fn get(report_id: i64) -> Result<Option<ErrorReport>, TelescopeError> {
+        send_query::<Self>(error_report_by_id::Variables { report_id })
+            .await
+            .map(|data| data.report)
+    }
+}
+
+impl CloseErrorReport {
+    /// Close an error report. Return false if there is no such report.
+    pub async fn execute(report_id: i64) -> Result<bool, TelescopeError> {
+        send_query::<Self>(close_error_report::Variables {
+            report_id,
+            now: Utc::now(),
+        })
+        .await
+        .map(|data| data.update_error_reports_by_pk.is_some())
+    }
+}
+
+impl SetErrorReportIssue {
+    /// Record the GitHub issue made from an error report.
+    pub async fn execute(report_id: i64, issue_url: String) -> Result<(), TelescopeError> {
+        send_query::<Self>(set_error_report_issue::Variables {
+            report_id,
+            issue_url,
+        })
+        .await
+        .map(|_| ())
+    }
+}
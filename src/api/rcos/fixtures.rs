@@ -0,0 +1,79 @@
+//! Offline mode for the RCOS API. When `api_fixtures` is set in the config,
+//! queries are answered from JSON files in that directory instead of being
+//! sent to Hasura, so the site can be run without a database.
+//!
+//! Each operation is read from a file named after it, e.g.
+//! `LandingPageStatistics.json`. The file holds either a GraphQL response
+//! (an object with `data` and/or `errors`) or a list of them. In a list, each
+//! response may have a `variables` object; the first response whose variables
+//! all equal the ones sent is used, and a response without variables matches
+//! anything.
+
+use crate::api::handle_graphql_response;
+use crate::error::TelescopeError;
+use graphql_client::Response as GraphQlResponse;
+use serde_json::Value;
+use std::path::Path;
+
+/// The name of the fixture source in error messages.
+const FIXTURES_NAME: &'static str = "RCOS API fixtures";
+
+/// Check if a fixture response applies to the variables of a query.
+fn matches(fixture: &Value, variables: &Value) -> bool {
+    match fixture.get("variables").and_then(Value::as_object) {
+        Some(expected) => expected
+            .iter()
+            .all(|(name, value)| variables.get(name) == Some(value)),
+        None => true,
+    }
+}
+
+/// Answer a query from the fixture files in a directory.
+pub async fn respond(
+    directory: &Path,
+    operation_name: &str,
+    variables: &Value,
+) -> Result<Value, TelescopeError> {
+    let path = directory.join(format!("{}.json", operation_name));
+    let contents: String = actix_web::web::block({
+        let path = path.clone();
+        move || std::fs::read_to_string(path)
+    })
+    .await
+    .map_err(|e| {
+        warn!("No RCOS API fixture at {}: {}", path.display(), e);
+        TelescopeError::RcosApiError(format!(
+            "No fixture for {} at {}.",
+            operation_name,
+            path.display()
+        ))
+    })?;
+
+    let fixture: Value = serde_json::from_str(contents.as_str()).map_err(|e| {
+        TelescopeError::RcosApiError(format!("Invalid fixture at {}: {}", path.display(), e))
+    })?;
+
+    // Pick the response to use from a list of them.
+    let response: Value = match fixture {
+        Value::Array(responses) => responses
+            .into_iter()
+            .find(|response| matches(response, variables))
+            .ok_or(TelescopeError::RcosApiError(format!(
+                "No fixture in {} matches the variables {}.",
+                path.display(),
+                variables
+            )))?,
+        response => response,
+    };
+
+    let response = serde_json::from_value::<GraphQlResponse<Value>>(response).map_err(|e| {
+        TelescopeError::RcosApiError(format!("Invalid fixture at {}: {}", path.display(), e))
+    })?;
+    if response.data.is_none() && response.errors.is_none() {
+        return Err(TelescopeError::RcosApiError(format!(
+            "Fixture at {} has no data or errors.",
+            path.display()
+        )));
+    }
+    return handle_graphql_response(FIXTURES_NAME, response);
+}
@@ -13,13 +13,34 @@ use chrono::Utc;
 )]
 pub struct LandingPageStatistics;
 
-use self::landing_page_statistics::{ResponseData, Variables};
+/// GraphQL Query for the statistics of one organization.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/stats/landing_page.graphql",
+    response_derives = "Serialize"
+)]
+pub struct OrganizationStatistics;
 
 impl LandingPageStatistics {
     /// Get the landing page statistics from the RCOS API.
-    pub async fn get() -> Result<ResponseData, TelescopeError> {
-        return send_query::<Self>(Variables {
+    pub async fn get() -> Result<landing_page_statistics::ResponseData, TelescopeError> {
+        return send_query::<Self>(landing_page_statistics::Variables {
+            now: Utc::today().naive_utc(),
+        })
+        .await;
+    }
+}
+
+impl OrganizationStatistics {
+    /// Get the landing page statistics of one organization. Only its semesters
+    /// and projects are counted.
+    pub async fn get(
+        org_id: String,
+    ) -> Result<organization_statistics::ResponseData, TelescopeError> {
+        return send_query::<Self>(organization_statistics::Variables {
             now: Utc::today().naive_utc(),
+            org: org_id,
         })
         .await;
     }
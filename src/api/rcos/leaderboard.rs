@@ -0,0 +1,28 @@
+//! Query for the data behind the attendance leaderboard.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// GraphQL query for a semester's meetings, status updates, and the
+/// attendance of users who opted in to the leaderboard.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/stats/leaderboard.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct LeaderboardData;
+
+impl LeaderboardData {
+    /// Get the leaderboard data for a semester as of now.
+    pub async fn get(
+        semester_id: String,
+    ) -> Result<leaderboard_data::ResponseData, TelescopeError> {
+        send_query::<Self>(leaderboard_data::Variables {
+            semester_id,
+            now: Utc::now(),
+        })
+        .await
+    }
+}
@@ -0,0 +1,68 @@
+//! GraphQL queries for stored links and the ones found broken.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use serde_json::{Map, Value};
+
+/// Type representing GraphQL query to get every stored link to check.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/link_health/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct LinkSources;
+
+/// Type representing GraphQL query to get the broken links among a set of URLs.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/link_health/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct BrokenLinks;
+
+/// Type representing GraphQL query to get every broken link.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/link_health/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct BrokenLinkReport;
+
+impl LinkSources {
+    /// Get the meetings and projects with links.
+    pub async fn get() -> Result<link_sources::ResponseData, TelescopeError> {
+        send_query::<Self>(link_sources::Variables {}).await
+    }
+}
+
+impl BrokenLinks {
+    /// Get the failed checks for any of the given URLs, as a JSON object
+    /// keyed by URL so templates can look up each link they render.
+    pub async fn get(urls: Vec<String>) -> Result<Value, TelescopeError> {
+        if urls.is_empty() {
+            return Ok(json!({}));
+        }
+
+        let broken = send_query::<Self>(broken_links::Variables { urls })
+            .await?
+            .broken_links
+            .into_iter()
+            .map(|link| (link.url.clone(), json!(link)))
+            .collect::<Map<String, Value>>();
+
+        return Ok(Value::Object(broken));
+    }
+}
+
+impl BrokenLinkReport {
+    /// Get every broken link.
+    pub async fn get(
+    ) -> Result<Vec<broken_link_report::BrokenLinkReportBrokenLinks>, TelescopeError> {
+        Ok(send_query::<Self>(broken_link_report::Variables {})
+            .await?
+            .broken_links)
+    }
+}
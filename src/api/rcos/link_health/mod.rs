@@ -0,0 +1,4 @@
+//! Health of links stored in the RCOS database.
+
+pub mod get;
+pub mod mutations;
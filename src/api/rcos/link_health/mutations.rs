@@ -0,0 +1,57 @@
+//! GraphQL mutations to record link check results.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+
+/// Type representing GraphQL mutation to record a broken link.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/link_health/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SaveBrokenLink;
+
+/// Type representing GraphQL mutation to clear links that are no longer broken.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/link_health/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ClearBrokenLinks;
+
+impl SaveBrokenLink {
+    /// Record that a link on a page failed its check.
+    pub async fn execute(
+        url: String,
+        source_path: String,
+        label: String,
+        status_code: Option<i64>,
+        error: Option<String>,
+        checked_at: timestamptz,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(save_broken_link::Variables {
+            url,
+            source_path,
+            label,
+            status_code,
+            error,
+            checked_at,
+        })
+        .await
+        .map(|_| ())
+    }
+}
+
+impl ClearBrokenLinks {
+    /// Clear every broken link recorded before a given time. Return the number
+    /// of links cleared.
+    pub async fn execute(before: timestamptz) -> Result<i64, TelescopeError> {
+        Ok(send_query::<Self>(clear_broken_links::Variables { before })
+            .await?
+            .delete_broken_links
+            .map(|response| response.affected_rows)
+            .unwrap_or(0))
+    }
+}
@@ -78,36 +78,24 @@ impl UserMeetingAuthorization {
         }
     }
 
-    /// Can the user associated with this authorization edit meetings with a given type
-    /// and optionally specified host user ID?
-    pub fn can_edit(&self, host_user_id: Option<uuid>) -> bool {
-        // If there is a host and viewer
-        if let (Some(host), Some(viewer)) = (host_user_id, self.user_id) {
-            // and they are the same person (or the viewer has coordinator or higher perms)
-            host == viewer || self.can_view_drafts()
-        } else {
-            // of the viewer is a coordinator or faculty advisor
-            self.can_view_drafts()
-        }
+    /// Can the user associated with this authorization edit a meeting with the given hosts?
+    /// Hosts, co-hosts, and guest speakers can all edit their meetings.
+    pub fn can_edit(&self, hosts: &[uuid]) -> bool {
+        // The viewer is a coordinator or faculty advisor
+        self.can_view_drafts()
+            // or one of the meeting's hosts.
+            || self.user_id.map_or(false, |viewer| hosts.contains(&viewer))
     }
 
     /// Can the user associated with this authorization edit the meeting
-    pub async fn can_edit_by_id(&self, meeting_id: i64) -> Result<bool, TelescopeError> {
+    pub async fn can_edit_by_id(&self, meeting_id: MeetingId) -> Result<bool, TelescopeError> {
         // If the authenticated user is a coordinator or professor, then they can edit this meeting.
         if self.can_view_drafts() {
             Ok(true)
         } else {
-            // Otherwise lookup the meeting and check if the authenticated user id matches the host
-            // user id.
-            let meeting_host: Option<uuid> = MeetingHost::get(meeting_id).await?;
-            match (meeting_host, self.user_id) {
-                // If there is both a host and a viewer, and they're the same,
-                // the meeting can be edited.
-                (Some(host), Some(viewer)) => Ok(host == viewer),
-
-                // In any other case, the meeting is not to be edited by the viewer.
-                _ => Ok(false),
-            }
+            // Otherwise lookup the meeting and check if the authenticated user is one of its hosts.
+            let hosts: Vec<uuid> = MeetingHost::get(meeting_id).await?;
+            Ok(self.can_edit(hosts.as_slice()))
         }
     }
 
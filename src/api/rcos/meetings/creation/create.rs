@@ -1,6 +1,6 @@
 //! GraphQL mutation to create a meeting.
 
-use crate::api::rcos::meetings::MeetingType;
+use crate::api::rcos::meetings::types::MeetingKind;
 use crate::api::rcos::prelude::*;
 use crate::api::rcos::send_query;
 use crate::error::TelescopeError;
@@ -31,11 +31,13 @@ impl CreateMeeting {
         is_remote: bool,
         location: Option<String>,
         meeting_url: Option<String>,
+        host_key: Option<String>,
         recording_url: Option<String>,
         external_slides_url: Option<String>,
         semester_id: String,
-        kind: MeetingType,
-    ) -> Result<Option<i64>, TelescopeError> {
+        kind: MeetingKind,
+        small_group_id: Option<i64>,
+    ) -> Result<Option<MeetingId>, TelescopeError> {
         send_query::<Self>(create_meeting::Variables {
             host,
             title,
@@ -47,12 +49,19 @@ impl CreateMeeting {
             location,
             // Coerce an empty or whitespace string to none.
             meeting_url: normalize_url(meeting_url),
+            host_key,
             recording_url: normalize_url(recording_url),
             external_slides_url: normalize_url(external_slides_url),
             semester_id,
-            kind,
+            kind: kind.meeting_type(),
+            custom_type_id: kind.custom_type_id(),
+            small_group_id,
         })
         .await
-        .map(|response| response.insert_meetings_one.map(|obj| obj.meeting_id))
+        .map(|response| {
+            response
+                .insert_meetings_one
+                .map(|obj| MeetingId(obj.meeting_id))
+        })
     }
 }
@@ -1,5 +1,6 @@
 //! Meeting deletion mutation.
 
+use crate::api::rcos::prelude::*;
 use crate::api::rcos::send_query;
 use crate::error::TelescopeError;
 
@@ -15,7 +16,12 @@ pub struct DeleteMeeting;
 
 impl DeleteMeeting {
     /// Delete a meeting and all associated attendances.
-    pub async fn execute(meeting_id: i64) -> Result<delete_meeting::ResponseData, TelescopeError> {
-        send_query::<Self>(delete_meeting::Variables { meeting_id }).await
+    pub async fn execute(
+        meeting_id: MeetingId,
+    ) -> Result<delete_meeting::ResponseData, TelescopeError> {
+        send_query::<Self>(delete_meeting::Variables {
+            meeting_id: meeting_id.into(),
+        })
+        .await
     }
 }
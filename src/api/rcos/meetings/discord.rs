@@ -0,0 +1,201 @@
+//! Discord announcements of meetings and the RSVPs collected from them.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL mutation to record a meeting announcement.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/discord.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RecordMeetingAnnouncement;
+
+/// Type representing GraphQL query to get the meeting a Discord message
+/// announced.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/discord.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AnnouncedMeeting;
+
+/// Type representing GraphQL mutation to record an RSVP from a reaction.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/discord.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AddDiscordRsvp;
+
+/// Type representing GraphQL mutation to remove an RSVP.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/discord.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RemoveDiscordRsvp;
+
+/// Type representing GraphQL query to get the open threads of a meeting's
+/// announcements.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/discord.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct MeetingThreads;
+
+/// Type representing GraphQL mutation to record the slides message in a
+/// thread.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/discord.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SetThreadSlidesMessage;
+
+/// Type representing GraphQL query to get the open threads of meetings that
+/// have ended.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/discord.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct FinishedMeetingThreads;
+
+/// Type representing GraphQL mutation to mark threads archived.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/discord.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct MarkThreadsArchived;
+
+/// A Discord message announcing a meeting.
+#[derive(Clone, Debug)]
+pub struct Announcement {
+    /// The channel the announcement was posted in.
+    pub channel_id: String,
+    /// The announcement message.
+    pub message_id: String,
+    /// The thread for questions started from the announcement, if any.
+    pub thread_id: Option<String>,
+    /// The message in the thread with the slides link, if it was posted.
+    pub slides_message_id: Option<String>,
+}
+
+impl RecordMeetingAnnouncement {
+    /// Record a Discord message announcing a meeting.
+    pub async fn execute(
+        meeting_id: MeetingId,
+        announcement: Announcement,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(record_meeting_announcement::Variables {
+            meeting_id: meeting_id.into(),
+            channel_id: announcement.channel_id,
+            message_id: announcement.message_id,
+            thread_id: announcement.thread_id,
+            slides_message_id: announcement.slides_message_id,
+        })
+        .await?;
+        return Ok(());
+    }
+}
+
+impl AnnouncedMeeting {
+    /// Get the meeting a Discord message announced, if it announced one.
+    pub async fn get(message_id: String) -> Result<Option<MeetingId>, TelescopeError> {
+        send_query::<Self>(announced_meeting::Variables { message_id })
+            .await
+            .map(|data| {
+                data.announcement
+                    .map(|announcement| MeetingId(announcement.meeting_id))
+            })
+    }
+}
+
+impl AddDiscordRsvp {
+    /// Record that a Discord user plans to come to a meeting. The user ID is
+    /// the Telescope account the Discord account is linked to, if any.
+    pub async fn execute(
+        meeting_id: MeetingId,
+        discord_id: String,
+        user_id: Option<uuid>,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(add_discord_rsvp::Variables {
+            meeting_id: meeting_id.into(),
+            discord_id,
+            user_id,
+        })
+        .await?;
+        return Ok(());
+    }
+}
+
+impl RemoveDiscordRsvp {
+    /// Remove a Discord user's RSVP to a meeting.
+    pub async fn execute(meeting_id: MeetingId, discord_id: String) -> Result<(), TelescopeError> {
+        send_query::<Self>(remove_discord_rsvp::Variables {
+            meeting_id: meeting_id.into(),
+            discord_id,
+        })
+        .await?;
+        return Ok(());
+    }
+}
+
+impl MeetingThreads {
+    /// Get the open threads of a meeting's announcements.
+    pub async fn get(
+        meeting_id: MeetingId,
+    ) -> Result<Vec<meeting_threads::MeetingThreadsThreads>, TelescopeError> {
+        send_query::<Self>(meeting_threads::Variables {
+            meeting_id: meeting_id.into(),
+        })
+        .await
+        .map(|data| data.threads)
+    }
+}
+
+impl SetThreadSlidesMessage {
+    /// Record the message with the slides link in the thread of an
+    /// announcement.
+    pub async fn execute(
+        message_id: String,
+        slides_message_id: String,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(set_thread_slides_message::Variables {
+            message_id,
+            slides_message_id,
+        })
+        .await?;
+        return Ok(());
+    }
+}
+
+impl FinishedMeetingThreads {
+    /// Get the open threads of meetings that have ended.
+    pub async fn get(
+    ) -> Result<Vec<finished_meeting_threads::FinishedMeetingThreadsThreads>, TelescopeError> {
+        send_query::<Self>(finished_meeting_threads::Variables { now: Utc::now() })
+            .await
+            .map(|data| data.threads)
+    }
+}
+
+impl MarkThreadsArchived {
+    /// Mark the threads of some announcements as archived.
+    pub async fn execute(message_ids: Vec<String>) -> Result<(), TelescopeError> {
+        send_query::<Self>(mark_threads_archived::Variables { message_ids }).await?;
+        return Ok(());
+    }
+}
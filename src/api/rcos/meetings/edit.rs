@@ -16,10 +16,14 @@ pub struct EditMeeting;
 impl EditMeeting {
     /// Execute a meeting edit mutation. Return the ID of the edited meeting if any
     /// changes were made.
-    pub async fn execute(vars: edit_meeting::Variables) -> Result<Option<i64>, TelescopeError> {
-        send_query::<Self>(vars)
-            .await
-            .map(|response| response.update_meetings_by_pk.map(|obj| obj.meeting_id))
+    pub async fn execute(
+        vars: edit_meeting::Variables,
+    ) -> Result<Option<MeetingId>, TelescopeError> {
+        send_query::<Self>(vars).await.map(|response| {
+            response
+                .update_meetings_by_pk
+                .map(|obj| MeetingId(obj.meeting_id))
+        })
     }
 }
 
@@ -34,7 +38,12 @@ pub struct EditHostSelection;
 
 impl EditHostSelection {
     /// Get the available hosts for this meeting.
-    pub async fn get(meeting_id: i64) -> Result<edit_host_selection::ResponseData, TelescopeError> {
-        send_query::<Self>(edit_host_selection::Variables { meeting_id }).await
+    pub async fn get(
+        meeting_id: MeetingId,
+    ) -> Result<edit_host_selection::ResponseData, TelescopeError> {
+        send_query::<Self>(edit_host_selection::Variables {
+            meeting_id: meeting_id.into(),
+        })
+        .await
     }
 }
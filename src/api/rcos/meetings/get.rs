@@ -35,3 +35,35 @@ impl Meetings {
         .meetings)
     }
 }
+
+impl MeetingsMeetings {
+    /// Get the title of this meeting, or make one from its type and date.
+    pub fn title(&self) -> String {
+        if let Some(title) = self.title.as_ref() {
+            return title.clone();
+        }
+
+        format!(
+            "RCOS {} - {}",
+            self.type_name(),
+            self.start_date_time.format("%B %_d, %Y")
+        )
+    }
+
+    /// Is this meeting listed by default for a viewer in the given small
+    /// groups? Meetings for one small group are only listed for its members.
+    pub fn listed_for(&self, small_groups: &[i64]) -> bool {
+        self.small_group
+            .as_ref()
+            .map_or(true, |group| small_groups.contains(&group.small_group_id))
+    }
+
+    /// Get the name of this meeting's type, using the coordinator defined
+    /// type if there is one.
+    pub fn type_name(&self) -> String {
+        self.custom_type
+            .as_ref()
+            .map(|custom| custom.name.clone())
+            .unwrap_or_else(|| self.type_.to_string())
+    }
+}
@@ -17,12 +17,14 @@ use self::meeting::{MeetingMeeting, Variables};
 
 impl Meeting {
     /// Get a meeting by its ID.
-    pub async fn get(meeting_id: i64) -> Result<Option<MeetingMeeting>, TelescopeError> {
-        Ok(send_query::<Self>(Variables { id: meeting_id })
-            // Wait for API response
-            .await?
-            // Extract the meeting object.
-            .meeting)
+    pub async fn get(meeting_id: MeetingId) -> Result<Option<MeetingMeeting>, TelescopeError> {
+        Ok(send_query::<Self>(Variables {
+            id: meeting_id.into(),
+        })
+        // Wait for API response
+        .await?
+        // Extract the meeting object.
+        .meeting)
     }
 }
 
@@ -38,8 +40,33 @@ impl MeetingMeeting {
         // Otherwise create a title.
         format!(
             "RCOS {} - {}",
-            self.type_,
+            self.type_name(),
             self.start_date_time.format("%B %_d, %Y")
         )
     }
+
+    /// Get the user IDs of everyone hosting this meeting: the primary host if there is one,
+    /// then co-hosts and guest speakers, then the mentors of the small group it is for.
+    pub fn host_ids(&self) -> Vec<uuid> {
+        let mentors = self
+            .small_group
+            .iter()
+            .flat_map(|group| group.small_group_mentors.iter());
+
+        self.host
+            .iter()
+            .map(|host| host.id)
+            .chain(self.hosts.iter().map(|host| host.user.id))
+            .chain(mentors.map(|mentor| mentor.user_id))
+            .collect()
+    }
+
+    /// Get the name of this meeting's type, using the coordinator defined
+    /// type if there is one.
+    pub fn type_name(&self) -> String {
+        self.custom_type
+            .as_ref()
+            .map(|custom| custom.name.clone())
+            .unwrap_or_else(|| self.type_.to_string())
+    }
 }
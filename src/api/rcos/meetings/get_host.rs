@@ -1,4 +1,4 @@
-//! GraphQL query to get the user ID of the host of a meeting by the meeting's ID.
+//! GraphQL query to get the user IDs of the hosts of a meeting by the meeting's ID.
 
 use crate::api::rcos::{prelude::*, send_query};
 use crate::error::TelescopeError;
@@ -12,15 +12,32 @@ use crate::error::TelescopeError;
 pub struct MeetingHost;
 
 impl MeetingHost {
-    /// Get the user ID of the host of a meeting if there is one.
-    pub async fn get(meeting_id: i64) -> Result<Option<uuid>, TelescopeError> {
-        send_query::<Self>(meeting_host::Variables { meeting_id })
-            .await
-            .map(|response| {
-                response
-                    .meetings_by_pk
-                    .and_then(|meeting| meeting.host)
-                    .map(|host| host.id)
-            })
+    /// Get the user IDs of everyone hosting a meeting: the primary host if
+    /// there is one, then co-hosts and guest speakers, then the mentors of
+    /// the small group the meeting is for.
+    pub async fn get(meeting_id: MeetingId) -> Result<Vec<uuid>, TelescopeError> {
+        send_query::<Self>(meeting_host::Variables {
+            meeting_id: meeting_id.into(),
+        })
+        .await
+        .map(|response| {
+            response
+                .meetings_by_pk
+                .map(|meeting| {
+                    let mentors = meeting
+                        .small_group
+                        .map(|group| group.small_group_mentors)
+                        .unwrap_or_default();
+
+                    meeting
+                        .host
+                        .map(|host| host.id)
+                        .into_iter()
+                        .chain(meeting.hosts.into_iter().map(|host| host.user_id))
+                        .chain(mentors.into_iter().map(|mentor| mentor.user_id))
+                        .collect()
+                })
+                .unwrap_or_default()
+        })
     }
 }
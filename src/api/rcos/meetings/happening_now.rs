@@ -0,0 +1,35 @@
+//! Query for meetings in progress or about to start.
+
+use crate::api::rcos::meetings::MeetingType;
+use crate::api::rcos::prelude::*;
+use crate::api::rcos::send_query;
+use crate::error::TelescopeError;
+use chrono::{DateTime, Utc};
+
+/// Type representing GraphQL query for meetings that are in progress or
+/// starting soon.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/happening_now.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct HappeningNow;
+
+impl HappeningNow {
+    /// Get the finalized meetings of the given types that end after `now`
+    /// and start by `cutoff`.
+    pub async fn get(
+        now: DateTime<Utc>,
+        cutoff: DateTime<Utc>,
+        accept_types: Vec<MeetingType>,
+    ) -> Result<Vec<happening_now::HappeningNowMeetings>, TelescopeError> {
+        send_query::<Self>(happening_now::Variables {
+            now,
+            cutoff,
+            accept_types,
+        })
+        .await
+        .map(|response| response.meetings)
+    }
+}
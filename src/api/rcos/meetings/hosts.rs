@@ -0,0 +1,83 @@
+//! People hosting a meeting besides its primary host.
+//!
+//! A meeting's primary host is the `host_user_id` column, picked when the
+//! meeting is created. Anyone else helping run it is listed in the
+//! `meeting_hosts` table with a [`HostRole`]. Everyone listed gets the same
+//! permissions on the meeting as the primary host.
+
+use crate::api::rcos::prelude::*;
+use crate::api::rcos::send_query;
+use crate::error::TelescopeError;
+
+/// Type representing GraphQL mutation to add someone to a meeting's hosts.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/hosts.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AddMeetingHost;
+
+/// Type representing GraphQL mutation to remove someone from a meeting's
+/// hosts.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/hosts.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RemoveMeetingHost;
+
+/// The part someone plays in hosting a meeting.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display)]
+#[serde(rename_all = "snake_case")]
+pub enum HostRole {
+    Host,
+    #[display(fmt = "Co-host")]
+    CoHost,
+    #[display(fmt = "Guest Speaker")]
+    GuestSpeaker,
+}
+
+impl HostRole {
+    /// Every role, in the order they are listed in forms.
+    pub const ALL: [HostRole; 3] = [HostRole::Host, HostRole::CoHost, HostRole::GuestSpeaker];
+
+    /// The name of this role in the database and in forms.
+    pub fn name(self) -> &'static str {
+        match self {
+            HostRole::Host => "host",
+            HostRole::CoHost => "co_host",
+            HostRole::GuestSpeaker => "guest_speaker",
+        }
+    }
+}
+
+impl AddMeetingHost {
+    /// Add someone to a meeting's hosts, or change their role.
+    pub async fn execute(
+        meeting_id: MeetingId,
+        user_id: uuid,
+        role: HostRole,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(add_meeting_host::Variables {
+            meeting_id: meeting_id.into(),
+            user_id,
+            role: role.name().to_string(),
+        })
+        .await?;
+        return Ok(());
+    }
+}
+
+impl RemoveMeetingHost {
+    /// Remove someone from a meeting's hosts. Return whether they were one.
+    pub async fn execute(meeting_id: MeetingId, user_id: uuid) -> Result<bool, TelescopeError> {
+        send_query::<Self>(remove_meeting_host::Variables {
+            meeting_id: meeting_id.into(),
+            user_id,
+        })
+        .await
+        .map(|response| response.delete_meeting_hosts_by_pk.is_some())
+    }
+}
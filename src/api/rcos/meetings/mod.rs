@@ -3,10 +3,19 @@
 pub mod authorization_for;
 pub mod creation;
 pub mod delete;
+pub mod discord;
 pub mod edit;
 pub mod get;
 pub mod get_by_id;
 pub mod get_host;
+pub mod happening_now;
+pub mod hosts;
+pub mod questions;
+pub mod recordings;
+pub mod publishing;
+pub mod small_groups;
+pub mod speakers;
+pub mod types;
 
 /// List of all existing meeting type variants.
 pub const ALL_MEETING_TYPES: [MeetingType; 8] = [
@@ -0,0 +1,107 @@
+//! Drafts that are published automatically at a scheduled time.
+
+use crate::api::rcos::prelude::*;
+use crate::api::rcos::send_query;
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL query to get drafts that are due to be
+/// published.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/publishing.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct DueDrafts;
+
+/// Type representing GraphQL mutation to publish drafts.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/publishing.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct PublishDrafts;
+
+/// Type representing GraphQL mutation to schedule a draft to be published.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/publishing.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SchedulePublishing;
+
+/// Type representing GraphQL query to list drafts that have not ended.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/publishing.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct UpcomingDrafts;
+
+impl DueDrafts {
+    /// Get the IDs of drafts scheduled to be published by now.
+    pub async fn get() -> Result<Vec<i64>, TelescopeError> {
+        Ok(
+            send_query::<Self>(due_drafts::Variables { now: Utc::now() })
+                .await?
+                .meetings
+                .into_iter()
+                .map(|meeting| meeting.meeting_id)
+                .collect(),
+        )
+    }
+}
+
+impl PublishDrafts {
+    /// Publish drafts. Return the meetings that were published, with whether
+    /// to announce each of them.
+    pub async fn execute(
+        meeting_ids: Vec<i64>,
+    ) -> Result<Vec<publish_drafts::PublishDraftsUpdateMeetingsReturning>, TelescopeError> {
+        Ok(
+            send_query::<Self>(publish_drafts::Variables { meeting_ids })
+                .await?
+                .update_meetings
+                .map(|updated| updated.returning)
+                .unwrap_or_default(),
+        )
+    }
+}
+
+impl SchedulePublishing {
+    /// Set when a draft is published, or clear it with `None`.
+    pub async fn execute(
+        meeting_id: MeetingId,
+        publish_at: Option<timestamptz>,
+        announce: bool,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(schedule_publishing::Variables {
+            meeting_id: meeting_id.into(),
+            publish_at,
+            announce,
+        })
+        .await
+        .map(|_| ())
+    }
+}
+
+impl UpcomingDrafts {
+    /// Get the drafts that have not ended yet that a user hosts, or every
+    /// such draft if `include_all` is set.
+    pub async fn get(
+        user_id: uuid,
+        include_all: bool,
+    ) -> Result<Vec<upcoming_drafts::UpcomingDraftsMeetings>, TelescopeError> {
+        Ok(send_query::<Self>(upcoming_drafts::Variables {
+            now: Utc::now(),
+            user_id,
+            include_all,
+        })
+        .await?
+        .meetings)
+    }
+}
@@ -0,0 +1,132 @@
+//! Live question queue for meetings.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL query to get a meeting's questions.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/questions.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct MeetingQuestions;
+
+/// Type representing GraphQL mutation to ask a question.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/questions.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AskQuestion;
+
+/// Type representing GraphQL mutation to upvote a question.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/questions.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct UpvoteQuestion;
+
+/// Type representing GraphQL mutation to take back an upvote.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/questions.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RemoveQuestionVote;
+
+/// Type representing GraphQL mutation to mark a question answered or reopen it.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/questions.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SetQuestionAnswered;
+
+impl MeetingQuestions {
+    /// Get the question queue for a meeting as seen by a viewer. The meeting is
+    /// `None` if it does not exist.
+    pub async fn get(
+        meeting_id: MeetingId,
+        viewer: uuid,
+    ) -> Result<meeting_questions::ResponseData, TelescopeError> {
+        send_query::<Self>(meeting_questions::Variables {
+            meeting_id: meeting_id.into(),
+            viewer,
+        })
+        .await
+    }
+}
+
+impl AskQuestion {
+    /// Add a question to a meeting's queue.
+    pub async fn execute(
+        meeting_id: MeetingId,
+        user_id: uuid,
+        body: String,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(ask_question::Variables {
+            meeting_id: meeting_id.into(),
+            user_id,
+            body,
+            now: Utc::now(),
+        })
+        .await?;
+        return Ok(());
+    }
+}
+
+impl UpvoteQuestion {
+    /// Upvote a question, or take the upvote back.
+    pub async fn execute(
+        question_id: i64,
+        user_id: uuid,
+        vote: bool,
+    ) -> Result<(), TelescopeError> {
+        if vote {
+            send_query::<Self>(upvote_question::Variables {
+                question_id,
+                user_id,
+                now: Utc::now(),
+            })
+            .await?;
+        } else {
+            send_query::<RemoveQuestionVote>(remove_question_vote::Variables {
+                question_id,
+                user_id,
+            })
+            .await?;
+        }
+        return Ok(());
+    }
+}
+
+impl SetQuestionAnswered {
+    /// Mark a question of a meeting answered (or not). Return false if the
+    /// meeting has no such question.
+    pub async fn execute(
+        meeting_id: MeetingId,
+        question_id: i64,
+        is_answered: bool,
+    ) -> Result<bool, TelescopeError> {
+        send_query::<Self>(set_question_answered::Variables {
+            meeting_id: meeting_id.into(),
+            question_id,
+            is_answered,
+            answered_at: Some(Utc::now()).filter(|_| is_answered),
+        })
+        .await
+        .map(|response| {
+            response
+                .update_meeting_questions
+                .map(|updated| updated.affected_rows > 0)
+                .unwrap_or(false)
+        })
+    }
+}
@@ -0,0 +1,360 @@
+//! Attaching recordings picked up from YouTube or the recording webhook to
+//! their meetings.
+//!
+//! A recording is matched against the published meetings without a recording
+//! that started in the week before it was published. Each is scored on how
+//! soon after it the recording was published, whether the recording's title
+//! has the meeting's date, and how many words of the meeting's title the
+//! recording's title shares. A clear winner gets the recording. Otherwise the
+//! best few meetings are queued for a coordinator to pick from.
+
+use crate::api::rcos::prelude::*;
+use crate::api::rcos::send_query;
+use crate::error::TelescopeError;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Utc};
+use regex::Regex;
+use std::collections::HashSet;
+
+/// Type representing GraphQL query to find recordings that were already
+/// picked up.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/recordings.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct KnownRecordings;
+
+/// Type representing GraphQL query to get meetings a recording could belong to.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/recordings.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RecordingCandidates;
+
+/// Type representing GraphQL mutation to attach a recording to a meeting.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/recordings.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AttachRecording;
+
+/// Type representing GraphQL mutation to queue a recording for coordinators.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/recordings.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct QueueRecording;
+
+/// Type representing GraphQL mutation to add a meeting a queued recording
+/// could belong to.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/recordings.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AddRecordingCandidate;
+
+/// Type representing GraphQL query to get the recordings waiting for a
+/// coordinator.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/recordings.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RecordingQueue;
+
+/// Type representing GraphQL mutation to attach a queued recording.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/recordings.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ConfirmRecording;
+
+/// Type representing GraphQL mutation to dismiss a queued recording.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/recordings.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct DismissRecording;
+
+use self::recording_candidates::RecordingCandidatesMeetings;
+
+/// How far back from a recording's publication to look for its meeting.
+const LOOKBACK_DAYS: i64 = 7;
+
+/// The least score a meeting needs to get a recording without a coordinator.
+const ATTACH_SCORE: i64 = 60;
+
+/// How far ahead of the next best meeting the best one has to be to get a
+/// recording without a coordinator.
+const ATTACH_MARGIN: i64 = 20;
+
+/// The most meetings a coordinator picks between.
+const MAX_CANDIDATES: usize = 5;
+
+/// Words that are in most meeting and recording titles, so they say nothing
+/// about which meeting a recording is of.
+const COMMON_WORDS: [&'static str; 6] = ["rcos", "meeting", "recording", "the", "and", "for"];
+
+/// The start of each month's name, as matched by [`NAMED_DATE`].
+const MONTHS: [&'static str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+lazy_static! {
+    /// Dates like 2022-03-04.
+    static ref ISO_DATE: Regex = Regex::new(r"\b(\d{4})-(\d{1,2})-(\d{1,2})\b").unwrap();
+
+    /// Dates like 3/4, 3/4/22, and 3/4/2022.
+    static ref US_DATE: Regex = Regex::new(r"\b(\d{1,2})/(\d{1,2})(?:/(\d{2}|\d{4}))?\b").unwrap();
+
+    /// Dates like March 4 and Mar 4.
+    static ref NAMED_DATE: Regex =
+        Regex::new(r"(?i)\b(jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)[a-z]*\.? (\d{1,2})\b")
+            .unwrap();
+}
+
+/// Where a recording was picked up from.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RecordingSource {
+    YouTube,
+    Webhook,
+}
+
+impl RecordingSource {
+    /// The name of this source in the database.
+    fn name(self) -> &'static str {
+        match self {
+            RecordingSource::YouTube => "youtube",
+            RecordingSource::Webhook => "webhook",
+        }
+    }
+}
+
+/// A recording to attach to a meeting.
+#[derive(Clone, Debug)]
+pub struct Recording {
+    pub url: String,
+    pub title: String,
+    pub published_at: DateTime<Utc>,
+    pub source: RecordingSource,
+}
+
+/// What happened to a recording.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    /// It was picked up before, and was left alone.
+    Known,
+    /// It was attached to this meeting.
+    Attached(i64),
+    /// It was queued for a coordinator.
+    Queued,
+    /// No meeting could have been recorded, so it was left off all of them.
+    Unmatched,
+}
+
+/// The lowercase words in a title, leaving out short and common ones.
+fn words(title: &str) -> HashSet<String> {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|word| word.len() >= 3 && !COMMON_WORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// The dates written in a title. Dates without a year are taken to be in the
+/// given year.
+fn title_dates(title: &str, year: i32) -> Vec<NaiveDate> {
+    let iso = ISO_DATE.captures_iter(title).filter_map(|c| {
+        NaiveDate::from_ymd_opt(c[1].parse().ok()?, c[2].parse().ok()?, c[3].parse().ok()?)
+    });
+
+    let us = US_DATE.captures_iter(title).filter_map(|c| {
+        let year: i32 = match c.get(3) {
+            Some(y) if y.as_str().len() == 2 => 2000 + y.as_str().parse::<i32>().ok()?,
+            Some(y) => y.as_str().parse().ok()?,
+            None => year,
+        };
+        NaiveDate::from_ymd_opt(year, c[1].parse().ok()?, c[2].parse().ok()?)
+    });
+
+    let named = NAMED_DATE.captures_iter(title).filter_map(|c| {
+        let prefix: String = c[1].to_lowercase();
+        let month: usize = MONTHS.iter().position(|m| *m == prefix)?;
+        NaiveDate::from_ymd_opt(year, month as u32 + 1, c[2].parse().ok()?)
+    });
+
+    return iso.chain(us).chain(named).collect();
+}
+
+impl RecordingCandidatesMeetings {
+    /// The title of this meeting, or its type if it has none.
+    fn title(&self) -> String {
+        self.title.clone().unwrap_or_else(|| {
+            self.custom_type
+                .as_ref()
+                .map(|custom| custom.name.clone())
+                .unwrap_or_else(|| self.type_.to_string())
+        })
+    }
+
+    /// Score how likely a recording is of this meeting, out of 100.
+    fn score(&self, recording: &Recording) -> i64 {
+        let mut score: i64 = 0;
+
+        // Recordings are usually published within a few hours.
+        let delay: Duration = recording.published_at - self.end_date_time;
+        if delay <= Duration::hours(6) {
+            score += 30;
+        } else if delay <= Duration::days(2) {
+            score += 15;
+        }
+
+        // Titles often have the date of the meeting in them.
+        let meeting_date: NaiveDate = self
+            .start_date_time
+            .with_timezone(&Local)
+            .naive_local()
+            .date();
+        let year: i32 = recording.published_at.with_timezone(&Local).year();
+        if title_dates(recording.title.as_str(), year).contains(&meeting_date) {
+            score += 40;
+        }
+
+        // And share words with the meeting title.
+        let meeting_words = words(self.title().as_str());
+        if !meeting_words.is_empty() {
+            let shared = meeting_words
+                .intersection(&words(recording.title.as_str()))
+                .count();
+            score += 30 * shared as i64 / meeting_words.len() as i64;
+        }
+
+        return score;
+    }
+}
+
+/// Attach a recording to the meeting it is of, or queue it for a coordinator
+/// if that is not clear. Recordings that were picked up before are left alone,
+/// so this can be called with the same recording again.
+pub async fn ingest(recording: Recording) -> Result<Outcome, TelescopeError> {
+    let known = send_query::<KnownRecordings>(known_recordings::Variables {
+        urls: vec![recording.url.clone()],
+    })
+    .await?;
+    if !known.recording_ingests.is_empty() {
+        return Ok(Outcome::Known);
+    }
+
+    let meetings = send_query::<RecordingCandidates>(recording_candidates::Variables {
+        start: recording.published_at - Duration::days(LOOKBACK_DAYS),
+        end: recording.published_at,
+    })
+    .await?
+    .meetings;
+
+    let mut scored: Vec<(i64, i64)> = meetings
+        .iter()
+        .map(|meeting| (meeting.score(&recording), meeting.meeting_id))
+        .collect();
+    scored.sort_by(|a, b| b.cmp(a));
+
+    let now: DateTime<Utc> = Utc::now();
+
+    // Attach the recording if one meeting is a clear match.
+    if let Some((best, meeting_id)) = scored.first().copied() {
+        let runner_up: i64 = scored.get(1).map_or(0, |(score, _)| *score);
+        if best >= ATTACH_SCORE && best - runner_up >= ATTACH_MARGIN {
+            send_query::<AttachRecording>(attach_recording::Variables {
+                url: recording.url,
+                title: recording.title,
+                published_at: recording.published_at,
+                source: recording.source.name().to_string(),
+                meeting_id,
+                now,
+            })
+            .await?;
+            return Ok(Outcome::Attached(meeting_id));
+        }
+    }
+
+    // Otherwise let a coordinator pick. Recordings with nothing to pick from
+    // are kept as dismissed, so they are not matched again.
+    let outcome: Outcome = if scored.is_empty() {
+        Outcome::Unmatched
+    } else {
+        Outcome::Queued
+    };
+    send_query::<QueueRecording>(queue_recording::Variables {
+        url: recording.url.clone(),
+        title: recording.title,
+        published_at: recording.published_at,
+        source: recording.source.name().to_string(),
+        status: if outcome == Outcome::Queued {
+            "pending"
+        } else {
+            "dismissed"
+        }
+        .to_string(),
+        now,
+    })
+    .await?;
+
+    for (score, meeting_id) in scored.into_iter().take(MAX_CANDIDATES) {
+        send_query::<AddRecordingCandidate>(add_recording_candidate::Variables {
+            url: recording.url.clone(),
+            meeting_id,
+            score,
+        })
+        .await?;
+    }
+    return Ok(outcome);
+}
+
+impl RecordingQueue {
+    /// Get the recordings waiting for a coordinator.
+    pub async fn get(
+    ) -> Result<Vec<recording_queue::RecordingQueueRecordingIngests>, TelescopeError> {
+        send_query::<Self>(recording_queue::Variables {})
+            .await
+            .map(|response| response.recording_ingests)
+    }
+}
+
+impl ConfirmRecording {
+    /// Attach a queued recording to the meeting a coordinator picked.
+    pub async fn execute(
+        url: String,
+        meeting_id: i64,
+        decided_by: uuid,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(confirm_recording::Variables {
+            url,
+            meeting_id,
+            decided_by,
+        })
+        .await?;
+        return Ok(());
+    }
+}
+
+impl DismissRecording {
+    /// Leave a queued recording off every meeting.
+    pub async fn execute(url: String, decided_by: uuid) -> Result<(), TelescopeError> {
+        send_query::<Self>(dismiss_recording::Variables { url, decided_by }).await?;
+        return Ok(());
+    }
+}
@@ -0,0 +1,87 @@
+//! Meetings that are only for one small group.
+//!
+//! These are listed for the group's members and hidden from everyone else's
+//! default meeting list. The group's mentors can edit them, and its members
+//! are reminded of them over Discord.
+
+use crate::api::rcos::prelude::*;
+use crate::api::rcos::send_query;
+use crate::error::TelescopeError;
+use chrono::{Duration, Local, Utc};
+
+/// Type representing GraphQL query to get the small groups a user is in.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/small_groups.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ViewerSmallGroups;
+
+/// Type representing GraphQL query to get small group meetings starting soon.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/small_groups.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct UpcomingGroupMeetings;
+
+/// Type representing GraphQL mutation to mark small group meeting reminders
+/// sent.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/small_groups.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct MarkGroupRemindersSent;
+
+impl ViewerSmallGroups {
+    /// Get the IDs of the current small groups a user mentors or has a project
+    /// in. Viewers who are not logged in are in no small groups.
+    pub async fn get(user_id: Option<uuid>) -> Result<Vec<i64>, TelescopeError> {
+        let user_id: uuid = match user_id {
+            Some(user_id) => user_id,
+            None => return Ok(Vec::new()),
+        };
+
+        send_query::<Self>(viewer_small_groups::Variables {
+            user_id,
+            today: Local::today().naive_local(),
+        })
+        .await
+        .map(|response| {
+            response
+                .small_groups
+                .into_iter()
+                .map(|group| group.small_group_id)
+                .collect()
+        })
+    }
+}
+
+impl UpcomingGroupMeetings {
+    /// Get published small group meetings starting within the given window
+    /// whose groups have not been reminded.
+    pub async fn get(
+        window: Duration,
+    ) -> Result<Vec<upcoming_group_meetings::UpcomingGroupMeetingsMeetings>, TelescopeError> {
+        let now = Utc::now();
+        Ok(send_query::<Self>(upcoming_group_meetings::Variables {
+            now,
+            until: now + window,
+        })
+        .await?
+        .meetings)
+    }
+}
+
+impl MarkGroupRemindersSent {
+    /// Mark reminders as sent for the given meetings.
+    pub async fn execute(meeting_ids: Vec<i64>) -> Result<(), TelescopeError> {
+        send_query::<Self>(mark_group_reminders_sent::Variables { meeting_ids })
+            .await
+            .map(|_| ())
+    }
+}
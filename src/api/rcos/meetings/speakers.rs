@@ -0,0 +1,116 @@
+//! External speakers: people from outside RCOS who speak at meetings.
+//!
+//! Speakers have a name, affiliation, bio, and photo, but no Telescope
+//! account. A speaker's record is kept when they are removed from a meeting,
+//! so that they can be added to later meetings without being entered again.
+
+use crate::api::rcos::prelude::*;
+use crate::api::rcos::send_query;
+use crate::error::TelescopeError;
+
+/// Type representing GraphQL query to list every external speaker.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/speakers.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ExternalSpeakers;
+
+/// Type representing GraphQL mutation to create an external speaker at a
+/// meeting.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/speakers.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct CreateExternalSpeaker;
+
+/// Type representing GraphQL mutation to add an existing external speaker to
+/// a meeting.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/speakers.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AddMeetingSpeaker;
+
+/// Type representing GraphQL mutation to remove an external speaker from a
+/// meeting.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/speakers.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RemoveMeetingSpeaker;
+
+/// The details of a new external speaker.
+#[derive(Clone, Debug)]
+pub struct SpeakerFields {
+    pub name: String,
+    /// Where the speaker works or studies.
+    pub affiliation: Option<String>,
+    pub bio: Option<String>,
+    pub photo_url: Option<String>,
+}
+
+impl ExternalSpeakers {
+    /// Get every external speaker, by name.
+    pub async fn get(
+    ) -> Result<Vec<external_speakers::ExternalSpeakersExternalSpeakers>, TelescopeError> {
+        send_query::<Self>(external_speakers::Variables {})
+            .await
+            .map(|response| response.external_speakers)
+    }
+}
+
+impl CreateExternalSpeaker {
+    /// Create an external speaker at a meeting. Return the speaker's ID.
+    pub async fn execute(
+        meeting_id: MeetingId,
+        fields: SpeakerFields,
+        created_by: uuid,
+    ) -> Result<Option<i64>, TelescopeError> {
+        send_query::<Self>(create_external_speaker::Variables {
+            meeting_id: meeting_id.into(),
+            name: fields.name,
+            affiliation: fields.affiliation,
+            bio: fields.bio,
+            photo_url: fields.photo_url,
+            created_by,
+        })
+        .await
+        .map(|response| {
+            response
+                .insert_external_speakers_one
+                .map(|speaker| speaker.speaker_id)
+        })
+    }
+}
+
+impl AddMeetingSpeaker {
+    /// Add an existing external speaker to a meeting.
+    pub async fn execute(meeting_id: MeetingId, speaker_id: i64) -> Result<(), TelescopeError> {
+        send_query::<Self>(add_meeting_speaker::Variables {
+            meeting_id: meeting_id.into(),
+            speaker_id,
+        })
+        .await?;
+        return Ok(());
+    }
+}
+
+impl RemoveMeetingSpeaker {
+    /// Remove an external speaker from a meeting.
+    pub async fn execute(meeting_id: MeetingId, speaker_id: i64) -> Result<(), TelescopeError> {
+        send_query::<Self>(remove_meeting_speaker::Variables {
+            meeting_id: meeting_id.into(),
+            speaker_id,
+        })
+        .await?;
+        return Ok(());
+    }
+}
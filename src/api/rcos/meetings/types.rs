@@ -0,0 +1,213 @@
+//! Meeting types defined by coordinators.
+//!
+//! The built in [`MeetingType`]s are an enum in the database. Coordinators can
+//! add their own types on top of those. Meetings of a custom type are stored
+//! with the built in type `other` and the ID of the custom type, so anything
+//! that only knows about the built in types treats them as uncategorized
+//! meetings.
+
+use crate::api::rcos::meetings::{MeetingType, ALL_MEETING_TYPES};
+use crate::api::rcos::prelude::*;
+use crate::api::rcos::send_query;
+use crate::error::TelescopeError;
+use serde::de::Error as _;
+use serde::{Deserializer, Serializer};
+
+/// Type representing GraphQL query to list custom meeting types.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/types.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct MeetingTypes;
+
+/// Type representing GraphQL mutation to add a meeting type.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/types.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct CreateMeetingType;
+
+/// Type representing GraphQL mutation to change a meeting type.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/meetings/types.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct EditMeetingType;
+
+/// The prefix of custom meeting types in form values.
+const CUSTOM_PREFIX: &'static str = "custom-";
+
+/// The type of a meeting, as picked in forms and filters. Serialized as the
+/// built in type's name, or `custom-` followed by the custom type's ID.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MeetingKind {
+    Builtin(MeetingType),
+    /// A coordinator defined type, by ID.
+    Custom(i64),
+}
+
+impl MeetingKind {
+    /// Get the kind of a meeting from its columns.
+    pub fn of(meeting_type: MeetingType, custom_type_id: Option<i64>) -> Self {
+        custom_type_id
+            .map(MeetingKind::Custom)
+            .unwrap_or(MeetingKind::Builtin(meeting_type))
+    }
+
+    /// The built in type to store. Custom types are stored as `other`.
+    pub fn meeting_type(self) -> MeetingType {
+        match self {
+            MeetingKind::Builtin(meeting_type) => meeting_type,
+            MeetingKind::Custom(_) => MeetingType::Other,
+        }
+    }
+
+    /// The custom type to store, if any.
+    pub fn custom_type_id(self) -> Option<i64> {
+        match self {
+            MeetingKind::Builtin(_) => None,
+            MeetingKind::Custom(id) => Some(id),
+        }
+    }
+
+    /// The value of this kind in forms.
+    pub fn value(self) -> String {
+        match self {
+            MeetingKind::Builtin(meeting_type) => json!(meeting_type)
+                .as_str()
+                .expect("Meeting type serialized to JSON string")
+                .to_string(),
+            MeetingKind::Custom(id) => format!("{}{}", CUSTOM_PREFIX, id),
+        }
+    }
+
+    /// Parse a kind from its form value.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.strip_prefix(CUSTOM_PREFIX) {
+            Some(id) => id.parse::<i64>().ok().map(MeetingKind::Custom),
+            None => serde_json::from_value::<MeetingType>(json!(value))
+                .ok()
+                .map(MeetingKind::Builtin),
+        }
+    }
+}
+
+impl From<MeetingType> for MeetingKind {
+    fn from(meeting_type: MeetingType) -> Self {
+        MeetingKind::Builtin(meeting_type)
+    }
+}
+
+impl Serialize for MeetingKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.value().as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for MeetingKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value: String = String::deserialize(deserializer)?;
+        MeetingKind::parse(value.as_str())
+            .ok_or_else(|| D::Error::custom(format!("unknown meeting type {:?}", value)))
+    }
+}
+
+/// A meeting type to pick from in forms and filters.
+#[derive(Serialize, Debug, Clone)]
+pub struct MeetingTypeOption {
+    pub kind: MeetingKind,
+    pub name: String,
+    /// The background color of custom types. Built in types are styled by
+    /// the stylesheet.
+    pub color: Option<String>,
+    /// How long meetings of this type usually last, if known.
+    pub default_duration_minutes: Option<i64>,
+}
+
+impl MeetingTypes {
+    /// Get the custom meeting types.
+    pub async fn get() -> Result<Vec<meeting_types::MeetingTypesMeetingTypes>, TelescopeError> {
+        send_query::<Self>(meeting_types::Variables {})
+            .await
+            .map(|response| response.meeting_types)
+    }
+
+    /// Get every meeting type to pick from: the built in types, then the
+    /// custom ones.
+    pub async fn options() -> Result<Vec<MeetingTypeOption>, TelescopeError> {
+        let builtin = ALL_MEETING_TYPES
+            .iter()
+            .map(|meeting_type| MeetingTypeOption {
+                kind: MeetingKind::Builtin(*meeting_type),
+                name: meeting_type.to_string(),
+                color: None,
+                default_duration_minutes: None,
+            });
+
+        let custom = MeetingTypes::get()
+            .await?
+            .into_iter()
+            .map(|custom| MeetingTypeOption {
+                kind: MeetingKind::Custom(custom.meeting_type_id),
+                name: custom.name,
+                color: Some(custom.color),
+                default_duration_minutes: Some(custom.default_duration_minutes),
+            });
+
+        return Ok(builtin.chain(custom).collect());
+    }
+}
+
+impl CreateMeetingType {
+    /// Add a meeting type. Return its ID.
+    pub async fn execute(
+        name: String,
+        color: String,
+        default_duration_minutes: i64,
+        counts_for_attendance: bool,
+    ) -> Result<Option<i64>, TelescopeError> {
+        send_query::<Self>(create_meeting_type::Variables {
+            name,
+            color,
+            default_duration_minutes,
+            counts_for_attendance,
+        })
+        .await
+        .map(|response| {
+            response
+                .insert_meeting_types_one
+                .map(|obj| obj.meeting_type_id)
+        })
+    }
+}
+
+impl EditMeetingType {
+    /// Change a meeting type. Return its ID if it exists.
+    pub async fn execute(
+        meeting_type_id: i64,
+        name: String,
+        color: String,
+        default_duration_minutes: i64,
+        counts_for_attendance: bool,
+    ) -> Result<Option<i64>, TelescopeError> {
+        send_query::<Self>(edit_meeting_type::Variables {
+            meeting_type_id,
+            name,
+            color,
+            default_duration_minutes,
+            counts_for_attendance,
+        })
+        .await
+        .map(|response| {
+            response
+                .update_meeting_types_by_pk
+                .map(|obj| obj.meeting_type_id)
+        })
+    }
+}
@@ -1,22 +1,51 @@
 //! API interactions and functionality.
 
 use crate::api::handle_graphql_response;
+use crate::api::http::{self, Integration};
 use crate::api::rcos::auth::ApiJwtClaims;
 use crate::env::global_config;
 use crate::error::TelescopeError;
+use crate::web::timing;
 use graphql_client::{GraphQLQuery, QueryBody, Response as GraphQlResponse};
-use reqwest::{header::HeaderValue, header::ACCEPT, Client};
+use reqwest::{header::HeaderValue, header::ACCEPT};
 use serde_json::Value;
 
+pub mod api_keys;
+pub mod attendance;
+pub mod audit_log;
 mod auth;
+pub mod badges;
+pub mod batch;
+pub mod contributions;
+pub mod custom_pages;
 pub mod discord_associations;
+pub mod discord_guilds;
+pub mod error_reports;
+mod fixtures;
 pub mod landing_page_stats;
+pub mod leaderboard;
+pub mod link_health;
 pub mod meetings;
+pub mod moderation;
+pub mod pending_actions;
+pub mod policies;
+pub mod polls;
 pub mod prelude;
+pub mod presentations;
 pub mod projects;
+pub mod research;
+pub mod retention;
+pub mod search_index;
 pub mod search_strings;
+pub mod seed;
+pub mod semester_stats;
 pub mod semesters;
+pub mod skills;
+pub mod sponsors;
+pub mod status_updates;
+pub mod tasks;
 pub mod users;
+pub mod webhooks;
 
 /// The name of this API in error messages.
 const API_NAME: &'static str = "RCOS Central Hasura GraphQL API";
@@ -59,6 +88,11 @@ pub async fn send_json_query(
     query_document: &str,
     variables: Value,
 ) -> Result<Value, TelescopeError> {
+    // Answer from fixture files instead if the API is mocked.
+    if let Some(directory) = global_config().api_fixtures.as_ref() {
+        return fixtures::respond(directory, query_name, &variables).await;
+    }
+
     // Build the GraphQL request body.
     let request_body: Value = json!({
         "query": query_document,
@@ -71,41 +105,55 @@ pub async fn send_json_query(
     // the other end.
     let jwt: String = ApiJwtClaims::new(None);
 
-    // Create a new reqwest client
-    return Client::new()
-        // Create a POST request to the API endpoint.
-        .post(global_config().api_url.as_str())
-        // With the serialized JSON of the GraphQL request
-        .json(&request_body)
-        // And the JWT for authentication
-        .bearer_auth(jwt)
-        // Add the Accept header so that the server sends back JSON.
-        .header(ACCEPT, HeaderValue::from_static("application/json"))
-        // Send the request and wait for the response
-        .send()
-        .await
-        // Convert and propagate any errors.
-        .map_err(TelescopeError::rcos_api_error)?
-        // Wait for the body to receive as a string
-        .text()
+    // Create a new reqwest client, counting the time as upstream time.
+    return timing::upstream(async {
+        http::client(Integration::RcosApi)
+            // Create a POST request to the API endpoint.
+            .post(global_config().api_url.as_str())
+            // With the serialized JSON of the GraphQL request
+            .json(&request_body)
+            // And the JWT for authentication
+            .bearer_auth(jwt)
+            // Add the Accept header so that the server sends back JSON.
+            .header(ACCEPT, HeaderValue::from_static("application/json"))
+            // Send the request and wait for the response
+            .send()
+            .await
+            // Convert and propagate any errors.
+            .map_err(TelescopeError::rcos_api_error)?
+            // Wait for the body to receive as a string
+            .text()
+            .await
+            // Convert and propagate any errors on deserializing the response body.
+            .map_err(TelescopeError::rcos_api_error)
+            // Convert the body into the GraphQL response type.
+            .and_then(|body| {
+                serde_json::from_str::<GraphQlResponse<Value>>(body.as_str())
+                    // Map Serde errors into telescope errors
+                    .map_err(|err| {
+                        // Log the error and response body.
+                        error!(
+                            "Error querying RCOS API: {}\nresponse body: {}",
+                            err,
+                            body.as_str()
+                        );
+                        // Convert the error
+                        TelescopeError::RcosApiError(err.to_string())
+                    })
+            })
+            // Convert any GraphQL errors.
+            .and_then(|response| handle_graphql_response(API_NAME, response))
+    })
+    .await;
+}
+
+/// Check that the RCOS API is up and accepting Telescope's JWT. With API
+/// fixtures there is nothing to check.
+pub async fn ping() -> Result<(), TelescopeError> {
+    if global_config().api_fixtures.is_some() {
+        return Ok(());
+    }
+    send_json_query("Ping", "query Ping { __typename }", json!({}))
         .await
-        // Convert and propagate any errors on deserializing the response body.
-        .map_err(TelescopeError::rcos_api_error)
-        // Convert the body into the GraphQL response type.
-        .and_then(|body| {
-            serde_json::from_str::<GraphQlResponse<Value>>(body.as_str())
-                // Map Serde errors into telescope errors
-                .map_err(|err| {
-                    // Log the error and response body.
-                    error!(
-                        "Error querying RCOS API: {}\nresponse body: {}",
-                        err,
-                        body.as_str()
-                    );
-                    // Convert the error
-                    TelescopeError::RcosApiError(err.to_string())
-                })
-        })
-        // Convert any GraphQL errors.
-        .and_then(|response| handle_graphql_response(API_NAME, response));
+        .map(|_| ())
 }
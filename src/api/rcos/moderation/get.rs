@@ -0,0 +1,145 @@
+//! Queries for content reports and the moderation queue.
+
+use crate::api::rcos::moderation::ContentRef;
+use crate::api::rcos::users::display::UserDisplay;
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+
+/// Type representing GraphQL query to get a reported question.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/moderation/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ReportedQuestion;
+
+/// Type representing GraphQL query to get a reported status update submission.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/moderation/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ReportedStatusUpdate;
+
+/// Type representing GraphQL query to get a reported profile.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/moderation/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ReportedProfile;
+
+/// Type representing GraphQL query to get the moderation queue.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/moderation/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ModerationQueue;
+
+/// Type representing GraphQL query to get a single report.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/moderation/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ReportById;
+
+/// Reportable content as it is now.
+#[derive(Serialize, Clone, Debug)]
+pub struct ContentSnapshot {
+    /// Where the content can be seen.
+    pub url: String,
+    /// The text of the content.
+    pub preview: String,
+}
+
+/// Get reportable content as it is now. `None` if it does not exist.
+pub async fn snapshot(content: ContentRef) -> Result<Option<ContentSnapshot>, TelescopeError> {
+    match content {
+        ContentRef::Question(question_id) => {
+            let question =
+                send_query::<ReportedQuestion>(reported_question::Variables { question_id })
+                    .await?
+                    .question;
+
+            Ok(question.map(|question| ContentSnapshot {
+                url: format!("/meeting/{}/questions", question.meeting_id),
+                preview: question.body,
+            }))
+        }
+
+        ContentRef::StatusUpdate {
+            status_update_id,
+            user_id,
+        } => {
+            let submission =
+                send_query::<ReportedStatusUpdate>(reported_status_update::Variables {
+                    status_update_id,
+                    user_id,
+                })
+                .await?
+                .submission;
+
+            Ok(submission.map(|submission| ContentSnapshot {
+                url: format!("/user/{}", user_id),
+                preview: format!(
+                    "This week:\n{}\n\nNext week:\n{}\n\nBlockers:\n{}",
+                    submission.this_week, submission.next_week, submission.blockers
+                ),
+            }))
+        }
+
+        ContentRef::Profile(user_id) => {
+            let user = send_query::<ReportedProfile>(reported_profile::Variables { user_id })
+                .await?
+                .user;
+
+            Ok(user.map(|user| {
+                let display = UserDisplay::new(
+                    user.first_name.as_str(),
+                    user.last_name.as_str(),
+                    user.preferred_name.clone(),
+                    user.pronouns.clone(),
+                );
+
+                // Everything on a profile that the user wrote themselves.
+                let mut preview: String = display.name();
+                if let Some(pronouns) = display.pronouns() {
+                    preview.push_str(format!(" ({})", pronouns).as_str());
+                }
+                if let Some(username) = user.username.as_ref() {
+                    preview.push_str(format!(" @{}", username).as_str());
+                }
+
+                ContentSnapshot {
+                    url: format!("/user/{}", user_id),
+                    preview,
+                }
+            }))
+        }
+    }
+}
+
+impl ModerationQueue {
+    /// Get the open reports and recent decisions.
+    pub async fn get() -> Result<moderation_queue::ResponseData, TelescopeError> {
+        send_query::<Self>(moderation_queue::Variables {}).await
+    }
+}
+
+impl ReportById {
+    /// Get a report by its ID, if it exists.
+    pub async fn get(
+        report_id: i64,
+    ) -> Result<Option<report_by_id::ReportByIdReport>, TelescopeError> {
+        send_query::<Self>(report_by_id::Variables { report_id })
+            .await
+            .map(|response| response.report)
+    }
+}
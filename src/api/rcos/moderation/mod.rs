@@ -0,0 +1,89 @@
+//! Content reports and moderation decisions.
+
+pub mod get;
+pub mod mutations;
+
+use uuid::Uuid;
+
+/// The kinds of user-generated content that can be reported.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportedContent {
+    #[display(fmt = "Status Update")]
+    StatusUpdate,
+    #[display(fmt = "Profile")]
+    Profile,
+    #[display(fmt = "Question")]
+    Question,
+}
+
+/// What happened to a report.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportStatus {
+    /// Waiting for a moderator.
+    #[display(fmt = "Open")]
+    Open,
+    /// The content was hidden from other members.
+    #[display(fmt = "Hidden")]
+    Hidden,
+    /// The content was removed.
+    #[display(fmt = "Removed")]
+    Removed,
+    /// The content was left alone.
+    #[display(fmt = "Dismissed")]
+    Dismissed,
+}
+
+/// A piece of reportable content. Reports store this as a content type and a
+/// key string.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ContentRef {
+    /// A status update submission, keyed as `<status_update_id>:<user_id>`.
+    StatusUpdate {
+        status_update_id: i64,
+        user_id: Uuid,
+    },
+    /// A user's profile, keyed by user ID.
+    Profile(Uuid),
+    /// A question asked during a meeting, keyed by question ID.
+    Question(i64),
+}
+
+impl ContentRef {
+    /// Read a content reference from its type and key.
+    pub fn parse(content_type: ReportedContent, key: &str) -> Option<Self> {
+        match content_type {
+            ReportedContent::StatusUpdate => {
+                let (status_update_id, user_id) = key.split_once(':')?;
+                Some(ContentRef::StatusUpdate {
+                    status_update_id: status_update_id.parse().ok()?,
+                    user_id: user_id.parse().ok()?,
+                })
+            }
+            ReportedContent::Profile => key.parse().ok().map(ContentRef::Profile),
+            ReportedContent::Question => key.parse().ok().map(ContentRef::Question),
+        }
+    }
+
+    /// The type of the content.
+    pub fn content_type(&self) -> ReportedContent {
+        match self {
+            ContentRef::StatusUpdate { .. } => ReportedContent::StatusUpdate,
+            ContentRef::Profile(_) => ReportedContent::Profile,
+            ContentRef::Question(_) => ReportedContent::Question,
+        }
+    }
+
+    /// The key of the content within its type.
+    pub fn key(&self) -> String {
+        match self {
+            ContentRef::StatusUpdate {
+                status_update_id,
+                user_id,
+            } => format!("{}:{}", status_update_id, user_id),
+            ContentRef::Profile(user_id) => user_id.to_string(),
+            ContentRef::Question(question_id) => question_id.to_string(),
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Mutations for content reports and moderation decisions.
+
+use crate::api::rcos::moderation::{ContentRef, ReportStatus};
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Text that replaces the parts of a removed status update.
+const REMOVED_PLACEHOLDER: &'static str = "[Removed by a moderator]";
+
+/// Type representing GraphQL mutation to file a report.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/moderation/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct FileReport;
+
+/// Type representing GraphQL mutation to close the open reports of some content.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/moderation/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct CloseReports;
+
+/// Type representing GraphQL mutation to record a decision in the moderation log.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/moderation/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct LogDecision;
+
+/// Type representing GraphQL mutation to hide a question.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/moderation/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct HideQuestion;
+
+/// Type representing GraphQL mutation to hide a status update submission.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/moderation/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct HideStatusUpdate;
+
+/// Type representing GraphQL mutation to hide a profile.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/moderation/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct HideProfile;
+
+/// Type representing GraphQL mutation to delete a question.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/moderation/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RemoveQuestion;
+
+/// Type representing GraphQL mutation to clear the text of a status update submission.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/moderation/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RemoveStatusUpdate;
+
+/// Type representing GraphQL mutation to clear what a user wrote on their profile.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/moderation/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RemoveProfile;
+
+impl FileReport {
+    /// File a report. Return its ID.
+    pub async fn execute(
+        content: ContentRef,
+        content_url: String,
+        content_preview: String,
+        reporter_id: uuid,
+        reason: String,
+    ) -> Result<i64, TelescopeError> {
+        send_query::<Self>(file_report::Variables {
+            content_type: content.content_type(),
+            content_key: content.key(),
+            content_url,
+            content_preview,
+            reporter_id,
+            reason,
+            now: Utc::now(),
+        })
+        .await?
+        .insert_moderation_reports_one
+        .map(|report| report.report_id)
+        .ok_or(TelescopeError::ise("Report was not filed."))
+    }
+}
+
+impl CloseReports {
+    /// Close every open report of some content with a decision. Return the
+    /// Discord user ID of each closed report's reporter, or `None` for
+    /// reporters without a linked Discord account.
+    pub async fn execute(
+        content: ContentRef,
+        action: ReportStatus,
+    ) -> Result<Vec<Option<u64>>, TelescopeError> {
+        let closed = send_query::<Self>(close_reports::Variables {
+            content_type: content.content_type(),
+            content_key: content.key(),
+            action,
+        })
+        .await?
+        .update_moderation_reports
+        .map(|updated| updated.returning)
+        .unwrap_or_default();
+
+        return Ok(closed
+            .into_iter()
+            .map(|report| {
+                report
+                    .reporter
+                    .discord
+                    .first()
+                    .and_then(|account| account.account_id.parse::<u64>().ok())
+            })
+            .collect());
+    }
+}
+
+impl LogDecision {
+    /// Record a moderator's decision on some content.
+    pub async fn execute(
+        content: ContentRef,
+        content_url: String,
+        action: ReportStatus,
+        note: Option<String>,
+        reports_closed: usize,
+        moderator_id: uuid,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(log_decision::Variables {
+            content_type: content.content_type(),
+            content_key: content.key(),
+            content_url,
+            action,
+            note,
+            reports_closed: reports_closed as i64,
+            moderator_id,
+            now: Utc::now(),
+        })
+        .await?;
+        return Ok(());
+    }
+}
+
+/// Hide content from other members. Return false if it does not exist.
+pub async fn hide(content: ContentRef) -> Result<bool, TelescopeError> {
+    match content {
+        ContentRef::Question(question_id) => send_query::<HideQuestion>(hide_question::Variables {
+            question_id,
+            hidden: true,
+        })
+        .await
+        .map(|response| response.update_meeting_questions_by_pk.is_some()),
+
+        ContentRef::StatusUpdate {
+            status_update_id,
+            user_id,
+        } => send_query::<HideStatusUpdate>(hide_status_update::Variables {
+            status_update_id,
+            user_id,
+            hidden: true,
+        })
+        .await
+        .map(|response| response.update_status_update_submissions_by_pk.is_some()),
+
+        ContentRef::Profile(user_id) => send_query::<HideProfile>(hide_profile::Variables {
+            user_id,
+            hidden: true,
+        })
+        .await
+        .map(|response| response.update_users_by_pk.is_some()),
+    }
+}
+
+/// Remove content. Questions are deleted, status updates lose their text, and
+/// profiles lose the name, pronouns, username, and picture the user set.
+/// Return false if the content does not exist.
+pub async fn remove(content: ContentRef) -> Result<bool, TelescopeError> {
+    match content {
+        ContentRef::Question(question_id) => {
+            send_query::<RemoveQuestion>(remove_question::Variables { question_id })
+                .await
+                .map(|response| response.delete_meeting_questions_by_pk.is_some())
+        }
+
+        ContentRef::StatusUpdate {
+            status_update_id,
+            user_id,
+        } => send_query::<RemoveStatusUpdate>(remove_status_update::Variables {
+            status_update_id,
+            user_id,
+            placeholder: REMOVED_PLACEHOLDER.to_string(),
+        })
+        .await
+        .map(|response| response.update_status_update_submissions_by_pk.is_some()),
+
+        ContentRef::Profile(user_id) => {
+            send_query::<RemoveProfile>(remove_profile::Variables { user_id })
+                .await
+                .map(|response| response.update_users_by_pk.is_some())
+        }
+    }
+}
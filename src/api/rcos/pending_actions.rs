@@ -0,0 +1,53 @@
+//! Counts of items waiting on coordinators.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL query to count items waiting on coordinators.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/pending_actions.graphql",
+    response_derives = "Debug"
+)]
+pub struct PendingActions;
+
+/// How many of each kind of item are waiting on coordinators.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PendingCounts {
+    pub mentor_applications: i64,
+    pub moderation_reports: i64,
+    pub excused_absences: i64,
+    pub join_requests: i64,
+}
+
+impl PendingCounts {
+    /// The number of items waiting, of every kind.
+    pub fn total(&self) -> i64 {
+        self.mentor_applications
+            + self.moderation_reports
+            + self.excused_absences
+            + self.join_requests
+    }
+}
+
+impl PendingActions {
+    /// Count the items waiting on coordinators.
+    pub async fn get() -> Result<PendingCounts, TelescopeError> {
+        let data = send_query::<Self>(pending_actions::Variables {
+            now: Utc::today().naive_utc(),
+        })
+        .await?;
+
+        return Ok(PendingCounts {
+            mentor_applications: data
+                .mentor_applications
+                .aggregate
+                .map_or(0, |agg| agg.count),
+            moderation_reports: data.moderation_reports.aggregate.map_or(0, |agg| agg.count),
+            excused_absences: data.excused_absences.aggregate.map_or(0, |agg| agg.count),
+            join_requests: data.join_requests.aggregate.map_or(0, |agg| agg.count),
+        });
+    }
+}
@@ -0,0 +1,84 @@
+//! Queries for versioned policies and who accepted them.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+
+/// Type representing GraphQL query to get the latest version of every policy.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/policies/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct LatestPolicies;
+
+/// Type representing GraphQL query to get which policy versions a user accepted.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/policies/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct PolicyAcceptances;
+
+/// Type representing GraphQL query to get every version of every policy.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/policies/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct PolicyVersions;
+
+/// Type representing GraphQL query to get who accepted a policy version.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/policies/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct PolicyAcceptanceReport;
+
+impl LatestPolicies {
+    /// Get the latest version of every policy that has been published.
+    pub async fn get() -> Result<Vec<latest_policies::PolicyInfo>, TelescopeError> {
+        send_query::<Self>(latest_policies::Variables {})
+            .await
+            .map(|response| response.policies)
+    }
+}
+
+impl PolicyAcceptances {
+    /// Get the IDs of the policy versions a user has accepted, out of the given ones.
+    pub async fn get(user_id: uuid, policy_ids: Vec<i64>) -> Result<Vec<i64>, TelescopeError> {
+        send_query::<Self>(policy_acceptances::Variables {
+            user_id,
+            policy_ids,
+        })
+        .await
+        .map(|response| {
+            response
+                .accepted
+                .into_iter()
+                .map(|acceptance| acceptance.policy_id)
+                .collect()
+        })
+    }
+}
+
+impl PolicyVersions {
+    /// Get every version of every policy.
+    pub async fn get() -> Result<policy_versions::ResponseData, TelescopeError> {
+        send_query::<Self>(policy_versions::Variables {}).await
+    }
+}
+
+impl PolicyAcceptanceReport {
+    /// Get a policy version and who accepted it. The policy is `None` if it
+    /// does not exist.
+    pub async fn get(
+        policy_id: i64,
+    ) -> Result<policy_acceptance_report::ResponseData, TelescopeError> {
+        send_query::<Self>(policy_acceptance_report::Variables { policy_id }).await
+    }
+}
@@ -0,0 +1,27 @@
+//! Versioned policies that members accept, like the code of conduct.
+
+pub mod get;
+pub mod mutations;
+
+/// The policies members have to accept.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyKind {
+    #[display(fmt = "Terms of Service")]
+    Terms,
+    #[display(fmt = "Code of Conduct")]
+    CodeOfConduct,
+}
+
+impl PolicyKind {
+    /// Every kind of policy.
+    pub const ALL: [PolicyKind; 2] = [PolicyKind::Terms, PolicyKind::CodeOfConduct];
+
+    /// The name of the policy kind in the API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PolicyKind::Terms => "terms",
+            PolicyKind::CodeOfConduct => "code_of_conduct",
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Mutations for versioned policies.
+
+use crate::api::rcos::policies::PolicyKind;
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL mutation to publish a policy version.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/policies/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct PublishPolicy;
+
+/// Type representing GraphQL mutation to accept a policy version.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/policies/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AcceptPolicy;
+
+impl PublishPolicy {
+    /// Publish a version of a policy. Return its ID.
+    pub async fn execute(
+        kind: PolicyKind,
+        version: i64,
+        title: String,
+        body: String,
+        published_by: uuid,
+    ) -> Result<i64, TelescopeError> {
+        send_query::<Self>(publish_policy::Variables {
+            kind: kind.as_str().to_string(),
+            version,
+            title,
+            body,
+            published_by,
+            now: Utc::now(),
+        })
+        .await?
+        .insert_policy_documents_one
+        .map(|policy| policy.policy_id)
+        .ok_or(TelescopeError::ise("Policy was not published."))
+    }
+}
+
+impl AcceptPolicy {
+    /// Record that a user accepted some policy versions.
+    pub async fn execute(user_id: uuid, policy_ids: Vec<i64>) -> Result<(), TelescopeError> {
+        for policy_id in policy_ids {
+            send_query::<Self>(accept_policy::Variables {
+                user_id,
+                policy_id,
+                now: Utc::now(),
+            })
+            .await?;
+        }
+        return Ok(());
+    }
+}
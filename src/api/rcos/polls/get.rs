@@ -0,0 +1,95 @@
+//! Queries for polls.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::{Duration, Utc};
+
+/// How many polls are listed on the polls page.
+const LIST_LIMIT: i64 = 50;
+
+/// How far back meetings can be picked to attach a poll to.
+const MEETING_LOOKBACK_DAYS: i64 = 7;
+
+/// Type representing GraphQL query to list recent polls.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/polls/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ListPolls;
+
+/// Type representing GraphQL query to get a poll and its results.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/polls/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct PollById;
+
+/// Type representing GraphQL query to get who voted for what on a poll.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/polls/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct PollVoters;
+
+/// Type representing GraphQL query to get meetings a poll can be attached to.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/polls/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct PollMeetings;
+
+impl ListPolls {
+    /// Get the most recent polls.
+    pub async fn get() -> Result<Vec<list_polls::ListPollsPolls>, TelescopeError> {
+        Ok(
+            send_query::<Self>(list_polls::Variables { limit: LIST_LIMIT })
+                .await?
+                .polls,
+        )
+    }
+}
+
+impl PollById {
+    /// Get a poll as seen by a viewer. The poll is `None` if it does not exist.
+    pub async fn get(
+        poll_id: i64,
+        viewer: uuid,
+    ) -> Result<Option<poll_by_id::PollByIdPoll>, TelescopeError> {
+        Ok(
+            send_query::<Self>(poll_by_id::Variables { poll_id, viewer })
+                .await?
+                .poll,
+        )
+    }
+}
+
+impl PollVoters {
+    /// Get the voters of each option of a poll. Callers must check that the
+    /// poll is not anonymous first.
+    pub async fn get(
+        poll_id: i64,
+    ) -> Result<Vec<poll_voters::PollVotersPollOptions>, TelescopeError> {
+        Ok(send_query::<Self>(poll_voters::Variables { poll_id })
+            .await?
+            .poll_options)
+    }
+}
+
+impl PollMeetings {
+    /// Get recent and upcoming meetings.
+    pub async fn get() -> Result<Vec<poll_meetings::PollMeetingsMeetings>, TelescopeError> {
+        Ok(send_query::<Self>(poll_meetings::Variables {
+            since: Utc::now() - Duration::days(MEETING_LOOKBACK_DAYS),
+        })
+        .await?
+        .meetings)
+    }
+}
@@ -0,0 +1,36 @@
+//! Polls created by coordinators, standalone or attached to a meeting.
+
+use crate::api::rcos::prelude::*;
+use chrono::Utc;
+
+pub mod get;
+pub mod mutations;
+
+/// Whether a poll is taking votes.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display)]
+#[serde(rename_all = "snake_case")]
+pub enum PollStatus {
+    /// The poll has not opened yet.
+    #[display(fmt = "Upcoming")]
+    Upcoming,
+    /// The poll is taking votes.
+    #[display(fmt = "Open")]
+    Open,
+    /// The poll has closed.
+    #[display(fmt = "Closed")]
+    Closed,
+}
+
+impl PollStatus {
+    /// Get the status of a poll with the given open and close times.
+    pub fn of(opens_at: Option<timestamptz>, closes_at: Option<timestamptz>) -> Self {
+        let now = Utc::now();
+        if opens_at.map(|opens| now < opens).unwrap_or(false) {
+            return PollStatus::Upcoming;
+        }
+        if closes_at.map(|closes| now >= closes).unwrap_or(false) {
+            return PollStatus::Closed;
+        }
+        return PollStatus::Open;
+    }
+}
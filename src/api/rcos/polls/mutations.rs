@@ -0,0 +1,158 @@
+//! Mutations for polls.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL mutation to create a poll.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/polls/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct CreatePoll;
+
+/// Type representing GraphQL mutation to add an option to a poll.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/polls/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AddPollOption;
+
+/// Type representing GraphQL mutation to remove a voter's other picks.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/polls/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ClearPollVotes;
+
+/// Type representing GraphQL mutation to pick an option.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/polls/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AddPollVote;
+
+/// Type representing GraphQL mutation to close a poll now.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/polls/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ClosePoll;
+
+/// Type representing GraphQL mutation to record the Discord message linking to a poll.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/polls/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SetPollDiscordMessage;
+
+/// The fields of a new poll.
+#[derive(Clone, Debug)]
+pub struct PollFields {
+    pub title: String,
+    pub description: Option<String>,
+    pub is_multiple_choice: bool,
+    pub is_anonymous: bool,
+    pub opens_at: Option<timestamptz>,
+    pub closes_at: Option<timestamptz>,
+    pub meeting_id: Option<i64>,
+    /// The options in the order they are shown.
+    pub options: Vec<String>,
+}
+
+impl CreatePoll {
+    /// Create a poll and its options. Return the poll's ID.
+    pub async fn execute(fields: PollFields, created_by: uuid) -> Result<i64, TelescopeError> {
+        let poll_id: i64 = send_query::<Self>(create_poll::Variables {
+            title: fields.title,
+            description: fields.description,
+            is_multiple_choice: fields.is_multiple_choice,
+            is_anonymous: fields.is_anonymous,
+            opens_at: fields.opens_at,
+            closes_at: fields.closes_at,
+            meeting_id: fields.meeting_id,
+            created_by,
+            now: Utc::now(),
+        })
+        .await?
+        .insert_polls_one
+        .map(|poll| poll.poll_id)
+        .ok_or(TelescopeError::ise("Poll was not created."))?;
+
+        for (position, text) in fields.options.into_iter().enumerate() {
+            send_query::<AddPollOption>(add_poll_option::Variables {
+                poll_id,
+                text,
+                position: position as i64,
+            })
+            .await?;
+        }
+
+        return Ok(poll_id);
+    }
+}
+
+impl ClearPollVotes {
+    /// Set a voter's picks on a poll, replacing any they made before. The
+    /// options must belong to the poll.
+    pub async fn cast(
+        poll_id: i64,
+        user_id: uuid,
+        option_ids: Vec<i64>,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(clear_poll_votes::Variables {
+            poll_id,
+            user_id,
+            keep: option_ids.clone(),
+        })
+        .await?;
+
+        for option_id in option_ids {
+            send_query::<AddPollVote>(add_poll_vote::Variables {
+                poll_id,
+                option_id,
+                user_id,
+                now: Utc::now(),
+            })
+            .await?;
+        }
+
+        return Ok(());
+    }
+}
+
+impl ClosePoll {
+    /// Stop a poll from taking votes.
+    pub async fn execute(poll_id: i64) -> Result<(), TelescopeError> {
+        send_query::<Self>(close_poll::Variables {
+            poll_id,
+            now: Utc::now(),
+        })
+        .await?;
+        return Ok(());
+    }
+}
+
+impl SetPollDiscordMessage {
+    /// Record the Discord message that links to a poll.
+    pub async fn execute(poll_id: i64, message_id: String) -> Result<(), TelescopeError> {
+        send_query::<Self>(set_poll_discord_message::Variables {
+            poll_id,
+            message_id,
+        })
+        .await?;
+        return Ok(());
+    }
+}
@@ -6,6 +6,8 @@
 use crate::api::rcos::discord_associations::ChannelType;
 use crate::api::rcos::{
     meetings::MeetingType,
+    moderation::{ReportStatus, ReportedContent},
+    tasks::TaskStatus,
     users::{UserAccountType, UserRole},
 };
 use chrono::{DateTime, NaiveDate, Utc};
@@ -33,8 +35,53 @@ pub type _varchar = Vec<String>;
 /// List of urls for some reason not properly set in Hasura.
 pub type _url = Vec<Url>;
 
+/// Coordinator task board columns.
+pub type task_status = TaskStatus;
+
+/// Kinds of reportable content.
+pub type reported_content = ReportedContent;
+
+/// Outcomes of content reports.
+pub type report_status = ReportStatus;
+
 /// Discord channel association variants.
 pub type channel_type = ChannelType;
 
 /// UUID type alias for hasura.
 pub type uuid = ::uuid::Uuid;
+
+// The RCOS API uses plain `String` and `Int` scalars for these IDs, so the
+// generated query types cannot use these newtypes directly. API wrappers take
+// and return them so that handlers cannot mix one kind of ID up with another;
+// unwrap them with `.into()` when building query variables.
+
+/// The ID of a meeting.
+#[derive(
+    Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Hash, Display, From, Into, FromStr,
+)]
+#[serde(transparent)]
+pub struct MeetingId(pub i64);
+
+/// The ID of a semester, the registrar's year and starting month, e.g. "202109".
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash, Display, From, Into)]
+#[serde(transparent)]
+pub struct SemesterId(pub String);
+
+/// A user's username.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash, Display, From, Into)]
+#[serde(transparent)]
+pub struct Username(pub String);
+
+impl SemesterId {
+    /// Borrow the ID as a string.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl Username {
+    /// Borrow the username as a string.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
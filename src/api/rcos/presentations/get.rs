@@ -0,0 +1,111 @@
+//! GraphQL queries for presentation slots.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::{Duration, Utc};
+
+/// Type representing GraphQL query to get a semester's presentation schedule.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/presentations/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct PresentationSchedule;
+
+/// Type representing GraphQL query to get the context for claiming or
+/// releasing a slot.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/presentations/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SlotClaimContext;
+
+/// Type representing GraphQL query to find slots that overlap a time range.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/presentations/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct OverlappingSlots;
+
+/// Type representing GraphQL query to get claimed slots that need reminders.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/presentations/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct UpcomingPresentations;
+
+impl PresentationSchedule {
+    /// Get the presentation schedule for a semester. The viewer is used to
+    /// find which projects they can claim slots for.
+    pub async fn get(
+        semester_id: String,
+        viewer: Option<uuid>,
+    ) -> Result<presentation_schedule::ResponseData, TelescopeError> {
+        send_query::<Self>(presentation_schedule::Variables {
+            semester_id,
+            viewer: viewer.map(|v| vec![v]).unwrap_or(Vec::new()),
+        })
+        .await
+    }
+}
+
+impl SlotClaimContext {
+    /// Get a slot along with the claimed projects in its semester and the
+    /// projects the user leads that semester.
+    pub async fn get(
+        slot_id: i64,
+        user_id: uuid,
+    ) -> Result<Option<slot_claim_context::SlotClaimContextPresentationSlotsByPk>, TelescopeError>
+    {
+        Ok(
+            send_query::<Self>(slot_claim_context::Variables { slot_id, user_id })
+                .await?
+                .presentation_slots_by_pk,
+        )
+    }
+}
+
+impl OverlappingSlots {
+    /// Check if any slot in the semester overlaps the given time range at the
+    /// given location.
+    pub async fn exists(
+        semester_id: String,
+        start: timestamptz,
+        end: timestamptz,
+        location: Option<String>,
+    ) -> Result<bool, TelescopeError> {
+        Ok(!send_query::<Self>(overlapping_slots::Variables {
+            semester_id,
+            start,
+            end,
+            location,
+        })
+        .await?
+        .presentation_slots
+        .is_empty())
+    }
+}
+
+impl UpcomingPresentations {
+    /// Get claimed slots starting within the given window whose presenters
+    /// have not been reminded.
+    pub async fn get(
+        window: Duration,
+    ) -> Result<Vec<upcoming_presentations::UpcomingPresentationsPresentationSlots>, TelescopeError>
+    {
+        let now = Utc::now();
+        Ok(send_query::<Self>(upcoming_presentations::Variables {
+            now,
+            until: now + window,
+        })
+        .await?
+        .presentation_slots)
+    }
+}
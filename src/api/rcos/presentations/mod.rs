@@ -0,0 +1,4 @@
+//! GraphQL queries and mutations for the end of semester presentation schedule.
+
+pub mod get;
+pub mod mutations;
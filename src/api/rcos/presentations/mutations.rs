@@ -0,0 +1,122 @@
+//! GraphQL mutations on presentation slots.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+
+/// Type representing GraphQL mutation to create a presentation slot.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/presentations/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct CreatePresentationSlot;
+
+/// Type representing GraphQL mutation to delete a presentation slot.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/presentations/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct DeletePresentationSlot;
+
+/// Type representing GraphQL mutation to claim an open slot for a project.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/presentations/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ClaimPresentationSlot;
+
+/// Type representing GraphQL mutation to release a claimed slot.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/presentations/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ReleasePresentationSlot;
+
+/// Type representing GraphQL mutation to mark slot reminders as sent.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/presentations/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct MarkPresentationRemindersSent;
+
+impl CreatePresentationSlot {
+    /// Create a slot and return its ID.
+    pub async fn execute(
+        semester_id: String,
+        start: timestamptz,
+        end: timestamptz,
+        location: Option<String>,
+    ) -> Result<Option<i64>, TelescopeError> {
+        send_query::<Self>(create_presentation_slot::Variables {
+            semester_id,
+            start,
+            end,
+            location,
+        })
+        .await
+        .map(|data| data.insert_presentation_slots_one.map(|obj| obj.slot_id))
+    }
+}
+
+impl DeletePresentationSlot {
+    /// Delete a slot. Return the slot's semester ID if it existed.
+    pub async fn execute(slot_id: i64) -> Result<Option<String>, TelescopeError> {
+        send_query::<Self>(delete_presentation_slot::Variables { slot_id })
+            .await
+            .map(|data| {
+                data.delete_presentation_slots_by_pk
+                    .map(|obj| obj.semester_id)
+            })
+    }
+}
+
+impl ClaimPresentationSlot {
+    /// Claim a slot for a project. Return `false` if the slot was already taken.
+    pub async fn execute(
+        slot_id: i64,
+        project_id: i64,
+        user_id: uuid,
+    ) -> Result<bool, TelescopeError> {
+        send_query::<Self>(claim_presentation_slot::Variables {
+            slot_id,
+            project_id,
+            user_id,
+        })
+        .await
+        .map(|data| {
+            data.update_presentation_slots
+                .map(|obj| obj.affected_rows > 0)
+                .unwrap_or(false)
+        })
+    }
+}
+
+impl ReleasePresentationSlot {
+    /// Release a claimed slot. Return the slot's semester ID if it exists.
+    pub async fn execute(slot_id: i64) -> Result<Option<String>, TelescopeError> {
+        send_query::<Self>(release_presentation_slot::Variables { slot_id })
+            .await
+            .map(|data| {
+                data.update_presentation_slots_by_pk
+                    .map(|obj| obj.semester_id)
+            })
+    }
+}
+
+impl MarkPresentationRemindersSent {
+    /// Mark reminders as sent for the given slots.
+    pub async fn execute(slot_ids: Vec<i64>) -> Result<(), TelescopeError> {
+        send_query::<Self>(mark_presentation_reminders_sent::Variables { slot_ids })
+            .await
+            .map(|_| ())
+    }
+}
@@ -0,0 +1,105 @@
+//! Team constraints, the per-semester limits on project teams.
+//!
+//! A semester can cap how many members a project has, and require projects to
+//! have at least one lead mentoring every so many members. They are checked
+//! when requests to join are approved, and projects that break them are
+//! flagged on the project health overview.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL mutation to set a semester's team constraints.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/projects/constraints.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SetTeamConstraints;
+
+/// Type representing GraphQL query to get a semester's team constraints.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/projects/constraints.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SemesterTeamConstraints;
+
+/// The limits on project teams in a semester. Unset limits are not checked.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TeamConstraints {
+    /// The most members a project may have.
+    pub max_team_size: Option<i64>,
+    /// Projects need at least one lead for every this many members.
+    pub members_per_mentor: Option<i64>,
+}
+
+impl TeamConstraints {
+    /// Describe how a team with this many members and leads breaks the
+    /// constraints. Empty if it does not.
+    pub fn violations(&self, members: i64, leads: i64) -> Vec<String> {
+        let mut violations: Vec<String> = Vec::new();
+
+        if let Some(max) = self.max_team_size.filter(|max| members > *max) {
+            violations.push(format!(
+                "{} members is more than the limit of {}.",
+                members, max
+            ));
+        }
+
+        if let Some(per) = self.members_per_mentor.filter(|per| *per > 0) {
+            // Round up, so that any members past a multiple need another lead.
+            let needed: i64 = (members + per - 1) / per;
+            if leads < needed {
+                violations.push(format!(
+                    "{} members need at least {} leads, but there are {}.",
+                    members, needed, leads
+                ));
+            }
+        }
+
+        return violations;
+    }
+}
+
+impl SetTeamConstraints {
+    /// Set a semester's team constraints.
+    pub async fn execute(
+        semester_id: SemesterId,
+        constraints: TeamConstraints,
+        updated_by: uuid,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(set_team_constraints::Variables {
+            semester_id: semester_id.into(),
+            max_team_size: constraints.max_team_size,
+            members_per_mentor: constraints.members_per_mentor,
+            updated_by,
+            now: Utc::now(),
+        })
+        .await?;
+        return Ok(());
+    }
+}
+
+impl SemesterTeamConstraints {
+    /// Get a semester's team constraints. Semesters without any have no
+    /// limits.
+    pub async fn get(semester_id: SemesterId) -> Result<TeamConstraints, TelescopeError> {
+        send_query::<Self>(semester_team_constraints::Variables {
+            semester_id: semester_id.into(),
+        })
+        .await
+        .map(|response| {
+            response
+                .semesters_by_pk
+                .and_then(|semester| semester.team_constraints)
+                .map(|c| TeamConstraints {
+                    max_team_size: c.max_team_size,
+                    members_per_mentor: c.members_per_mentor,
+                })
+                .unwrap_or_default()
+        })
+    }
+}
@@ -0,0 +1,173 @@
+//! Requests from members to join a project.
+//!
+//! Members ask to join a project for the current semester, and the project's
+//! leads (or a coordinator) approve or deny the request. Approving a request
+//! puts the member on the project.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL query to get the requests to join a project.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/projects/join_requests.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct JoinRequests;
+
+/// Type representing GraphQL mutation to ask to join a project.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/projects/join_requests.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RequestToJoin;
+
+/// Type representing GraphQL mutation to approve a request to join a project.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/projects/join_requests.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ApproveJoinRequest;
+
+/// Type representing GraphQL mutation to deny a request to join a project.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/projects/join_requests.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct DenyJoinRequest;
+
+/// Type representing GraphQL query to get a project's Discord role.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/projects/join_requests.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ProjectRole;
+
+/// Where a request to join a project stands.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+pub enum JoinRequestStatus {
+    #[display(fmt = "pending")]
+    Pending,
+    #[display(fmt = "approved")]
+    Approved,
+    #[display(fmt = "denied")]
+    Denied,
+}
+
+impl JoinRequests {
+    /// Get the viewer's latest request to join a project in a semester, and
+    /// the requests waiting for a decision.
+    pub async fn get(
+        project_id: i64,
+        semester_id: SemesterId,
+        viewer: uuid,
+    ) -> Result<join_requests::ResponseData, TelescopeError> {
+        send_query::<Self>(join_requests::Variables {
+            project_id,
+            semester_id: semester_id.into(),
+            user_id: viewer,
+        })
+        .await
+    }
+}
+
+impl RequestToJoin {
+    /// Ask to join a project in a semester.
+    pub async fn execute(
+        project_id: i64,
+        user_id: uuid,
+        semester_id: SemesterId,
+        message: Option<String>,
+    ) -> Result<Option<i64>, TelescopeError> {
+        send_query::<Self>(request_to_join::Variables {
+            project_id,
+            user_id,
+            semester_id: semester_id.into(),
+            message,
+            now: Utc::now(),
+        })
+        .await
+        .map(|response| {
+            response
+                .insert_project_join_requests_one
+                .map(|obj| obj.request_id)
+        })
+    }
+}
+
+impl ApproveJoinRequest {
+    /// Approve a pending request from a member to join a project in a
+    /// semester, and put them on the project. Return false if the request was
+    /// not pending.
+    pub async fn execute(
+        request_id: i64,
+        project_id: i64,
+        user_id: uuid,
+        semester_id: SemesterId,
+        decided_by: uuid,
+    ) -> Result<bool, TelescopeError> {
+        send_query::<Self>(approve_join_request::Variables {
+            request_id,
+            project_id,
+            user_id,
+            semester_id: semester_id.into(),
+            decided_by,
+            now: Utc::now(),
+        })
+        .await
+        .map(|response| {
+            response
+                .update_project_join_requests
+                .map_or(false, |r| r.affected_rows > 0)
+        })
+    }
+}
+
+impl DenyJoinRequest {
+    /// Deny a pending request to join a project. Return false if the request
+    /// was not pending.
+    pub async fn execute(
+        request_id: i64,
+        project_id: i64,
+        decided_by: uuid,
+    ) -> Result<bool, TelescopeError> {
+        send_query::<Self>(deny_join_request::Variables {
+            request_id,
+            project_id,
+            decided_by,
+            now: Utc::now(),
+        })
+        .await
+        .map(|response| {
+            response
+                .update_project_join_requests
+                .map_or(false, |r| r.affected_rows > 0)
+        })
+    }
+}
+
+impl ProjectRole {
+    /// Get the ID of the Discord role for a project's channels, if it has
+    /// one.
+    pub async fn get(project_id: i64) -> Result<Option<u64>, TelescopeError> {
+        send_query::<Self>(project_role::Variables { project_id })
+            .await
+            .map(|response| {
+                response
+                    .project_roles
+                    .into_iter()
+                    .find_map(|role| role.role_id.parse::<u64>().ok())
+            })
+    }
+}
@@ -0,0 +1,177 @@
+//! GraphQL mutations for project milestones, and the query for the project
+//! health overview.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::{NaiveDate, Utc};
+
+/// Type representing GraphQL mutation to add a milestone.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/projects/milestones.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct CreateMilestone;
+
+/// Type representing GraphQL mutation to change a milestone.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/projects/milestones.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct EditMilestone;
+
+/// Type representing GraphQL mutation to remove a milestone.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/projects/milestones.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct DeleteMilestone;
+
+/// Type representing GraphQL query to get current projects and their
+/// milestones.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/projects/milestones.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ProjectHealth;
+
+/// How far along a milestone is.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Display)]
+#[serde(rename_all = "snake_case")]
+pub enum MilestoneStatus {
+    #[display(fmt = "planned")]
+    Planned,
+    #[display(fmt = "in_progress")]
+    InProgress,
+    #[display(fmt = "done")]
+    Done,
+}
+
+/// Fields of a milestone to save.
+#[derive(Clone, Debug)]
+pub struct MilestoneFields {
+    pub title: String,
+    pub due_date: NaiveDate,
+    pub status: MilestoneStatus,
+}
+
+/// Progress of a project through its milestones.
+#[derive(Copy, Clone, Debug, Default, Serialize)]
+pub struct MilestoneProgress {
+    pub total: usize,
+    pub done: usize,
+    /// Milestones past their due date that are not done.
+    pub overdue: usize,
+    /// Done milestones out of 100.
+    pub percent: usize,
+}
+
+impl MilestoneProgress {
+    /// Measure progress from the due dates and statuses of milestones.
+    pub fn new<'a>(milestones: impl Iterator<Item = (NaiveDate, &'a str)>) -> Self {
+        let today: NaiveDate = Utc::today().naive_utc();
+        let done: String = MilestoneStatus::Done.to_string();
+
+        let mut progress = MilestoneProgress::default();
+        for (due_date, status) in milestones {
+            progress.total += 1;
+            if status == done {
+                progress.done += 1;
+            } else if due_date < today {
+                progress.overdue += 1;
+            }
+        }
+
+        if progress.total > 0 {
+            progress.percent = 100 * progress.done / progress.total;
+        }
+        return progress;
+    }
+
+    /// Check if a milestone is overdue.
+    pub fn is_overdue(due_date: NaiveDate, status: &str) -> bool {
+        status != MilestoneStatus::Done.to_string() && due_date < Utc::today().naive_utc()
+    }
+}
+
+impl CreateMilestone {
+    /// Add a milestone to a project. Return its ID.
+    pub async fn execute(
+        project_id: i64,
+        fields: MilestoneFields,
+        created_by: uuid,
+    ) -> Result<Option<i64>, TelescopeError> {
+        send_query::<Self>(create_milestone::Variables {
+            project_id,
+            title: fields.title,
+            due_date: fields.due_date,
+            status: fields.status.to_string(),
+            created_by,
+            now: Utc::now(),
+        })
+        .await
+        .map(|response| {
+            response
+                .insert_project_milestones_one
+                .map(|obj| obj.milestone_id)
+        })
+    }
+}
+
+impl EditMilestone {
+    /// Change a milestone of a project. Return false if the project has no
+    /// such milestone.
+    pub async fn execute(
+        project_id: i64,
+        milestone_id: i64,
+        fields: MilestoneFields,
+    ) -> Result<bool, TelescopeError> {
+        send_query::<Self>(edit_milestone::Variables {
+            project_id,
+            milestone_id,
+            title: fields.title,
+            due_date: fields.due_date,
+            status: fields.status.to_string(),
+        })
+        .await
+        .map(|response| {
+            response
+                .update_project_milestones
+                .map_or(false, |r| r.affected_rows > 0)
+        })
+    }
+}
+
+impl DeleteMilestone {
+    /// Remove a milestone from a project. Return false if the project has no
+    /// such milestone.
+    pub async fn execute(project_id: i64, milestone_id: i64) -> Result<bool, TelescopeError> {
+        send_query::<Self>(delete_milestone::Variables {
+            project_id,
+            milestone_id,
+        })
+        .await
+        .map(|response| {
+            response
+                .delete_project_milestones
+                .map_or(false, |r| r.affected_rows > 0)
+        })
+    }
+}
+
+impl ProjectHealth {
+    /// Get the projects with members this semester, and the semester.
+    pub async fn get() -> Result<project_health::ResponseData, TelescopeError> {
+        send_query::<Self>(project_health::Variables {
+            now: Utc::today().naive_utc(),
+        })
+        .await
+    }
+}
@@ -1,3 +1,8 @@
 //! RCOS API interactions related to projects.
 
+pub mod constraints;
+pub mod join_requests;
+pub mod milestones;
+pub mod mutations;
+pub mod project;
 pub mod projects_page;
@@ -0,0 +1,92 @@
+//! GraphQL mutations to create, edit, and archive projects.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+use url::Url;
+
+/// Type representing GraphQL mutation to create a project.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/projects/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct CreateProject;
+
+/// Type representing GraphQL mutation to edit a project.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/projects/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct EditProject;
+
+/// Type representing GraphQL mutation to archive or restore a project.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/projects/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SetProjectArchived;
+
+/// The editable fields of a project.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProjectFields {
+    pub title: String,
+    /// Markdown shown on the project page.
+    pub description: String,
+    pub homepage_url: Option<String>,
+    pub repository_urls: Vec<Url>,
+    /// Technologies the project uses.
+    pub stack: Vec<String>,
+}
+
+impl CreateProject {
+    /// Create a project and return its ID.
+    pub async fn execute(fields: ProjectFields) -> Result<Option<i64>, TelescopeError> {
+        send_query::<Self>(create_project::Variables {
+            title: fields.title,
+            description: fields.description,
+            homepage_url: fields.homepage_url,
+            repository_urls: fields.repository_urls,
+            stack: fields.stack,
+        })
+        .await
+        .map(|data| data.insert_projects_one.map(|obj| obj.project_id))
+    }
+}
+
+impl EditProject {
+    /// Edit a project. Return the ID if the project exists.
+    pub async fn execute(
+        project_id: i64,
+        fields: ProjectFields,
+    ) -> Result<Option<i64>, TelescopeError> {
+        send_query::<Self>(edit_project::Variables {
+            project_id,
+            title: fields.title,
+            description: fields.description,
+            homepage_url: fields.homepage_url,
+            repository_urls: fields.repository_urls,
+            stack: fields.stack,
+        })
+        .await
+        .map(|data| data.update_projects_by_pk.map(|obj| obj.project_id))
+    }
+}
+
+impl SetProjectArchived {
+    /// Archive a project as of now, or restore it. Return the ID if the
+    /// project exists.
+    pub async fn execute(project_id: i64, archived: bool) -> Result<Option<i64>, TelescopeError> {
+        send_query::<Self>(set_project_archived::Variables {
+            project_id,
+            archived_at: archived.then(Utc::now),
+        })
+        .await
+        .map(|data| data.update_projects_by_pk.map(|obj| obj.project_id))
+    }
+}
@@ -0,0 +1,43 @@
+//! GraphQL query to get a single project.
+
+use crate::api::rcos::contributions::{sparkline_since, weekly_series};
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// GraphQL query to get a project by ID.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/projects/project.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct Project;
+
+use project::ProjectProjectsByPk;
+
+impl Project {
+    /// Get a project by ID.
+    pub async fn get(project_id: i64) -> Result<Option<ProjectProjectsByPk>, TelescopeError> {
+        Ok(send_query::<Self>(project::Variables {
+            project_id,
+            now: Utc::today().naive_utc(),
+            since: sparkline_since(),
+        })
+        .await?
+        .projects_by_pk)
+    }
+}
+
+impl ProjectProjectsByPk {
+    /// Get the weekly contribution totals for the project, or for one member
+    /// of the project if a user ID is given.
+    pub fn contribution_series(&self, user_id: Option<uuid>) -> Vec<i64> {
+        let weeks = self
+            .contribution_weeks
+            .iter()
+            .filter(|week| user_id.map(|id| week.user_id == id).unwrap_or(true))
+            .map(|week| (week.week_start, week.commits + week.pull_requests));
+        return weekly_series(weeks);
+    }
+}
@@ -5,7 +5,7 @@ use crate::error::TelescopeError;
 use chrono::Utc;
 
 /// Projects per page.
-const PER_PAGE: u32 = 20;
+pub const PER_PAGE: u32 = 20;
 
 /// GraphQL query to get projects with enrollments in an ongoing semester.
 #[derive(GraphQLQuery)]
@@ -25,6 +25,15 @@ pub struct CurrentProjects;
 )]
 pub struct AllProjects;
 
+/// GraphQL query to get the projects of one organization.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/projects/projects.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct OrganizationProjects;
+
 impl CurrentProjects {
     /// Get projects for a given page number (zero indexed).
     pub async fn get(
@@ -55,3 +64,20 @@ impl AllProjects {
         .await
     }
 }
+
+impl OrganizationProjects {
+    /// Get an organization's projects for a given page number (zero indexed).
+    pub async fn get(
+        org_id: String,
+        page: u32,
+        search: Option<String>,
+    ) -> Result<organization_projects::ResponseData, TelescopeError> {
+        send_query::<Self>(organization_projects::Variables {
+            limit: PER_PAGE as i64,
+            offset: (PER_PAGE * page) as i64,
+            search: resolve_search_string(search),
+            org: org_id,
+        })
+        .await
+    }
+}
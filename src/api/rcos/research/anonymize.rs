@@ -0,0 +1,320 @@
+//! Rules for anonymizing the research dataset.
+//!
+//! - Users and projects are replaced with pseudonyms: a keyed hash of their ID.
+//!   The key is random and made fresh for every export, so a pseudonym links
+//!   rows within one file but not across files, and cannot be reversed by
+//!   hashing known IDs.
+//! - Timestamps are cut down to the week of the semester they fall in.
+//! - Credits are put into ranges, and the quasi-identifiers (role, cohort,
+//!   credits, and how the member took part) are blanked out for members
+//!   whose combination of them is shared by fewer than [`MIN_CELL_SIZE`]
+//!   members that semester. See [`suppress_small_cells`].
+//! - Free text (names, titles, descriptions, status update answers) is never
+//!   part of the dataset, so there is nothing to scrub here.
+
+use super::ResearchRow;
+use crate::api::rcos::users::UserRole;
+use chrono::{DateTime, NaiveDate, Utc};
+use hmac::{Hmac, Mac, NewMac};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// How many bytes of random key each export is hashed with.
+const KEY_LENGTH: usize = 32;
+
+/// How many characters of the (hex) hash make up a pseudonym. 24 characters
+/// is 96 bits, plenty to keep pseudonyms from colliding.
+const PSEUDONYM_LENGTH: usize = 24;
+
+/// The fewest members that can share a combination of quasi-identifiers in a
+/// semester.
+pub const MIN_CELL_SIZE: usize = 5;
+
+/// What a pseudonym stands for. Hashed in with the ID so that a user and a
+/// project never share one.
+#[derive(Copy, Clone, Debug, Display)]
+pub enum Subject {
+    #[display(fmt = "user")]
+    User,
+    #[display(fmt = "project")]
+    Project,
+}
+
+/// Anonymizes one export.
+pub struct Anonymizer {
+    key: [u8; KEY_LENGTH],
+}
+
+impl Anonymizer {
+    /// Make an anonymizer with a fresh random key.
+    pub fn new() -> Self {
+        let mut key = [0u8; KEY_LENGTH];
+        OsRng.fill_bytes(&mut key);
+        return Anonymizer { key };
+    }
+
+    /// The pseudonym of a user or project. The same ID always gets the same
+    /// pseudonym from the same anonymizer.
+    pub fn pseudonym(&self, subject: Subject, id: impl Display) -> String {
+        // HMAC takes keys of any length.
+        let mut mac = Hmac::<Sha256>::new_varkey(&self.key).expect("Invalid HMAC key length");
+        mac.update(format!("{}:{}", subject, id).as_bytes());
+
+        let mut hash: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        hash.truncate(PSEUDONYM_LENGTH);
+        return hash;
+    }
+}
+
+/// The range of credits a member enrolled for. Exact counts would single out
+/// the few members taking an unusual number.
+pub fn credit_range(credits: i64) -> &'static str {
+    match credits {
+        i64::MIN..=0 => "0",
+        1..=3 => "1-3",
+        _ => "4+",
+    }
+}
+
+/// The combination of quasi-identifiers of a row, with its semester.
+type Cell<'a> = (
+    &'a str,
+    Option<UserRole>,
+    Option<i64>,
+    Option<&'static str>,
+    Option<bool>,
+    Option<bool>,
+    Option<bool>,
+);
+
+/// The cell a row is counted in.
+fn cell(row: &ResearchRow) -> Cell<'_> {
+    (
+        row.semester_id.as_str(),
+        row.role,
+        row.cohort,
+        row.credits,
+        row.is_for_pay,
+        row.is_project_lead,
+        row.is_coordinator,
+    )
+}
+
+/// Blank out the next quasi-identifier of a row, from the most to the least
+/// identifying. Return false if they are all blank already.
+fn suppress_next(row: &mut ResearchRow) -> bool {
+    if row.cohort.take().is_some() {
+        return true;
+    }
+    if row.credits.take().is_some() {
+        return true;
+    }
+    if row.is_for_pay.take().is_some() {
+        return true;
+    }
+    if row.is_project_lead.take().is_some() {
+        return true;
+    }
+    if row.is_coordinator.take().is_some() {
+        return true;
+    }
+    return row.role.take().is_some();
+}
+
+/// Blank out quasi-identifiers until every combination of them in a semester
+/// is shared by at least [`MIN_CELL_SIZE`] rows, or is blank. Rows in cells
+/// that are too small lose one more quasi-identifier each round, which merges
+/// them into larger cells.
+pub fn suppress_small_cells(rows: &mut [ResearchRow]) {
+    loop {
+        let mut sizes: HashMap<Cell, usize> = HashMap::new();
+        for row in rows.iter() {
+            *sizes.entry(cell(row)).or_default() += 1;
+        }
+        let small: Vec<bool> = rows
+            .iter()
+            .map(|row| sizes[&cell(row)] < MIN_CELL_SIZE)
+            .collect();
+
+        let mut changed: bool = false;
+        for (row, small) in rows.iter_mut().zip(small) {
+            if small && suppress_next(row) {
+                changed = true;
+            }
+        }
+        if !changed {
+            return;
+        }
+    }
+}
+
+/// The week of a semester a date falls in. The week the semester starts is
+/// week 1. Anything from before the semester starts is week 0, so early
+/// enrollments don't reveal how early they were.
+pub fn semester_week(semester_start: NaiveDate, date: NaiveDate) -> i64 {
+    let days: i64 = (date - semester_start).num_days();
+    if days < 0 {
+        return 0;
+    }
+    return days / 7 + 1;
+}
+
+/// The week of a semester a timestamp falls in. See [`semester_week`].
+pub fn semester_week_of(semester_start: NaiveDate, at: DateTime<Utc>) -> i64 {
+    semester_week(semester_start, at.naive_utc().date())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A row with some quasi-identifiers.
+    fn row(participant: &str, role: UserRole, cohort: i64, credits: i64) -> ResearchRow {
+        ResearchRow {
+            participant: participant.to_string(),
+            semester_id: "202209".to_string(),
+            project: None,
+            role: Some(role),
+            cohort: Some(cohort),
+            credits: Some(credit_range(credits)),
+            is_for_pay: Some(false),
+            is_project_lead: Some(false),
+            is_coordinator: Some(false),
+            enrolled_week: 1,
+            meetings_held: 10,
+            meetings_attended: 8,
+            first_attended_week: Some(1),
+            last_attended_week: Some(12),
+            status_updates_submitted: 10,
+            commits: 40,
+            pull_requests: 6,
+            active_weeks: 9,
+        }
+    }
+
+    #[test]
+    fn pseudonyms_are_stable() {
+        let anonymizer = Anonymizer::new();
+        let first: String = anonymizer.pseudonym(Subject::User, 42);
+        assert_eq!(first, anonymizer.pseudonym(Subject::User, 42));
+        assert_eq!(first.len(), PSEUDONYM_LENGTH);
+        assert_ne!(first, anonymizer.pseudonym(Subject::User, 43));
+    }
+
+    #[test]
+    fn pseudonyms_are_salted() {
+        let anonymizer = Anonymizer::new();
+        // Exports use separate keys, so pseudonyms do not link across files.
+        assert_ne!(
+            anonymizer.pseudonym(Subject::User, 42),
+            Anonymizer::new().pseudonym(Subject::User, 42)
+        );
+        // Users and projects with the same ID get separate pseudonyms.
+        assert_ne!(
+            anonymizer.pseudonym(Subject::User, 42),
+            anonymizer.pseudonym(Subject::Project, 42)
+        );
+    }
+
+    #[test]
+    fn weeks() {
+        let start = NaiveDate::from_ymd(2022, 9, 1);
+        assert_eq!(semester_week(start, NaiveDate::from_ymd(2022, 8, 20)), 0);
+        assert_eq!(semester_week(start, start), 1);
+        assert_eq!(semester_week(start, NaiveDate::from_ymd(2022, 9, 8)), 2);
+    }
+
+    #[test]
+    fn credit_ranges() {
+        assert_eq!(credit_range(0), "0");
+        assert_eq!(credit_range(1), "1-3");
+        assert_eq!(credit_range(3), "1-3");
+        assert_eq!(credit_range(4), "4+");
+    }
+
+    #[test]
+    fn large_cells_are_kept() {
+        let mut rows: Vec<ResearchRow> = (0..MIN_CELL_SIZE)
+            .map(|i| row(&i.to_string(), UserRole::Student, 2024, 4))
+            .collect();
+        suppress_small_cells(rows.as_mut_slice());
+        for row in rows {
+            assert_eq!(row.role, Some(UserRole::Student));
+            assert_eq!(row.cohort, Some(2024));
+            assert_eq!(row.credits, Some("4+"));
+        }
+    }
+
+    #[test]
+    fn small_cells_are_suppressed() {
+        let mut rows: Vec<ResearchRow> = (0..MIN_CELL_SIZE)
+            .map(|i| row(&i.to_string(), UserRole::Student, 2024, 4))
+            .collect();
+        // The only faculty member, and a student from a cohort of one.
+        rows.push(row("faculty", UserRole::Faculty, 1990, 0));
+        rows.push(row("student", UserRole::Student, 2019, 4));
+        suppress_small_cells(rows.as_mut_slice());
+
+        let faculty = rows
+            .iter()
+            .find(|row| row.participant == "faculty")
+            .unwrap();
+        assert_eq!(faculty.role, None);
+        assert_eq!(faculty.cohort, None);
+        assert_eq!(faculty.credits, None);
+        assert_eq!(faculty.is_coordinator, None);
+        // Counts are not quasi-identifiers, and are kept.
+        assert_eq!(faculty.commits, 40);
+
+        let student = rows
+            .iter()
+            .find(|row| row.participant == "student")
+            .unwrap();
+        assert_eq!(student.cohort, None);
+        assert_eq!(student.role, None);
+
+        // The large cell is untouched.
+        let kept: usize = rows
+            .iter()
+            .filter(|row| row.cohort == Some(2024) && row.role == Some(UserRole::Student))
+            .count();
+        assert_eq!(kept, MIN_CELL_SIZE);
+    }
+
+    #[test]
+    fn every_cell_is_large_or_blank() {
+        let mut rows: Vec<ResearchRow> = (0..23)
+            .map(|i| {
+                let role = if i % 7 == 0 {
+                    UserRole::ExternalMentor
+                } else {
+                    UserRole::Student
+                };
+                row(&i.to_string(), role, 2018 + i % 6, i % 5)
+            })
+            .collect();
+        suppress_small_cells(rows.as_mut_slice());
+
+        let mut sizes: HashMap<Cell, usize> = HashMap::new();
+        for row in rows.iter() {
+            *sizes.entry(cell(row)).or_default() += 1;
+        }
+        for (cell, size) in sizes {
+            let blank: bool = cell.1.is_none() && cell.2.is_none() && cell.3.is_none();
+            assert!(
+                size >= MIN_CELL_SIZE || blank,
+                "{:?} has {} rows",
+                cell,
+                size
+            );
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Anonymized dataset of program participation for outcome research.
+//!
+//! One row per enrollment: what the member signed up for, how many meetings
+//! they attended, how many status updates they submitted, and how much they
+//! contributed to project repositories that semester. See [`anonymize`] for
+//! how members and projects are kept from being identified.
+
+use crate::api::rcos::users::UserRole;
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::{NaiveDate, Utc};
+use std::collections::HashMap;
+
+pub mod anonymize;
+
+use self::anonymize::{
+    credit_range, semester_week, semester_week_of, suppress_small_cells, Anonymizer, Subject,
+};
+
+/// GraphQL query for everything the research dataset is built from.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/research/dataset.graphql",
+    response_derives = "Debug"
+)]
+pub struct ResearchDataset;
+
+use self::research_dataset::{ResponseData, Variables};
+
+/// One row of the research dataset.
+#[derive(Clone, Debug, Serialize)]
+pub struct ResearchRow {
+    /// Pseudonym of the member.
+    pub participant: String,
+    pub semester_id: String,
+    /// Pseudonym of the member's project, if they had one.
+    pub project: Option<String>,
+    /// The quasi-identifiers from here to `is_coordinator` are blank if
+    /// they were [suppressed](anonymize::suppress_small_cells).
+    pub role: Option<UserRole>,
+    /// The year the member entered the university, if they said.
+    pub cohort: Option<i64>,
+    /// The [range](anonymize::credit_range) of credits enrolled for.
+    pub credits: Option<&'static str>,
+    pub is_for_pay: Option<bool>,
+    pub is_project_lead: Option<bool>,
+    pub is_coordinator: Option<bool>,
+    /// Week of the semester the member enrolled in.
+    pub enrolled_week: i64,
+    /// Meetings held that semester.
+    pub meetings_held: i64,
+    pub meetings_attended: i64,
+    /// Week of the semester of the first and last meeting the member attended.
+    pub first_attended_week: Option<i64>,
+    pub last_attended_week: Option<i64>,
+    pub status_updates_submitted: i64,
+    pub commits: i64,
+    pub pull_requests: i64,
+    /// Weeks with at least one commit or pull request.
+    pub active_weeks: i64,
+}
+
+/// What a member did in one semester, before it is put into a row.
+#[derive(Default)]
+struct Participation {
+    meetings_attended: i64,
+    first_attended_week: Option<i64>,
+    last_attended_week: Option<i64>,
+    status_updates_submitted: i64,
+    commits: i64,
+    pull_requests: i64,
+    active_weeks: i64,
+}
+
+impl ResearchDataset {
+    /// Build the dataset for every semester that has started, oldest first.
+    /// Pseudonyms come from the given anonymizer.
+    pub async fn get(anonymizer: &Anonymizer) -> Result<Vec<ResearchRow>, TelescopeError> {
+        let data: ResponseData = send_query::<Self>(Variables {
+            now: Utc::today().naive_utc(),
+        })
+        .await?;
+
+        // Start and end dates and meeting counts of each semester.
+        let semesters: Vec<(String, NaiveDate, NaiveDate, i64)> = data
+            .semesters
+            .into_iter()
+            .map(|semester| {
+                (
+                    semester.semester_id,
+                    semester.start_date,
+                    semester.end_date,
+                    semester.meetings.aggregate.map_or(0, |agg| agg.count),
+                )
+            })
+            .collect();
+        let starts: HashMap<&str, NaiveDate> = semesters
+            .iter()
+            .map(|(id, start, _, _)| (id.as_str(), *start))
+            .collect();
+
+        let mut participation: HashMap<(uuid, &str), Participation> = HashMap::new();
+
+        for attendance in data.meeting_attendances.iter() {
+            let semester_id: &str = attendance.meeting.semester_id.as_str();
+            let start: NaiveDate = match starts.get(semester_id) {
+                Some(start) => *start,
+                None => continue,
+            };
+            let week: i64 = semester_week_of(start, attendance.meeting.start_date_time);
+            let entry = participation
+                .entry((attendance.user_id, semester_id))
+                .or_default();
+            entry.meetings_attended += 1;
+            entry.first_attended_week = Some(
+                entry
+                    .first_attended_week
+                    .map_or(week, |first| first.min(week)),
+            );
+            entry.last_attended_week =
+                Some(entry.last_attended_week.map_or(week, |last| last.max(week)));
+        }
+
+        for submission in data.status_update_submissions.iter() {
+            let semester_id: &str = submission.status_update.semester_id.as_str();
+            if starts.contains_key(semester_id) {
+                participation
+                    .entry((submission.user_id, semester_id))
+                    .or_default()
+                    .status_updates_submitted += 1;
+            }
+        }
+
+        for week in data.contribution_weeks.iter() {
+            // Contribution weeks count toward the semester they start in.
+            let semester = semesters
+                .iter()
+                .find(|(_, start, end, _)| *start <= week.week_start && week.week_start <= *end);
+            if let Some((semester_id, _, _, _)) = semester {
+                let entry = participation
+                    .entry((week.user_id, semester_id.as_str()))
+                    .or_default();
+                entry.commits += week.commits;
+                entry.pull_requests += week.pull_requests;
+                if week.commits + week.pull_requests > 0 {
+                    entry.active_weeks += 1;
+                }
+            }
+        }
+
+        let mut rows: Vec<ResearchRow> = Vec::with_capacity(data.enrollments.len());
+        for (semester_id, start, _, meetings_held) in semesters.iter() {
+            for enrollment in data
+                .enrollments
+                .iter()
+                .filter(|enrollment| enrollment.semester_id == *semester_id)
+            {
+                let done = participation
+                    .remove(&(enrollment.user_id, semester_id.as_str()))
+                    .unwrap_or_default();
+
+                rows.push(ResearchRow {
+                    participant: anonymizer.pseudonym(Subject::User, enrollment.user_id),
+                    semester_id: semester_id.clone(),
+                    project: enrollment
+                        .project_id
+                        .map(|id| anonymizer.pseudonym(Subject::Project, id)),
+                    role: Some(enrollment.user.role),
+                    cohort: enrollment.user.cohort,
+                    credits: Some(credit_range(enrollment.credits)),
+                    is_for_pay: Some(enrollment.is_for_pay),
+                    is_project_lead: Some(enrollment.is_project_lead),
+                    is_coordinator: Some(enrollment.is_coordinator),
+                    enrolled_week: semester_week(*start, enrollment.created_at.naive_utc().date()),
+                    meetings_held: *meetings_held,
+                    meetings_attended: done.meetings_attended,
+                    first_attended_week: done.first_attended_week,
+                    last_attended_week: done.last_attended_week,
+                    status_updates_submitted: done.status_updates_submitted,
+                    commits: done.commits,
+                    pull_requests: done.pull_requests,
+                    active_weeks: done.active_weeks,
+                });
+            }
+        }
+
+        suppress_small_cells(rows.as_mut_slice());
+
+        // Order by pseudonym within each semester, so the order of rows says
+        // nothing about who enrolled first.
+        rows.sort_by(|a, b| {
+            (a.semester_id.as_str(), a.participant.as_str())
+                .cmp(&(b.semester_id.as_str(), b.participant.as_str()))
+        });
+        return Ok(rows);
+    }
+}
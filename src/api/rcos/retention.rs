@@ -0,0 +1,200 @@
+//! Data retention rules.
+//!
+//! What a run of the retention job would remove is worked out as a
+//! [`RetentionPlan`] first. The admin panel shows the plan, and the job carries
+//! it out unless it is in dry-run mode.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::env::RetentionConfig;
+use crate::error::TelescopeError;
+use chrono::{DateTime, Duration, Utc};
+
+/// Type representing GraphQL query to count attendance older than the
+/// retention period.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/retention.graphql",
+    response_derives = "Debug"
+)]
+pub struct ExpiredAttendance;
+
+/// Type representing GraphQL mutation to delete attendance older than the
+/// retention period.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/retention.graphql",
+    response_derives = "Debug"
+)]
+pub struct DeleteExpiredAttendance;
+
+/// Type representing GraphQL query to get accounts waiting to be anonymized.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/retention.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct WithdrawnAccounts;
+
+/// Type representing GraphQL mutation to mark an account withdrawn.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/retention.graphql",
+    response_derives = "Debug"
+)]
+pub struct MarkWithdrawn;
+
+/// Type representing GraphQL mutation to take back a withdrawal.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/retention.graphql",
+    response_derives = "Debug"
+)]
+pub struct UnmarkWithdrawn;
+
+/// Type representing GraphQL mutation to anonymize an account.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/retention.graphql",
+    response_derives = "Debug"
+)]
+pub struct AnonymizeUser;
+
+/// An account marked withdrawn and not anonymized yet.
+pub type WithdrawnAccount = withdrawn_accounts::WithdrawnAccountsUsers;
+
+/// What a run of the retention job would remove.
+#[derive(Clone, Debug, Serialize)]
+pub struct RetentionPlan {
+    /// Attendance of meetings that started before this is deleted. None if the
+    /// attendance rule is off.
+    pub attendance_cutoff: Option<DateTime<Utc>>,
+    /// Attendance records that would be deleted.
+    pub attendance_records: i64,
+    /// Excused absences that would be deleted.
+    pub excused_absences: i64,
+    /// Accounts withdrawn before this are anonymized. None if the withdrawal
+    /// rule is off.
+    pub withdrawal_cutoff: Option<DateTime<Utc>>,
+    /// Accounts that would be anonymized.
+    pub to_anonymize: Vec<WithdrawnAccount>,
+    /// Withdrawn accounts that are not due to be anonymized yet.
+    pub waiting: Vec<WithdrawnAccount>,
+}
+
+impl RetentionPlan {
+    /// Work out what the retention rules would remove if enforced now.
+    pub async fn get(rules: &RetentionConfig) -> Result<Self, TelescopeError> {
+        let now: DateTime<Utc> = Utc::now();
+        let mut plan = RetentionPlan {
+            // Years are counted as 365 days. A leap day more or less does not
+            // matter for a retention period.
+            attendance_cutoff: rules
+                .attendance_years
+                .map(|years| now - Duration::days(365 * years as i64)),
+            attendance_records: 0,
+            excused_absences: 0,
+            withdrawal_cutoff: rules
+                .anonymize_withdrawn_days
+                .map(|days| now - Duration::days(days as i64)),
+            to_anonymize: Vec::new(),
+            waiting: Vec::new(),
+        };
+
+        if let Some(before) = plan.attendance_cutoff {
+            let expired =
+                send_query::<ExpiredAttendance>(expired_attendance::Variables { before }).await?;
+            plan.attendance_records = expired
+                .meeting_attendances_aggregate
+                .aggregate
+                .map_or(0, |agg| agg.count);
+            plan.excused_absences = expired
+                .excused_absences_aggregate
+                .aggregate
+                .map_or(0, |agg| agg.count);
+        }
+
+        let withdrawn = send_query::<WithdrawnAccounts>(withdrawn_accounts::Variables {})
+            .await?
+            .users;
+        for account in withdrawn {
+            let due: bool = match (plan.withdrawal_cutoff, account.withdrawn_at) {
+                (Some(cutoff), Some(withdrawn_at)) => withdrawn_at < cutoff,
+                _ => false,
+            };
+            if due {
+                plan.to_anonymize.push(account);
+            } else {
+                plan.waiting.push(account);
+            }
+        }
+
+        return Ok(plan);
+    }
+
+    /// Whether a run would remove anything.
+    pub fn is_empty(&self) -> bool {
+        self.attendance_records == 0 && self.excused_absences == 0 && self.to_anonymize.is_empty()
+    }
+}
+
+impl DeleteExpiredAttendance {
+    /// Delete attendance records and excused absences of meetings that started
+    /// before a time. Return how many of each were deleted.
+    pub async fn execute(before: DateTime<Utc>) -> Result<(i64, i64), TelescopeError> {
+        let deleted = send_query::<Self>(delete_expired_attendance::Variables { before }).await?;
+        return Ok((
+            deleted
+                .delete_meeting_attendances
+                .map_or(0, |rows| rows.affected_rows),
+            deleted
+                .delete_excused_absences
+                .map_or(0, |rows| rows.affected_rows),
+        ));
+    }
+}
+
+impl MarkWithdrawn {
+    /// Mark an account withdrawn. Return false if there is no account by this
+    /// ID that has not been anonymized already.
+    pub async fn execute(user_id: uuid) -> Result<bool, TelescopeError> {
+        send_query::<Self>(mark_withdrawn::Variables {
+            user_id,
+            now: Utc::now(),
+        })
+        .await
+        .map(|data| {
+            data.update_users
+                .map_or(false, |rows| rows.affected_rows > 0)
+        })
+    }
+}
+
+impl UnmarkWithdrawn {
+    /// Take back the withdrawal of an account that has not been anonymized.
+    pub async fn execute(user_id: uuid) -> Result<bool, TelescopeError> {
+        send_query::<Self>(unmark_withdrawn::Variables { user_id })
+            .await
+            .map(|data| {
+                data.update_users
+                    .map_or(false, |rows| rows.affected_rows > 0)
+            })
+    }
+}
+
+impl AnonymizeUser {
+    /// Anonymize an account.
+    pub async fn execute(user_id: uuid) -> Result<(), TelescopeError> {
+        send_query::<Self>(anonymize_user::Variables {
+            user_id,
+            now: Utc::now(),
+        })
+        .await?;
+        return Ok(());
+    }
+}
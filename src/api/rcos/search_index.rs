@@ -0,0 +1,21 @@
+//! GraphQL query for the documents in the full-text search index.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+
+/// Type representing GraphQL query to get every meeting and status update
+/// submission to index for search.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/search/documents.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SearchDocuments;
+
+impl SearchDocuments {
+    /// Get every meeting and status update submission.
+    pub async fn get() -> Result<search_documents::ResponseData, TelescopeError> {
+        send_query::<Self>(search_documents::Variables {}).await
+    }
+}
@@ -0,0 +1,135 @@
+//! Inserts used to fill a development database with fake data. Nothing else in
+//! Telescope creates projects, small groups, or enrollments, so these are kept
+//! together here rather than spread across the modules that read them.
+
+use crate::api::rcos::prelude::*;
+use crate::api::rcos::send_query;
+use crate::error::TelescopeError;
+use url::Url;
+
+/// Create a project.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/seed.graphql"
+)]
+pub struct SeedProject;
+
+/// Create a small group.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/seed.graphql"
+)]
+pub struct SeedSmallGroup;
+
+/// Add a project to a small group.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/seed.graphql"
+)]
+pub struct SeedSmallGroupProject;
+
+/// Add a mentor to a small group.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/seed.graphql"
+)]
+pub struct SeedSmallGroupMentor;
+
+/// Enroll a user in a semester.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/seed.graphql"
+)]
+pub struct SeedEnrollment;
+
+impl SeedProject {
+    /// Create a project and return its ID.
+    pub async fn execute(
+        title: String,
+        description: String,
+        stack: Vec<String>,
+        repository_urls: Vec<Url>,
+    ) -> Result<i64, TelescopeError> {
+        send_query::<Self>(seed_project::Variables {
+            title,
+            description,
+            stack,
+            repository_urls,
+        })
+        .await?
+        .insert_projects_one
+        .map(|project| project.project_id)
+        .ok_or(TelescopeError::ise("Project was not created."))
+    }
+}
+
+impl SeedSmallGroup {
+    /// Create a small group and return its ID.
+    pub async fn execute(
+        semester_id: String,
+        title: String,
+        location: Option<String>,
+    ) -> Result<i64, TelescopeError> {
+        send_query::<Self>(seed_small_group::Variables {
+            semester_id,
+            title,
+            location,
+        })
+        .await?
+        .insert_small_groups_one
+        .map(|group| group.small_group_id)
+        .ok_or(TelescopeError::ise("Small group was not created."))
+    }
+}
+
+impl SeedSmallGroupProject {
+    /// Add a project to a small group.
+    pub async fn execute(small_group_id: i64, project_id: i64) -> Result<(), TelescopeError> {
+        send_query::<Self>(seed_small_group_project::Variables {
+            small_group_id,
+            project_id,
+        })
+        .await
+        .map(|_| ())
+    }
+}
+
+impl SeedSmallGroupMentor {
+    /// Add a mentor to a small group.
+    pub async fn execute(small_group_id: i64, user_id: uuid) -> Result<(), TelescopeError> {
+        send_query::<Self>(seed_small_group_mentor::Variables {
+            small_group_id,
+            user_id,
+        })
+        .await
+        .map(|_| ())
+    }
+}
+
+impl SeedEnrollment {
+    /// Enroll a user in a semester, optionally on a project.
+    pub async fn execute(
+        semester_id: String,
+        user_id: uuid,
+        project_id: Option<i64>,
+        credits: i64,
+        is_project_lead: bool,
+        is_coordinator: bool,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(seed_enrollment::Variables {
+            semester_id,
+            user_id,
+            project_id,
+            credits,
+            is_project_lead,
+            is_coordinator,
+        })
+        .await
+        .map(|_| ())
+    }
+}
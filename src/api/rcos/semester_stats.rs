@@ -0,0 +1,53 @@
+//! Organization-wide statistics for each semester.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::{NaiveDate, Utc};
+
+/// GraphQL query for membership, project, and meeting counts per semester.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/stats/semesters.graphql",
+    response_derives = "Debug"
+)]
+pub struct SemesterStatistics;
+
+use self::semester_statistics::{ResponseData, Variables};
+
+/// The counts for one semester.
+#[derive(Clone, Debug, Serialize)]
+pub struct SemesterCounts {
+    pub semester_id: String,
+    pub title: String,
+    pub start_date: NaiveDate,
+    /// Users enrolled in the semester.
+    pub members: i64,
+    /// Projects with at least one enrolled member.
+    pub projects: i64,
+    /// Meetings that were not left as drafts.
+    pub meetings: i64,
+}
+
+impl SemesterStatistics {
+    /// Get the counts for every semester that has started, oldest first.
+    pub async fn get() -> Result<Vec<SemesterCounts>, TelescopeError> {
+        let data: ResponseData = send_query::<Self>(Variables {
+            now: Utc::today().naive_utc(),
+        })
+        .await?;
+
+        return Ok(data
+            .semesters
+            .into_iter()
+            .map(|semester| SemesterCounts {
+                semester_id: semester.semester_id,
+                title: semester.title,
+                start_date: semester.start_date,
+                members: semester.members.aggregate.map_or(0, |agg| agg.count),
+                projects: semester.projects.aggregate.map_or(0, |agg| agg.count),
+                meetings: semester.meetings.aggregate.map_or(0, |agg| agg.count),
+            })
+            .collect());
+    }
+}
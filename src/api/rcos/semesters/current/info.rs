@@ -1,6 +1,8 @@
 //! GraphQL query for info about the current semester.
 
-use crate::api::rcos::prelude::*;
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
 
 /// Type representing GraphQL query for current semester data.
 #[derive(GraphQLQuery)]
@@ -10,3 +12,15 @@ use crate::api::rcos::prelude::*;
     response_derives = "Debug,Clone,Serialize"
 )]
 pub struct CurrentSemesters;
+
+impl CurrentSemesters {
+    /// Get the semesters that contain today's date.
+    pub async fn get() -> Result<Vec<current_semesters::CurrentSemestersSemesters>, TelescopeError>
+    {
+        Ok(send_query::<Self>(current_semesters::Variables {
+            now: Utc::today().naive_utc(),
+        })
+        .await?
+        .semesters)
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::api::rcos::{prelude::*, send_query};
 use crate::error::TelescopeError;
+use crate::templates::pagination::PaginationParameter;
 
 /// Type representing GraphQL query for current semester data.
 #[derive(GraphQLQuery)]
@@ -16,11 +17,11 @@ pub struct Semesters;
 pub const PER_PAGE: u32 = 20;
 
 impl Semesters {
-    /// Get semester records (using a zero indexed page number)
-    pub async fn get(page_num: u32) -> Result<semesters::ResponseData, TelescopeError> {
+    /// Get a page of semester records.
+    pub async fn get(page: PaginationParameter) -> Result<semesters::ResponseData, TelescopeError> {
         send_query::<Self>(semesters::Variables {
-            limit: PER_PAGE as i64,
-            offset: (page_num * PER_PAGE) as i64,
+            limit: page.page_size as i64,
+            offset: page.offset as i64,
         })
         .await
     }
@@ -16,9 +16,9 @@ pub struct Semester;
 impl Semester {
     /// Get a semester record by ID.
     pub async fn get_by_id(
-        id: String,
+        id: SemesterId,
     ) -> Result<Option<semester::SemesterSemestersByPk>, TelescopeError> {
-        send_query::<Self>(semester::Variables { id })
+        send_query::<Self>(semester::Variables { id: id.into() })
             .await
             .map(|data| data.semesters_by_pk)
     }
@@ -0,0 +1,135 @@
+//! Grading report data for a semester.
+
+use crate::api::rcos::attendance::policy::{Policy, Standing};
+use crate::api::rcos::users::display::UserDisplay;
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use std::collections::HashMap;
+
+/// An enrollment in the grading report.
+pub type ReportEnrollment = grading_report::GradingReportEnrollments;
+
+/// Type representing GraphQL query to get a semester's grading report.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/semesters/grading_report.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct GradingReport;
+
+/// The enrollments of one small group.
+#[derive(Clone, Debug)]
+pub struct ReportGroup {
+    pub title: String,
+    /// Display names of the group's mentors.
+    pub mentors: Vec<String>,
+    /// How many projects are in the group.
+    pub project_count: usize,
+    pub enrollments: Vec<ReportEnrollment>,
+}
+
+/// A semester's enrollments sorted into small groups.
+#[derive(Clone, Debug)]
+pub struct GroupedReport {
+    pub semester_id: SemesterId,
+    pub semester_title: String,
+    /// The semester's attendance policy, if it has one.
+    pub policy: Option<Policy>,
+    pub groups: Vec<ReportGroup>,
+    /// Students without a project, or whose project is not in a small group.
+    pub unassigned: Vec<ReportEnrollment>,
+}
+
+impl GroupedReport {
+    /// Every enrollment in the semester.
+    pub fn all(&self) -> impl Iterator<Item = &ReportEnrollment> + Clone {
+        self.groups
+            .iter()
+            .flat_map(|group| group.enrollments.iter())
+            .chain(self.unassigned.iter())
+    }
+
+    /// A student's attendance measured against the semester's policy.
+    pub fn standing(&self, enrollment: &ReportEnrollment) -> Standing {
+        let user = &enrollment.user;
+        Standing::new(
+            self.policy,
+            user.attended.aggregate.as_ref().map_or(0, |a| a.count),
+            user.excused.aggregate.as_ref().map_or(0, |a| a.count),
+        )
+    }
+}
+
+impl GradingReport {
+    /// Get the small groups and enrollments of a semester. The semester is
+    /// `None` if it does not exist.
+    pub async fn get(
+        semester_id: SemesterId,
+    ) -> Result<grading_report::ResponseData, TelescopeError> {
+        send_query::<Self>(grading_report::Variables {
+            semester_id: semester_id.into(),
+        })
+        .await
+    }
+
+    /// Get a semester's enrollments sorted into small groups by their project.
+    /// Return `None` if the semester does not exist.
+    pub async fn grouped(semester_id: SemesterId) -> Result<Option<GroupedReport>, TelescopeError> {
+        let report = Self::get(semester_id).await?;
+        let semester = match report.semester {
+            Some(semester) => semester,
+            None => return Ok(None),
+        };
+
+        let mut group_of_project: HashMap<i64, usize> = HashMap::new();
+        let mut groups: Vec<ReportGroup> = Vec::with_capacity(report.small_groups.len());
+        for (index, group) in report.small_groups.into_iter().enumerate() {
+            for project in group.small_group_projects.iter() {
+                group_of_project.entry(project.project_id).or_insert(index);
+            }
+
+            groups.push(ReportGroup {
+                title: group.title,
+                mentors: group
+                    .mentors
+                    .into_iter()
+                    .map(|mentor| {
+                        let user = mentor.user;
+                        UserDisplay::new(
+                            user.first_name,
+                            user.last_name,
+                            user.preferred_name,
+                            user.pronouns,
+                        )
+                        .name()
+                    })
+                    .collect(),
+                project_count: group.small_group_projects.len(),
+                enrollments: Vec::new(),
+            });
+        }
+
+        let mut unassigned: Vec<ReportEnrollment> = Vec::new();
+        for enrollment in report.enrollments {
+            match enrollment
+                .project_id
+                .and_then(|id| group_of_project.get(&id))
+            {
+                Some(index) => groups[*index].enrollments.push(enrollment),
+                None => unassigned.push(enrollment),
+            }
+        }
+
+        return Ok(Some(GroupedReport {
+            semester_id: SemesterId(semester.semester_id),
+            semester_title: semester.title,
+            policy: semester.attendance_policy.map(|policy| Policy {
+                required_count: policy.required_count,
+                max_excused: policy.max_excused,
+            }),
+            groups,
+            unassigned,
+        }));
+    }
+}
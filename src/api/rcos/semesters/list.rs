@@ -0,0 +1,46 @@
+//! GraphQL query to list every semester.
+
+use crate::api::rcos::prelude::*;
+use crate::api::rcos::send_query;
+use crate::error::TelescopeError;
+use chrono::NaiveDate;
+
+/// Type representing GraphQL query to list every semester.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/semesters/list.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SemesterList;
+
+use self::semester_list::SemesterListSemesters;
+
+impl SemesterList {
+    /// Get every semester, oldest first.
+    pub async fn get() -> Result<Vec<SemesterListSemesters>, TelescopeError> {
+        send_query::<Self>(semester_list::Variables {})
+            .await
+            .map(|data| data.semesters)
+    }
+
+    /// Pick a semester from a list sorted oldest first: the one with the
+    /// given ID if there is one, otherwise the semester containing `today`,
+    /// otherwise the latest one that has started. Returns the index of the
+    /// semester in the list.
+    pub fn pick(
+        semesters: &[SemesterListSemesters],
+        semester_id: Option<&str>,
+        today: NaiveDate,
+    ) -> Option<usize> {
+        if let Some(id) = semester_id {
+            return semesters.iter().position(|s| s.semester_id == id);
+        }
+
+        semesters
+            .iter()
+            .position(|s| s.start_date <= today && today <= s.end_date)
+            .or_else(|| semesters.iter().rposition(|s| s.start_date <= today))
+            .or_else(|| semesters.len().checked_sub(1))
+    }
+}
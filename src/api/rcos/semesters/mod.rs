@@ -3,4 +3,6 @@
 pub mod current;
 pub mod get;
 pub mod get_by_id;
+pub mod grading_report;
+pub mod list;
 pub mod mutations;
@@ -0,0 +1,261 @@
+//! The skill taxonomy, and matching members to projects by skill.
+//!
+//! Coordinators manage the list of skills. Members tag themselves with the
+//! skills they have, and project leads tag their projects with the skills
+//! they need. Projects are suggested to a member by how many of the project's
+//! needed skills the member has.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+use std::collections::HashSet;
+
+/// Type representing GraphQL query to get every skill.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/skills.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AllSkills;
+
+/// Type representing GraphQL mutation to add a skill.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/skills.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct CreateSkill;
+
+/// Type representing GraphQL mutation to remove a skill.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/skills.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct DeleteSkill;
+
+/// Type representing GraphQL mutation to untag a user from skills.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/skills.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ClearUserSkills;
+
+/// Type representing GraphQL mutation to tag a user with a skill.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/skills.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AddUserSkill;
+
+/// Type representing GraphQL mutation to untag a project from skills.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/skills.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ClearProjectSkills;
+
+/// Type representing GraphQL mutation to tag a project with a skill.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/skills.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AddProjectSkill;
+
+/// Type representing GraphQL query to get a user's skills and the projects
+/// that need skills.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/skills.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct Matchmaking;
+
+/// The kinds of skill in the taxonomy.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+pub enum SkillCategory {
+    #[display(fmt = "language")]
+    Language,
+    #[display(fmt = "framework")]
+    Framework,
+    #[display(fmt = "domain")]
+    Domain,
+}
+
+impl SkillCategory {
+    /// Every category, in the order they are shown.
+    pub const ALL: [SkillCategory; 3] = [
+        SkillCategory::Language,
+        SkillCategory::Framework,
+        SkillCategory::Domain,
+    ];
+
+    /// The heading for skills of this category.
+    pub fn label(self) -> &'static str {
+        match self {
+            SkillCategory::Language => "Languages",
+            SkillCategory::Framework => "Frameworks",
+            SkillCategory::Domain => "Domains",
+        }
+    }
+}
+
+/// How well a project's needed skills overlap with a member's skills.
+#[derive(Clone, Debug, Serialize)]
+pub struct ProjectMatch {
+    pub project_id: i64,
+    pub title: String,
+    pub description: String,
+    /// Needed skills the member has.
+    pub matched: Vec<String>,
+    /// Needed skills the member does not have.
+    pub missing: Vec<String>,
+    /// How many skills the project needs.
+    pub needed: usize,
+    /// Needed skills the member has out of 100.
+    pub percent: usize,
+}
+
+impl AllSkills {
+    /// Get every skill, ordered by category and name.
+    pub async fn get() -> Result<Vec<all_skills::AllSkillsSkills>, TelescopeError> {
+        send_query::<Self>(all_skills::Variables {})
+            .await
+            .map(|response| response.skills)
+    }
+}
+
+impl CreateSkill {
+    /// Add a skill to the taxonomy. Return its ID, or none if a skill by this
+    /// name already exists.
+    pub async fn execute(
+        name: String,
+        category: SkillCategory,
+    ) -> Result<Option<i64>, TelescopeError> {
+        send_query::<Self>(create_skill::Variables {
+            name,
+            category: category.to_string(),
+            now: Utc::now(),
+        })
+        .await
+        .map(|response| response.insert_skills_one.map(|obj| obj.skill_id))
+    }
+}
+
+impl DeleteSkill {
+    /// Remove a skill and untag it from every user and project. Return false
+    /// if there is no such skill.
+    pub async fn execute(skill_id: i64) -> Result<bool, TelescopeError> {
+        send_query::<Self>(delete_skill::Variables { skill_id })
+            .await
+            .map(|response| response.delete_skills_by_pk.is_some())
+    }
+}
+
+impl ClearUserSkills {
+    /// Set the skills a user is tagged with, replacing any they had before.
+    /// The skills must exist.
+    pub async fn set(user_id: uuid, skill_ids: Vec<i64>) -> Result<(), TelescopeError> {
+        send_query::<Self>(clear_user_skills::Variables {
+            user_id,
+            keep: skill_ids.clone(),
+        })
+        .await?;
+
+        for skill_id in skill_ids {
+            send_query::<AddUserSkill>(add_user_skill::Variables {
+                user_id,
+                skill_id,
+                now: Utc::now(),
+            })
+            .await?;
+        }
+
+        return Ok(());
+    }
+}
+
+impl ClearProjectSkills {
+    /// Set the skills a project needs, replacing any it had before. The skills
+    /// must exist.
+    pub async fn set(project_id: i64, skill_ids: Vec<i64>) -> Result<(), TelescopeError> {
+        send_query::<Self>(clear_project_skills::Variables {
+            project_id,
+            keep: skill_ids.clone(),
+        })
+        .await?;
+
+        for skill_id in skill_ids {
+            send_query::<AddProjectSkill>(add_project_skill::Variables {
+                project_id,
+                skill_id,
+                now: Utc::now(),
+            })
+            .await?;
+        }
+
+        return Ok(());
+    }
+}
+
+impl Matchmaking {
+    /// Get the projects that need at least one of a user's skills, best match
+    /// first. Projects the user is already a member of this semester are left
+    /// out.
+    pub async fn get(user_id: uuid) -> Result<Vec<ProjectMatch>, TelescopeError> {
+        let data = send_query::<Self>(matchmaking::Variables {
+            user_id,
+            now: Utc::today().naive_utc(),
+        })
+        .await?;
+
+        let mine: HashSet<i64> = data.mine.iter().map(|skill| skill.skill_id).collect();
+        let mut matches: Vec<ProjectMatch> = data
+            .projects
+            .into_iter()
+            .map(|project| {
+                let (matched, missing): (Vec<_>, Vec<_>) = project
+                    .needed_skills
+                    .into_iter()
+                    .map(|row| row.skill)
+                    .partition(|skill| mine.contains(&skill.skill_id));
+
+                let needed: usize = matched.len() + missing.len();
+                ProjectMatch {
+                    project_id: project.project_id,
+                    title: project.title,
+                    description: project.description,
+                    needed,
+                    percent: 100 * matched.len() / needed.max(1),
+                    matched: matched.into_iter().map(|skill| skill.name).collect(),
+                    missing: missing.into_iter().map(|skill| skill.name).collect(),
+                }
+            })
+            .filter(|project| !project.matched.is_empty())
+            .collect();
+
+        // Most skills in common first. Between projects with as many in
+        // common, the one the member covers more of comes first.
+        matches.sort_by(|a, b| {
+            b.matched
+                .len()
+                .cmp(&a.matched.len())
+                .then(b.percent.cmp(&a.percent))
+                .then(a.title.cmp(&b.title))
+        });
+        return Ok(matches);
+    }
+}
@@ -0,0 +1,61 @@
+//! GraphQL queries to get sponsor records.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL query to get the currently active sponsors.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/sponsors/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct CurrentSponsors;
+
+/// Type representing GraphQL query to get all sponsor records.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/sponsors/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AllSponsors;
+
+/// Type representing GraphQL query to get a sponsor by ID.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/sponsors/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct Sponsor;
+
+impl CurrentSponsors {
+    /// Get the sponsors active today, in tier order.
+    pub async fn get() -> Result<Vec<current_sponsors::CurrentSponsorsSponsors>, TelescopeError> {
+        Ok(send_query::<Self>(current_sponsors::Variables {
+            now: Utc::today().naive_utc(),
+        })
+        .await?
+        .sponsors)
+    }
+}
+
+impl AllSponsors {
+    /// Get every sponsor record.
+    pub async fn get() -> Result<Vec<all_sponsors::AllSponsorsSponsors>, TelescopeError> {
+        Ok(send_query::<Self>(all_sponsors::Variables {})
+            .await?
+            .sponsors)
+    }
+}
+
+impl Sponsor {
+    /// Get a sponsor by ID.
+    pub async fn get(id: i64) -> Result<Option<sponsor::SponsorSponsorsByPk>, TelescopeError> {
+        Ok(send_query::<Self>(sponsor::Variables { id })
+            .await?
+            .sponsors_by_pk)
+    }
+}
@@ -0,0 +1,4 @@
+//! GraphQL queries and mutations on sponsor records.
+
+pub mod get;
+pub mod mutations;
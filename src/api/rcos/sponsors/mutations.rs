@@ -0,0 +1,113 @@
+//! GraphQL mutations to create, edit, and delete sponsor records.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::NaiveDate;
+
+/// Type representing GraphQL mutation to create a sponsor.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/sponsors/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct CreateSponsor;
+
+/// Type representing GraphQL mutation to edit a sponsor.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/sponsors/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct EditSponsor;
+
+/// Type representing GraphQL mutation to delete a sponsor.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/sponsors/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct DeleteSponsor;
+
+/// Type representing GraphQL mutation to replace a sponsor's logo.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/sponsors/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SetSponsorLogo;
+
+/// The editable fields of a sponsor record.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SponsorFields {
+    /// The name of the sponsor.
+    pub name: String,
+    /// A short blurb about the sponsorship.
+    pub description: Option<String>,
+    /// The URL of the sponsor's logo.
+    pub logo_url: String,
+    /// The sponsor's website.
+    pub website_url: Option<String>,
+    /// Sponsors are shown in tier order, lowest first.
+    pub tier: i64,
+    /// The first day the sponsor is shown.
+    pub start_date: NaiveDate,
+    /// The last day the sponsor is shown, if any.
+    pub end_date: Option<NaiveDate>,
+}
+
+impl CreateSponsor {
+    /// Create a sponsor record and return its ID.
+    pub async fn execute(fields: SponsorFields) -> Result<Option<i64>, TelescopeError> {
+        send_query::<Self>(create_sponsor::Variables {
+            name: fields.name,
+            description: fields.description,
+            logo_url: fields.logo_url,
+            website_url: fields.website_url,
+            tier: fields.tier,
+            start_date: fields.start_date,
+            end_date: fields.end_date,
+        })
+        .await
+        .map(|data| data.insert_sponsors_one.map(|obj| obj.sponsor_id))
+    }
+}
+
+impl EditSponsor {
+    /// Edit a sponsor record. Return the ID if the sponsor exists.
+    pub async fn execute(id: i64, fields: SponsorFields) -> Result<Option<i64>, TelescopeError> {
+        send_query::<Self>(edit_sponsor::Variables {
+            id,
+            name: fields.name,
+            description: fields.description,
+            logo_url: fields.logo_url,
+            website_url: fields.website_url,
+            tier: fields.tier,
+            start_date: fields.start_date,
+            end_date: fields.end_date,
+        })
+        .await
+        .map(|data| data.update_sponsors_by_pk.map(|obj| obj.sponsor_id))
+    }
+}
+
+impl DeleteSponsor {
+    /// Delete a sponsor record. Return the ID if the sponsor existed.
+    pub async fn execute(id: i64) -> Result<Option<i64>, TelescopeError> {
+        send_query::<Self>(delete_sponsor::Variables { id })
+            .await
+            .map(|data| data.delete_sponsors_by_pk.map(|obj| obj.sponsor_id))
+    }
+}
+
+impl SetSponsorLogo {
+    /// Replace a sponsor's logo. Return the ID if the sponsor exists.
+    pub async fn execute(id: i64, logo_url: String) -> Result<Option<i64>, TelescopeError> {
+        send_query::<Self>(set_sponsor_logo::Variables { id, logo_url })
+            .await
+            .map(|data| data.update_sponsors_by_pk.map(|obj| obj.sponsor_id))
+    }
+}
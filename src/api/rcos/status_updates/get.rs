@@ -0,0 +1,115 @@
+//! GraphQL queries to list status updates and browse submissions.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::{Local, Utc};
+
+/// Type representing GraphQL query to list this semester's status updates
+/// for a user.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/status_updates/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct StatusUpdateOverview;
+
+/// Type representing GraphQL query to get a status update and a user's
+/// submission to it.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/status_updates/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct StatusUpdateSubmission;
+
+/// Type representing GraphQL query to get a small group and the status
+/// updates of its semester.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/status_updates/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SmallGroupStatusUpdates;
+
+/// Type representing GraphQL query to get the submissions from a small group
+/// to a status update.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/status_updates/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SmallGroupSubmissions;
+
+impl StatusUpdateOverview {
+    /// Get the status updates that have opened this semester, with the
+    /// user's submissions. Small groups are the ones the user mentors, or all
+    /// of this semester's if `all_groups` is set.
+    pub async fn get(
+        user_id: uuid,
+        all_groups: bool,
+    ) -> Result<status_update_overview::ResponseData, TelescopeError> {
+        send_query::<Self>(status_update_overview::Variables {
+            user_id,
+            today: Local::today().naive_local(),
+            now: Utc::now(),
+            only_mentored: !all_groups,
+        })
+        .await
+    }
+}
+
+impl StatusUpdateSubmission {
+    /// Get a status update and a user's submission to it.
+    pub async fn get(
+        status_update_id: i64,
+        user_id: uuid,
+    ) -> Result<Option<status_update_submission::StatusUpdateSubmissionStatusUpdate>, TelescopeError>
+    {
+        Ok(send_query::<Self>(status_update_submission::Variables {
+            status_update_id,
+            user_id,
+        })
+        .await?
+        .status_update)
+    }
+}
+
+impl SmallGroupStatusUpdates {
+    /// Get a small group and the status updates of its semester that have
+    /// opened, newest first.
+    pub async fn get(
+        small_group_id: i64,
+    ) -> Result<Option<small_group_status_updates::SmallGroupStatusUpdatesSmallGroup>, TelescopeError>
+    {
+        Ok(send_query::<Self>(small_group_status_updates::Variables {
+            small_group_id,
+            now: Utc::now(),
+        })
+        .await?
+        .small_group)
+    }
+}
+
+impl SmallGroupSubmissions {
+    /// Get the submissions to a status update from members of a small group.
+    pub async fn get(
+        small_group_id: i64,
+        status_update_id: i64,
+        show_hidden: bool,
+    ) -> Result<
+        Vec<small_group_submissions::SmallGroupSubmissionsStatusUpdateSubmissions>,
+        TelescopeError,
+    > {
+        Ok(send_query::<Self>(small_group_submissions::Variables {
+            small_group_id,
+            status_update_id,
+            show_hidden,
+        })
+        .await?
+        .status_update_submissions)
+    }
+}
@@ -0,0 +1,5 @@
+//! GraphQL queries and mutations on weekly status updates and members'
+//! submissions to them.
+
+pub mod get;
+pub mod mutations;
@@ -0,0 +1,64 @@
+//! Queries for coordinator tasks.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::{Duration, Utc};
+
+/// How long finished tasks stay on the board.
+const DONE_VISIBLE_DAYS: i64 = 14;
+
+/// Type representing GraphQL query to get the task board.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/tasks/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct TaskBoard;
+
+/// Type representing GraphQL query to get a single task.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/tasks/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct TaskById;
+
+/// Type representing GraphQL query to get submissions that are waiting on review.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/tasks/get.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct PendingReviews;
+
+impl TaskBoard {
+    /// Get the task board, optionally only showing tasks assigned to one user.
+    pub async fn get(assignee: Option<uuid>) -> Result<task_board::ResponseData, TelescopeError> {
+        send_query::<Self>(task_board::Variables {
+            assignee,
+            now: Utc::today().naive_utc(),
+            done_since: Utc::now() - Duration::days(DONE_VISIBLE_DAYS),
+        })
+        .await
+    }
+}
+
+impl TaskById {
+    /// Get a task by its ID. The task is `None` if it does not exist.
+    pub async fn get(task_id: i64) -> Result<task_by_id::ResponseData, TelescopeError> {
+        send_query::<Self>(task_by_id::Variables { task_id }).await
+    }
+}
+
+impl PendingReviews {
+    /// Get the submissions in current semesters that have not been reviewed.
+    pub async fn get() -> Result<pending_reviews::ResponseData, TelescopeError> {
+        send_query::<Self>(pending_reviews::Variables {
+            now: Utc::today().naive_utc(),
+        })
+        .await
+    }
+}
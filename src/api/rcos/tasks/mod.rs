@@ -0,0 +1,16 @@
+//! Coordinator task board.
+
+pub mod get;
+pub mod mutations;
+
+/// The columns of the task board.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    #[display(fmt = "To Do")]
+    Todo,
+    #[display(fmt = "In Progress")]
+    InProgress,
+    #[display(fmt = "Done")]
+    Done,
+}
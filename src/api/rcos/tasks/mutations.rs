@@ -0,0 +1,129 @@
+//! Mutations for coordinator tasks.
+
+use crate::api::rcos::tasks::TaskStatus;
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL mutation to create a task by hand.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/tasks/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct CreateTask;
+
+/// Type representing GraphQL mutation to create a task for an event.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/tasks/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct CreateEventTask;
+
+/// Type representing GraphQL mutation to edit a task.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/tasks/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct EditTask;
+
+/// Type representing GraphQL mutation to move a task to another column.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/tasks/mutations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SetTaskStatus;
+
+/// The editable fields of a task.
+#[derive(Clone, Debug)]
+pub struct TaskFields {
+    pub title: String,
+    pub description: Option<String>,
+    pub assignee_id: Option<uuid>,
+    pub due_date: Option<date>,
+}
+
+impl CreateTask {
+    /// Create a task. Return its ID.
+    pub async fn execute(fields: TaskFields, created_by: uuid) -> Result<i64, TelescopeError> {
+        send_query::<Self>(create_task::Variables {
+            title: fields.title,
+            description: fields.description,
+            assignee_id: fields.assignee_id,
+            due_date: fields.due_date,
+            created_by,
+            now: Utc::now(),
+        })
+        .await?
+        .insert_coordinator_tasks_one
+        .map(|task| task.task_id)
+        .ok_or(TelescopeError::ise("Task was not created."))
+    }
+}
+
+impl CreateEventTask {
+    /// Create a task for an event identified by `source_key`. Return true if a
+    /// new task was created, or false if the event already has one.
+    pub async fn execute(
+        title: String,
+        description: Option<String>,
+        source: &str,
+        source_key: String,
+    ) -> Result<bool, TelescopeError> {
+        send_query::<Self>(create_event_task::Variables {
+            title,
+            description,
+            source: source.to_string(),
+            source_key,
+            now: Utc::now(),
+        })
+        .await
+        .map(|response| response.insert_coordinator_tasks_one.is_some())
+    }
+}
+
+impl EditTask {
+    /// Save changes to a task. Return its ID if it exists.
+    pub async fn execute(task_id: i64, fields: TaskFields) -> Result<Option<i64>, TelescopeError> {
+        send_query::<Self>(edit_task::Variables {
+            task_id,
+            title: fields.title,
+            description: fields.description,
+            assignee_id: fields.assignee_id,
+            due_date: fields.due_date,
+            now: Utc::now(),
+        })
+        .await
+        .map(|response| {
+            response
+                .update_coordinator_tasks_by_pk
+                .map(|task| task.task_id)
+        })
+    }
+}
+
+impl SetTaskStatus {
+    /// Move a task to another column. Return its ID if it exists.
+    pub async fn execute(task_id: i64, status: TaskStatus) -> Result<Option<i64>, TelescopeError> {
+        let now = Utc::now();
+        send_query::<Self>(set_task_status::Variables {
+            task_id,
+            status,
+            completed_at: Some(now).filter(|_| status == TaskStatus::Done),
+            now,
+        })
+        .await
+        .map(|response| {
+            response
+                .update_coordinator_tasks_by_pk
+                .map(|task| task.task_id)
+        })
+    }
+}
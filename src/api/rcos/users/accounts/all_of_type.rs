@@ -0,0 +1,31 @@
+//! Lookup every account linked on a given platform.
+
+// Namespacing
+use crate::api::rcos::prelude::*;
+use crate::api::rcos::users::UserAccountType as user_account;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/accounts/all_of_type.graphql"
+)]
+pub struct AllAccountsOfType;
+
+use crate::api::rcos::send_query;
+use crate::error::TelescopeError;
+use all_accounts_of_type::Variables;
+
+impl AllAccountsOfType {
+    /// Get the ID of every account linked on a platform.
+    pub async fn get(platform: user_account) -> Result<Vec<String>, TelescopeError> {
+        send_query::<Self>(Variables { platform })
+            .await
+            .map(|response| {
+                response
+                    .user_accounts
+                    .into_iter()
+                    .map(|linked_account| linked_account.account_id)
+                    .collect()
+            })
+    }
+}
@@ -1,5 +1,6 @@
 //! RCOS User account queries and mutations.
 
+pub mod all_of_type;
 pub mod for_user;
 pub mod link;
 pub mod lookup;
@@ -0,0 +1,311 @@
+//! A user's activity timeline.
+//!
+//! Each kind of activity comes from its own query, and the queries are sent at
+//! the same time. Pages are cut at a timestamp: the next page has the activity
+//! from before the last entry of this one.
+
+use crate::api::rcos::meetings::MeetingType;
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use futures::future::{self, FutureExt, LocalBoxFuture};
+
+/// Type representing GraphQL query to get who an activity timeline is for.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/activity.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ActivityTarget;
+
+/// Type representing GraphQL query to get meetings a user attended.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/activity.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AttendanceActivity;
+
+/// Type representing GraphQL query to get status updates a user submitted.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/activity.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct StatusUpdateActivity;
+
+/// Type representing GraphQL query to get meetings a user hosted.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/activity.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct HostedActivity;
+
+/// Type representing GraphQL query to get weeks a user contributed to a
+/// project.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/activity.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ContributionActivity;
+
+/// The kinds of activity on a timeline.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Display)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    #[display(fmt = "attendance")]
+    Attendance,
+    #[display(fmt = "status_update")]
+    StatusUpdate,
+    #[display(fmt = "hosted")]
+    Hosted,
+    #[display(fmt = "contribution")]
+    Contribution,
+}
+
+impl ActivityKind {
+    /// Every kind of activity, in the order they are offered as filters.
+    pub const ALL: [ActivityKind; 4] = [
+        ActivityKind::Attendance,
+        ActivityKind::StatusUpdate,
+        ActivityKind::Hosted,
+        ActivityKind::Contribution,
+    ];
+
+    /// The label of this kind of activity in filters.
+    pub fn label(self) -> &'static str {
+        match self {
+            ActivityKind::Attendance => "Attendance",
+            ActivityKind::StatusUpdate => "Status updates",
+            ActivityKind::Hosted => "Meetings hosted",
+            ActivityKind::Contribution => "Contributions",
+        }
+    }
+}
+
+/// One entry on an activity timeline.
+#[derive(Clone, Debug, Serialize)]
+pub struct ActivityItem {
+    pub kind: ActivityKind,
+    /// When this happened. Contributions happened in the week starting here.
+    pub at: DateTime<Utc>,
+    pub title: String,
+    pub url: Option<String>,
+    /// Extra detail shown under the title.
+    pub detail: Option<String>,
+}
+
+/// A page of an activity timeline, newest first.
+#[derive(Clone, Debug, Serialize)]
+pub struct ActivityPage {
+    pub items: Vec<ActivityItem>,
+    /// Where the next page starts, if there is more activity.
+    pub next: Option<DateTime<Utc>>,
+}
+
+/// The activity from one query, and whether the query hit its limit. If it
+/// did, there may be more activity of this kind older than what was returned.
+type Batch = (Vec<ActivityItem>, bool);
+
+/// The name of a meeting on a timeline.
+fn meeting_title(title: Option<String>, custom_type: Option<String>, kind: MeetingType) -> String {
+    title.or(custom_type).unwrap_or_else(|| kind.to_string())
+}
+
+/// Get meetings a user attended.
+async fn attendance(
+    user_id: uuid,
+    before: DateTime<Utc>,
+    limit: i64,
+) -> Result<Batch, TelescopeError> {
+    let rows = send_query::<AttendanceActivity>(attendance_activity::Variables {
+        user_id,
+        before,
+        limit,
+    })
+    .await?
+    .meeting_attendances;
+
+    let full: bool = rows.len() as i64 == limit;
+    let items = rows
+        .into_iter()
+        .map(|row| {
+            let meeting = row.meeting;
+            ActivityItem {
+                kind: ActivityKind::Attendance,
+                at: meeting.start_date_time,
+                title: meeting_title(
+                    meeting.title,
+                    meeting.custom_type.map(|t| t.name),
+                    meeting.type_,
+                ),
+                url: Some(format!("/meeting/{}", meeting.meeting_id)),
+                detail: None,
+            }
+        })
+        .collect();
+    return Ok((items, full));
+}
+
+/// Get status updates a user submitted.
+async fn status_updates(
+    user_id: uuid,
+    before: DateTime<Utc>,
+    limit: i64,
+    show_hidden: bool,
+) -> Result<Batch, TelescopeError> {
+    let rows = send_query::<StatusUpdateActivity>(status_update_activity::Variables {
+        user_id,
+        before,
+        limit,
+        show_hidden,
+    })
+    .await?
+    .status_update_submissions;
+
+    let full: bool = rows.len() as i64 == limit;
+    let items = rows
+        .into_iter()
+        .map(|row| ActivityItem {
+            kind: ActivityKind::StatusUpdate,
+            at: row.created_at,
+            title: row
+                .status_update
+                .title
+                .unwrap_or_else(|| "Status update".to_string()),
+            url: None,
+            detail: row.is_hidden.then(|| "Hidden by a moderator".to_string()),
+        })
+        .collect();
+    return Ok((items, full));
+}
+
+/// Get meetings a user hosted.
+async fn hosted(user_id: uuid, before: DateTime<Utc>, limit: i64) -> Result<Batch, TelescopeError> {
+    let rows = send_query::<HostedActivity>(hosted_activity::Variables {
+        user_id,
+        before,
+        limit,
+    })
+    .await?
+    .meetings;
+
+    let full: bool = rows.len() as i64 == limit;
+    let items = rows
+        .into_iter()
+        .map(|meeting| ActivityItem {
+            kind: ActivityKind::Hosted,
+            at: meeting.start_date_time,
+            url: Some(format!("/meeting/{}", meeting.meeting_id)),
+            title: meeting_title(
+                meeting.title,
+                meeting.custom_type.map(|t| t.name),
+                meeting.type_,
+            ),
+            detail: None,
+        })
+        .collect();
+    return Ok((items, full));
+}
+
+/// Get weeks a user contributed to project repositories.
+async fn contributions(
+    user_id: uuid,
+    before: DateTime<Utc>,
+    limit: i64,
+) -> Result<Batch, TelescopeError> {
+    let rows = send_query::<ContributionActivity>(contribution_activity::Variables {
+        user_id,
+        before: before.naive_utc().date(),
+        limit,
+    })
+    .await?
+    .contribution_weeks;
+
+    let full: bool = rows.len() as i64 == limit;
+    let week_start = |date: NaiveDate| Utc.from_utc_date(&date).and_hms(0, 0, 0);
+    let items = rows
+        .into_iter()
+        .filter(|row| week_start(row.week_start) < before)
+        .map(|row| ActivityItem {
+            kind: ActivityKind::Contribution,
+            at: week_start(row.week_start),
+            title: row.project.title,
+            url: Some(format!("/project/{}", row.project.project_id)),
+            detail: Some(format!(
+                "{} commits and {} pull requests that week",
+                row.commits, row.pull_requests
+            )),
+        })
+        .collect();
+    return Ok((items, full));
+}
+
+impl ActivityTarget {
+    /// Get who an activity timeline is for.
+    pub async fn get(
+        user_id: uuid,
+    ) -> Result<Option<activity_target::ActivityTargetUsersByPk>, TelescopeError> {
+        send_query::<Self>(activity_target::Variables { user_id })
+            .await
+            .map(|response| response.users_by_pk)
+    }
+}
+
+impl ActivityPage {
+    /// Get a page of at most `page_size` entries of a user's activity from
+    /// before a time, newest first. Only the given kinds of activity are
+    /// included. Status updates hidden by a moderator are only included if
+    /// `show_hidden` is set.
+    pub async fn get(
+        user_id: uuid,
+        kinds: &[ActivityKind],
+        before: DateTime<Utc>,
+        page_size: usize,
+        show_hidden: bool,
+    ) -> Result<Self, TelescopeError> {
+        let limit = page_size as i64;
+        let queries: Vec<LocalBoxFuture<Result<Batch, TelescopeError>>> = kinds
+            .iter()
+            .map(|kind| match kind {
+                ActivityKind::Attendance => attendance(user_id, before, limit).boxed_local(),
+                ActivityKind::StatusUpdate => {
+                    status_updates(user_id, before, limit, show_hidden).boxed_local()
+                }
+                ActivityKind::Hosted => hosted(user_id, before, limit).boxed_local(),
+                ActivityKind::Contribution => contributions(user_id, before, limit).boxed_local(),
+            })
+            .collect();
+
+        // Activity older than the oldest entry of a query that hit its limit
+        // may be missing, so the page stops there.
+        let mut horizon: Option<DateTime<Utc>> = None;
+        let mut items: Vec<ActivityItem> = Vec::new();
+        for (batch, full) in future::try_join_all(queries).await? {
+            if full {
+                let oldest = batch.iter().map(|item| item.at).min();
+                horizon = horizon.max(oldest);
+            }
+            items.extend(batch);
+        }
+
+        items.sort_by(|a, b| b.at.cmp(&a.at));
+        if let Some(horizon) = horizon {
+            items.retain(|item| item.at >= horizon);
+        }
+        let more: bool = horizon.is_some() || items.len() > page_size;
+        items.truncate(page_size);
+
+        return Ok(ActivityPage {
+            next: items.last().map(|item| item.at).filter(|_| more),
+            items,
+        });
+    }
+}
@@ -0,0 +1,79 @@
+//! Queries and mutations for marking students as alumni.
+//!
+//! Alumni are users with the [`UserRole::Alum`] role. They keep their
+//! accounts and profiles, but are not counted as active members and cannot be
+//! picked for things that need a current enrollment, like hosting meetings.
+//!
+//! [`UserRole::Alum`]: crate::api::rcos::users::UserRole::Alum
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL query to get the students who could be marked as
+/// alumni in a semester.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/alumni.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct GraduationCandidates;
+
+/// Type representing GraphQL query to get the most recent semester to start.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/alumni.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct LatestSemester;
+
+/// Type representing GraphQL mutation to mark students as alumni.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/alumni.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct MarkAlumni;
+
+impl GraduationCandidates {
+    /// Get the students enrolled in a semester.
+    pub async fn get(
+        semester_id: String,
+    ) -> Result<graduation_candidates::ResponseData, TelescopeError> {
+        send_query::<Self>(graduation_candidates::Variables { semester_id }).await
+    }
+}
+
+impl LatestSemester {
+    /// Get the ID of the most recent semester to have started, if any.
+    pub async fn get() -> Result<Option<String>, TelescopeError> {
+        send_query::<Self>(latest_semester::Variables {
+            now: Utc::today().naive_utc(),
+        })
+        .await
+        .map(|response| {
+            response
+                .semesters
+                .into_iter()
+                .next()
+                .map(|semester| semester.semester_id)
+        })
+    }
+}
+
+impl MarkAlumni {
+    /// Mark students as alumni. Return how many users were changed.
+    pub async fn execute(user_ids: Vec<uuid>) -> Result<i64, TelescopeError> {
+        send_query::<Self>(mark_alumni::Variables { user_ids })
+            .await
+            .map(|response| {
+                response
+                    .update_users
+                    .map(|updated| updated.affected_rows)
+                    .unwrap_or(0)
+            })
+    }
+}
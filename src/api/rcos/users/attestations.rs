@@ -0,0 +1,80 @@
+//! GraphQL queries and mutations for the attestations users answer at
+//! registration.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL mutation to record a user's answer to an attestation.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/attestations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RecordAttestation;
+
+/// Type representing GraphQL query to get every answer to an attestation.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/attestations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AttestationReport;
+
+/// Type representing GraphQL query to get the answers to every attestation.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/attestations.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct AttestationAnswers;
+
+impl RecordAttestation {
+    /// Record a user's answers. Each answer is the key of the attestation,
+    /// the label the user was shown, and whether they checked it.
+    pub async fn execute(
+        user_id: uuid,
+        answers: Vec<(String, String, bool)>,
+    ) -> Result<(), TelescopeError> {
+        for (key, label, accepted) in answers {
+            send_query::<Self>(record_attestation::Variables {
+                user_id,
+                key,
+                label,
+                accepted,
+                now: Utc::now(),
+            })
+            .await?;
+        }
+        return Ok(());
+    }
+}
+
+impl AttestationReport {
+    /// Get every answer to an attestation, ordered by the users' names.
+    pub async fn get(
+        key: String,
+    ) -> Result<Vec<attestation_report::AttestationReportUserAttestations>, TelescopeError> {
+        send_query::<Self>(attestation_report::Variables { key })
+            .await
+            .map(|response| response.user_attestations)
+    }
+}
+
+impl AttestationAnswers {
+    /// Get the key and answer of every attestation answer.
+    pub async fn get() -> Result<Vec<(String, bool)>, TelescopeError> {
+        send_query::<Self>(attestation_answers::Variables {})
+            .await
+            .map(|response| {
+                response
+                    .user_attestations
+                    .into_iter()
+                    .map(|answer| (answer.key, answer.accepted))
+                    .collect()
+            })
+    }
+}
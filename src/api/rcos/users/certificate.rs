@@ -0,0 +1,27 @@
+//! Participation certificate data.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+
+/// Type representing GraphQL query to get what goes on a participation certificate.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/certificate.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct Certificate;
+
+impl Certificate {
+    /// Get a user's enrollment and participation in a semester.
+    pub async fn get(
+        user_id: uuid,
+        semester_id: SemesterId,
+    ) -> Result<certificate::ResponseData, TelescopeError> {
+        send_query::<Self>(certificate::Variables {
+            user_id,
+            semester_id: semester_id.into(),
+        })
+        .await
+    }
+}
@@ -1,14 +1,38 @@
 //! RCOS API query to get list of developers to display on the developers page.
 
 use crate::api::rcos::send_query;
+use crate::api::rcos::users::field_visibility::{FieldVisibility, ProfileField, Viewer};
 use crate::api::rcos::{prelude::*, search_strings::resolve_search_string};
 use crate::error::TelescopeError;
+use crate::templates::pagination::PaginationParameter;
 use chrono::Utc;
 use graphql_client::GraphQLQuery;
 
-/// The query returns 20 developers per page.
+/// The developers page shows 20 developers per page.
 pub const PER_PAGE: u32 = 20;
 
+/// Remove the fields of each listed user that the viewer cannot see. The two
+/// queries generate separate types for the shared fragment, so this is a
+/// macro.
+macro_rules! redact {
+    ($users:expr, $viewer:expr) => {
+        for user in $users.iter_mut() {
+            let visibility = FieldVisibility::from_rows(
+                user.field_visibility
+                    .iter()
+                    .map(|row| (row.field.as_str(), row.audience.as_str())),
+            );
+            let audience = $viewer.audience_for(user.id);
+            if !visibility.shows(ProfileField::Pronouns, audience) {
+                user.pronouns = None;
+            }
+            if !visibility.shows(ProfileField::Email, audience) {
+                user.rcs_id.clear();
+            }
+        }
+    };
+}
+
 /// Type representing GraphQL query to get a list of all users and their
 /// account associations for the developers page.
 #[derive(GraphQLQuery)]
@@ -31,31 +55,41 @@ pub struct CurrentDevelopers;
 
 impl AllDevelopers {
     /// Send the query to get all the developers (including old ones) and wait for a response.
+    /// Fields the viewer is not allowed to see are removed.
     pub async fn get(
-        page_num: u32,
+        page: PaginationParameter,
         search: Option<String>,
+        viewer: &Viewer,
     ) -> Result<<Self as GraphQLQuery>::ResponseData, TelescopeError> {
-        send_query::<Self>(all_developers::Variables {
-            limit: PER_PAGE as i64,
-            offset: (PER_PAGE * page_num) as i64,
+        let mut response = send_query::<Self>(all_developers::Variables {
+            limit: page.page_size as i64,
+            offset: page.offset as i64,
             search: resolve_search_string(search),
         })
-        .await
+        .await?;
+
+        redact!(response.users, viewer);
+        return Ok(response);
     }
 }
 
 impl CurrentDevelopers {
     /// Send the developers page query (and limit to current developers) and wait for a response.
+    /// Fields the viewer is not allowed to see are removed.
     pub async fn get(
-        page_num: u32,
+        page: PaginationParameter,
         search: Option<String>,
+        viewer: &Viewer,
     ) -> Result<<Self as GraphQLQuery>::ResponseData, TelescopeError> {
-        send_query::<Self>(current_developers::Variables {
-            limit: PER_PAGE as i64,
-            offset: (PER_PAGE * page_num) as i64,
+        let mut response = send_query::<Self>(current_developers::Variables {
+            limit: page.page_size as i64,
+            offset: page.offset as i64,
             search: resolve_search_string(search),
             now: Utc::today().naive_utc(),
         })
-        .await
+        .await?;
+
+        redact!(response.users, viewer);
+        return Ok(response);
     }
 }
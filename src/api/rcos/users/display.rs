@@ -0,0 +1,72 @@
+//! How users are shown across Telescope and the Discord bot.
+//!
+//! Queries that show a person should select `first_name`, `last_name`,
+//! `preferred_name`, and `pronouns`, so that the response can be formatted
+//! by [`UserDisplay`] (or by the `display_name` handlebars helper, which uses
+//! it) instead of each page putting the name together itself.
+
+use serde_json::Value;
+use std::fmt;
+
+/// The parts of a user needed to show them by name.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UserDisplay {
+    pub first_name: String,
+    pub last_name: String,
+    /// Preferred first name, used in place of the first name if set.
+    #[serde(default)]
+    pub preferred_name: Option<String>,
+    /// Pronouns, like "she/her" or "they/them".
+    #[serde(default)]
+    pub pronouns: Option<String>,
+}
+
+/// Treat empty strings from the database the same as missing values.
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|s| !s.is_empty())
+}
+
+impl UserDisplay {
+    /// Create a display for a user from their name fields.
+    pub fn new(
+        first_name: impl Into<String>,
+        last_name: impl Into<String>,
+        preferred_name: Option<String>,
+        pronouns: Option<String>,
+    ) -> Self {
+        UserDisplay {
+            first_name: first_name.into(),
+            last_name: last_name.into(),
+            preferred_name,
+            pronouns,
+        }
+    }
+
+    /// Get the display for a user serialized from a query response. Return
+    /// `None` if the value does not have the user's name.
+    pub fn from_json(value: &Value) -> Option<Self> {
+        serde_json::from_value(value.clone()).ok()
+    }
+
+    /// The name to show for this user -- their preferred first name (or first
+    /// name) and last name.
+    pub fn name(&self) -> String {
+        let first: &str = non_empty(&self.preferred_name).unwrap_or(self.first_name.as_str());
+        format!("{} {}", first, self.last_name)
+    }
+
+    /// This user's pronouns, if they have set them.
+    pub fn pronouns(&self) -> Option<&str> {
+        non_empty(&self.pronouns)
+    }
+}
+
+/// Format as the user's name followed by their pronouns in parentheses, if set.
+impl fmt::Display for UserDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.pronouns() {
+            Some(pronouns) => write!(f, "{} ({})", self.name(), pronouns),
+            None => write!(f, "{}", self.name()),
+        }
+    }
+}
@@ -25,6 +25,17 @@ pub struct EditProfileContext;
 )]
 pub struct SaveProfileEdits;
 
+/// Type representing GraphQL mutation to set or clear a user's profile
+/// picture.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/edit_profile.graphql",
+    variables_derives = "Debug,Clone",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SetAvatar;
+
 impl EditProfileContext {
     /// Get the context to edit a user's profile.
     pub async fn get(
@@ -42,17 +53,38 @@ impl SaveProfileEdits {
         user_id: uuid,
         first_name: String,
         last_name: String,
+        preferred_name: Option<String>,
+        pronouns: Option<String>,
         cohort: Option<i64>,
         role: UserRole,
+        leaderboard_opt_in: bool,
     ) -> Result<Option<uuid>, TelescopeError> {
         send_query::<Self>(save_profile_edits::Variables {
             user_id,
             fname: first_name,
             lname: last_name,
+            preferred_name,
+            pronouns,
             cohort,
             role,
+            leaderboard_opt_in,
         })
         .await
         .map(|response| response.update_users_by_pk.map(|obj| obj.id))
     }
 }
+
+impl SetAvatar {
+    /// Set a user's profile picture to an uploaded image, or clear it.
+    pub async fn execute(
+        user_id: uuid,
+        avatar_image_id: Option<uuid>,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(set_avatar::Variables {
+            user_id,
+            avatar_image_id,
+        })
+        .await?;
+        return Ok(());
+    }
+}
@@ -0,0 +1,146 @@
+//! Discord roles that follow a user's enrollment.
+//!
+//! Students enrolled in an ongoing semester, mentors of a small group in one,
+//! its coordinators, and faculty each get a role on servers that configure
+//! one. The bot adds and removes these roles to match the RCOS database.
+
+use crate::api::rcos::users::UserRole;
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+use std::collections::HashSet;
+
+/// Type representing GraphQL query to get what decides one user's enrollment
+/// roles.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/enrollment_roles.graphql",
+    response_derives = "Debug"
+)]
+pub struct UserEnrollmentRoles;
+
+/// Type representing GraphQL query to get what decides the enrollment roles of
+/// every user with Discord linked.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/enrollment_roles.graphql",
+    response_derives = "Debug"
+)]
+pub struct LinkedEnrollmentRoles;
+
+/// A role given on Discord because of a user's enrollment.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, Display)]
+#[serde(rename_all = "snake_case")]
+pub enum EnrollmentRole {
+    #[display(fmt = "student")]
+    Student,
+    #[display(fmt = "mentor")]
+    Mentor,
+    #[display(fmt = "coordinator")]
+    Coordinator,
+    #[display(fmt = "faculty")]
+    Faculty,
+}
+
+impl EnrollmentRole {
+    /// Every enrollment role.
+    pub const ALL: [EnrollmentRole; 4] = [
+        EnrollmentRole::Student,
+        EnrollmentRole::Mentor,
+        EnrollmentRole::Coordinator,
+        EnrollmentRole::Faculty,
+    ];
+}
+
+/// A user with Discord linked and the enrollment roles they should have.
+#[derive(Clone, Debug)]
+pub struct LinkedMember {
+    pub user_id: uuid,
+    pub discord_id: u64,
+    pub roles: HashSet<EnrollmentRole>,
+}
+
+/// Decide which enrollment roles a user should have. `enrollments` has
+/// whether the user coordinates each ongoing semester they are enrolled in.
+fn decide(role: UserRole, enrollments: &[bool], groups_mentored: i64) -> HashSet<EnrollmentRole> {
+    let mut roles = HashSet::new();
+    if role == UserRole::Faculty || role == UserRole::FacultyAdvisor {
+        roles.insert(EnrollmentRole::Faculty);
+    } else if !enrollments.is_empty() {
+        roles.insert(EnrollmentRole::Student);
+    }
+    if enrollments.iter().any(|is_coordinator| *is_coordinator) {
+        roles.insert(EnrollmentRole::Coordinator);
+    }
+    if groups_mentored > 0 {
+        roles.insert(EnrollmentRole::Mentor);
+    }
+    return roles;
+}
+
+/// Parse the Discord ID of a linked account. Malformed IDs are treated as
+/// not linked.
+fn parse_discord_id(account_id: Option<&String>) -> Option<u64> {
+    account_id.and_then(|id| id.parse::<u64>().ok())
+}
+
+impl UserEnrollmentRoles {
+    /// Get the enrollment roles of a user. Return `None` if the user does not
+    /// exist or does not have Discord linked.
+    pub async fn get(user_id: uuid) -> Result<Option<LinkedMember>, TelescopeError> {
+        let user = send_query::<Self>(user_enrollment_roles::Variables {
+            user_id,
+            now: Utc::today().naive_utc(),
+        })
+        .await?
+        .users_by_pk;
+
+        return Ok(user.and_then(|user| {
+            let discord_id = parse_discord_id(user.discord.first().map(|a| &a.account_id))?;
+            let enrollments: Vec<bool> =
+                user.enrollments.iter().map(|e| e.is_coordinator).collect();
+            let mentored: i64 = user
+                .small_group_mentors_aggregate
+                .aggregate
+                .map_or(0, |agg| agg.count);
+
+            Some(LinkedMember {
+                user_id: user.id,
+                discord_id,
+                roles: decide(user.role, enrollments.as_slice(), mentored),
+            })
+        }));
+    }
+}
+
+impl LinkedEnrollmentRoles {
+    /// Get the enrollment roles of every user with Discord linked.
+    pub async fn get() -> Result<Vec<LinkedMember>, TelescopeError> {
+        let users = send_query::<Self>(linked_enrollment_roles::Variables {
+            now: Utc::today().naive_utc(),
+        })
+        .await?
+        .users;
+
+        return Ok(users
+            .into_iter()
+            .filter_map(|user| {
+                let discord_id = parse_discord_id(user.discord.first().map(|a| &a.account_id))?;
+                let enrollments: Vec<bool> =
+                    user.enrollments.iter().map(|e| e.is_coordinator).collect();
+                let mentored: i64 = user
+                    .small_group_mentors_aggregate
+                    .aggregate
+                    .map_or(0, |agg| agg.count);
+
+                Some(LinkedMember {
+                    user_id: user.id,
+                    discord_id,
+                    roles: decide(user.role, enrollments.as_slice(), mentored),
+                })
+            })
+            .collect());
+    }
+}
@@ -0,0 +1,237 @@
+//! Who can see which parts of a user's profile.
+//!
+//! Optional profile fields each have an [`Audience`], which users choose in
+//! their profile settings. The profile and developers page queries redact
+//! fields the viewer is not in the audience for before returning them, so
+//! templates never get data they should not show.
+
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::api::rcos::prelude::*;
+use crate::api::rcos::send_query;
+use crate::error::TelescopeError;
+use std::collections::HashMap;
+
+/// Type representing GraphQL mutation to save who can see each optional
+/// profile field.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/field_visibility.graphql",
+    variables_derives = "Debug,Clone",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SaveFieldVisibility;
+
+/// Who can see a profile field. Audiences are ordered from widest to
+/// narrowest, and each includes the ones after it.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Audience {
+    /// Everyone, including visitors who are not logged in.
+    Public,
+    /// Anyone who is logged in.
+    Members,
+    /// Current coordinators, faculty advisors, and sysadmins.
+    Coordinators,
+    /// Only the user themself.
+    #[serde(rename = "self")]
+    Owner,
+}
+
+impl Audience {
+    /// Every audience, from widest to narrowest.
+    pub const ALL: [Audience; 4] = [
+        Audience::Public,
+        Audience::Members,
+        Audience::Coordinators,
+        Audience::Owner,
+    ];
+
+    /// The name of this audience in the database and in forms.
+    pub fn name(self) -> &'static str {
+        match self {
+            Audience::Public => "public",
+            Audience::Members => "members",
+            Audience::Coordinators => "coordinators",
+            Audience::Owner => "self",
+        }
+    }
+
+    /// The label of this audience in forms.
+    pub fn label(self) -> &'static str {
+        match self {
+            Audience::Public => "Everyone",
+            Audience::Members => "Logged in members",
+            Audience::Coordinators => "Coordinators",
+            Audience::Owner => "Only me",
+        }
+    }
+
+    /// Parse an audience from its name.
+    pub fn parse(name: &str) -> Option<Self> {
+        Audience::ALL.iter().copied().find(|a| a.name() == name)
+    }
+}
+
+/// A profile field that users can choose the audience of.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileField {
+    Pronouns,
+    /// Entry year at RPI.
+    Cohort,
+    /// RPI email address, from the linked RCS ID.
+    Email,
+    Discord,
+    Github,
+    /// Weekly repository contributions.
+    Contributions,
+}
+
+impl ProfileField {
+    /// Every field with a configurable audience.
+    pub const ALL: [ProfileField; 6] = [
+        ProfileField::Pronouns,
+        ProfileField::Cohort,
+        ProfileField::Email,
+        ProfileField::Discord,
+        ProfileField::Github,
+        ProfileField::Contributions,
+    ];
+
+    /// The name of this field in the database and in forms.
+    pub fn name(self) -> &'static str {
+        match self {
+            ProfileField::Pronouns => "pronouns",
+            ProfileField::Cohort => "cohort",
+            ProfileField::Email => "email",
+            ProfileField::Discord => "discord",
+            ProfileField::Github => "github",
+            ProfileField::Contributions => "contributions",
+        }
+    }
+
+    /// The label of this field in forms.
+    pub fn label(self) -> &'static str {
+        match self {
+            ProfileField::Pronouns => "Pronouns",
+            ProfileField::Cohort => "Entry year",
+            ProfileField::Email => "RPI e-mail",
+            ProfileField::Discord => "Discord account",
+            ProfileField::Github => "GitHub account",
+            ProfileField::Contributions => "Repository contributions",
+        }
+    }
+
+    /// The audience of this field for users who have not picked one.
+    pub fn default_audience(self) -> Audience {
+        match self {
+            // Linked accounts have always been shown to logged in members only.
+            ProfileField::Email | ProfileField::Discord | ProfileField::Github => Audience::Members,
+            _ => Audience::Public,
+        }
+    }
+}
+
+/// The audience of each optional field of a user's profile.
+#[derive(Clone, Debug, Default)]
+pub struct FieldVisibility(HashMap<ProfileField, Audience>);
+
+impl FieldVisibility {
+    /// Read a user's settings from `(field, audience)` rows. Unknown fields
+    /// and audiences are ignored.
+    pub fn from_rows<'a>(rows: impl Iterator<Item = (&'a str, &'a str)>) -> Self {
+        let settings = rows
+            .filter_map(|(field, audience)| {
+                let field = ProfileField::ALL
+                    .iter()
+                    .copied()
+                    .find(|f| f.name() == field)?;
+                Some((field, Audience::parse(audience)?))
+            })
+            .collect();
+        FieldVisibility(settings)
+    }
+
+    /// Set the audience of a field.
+    pub fn set(&mut self, field: ProfileField, audience: Audience) {
+        self.0.insert(field, audience);
+    }
+
+    /// Get the audience of a field.
+    pub fn audience(&self, field: ProfileField) -> Audience {
+        self.0
+            .get(&field)
+            .copied()
+            .unwrap_or_else(|| field.default_audience())
+    }
+
+    /// Check if a viewer in the given audience can see a field.
+    pub fn shows(&self, field: ProfileField, viewer: Audience) -> bool {
+        viewer >= self.audience(field)
+    }
+
+    /// List the fields a viewer in the given audience cannot see.
+    pub fn hidden_from(&self, viewer: Audience) -> Vec<ProfileField> {
+        ProfileField::ALL
+            .iter()
+            .copied()
+            .filter(|field| !self.shows(*field, viewer))
+            .collect()
+    }
+}
+
+/// The person looking at profile data.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Viewer {
+    /// The viewer's user ID, if they are logged in.
+    pub user_id: Option<uuid>,
+    /// Is the viewer a current coordinator, faculty advisor, or sysadmin?
+    pub is_coordinator: bool,
+}
+
+impl Viewer {
+    /// Look up whether a viewer is a coordinator.
+    pub async fn get(user_id: Option<uuid>) -> Result<Self, TelescopeError> {
+        let is_coordinator: bool = AuthorizationFor::get(user_id).await?.can_view_drafts();
+        return Ok(Viewer {
+            user_id,
+            is_coordinator,
+        });
+    }
+
+    /// The narrowest audience this viewer is in for a user's profile.
+    pub fn audience_for(&self, target: uuid) -> Audience {
+        if self.user_id == Some(target) {
+            Audience::Owner
+        } else if self.is_coordinator {
+            Audience::Coordinators
+        } else if self.user_id.is_some() {
+            Audience::Members
+        } else {
+            Audience::Public
+        }
+    }
+}
+
+impl SaveFieldVisibility {
+    /// Save the audience of every optional field of a user's profile.
+    pub async fn execute(
+        user_id: uuid,
+        visibility: &FieldVisibility,
+    ) -> Result<(), TelescopeError> {
+        let audience = |field: ProfileField| visibility.audience(field).name().to_string();
+
+        send_query::<Self>(save_field_visibility::Variables {
+            user_id,
+            pronouns: audience(ProfileField::Pronouns),
+            cohort: audience(ProfileField::Cohort),
+            email: audience(ProfileField::Email),
+            discord: audience(ProfileField::Discord),
+            github: audience(ProfileField::Github),
+            contributions: audience(ProfileField::Contributions),
+        })
+        .await?;
+        return Ok(());
+    }
+}
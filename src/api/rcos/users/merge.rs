@@ -0,0 +1,360 @@
+//! Duplicate account detection and merging.
+
+use crate::api::rcos::users::UserAccountType;
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use std::collections::{HashMap, HashSet};
+
+/// Type representing GraphQL query to get every user and their linked accounts.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/merge.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct DuplicateCandidates;
+
+/// Type representing GraphQL query to get the records a merge would touch.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/merge.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct MergePreview;
+
+/// Type representing GraphQL mutation to merge one user into another.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/merge_mutation.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct MergeUsers;
+
+use duplicate_candidates::DuplicateCandidatesUsers as Candidate;
+
+/// Minimum length of a shortened first name (e.g. "Sam" for "Samantha") for it
+/// to count as similar.
+const MIN_NAME_PREFIX: usize = 3;
+
+/// Two users that are likely the same person. The older account is suggested
+/// as the one to keep.
+#[derive(Clone, Debug, Serialize)]
+pub struct DuplicatePair {
+    pub keep: Candidate,
+    pub remove: Candidate,
+    /// Why these users were flagged.
+    pub reasons: Vec<String>,
+}
+
+/// Lowercase a name and drop anything that is not a letter.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphabetic())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Get the RCS ID linked to a user if there is one.
+fn rcs_id(user: &Candidate) -> Option<String> {
+    user.accounts
+        .iter()
+        .find(|account| account.type_ == UserAccountType::Rpi)
+        .map(|account| account.account_id.to_lowercase())
+}
+
+/// Check if two users have similar enough names to be the same person.
+fn similar_names(a: &Candidate, b: &Candidate) -> Option<String> {
+    let (a_first, a_last) = (normalize(&a.first_name), normalize(&a.last_name));
+    let (b_first, b_last) = (normalize(&b.first_name), normalize(&b.last_name));
+
+    if a_last.is_empty() || a_last != b_last {
+        return None;
+    }
+
+    if a_first == b_first {
+        return Some("Same name".into());
+    }
+
+    let (short, long) = if a_first.len() < b_first.len() {
+        (&a_first, &b_first)
+    } else {
+        (&b_first, &a_first)
+    };
+
+    if short.len() >= MIN_NAME_PREFIX && long.starts_with(short.as_str()) {
+        return Some("Similar name".into());
+    }
+
+    return None;
+}
+
+impl DuplicateCandidates {
+    /// Find pairs of users that are likely the same person. Users are paired if
+    /// they have linked the same external account (compared case-insensitively)
+    /// or if they have similar names and share an email domain -- since RPI email
+    /// addresses are the RCS ID, two users with different RCS IDs are assumed
+    /// to be different people.
+    pub async fn get() -> Result<Vec<DuplicatePair>, TelescopeError> {
+        let users: Vec<Candidate> = send_query::<Self>(duplicate_candidates::Variables {})
+            .await?
+            .users;
+
+        // Reasons each pair of user indices was flagged.
+        let mut flagged: HashMap<(usize, usize), Vec<String>> = HashMap::new();
+
+        // Group users by linked account to find shared ones without comparing every pair.
+        let mut by_account: HashMap<(UserAccountType, String), Vec<usize>> = HashMap::new();
+        for (index, user) in users.iter().enumerate() {
+            for account in &user.accounts {
+                by_account
+                    .entry((account.type_, account.account_id.to_lowercase()))
+                    .or_default()
+                    .push(index);
+            }
+        }
+
+        for ((platform, _), indices) in by_account {
+            for (i, a) in indices.iter().enumerate() {
+                for b in &indices[i + 1..] {
+                    if a != b {
+                        flagged
+                            .entry((*a.min(b), *a.max(b)))
+                            .or_default()
+                            .push(format!("Same {} account", platform));
+                    }
+                }
+            }
+        }
+
+        // Only users with the same last name can have similar names.
+        let mut by_last_name: HashMap<String, Vec<usize>> = HashMap::new();
+        for (index, user) in users.iter().enumerate() {
+            by_last_name
+                .entry(normalize(&user.last_name))
+                .or_default()
+                .push(index);
+        }
+
+        for indices in by_last_name.values() {
+            for (i, a) in indices.iter().enumerate() {
+                for b in &indices[i + 1..] {
+                    let (left, right) = (&users[*a], &users[*b]);
+                    match (rcs_id(left), rcs_id(right)) {
+                        (Some(x), Some(y)) if x != y => continue,
+                        _ => {}
+                    }
+
+                    if let Some(reason) = similar_names(left, right) {
+                        flagged.entry((*a, *b)).or_default().push(reason);
+                    }
+                }
+            }
+        }
+
+        let mut pairs = flagged
+            .into_iter()
+            .map(|((a, b), reasons)| {
+                let (a, b) = (users[a].clone(), users[b].clone());
+                // Suggest keeping the older account.
+                let (keep, remove) = if a.created_at <= b.created_at {
+                    (a, b)
+                } else {
+                    (b, a)
+                };
+
+                DuplicatePair {
+                    keep,
+                    remove,
+                    reasons,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        pairs.sort_by(|a, b| {
+            (&a.keep.last_name, &a.keep.first_name).cmp(&(&b.keep.last_name, &b.keep.first_name))
+        });
+
+        return Ok(pairs);
+    }
+}
+
+/// A number of records of one kind affected by a merge.
+#[derive(Clone, Debug, Serialize)]
+pub struct RecordCount {
+    pub label: &'static str,
+    pub count: i64,
+}
+
+/// The changes merging one user into another would make.
+#[derive(Clone, Debug, Serialize)]
+pub struct MergeDiff {
+    pub keep: merge_preview::MergePreviewKeep,
+    pub remove: merge_preview::MergePreviewRemove,
+    /// Records moved to the kept user.
+    pub moved: Vec<RecordCount>,
+    /// Records of the removed user that duplicate ones the kept user already
+    /// has. These are deleted.
+    pub discarded: Vec<RecordCount>,
+    /// Linked accounts of the removed user on platforms the kept user has
+    /// already linked. These are unlinked.
+    pub discarded_accounts: Vec<UserAccountType>,
+    /// Records that have to be dealt with by hand before merging.
+    pub blockers: Vec<RecordCount>,
+}
+
+impl MergeDiff {
+    /// Can the merge go ahead?
+    pub fn is_mergeable(&self) -> bool {
+        self.blockers.is_empty()
+    }
+}
+
+/// Get the count out of a Hasura aggregate.
+macro_rules! count {
+    ($aggregate:expr) => {
+        $aggregate.aggregate.as_ref().map(|a| a.count).unwrap_or(0)
+    };
+}
+
+/// Keep only the kinds of records that have at least one affected record.
+fn non_zero(counts: Vec<(&'static str, i64)>) -> Vec<RecordCount> {
+    counts
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(label, count)| RecordCount { label, count })
+        .collect()
+}
+
+impl MergePreview {
+    /// Get a dry-run of merging one user into another. Return `None` if either
+    /// user does not exist.
+    pub async fn get(keep: uuid, remove: uuid) -> Result<Option<MergeDiff>, TelescopeError> {
+        let data = send_query::<Self>(merge_preview::Variables { keep, remove }).await?;
+
+        let (keep, remove) = match (data.keep, data.remove) {
+            (Some(keep), Some(remove)) => (keep, remove),
+            _ => return Ok(None),
+        };
+
+        let kept_platforms: HashSet<UserAccountType> =
+            keep.accounts.iter().map(|account| account.type_).collect();
+        let discarded_accounts: Vec<UserAccountType> = remove
+            .accounts
+            .iter()
+            .map(|account| account.type_)
+            .filter(|platform| kept_platforms.contains(platform))
+            .collect();
+
+        let discarded = vec![
+            ("Enrollments", remove.conflicting_enrollments.len() as i64),
+            (
+                "Meeting attendances",
+                count!(remove.conflicting_attendances),
+            ),
+            (
+                "Status update submissions",
+                count!(remove.conflicting_submissions),
+            ),
+            (
+                "Small group mentor roles",
+                count!(remove.conflicting_mentors),
+            ),
+            ("Badges", remove.conflicting_badges.len() as i64),
+        ];
+
+        let moved = vec![
+            (
+                "Enrollments",
+                (remove.enrollments.len() - remove.conflicting_enrollments.len()) as i64,
+            ),
+            (
+                "Meeting attendances",
+                count!(remove.meeting_attendances_aggregate)
+                    - count!(remove.conflicting_attendances),
+            ),
+            (
+                "Status update submissions",
+                count!(remove.status_update_submissions_aggregate)
+                    - count!(remove.conflicting_submissions),
+            ),
+            (
+                "Small group mentor roles",
+                count!(remove.small_group_mentors_aggregate) - count!(remove.conflicting_mentors),
+            ),
+            (
+                "Badges",
+                (remove.badges.len() - remove.conflicting_badges.len()) as i64,
+            ),
+            ("Hosted meetings", count!(remove.hosted)),
+            (
+                "Linked accounts",
+                (remove.accounts.len() - discarded_accounts.len()) as i64,
+            ),
+        ];
+
+        let blockers = vec![
+            (
+                "Bonus attendances",
+                count!(remove.bonus_attendances_aggregate),
+            ),
+            ("Pay requests", count!(remove.pay_requests_aggregate)),
+            (
+                "Final grade appeals",
+                count!(remove.final_grade_appeals_aggregate),
+            ),
+            (
+                "Project presentation grades",
+                count!(remove.project_presentation_grades_aggregate),
+            ),
+            (
+                "Mentor proposals",
+                count!(remove.mentor_proposals_aggregate),
+            ),
+            ("Project pitches", count!(remove.project_pitches_aggregate)),
+            (
+                "Workshop proposals",
+                count!(remove.workshop_proposals_aggregate),
+            ),
+            (
+                "Reviewed mentor proposals",
+                count!(remove.reviewed_mentor_proposals),
+            ),
+            (
+                "Reviewed project pitches",
+                count!(remove.reviewed_project_pitches),
+            ),
+            (
+                "Reviewed workshop proposals",
+                count!(remove.reviewed_workshop_proposals),
+            ),
+        ];
+
+        return Ok(Some(MergeDiff {
+            keep,
+            remove,
+            moved: non_zero(moved),
+            discarded: non_zero(discarded),
+            discarded_accounts,
+            blockers: non_zero(blockers),
+        }));
+    }
+}
+
+impl MergeUsers {
+    /// Merge one user into another. This should only be called once
+    /// [`MergePreview`] has shown that there is nothing blocking the merge.
+    pub async fn execute(
+        keep: uuid,
+        remove: uuid,
+        discard_account_types: Vec<UserAccountType>,
+    ) -> Result<merge_users::ResponseData, TelescopeError> {
+        send_query::<Self>(merge_users::Variables {
+            keep,
+            remove,
+            discard_account_types,
+        })
+        .await
+    }
+}
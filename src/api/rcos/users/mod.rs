@@ -1,15 +1,28 @@
 //! API interactions for RCOS users from the central RCOS API.
 
 pub mod accounts;
+pub mod activity;
+pub mod alumni;
+pub mod attestations;
+pub mod certificate;
 pub mod create;
 pub mod delete;
 pub mod developers_page;
 pub mod discord_whois;
+pub mod display;
 pub mod edit_profile;
+pub mod field_visibility;
+pub mod enrollment_roles;
 pub mod enrollments;
+pub mod merge;
 pub mod navbar_auth;
 pub mod profile;
+pub mod quick_nav;
 pub mod role_lookup;
+pub mod saved_searches;
+pub mod search;
+pub mod sysadmins;
+pub mod username;
 
 /// The valid user roles for all users in the RCOS database.
 #[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Hash, Display)]
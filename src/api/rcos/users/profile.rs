@@ -1,5 +1,7 @@
 //! Profile query.
 
+use crate::api::rcos::contributions::{sparkline_since, weekly_series};
+use crate::api::rcos::users::field_visibility::{FieldVisibility, ProfileField, Viewer};
 use crate::api::rcos::{prelude::*, send_query};
 use crate::error::TelescopeError;
 use chrono::Utc;
@@ -16,7 +18,8 @@ pub struct Profile;
 use profile::{ResponseData, Variables};
 
 impl Profile {
-    /// Get the profile data for a given user ID..
+    /// Get the profile data for a given user ID. Fields the viewer is not
+    /// allowed to see are removed.
     pub async fn for_user(
         target: uuid,
         viewer: Option<uuid>,
@@ -25,16 +28,67 @@ impl Profile {
         let viewer = viewer.map(|v| vec![v]).unwrap_or(Vec::new());
 
         // Send the query and await the response.
-        send_query::<Self>(Variables {
+        let mut response: ResponseData = send_query::<Self>(Variables {
             target,
             viewer,
             now: Utc::today().naive_utc(),
+            since: sparkline_since(),
         })
-        .await
+        .await?;
+
+        response.redact();
+        return Ok(response);
     }
 }
 
 impl ResponseData {
+    /// The viewer, with coordinator status taken from the response.
+    fn viewer(&self) -> Viewer {
+        let viewer = self.viewer.first();
+        Viewer {
+            user_id: viewer.map(|viewer| viewer.id),
+            is_coordinator: viewer.map_or(false, |viewer| {
+                viewer.role.is_admin() || !viewer.is_current_coordinator.is_empty()
+            }),
+        }
+    }
+
+    /// Get the optional fields of the target's profile that the viewer cannot see.
+    pub fn hidden_fields(&self) -> Vec<ProfileField> {
+        let target = match self.target.as_ref() {
+            Some(target) => target,
+            None => return Vec::new(),
+        };
+
+        let visibility = FieldVisibility::from_rows(
+            target
+                .field_visibility
+                .iter()
+                .map(|row| (row.field.as_str(), row.audience.as_str())),
+        );
+        return visibility.hidden_from(self.viewer().audience_for(target.id));
+    }
+
+    /// Remove the fields the viewer cannot see.
+    fn redact(&mut self) {
+        let hidden: Vec<ProfileField> = self.hidden_fields();
+        let target = match self.target.as_mut() {
+            Some(target) => target,
+            None => return,
+        };
+
+        for field in hidden {
+            match field {
+                ProfileField::Pronouns => target.pronouns = None,
+                ProfileField::Cohort => target.cohort = None,
+                ProfileField::Email => target.rcs_id.clear(),
+                ProfileField::Discord => target.discord.clear(),
+                ProfileField::Github => target.github.clear(),
+                ProfileField::Contributions => target.contribution_weeks.clear(),
+            }
+        }
+    }
+
     /// Get the target user's Discord ID if available.
     pub fn discord(&self) -> Option<&str> {
         self.target
@@ -43,4 +97,15 @@ impl ResponseData {
             .get(0)
             .map(|disc| disc.account_id.as_str())
     }
+
+    /// Get the target user's total commits and merged pull requests for each
+    /// week of the sparkline window.
+    pub fn contribution_series(&self) -> Vec<i64> {
+        let weeks = self
+            .target
+            .iter()
+            .flat_map(|target| target.contribution_weeks.iter())
+            .map(|week| (week.week_start, week.commits + week.pull_requests));
+        return weekly_series(weeks);
+    }
 }
@@ -0,0 +1,35 @@
+//! GraphQL query for the destinations in a user's navbar quick switcher.
+
+use crate::api::rcos::meetings::authorization_for::UserMeetingAuthorization;
+use crate::api::rcos::prelude::*;
+use crate::api::rcos::send_query;
+use crate::error::TelescopeError;
+use chrono::{Local, Utc};
+
+/// Type representing GraphQL query to get a user's project, small groups,
+/// upcoming meetings, and drafts.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/quick_nav.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct QuickNav;
+
+impl QuickNav {
+    /// Get the quick switcher destinations of a user. Meetings are limited to
+    /// the ones the user's authorization lets them see.
+    pub async fn get(
+        user_id: uuid,
+        authorization: &UserMeetingAuthorization,
+    ) -> Result<quick_nav::ResponseData, TelescopeError> {
+        send_query::<Self>(quick_nav::Variables {
+            user_id,
+            today: Local::today().naive_local(),
+            now: Utc::now(),
+            include_drafts: authorization.can_view_drafts(),
+            accept_types: authorization.viewable_types(),
+        })
+        .await
+    }
+}
@@ -0,0 +1,111 @@
+//! GraphQL queries and mutations for searches users save on list pages.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+
+/// Type representing GraphQL query to get a user's saved searches on a page.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/saved_searches.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SavedSearches;
+
+/// Type representing GraphQL mutation to save a search.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/saved_searches.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SaveSearch;
+
+/// Type representing GraphQL mutation to change a user's default search.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/saved_searches.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SetDefaultSearch;
+
+/// Type representing GraphQL mutation to delete a saved search.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/saved_searches.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct DeleteSavedSearch;
+
+impl SavedSearches {
+    /// Get the searches a user has saved on a page, by name.
+    pub async fn get(
+        user_id: uuid,
+        page: String,
+    ) -> Result<Vec<saved_searches::SavedSearchesSavedSearches>, TelescopeError> {
+        Ok(
+            send_query::<Self>(saved_searches::Variables { user_id, page })
+                .await?
+                .saved_searches,
+        )
+    }
+}
+
+impl SaveSearch {
+    /// Save a search for a user. Return the ID of the saved search.
+    pub async fn execute(
+        user_id: uuid,
+        page: String,
+        name: String,
+        query: String,
+        is_default: bool,
+    ) -> Result<Option<i64>, TelescopeError> {
+        Ok(send_query::<Self>(save_search::Variables {
+            user_id,
+            page,
+            name,
+            query,
+            is_default,
+        })
+        .await?
+        .insert_saved_searches_one
+        .map(|saved| saved.saved_search_id))
+    }
+}
+
+impl SetDefaultSearch {
+    /// Make a saved search the default for its page, or clear the user's
+    /// default on that page if `is_default` is false. Return whether the
+    /// saved search was found.
+    pub async fn execute(
+        user_id: uuid,
+        page: String,
+        saved_search_id: i64,
+        is_default: bool,
+    ) -> Result<bool, TelescopeError> {
+        Ok(send_query::<Self>(set_default_search::Variables {
+            user_id,
+            page,
+            saved_search_id,
+            is_default,
+        })
+        .await?
+        .set_default
+        .map_or(false, |response| response.affected_rows > 0))
+    }
+}
+
+impl DeleteSavedSearch {
+    /// Delete one of a user's saved searches. Return whether it was found.
+    pub async fn execute(user_id: uuid, saved_search_id: i64) -> Result<bool, TelescopeError> {
+        Ok(send_query::<Self>(delete_saved_search::Variables {
+            user_id,
+            saved_search_id,
+        })
+        .await?
+        .delete_saved_searches
+        .map_or(false, |response| response.affected_rows > 0))
+    }
+}
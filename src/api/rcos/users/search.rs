@@ -0,0 +1,45 @@
+//! GraphQL query to search users by username and name.
+
+use crate::api::rcos::{prelude::*, search_strings::resolve_search_string, send_query};
+use crate::error::TelescopeError;
+use crate::templates::pagination::PaginationParameter;
+
+/// Type representing GraphQL query to search users.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/search.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct UserSearch;
+
+/// A user matched by a search.
+pub type UserSearchResult = user_search::UserSearchUsers;
+
+impl UserSearch {
+    /// Get a page of users matching a search, and how many match in total.
+    /// The first word of the search can match any of a user's username, first
+    /// name, preferred name, or last name, and so can the rest of it, so
+    /// "ada love" finds Ada Lovelace. Matching ignores case.
+    pub async fn get(
+        search: &str,
+        page: PaginationParameter,
+        show_hidden: bool,
+    ) -> Result<(Vec<UserSearchResult>, i64), TelescopeError> {
+        let mut words = search.split_whitespace();
+        let first: Option<String> = words.next().map(str::to_string);
+        let rest: String = words.collect::<Vec<&str>>().join(" ");
+
+        let data = send_query::<Self>(user_search::Variables {
+            first: resolve_search_string(first),
+            second: resolve_search_string(Some(rest).filter(|rest| !rest.is_empty())),
+            show_hidden,
+            limit: page.page_size as i64,
+            offset: page.offset as i64,
+        })
+        .await?;
+
+        let total: i64 = data.total.aggregate.map_or(0, |agg| agg.count);
+        return Ok((data.users, total));
+    }
+}
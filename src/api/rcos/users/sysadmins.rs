@@ -0,0 +1,29 @@
+//! GraphQL query to find sysadmins on Discord.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+
+/// Type representing GraphQL query to get the Discord accounts of sysadmins.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/sysadmins.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SysadminDiscordAccounts;
+
+impl SysadminDiscordAccounts {
+    /// Get the Discord IDs of every sysadmin with a linked Discord account.
+    pub async fn get() -> Result<Vec<u64>, TelescopeError> {
+        send_query::<Self>(sysadmin_discord_accounts::Variables {})
+            .await
+            .map(|response| {
+                response
+                    .users
+                    .into_iter()
+                    .filter_map(|user| user.discord.into_iter().next())
+                    .filter_map(|account| account.account_id.parse::<u64>().ok())
+                    .collect()
+            })
+    }
+}
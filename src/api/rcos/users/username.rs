@@ -0,0 +1,175 @@
+//! Usernames used in place of user IDs in profile URLs.
+//!
+//! When a user changes their username, the old one keeps redirecting to their
+//! profile for [`REDIRECT_GRACE_PERIOD_DAYS`] days and cannot be taken by
+//! anyone else during that time.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::{Duration, Utc};
+
+/// How long old usernames keep redirecting to their owner.
+pub const REDIRECT_GRACE_PERIOD_DAYS: i64 = 90;
+
+/// How long users have to wait between username changes.
+pub const CHANGE_COOLDOWN_DAYS: i64 = 30;
+
+/// Shortest allowed username.
+pub const MIN_LENGTH: usize = 3;
+
+/// Longest allowed username. This is shorter than a hyphenated UUID so that
+/// usernames and user IDs in profile URLs can never be confused.
+pub const MAX_LENGTH: usize = 32;
+
+/// Type representing GraphQL query to find who a username belongs to.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/username.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct UsernameLookup;
+
+/// Type representing GraphQL query to check if a username can be taken.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/username.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct UsernameAvailable;
+
+/// Type representing GraphQL mutation to set a first username.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/username.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct SetUsername;
+
+/// Type representing GraphQL mutation to change a username.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/users/username.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct ChangeUsername;
+
+/// Where a username points.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UsernameTarget {
+    /// The username currently belongs to this user.
+    Current(uuid),
+    /// The username used to belong to this user, who now has a different one
+    /// (or none, if it was removed by merging accounts).
+    Redirect {
+        user_id: uuid,
+        username: Option<String>,
+    },
+}
+
+/// The earliest time a redirect could have been created and still be active.
+fn grace_period_start() -> timestamptz {
+    Utc::now() - Duration::days(REDIRECT_GRACE_PERIOD_DAYS)
+}
+
+/// Normalize a username as submitted by a user. Usernames are stored lowercase.
+pub fn normalize(username: &str) -> Username {
+    Username(username.trim().to_lowercase())
+}
+
+/// Check that a normalized username is well formed. Return a message for the
+/// user if not.
+pub fn validate(username: &Username) -> Result<(), String> {
+    let username: &str = username.as_str();
+    if username.len() < MIN_LENGTH || username.len() > MAX_LENGTH {
+        return Err(format!(
+            "Must be between {} and {} characters.",
+            MIN_LENGTH, MAX_LENGTH
+        ));
+    }
+
+    if !username
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        return Err("Can only contain letters, numbers, hyphens, and underscores.".into());
+    }
+
+    if !username.starts_with(|c: char| c.is_ascii_lowercase()) {
+        return Err("Must start with a letter.".into());
+    }
+
+    return Ok(());
+}
+
+impl UsernameLookup {
+    /// Find the user a username points to, if any.
+    pub async fn get(username: Username) -> Result<Option<UsernameTarget>, TelescopeError> {
+        let response = send_query::<Self>(username_lookup::Variables {
+            username: username.into(),
+            since: grace_period_start(),
+        })
+        .await?;
+
+        if let Some(user) = response.users.first() {
+            return Ok(Some(UsernameTarget::Current(user.id)));
+        }
+
+        return Ok(response.redirects.into_iter().next().map(|redirect| {
+            UsernameTarget::Redirect {
+                user_id: redirect.user.id,
+                username: redirect.user.username,
+            }
+        }));
+    }
+}
+
+impl UsernameAvailable {
+    /// Check if a user can take a username.
+    pub async fn get(username: Username, user_id: uuid) -> Result<bool, TelescopeError> {
+        let response = send_query::<Self>(username_available::Variables {
+            username: username.into(),
+            user_id,
+            since: grace_period_start(),
+        })
+        .await?;
+
+        let taken: i64 = response.taken.aggregate.map(|a| a.count).unwrap_or(0);
+        let reserved: i64 = response.reserved.aggregate.map(|a| a.count).unwrap_or(0);
+        return Ok(taken == 0 && reserved == 0);
+    }
+}
+
+impl ChangeUsername {
+    /// Give a user a new username, keeping their old one (if any) as a
+    /// redirect. Return the user's ID if they were found.
+    pub async fn execute(
+        user_id: uuid,
+        old_username: Option<Username>,
+        username: Username,
+    ) -> Result<Option<uuid>, TelescopeError> {
+        let username: String = username.into();
+        let now = Utc::now();
+        match old_username {
+            None => send_query::<SetUsername>(set_username::Variables {
+                user_id,
+                username,
+                now,
+            })
+            .await
+            .map(|response| response.update_users_by_pk.map(|user| user.id)),
+
+            Some(old_username) => send_query::<Self>(change_username::Variables {
+                user_id,
+                old_username: old_username.into(),
+                username,
+                now,
+            })
+            .await
+            .map(|response| response.update_users_by_pk.map(|user| user.id)),
+        }
+    }
+}
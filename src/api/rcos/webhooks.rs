@@ -0,0 +1,258 @@
+//! Outgoing webhook endpoints and the outbox of events waiting to be posted
+//! to them.
+
+use crate::api::rcos::{prelude::*, send_query};
+use crate::error::TelescopeError;
+use chrono::Utc;
+
+/// Type representing GraphQL query to list webhook endpoints.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/webhooks.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct WebhookEndpoints;
+
+/// Type representing GraphQL mutation to register a webhook endpoint.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/webhooks.graphql",
+    response_derives = "Debug"
+)]
+pub struct CreateWebhookEndpoint;
+
+/// Type representing GraphQL mutation to subscribe an endpoint to an event.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/webhooks.graphql",
+    response_derives = "Debug"
+)]
+pub struct AddWebhookSubscription;
+
+/// Type representing GraphQL mutation to enable or disable an endpoint.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/webhooks.graphql",
+    response_derives = "Debug"
+)]
+pub struct SetWebhookEndpointEnabled;
+
+/// Type representing GraphQL mutation to remove an endpoint.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/webhooks.graphql",
+    response_derives = "Debug"
+)]
+pub struct DeleteWebhookEndpoint;
+
+/// Type representing GraphQL query to get the endpoints subscribed to an
+/// event.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/webhooks.graphql",
+    response_derives = "Debug"
+)]
+pub struct WebhookSubscribers;
+
+/// Type representing GraphQL mutation to add an event to the outbox.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/webhooks.graphql",
+    response_derives = "Debug"
+)]
+pub struct QueueWebhookDelivery;
+
+/// Type representing GraphQL query to get deliveries due to be tried.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/webhooks.graphql",
+    response_derives = "Debug,Clone"
+)]
+pub struct DueWebhookDeliveries;
+
+/// Type representing GraphQL mutation to log a delivery attempt.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/webhooks.graphql",
+    response_derives = "Debug"
+)]
+pub struct RecordWebhookAttempt;
+
+/// Type representing GraphQL mutation to retry a delivery.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/webhooks.graphql",
+    response_derives = "Debug"
+)]
+pub struct RetryWebhookDelivery;
+
+/// Type representing GraphQL query to get the latest delivery attempts.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/rcos/schema.json",
+    query_path = "graphql/rcos/webhooks.graphql",
+    response_derives = "Debug,Clone,Serialize"
+)]
+pub struct RecentWebhookAttempts;
+
+impl WebhookEndpoints {
+    /// Get every webhook endpoint.
+    pub async fn get(
+    ) -> Result<Vec<webhook_endpoints::WebhookEndpointsWebhookEndpoints>, TelescopeError> {
+        Ok(send_query::<Self>(webhook_endpoints::Variables {})
+            .await?
+            .webhook_endpoints)
+    }
+}
+
+impl CreateWebhookEndpoint {
+    /// Register an endpoint and subscribe it to events. Return its ID.
+    pub async fn execute(
+        url: String,
+        description: Option<String>,
+        secret: String,
+        events: Vec<&'static str>,
+    ) -> Result<i64, TelescopeError> {
+        let endpoint_id: i64 = send_query::<Self>(create_webhook_endpoint::Variables {
+            url,
+            description,
+            secret,
+            now: Utc::now(),
+        })
+        .await?
+        .insert_webhook_endpoints_one
+        .map(|endpoint| endpoint.endpoint_id)
+        .ok_or_else(|| TelescopeError::ise("Webhook endpoint creation did not return an ID."))?;
+
+        for event in events {
+            send_query::<AddWebhookSubscription>(add_webhook_subscription::Variables {
+                endpoint_id,
+                event: event.to_string(),
+            })
+            .await?;
+        }
+        return Ok(endpoint_id);
+    }
+}
+
+impl SetWebhookEndpointEnabled {
+    /// Enable or disable an endpoint. Return false if there is no such
+    /// endpoint.
+    pub async fn execute(endpoint_id: i64, enabled: bool) -> Result<bool, TelescopeError> {
+        Ok(send_query::<Self>(set_webhook_endpoint_enabled::Variables {
+            endpoint_id,
+            enabled,
+        })
+        .await?
+        .update_webhook_endpoints_by_pk
+        .is_some())
+    }
+}
+
+impl DeleteWebhookEndpoint {
+    /// Remove an endpoint. Return false if there is no such endpoint.
+    pub async fn execute(endpoint_id: i64) -> Result<bool, TelescopeError> {
+        Ok(
+            send_query::<Self>(delete_webhook_endpoint::Variables { endpoint_id })
+                .await?
+                .delete_webhook_endpoints_by_pk
+                .is_some(),
+        )
+    }
+}
+
+impl WebhookSubscribers {
+    /// Get the IDs of the enabled endpoints subscribed to an event.
+    pub async fn get(event: &str) -> Result<Vec<i64>, TelescopeError> {
+        Ok(send_query::<Self>(webhook_subscribers::Variables {
+            event: event.to_string(),
+        })
+        .await?
+        .webhook_endpoints
+        .into_iter()
+        .map(|endpoint| endpoint.endpoint_id)
+        .collect())
+    }
+}
+
+impl QueueWebhookDelivery {
+    /// Add an event to the outbox for an endpoint.
+    pub async fn execute(
+        endpoint_id: i64,
+        event: &str,
+        payload: String,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(queue_webhook_delivery::Variables {
+            endpoint_id,
+            event: event.to_string(),
+            payload,
+            now: Utc::now(),
+        })
+        .await
+        .map(|_| ())
+    }
+}
+
+impl DueWebhookDeliveries {
+    /// Get up to `limit` deliveries that are due to be tried.
+    pub async fn get(
+        limit: i64,
+    ) -> Result<Vec<due_webhook_deliveries::DueWebhookDeliveriesWebhookDeliveries>, TelescopeError>
+    {
+        Ok(send_query::<Self>(due_webhook_deliveries::Variables {
+            now: Utc::now(),
+            limit,
+        })
+        .await?
+        .webhook_deliveries)
+    }
+}
+
+impl RecordWebhookAttempt {
+    /// Log an attempt to send a delivery.
+    pub async fn execute(
+        variables: record_webhook_attempt::Variables,
+    ) -> Result<(), TelescopeError> {
+        send_query::<Self>(variables).await.map(|_| ())
+    }
+}
+
+impl RetryWebhookDelivery {
+    /// Put a delivery back in the outbox to be tried right away. Return false
+    /// if there is no such delivery, or it was already delivered.
+    pub async fn execute(delivery_id: i64) -> Result<bool, TelescopeError> {
+        Ok(send_query::<Self>(retry_webhook_delivery::Variables {
+            delivery_id,
+            now: Utc::now(),
+        })
+        .await?
+        .update_webhook_deliveries
+        .map_or(false, |updated| updated.affected_rows > 0))
+    }
+}
+
+impl RecentWebhookAttempts {
+    /// Get the latest `limit` delivery attempts.
+    pub async fn get(
+        limit: i64,
+    ) -> Result<
+        Vec<recent_webhook_attempts::RecentWebhookAttemptsWebhookDeliveryAttempts>,
+        TelescopeError,
+    > {
+        Ok(
+            send_query::<Self>(recent_webhook_attempts::Variables { limit })
+                .await?
+                .webhook_delivery_attempts,
+        )
+    }
+}
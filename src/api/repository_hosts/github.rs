@@ -0,0 +1,72 @@
+//! GitHub repository host.
+
+use super::{Contribution, RepositoryHost};
+use crate::api::github::repositories::contributions::{RepositoryCommits, RepositoryPullRequests};
+use crate::api::rcos::users::UserAccountType;
+use crate::error::TelescopeError;
+use chrono::{DateTime, Utc};
+use oauth2::AccessToken;
+use url::Url;
+
+/// Repositories on github.com, read through the GitHub GraphQL API.
+pub struct GitHubHost {
+    /// The token to authenticate API requests with.
+    token: AccessToken,
+}
+
+impl GitHubHost {
+    /// Create a GitHub host that authenticates with the given token.
+    pub fn new(token: AccessToken) -> Self {
+        Self { token }
+    }
+}
+
+/// Split a repository path into its owner and name.
+fn split_path(repository: &str) -> Result<(&str, &str), TelescopeError> {
+    let mut parts = repository.splitn(2, '/');
+    match (parts.next(), parts.next()) {
+        (Some(owner), Some(name)) => Ok((owner, name)),
+        _ => Err(TelescopeError::ise(format!(
+            "Malformed GitHub repository path: {}",
+            repository
+        ))),
+    }
+}
+
+#[async_trait::async_trait]
+impl RepositoryHost for GitHubHost {
+    fn account_type(&self) -> UserAccountType {
+        UserAccountType::GitHub
+    }
+
+    fn parse_url(&self, url: &Url) -> Option<String> {
+        // Only GitHub hosted repositories can be queried.
+        if url.host_str()? != "github.com" && url.host_str()? != "www.github.com" {
+            return None;
+        }
+
+        // The first two path segments are the owner and repository name.
+        let mut segments = url.path_segments()?.filter(|s| !s.is_empty());
+        let owner: &str = segments.next()?;
+        let name: &str = segments.next()?.trim_end_matches(".git");
+        return Some(format!("{}/{}", owner, name));
+    }
+
+    async fn commits(
+        &self,
+        repository: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Contribution>, TelescopeError> {
+        let (owner, name) = split_path(repository)?;
+        RepositoryCommits::get(&self.token, owner, name, since).await
+    }
+
+    async fn merged_pull_requests(
+        &self,
+        repository: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Contribution>, TelescopeError> {
+        let (owner, name) = split_path(repository)?;
+        RepositoryPullRequests::get(&self.token, owner, name, since).await
+    }
+}
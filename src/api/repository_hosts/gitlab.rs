@@ -0,0 +1,76 @@
+//! GitLab repository host.
+
+use super::{Contribution, RepositoryHost};
+use crate::api::gitlab;
+use crate::api::rcos::users::UserAccountType;
+use crate::env::GitLabConfig;
+use crate::error::TelescopeError;
+use chrono::{DateTime, Utc};
+use std::iter;
+use url::Url;
+
+/// Repositories on a GitLab instance, read through its REST API.
+pub struct GitLabHost {
+    config: GitLabConfig,
+    /// The host name repository URLs on this instance have.
+    host: Option<String>,
+}
+
+impl GitLabHost {
+    /// Create a GitLab host for a configured instance.
+    pub fn new(config: GitLabConfig) -> Self {
+        let host: Option<String> = Url::parse(config.url.as_str())
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string));
+        Self { config, host }
+    }
+}
+
+#[async_trait::async_trait]
+impl RepositoryHost for GitLabHost {
+    fn account_type(&self) -> UserAccountType {
+        UserAccountType::GitLab
+    }
+
+    fn parse_url(&self, url: &Url) -> Option<String> {
+        if url.host_str()? != self.host.as_deref()? {
+            return None;
+        }
+
+        // GitLab projects can be nested in groups, so the path is everything
+        // before the "/-/" separator used for pages within a project.
+        let path: &str = url.path().trim_matches('/');
+        let path: &str = path.split("/-/").next()?.trim_end_matches(".git");
+        // A project path has at least a namespace and a name.
+        return path.contains('/').then(|| path.to_string());
+    }
+
+    async fn commits(
+        &self,
+        repository: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Contribution>, TelescopeError> {
+        // GitLab does not link commits to user accounts, but push events
+        // carry the pusher and the number of commits pushed.
+        let events = gitlab::push_events(&self.config, repository, since).await?;
+        return Ok(events
+            .into_iter()
+            .flat_map(|event| {
+                let count = event.push_data.map(|push| push.commit_count).unwrap_or(0);
+                iter::repeat((event.author_id.to_string(), event.created_at)).take(count as usize)
+            })
+            .collect());
+    }
+
+    async fn merged_pull_requests(
+        &self,
+        repository: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Contribution>, TelescopeError> {
+        let merge_requests = gitlab::merged_requests(&self.config, repository, since).await?;
+        return Ok(merge_requests
+            .into_iter()
+            .filter_map(|mr| Some((mr.author.id.to_string(), mr.merged_at?)))
+            .collect());
+    }
+}
@@ -0,0 +1,76 @@
+//! Abstraction over the services that host project repositories, so that
+//! repository activity can be read the same way whether a project lives on
+//! GitHub or GitLab. The host is picked from each repository URL.
+
+use crate::api::rcos::users::UserAccountType;
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use chrono::{DateTime, Utc};
+use url::Url;
+
+mod github;
+mod gitlab;
+
+/// A contribution to a repository: the author's account ID on the repository
+/// host and when the contribution was made.
+pub type Contribution = (String, DateTime<Utc>);
+
+/// A service hosting repositories.
+#[async_trait::async_trait]
+pub trait RepositoryHost: Send + Sync {
+    /// The type of linked user account that holds author IDs on this host.
+    fn account_type(&self) -> UserAccountType;
+
+    /// Get the path of a repository on this host from its URL. Return `None`
+    /// if the URL is not a repository on this host.
+    fn parse_url(&self, url: &Url) -> Option<String>;
+
+    /// Get every commit to the repository since a given time.
+    async fn commits(
+        &self,
+        repository: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Contribution>, TelescopeError>;
+
+    /// Get every pull (or merge) request merged since a given time.
+    async fn merged_pull_requests(
+        &self,
+        repository: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Contribution>, TelescopeError>;
+}
+
+lazy_static! {
+    /// The repository hosts configured for this instance.
+    static ref HOSTS: Vec<Box<dyn RepositoryHost>> = configured_hosts();
+}
+
+/// Build the list of hosts that are configured.
+fn configured_hosts() -> Vec<Box<dyn RepositoryHost>> {
+    let config = global_config();
+    let mut hosts: Vec<Box<dyn RepositoryHost>> = Vec::new();
+
+    // GitHub's API requires a token.
+    if let Some(token) = config.github_credentials.api_token.clone() {
+        hosts.push(Box::new(github::GitHubHost::new(token)));
+    }
+
+    if let Some(gitlab) = config.gitlab.clone() {
+        hosts.push(Box::new(gitlab::GitLabHost::new(gitlab)));
+    }
+
+    return hosts;
+}
+
+/// Check if any repository host is configured.
+pub fn any_configured() -> bool {
+    !HOSTS.is_empty()
+}
+
+/// Find the configured host for a repository URL, along with the path of the
+/// repository on that host.
+pub fn resolve(url: &Url) -> Option<(&'static dyn RepositoryHost, String)> {
+    HOSTS
+        .iter()
+        .find_map(|host| host.parse_url(url).map(|path| (host.as_ref(), path)))
+}
@@ -0,0 +1,63 @@
+//! Video call providers that can create calls for remote meetings.
+
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use chrono::{DateTime, Utc};
+
+mod zoom;
+
+/// The details of a call to create.
+#[derive(Clone, Debug)]
+pub struct CallRequest {
+    /// The title of the call.
+    pub topic: String,
+    /// When the call starts.
+    pub start: DateTime<Utc>,
+    /// When the call ends.
+    pub end: DateTime<Utc>,
+    /// How the call repeats, for a series of meetings. `start` and `end` are
+    /// then the first meeting's.
+    pub repeat: Option<CallRepeat>,
+}
+
+/// How a call for a series of meetings repeats.
+#[derive(Copy, Clone, Debug)]
+pub struct CallRepeat {
+    /// The number of days between meetings.
+    pub interval_days: i64,
+    /// When the last meeting starts.
+    pub last_start: DateTime<Utc>,
+}
+
+/// A call created by a provider.
+#[derive(Clone, Debug)]
+pub struct Call {
+    /// The link participants join the call with.
+    pub join_url: String,
+    /// The key that lets someone claim host controls in the call, if the
+    /// provider has one.
+    pub host_key: Option<String>,
+}
+
+/// A service that can create video calls.
+#[async_trait::async_trait]
+pub trait Provider: Send + Sync {
+    /// The name of the service shown to users.
+    fn name(&self) -> &'static str;
+
+    /// Create a call.
+    async fn create_call(&self, request: &CallRequest) -> Result<Call, TelescopeError>;
+}
+
+lazy_static! {
+    /// The configured video call provider.
+    static ref PROVIDER: Option<Box<dyn Provider>> = global_config()
+        .zoom
+        .clone()
+        .map(|config| Box::new(zoom::Zoom::new(config)) as Box<dyn Provider>);
+}
+
+/// Get the configured video call provider, if there is one.
+pub fn provider() -> Option<&'static dyn Provider> {
+    PROVIDER.as_deref()
+}
@@ -0,0 +1,188 @@
+//! Zoom calls, created through a Server-to-Server OAuth app.
+//!
+//! See <https://developers.zoom.us/docs/internal-apps/s2s-oauth/>.
+
+use super::{Call, CallRequest, Provider};
+use crate::api::http::{self, Integration};
+use crate::env::ZoomConfig;
+use crate::error::TelescopeError;
+use crate::web::telescope_ua;
+use reqwest::header::USER_AGENT;
+use reqwest::Client;
+
+/// Zoom's OAuth token endpoint.
+const TOKEN_URL: &'static str = "https://zoom.us/oauth/token";
+
+/// The base URL of Zoom's REST API.
+const API_URL: &'static str = "https://api.zoom.us/v2";
+
+/// Zoom's meeting type for a call scheduled at a fixed time.
+const SCHEDULED_MEETING: i64 = 2;
+
+/// Zoom's meeting type for a call that repeats at fixed times.
+const RECURRING_MEETING: i64 = 8;
+
+/// Zoom's recurrence type for calls repeating every given number of days.
+const DAILY_RECURRENCE: i64 = 1;
+
+/// The format Zoom takes times in.
+const TIME_FORMAT: &'static str = "%Y-%m-%dT%H:%M:%SZ";
+
+/// Access token response.
+#[derive(Deserialize, Debug)]
+struct TokenResponse {
+    access_token: String,
+}
+
+/// The body of a request to create a meeting.
+#[derive(Serialize, Debug)]
+struct MeetingRequest<'a> {
+    topic: &'a str,
+    #[serde(rename = "type")]
+    kind: i64,
+    start_time: String,
+    timezone: &'static str,
+    duration: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recurrence: Option<Recurrence>,
+}
+
+/// How a recurring meeting repeats.
+#[derive(Serialize, Debug)]
+struct Recurrence {
+    #[serde(rename = "type")]
+    kind: i64,
+    repeat_interval: i64,
+    end_date_time: String,
+}
+
+/// The fields of a created meeting that telescope keeps.
+#[derive(Deserialize, Debug)]
+struct MeetingResponse {
+    join_url: String,
+}
+
+/// The fields of a Zoom user that telescope reads.
+#[derive(Deserialize, Debug)]
+struct UserResponse {
+    #[serde(default)]
+    host_key: Option<String>,
+}
+
+/// Video calls on Zoom.
+pub struct Zoom {
+    config: ZoomConfig,
+}
+
+impl Zoom {
+    /// Create a Zoom provider from the app config.
+    pub fn new(config: ZoomConfig) -> Self {
+        Self { config }
+    }
+
+    /// Convert a Zoom API failure into a telescope error.
+    fn error(err: reqwest::Error) -> TelescopeError {
+        error!("Error interacting with Zoom API: {}", err);
+        TelescopeError::GatewayError {
+            header: "Zoom Error".into(),
+            message: format!(
+                "Could not create a Zoom call for this meeting ({}). You can create one \
+                yourself and add its link to the meeting instead.",
+                err
+            ),
+        }
+    }
+
+    /// Get an access token for the app. Tokens last an hour, and calls are
+    /// created rarely enough that a new one is requested each time.
+    async fn access_token(&self, client: &Client) -> Result<String, TelescopeError> {
+        let response: TokenResponse = client
+            .post(TOKEN_URL)
+            .basic_auth(&self.config.client_id, Some(&self.config.client_secret))
+            .query(&[
+                ("grant_type", "account_credentials"),
+                ("account_id", self.config.account_id.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(Self::error)?
+            .error_for_status()
+            .map_err(Self::error)?
+            .json()
+            .await
+            .map_err(Self::error)?;
+
+        return Ok(response.access_token);
+    }
+}
+
+#[async_trait::async_trait]
+impl Provider for Zoom {
+    fn name(&self) -> &'static str {
+        "Zoom"
+    }
+
+    async fn create_call(&self, request: &CallRequest) -> Result<Call, TelescopeError> {
+        let client: Client = http::client(Integration::Zoom);
+        let token: String = self.access_token(&client).await?;
+
+        // A series gets one call that repeats until its last meeting.
+        let recurrence: Option<Recurrence> = request.repeat.map(|repeat| Recurrence {
+            kind: DAILY_RECURRENCE,
+            repeat_interval: repeat.interval_days,
+            end_date_time: repeat.last_start.format(TIME_FORMAT).to_string(),
+        });
+        let body = MeetingRequest {
+            topic: request.topic.as_str(),
+            kind: if recurrence.is_some() {
+                RECURRING_MEETING
+            } else {
+                SCHEDULED_MEETING
+            },
+            start_time: request.start.format(TIME_FORMAT).to_string(),
+            timezone: "UTC",
+            duration: (request.end - request.start).num_minutes().max(1),
+            recurrence,
+        };
+
+        let meeting: MeetingResponse = client
+            .post(format!("{}/users/{}/meetings", API_URL, self.config.user).as_str())
+            .bearer_auth(&token)
+            .header(USER_AGENT, telescope_ua())
+            .json(&body)
+            .send()
+            .await
+            .map_err(Self::error)?
+            .error_for_status()
+            .map_err(Self::error)?
+            .json()
+            .await
+            .map_err(Self::error)?;
+
+        // The host key belongs to the hosting user, not the meeting. Don't
+        // fail the call creation if it cannot be read.
+        let host_key: Option<String> = match client
+            .get(format!("{}/users/{}", API_URL, self.config.user).as_str())
+            .bearer_auth(&token)
+            .header(USER_AGENT, telescope_ua())
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+        {
+            Ok(response) => response
+                .json::<UserResponse>()
+                .await
+                .ok()
+                .and_then(|user| user.host_key),
+            Err(e) => {
+                warn!("Could not read Zoom host key: {}", e);
+                None
+            }
+        };
+
+        return Ok(Call {
+            join_url: meeting.join_url,
+            host_key,
+        });
+    }
+}
@@ -0,0 +1,99 @@
+//! Reading videos from a YouTube playlist through the YouTube Data API.
+//!
+//! See <https://developers.google.com/youtube/v3/docs/playlistItems/list>.
+
+use crate::api::http::{self, Integration};
+use crate::error::TelescopeError;
+use crate::web::telescope_ua;
+use chrono::{DateTime, Utc};
+use reqwest::header::USER_AGENT;
+
+/// The playlist items endpoint of the YouTube Data API.
+const PLAYLIST_ITEMS_URL: &'static str = "https://www.googleapis.com/youtube/v3/playlistItems";
+
+/// The most items the API returns in one page.
+const PAGE_SIZE: &'static str = "50";
+
+/// A page of playlist items.
+#[derive(Deserialize, Debug)]
+struct PlaylistItems {
+    items: Vec<PlaylistItem>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct PlaylistItem {
+    snippet: Snippet,
+    content_details: ContentDetails,
+}
+
+#[derive(Deserialize, Debug)]
+struct Snippet {
+    title: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ContentDetails {
+    video_id: String,
+    /// Missing for private and deleted videos.
+    #[serde(default)]
+    video_published_at: Option<DateTime<Utc>>,
+}
+
+/// A public video in a playlist.
+#[derive(Clone, Debug)]
+pub struct Video {
+    /// The watch page of the video.
+    pub url: String,
+    pub title: String,
+    pub published_at: DateTime<Utc>,
+}
+
+/// Convert a YouTube API failure into a telescope error.
+fn error(err: reqwest::Error) -> TelescopeError {
+    error!("Error interacting with YouTube API: {}", err);
+    TelescopeError::GatewayError {
+        header: "YouTube Error".into(),
+        message: format!("Could not read videos from YouTube ({}).", err),
+    }
+}
+
+/// Get the most recently added public videos of a playlist.
+pub async fn playlist_videos(
+    api_key: &str,
+    playlist_id: &str,
+) -> Result<Vec<Video>, TelescopeError> {
+    let page: PlaylistItems = http::client(Integration::YouTube)
+        .get(PLAYLIST_ITEMS_URL)
+        .header(USER_AGENT, telescope_ua())
+        .query(&[
+            ("part", "snippet,contentDetails"),
+            ("playlistId", playlist_id),
+            ("maxResults", PAGE_SIZE),
+            ("key", api_key),
+        ])
+        .send()
+        .await
+        .map_err(error)?
+        .error_for_status()
+        .map_err(error)?
+        .json()
+        .await
+        .map_err(error)?;
+
+    return Ok(page
+        .items
+        .into_iter()
+        .filter_map(|item| {
+            Some(Video {
+                url: format!(
+                    "https://www.youtube.com/watch?v={}",
+                    item.content_details.video_id
+                ),
+                title: item.snippet.title,
+                published_at: item.content_details.video_published_at?,
+            })
+        })
+        .collect());
+}
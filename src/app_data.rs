@@ -1,7 +1,13 @@
+use crate::env::global_config;
 use crate::templates::helpers::register_helpers;
 use handlebars::Handlebars;
 use std::sync::Arc;
 
+/// Templates embedded by the build script.
+mod embedded {
+    include!(concat!(env!("OUT_DIR"), "/templates.rs"));
+}
+
 lazy_static! {
     /// Lazy Static to store app data at runtime.
     static ref APP_DATA: Arc<AppData> = {
@@ -14,31 +20,47 @@ lazy_static! {
 pub struct AppData {
     /// The handlebars template registry.
     template_registry: Arc<Handlebars<'static>>,
+    /// The same registry without strict mode.
+    lenient_registry: Arc<Handlebars<'static>>,
 }
 
 impl AppData {
     /// Create new App Data object using the global static config.
     fn new() -> Self {
-        // Register handlebars templates
         let mut template_registry = Handlebars::new();
-        template_registry
-            .register_templates_directory(".hbs", "templates")
-            .map_err(|e| {
-                error!("Failed to properly register handlebars templates: {}", e);
-                e
-            })
-            .unwrap();
-
-        // We do not use handlebars strict mode anymore since it increasingly breaks templates
-        // without warning.
-        // template_registry.set_strict_mode(true);
+        if cfg!(debug_assertions) {
+            // Debug builds read templates from disk and reload them on every
+            // render, so edits show up without rebuilding.
+            template_registry.set_dev_mode(true);
+            template_registry
+                .register_templates_directory(".hbs", "templates")
+                .map_err(|e| {
+                    error!("Failed to properly register handlebars templates: {}", e);
+                    e
+                })
+                .unwrap();
+        } else {
+            // Release builds use the templates embedded by the build script.
+            for (name, source) in embedded::TEMPLATES.iter() {
+                template_registry
+                    .register_template_string(name, source)
+                    .expect("Embedded templates are checked by the build script.");
+            }
+        }
 
         // Register the helpers defined in the helpers module.
         register_helpers(&mut template_registry);
+
+        // Strict mode is off unless the config turns it on, since templates
+        // commonly leave out optional fields. Debug builds fall back to the
+        // lenient copy when a field is missing.
+        let lenient_registry = template_registry.clone();
+        template_registry.set_strict_mode(global_config().strict_templates);
         info!("Handlebars templates registered.");
 
         Self {
             template_registry: Arc::new(template_registry),
+            lenient_registry: Arc::new(lenient_registry),
         }
     }
 
@@ -51,4 +73,9 @@ impl AppData {
     pub fn get_handlebars_registry(&self) -> Arc<Handlebars<'static>> {
         self.template_registry.clone()
     }
+
+    /// Get an [`Arc`] reference to the template registry without strict mode.
+    pub fn get_lenient_registry(&self) -> Arc<Handlebars<'static>> {
+        self.lenient_registry.clone()
+    }
 }
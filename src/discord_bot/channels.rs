@@ -0,0 +1,404 @@
+//! Categories and channels for small groups and their projects.
+//!
+//! Every small group of the current semester gets a category on the RCOS
+//! server with a text and a voice channel in it, and each of its projects gets
+//! a text and a voice channel in the same category. Channels are private to
+//! staff and to the group's or project's role, if that has been generated.
+//!
+//! Generating is idempotent. Categories and channels the RCOS API already
+//! knows about are kept as long as they still exist on Discord; ones deleted
+//! on Discord are forgotten and made again. Before making one, a category or
+//! channel with the same name in the same place is looked for and adopted, so
+//! a run that failed partway does not leave duplicates behind.
+
+use crate::api::discord::global_discord_client;
+use crate::api::rcos::discord_associations::forget_channel::ForgetDiscordChannel;
+use crate::api::rcos::discord_associations::project::create_project_channel::CreateOneProjectChannel;
+use crate::api::rcos::discord_associations::small_group::create_small_group_category::CreateOneSmallGroupCategory;
+use crate::api::rcos::discord_associations::small_group::create_small_group_channel::CreateOneSmallGroupChannel;
+use crate::api::rcos::discord_associations::small_group::small_group_info::CurrSmallGroups;
+use crate::api::rcos::discord_associations::ChannelType;
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use serenity::model::channel::{
+    ChannelType as SerenityChannelType, GuildChannel, PermissionOverwrite, PermissionOverwriteType,
+};
+use serenity::model::guild::Role;
+use serenity::model::id::{ChannelId, GuildId, RoleId};
+use serenity::model::permissions::Permissions;
+use std::collections::HashSet;
+
+/// The longest name Discord allows for a category or channel.
+const MAX_NAME_LENGTH: usize = 100;
+
+/// Names of the roles that can see every generated category and channel, on
+/// top of the server's administrators.
+const STAFF_ROLES: [&'static str; 2] = ["Faculty Advisors", "Coordinators"];
+
+/// The kinds of Discord channels that are generated.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Display)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelKind {
+    #[display(fmt = "category")]
+    Category,
+    #[display(fmt = "text channel")]
+    Text,
+    #[display(fmt = "voice channel")]
+    Voice,
+}
+
+impl ChannelKind {
+    /// The Discord channel type of this kind.
+    fn serenity_type(self) -> SerenityChannelType {
+        match self {
+            ChannelKind::Category => SerenityChannelType::Category,
+            ChannelKind::Text => SerenityChannelType::Text,
+            ChannelKind::Voice => SerenityChannelType::Voice,
+        }
+    }
+}
+
+/// What generating did, or would do, for one category or channel.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelAction {
+    /// It is known and still exists.
+    Keep,
+    /// It was not known, but one with the right name was found and recorded.
+    Adopt,
+    /// It did not exist and was made.
+    Create,
+    /// It was known but deleted on Discord, so it was made again or adopted.
+    Replace,
+}
+
+/// One category or channel that generating went over.
+#[derive(Clone, Debug, Serialize)]
+pub struct ChannelChange {
+    /// The title of the small group or project it is for.
+    pub owner: String,
+    pub kind: ChannelKind,
+    pub name: String,
+    pub action: ChannelAction,
+    /// The ID on Discord, unless it is yet to be made.
+    pub channel_id: Option<String>,
+}
+
+/// Where a generated category or channel is recorded in the RCOS API.
+#[derive(Copy, Clone, Debug)]
+enum Slot {
+    GroupCategory(i64),
+    GroupChannel(i64, ChannelType),
+    ProjectChannel(i64, ChannelType),
+}
+
+impl Slot {
+    /// The kind of Discord channel that goes in this slot.
+    fn kind(self) -> ChannelKind {
+        match self {
+            Slot::GroupCategory(_) => ChannelKind::Category,
+            Slot::GroupChannel(_, kind) | Slot::ProjectChannel(_, kind) => match kind {
+                ChannelType::DiscordText => ChannelKind::Text,
+                ChannelType::DiscordVoice => ChannelKind::Voice,
+            },
+        }
+    }
+
+    /// Record a Discord category or channel in this slot.
+    async fn record(self, channel_id: ChannelId) -> Result<(), TelescopeError> {
+        let id: String = channel_id.0.to_string();
+        match self {
+            Slot::GroupCategory(group) => {
+                CreateOneSmallGroupCategory::execute(group, id).await?;
+            }
+            Slot::GroupChannel(group, kind) => {
+                CreateOneSmallGroupChannel::execute(group, id, kind).await?;
+            }
+            Slot::ProjectChannel(project, kind) => {
+                CreateOneProjectChannel::execute(project, id, kind).await?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+/// The name of a category or channel for a small group or project title.
+/// Discord lowercases text channel names and replaces spaces with dashes, so
+/// that is done here too to be able to find them again.
+fn channel_name(kind: ChannelKind, title: &str) -> String {
+    let name: String = match kind {
+        ChannelKind::Text => title
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join("-")
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+            .collect(),
+        _ => title.trim().to_string(),
+    };
+    return name.chars().take(MAX_NAME_LENGTH).collect();
+}
+
+/// The roles on a server that can see every generated category and channel,
+/// along with `@everyone`.
+pub fn staff_roles(roles: impl IntoIterator<Item = Role>) -> Vec<Role> {
+    roles
+        .into_iter()
+        .filter(|role| role.name == "@everyone" || STAFF_ROLES.contains(&role.name.as_str()))
+        .collect()
+}
+
+/// Permission overwrites making a category or channel private to the given
+/// staff roles and to members with `member_role`, if there is one. The
+/// `@everyone` role in `roles` is denied access.
+pub fn permission_overwrites(
+    member_role: Option<RoleId>,
+    roles: Vec<Role>,
+) -> Vec<PermissionOverwrite> {
+    let mut overwrite = Vec::new();
+
+    for role in roles {
+        // set channel to be private
+        if role.name == "@everyone" {
+            overwrite.push(PermissionOverwrite {
+                allow: Permissions::empty(),
+                deny: Permissions::READ_MESSAGES,
+                kind: PermissionOverwriteType::Role(role.id),
+            })
+            // Grant permission for Faculty Advisors, Coordinators and Sysadmins.
+        } else {
+            overwrite.push(PermissionOverwrite {
+                allow: Permissions::all(),
+                deny: Permissions::empty(),
+                kind: PermissionOverwriteType::Role(role.id),
+            });
+        }
+    }
+
+    // If roles for the project have been generated, also grant permission for users who have the roles.
+    if let Some(r) = member_role {
+        overwrite.push(PermissionOverwrite {
+            allow: Permissions::READ_MESSAGES
+                | Permissions::SEND_MESSAGES
+                | Permissions::EMBED_LINKS
+                | Permissions::ATTACH_FILES
+                | Permissions::READ_MESSAGE_HISTORY
+                | Permissions::CONNECT
+                | Permissions::SPEAK,
+            deny: Permissions::empty(),
+            kind: PermissionOverwriteType::Role(r),
+        });
+    }
+    return overwrite;
+}
+
+/// Parse a role ID saved in the RCOS API.
+fn parse_role(role_id: &str) -> Option<RoleId> {
+    role_id.parse::<u64>().ok().map(RoleId)
+}
+
+/// State of one generating run.
+struct Run {
+    guild: GuildId,
+    /// Categories and channels on the server when the run started.
+    existing: Vec<GuildChannel>,
+    /// IDs already kept, adopted, or made in this run, so two small groups or
+    /// projects with the same name don't end up sharing one.
+    claimed: HashSet<u64>,
+    staff: Vec<Role>,
+    dry_run: bool,
+    changes: Vec<ChannelChange>,
+}
+
+impl Run {
+    /// Make sure a slot has a category or channel on Discord. `known` is the ID
+    /// saved in the slot, if any. `parent` is the category to put a channel in;
+    /// it is only missing for channels of a category a dry run would make.
+    /// Returns the ID of the category or channel, unless a dry run would make
+    /// it.
+    async fn ensure(
+        &mut self,
+        slot: Slot,
+        owner: &str,
+        known: Option<&str>,
+        parent: Option<ChannelId>,
+        member_role: Option<RoleId>,
+    ) -> Result<Option<ChannelId>, TelescopeError> {
+        let kind: ChannelKind = slot.kind();
+        let name: String = channel_name(kind, owner);
+        let mut change = ChannelChange {
+            owner: owner.to_string(),
+            kind,
+            name: name.clone(),
+            action: ChannelAction::Create,
+            channel_id: None,
+        };
+
+        if let Some(known) = known {
+            let still_there: bool = self
+                .existing
+                .iter()
+                .any(|channel| channel.id.0.to_string() == known);
+            if still_there {
+                change.action = ChannelAction::Keep;
+                change.channel_id = Some(known.to_string());
+                self.changes.push(change);
+                let id: u64 = known.parse().unwrap_or_default();
+                self.claimed.insert(id);
+                return Ok(Some(ChannelId(id)));
+            }
+
+            // Deleted on Discord. Forget it so it can be replaced.
+            change.action = ChannelAction::Replace;
+            if !self.dry_run {
+                ForgetDiscordChannel::execute(known.to_string()).await?;
+            }
+        }
+
+        // Channels of a category that is yet to be made can't be adopted.
+        let adoptable: Option<ChannelId> = if kind == ChannelKind::Category || parent.is_some() {
+            self.existing
+                .iter()
+                .find(|channel| {
+                    channel.kind == kind.serenity_type()
+                        && channel.name == name
+                        && channel.category_id == parent
+                        && !self.claimed.contains(&channel.id.0)
+                })
+                .map(|channel| channel.id)
+        } else {
+            None
+        };
+
+        let id: Option<ChannelId> = match adoptable {
+            Some(id) => {
+                if change.action == ChannelAction::Create {
+                    change.action = ChannelAction::Adopt;
+                }
+                Some(id)
+            }
+
+            None if self.dry_run => None,
+
+            None => {
+                let overwrites = permission_overwrites(member_role, self.staff.clone());
+                let created: GuildChannel = self
+                    .guild
+                    .create_channel(global_discord_client(), |c| {
+                        c.name(&name)
+                            .kind(kind.serenity_type())
+                            .permissions(overwrites);
+                        if let Some(parent) = parent {
+                            c.category(parent);
+                        }
+                        c
+                    })
+                    .await
+                    .map_err(TelescopeError::serenity_error)?;
+                Some(created.id)
+            }
+        };
+
+        if let Some(id) = id {
+            if !self.dry_run {
+                slot.record(id).await?;
+            }
+            self.claimed.insert(id.0);
+            change.channel_id = Some(id.0.to_string());
+        }
+        self.changes.push(change);
+        return Ok(id);
+    }
+}
+
+/// Generate the categories and channels of every small group of the current
+/// semester and their projects on the RCOS server. With `dry_run` set, nothing
+/// is changed, and the result is what would be done.
+pub async fn generate(dry_run: bool) -> Result<Vec<ChannelChange>, TelescopeError> {
+    let guild = GuildId(global_config().discord_config.rcos_guild_id());
+    let existing: Vec<GuildChannel> = guild
+        .channels(global_discord_client())
+        .await
+        .map_err(TelescopeError::serenity_error)?
+        .into_values()
+        .collect();
+    let staff: Vec<Role> = staff_roles(
+        guild
+            .roles(global_discord_client())
+            .await
+            .map_err(TelescopeError::serenity_error)?
+            .into_values(),
+    );
+
+    let mut run = Run {
+        guild,
+        existing,
+        claimed: HashSet::new(),
+        staff,
+        dry_run,
+        changes: Vec::new(),
+    };
+
+    for group in CurrSmallGroups::get(0, None).await?.small_groups {
+        let group_id: i64 = group.small_group_id;
+        let group_role: Option<RoleId> = group
+            .small_group_role
+            .as_ref()
+            .and_then(|role| parse_role(&role.role_id));
+
+        let category: Option<ChannelId> = run
+            .ensure(
+                Slot::GroupCategory(group_id),
+                &group.title,
+                group
+                    .small_group_categories
+                    .first()
+                    .map(|category| category.category_id.as_str()),
+                None,
+                group_role,
+            )
+            .await?;
+
+        for kind in [ChannelType::DiscordText, ChannelType::DiscordVoice] {
+            let known: Option<&str> = group
+                .small_group_channels
+                .iter()
+                .find(|channel| channel.kind == kind)
+                .map(|channel| channel.channel_id.as_str());
+            run.ensure(
+                Slot::GroupChannel(group_id, kind),
+                &group.title,
+                known,
+                category,
+                group_role,
+            )
+            .await?;
+        }
+
+        for project in group.small_group_projects.iter().map(|p| &p.project) {
+            let project_role: Option<RoleId> = project
+                .project_role
+                .as_ref()
+                .and_then(|role| parse_role(&role.role_id));
+
+            for kind in [ChannelType::DiscordText, ChannelType::DiscordVoice] {
+                let known: Option<&str> = project
+                    .project_channels
+                    .iter()
+                    .find(|channel| channel.kind == kind)
+                    .map(|channel| channel.channel_id.as_str());
+                run.ensure(
+                    Slot::ProjectChannel(project.project_id, kind),
+                    &project.title,
+                    known,
+                    category,
+                    project_role,
+                )
+                .await?;
+            }
+        }
+    }
+
+    return Ok(run.changes);
+}
@@ -12,7 +12,7 @@ use crate::api::rcos::discord_associations::small_group::{
 };
 use crate::api::rcos::discord_associations::ChannelType;
 
-use crate::discord_bot::commands::InteractionResult;
+use crate::discord_bot::commands::{respond, InteractionResult};
 use crate::env::global_config;
 use serenity::builder::{CreateApplicationCommand, CreateApplicationCommandOption, CreateEmbed};
 use serenity::client::Context;
@@ -20,14 +20,11 @@ use serenity::model::channel::ChannelType as SerenityChannelType;
 use serenity::model::guild::Role;
 use serenity::model::id::ChannelId;
 use serenity::model::id::{GuildId, RoleId};
+use serenity::model::interactions::application_command::ApplicationCommandOptionType;
 use serenity::model::interactions::application_command::{
     ApplicationCommandInteraction, ApplicationCommandInteractionDataOptionValue,
 };
-use serenity::model::interactions::{
-    application_command::ApplicationCommandOptionType, InteractionResponseType,
-};
 use serenity::model::permissions::Permissions;
-use serenity::model::prelude::InteractionApplicationCommandCallbackDataFlags;
 use serenity::utils::Color;
 use serenity::Result as SerenityResult;
 
@@ -53,28 +50,16 @@ async fn interaction_error(
     ctx: &Context,
     interaction: &ApplicationCommandInteraction,
 ) -> SerenityResult<()> {
-    return interaction
-        .create_interaction_response(&ctx.http, |create_response| {
-            create_response
-                .kind(InteractionResponseType::ChannelMessageWithSource)
-                .interaction_response_data(|rdata| {
-                    rdata
-                        // Do not allow any mentions
-                        .allowed_mentions(|am| am.empty_parse())
-                        // Use the ephemeral flag to mark the response as only visible to the user who invoked it.
-                        .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
-                        .create_embed(|embed| {
-                            // Add common attributes
-                            embed_common(embed)
-                                .color(ERROR_COLOR)
-                                .title(error_title)
-                                .description(error_description)
-                                // Include the error as a field of the embed.
-                                .field("Error Message", err, false)
-                        })
-                })
-        })
-        .await;
+    return respond(ctx, interaction, |embed| {
+        // Add common attributes
+        embed_common(embed)
+            .color(ERROR_COLOR)
+            .title(error_title)
+            .description(error_description)
+            // Include the error as a field of the embed.
+            .field("Error Message", err, false)
+    })
+    .await;
 }
 
 // Return success for interaction
@@ -83,23 +68,11 @@ async fn interaction_success(
     interaction: &ApplicationCommandInteraction,
     description: &str,
 ) -> SerenityResult<()> {
-    return interaction
-        .create_interaction_response(&ctx.http, |create_response| {
-            create_response
-                .kind(InteractionResponseType::ChannelMessageWithSource)
-                .interaction_response_data(|rdata| {
-                    rdata
-                        // Do not allow any mentions
-                        .allowed_mentions(|am| am.empty_parse())
-                        // Use the ephemeral flag to mark the response as only visible to the user who invoked it.
-                        .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
-                        .create_embed(|embed| {
-                            // Add common attributes
-                            embed_common(embed).title("OK").description(description)
-                        })
-                })
-        })
-        .await;
+    return respond(ctx, interaction, |embed| {
+        // Add common attributes
+        embed_common(embed).title("OK").description(description)
+    })
+    .await;
 }
 
 // Get roles information about @everyone, Faculty Advisors and Coordinator in the guilds.
@@ -317,28 +290,16 @@ async fn handle(ctx: &Context, interaction: &ApplicationCommandInteraction) -> S
             .unwrap()
             .administrator()
     {
-        return interaction
-            .create_interaction_response(&ctx.http, |create_response| {
-                create_response
-                    .kind(InteractionResponseType::ChannelMessageWithSource)
-                    .interaction_response_data(|rdata| {
-                        rdata
-                            // Do not allow any mentions
-                            .allowed_mentions(|am| am.empty_parse())
-                            // Use the ephemeral flag to mark the response as only visible to the user who invoked it.
-                            .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
-                            .create_embed(|embed| {
-                                // Add common attributes
-                                embed_common(embed)
-                                    .color(ERROR_COLOR)
-                                    .title("Permission Error")
-                                    .description("You need Coordinator/Faculty Advisor role.")
-                                    // Include the error as a field of the embed.
-                                    .field("Error Message", "Permission Error", false)
-                            })
-                    })
-            })
-            .await;
+        return respond(ctx, interaction, |embed| {
+            // Add common attributes
+            embed_common(embed)
+                .color(ERROR_COLOR)
+                .title("Permission Error")
+                .description("You need Coordinator/Faculty Advisor role.")
+                // Include the error as a field of the embed.
+                .field("Error Message", "Permission Error", false)
+        })
+        .await;
     }
     match subcommand_group {
         _ if subcommand_group == SUBCOMMAND_GROUP[0] => {
@@ -384,28 +345,16 @@ async fn handle(ctx: &Context, interaction: &ApplicationCommandInteraction) -> S
             }
         }
         _ => {
-            return interaction
-                .create_interaction_response(&ctx.http, |create_response| {
-                    create_response
-                        .kind(InteractionResponseType::ChannelMessageWithSource)
-                        .interaction_response_data(|rdata| {
-                            rdata
-                                // Do not allow any mentions
-                                .allowed_mentions(|am| am.empty_parse())
-                                // Use the ephemeral flag to mark the response as only visible to the user who invoked it.
-                                .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
-                                .create_embed(|embed| {
-                                    // Add common attributes
-                                    embed_common(embed)
-                                        .color(ERROR_COLOR)
-                                        .title("Discord Error")
-                                        .description("Wrong option name.")
-                                        // Include the error as a field of the embed.
-                                        .field("Error Message", "Option Error", false)
-                                })
-                        })
-                })
-                .await;
+            return respond(ctx, interaction, |embed| {
+                // Add common attributes
+                embed_common(embed)
+                    .color(ERROR_COLOR)
+                    .title("Discord Error")
+                    .description("Wrong option name.")
+                    // Include the error as a field of the embed.
+                    .field("Error Message", "Option Error", false)
+            })
+            .await;
         }
     }
 }
@@ -8,22 +8,18 @@ use crate::api::rcos::discord_associations::small_group::{
     create_small_group_category, create_small_group_role, small_group_info,
 };
 use crate::api::rcos::discord_associations::ChannelType;
-use crate::discord_bot::commands::InteractionResult;
+use crate::discord_bot::channels::{permission_overwrites, staff_roles};
+use crate::discord_bot::commands::{respond, InteractionResult};
 use crate::env::global_config;
 use serenity::builder::{CreateApplicationCommand, CreateApplicationCommandOption, CreateEmbed};
 use serenity::client::Context;
-use serenity::model::channel::{
-    ChannelType as SerenityChannelType, PermissionOverwrite, PermissionOverwriteType,
-};
+use serenity::model::channel::ChannelType as SerenityChannelType;
 use serenity::model::guild::Role;
 use serenity::model::id::ChannelId;
 use serenity::model::id::{GuildId, RoleId};
 use serenity::model::interactions::application_command::ApplicationCommandInteraction;
-use serenity::model::interactions::{
-    application_command::ApplicationCommandOptionType, InteractionResponseType,
-};
+use serenity::model::interactions::application_command::ApplicationCommandOptionType;
 use serenity::model::permissions::Permissions;
-use serenity::model::prelude::InteractionApplicationCommandCallbackDataFlags;
 use serenity::utils::Color;
 use serenity::Result as SerenityResult;
 
@@ -40,46 +36,6 @@ pub fn has_permission(invoker: &RoleId, roles: &Vec<Role>) -> bool {
     })
 }
 
-// Grant permission for certain users
-fn generate_permission(project_role: Option<RoleId>, roles: Vec<Role>) -> Vec<PermissionOverwrite> {
-    let mut overwrite = Vec::new();
-    // set channel to be private
-
-    for role in roles {
-        // set channel to be private
-        if role.name == "@everyone" {
-            overwrite.push(PermissionOverwrite {
-                allow: Permissions::empty(),
-                deny: Permissions::READ_MESSAGES,
-                kind: PermissionOverwriteType::Role(role.id),
-            })
-            // Grant permission for Faculty Advisors, Coordinators and Sysadmins.
-        } else {
-            overwrite.push(PermissionOverwrite {
-                allow: Permissions::all(),
-                deny: Permissions::empty(),
-                kind: PermissionOverwriteType::Role(role.id),
-            });
-        }
-    }
-
-    // If roles for the project have been generated, also grant permission for users who have the roles.
-    if let Some(r) = project_role {
-        overwrite.push(PermissionOverwrite {
-            allow: Permissions::READ_MESSAGES
-                | Permissions::SEND_MESSAGES
-                | Permissions::EMBED_LINKS
-                | Permissions::ATTACH_FILES
-                | Permissions::READ_MESSAGE_HISTORY
-                | Permissions::CONNECT
-                | Permissions::SPEAK,
-            deny: Permissions::empty(),
-            kind: PermissionOverwriteType::Role(r),
-        });
-    }
-    return overwrite;
-}
-
 // Return error for interaction
 async fn interaction_error(
     error_title: &str,
@@ -88,28 +44,16 @@ async fn interaction_error(
     ctx: &Context,
     interaction: &ApplicationCommandInteraction,
 ) -> SerenityResult<()> {
-    return interaction
-        .create_interaction_response(&ctx.http, |create_response| {
-            create_response
-                .kind(InteractionResponseType::ChannelMessageWithSource)
-                .interaction_response_data(|rdata| {
-                    rdata
-                        // Do not allow any mentions
-                        .allowed_mentions(|am| am.empty_parse())
-                        // Use the ephemeral flag to mark the response as only visible to the user who invoked it.
-                        .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
-                        .create_embed(|embed| {
-                            // Add common attributes
-                            embed_common(embed)
-                                .color(ERROR_COLOR)
-                                .title(error_title)
-                                .description(error_description)
-                                // Include the error as a field of the embed.
-                                .field("Error Message", err, false)
-                        })
-                })
-        })
-        .await;
+    return respond(ctx, interaction, |embed| {
+        // Add common attributes
+        embed_common(embed)
+            .color(ERROR_COLOR)
+            .title(error_title)
+            .description(error_description)
+            // Include the error as a field of the embed.
+            .field("Error Message", err, false)
+    })
+    .await;
 }
 
 // Return success for interaction
@@ -118,40 +62,22 @@ async fn interaction_success(
     interaction: &ApplicationCommandInteraction,
     description: &str,
 ) -> SerenityResult<()> {
-    return interaction
-        .create_interaction_response(&ctx.http, |create_response| {
-            create_response
-                .kind(InteractionResponseType::ChannelMessageWithSource)
-                .interaction_response_data(|rdata| {
-                    rdata
-                        // Do not allow any mentions
-                        .allowed_mentions(|am| am.empty_parse())
-                        // Use the ephemeral flag to mark the response as only visible to the user who invoked it.
-                        .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
-                        .create_embed(|embed| {
-                            // Add common attributes
-                            embed_common(embed).title("OK").description(description)
-                        })
-                })
-        })
-        .await;
+    return respond(ctx, interaction, |embed| {
+        // Add common attributes
+        embed_common(embed).title("OK").description(description)
+    })
+    .await;
 }
 
 // Get roles information about @everyone, Faculty Advisors and Coordinator in the guilds.
 async fn get_roles(ctx: &Context) -> Vec<Role> {
-    GuildId(global_config().discord_config.rcos_guild_id())
-        .roles(&ctx)
-        .await
-        .unwrap()
-        .into_values()
-        .collect::<Vec<Role>>()
-        .into_iter()
-        .filter(|role| {
-            role.name == "@everyone"
-                || role.name == "Faculty Advisors"
-                || role.name == "Coordinators"
-        })
-        .collect()
+    staff_roles(
+        GuildId(global_config().discord_config.rcos_guild_id())
+            .roles(&ctx)
+            .await
+            .unwrap()
+            .into_values(),
+    )
 }
 
 // Build the option for the /generate command.
@@ -228,28 +154,16 @@ async fn handle(ctx: &Context, interaction: &ApplicationCommandInteraction) -> S
             .unwrap()
             .administrator()
     {
-        return interaction
-            .create_interaction_response(&ctx.http, |create_response| {
-                create_response
-                    .kind(InteractionResponseType::ChannelMessageWithSource)
-                    .interaction_response_data(|rdata| {
-                        rdata
-                            // Do not allow any mentions
-                            .allowed_mentions(|am| am.empty_parse())
-                            // Use the ephemeral flag to mark the response as only visible to the user who invoked it.
-                            .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
-                            .create_embed(|embed| {
-                                // Add common attributes
-                                embed_common(embed)
-                                    .color(ERROR_COLOR)
-                                    .title("Permission Error")
-                                    .description("You need Coordinator/Faculty Advisor role.")
-                                    // Include the error as a field of the embed.
-                                    .field("Error Message", "Permission Error", false)
-                            })
-                    })
-            })
-            .await;
+        return respond(ctx, interaction, |embed| {
+            // Add common attributes
+            embed_common(embed)
+                .color(ERROR_COLOR)
+                .title("Permission Error")
+                .description("You need Coordinator/Faculty Advisor role.")
+                // Include the error as a field of the embed.
+                .field("Error Message", "Permission Error", false)
+        })
+        .await;
     }
     match option_name {
         _ if option_name == OPTION_NAME[0] => {
@@ -296,28 +210,16 @@ async fn handle(ctx: &Context, interaction: &ApplicationCommandInteraction) -> S
         }
         _ if option_name == OPTION_NAME[3] => handle_generate_all(ctx, interaction).await,
         _ => {
-            return interaction
-                .create_interaction_response(&ctx.http, |create_response| {
-                    create_response
-                        .kind(InteractionResponseType::ChannelMessageWithSource)
-                        .interaction_response_data(|rdata| {
-                            rdata
-                                // Do not allow any mentions
-                                .allowed_mentions(|am| am.empty_parse())
-                                // Use the ephemeral flag to mark the response as only visible to the user who invoked it.
-                                .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
-                                .create_embed(|embed| {
-                                    // Add common attributes
-                                    embed_common(embed)
-                                        .color(ERROR_COLOR)
-                                        .title("Option Error")
-                                        .description("Wrong option name .")
-                                        // Include the error as a field of the embed.
-                                        .field("Error Message", "Option Error", false)
-                                })
-                        })
-                })
-                .await;
+            return respond(ctx, interaction, |embed| {
+                // Add common attributes
+                embed_common(embed)
+                    .color(ERROR_COLOR)
+                    .title("Option Error")
+                    .description("Wrong option name .")
+                    // Include the error as a field of the embed.
+                    .field("Error Message", "Option Error", false)
+            })
+            .await;
         }
     }
 }
@@ -383,9 +285,9 @@ async fn handle_generate_channels(
                 if small_group_project.project.project_channels.is_empty() {
                     // Generate permission for certain groups for the channel.
                     let overwrite = if let None = small_group_project.project.project_role {
-                        generate_permission(None, get_roles(ctx).await)
+                        permission_overwrites(None, get_roles(ctx).await)
                     } else {
-                        generate_permission(
+                        permission_overwrites(
                             Some(RoleId(
                                 small_group_project
                                     .project
@@ -755,9 +657,9 @@ async fn handle_generate_categories(
         if small_group.small_group_categories.is_empty() {
             // Generate permission for certain groups for the channel.
             let overwrite = if let true = small_group.small_group_role.is_none() {
-                generate_permission(None, get_roles(ctx).await)
+                permission_overwrites(None, get_roles(ctx).await)
             } else {
-                generate_permission(
+                permission_overwrites(
                     Some(RoleId(
                         small_group
                             .small_group_role
@@ -1,12 +1,25 @@
 //! Telescope's discord bot commands.
+//!
+//! Discord only waits 3 seconds for a response to a slash command, which
+//! commands that call the RCOS API can easily miss. The dispatcher defers
+//! every command as soon as it arrives and then gives the handler up to
+//! [`HANDLER_TIMEOUT`] to fill in the response with [`respond`]. Handlers that
+//! fail or run out of time get an error embed in their place.
 
+use crate::discord_bot::status;
+use actix::clock::delay_for;
 use dashmap::DashMap;
-use futures::future::BoxFuture;
-use serenity::builder::CreateApplicationCommand;
+use futures::future::{self, BoxFuture, Either};
+use serenity::builder::{CreateApplicationCommand, CreateEmbed};
 use serenity::client::Context;
-use serenity::model::guild::Guild;
+use serenity::http::Http;
+use serenity::model::id::GuildId;
 use serenity::model::interactions::application_command::ApplicationCommand;
 use serenity::model::interactions::application_command::ApplicationCommandInteraction;
+use serenity::model::interactions::InteractionResponseType;
+use serenity::model::prelude::InteractionApplicationCommandCallbackDataFlags;
+use serenity::utils::Color;
+use std::time::Duration;
 
 mod associate;
 mod generate;
@@ -19,6 +32,13 @@ type InteractionResult<'a> = BoxFuture<'a, serenity::Result<()>>;
 pub type InteractionHandler =
     for<'a> fn(&'a Context, &'a ApplicationCommandInteraction) -> InteractionResult<'a>;
 
+/// How long a handler has to respond to a deferred command. Discord keeps
+/// interaction tokens valid for 15 minutes, so this stays well under that.
+const HANDLER_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The embed color of responses to commands that failed or timed out.
+const ERROR_COLOR: Color = Color::new(0xDC3545); // bootstrap 4 error color
+
 /// Command builder type. These builder function all act on serenity models
 /// and add the necessary info to them for each command.
 type CommandBuilder = fn(&mut CreateApplicationCommand) -> &mut CreateApplicationCommand;
@@ -81,31 +101,164 @@ pub fn get_handler(command_name: &str) -> Option<InteractionHandler> {
         .map(|entry| *entry.value())
 }
 
+/// Fill in the response to a command with an embed. Every command is deferred
+/// before its handler is called, so handlers respond by editing the deferred
+/// response rather than creating a new one.
+pub async fn respond<F>(
+    ctx: &Context,
+    interaction: &ApplicationCommandInteraction,
+    embed: F,
+) -> serenity::Result<()>
+where
+    F: FnOnce(&mut CreateEmbed) -> &mut CreateEmbed,
+{
+    return interaction
+        .edit_original_interaction_response(&ctx.http, |response| response.create_embed(embed))
+        .await
+        .map(|_| ());
+}
+
+/// Respond to a command that failed or ran out of time with an error embed.
+async fn respond_with_error(
+    ctx: &Context,
+    interaction: &ApplicationCommandInteraction,
+    title: &str,
+    description: &str,
+) {
+    respond(ctx, interaction, |embed| {
+        embed
+            .color(ERROR_COLOR)
+            .title(title)
+            .description(description)
+            .footer(|footer| footer.text(format!("Telescope {}", env!("CARGO_PKG_VERSION"))))
+    })
+    .await
+    .unwrap_or_else(|err| {
+        error!(
+            "Could not send error response to '/{}': {}",
+            interaction.data.name, err
+        );
+    });
+}
+
+/// Defer a command, then run its handler and make sure the user gets a
+/// response even if the handler fails or hangs.
+pub async fn dispatch(
+    ctx: &Context,
+    interaction: &ApplicationCommandInteraction,
+    handler: InteractionHandler,
+) {
+    let command_name: &str = interaction.data.name.as_str();
+
+    // Tell Discord a response is coming. This has to happen within 3 seconds
+    // of the command being used. Responses are only visible to the user who
+    // used the command.
+    let deferred = interaction
+        .create_interaction_response(&ctx.http, |create_response| {
+            create_response
+                .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                .interaction_response_data(|rdata| {
+                    rdata.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
+                })
+        })
+        .await;
+
+    if let Err(err) = deferred {
+        error!("Could not defer '/{}': {}", command_name, err);
+        return;
+    }
+
+    // Race the handler against the timeout.
+    let timeout = Box::pin(delay_for(HANDLER_TIMEOUT));
+    match future::select(handler(ctx, interaction), timeout).await {
+        Either::Left((Ok(()), _)) => {}
+        Either::Left((Err(err), _)) => {
+            error!("'/{}' handler returned an error: {}", command_name, err);
+            respond_with_error(
+                ctx,
+                interaction,
+                "Something Went Wrong",
+                "Telescope could not finish this command. Please try again, and report \
+                this on Telescope's GitHub if it keeps happening.",
+            )
+            .await;
+        }
+        Either::Right(_) => {
+            error!(
+                "'/{}' handler did not respond within {} seconds.",
+                command_name,
+                HANDLER_TIMEOUT.as_secs()
+            );
+            respond_with_error(
+                ctx,
+                interaction,
+                "Timed Out",
+                "Telescope took too long to finish this command. It may still finish in the \
+                background -- check before trying again.",
+            )
+            .await;
+        }
+    }
+}
+
+/// Build the JSON body Discord expects to create a command.
+fn command_json(cmd: &Command) -> serde_json::Value {
+    // Create the default command application command object with no fields.
+    let mut command_builder: CreateApplicationCommand = CreateApplicationCommand::default();
+    // Populate the objects fields using the builder method for this command.
+    (cmd.builder)(&mut command_builder);
+    // Convert serenity's hashmap to a JSON map.
+    let json_map = serenity::utils::hashmap_to_json_map(command_builder.0);
+    // And put that map in a JSON value.
+    return serde_json::Value::Object(json_map);
+}
+
 /// Register all telescope slash command for a whitelisted guild.
-pub async fn register_commands_for_guild(ctx: &mut Context, guild: &Guild) -> serenity::Result<()> {
+pub async fn register_commands_for_guild(
+    http: &Http,
+    guild_id: GuildId,
+    guild_name: &str,
+) -> serenity::Result<()> {
     // Register each command to the whitelisted Guild ID.
+    let mut registered: Vec<String> = Vec::with_capacity(COMMANDS.len());
 
     for cmd in COMMANDS {
-        // Create the default command application command object with no fields.
-        let mut command_builder: CreateApplicationCommand = CreateApplicationCommand::default();
-        // Populate the objects fields using the builder method for this command.
-        (cmd.builder)(&mut command_builder);
-        // Convert serenity's hashmap to a JSON map.
-        let json_map = serenity::utils::hashmap_to_json_map(command_builder.0);
-        // And put that map in a JSON value.
-        let json_value = serde_json::Value::Object(json_map);
-
         // Send the HTTP request to create (or update) the guild command.
-        let created: ApplicationCommand = ctx
-            .http
-            .create_guild_application_command(*guild.id.as_u64(), &json_value)
+        let created: ApplicationCommand = http
+            .create_guild_application_command(guild_id.0, &command_json(cmd))
             .await?;
 
         info!(
             "Registered '/{}' command for '{}' guild (command ID: {}) (guild ID: {})",
-            created.name, guild.name, created.id, guild.id
+            created.name, guild_name, created.id, guild_id
+        );
+        registered.push(created.name);
+    }
+
+    // Show the registered commands on the admin panel.
+    status::commands_registered(guild_id.0, registered);
+    return Ok(());
+}
+
+/// Register all telescope slash commands globally, on every guild the bot is
+/// in. Discord can take up to an hour to make changes to global commands
+/// visible.
+pub async fn register_global_commands(http: &Http) -> serenity::Result<()> {
+    let mut registered: Vec<String> = Vec::with_capacity(COMMANDS.len());
+
+    for cmd in COMMANDS {
+        // Create (or update) the global command.
+        let created: ApplicationCommand = http
+            .create_global_application_command(&command_json(cmd))
+            .await?;
+
+        info!(
+            "Registered global '/{}' command (command ID: {})",
+            created.name, created.id
         );
+        registered.push(created.name);
     }
 
+    status::global_commands_registered(registered);
     return Ok(());
 }
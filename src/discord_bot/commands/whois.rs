@@ -1,17 +1,18 @@
 //! Discord slash command to get information about a user.
 
+use crate::api::rcos::users::discord_whois::discord_who_is::ResponseData;
 use crate::api::rcos::users::discord_whois::DiscordWhoIs;
-use crate::discord_bot::commands::InteractionResult;
+use crate::api::rcos::users::display::UserDisplay;
+use crate::discord_bot::commands::{respond, InteractionResult};
+use crate::discord_bot::embeds;
 use crate::env::global_config;
+use crate::error::TelescopeError;
+use crate::templates::Template;
 use serenity::builder::{CreateApplicationCommand, CreateApplicationCommandOption, CreateEmbed};
 use serenity::client::Context;
 use serenity::model::interactions::application_command::ApplicationCommandInteraction;
-use serenity::model::interactions::{
-    application_command::ApplicationCommandOptionType, InteractionResponseType,
-};
-use serenity::model::prelude::InteractionApplicationCommandCallbackDataFlags;
-use serenity::utils::Color;
-use serenity::Result as SerenityResult;
+use serenity::model::interactions::application_command::ApplicationCommandOptionType;
+use serenity::{Error as SerenityError, Result as SerenityResult};
 
 /// The name of this slash command.
 pub const COMMAND_NAME: &'static str = "whois";
@@ -19,8 +20,11 @@ pub const COMMAND_NAME: &'static str = "whois";
 /// The name of the only option available on this command.
 pub const OPTION_NAME: &'static str = "user";
 
-/// The embed color of /whois error responses.
-pub const ERROR_COLOR: Color = Color::new(0xDC3545); // bootstrap 4 error color
+/// The embed template for /whois responses.
+const TEMPLATE: &'static str = "discord/whois";
+
+/// The embed template for /whois responses when the RCOS API fails.
+const ERROR_TEMPLATE: &'static str = "discord/whois_error";
 
 /// Build the option for the /whois command.
 fn whois_option(obj: &mut CreateApplicationCommandOption) -> &mut CreateApplicationCommandOption {
@@ -80,83 +84,20 @@ async fn handle(ctx: &Context, interaction: &ApplicationCommandInteraction) -> S
             err
         });
 
-    // Respond with an embed indicating an error on RCOS API error.
-    if let Err(err) = rcos_api_response {
-        return interaction
-            .create_interaction_response(&ctx.http, |create_response| {
-                create_response
-                    // Sent the response to be a message
-                    .kind(InteractionResponseType::ChannelMessageWithSource)
-                    // Set the content of the message.
-                    .interaction_response_data(|rdata| {
-                        rdata
-                            // Do not allow any mentions
-                            .allowed_mentions(|am| am.empty_parse())
-                            // Use the ephemeral flag to mark the response as only visible to the user who invoked it.
-                            .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
-                            .create_embed(|embed| {
-                                // Add common attributes
-                                embed_common(embed)
-                                    .color(ERROR_COLOR)
-                                    .title("RCOS API Error")
-                                    .description(
-                                        "We could not get data about this user because the \
-                                RCOS API responded with an error. Please contact a coordinator and \
-                                report this error on Telescope's GitHub.",
-                                    )
-                                    // Include the error as a field of the embed.
-                                    .field("Error Message", err, false)
-                            })
-                    })
-            })
-            .await;
-    }
-
-    // Error handled -- unwrap API response.
-    let rcos_user: Option<_> = rcos_api_response.unwrap().get_user();
+    let embed: CreateEmbed = make_template(rcos_api_response)
+        .and_then(|template| embeds::render(&template))
+        .map_err(|err| {
+            error!("Could not render /whois embed: {}", err);
+            SerenityError::Other("Could not render /whois embed")
+        })?;
 
     // Respond to the discord interaction.
-    return interaction
-        .create_interaction_response(&ctx.http, |create_response| {
-            create_response
-                .kind(InteractionResponseType::ChannelMessageWithSource)
-                .interaction_response_data(|rdata| {
-                    rdata
-                        // Allow no mentions
-                        .allowed_mentions(|am| am.empty_parse())
-                        // Use the ephemeral flag to hide the response from everyone except the user who invoked it.
-                        .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
-                        .create_embed(|create_embed| {
-                            // Set common embed fields (author, footer, timestamp)
-                            embed_common(create_embed);
-
-                            // Set remaining fields based on user
-                            if let Some(u) = rcos_user {
-                                create_embed
-                                    // Title with the user's name
-                                    .title(format!("{} {}", u.first_name, u.last_name))
-                                    // Link to their profile
-                                    .url(format!("{}/user/{}", global_config().telescope_url, u.id))
-                                    // List their role inline
-                                    .field("User Role", u.role, true);
-
-                                // Add their RPI email if available
-                                let rcs_id = u
-                                    .rcs_id
-                                    .get(0)
-                                    .map(|o| format!("{}@rpi.edu", o.account_id))
-                                    .unwrap_or("RPI CAS not linked to this user.".into());
-
-                                create_embed.field("RPI Email", rcs_id, true)
-                            } else {
-                                create_embed
-                                    .color(ERROR_COLOR)
-                                    .description("User not found in RCOS database.")
-                            }
-                        })
-                })
-        })
-        .await;
+    return respond(ctx, interaction, |create_embed| {
+        *create_embed = embed;
+        // Set common embed fields (author, footer, timestamp)
+        embed_common(create_embed)
+    })
+    .await;
 }
 
 /// Add common data to a Discord embed. This includes the author, footer, and timestamp.
@@ -176,3 +117,33 @@ fn embed_common(create_embed: &mut CreateEmbed) -> &mut CreateEmbed {
                 .url("https://github.com/rcos/Telescope")
         })
 }
+
+/// Make the embed template for a /whois response. Respond with an embed
+/// indicating an error on RCOS API error.
+fn make_template(
+    rcos_api_response: Result<ResponseData, TelescopeError>,
+) -> Result<Template, TelescopeError> {
+    let template: Template = match rcos_api_response {
+        Err(err) => Template::new(ERROR_TEMPLATE).try_field("error", err.to_string())?,
+
+        Ok(response) => {
+            let mut template = Template::new(TEMPLATE);
+            if let Some(u) = response.get_user() {
+                let display =
+                    UserDisplay::new(u.first_name, u.last_name, u.preferred_name, u.pronouns);
+                template.set_field(
+                    "user",
+                    json!({
+                        "name": display.name(),
+                        "url": format!("{}/user/{}", global_config().telescope_url, u.id),
+                        "role": u.role.to_string(),
+                        "pronouns": display.pronouns(),
+                        "rpi_email": u.rcs_id.get(0).map(|o| format!("{}@rpi.edu", o.account_id)),
+                    }),
+                )?;
+            }
+            template
+        }
+    };
+    return Ok(template);
+}
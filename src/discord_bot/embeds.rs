@@ -0,0 +1,200 @@
+//! Discord embeds rendered from handlebars templates.
+//!
+//! Embed templates live under `templates/discord`. A template renders to plain
+//! text split into sections by marker lines:
+//!
+//! ```text
+//! @title
+//! Poll: {{{title}}}
+//! @description
+//! {{{description}}}
+//! @field Vote
+//! {{{url}}}
+//! ```
+//!
+//! The markers are `@title`, `@url`, `@description`, `@footer`, `@color` (a
+//! hex color like `#DC3545`), `@field <name>`, and `@inline <name>` for an
+//! inline field. Sections that render empty are left out, so they can be
+//! wrapped in `{{#if}}` blocks. Values should use triple braces, since the
+//! template registry escapes HTML and Discord would show the escapes as-is.
+//! Every part is clamped to Discord's length limits.
+
+use crate::error::TelescopeError;
+use crate::templates::Template;
+use serenity::builder::CreateEmbed;
+use serenity::utils::Color;
+
+/// The most characters Discord allows in an embed title.
+const TITLE_LIMIT: usize = 256;
+/// The most characters Discord allows in an embed description.
+const DESCRIPTION_LIMIT: usize = 4096;
+/// The most characters Discord allows in a field name.
+const FIELD_NAME_LIMIT: usize = 256;
+/// The most characters Discord allows in a field value.
+const FIELD_VALUE_LIMIT: usize = 1024;
+/// The most characters Discord allows in an embed footer.
+const FOOTER_LIMIT: usize = 2048;
+/// The most fields Discord allows on an embed.
+const FIELD_COUNT_LIMIT: usize = 25;
+/// The most characters Discord allows across all the text of an embed.
+const TOTAL_LIMIT: usize = 6000;
+
+/// The parts of an embed read from a rendered template.
+#[derive(Default, Debug)]
+struct EmbedParts {
+    title: Option<String>,
+    url: Option<String>,
+    description: Option<String>,
+    footer: Option<String>,
+    color: Option<Color>,
+    /// Name, value, and whether the field is inline.
+    fields: Vec<(String, String, bool)>,
+}
+
+/// Cut text down to a number of characters, ending it with an ellipsis if it
+/// was cut.
+fn clamp(text: &str, limit: usize) -> String {
+    if text.chars().count() <= limit {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(limit.saturating_sub(1)).collect();
+    cut.push('…');
+    return cut;
+}
+
+impl EmbedParts {
+    /// Read the sections of a rendered template.
+    fn parse(rendered: &str) -> Self {
+        let mut parts = EmbedParts::default();
+        let mut marker: Option<&str> = None;
+        let mut body: Vec<&str> = Vec::new();
+
+        for line in rendered.lines() {
+            let trimmed: &str = line.trim();
+            let is_marker: bool = trimmed
+                .strip_prefix('@')
+                .and_then(|rest| rest.split_whitespace().next())
+                .map_or(false, |keyword| {
+                    [
+                        "title",
+                        "url",
+                        "description",
+                        "footer",
+                        "color",
+                        "field",
+                        "inline",
+                    ]
+                    .contains(&keyword)
+                });
+
+            if is_marker {
+                if let Some(marker) = marker {
+                    parts.add(marker, body.join("\n").trim());
+                }
+                marker = Some(trimmed);
+                body.clear();
+            } else {
+                body.push(line);
+            }
+        }
+
+        if let Some(marker) = marker {
+            parts.add(marker, body.join("\n").trim());
+        }
+        return parts;
+    }
+
+    /// Add a section. Empty sections are skipped.
+    fn add(&mut self, marker: &str, body: &str) {
+        if body.is_empty() {
+            return;
+        }
+
+        let (keyword, name) = marker[1..]
+            .split_once(char::is_whitespace)
+            .map(|(keyword, name)| (keyword, name.trim()))
+            .unwrap_or((&marker[1..], ""));
+
+        match keyword {
+            "title" => self.title = Some(clamp(body, TITLE_LIMIT)),
+            "url" => self.url = Some(body.to_string()),
+            "description" => self.description = Some(clamp(body, DESCRIPTION_LIMIT)),
+            "footer" => self.footer = Some(clamp(body, FOOTER_LIMIT)),
+            "color" => match u32::from_str_radix(body.trim_start_matches('#'), 16) {
+                Ok(color) => self.color = Some(Color::new(color)),
+                Err(_) => warn!("Ignoring malformed embed color: {}", body),
+            },
+            "field" | "inline" if self.fields.len() < FIELD_COUNT_LIMIT => {
+                // Discord needs every field to have a name. Use a zero width space.
+                let name: &str = if name.is_empty() { "\u{200b}" } else { name };
+                self.fields.push((
+                    clamp(name, FIELD_NAME_LIMIT),
+                    clamp(body, FIELD_VALUE_LIMIT),
+                    keyword == "inline",
+                ));
+            }
+            _ => warn!("Dropping embed section over Discord's limits: {}", marker),
+        }
+    }
+
+    /// The number of characters counted against Discord's total limit.
+    fn total_length(&self) -> usize {
+        let count = |text: &Option<String>| text.as_ref().map_or(0, |text| text.chars().count());
+        count(&self.title)
+            + count(&self.description)
+            + count(&self.footer)
+            + self
+                .fields
+                .iter()
+                .map(|(name, value, _)| name.chars().count() + value.chars().count())
+                .sum::<usize>()
+    }
+
+    /// Drop fields from the end, then shorten the description, until the embed
+    /// fits in Discord's total limit.
+    fn fit(&mut self) {
+        while self.total_length() > TOTAL_LIMIT && !self.fields.is_empty() {
+            self.fields.pop();
+        }
+
+        let over: usize = self.total_length().saturating_sub(TOTAL_LIMIT);
+        if over > 0 {
+            if let Some(description) = self.description.as_ref() {
+                let keep: usize = description.chars().count().saturating_sub(over);
+                self.description = Some(clamp(description, keep));
+            }
+        }
+    }
+
+    /// Build a serenity embed.
+    fn build(self) -> CreateEmbed {
+        let mut embed = CreateEmbed::default();
+        if let Some(title) = self.title {
+            embed.title(title);
+        }
+        if let Some(url) = self.url {
+            embed.url(url);
+        }
+        if let Some(description) = self.description {
+            embed.description(description);
+        }
+        if let Some(color) = self.color {
+            embed.color(color);
+        }
+        for (name, value, inline) in self.fields {
+            embed.field(name, value, inline);
+        }
+        if let Some(footer) = self.footer {
+            embed.footer(|create_footer| create_footer.text(footer));
+        }
+        return embed;
+    }
+}
+
+/// Render an embed template.
+pub fn render(template: &Template) -> Result<CreateEmbed, TelescopeError> {
+    let rendered: String = template.render()?;
+    let mut parts = EmbedParts::parse(rendered.as_str());
+    parts.fit();
+    return Ok(parts.build());
+}
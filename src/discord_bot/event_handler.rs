@@ -1,8 +1,13 @@
 //! Event handling code for the telescope Discord Bot.
 
-use crate::discord_bot::commands::{get_handler, register_commands_for_guild, InteractionHandler};
+use crate::discord_bot::commands::{
+    dispatch, get_handler, register_commands_for_guild, register_global_commands,
+    InteractionHandler,
+};
+use crate::discord_bot::{guilds, rsvps, status};
 use crate::env::global_config;
 use serenity::client::{Context, EventHandler};
+use serenity::model::channel::Reaction;
 use serenity::model::gateway::Ready;
 use serenity::model::guild::Guild;
 use serenity::model::interactions::Interaction;
@@ -12,7 +17,7 @@ pub struct Handler;
 
 #[serenity::async_trait]
 impl EventHandler for Handler {
-    async fn guild_create(&self, mut ctx: Context, guild: Guild, is_new: bool) {
+    async fn guild_create(&self, ctx: Context, guild: Guild, is_new: bool) {
         info!(
             "{}uild connected: {} (ID: {})",
             is_new.then(|| "NEW g").unwrap_or("G"),
@@ -20,15 +25,19 @@ impl EventHandler for Handler {
             guild.id
         );
 
-        // Check if the guild is whitelisted.
-        if global_config().discord_config.rcos_guild_id == guild.id.to_string() {
+        // Check if commands are enabled on this guild. Debug guilds always get them.
+        let is_debug: bool = global_config()
+            .discord_config
+            .debug_guild_ids()
+            .contains(&guild.id.0);
+        if is_debug || guilds::get(guild.id.0).map_or(false, |settings| settings.commands_enabled) {
             // If so, register telescope's commands
             info!(
                 "Registering telescope's Discord commands for guild \"{}\" (ID: {})",
                 guild.name, guild.id
             );
 
-            register_commands_for_guild(&mut ctx, &guild)
+            register_commands_for_guild(&ctx.http, guild.id, &guild.name)
                 .await
                 .unwrap_or_else(|err| {
                     error!(
@@ -38,7 +47,7 @@ impl EventHandler for Handler {
                 });
         } else {
             warn!(
-                "Non-RCOS guild connected: \"{}\" (ID: {})",
+                "Guild without commands enabled connected: \"{}\" (ID: {})",
                 guild.name, guild.id
             );
         }
@@ -51,23 +60,25 @@ impl EventHandler for Handler {
             ready.user.tag(),
             ready.user.id
         );
+        status::connected(ready.user.tag(), ctx.http.clone());
 
-        // Get the list of global application commands.
-        ctx.http
-            .get_global_application_commands()
-            .await
-            // Log them on success
-            .map(|list| {
-                info!(
-                    "{} global application commands registered: {:#?}",
-                    list.len(),
-                    list
-                );
-            })
-            // Otherwise log an error message.
-            .unwrap_or_else(|err| {
-                error!("Could not get list of global application commands: {}", err);
-            });
+        // Register the global commands if they are turned on. Guild commands are
+        // registered as each guild connects.
+        if global_config().discord_config.global_commands {
+            register_global_commands(&ctx.http)
+                .await
+                .unwrap_or_else(|err| {
+                    error!("Could not register global application commands: {}", err);
+                });
+        }
+    }
+
+    async fn reaction_add(&self, ctx: Context, reaction: Reaction) {
+        rsvps::reaction_changed(&ctx, &reaction, true).await;
+    }
+
+    async fn reaction_remove(&self, ctx: Context, reaction: Reaction) {
+        rsvps::reaction_changed(&ctx, &reaction, false).await;
     }
 
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
@@ -90,13 +101,8 @@ impl EventHandler for Handler {
                     return;
                 }
 
-                // Call the handler on the interaction.
-                let result: serenity::Result<()> = (handler.unwrap())(&ctx, &command).await;
-
-                // Log any errors from the handler.
-                if let Err(err) = result {
-                    error!("'/{}' handler returned an error: {}", command_name, err);
-                }
+                // Defer the command and run the handler.
+                dispatch(&ctx, &command, handler.unwrap()).await;
             }
 
             // Non-exhaustive match requires other branch.
@@ -0,0 +1,154 @@
+//! Per-server settings for the Discord bot.
+//!
+//! Settings are saved in the RCOS database from the admin panel. The bot
+//! keeps a copy in memory so that event handlers and web handlers can look
+//! them up without calling the API. The copy is reloaded when the bot starts
+//! and whenever settings are saved. The RCOS server from the config file
+//! always has settings, taken from the config unless some are saved for it.
+
+use crate::api::rcos::discord_guilds::get::AllDiscordGuilds;
+use crate::api::rcos::users::enrollment_roles::EnrollmentRole;
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use dashmap::DashMap;
+use std::collections::HashMap;
+
+/// The settings of one Discord server.
+#[derive(Clone, Debug, Serialize)]
+pub struct GuildSettings {
+    /// The Discord server ID.
+    pub guild_id: u64,
+    /// A name to recognize the server by.
+    pub name: Option<String>,
+    /// The channel announcements are posted in.
+    pub announcements_channel_id: Option<u64>,
+    /// The role given to members with a verified Telescope account.
+    pub member_role_id: Option<u64>,
+    /// The roles kept in sync with members' enrollment.
+    pub student_role_id: Option<u64>,
+    pub mentor_role_id: Option<u64>,
+    pub coordinator_role_id: Option<u64>,
+    pub faculty_role_id: Option<u64>,
+    /// Whether Telescope's slash commands are registered on this server.
+    pub commands_enabled: bool,
+    /// Whether announcements are posted on this server.
+    pub announcements_enabled: bool,
+}
+
+impl GuildSettings {
+    /// The settings for the RCOS server from the config file.
+    fn rcos_default() -> Self {
+        let discord_conf = &global_config().discord_config;
+        let announcements_channel_id: Option<u64> = discord_conf.announcements_channel_id();
+        GuildSettings {
+            guild_id: discord_conf.rcos_guild_id(),
+            name: Some("RCOS".into()),
+            announcements_channel_id,
+            member_role_id: None,
+            student_role_id: None,
+            mentor_role_id: None,
+            coordinator_role_id: None,
+            faculty_role_id: None,
+            commands_enabled: true,
+            announcements_enabled: announcements_channel_id.is_some(),
+        }
+    }
+
+    /// The Discord role for an enrollment role on this server, if it has one.
+    pub fn enrollment_role_id(&self, role: EnrollmentRole) -> Option<u64> {
+        match role {
+            EnrollmentRole::Student => self.student_role_id,
+            EnrollmentRole::Mentor => self.mentor_role_id,
+            EnrollmentRole::Coordinator => self.coordinator_role_id,
+            EnrollmentRole::Faculty => self.faculty_role_id,
+        }
+    }
+
+    /// The channel to post announcements in, if this server gets them.
+    pub fn announcements_channel(&self) -> Option<u64> {
+        self.announcements_channel_id
+            .filter(|_| self.announcements_enabled)
+    }
+}
+
+lazy_static! {
+    static ref GUILDS: DashMap<u64, GuildSettings> = {
+        let map = DashMap::new();
+        // Start with the RCOS server so that it works before the first reload.
+        let rcos = GuildSettings::rcos_default();
+        map.insert(rcos.guild_id, rcos);
+        map
+    };
+}
+
+/// Parse a Discord ID saved in the database. Malformed IDs are logged and
+/// treated as missing.
+fn parse_id(what: &str, id: Option<String>) -> Option<u64> {
+    let id: String = id?;
+    let parsed = id.trim().parse::<u64>().ok();
+    if parsed.is_none() {
+        warn!(
+            "Ignoring malformed Discord {} ID in server settings: {}",
+            what, id
+        );
+    }
+    return parsed;
+}
+
+/// Reload every server's settings from the RCOS database. Return the number
+/// of servers with settings.
+pub async fn reload() -> Result<usize, TelescopeError> {
+    let mut settings: HashMap<u64, GuildSettings> = HashMap::new();
+    let rcos = GuildSettings::rcos_default();
+    settings.insert(rcos.guild_id, rcos);
+
+    for saved in AllDiscordGuilds::get().await? {
+        let guild_id: u64 = match parse_id("server", Some(saved.guild_id)) {
+            Some(id) => id,
+            None => continue,
+        };
+
+        settings.insert(
+            guild_id,
+            GuildSettings {
+                guild_id,
+                name: saved.name,
+                announcements_channel_id: parse_id("channel", saved.announcements_channel_id),
+                member_role_id: parse_id("role", saved.member_role_id),
+                student_role_id: parse_id("role", saved.student_role_id),
+                mentor_role_id: parse_id("role", saved.mentor_role_id),
+                coordinator_role_id: parse_id("role", saved.coordinator_role_id),
+                faculty_role_id: parse_id("role", saved.faculty_role_id),
+                commands_enabled: saved.commands_enabled,
+                announcements_enabled: saved.announcements_enabled,
+            },
+        );
+    }
+
+    GUILDS.retain(|guild_id, _| settings.contains_key(guild_id));
+    let count: usize = settings.len();
+    for (guild_id, guild) in settings {
+        GUILDS.insert(guild_id, guild);
+    }
+
+    info!("Loaded Discord settings for {} servers.", count);
+    return Ok(count);
+}
+
+/// Get the settings of a server, if it has any.
+pub fn get(guild_id: u64) -> Option<GuildSettings> {
+    GUILDS.get(&guild_id).map(|entry| entry.value().clone())
+}
+
+/// Get the announcement channel of every server that gets announcements.
+pub fn announcement_channels() -> Vec<u64> {
+    GUILDS
+        .iter()
+        .filter_map(|entry| entry.value().announcements_channel())
+        .collect()
+}
+
+/// Get the settings of every server that has them.
+pub fn all() -> Vec<GuildSettings> {
+    GUILDS.iter().map(|entry| entry.value().clone()).collect()
+}
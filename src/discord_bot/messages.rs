@@ -0,0 +1,572 @@
+//! Messages web handlers send to the Discord bot thread.
+
+use crate::api::discord::{global_discord_client, rcos_discord_verified_role_id};
+use crate::api::rcos::meetings::discord::{Announcement, RecordMeetingAnnouncement};
+use crate::api::rcos::meetings::get_by_id::{meeting::MeetingMeeting, Meeting};
+use crate::api::rcos::polls::mutations::SetPollDiscordMessage;
+use crate::api::rcos::prelude::MeetingId;
+use crate::api::rcos::projects::join_requests::ProjectRole;
+use crate::api::rcos::users::accounts::all_of_type::AllAccountsOfType;
+use crate::api::rcos::users::accounts::lookup::AccountLookup;
+use crate::api::rcos::users::UserAccountType;
+use crate::discord_bot::channels::{self, ChannelAction, ChannelChange};
+use crate::discord_bot::commands::{register_commands_for_guild, register_global_commands};
+use crate::discord_bot::rsvps::RSVP_EMOJI;
+use crate::discord_bot::status::{self, RoleSyncResult};
+use crate::discord_bot::{embeds, guilds, roles, threads};
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use crate::templates::Template;
+use chrono::{Local, TimeZone, Utc};
+use serenity::builder::CreateEmbed;
+use serenity::model::channel::ReactionType;
+use serenity::model::guild::Member;
+use serenity::model::id::{ChannelId, GuildId};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// The embed template for poll announcements.
+const POLL_TEMPLATE: &'static str = "discord/poll";
+
+/// The embed template for meeting announcements.
+const MEETING_TEMPLATE: &'static str = "discord/meeting";
+
+/// How many members to ask Discord for at once during a role sync. This is
+/// the most Discord allows.
+const MEMBER_PAGE_SIZE: u64 = 1000;
+
+/// Work for the Discord bot to do on behalf of a web handler.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum DiscordMessage {
+    /// Post a link to a new poll in the announcements channels.
+    AnnouncePoll {
+        poll_id: i64,
+        title: String,
+        description: Option<String>,
+    },
+
+    /// Post an upcoming meeting in the announcements channels, with a
+    /// reaction members can click to RSVP.
+    AnnounceMeeting { meeting_id: MeetingId },
+
+    /// Bring a meeting's announcements up to date after it was edited, such
+    /// as posting slides in its threads.
+    MeetingChanged { meeting_id: MeetingId },
+
+    /// Give a member who joined a project the project's Discord role, which
+    /// opens its channels to them.
+    GrantProjectRole { project_id: i64, user_id: Uuid },
+
+    /// Reload per-server settings after they were changed.
+    ReloadGuilds,
+
+    /// Register slash commands again on every server with commands enabled.
+    RegisterCommands,
+
+    /// Give a user the enrollment roles that apply to them and take away the
+    /// ones that do not, after they link Discord or join a server.
+    SyncEnrollmentRoles { user_id: Uuid },
+
+    /// Give the member role to every linked member of the RCOS server who is
+    /// missing it, and reconcile enrollment roles on every server.
+    SyncRoles,
+
+    /// Disconnect from the gateway and stay disconnected until restarted.
+    Stop,
+
+    /// Connect to the gateway again, whether the bot was stopped or not.
+    Restart,
+
+    /// Post a test message in every announcements channel.
+    TestAnnouncement,
+
+    /// Make the categories and channels of this semester's small groups and
+    /// their projects that are missing on the RCOS server.
+    GenerateChannels,
+}
+
+impl DiscordMessage {
+    /// Carry out this message. Failures are logged rather than returned, since
+    /// the handler that sent it has already responded.
+    pub async fn deliver(self) {
+        match self {
+            DiscordMessage::AnnouncePoll {
+                poll_id,
+                title,
+                description,
+            } => announce_poll(poll_id, title.as_str(), description.as_deref()).await,
+
+            DiscordMessage::AnnounceMeeting { meeting_id } => announce_meeting(meeting_id).await,
+
+            DiscordMessage::MeetingChanged { meeting_id } => {
+                threads::meeting_changed(meeting_id).await
+            }
+
+            DiscordMessage::GrantProjectRole {
+                project_id,
+                user_id,
+            } => grant_project_role(project_id, user_id).await,
+
+            DiscordMessage::ReloadGuilds => {
+                if let Err(e) = guilds::reload().await {
+                    error!("Could not reload Discord server settings: {}", e);
+                }
+            }
+
+            DiscordMessage::RegisterCommands => register_commands().await,
+            DiscordMessage::SyncEnrollmentRoles { user_id } => roles::sync_user(user_id).await,
+            DiscordMessage::SyncRoles => {
+                let result: RoleSyncResult = sync_roles().await;
+                let mut ok: bool = result.error.is_none();
+                let mut message: String = match &result.error {
+                    Some(error) => error.clone(),
+                    None => format!(
+                        "{} linked members checked, {} given the role, {} failed.",
+                        result.linked, result.added, result.failed
+                    ),
+                };
+                match roles::reconcile().await {
+                    Ok(changes) => {
+                        message = format!(
+                            "{} Enrollment roles: {} added, {} removed, {} failed.",
+                            message, changes.added, changes.removed, changes.failed
+                        )
+                    }
+                    Err(e) => {
+                        ok = false;
+                        message =
+                            format!("{} Could not reconcile enrollment roles: {}", message, e);
+                    }
+                }
+                status::action_finished("Sync roles", ok, message);
+                status::role_sync_finished(result);
+            }
+            DiscordMessage::Stop => {
+                super::stop().await;
+                status::action_finished("Stop", true, "Disconnected from Discord.".into());
+            }
+            DiscordMessage::Restart => {
+                super::restart().await;
+                status::action_finished("Restart", true, "Reconnecting to Discord.".into());
+            }
+            DiscordMessage::TestAnnouncement => test_announcement().await,
+            DiscordMessage::GenerateChannels => generate_channels().await,
+        }
+    }
+}
+
+/// Make the announcement embed for a poll.
+fn poll_embed(
+    poll_id: i64,
+    title: &str,
+    description: Option<&str>,
+) -> Result<CreateEmbed, TelescopeError> {
+    let url = format!("{}/polls/{}", global_config().telescope_url, poll_id);
+    let template = Template::new(POLL_TEMPLATE)
+        .try_field("title", title)?
+        .try_field("description", description)?
+        .try_field("url", url)?;
+    return embeds::render(&template);
+}
+
+/// Post a link to a new poll in the announcements channel of every server
+/// that gets announcements, and record the first message on the poll.
+async fn announce_poll(poll_id: i64, title: &str, description: Option<&str>) {
+    let embed: CreateEmbed = match poll_embed(poll_id, title, description) {
+        Ok(embed) => embed,
+        Err(e) => {
+            error!("Could not render poll {} announcement: {}", poll_id, e);
+            return;
+        }
+    };
+
+    let mut recorded: bool = false;
+
+    for channel_id in guilds::announcement_channels() {
+        let result = ChannelId(channel_id)
+            .send_message(global_discord_client(), |message| {
+                message.set_embed(embed.clone())
+            })
+            .await;
+
+        match result {
+            Ok(message) if !recorded => {
+                recorded = true;
+                if let Err(e) =
+                    SetPollDiscordMessage::execute(poll_id, message.id.to_string()).await
+                {
+                    error!(
+                        "Could not record Discord message for poll {}: {}",
+                        poll_id, e
+                    );
+                }
+            }
+            Ok(_) => {}
+            Err(e) => warn!(
+                "Could not post poll {} to Discord channel {}: {}",
+                poll_id, channel_id, e
+            ),
+        }
+    }
+}
+
+/// Make the announcement embed for a meeting.
+fn meeting_embed(meeting: &MeetingMeeting) -> Result<CreateEmbed, TelescopeError> {
+    let start = Local.from_utc_datetime(&meeting.start_date_time.naive_utc());
+    let end = Local.from_utc_datetime(&meeting.end_date_time.naive_utc());
+    let location: Option<&str> = meeting
+        .location
+        .as_deref()
+        .filter(|location| !location.trim().is_empty());
+
+    let template = Template::new(MEETING_TEMPLATE)
+        .try_field("title", meeting.title())?
+        .try_field(
+            "url",
+            format!(
+                "{}/meeting/{}",
+                global_config().telescope_url,
+                meeting.meeting_id
+            ),
+        )?
+        .try_field("description", &meeting.description)?
+        .try_field(
+            "when",
+            format!(
+                "{} from {} to {}",
+                start.format("%A, %B %-d"),
+                start.format("%-I:%M %P"),
+                end.format("%-I:%M %P")
+            ),
+        )?
+        .try_field("where", location.or(meeting.is_remote.then(|| "Remote")))?
+        .try_field(
+            "host",
+            meeting
+                .host
+                .as_ref()
+                .map(|host| format!("{} {}", host.first_name, host.last_name)),
+        )?
+        .try_field("rsvp_emoji", RSVP_EMOJI)?;
+    return embeds::render(&template);
+}
+
+/// Post a meeting in the announcements channel of every server that gets
+/// announcements. Each announcement gets the RSVP reaction to start it off,
+/// and workshops get a thread for questions. Announcements are recorded so
+/// that reactions on them can be matched to the meeting.
+async fn announce_meeting(meeting_id: MeetingId) {
+    let meeting: MeetingMeeting = match Meeting::get(meeting_id).await {
+        Ok(Some(meeting)) => meeting,
+        Ok(None) => {
+            warn!(
+                "Meeting {} was deleted before it was announced.",
+                meeting_id
+            );
+            return;
+        }
+        Err(e) => {
+            error!("Could not get meeting {} to announce: {}", meeting_id, e);
+            return;
+        }
+    };
+
+    let embed: CreateEmbed = match meeting_embed(&meeting) {
+        Ok(embed) => embed,
+        Err(e) => {
+            error!(
+                "Could not render meeting {} announcement: {}",
+                meeting_id, e
+            );
+            return;
+        }
+    };
+
+    for channel_id in guilds::announcement_channels() {
+        let message = match ChannelId(channel_id)
+            .send_message(global_discord_client(), |message| {
+                message.set_embed(embed.clone())
+            })
+            .await
+        {
+            Ok(message) => message,
+            Err(e) => {
+                warn!(
+                    "Could not post meeting {} to Discord channel {}: {}",
+                    meeting_id, channel_id, e
+                );
+                continue;
+            }
+        };
+
+        let reaction = ReactionType::Unicode(RSVP_EMOJI.to_string());
+        if let Err(e) = message.react(global_discord_client(), reaction).await {
+            warn!(
+                "Could not add the RSVP reaction to meeting {}: {}",
+                meeting_id, e
+            );
+        }
+
+        let mut announcement = Announcement {
+            channel_id: channel_id.to_string(),
+            message_id: message.id.to_string(),
+            thread_id: None,
+            slides_message_id: None,
+        };
+
+        if threads::is_workshop(&meeting) {
+            match threads::open(&meeting, ChannelId(channel_id), message.id).await {
+                Ok((thread_id, slides)) => {
+                    announcement.thread_id = Some(thread_id.to_string());
+                    announcement.slides_message_id = slides.map(|id| id.to_string());
+                }
+                Err(e) => warn!(
+                    "Could not start a thread for meeting {} in channel {}: {}",
+                    meeting_id, channel_id, e
+                ),
+            }
+        }
+
+        if let Err(e) = RecordMeetingAnnouncement::execute(meeting_id, announcement).await {
+            error!(
+                "Could not record Discord announcement of meeting {}: {}",
+                meeting_id, e
+            );
+        }
+    }
+}
+
+/// Register slash commands on every server with commands enabled and every
+/// debug server, and globally if global commands are turned on.
+async fn register_commands() {
+    const ACTION: &'static str = "Register commands";
+
+    let http = match status::client_http() {
+        Some(http) => http,
+        None => {
+            status::action_finished(ACTION, false, "The bot is not connected.".into());
+            return;
+        }
+    };
+
+    // Servers with commands enabled, and the debug servers from the config.
+    let mut targets: Vec<(u64, String)> = guilds::all()
+        .into_iter()
+        .filter(|s| s.commands_enabled)
+        .map(|s| (s.guild_id, s.name.unwrap_or_else(|| s.guild_id.to_string())))
+        .collect();
+    for guild_id in global_config().discord_config.debug_guild_ids() {
+        if !targets.iter().any(|(id, _)| *id == guild_id) {
+            targets.push((guild_id, format!("{} (debug)", guild_id)));
+        }
+    }
+
+    let mut registered: Vec<String> = Vec::new();
+    let mut failures: Vec<String> = Vec::new();
+    for (guild_id, name) in targets {
+        match register_commands_for_guild(&http, GuildId(guild_id), name.as_str()).await {
+            Ok(()) => registered.push(name),
+            Err(e) => {
+                error!("Could not register commands on {}: {}", name, e);
+                failures.push(format!("{} ({})", name, e));
+            }
+        }
+    }
+
+    if global_config().discord_config.global_commands {
+        match register_global_commands(&http).await {
+            Ok(()) => registered.push("all servers (global)".into()),
+            Err(e) => {
+                error!("Could not register global commands: {}", e);
+                failures.push(format!("global ({})", e));
+            }
+        }
+    }
+
+    let mut message: String = format!("Registered on: {}.", registered.join(", "));
+    if !failures.is_empty() {
+        message = format!("{} Failed on: {}.", message, failures.join(", "));
+    }
+    status::action_finished(ACTION, failures.is_empty(), message);
+}
+
+/// Give a member a project's Discord role on the RCOS server. Projects without
+/// a role and members without Discord linked are skipped.
+async fn grant_project_role(project_id: i64, user_id: Uuid) {
+    let role_id: u64 = match ProjectRole::get(project_id).await {
+        Ok(Some(role_id)) => role_id,
+        Ok(None) => return,
+        Err(e) => {
+            error!(
+                "Could not get the Discord role of project {}: {}",
+                project_id, e
+            );
+            return;
+        }
+    };
+
+    let discord_id: u64 = match AccountLookup::send(user_id, UserAccountType::Discord).await {
+        Ok(account) => match account.and_then(|id| id.parse::<u64>().ok()) {
+            Some(discord_id) => discord_id,
+            None => return,
+        },
+        Err(e) => {
+            error!(
+                "Could not get the Discord account of user {}: {}",
+                user_id, e
+            );
+            return;
+        }
+    };
+
+    let guild_id: u64 = global_config().discord_config.rcos_guild_id();
+    if let Err(e) = global_discord_client()
+        .add_member_role(guild_id, discord_id, role_id)
+        .await
+    {
+        warn!(
+            "Could not give user {} the role of project {}: {}",
+            user_id, project_id, e
+        );
+    }
+}
+
+/// Give the member role to every member of the RCOS server with a linked
+/// Telescope account who does not have it yet.
+async fn sync_roles() -> RoleSyncResult {
+    let mut result = RoleSyncResult {
+        finished: Utc::now(),
+        linked: 0,
+        added: 0,
+        failed: 0,
+        error: None,
+    };
+
+    let role = match rcos_discord_verified_role_id().await {
+        Ok(Some(role)) => role,
+        Ok(None) => {
+            result.error = Some("The RCOS server has no member role.".into());
+            return result;
+        }
+        Err(e) => {
+            result.error = Some(format!("Could not get the member role: {}", e));
+            return result;
+        }
+    };
+
+    // Discord IDs of every Telescope account with Discord linked.
+    let linked: HashSet<u64> = match AllAccountsOfType::get(UserAccountType::Discord).await {
+        Ok(ids) => ids.iter().filter_map(|id| id.parse::<u64>().ok()).collect(),
+        Err(e) => {
+            result.error = Some(format!("Could not get linked Discord accounts: {}", e));
+            return result;
+        }
+    };
+
+    let guild_id: u64 = global_config().discord_config.rcos_guild_id();
+    let mut after: Option<u64> = None;
+    loop {
+        // Listing members needs the server members intent turned on for the bot.
+        let members: Vec<Member> = match global_discord_client()
+            .get_guild_members(guild_id, Some(MEMBER_PAGE_SIZE), after)
+            .await
+        {
+            Ok(members) => members,
+            Err(e) => {
+                result.error = Some(format!("Could not list server members: {}", e));
+                break;
+            }
+        };
+
+        for member in members.iter().filter(|m| linked.contains(&m.user.id.0)) {
+            result.linked += 1;
+            if member.roles.contains(&role) {
+                continue;
+            }
+
+            match global_discord_client()
+                .add_member_role(guild_id, member.user.id.0, role.0)
+                .await
+            {
+                Ok(()) => result.added += 1,
+                Err(e) => {
+                    warn!(
+                        "Could not give {} the member role: {}",
+                        member.user.tag(),
+                        e
+                    );
+                    result.failed += 1;
+                }
+            }
+        }
+
+        // Members come in order of ID. A short page is the last one.
+        after = members.last().map(|member| member.user.id.0);
+        if (members.len() as u64) < MEMBER_PAGE_SIZE {
+            break;
+        }
+    }
+
+    result.finished = Utc::now();
+    return result;
+}
+
+/// Post a test message in every announcements channel.
+async fn test_announcement() {
+    const ACTION: &'static str = "Test announcement";
+
+    let channels: Vec<u64> = guilds::announcement_channels();
+    if channels.is_empty() {
+        status::action_finished(ACTION, false, "No servers get announcements.".into());
+        return;
+    }
+
+    let mut failures: Vec<String> = Vec::new();
+    for channel_id in channels.iter() {
+        let result = ChannelId(*channel_id)
+            .say(
+                global_discord_client(),
+                "This is a test announcement from the Telescope admin panel.",
+            )
+            .await;
+
+        if let Err(e) = result {
+            failures.push(format!("{} ({})", channel_id, e));
+        }
+    }
+
+    let message: String = if failures.is_empty() {
+        format!("Posted in {} channels.", channels.len())
+    } else {
+        format!("Failed in: {}.", failures.join(", "))
+    };
+    status::action_finished(ACTION, failures.is_empty(), message);
+}
+
+/// Generate small group and project channels, and record how it went.
+async fn generate_channels() {
+    const ACTION: &'static str = "Generate channels";
+
+    match channels::generate(false).await {
+        Ok(changes) => {
+            let count = |action: ChannelAction| {
+                changes
+                    .iter()
+                    .filter(|change: &&ChannelChange| change.action == action)
+                    .count()
+            };
+            let message: String = format!(
+                "{} created, {} replaced, {} adopted, {} already there.",
+                count(ChannelAction::Create),
+                count(ChannelAction::Replace),
+                count(ChannelAction::Adopt),
+                count(ChannelAction::Keep)
+            );
+            status::action_finished(ACTION, true, message);
+        }
+        Err(e) => {
+            error!("Could not generate channels: {}", e);
+            status::action_finished(ACTION, false, e.to_string());
+        }
+    }
+}
@@ -1,115 +1,316 @@
 //! Discord functionality built on top of serenity.
+//!
+//! The bot runs on its own OS thread with its own async runtime, so that a
+//! stalled gateway connection cannot hold up the web server. Web handlers talk
+//! to the bot through a bounded channel using [`send`], which never waits: if
+//! the bot has fallen behind, the message is refused rather than queued
+//! without limit.
+//!
+//! When the gateway connection drops, the bot reconnects with exponential
+//! backoff. It can also be stopped and restarted from the admin panel with
+//! [`DiscordMessage::Stop`] and [`DiscordMessage::Restart`].
 
+pub mod channels;
 mod commands;
+pub mod embeds;
 mod event_handler;
+pub mod guilds;
+mod messages;
+mod roles;
+mod rsvps;
+pub mod status;
+mod threads;
+
+pub use self::messages::DiscordMessage;
 
 use crate::discord_bot::event_handler::Handler;
 use crate::env::{global_config, DiscordConfig};
-use actix::{Actor, ActorContext, ActorFuture, AsyncContext, Context, Supervised};
-use futures::future::LocalBoxFuture;
-use futures::Future;
+use crate::error::TelescopeError;
+use crate::web::cluster::{self, Broadcast};
+use actix::clock::delay_for;
+use actix::System;
+use futures::channel::mpsc::{self, Receiver, Sender};
+use futures::channel::oneshot;
+use futures::future::{self, Either};
+use futures::StreamExt;
+use serenity::client::bridge::gateway::ShardManager;
+use serenity::prelude::Mutex as AsyncMutex;
 use serenity::{Client, Result as SerenityResult};
-use std::pin::Pin;
-use std::task::Context as StdContext;
-use std::task::Poll;
-
-/// ZST representing Telescope's discord bot. The actual client is stored by the
-/// future representing the bots listening state.
-pub struct DiscordBot;
-
-impl DiscordBot {
-    /// Create a Serenity Discord client.
-    async fn create() -> SerenityResult<Client> {
-        // Get the global Discord config
-        let discord_conf: &DiscordConfig = &global_config().discord_config;
-        // Extract the application ID.
-        let app_id = discord_conf
-            .client_id
-            .as_str()
-            .parse::<u64>()
-            .expect("Could not parse Discord Application ID.");
-
-        // Instantiate a serenity Discord client.
-        return Client::builder(&discord_conf.bot_token)
-            .event_handler(Handler)
-            .application_id(app_id)
-            .await;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How many messages can wait for the bot before new ones are refused.
+const OUTBOX_CAPACITY: usize = 64;
+
+/// How long to wait before reconnecting the first time after the client
+/// stops. The wait doubles every time it fails to stay connected.
+const RESTART_DELAY: Duration = Duration::from_secs(10);
+
+/// The longest wait between attempts to reconnect.
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(10 * 60);
+
+/// How long a connection has to last for the wait to go back to
+/// [`RESTART_DELAY`].
+const STABLE_CONNECTION: Duration = Duration::from_secs(5 * 60);
+
+lazy_static! {
+    /// The sending half of the channel to the bot thread. This is `None`
+    /// until the bot is started.
+    static ref OUTBOX: Mutex<Option<Sender<DiscordMessage>>> = Mutex::new(None);
+
+    /// The shard manager of the running client, used to disconnect it. This
+    /// is `None` while the client is not running.
+    static ref SHARD_MANAGER: Mutex<Option<Arc<AsyncMutex<ShardManager>>>> = Mutex::new(None);
+
+    /// Wakes the bot up while it waits to reconnect or is stopped.
+    static ref WAKE: Mutex<Option<oneshot::Sender<()>>> = Mutex::new(None);
+}
+
+/// Set while the bot is stopped from the admin panel. It does not reconnect
+/// until it is restarted.
+static STOPPED: AtomicBool = AtomicBool::new(false);
+
+/// Set when the bot is restarted while connected, so that it reconnects
+/// right away instead of backing off.
+static RESTART_NOW: AtomicBool = AtomicBool::new(false);
+
+/// Create a Serenity Discord client.
+async fn create() -> SerenityResult<Client> {
+    // Get the global Discord config
+    let discord_conf: &DiscordConfig = &global_config().discord_config;
+    // Extract the application ID.
+    let app_id = discord_conf
+        .client_id
+        .as_str()
+        .parse::<u64>()
+        .expect("Could not parse Discord Application ID.");
+
+    // Instantiate a serenity Discord client.
+    return Client::builder(&discord_conf.bot_token)
+        .event_handler(Handler)
+        .application_id(app_id)
+        .await;
+}
+
+/// Connect to the Discord gateway and run the client until it stops.
+async fn run_client() {
+    let mut client: Client = match create().await {
+        Ok(client) => client,
+        Err(e) => {
+            error!("Could not create Serenity Discord client: {}", e);
+            status::disconnected(Some(e.to_string()));
+            return;
+        }
+    };
+
+    *SHARD_MANAGER.lock().expect("Shard manager lock poisoned") =
+        Some(client.shard_manager.clone());
+
+    match client.start_autosharded().await {
+        Ok(()) => {
+            warn!("Serenity Discord client stopped.");
+            status::disconnected(None);
+        }
+        Err(e) => {
+            error!("Serenity Discord client crashed and returned: {}", e);
+            status::disconnected(Some(e.to_string()));
+        }
+    }
+
+    *SHARD_MANAGER.lock().expect("Shard manager lock poisoned") = None;
+}
+
+/// Wait until the bot is woken up, or the timeout passes if there is one.
+async fn wait(timeout: Option<Duration>) {
+    let (sender, receiver) = oneshot::channel();
+    *WAKE.lock().expect("Discord wake lock poisoned") = Some(sender);
+
+    match timeout {
+        Some(timeout) => {
+            // If the timeout passed first, nobody took the sender.
+            if let Either::Left(_) = future::select(Box::pin(delay_for(timeout)), receiver).await {
+                WAKE.lock().expect("Discord wake lock poisoned").take();
+            }
+        }
+        None => {
+            let _ = receiver.await;
+        }
     }
+}
 
-    /// Create a Discord client and start listening for Discord events.
-    async fn create_and_listen() -> SerenityResult<()> {
-        Self::create().await?.start_autosharded().await
+/// Wake the bot up if it is waiting to reconnect or stopped.
+fn wake() {
+    if let Some(sender) = WAKE.lock().expect("Discord wake lock poisoned").take() {
+        let _ = sender.send(());
     }
+}
+
+/// Disconnect every shard of the running client, if there is one. Return
+/// false if the client was not running.
+async fn shutdown_shards() -> bool {
+    let manager = SHARD_MANAGER
+        .lock()
+        .expect("Shard manager lock poisoned")
+        .clone();
 
-    /// Run create_and_listen in an Actix compatible future.
-    fn wrapped_create_and_listen() -> ListeningFuture {
-        ListeningFuture {
-            inner: Box::pin(Self::create_and_listen()),
+    return match manager {
+        Some(manager) => {
+            manager.lock().await.shutdown_all().await;
+            true
         }
+        None => false,
+    };
+}
+
+/// Stop the bot. It stays disconnected until [`restart`] is called.
+async fn stop() {
+    STOPPED.store(true, Ordering::SeqCst);
+    status::stopped(true);
+    if !shutdown_shards().await {
+        // Stop waiting to reconnect.
+        wake();
     }
+    info!("Discord bot stopped.");
 }
 
-/// Future representing the indefinite async computation of the Discord bot
-/// listening for events.
-struct ListeningFuture {
-    inner: LocalBoxFuture<'static, SerenityResult<()>>,
+/// Connect the bot again, whether it was stopped, waiting to reconnect, or
+/// connected.
+async fn restart() {
+    STOPPED.store(false, Ordering::SeqCst);
+    status::stopped(false);
+    RESTART_NOW.store(true, Ordering::SeqCst);
+    if !shutdown_shards().await {
+        wake();
+    }
+    info!("Discord bot restarting.");
 }
 
-impl ActorFuture for ListeningFuture {
-    type Output = ();
-    type Actor = DiscordBot;
-
-    fn poll(
-        mut self: Pin<&mut Self>,
-        _: &mut Self::Actor,
-        ctx: &mut <DiscordBot as Actor>::Context,
-        task: &mut StdContext<'_>,
-    ) -> Poll<Self::Output> {
-        // Pin a mutable reference to the internal future.
-        let pinned_inner = Pin::new(&mut self.inner);
-        // Poll the pinned internal future.
-        match Future::poll(pinned_inner, task) {
-            // If the internal future is pending, so is this one.
-            // Unless the bot crashes, the internal future will be pending indefinitely.
-            Poll::Pending => Poll::Pending,
-
-            // If it's ready, the bot has crashed. Log an error message and stop the context.
-            Poll::Ready(res) => {
-                // Log the error
-                error!("Serenity Discord client crashed and returned: {:?}", res);
-                // Stop the context and the actor
-                ctx.stop();
-                // Return ready with no value.
-                Poll::Ready(())
-            }
+/// Connect to the Discord gateway and listen for events. If the client stops
+/// for any reason other than being stopped on purpose, wait and then connect
+/// again, waiting longer after every connection that does not last.
+async fn listen() {
+    let mut delay: Duration = RESTART_DELAY;
+
+    loop {
+        if STOPPED.load(Ordering::SeqCst) {
+            info!("Discord bot is stopped. Waiting for a restart.");
+            wait(None).await;
+            continue;
+        }
+
+        RESTART_NOW.store(false, Ordering::SeqCst);
+        let started: Instant = Instant::now();
+        run_client().await;
+
+        if STOPPED.load(Ordering::SeqCst) {
+            continue;
+        }
+
+        if RESTART_NOW.swap(false, Ordering::SeqCst) {
+            delay = RESTART_DELAY;
+            continue;
         }
+
+        if started.elapsed() >= STABLE_CONNECTION {
+            delay = RESTART_DELAY;
+        }
+
+        error!(
+            "Discord bot is down. Restarting in {} seconds.",
+            delay.as_secs()
+        );
+        wait(Some(delay)).await;
+        delay = (delay * 2).min(MAX_RESTART_DELAY);
     }
 }
 
-impl Actor for DiscordBot {
-    type Context = Context<Self>;
+/// Deliver messages from web handlers one at a time, in the order they were
+/// sent.
+async fn deliver(mut receiver: Receiver<DiscordMessage>) {
+    while let Some(message) = receiver.next().await {
+        message.deliver().await;
+    }
+}
+
+/// Start the Discord bot on a dedicated thread.
+pub fn start() {
+    // Get the global Discord config
+    let discord_conf: &DiscordConfig = &global_config().discord_config;
 
-    fn started(&mut self, ctx: &mut Self::Context) {
-        info!("Starting Discord bot");
+    // Log a link to invite the bot to a server.
+    info!("Invite bot using \
+    https://discord.com/api/oauth2/authorize?client_id={}&permissions=53955553361&response_type=code&scope=bot%20applications.commands",
+          discord_conf.client_id.as_str());
 
-        // Get the global Discord config
-        let discord_conf: &DiscordConfig = &global_config().discord_config;
+    let (sender, receiver) = mpsc::channel(OUTBOX_CAPACITY);
+    *OUTBOX.lock().expect("Discord outbox lock poisoned") = Some(sender);
 
-        // Log a link to invite the bot to a server.
-        info!("Invite bot using \
-        https://discord.com/api/oauth2/authorize?client_id={}&permissions=19595806737&response_type=code&scope=bot%20applications.commands",
-              discord_conf.client_id.as_str());
+    thread::Builder::new()
+        .name("discord-bot".into())
+        .spawn(move || {
+            info!("Starting Discord bot");
+            // The bot gets its own runtime so that nothing it does can block
+            // the web server's workers.
+            System::new("discord-bot").block_on(async move {
+                // Load server settings before connecting, so that servers are
+                // recognized as they connect.
+                if let Err(e) = guilds::reload().await {
+                    error!("Could not load Discord server settings: {}", e);
+                }
+                futures::join!(
+                    listen(),
+                    deliver(receiver),
+                    threads::archive_periodically(),
+                    roles::reconcile_periodically()
+                );
+            });
+        })
+        .expect("Could not spawn Discord bot thread.");
+}
 
-        // Listen for incoming Discord events on this actor's context.
-        ctx.wait(Self::wrapped_create_and_listen());
+/// Hand a message to the Discord bot without waiting for it to be delivered.
+/// This fails if the bot is not running or is too far behind to accept more.
+///
+/// In cluster mode the bot only runs on the leader, so the message is
+/// broadcast to every replica and this cannot tell if it was accepted.
+pub fn send(message: DiscordMessage) -> Result<(), TelescopeError> {
+    if global_config().cluster_mode {
+        cluster::broadcast(Broadcast::Discord(message));
+        return Ok(());
     }
+    return enqueue(message);
 }
 
-impl Supervised for DiscordBot {
-    fn restarting(&mut self, ctx: &mut Self::Context) {
-        // Restart the Discord bot when it crashes.
-        error!("Discord bot crashed. Restarting now");
-        // Start listening for Discord events again on the new context.
-        ctx.wait(Self::wrapped_create_and_listen());
+/// Hand a message broadcast by another replica to the bot, if it runs here.
+pub(crate) fn receive(message: DiscordMessage) {
+    let running: bool = OUTBOX.lock().map_or(false, |outbox| outbox.is_some());
+    if running {
+        if let Err(e) = enqueue(message) {
+            error!(
+                "Could not hand a broadcast message to the Discord bot: {}",
+                e
+            );
+        }
     }
 }
+
+/// Put a message in the outbox of the bot running in this process.
+fn enqueue(message: DiscordMessage) -> Result<(), TelescopeError> {
+    let mut outbox = OUTBOX
+        .lock()
+        .map_err(|_| TelescopeError::ise("Discord outbox lock poisoned."))?;
+
+    let sender: &mut Sender<DiscordMessage> = outbox
+        .as_mut()
+        .ok_or_else(|| TelescopeError::ise("The Discord bot has not been started."))?;
+
+    return sender.try_send(message).map_err(|e| {
+        if e.is_full() {
+            TelescopeError::ise("The Discord bot is busy. Please try again later.")
+        } else {
+            TelescopeError::ise("The Discord bot has stopped.")
+        }
+    });
+}
@@ -0,0 +1,181 @@
+//! Discord roles that follow members' enrollment.
+//!
+//! Servers can set a role for students, mentors, coordinators, and faculty of
+//! the current semester. Members with Discord linked get the roles that apply
+//! to them and lose the ones that no longer do. A member's roles are synced
+//! when they link Discord or join the RCOS server, and every server is
+//! reconciled every few hours to catch enrollment changes. Members without a
+//! linked account are left alone, so roles given by hand to guests stay.
+
+use crate::api::discord::global_discord_client;
+use crate::api::rcos::users::enrollment_roles::{
+    EnrollmentRole, LinkedEnrollmentRoles, LinkedMember, UserEnrollmentRoles,
+};
+use crate::discord_bot::guilds::{self, GuildSettings};
+use crate::error::TelescopeError;
+use actix::clock::delay_for;
+use serenity::model::guild::Member;
+use serenity::model::id::RoleId;
+use std::collections::HashMap;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How often every server's enrollment roles are reconciled.
+const RECONCILE_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// How many members to ask Discord for at once. This is the most Discord
+/// allows.
+const MEMBER_PAGE_SIZE: u64 = 1000;
+
+/// How many role changes a sync made.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct RoleChanges {
+    pub added: usize,
+    pub removed: usize,
+    pub failed: usize,
+}
+
+/// Servers that set at least one enrollment role.
+fn managed_guilds() -> Vec<GuildSettings> {
+    guilds::all()
+        .into_iter()
+        .filter(|guild| {
+            EnrollmentRole::ALL
+                .iter()
+                .any(|role| guild.enrollment_role_id(*role).is_some())
+        })
+        .collect()
+}
+
+/// Add and remove a member's enrollment roles on a server to match what they
+/// should have.
+async fn apply(
+    guild: &GuildSettings,
+    member: &Member,
+    linked: &LinkedMember,
+    changes: &mut RoleChanges,
+) {
+    for role in EnrollmentRole::ALL.iter() {
+        let role_id: u64 = match guild.enrollment_role_id(*role) {
+            Some(role_id) => role_id,
+            None => continue,
+        };
+
+        let wanted: bool = linked.roles.contains(role);
+        let has: bool = member.roles.contains(&RoleId(role_id));
+        let result = if wanted && !has {
+            global_discord_client()
+                .add_member_role(guild.guild_id, member.user.id.0, role_id)
+                .await
+                .map(|_| changes.added += 1)
+        } else if !wanted && has {
+            global_discord_client()
+                .remove_member_role(guild.guild_id, member.user.id.0, role_id)
+                .await
+                .map(|_| changes.removed += 1)
+        } else {
+            continue;
+        };
+
+        if let Err(e) = result {
+            warn!(
+                "Could not update the {} role of {} on server {}: {}",
+                role,
+                member.user.tag(),
+                guild.guild_id,
+                e
+            );
+            changes.failed += 1;
+        }
+    }
+}
+
+/// Sync one user's enrollment roles on every server that sets any. Users
+/// without Discord linked and servers they are not in are skipped.
+pub async fn sync_user(user_id: Uuid) {
+    let linked: LinkedMember = match UserEnrollmentRoles::get(user_id).await {
+        Ok(Some(linked)) => linked,
+        Ok(None) => return,
+        Err(e) => {
+            error!(
+                "Could not get the enrollment roles of user {}: {}",
+                user_id, e
+            );
+            return;
+        }
+    };
+
+    let mut changes = RoleChanges::default();
+    for guild in managed_guilds() {
+        // This fails if the user is not in the server.
+        let member: Member = match global_discord_client()
+            .get_member(guild.guild_id, linked.discord_id)
+            .await
+        {
+            Ok(member) => member,
+            Err(_) => continue,
+        };
+        apply(&guild, &member, &linked, &mut changes).await;
+    }
+
+    info!(
+        "Synced enrollment roles of user {}: {} added, {} removed, {} failed.",
+        user_id, changes.added, changes.removed, changes.failed
+    );
+}
+
+/// Reconcile the enrollment roles of every linked member on every server that
+/// sets any.
+pub async fn reconcile() -> Result<RoleChanges, TelescopeError> {
+    let mut changes = RoleChanges::default();
+    let guilds: Vec<GuildSettings> = managed_guilds();
+    if guilds.is_empty() {
+        return Ok(changes);
+    }
+
+    let linked: HashMap<u64, LinkedMember> = LinkedEnrollmentRoles::get()
+        .await?
+        .into_iter()
+        .map(|member| (member.discord_id, member))
+        .collect();
+
+    for guild in guilds {
+        let mut after: Option<u64> = None;
+        loop {
+            // Listing members needs the server members intent turned on for
+            // the bot.
+            let members: Vec<Member> = global_discord_client()
+                .get_guild_members(guild.guild_id, Some(MEMBER_PAGE_SIZE), after)
+                .await
+                .map_err(TelescopeError::serenity_error)?;
+
+            for member in members.iter() {
+                if let Some(linked) = linked.get(&member.user.id.0) {
+                    apply(&guild, member, linked, &mut changes).await;
+                }
+            }
+
+            // Members come in order of ID. A short page is the last one.
+            after = members.last().map(|member| member.user.id.0);
+            if (members.len() as u64) < MEMBER_PAGE_SIZE {
+                break;
+            }
+        }
+    }
+
+    return Ok(changes);
+}
+
+/// Reconcile enrollment roles every so often, for as long as the bot runs.
+pub async fn reconcile_periodically() {
+    loop {
+        delay_for(RECONCILE_INTERVAL).await;
+        match reconcile().await {
+            Ok(changes) => info!(
+                "Reconciled enrollment roles: {} added, {} removed, {} failed.",
+                changes.added, changes.removed, changes.failed
+            ),
+            Err(e) => error!("Could not reconcile enrollment roles: {}", e),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Informal RSVPs collected from reactions on meeting announcements.
+//!
+//! Members who react to a meeting announcement with [`RSVP_EMOJI`] are
+//! recorded as planning to come. Reactions from Discord accounts linked to
+//! Telescope are tied to the account, so that the meeting page can tell them
+//! apart from the rest.
+
+use crate::api::rcos::meetings::discord::{AddDiscordRsvp, AnnouncedMeeting, RemoveDiscordRsvp};
+use crate::api::rcos::prelude::MeetingId;
+use crate::api::rcos::users::accounts::reverse_lookup::ReverseLookup;
+use crate::api::rcos::users::UserAccountType;
+use crate::error::TelescopeError;
+use serenity::client::Context;
+use serenity::model::channel::{Reaction, ReactionType};
+use serenity::model::id::UserId;
+
+/// The reaction that counts as an RSVP.
+pub const RSVP_EMOJI: &'static str = "✅";
+
+/// Record a reaction being added or removed, if it is an RSVP on a meeting
+/// announcement. Other reactions are ignored.
+pub async fn reaction_changed(ctx: &Context, reaction: &Reaction, added: bool) {
+    let is_rsvp: bool = match &reaction.emoji {
+        ReactionType::Unicode(emoji) => emoji == RSVP_EMOJI,
+        _ => false,
+    };
+
+    // The bot adds the first reaction itself. Don't count it.
+    let user_id: UserId = match reaction.user_id {
+        Some(user_id) if is_rsvp && user_id != ctx.cache.current_user_id().await => user_id,
+        _ => return,
+    };
+
+    if let Err(e) = record(reaction, user_id, added).await {
+        error!(
+            "Could not record RSVP reaction from Discord user {} on message {}: {}",
+            user_id, reaction.message_id, e
+        );
+    }
+}
+
+/// Add or remove the RSVP of a Discord user.
+async fn record(reaction: &Reaction, user_id: UserId, added: bool) -> Result<(), TelescopeError> {
+    let meeting_id: MeetingId = match AnnouncedMeeting::get(reaction.message_id.to_string()).await?
+    {
+        Some(meeting_id) => meeting_id,
+        // Not a meeting announcement.
+        None => return Ok(()),
+    };
+
+    let discord_id: String = user_id.to_string();
+    if !added {
+        return RemoveDiscordRsvp::execute(meeting_id, discord_id).await;
+    }
+
+    let linked_user = ReverseLookup::execute(UserAccountType::Discord, discord_id.clone()).await?;
+    return AddDiscordRsvp::execute(meeting_id, discord_id, linked_user).await;
+}
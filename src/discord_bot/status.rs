@@ -0,0 +1,153 @@
+//! What the Discord bot is doing, for the admin panel.
+//!
+//! The bot thread records its connection state, the commands it registered,
+//! and the outcome of actions requested from the admin panel here. Web
+//! handlers read a snapshot with [`get`].
+
+use chrono::{DateTime, Utc};
+use serenity::http::Http;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, RwLock};
+
+/// How many finished actions to remember.
+const ACTION_LOG_LENGTH: usize = 10;
+
+/// The outcome of an action requested from the admin panel.
+#[derive(Clone, Debug, Serialize)]
+pub struct ActionLog {
+    /// What was done.
+    pub action: String,
+    /// When it finished.
+    pub finished: DateTime<Utc>,
+    /// Whether it worked.
+    pub ok: bool,
+    /// What happened, for the admin panel.
+    pub message: String,
+}
+
+/// The outcome of the last role sync.
+#[derive(Clone, Debug, Serialize)]
+pub struct RoleSyncResult {
+    /// When the sync finished.
+    pub finished: DateTime<Utc>,
+    /// Members of the RCOS server with a linked Telescope account.
+    pub linked: usize,
+    /// Members who were missing the role and got it.
+    pub added: usize,
+    /// Members who could not be given the role.
+    pub failed: usize,
+    /// Set if the sync could not run at all.
+    pub error: Option<String>,
+}
+
+/// A snapshot of the bot's state.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct BotStatus {
+    /// Whether the gateway connection is up.
+    pub connected: bool,
+    /// Whether the bot was stopped from the admin panel.
+    pub stopped: bool,
+    /// When the connection last went up or down.
+    pub since: Option<DateTime<Utc>>,
+    /// The bot's Discord tag, once it has connected.
+    pub user_tag: Option<String>,
+    /// Why the client last stopped, if it has.
+    pub last_error: Option<String>,
+    /// The commands registered on each server, by server ID.
+    pub commands: BTreeMap<String, Vec<String>>,
+    /// The commands registered globally.
+    pub global_commands: Vec<String>,
+    /// The outcome of the last role sync, if there has been one.
+    pub last_role_sync: Option<RoleSyncResult>,
+    /// Recently finished actions, newest first.
+    pub actions: VecDeque<ActionLog>,
+}
+
+lazy_static! {
+    static ref STATUS: RwLock<BotStatus> = RwLock::new(BotStatus::default());
+
+    /// The HTTP client of the connected bot. Unlike the global Discord client,
+    /// this one knows the application ID, which registering commands needs.
+    static ref CLIENT_HTTP: RwLock<Option<Arc<Http>>> = RwLock::new(None);
+}
+
+/// Get a snapshot of the bot's state.
+pub fn get() -> BotStatus {
+    STATUS
+        .read()
+        .map(|status| status.clone())
+        .unwrap_or_default()
+}
+
+/// Change the bot's state.
+fn update<F: FnOnce(&mut BotStatus)>(f: F) {
+    if let Ok(mut status) = STATUS.write() {
+        f(&mut status);
+    }
+}
+
+/// Record that the bot connected.
+pub fn connected(user_tag: String, http: Arc<Http>) {
+    update(|status| {
+        status.connected = true;
+        status.since = Some(Utc::now());
+        status.user_tag = Some(user_tag);
+    });
+
+    if let Ok(mut client_http) = CLIENT_HTTP.write() {
+        *client_http = Some(http);
+    }
+}
+
+/// Record that the client stopped, with the reason if there is one.
+pub fn disconnected(error: Option<String>) {
+    update(|status| {
+        status.connected = false;
+        status.since = Some(Utc::now());
+        status.last_error = error;
+    });
+
+    if let Ok(mut client_http) = CLIENT_HTTP.write() {
+        *client_http = None;
+    }
+}
+
+/// Record that the bot was stopped or restarted from the admin panel.
+pub fn stopped(stopped: bool) {
+    update(|status| status.stopped = stopped);
+}
+
+/// Get the HTTP client of the connected bot, if it is connected.
+pub fn client_http() -> Option<Arc<Http>> {
+    CLIENT_HTTP.read().ok().and_then(|http| http.clone())
+}
+
+/// Record the commands registered on a server.
+pub fn commands_registered(guild_id: u64, names: Vec<String>) {
+    update(|status| {
+        status.commands.insert(guild_id.to_string(), names);
+    });
+}
+
+/// Record the commands registered globally.
+pub fn global_commands_registered(names: Vec<String>) {
+    update(|status| status.global_commands = names);
+}
+
+/// Record the outcome of a role sync.
+pub fn role_sync_finished(result: RoleSyncResult) {
+    update(|status| status.last_role_sync = Some(result));
+}
+
+/// Record the outcome of an action requested from the admin panel.
+pub fn action_finished(action: &str, ok: bool, message: String) {
+    update(|status| {
+        status.actions.push_front(ActionLog {
+            action: action.to_string(),
+            finished: Utc::now(),
+            ok,
+            message,
+        });
+        status.actions.truncate(ACTION_LOG_LENGTH);
+    });
+}
@@ -0,0 +1,176 @@
+//! Discord threads for questions about workshops.
+//!
+//! When a workshop is announced, a thread for questions is started from the
+//! announcement. Once the meeting has a slides link, it is posted and pinned
+//! in the thread. Threads are archived after their meeting ends.
+
+use crate::api::discord::global_discord_client;
+use crate::api::rcos::meetings::discord::{
+    FinishedMeetingThreads, MarkThreadsArchived, MeetingThreads, SetThreadSlidesMessage,
+};
+use crate::api::rcos::meetings::get_by_id::{meeting::MeetingMeeting, Meeting};
+use crate::api::rcos::meetings::MeetingType;
+use crate::api::rcos::prelude::MeetingId;
+use actix::clock::delay_for;
+use serenity::model::id::{ChannelId, MessageId};
+use serenity::Result as SerenityResult;
+use std::time::Duration;
+
+/// The most characters Discord allows in a thread name.
+const THREAD_NAME_LIMIT: usize = 100;
+
+/// How long Discord waits before archiving a thread nobody has posted in, in
+/// minutes. Threads are archived when their meeting ends anyway.
+const AUTO_ARCHIVE_MINUTES: u16 = 1440;
+
+/// How often to look for threads of meetings that have ended.
+const ARCHIVE_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Check if a meeting gets a thread when it is announced.
+pub fn is_workshop(meeting: &MeetingMeeting) -> bool {
+    meeting.type_ == MeetingType::BonusSession
+}
+
+/// Post the slides link in a thread and pin it.
+async fn post_slides(thread: ChannelId, url: &str) -> SerenityResult<MessageId> {
+    let message = thread
+        .say(global_discord_client(), format!("Slides: {}", url))
+        .await?;
+    message.pin(global_discord_client()).await?;
+    return Ok(message.id);
+}
+
+/// Start a thread for questions from a workshop announcement, and post the
+/// slides if the meeting already has them. Return the thread and the slides
+/// message.
+pub async fn open(
+    meeting: &MeetingMeeting,
+    channel_id: ChannelId,
+    message_id: MessageId,
+) -> SerenityResult<(ChannelId, Option<MessageId>)> {
+    let name: String = format!("Questions: {}", meeting.title())
+        .chars()
+        .take(THREAD_NAME_LIMIT)
+        .collect();
+
+    let thread = channel_id
+        .create_public_thread(global_discord_client(), message_id, |thread| {
+            thread
+                .name(name)
+                .auto_archive_duration(AUTO_ARCHIVE_MINUTES)
+        })
+        .await?;
+
+    let slides: Option<MessageId> = match meeting.external_presentation_url.as_deref() {
+        Some(url) => match post_slides(thread.id, url).await {
+            Ok(message_id) => Some(message_id),
+            Err(e) => {
+                warn!(
+                    "Could not post slides in the thread for meeting {}: {}",
+                    meeting.meeting_id, e
+                );
+                None
+            }
+        },
+        None => None,
+    };
+
+    return Ok((thread.id, slides));
+}
+
+/// Post the slides in a meeting's threads that don't have them yet, if the
+/// meeting has slides now.
+pub async fn meeting_changed(meeting_id: MeetingId) {
+    let url: String = match Meeting::get(meeting_id).await {
+        Ok(Some(meeting)) => match meeting.external_presentation_url {
+            Some(url) => url,
+            None => return,
+        },
+        Ok(None) => return,
+        Err(e) => {
+            error!(
+                "Could not get meeting {} to update threads: {}",
+                meeting_id, e
+            );
+            return;
+        }
+    };
+
+    let threads = match MeetingThreads::get(meeting_id).await {
+        Ok(threads) => threads,
+        Err(e) => {
+            error!("Could not get threads of meeting {}: {}", meeting_id, e);
+            return;
+        }
+    };
+
+    for thread in threads
+        .into_iter()
+        .filter(|t| t.slides_message_id.is_none())
+    {
+        let thread_id: u64 = match thread.thread_id.as_deref().and_then(|id| id.parse().ok()) {
+            Some(id) => id,
+            None => continue,
+        };
+
+        let slides: MessageId = match post_slides(ChannelId(thread_id), url.as_str()).await {
+            Ok(message_id) => message_id,
+            Err(e) => {
+                warn!("Could not post slides in thread {}: {}", thread_id, e);
+                continue;
+            }
+        };
+
+        if let Err(e) = SetThreadSlidesMessage::execute(thread.message_id, slides.to_string()).await
+        {
+            error!(
+                "Could not record slides message in thread {}: {}",
+                thread_id, e
+            );
+        }
+    }
+}
+
+/// Archive the threads of every meeting that has ended.
+async fn archive_finished() {
+    let threads = match FinishedMeetingThreads::get().await {
+        Ok(threads) => threads,
+        Err(e) => {
+            error!("Could not get threads of finished meetings: {}", e);
+            return;
+        }
+    };
+
+    if threads.is_empty() {
+        return;
+    }
+
+    // Discord archives idle threads on its own, so threads that could not be
+    // archived here are not tried again.
+    let mut archived: Vec<String> = Vec::with_capacity(threads.len());
+    for thread in threads {
+        if let Some(thread_id) = thread.thread_id.as_deref().and_then(|id| id.parse().ok()) {
+            if let Err(e) = ChannelId(thread_id)
+                .edit_thread(global_discord_client(), |edit| edit.archived(true))
+                .await
+            {
+                warn!("Could not archive thread {}: {}", thread_id, e);
+            }
+        }
+        archived.push(thread.message_id);
+    }
+
+    info!("Archived {} meeting threads.", archived.len());
+    if let Err(e) = MarkThreadsArchived::execute(archived).await {
+        error!("Could not mark meeting threads archived: {}", e);
+    }
+}
+
+/// Archive the threads of finished meetings every so often, for as long as
+/// the bot runs.
+pub async fn archive_periodically() {
+    loop {
+        delay_for(ARCHIVE_INTERVAL).await;
+        archive_finished().await;
+    }
+}
@@ -1,4 +1,8 @@
-use oauth2::{ClientId, ClientSecret};
+use crate::api::rcos::meetings::MeetingType;
+use crate::error::TelescopeError;
+use crate::logging;
+use ipnet::IpNet;
+use oauth2::{AccessToken, ClientId, ClientSecret};
 use std::sync::Arc;
 use std::{collections::HashMap, env, path::PathBuf};
 use std::{fs::File, io::Read, process::exit};
@@ -13,6 +17,314 @@ pub struct GithubOauthConfig {
     pub client_id: ClientId,
     /// The GitHub OAuth application client secret.
     pub client_secret: ClientSecret,
+    /// Optional personal access token used by background jobs to read
+    /// repository activity. Contribution tracking is disabled without it.
+    #[serde(default)]
+    pub api_token: Option<AccessToken>,
+    /// Optional repository, as `owner/name`, that admins can open issues on
+    /// from error reports. The API token needs permission to write issues
+    /// there.
+    #[serde(default)]
+    pub issue_repository: Option<String>,
+}
+
+impl GithubOauthConfig {
+    /// The owner and name of the repository to open issues on, if one is set
+    /// along with an API token.
+    pub fn issue_repository(&self) -> Option<(&AccessToken, &str, &str)> {
+        let token: &AccessToken = self.api_token.as_ref()?;
+        let (owner, name) = self.issue_repository.as_ref()?.split_once('/')?;
+        return Some((token, owner, name));
+    }
+}
+
+/// Access to a GitLab instance for reading repository activity.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GitLabConfig {
+    /// The base URL of the GitLab instance, without a trailing slash.
+    /// Repositories hosted here are read through its API.
+    #[serde(default = "GitLabConfig::default_url")]
+    pub url: String,
+    /// Optional access token with the `read_api` scope. Public projects can
+    /// be read without one.
+    #[serde(default)]
+    pub api_token: Option<AccessToken>,
+}
+
+impl GitLabConfig {
+    /// GitLab's hosted instance.
+    fn default_url() -> String {
+        "https://gitlab.com".into()
+    }
+}
+
+/// How long around a meeting attendance codes are accepted.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct CheckInWindow {
+    /// Minutes before the meeting starts that check-in opens.
+    pub opens_before: i64,
+    /// Minutes after the meeting ends that check-in closes.
+    pub closes_after: i64,
+}
+
+impl Default for CheckInWindow {
+    fn default() -> Self {
+        CheckInWindow {
+            opens_before: 15,
+            closes_after: 15,
+        }
+    }
+}
+
+/// Limits on how many requests of each class can be handled at once. Requests
+/// over a limit get a 503 response asking the client to retry later.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConcurrencyLimits {
+    /// Static files, login, and registration. These do little work, and are
+    /// limited separately so they keep working when pages are saturated.
+    pub light: usize,
+    /// Pages and forms, most of which query the RCOS API.
+    pub pages: usize,
+    /// Generated downloads like spreadsheets, PDFs, and calendars.
+    pub exports: usize,
+    /// Seconds clients are asked to wait before retrying.
+    pub retry_after: u64,
+}
+
+impl Default for ConcurrencyLimits {
+    fn default() -> Self {
+        ConcurrencyLimits {
+            light: 512,
+            pages: 128,
+            exports: 4,
+            retry_after: 5,
+        }
+    }
+}
+
+/// How many requests per minute each tier of public API caller can make.
+/// Anonymous callers are counted by IP address, and key holders by key.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiRateLimits {
+    pub anonymous: u64,
+    pub standard: u64,
+    pub elevated: u64,
+}
+
+impl Default for ApiRateLimits {
+    fn default() -> Self {
+        ApiRateLimits {
+            anonymous: 30,
+            standard: 300,
+            elevated: 3000,
+        }
+    }
+}
+
+/// How many seconds requests of each class have to finish their calls to
+/// upstream services, like the RCOS API, GitHub, and Discord. Classes are the
+/// same as for [`ConcurrencyLimits`].
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RequestDeadlines {
+    pub light: u64,
+    pub pages: u64,
+    pub exports: u64,
+}
+
+impl Default for RequestDeadlines {
+    fn default() -> Self {
+        RequestDeadlines {
+            light: 10,
+            pages: 15,
+            exports: 60,
+        }
+    }
+}
+
+/// How many milliseconds requests of each class should take before a warning
+/// is logged for being slow. Classes are the same as for
+/// [`ConcurrencyLimits`].
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoadBudgets {
+    pub light: u64,
+    pub pages: u64,
+    pub exports: u64,
+}
+
+impl Default for LoadBudgets {
+    fn default() -> Self {
+        LoadBudgets {
+            light: 250,
+            pages: 1000,
+            exports: 10000,
+        }
+    }
+}
+
+/// Limits and checks for files uploaded through forms.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UploadConfig {
+    /// The most bytes accepted in one form submission.
+    pub max_size: usize,
+    /// Where uploads that fail a malware scan are kept for review.
+    pub quarantine_dir: PathBuf,
+    /// Where processed profile pictures and logos are stored.
+    pub images_dir: PathBuf,
+    /// The address of a ClamAV daemon to scan uploads with, like
+    /// `127.0.0.1:3310`. Uploads are not scanned without it.
+    pub clamav: Option<String>,
+}
+
+impl Default for UploadConfig {
+    fn default() -> Self {
+        UploadConfig {
+            max_size: 10 * 1024 * 1024,
+            quarantine_dir: PathBuf::from("uploads/quarantine"),
+            images_dir: PathBuf::from("uploads/images"),
+            clamav: None,
+        }
+    }
+}
+
+/// Rules for removing personal data once it is no longer needed. Rules that
+/// are not set are not enforced.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionConfig {
+    /// Delete attendance records and excused absences of meetings that started
+    /// more than this many years ago.
+    pub attendance_years: Option<u32>,
+    /// Anonymize accounts this many days after they are marked withdrawn.
+    pub anonymize_withdrawn_days: Option<u32>,
+    /// Only log what the retention job would remove, without removing it.
+    pub dry_run: bool,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        RetentionConfig {
+            attendance_years: None,
+            anonymize_withdrawn_days: None,
+            dry_run: true,
+        }
+    }
+}
+
+/// A Unix domain socket to listen on, for deployments where the reverse proxy
+/// runs on the same host.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UnixSocketConfig {
+    /// Where to make the socket. A stale socket left here is removed.
+    pub path: PathBuf,
+    /// The permissions to give the socket, like `0o660`. The umask applies
+    /// if this is not set.
+    pub mode: Option<u32>,
+}
+
+/// Certificate and key files to serve HTTPS with.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// PEM file of the certificate chain, starting with the server's certificate.
+    pub cert: PathBuf,
+    /// PEM file of the private key, in PKCS#8 or PKCS#1 (RSA) format.
+    pub key: PathBuf,
+}
+
+/// How log lines are written to standard error.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// The human-readable env_logger format.
+    Text,
+    /// One JSON object per line, for log collectors like Loki or Elasticsearch.
+    Json,
+}
+
+impl Default for LogFormat {
+    fn default() -> Self {
+        LogFormat::Text
+    }
+}
+
+/// Where short-lived session state, like OAuth CSRF tokens and form
+/// idempotency keys, is kept.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum SessionStoreConfig {
+    /// In the memory of this process. State is lost on restart and is not
+    /// shared with other replicas.
+    Memory,
+    /// In a Redis server shared by every replica.
+    Redis {
+        /// Connection URL, like `redis://127.0.0.1:6379/0`.
+        url: String,
+        /// Prefix for every key Telescope stores, so the server can be shared.
+        #[serde(default = "SessionStoreConfig::default_prefix")]
+        prefix: String,
+    },
+}
+
+impl SessionStoreConfig {
+    fn default_prefix() -> String {
+        "telescope:".into()
+    }
+}
+
+impl Default for SessionStoreConfig {
+    fn default() -> Self {
+        SessionStoreConfig::Memory
+    }
+}
+
+/// Zoom Server-to-Server OAuth app used to create calls for remote meetings.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ZoomConfig {
+    /// The Zoom account the app is installed on.
+    pub account_id: String,
+    /// The app's client ID.
+    pub client_id: String,
+    /// The app's client secret.
+    pub client_secret: String,
+    /// The Zoom user that hosts created calls. Defaults to the app's own user.
+    #[serde(default = "ZoomConfig::default_user")]
+    pub user: String,
+}
+
+impl ZoomConfig {
+    /// The user the app is authenticated as.
+    fn default_user() -> String {
+        "me".into()
+    }
+}
+
+/// Sources of meeting recordings to attach to meetings automatically. Either
+/// source can be used without the other.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordingIngestConfig {
+    /// A YouTube Data API key, to read the playlist with.
+    pub youtube_api_key: Option<String>,
+    /// The YouTube playlist new recordings are added to. A channel's uploads
+    /// are the playlist with the channel's ID, starting with `UU` instead of
+    /// `UC`.
+    pub youtube_playlist_id: Option<String>,
+    /// The bearer token other services post recordings to the recording
+    /// webhook with. The webhook is off without it.
+    pub webhook_secret: Option<String>,
+}
+
+impl RecordingIngestConfig {
+    /// The YouTube API key and playlist ID, if both are set.
+    pub fn youtube(&self) -> Option<(&str, &str)> {
+        Some((
+            self.youtube_api_key.as_deref()?,
+            self.youtube_playlist_id.as_deref()?,
+        ))
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -29,6 +341,21 @@ pub struct DiscordConfig {
 
     /// The RCOS Discord Guild ID.
     pub rcos_guild_id: String,
+
+    /// The ID of the channel on the RCOS Discord that links to new polls are
+    /// posted in. Polls are not cross-posted if this is not set.
+    pub announcements_channel_id: Option<String>,
+
+    /// Register slash commands globally, so they work on every server the bot
+    /// is in. Discord can take up to an hour to roll out global commands.
+    #[serde(default)]
+    pub global_commands: bool,
+
+    /// Servers to always register slash commands on, whatever their saved
+    /// settings. Server commands update right away, which helps when working
+    /// on commands.
+    #[serde(default)]
+    pub debug_guild_ids: Vec<String>,
 }
 
 impl DiscordConfig {
@@ -39,6 +366,80 @@ impl DiscordConfig {
             .parse::<u64>()
             .expect("Malformed RCOS Guild ID")
     }
+
+    /// Get the announcements channel ID as a `u64` if one is configured.
+    pub fn announcements_channel_id(&self) -> Option<u64> {
+        self.announcements_channel_id.as_ref().map(|id| {
+            id.as_str()
+                .parse::<u64>()
+                .expect("Malformed announcements channel ID")
+        })
+    }
+
+    /// Get the debug Guild IDs as `u64`s.
+    pub fn debug_guild_ids(&self) -> Vec<u64> {
+        self.debug_guild_ids
+            .iter()
+            .map(|id| {
+                id.as_str()
+                    .parse::<u64>()
+                    .expect("Malformed debug Guild ID")
+            })
+            .collect()
+    }
+}
+
+/// Config for one organization (club) served by this Telescope instance. Organizations
+/// are keyed by their slug in the config file, which should match the `org_id` used in the
+/// RCOS database.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrganizationConfig {
+    /// The display name of the organization.
+    pub name: String,
+
+    /// A short description shown on the organization's landing page.
+    pub description: Option<String>,
+
+    /// URL (or path under `/static`) of the organization's logo.
+    pub logo_url: Option<String>,
+
+    /// The ID of the organization's Discord server, if it has one. This is used to
+    /// embed the server widget on the organization's pages.
+    pub discord_guild_id: Option<String>,
+}
+
+impl OrganizationConfig {
+    /// Get the organization's Discord Guild ID as a `u64` if one is configured.
+    /// Error if the configured ID is not a number.
+    pub fn discord_guild_id(&self) -> Result<Option<u64>, TelescopeError> {
+        self.discord_guild_id
+            .as_ref()
+            .map(|id| {
+                id.as_str().parse::<u64>().map_err(|_| {
+                    TelescopeError::ise(format!(
+                        "Malformed Discord Guild ID for organization {}.",
+                        self.name
+                    ))
+                })
+            })
+            .transpose()
+    }
+}
+
+/// A statement users are asked to check when they create an account, like
+/// "I am over 18". Answers are stored with the account.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AttestationConfig {
+    /// Identifies the attestation in stored answers and the faculty report.
+    /// Should not change once users have answered.
+    pub key: String,
+
+    /// The statement shown next to the checkbox.
+    pub label: String,
+
+    /// If users have to check the box to create an account.
+    #[serde(default)]
+    pub required: bool,
 }
 
 /// The config of the server instance.
@@ -48,18 +449,35 @@ struct TelescopeConfig {
     /// See <https://docs.rs/env_logger/0.8.1/env_logger/> for reference.
     log_level: Option<String>,
 
+    /// How log lines are written.
+    log_format: Option<LogFormat>,
+
     /// GitHub OAuth application credentials.
     github_credentials: Option<GithubOauthConfig>,
 
     /// Discord application config and credentials.
     discord_config: Option<DiscordConfig>,
 
+    /// GitLab instance config. GitLab repositories are not read without it.
+    gitlab: Option<GitLabConfig>,
+
+    /// Zoom app config. Calls are not created for remote meetings without it.
+    zoom: Option<ZoomConfig>,
+
+    /// Where meeting recordings are picked up from. Recordings are only
+    /// attached by hand without it.
+    recording_ingest: Option<RecordingIngestConfig>,
+
     /// The URL of the RCOS central API (in the OpenAPI Spec via RCOS-data).
     api_url: Option<String>,
 
     /// The JWT secret used to authenticate with the central API.
     jwt_secret: Option<String>,
 
+    /// Directory of JSON fixtures to answer RCOS API queries from instead of
+    /// sending them to the API. Meant for working on pages without a database.
+    api_fixtures: Option<PathBuf>,
+
     /// Profiles. These can be used and specified at runtime to override values
     /// defined globally. Profiles are scoped and can have sub profiles.
     profile: Option<HashMap<String, TelescopeConfig>>,
@@ -67,6 +485,77 @@ struct TelescopeConfig {
     /// The URL that Telescope is running at. This is used in Discord embeds
     /// and the Open Graph Protocol meta tags. Should not end with a slash.
     telescope_url: Option<String>,
+
+    /// Organizations served by this instance, keyed by slug.
+    organizations: Option<HashMap<String, OrganizationConfig>>,
+
+    /// Domains meeting recordings may be hosted on. Any domain is allowed if
+    /// this is not set.
+    recording_domains: Option<Vec<String>>,
+
+    /// Check-in windows keyed by meeting type.
+    check_in_windows: Option<HashMap<String, CheckInWindow>>,
+
+    /// Limits on requests handled at once by route class.
+    concurrency_limits: Option<ConcurrencyLimits>,
+
+    /// Public API requests per minute by caller tier.
+    api_rate_limits: Option<ApiRateLimits>,
+
+    /// Time limits on upstream calls by route class.
+    request_deadlines: Option<RequestDeadlines>,
+
+    /// Time after which requests are logged as slow, by route class.
+    load_budgets: Option<LoadBudgets>,
+
+    /// Upload size limit, quarantine, and scanning.
+    uploads: Option<UploadConfig>,
+
+    /// Directory the full-text search index is kept in.
+    search_index_dir: Option<PathBuf>,
+
+    /// Check rendered pages for accessibility problems. Meant for development.
+    a11y_audit: Option<bool>,
+
+    /// Fail to render templates that use fields their handler did not set.
+    strict_templates: Option<bool>,
+
+    /// Attestations asked for at registration.
+    attestations: Option<Vec<AttestationConfig>>,
+
+    /// Rules for removing personal data.
+    retention: Option<RetentionConfig>,
+
+    /// Proxy for outbound plain HTTP requests.
+    http_proxy: Option<String>,
+
+    /// Proxy for outbound HTTPS requests.
+    https_proxy: Option<String>,
+
+    /// Proxies for single integrations, keyed by integration name. "direct"
+    /// means no proxy.
+    proxy_overrides: Option<HashMap<String, String>>,
+
+    /// Addresses to listen on.
+    bind_to: Option<Vec<String>>,
+
+    /// Address to serve the admin panel on, instead of the `bind_to` addresses.
+    admin_bind_to: Option<String>,
+
+    /// Reverse proxies whose forwarded headers are trusted for client addresses.
+    trusted_proxies: Option<Vec<IpNet>>,
+
+    /// Unix domain socket to listen on.
+    unix_socket: Option<UnixSocketConfig>,
+
+    /// Certificate to serve HTTPS with on the TCP listeners.
+    tls: Option<TlsConfig>,
+
+    /// Where session state is kept.
+    session_store: Option<SessionStoreConfig>,
+
+    /// Coordinate with other replicas through the session store.
+    cluster_mode: Option<bool>,
 }
 
 /// A concrete config found by searching the specified profile and parents
@@ -78,16 +567,95 @@ struct TelescopeConfig {
 pub struct ConcreteConfig {
     /// The log level. Private because the logger is initialized in this module.
     log_level: String,
+    /// How log lines are written.
+    pub log_format: LogFormat,
     /// The GitHub OAuth Application Credentials.
     pub github_credentials: GithubOauthConfig,
     /// The Discord Config and Credentials.
     pub discord_config: DiscordConfig,
+    /// The GitLab instance config, if any.
+    pub gitlab: Option<GitLabConfig>,
+    /// The Zoom app config, if any.
+    pub zoom: Option<ZoomConfig>,
+    /// Where meeting recordings are picked up from, if anywhere.
+    pub recording_ingest: Option<RecordingIngestConfig>,
     /// The url of the RCOS API that telescope will read and write to.
     pub api_url: String,
+    /// Directory of RCOS API fixtures to use instead of the API, if any.
+    pub api_fixtures: Option<PathBuf>,
     /// The domain that telescope is available at. Should not end with a slash.
     pub telescope_url: String,
     /// The JWT secret used to authenticate with the central API.
     pub jwt_secret: String,
+    /// The organizations served by this instance keyed by slug. May be empty.
+    pub organizations: HashMap<String, OrganizationConfig>,
+    /// Domains meeting recordings may be hosted on. Empty if any are allowed.
+    pub recording_domains: Vec<String>,
+    /// Check-in windows keyed by meeting type. Types without one use the default.
+    check_in_windows: HashMap<String, CheckInWindow>,
+    /// Limits on requests handled at once by route class.
+    pub concurrency_limits: ConcurrencyLimits,
+    /// Public API requests per minute by caller tier.
+    pub api_rate_limits: ApiRateLimits,
+    /// Time limits on upstream calls by route class.
+    pub request_deadlines: RequestDeadlines,
+    /// Time after which requests are logged as slow, by route class.
+    pub load_budgets: LoadBudgets,
+    /// Upload size limit, quarantine, and scanning.
+    pub uploads: UploadConfig,
+    /// Directory the full-text search index is kept in.
+    pub search_index_dir: PathBuf,
+    /// Check rendered pages for accessibility problems.
+    pub a11y_audit: bool,
+    /// Fail to render templates that use fields their handler did not set.
+    pub strict_templates: bool,
+    /// Attestations asked for at registration, in the order they are shown.
+    pub attestations: Vec<AttestationConfig>,
+    /// Rules for removing personal data. None are enforced by default.
+    pub retention: RetentionConfig,
+    /// Proxy for outbound plain HTTP requests, if any.
+    pub http_proxy: Option<String>,
+    /// Proxy for outbound HTTPS requests, if any.
+    pub https_proxy: Option<String>,
+    /// Proxies for single integrations, keyed by integration name. May be empty.
+    pub proxy_overrides: HashMap<String, String>,
+    /// Addresses to listen on.
+    pub bind_to: Vec<String>,
+    /// Address the admin panel is served on, if it is kept off the others.
+    pub admin_bind_to: Option<String>,
+    /// Reverse proxies whose `Forwarded` and `X-Forwarded-For` headers are
+    /// trusted to say the client's address. Empty by default.
+    pub trusted_proxies: Vec<IpNet>,
+    /// Unix domain socket to listen on, if any.
+    pub unix_socket: Option<UnixSocketConfig>,
+    /// Certificate to serve HTTPS with. Plain HTTP is served without it.
+    pub tls: Option<TlsConfig>,
+    /// Where session state is kept. In memory by default.
+    pub session_store: SessionStoreConfig,
+    /// Whether this is one of several replicas behind a load balancer.
+    pub cluster_mode: bool,
+}
+
+impl ConcreteConfig {
+    /// Get the configured log filter, in the env_logger syntax.
+    pub fn log_level(&self) -> &str {
+        self.log_level.as_str()
+    }
+
+    /// Get the config of an organization by its slug.
+    pub fn organization(&self, slug: &str) -> Option<&OrganizationConfig> {
+        self.organizations.get(slug)
+    }
+
+    /// Get the check-in window for a type of meeting.
+    pub fn check_in_window(&self, kind: MeetingType) -> CheckInWindow {
+        // Meeting types are keyed by their name in the RCOS database.
+        json!(kind)
+            .as_str()
+            .and_then(|key| self.check_in_windows.get(key))
+            .copied()
+            .unwrap_or_default()
+    }
 }
 
 impl TelescopeConfig {
@@ -113,25 +681,99 @@ impl TelescopeConfig {
         }
 
         let profile_slice = &profile[..];
+        let unix_socket = self.reverse_lookup(profile_slice, |c| c.unix_socket.clone());
         ConcreteConfig {
             log_level: self
                 .reverse_lookup(profile_slice, |c| c.log_level.clone())
                 .expect("Could not resolve log level."),
+            log_format: self
+                .reverse_lookup(profile_slice, |c| c.log_format)
+                .unwrap_or_default(),
             github_credentials: self
                 .reverse_lookup(profile_slice, |c| c.github_credentials.clone())
                 .expect("Could not resolve GitHub OAuth credentials."),
             discord_config: self
                 .reverse_lookup(profile_slice, |c| c.discord_config.clone())
                 .expect("Could not resolve Discord credentials"),
+            gitlab: self.reverse_lookup(profile_slice, |c| c.gitlab.clone()),
+            zoom: self.reverse_lookup(profile_slice, |c| c.zoom.clone()),
+            recording_ingest: self.reverse_lookup(profile_slice, |c| c.recording_ingest.clone()),
             api_url: self
                 .reverse_lookup(profile_slice, |c| c.api_url.clone())
                 .expect("Could not resolve RCOS central API URL."),
+            api_fixtures: self.reverse_lookup(profile_slice, |c| c.api_fixtures.clone()),
             jwt_secret: self
                 .reverse_lookup(profile_slice, |c| c.jwt_secret.clone())
                 .expect("Could not resolve JWT secret."),
             telescope_url: self
                 .reverse_lookup(profile_slice, |c| c.telescope_url.clone())
                 .expect("Could not resolve Telescope URl."),
+            organizations: self
+                .reverse_lookup(profile_slice, |c| c.organizations.clone())
+                .unwrap_or_default(),
+            recording_domains: self
+                .reverse_lookup(profile_slice, |c| c.recording_domains.clone())
+                .unwrap_or_default(),
+            check_in_windows: self
+                .reverse_lookup(profile_slice, |c| c.check_in_windows.clone())
+                .unwrap_or_default(),
+            concurrency_limits: self
+                .reverse_lookup(profile_slice, |c| c.concurrency_limits)
+                .unwrap_or_default(),
+            api_rate_limits: self
+                .reverse_lookup(profile_slice, |c| c.api_rate_limits)
+                .unwrap_or_default(),
+            request_deadlines: self
+                .reverse_lookup(profile_slice, |c| c.request_deadlines)
+                .unwrap_or_default(),
+            load_budgets: self
+                .reverse_lookup(profile_slice, |c| c.load_budgets)
+                .unwrap_or_default(),
+            uploads: self
+                .reverse_lookup(profile_slice, |c| c.uploads.clone())
+                .unwrap_or_default(),
+            search_index_dir: self
+                .reverse_lookup(profile_slice, |c| c.search_index_dir.clone())
+                .unwrap_or(PathBuf::from("search_index")),
+            a11y_audit: self
+                .reverse_lookup(profile_slice, |c| c.a11y_audit)
+                .unwrap_or(false),
+            strict_templates: self
+                .reverse_lookup(profile_slice, |c| c.strict_templates)
+                .unwrap_or(false),
+            attestations: self
+                .reverse_lookup(profile_slice, |c| c.attestations.clone())
+                .unwrap_or_default(),
+            retention: self
+                .reverse_lookup(profile_slice, |c| c.retention.clone())
+                .unwrap_or_default(),
+            http_proxy: self.reverse_lookup(profile_slice, |c| c.http_proxy.clone()),
+            https_proxy: self.reverse_lookup(profile_slice, |c| c.https_proxy.clone()),
+            proxy_overrides: self
+                .reverse_lookup(profile_slice, |c| c.proxy_overrides.clone())
+                .unwrap_or_default(),
+            // Only listen on port 80 by default if there is no Unix socket.
+            bind_to: self
+                .reverse_lookup(profile_slice, |c| c.bind_to.clone())
+                .unwrap_or_else(|| {
+                    if unix_socket.is_some() {
+                        Vec::new()
+                    } else {
+                        vec!["0.0.0.0:80".into()]
+                    }
+                }),
+            admin_bind_to: self.reverse_lookup(profile_slice, |c| c.admin_bind_to.clone()),
+            trusted_proxies: self
+                .reverse_lookup(profile_slice, |c| c.trusted_proxies.clone())
+                .unwrap_or_default(),
+            unix_socket,
+            tls: self.reverse_lookup(profile_slice, |c| c.tls.clone()),
+            session_store: self
+                .reverse_lookup(profile_slice, |c| c.session_store.clone())
+                .unwrap_or_default(),
+            cluster_mode: self
+                .reverse_lookup(profile_slice, |c| c.cluster_mode)
+                .unwrap_or(false),
         }
     }
 
@@ -188,6 +830,31 @@ struct CommandLine {
     /// 'dev.local'
     #[structopt(short = "p", long = "profile", env)]
     profile: Option<String>,
+    /// Run a one-off command instead of starting the web server.
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+/// One-off commands that can be run instead of the web server.
+#[derive(Clone, Debug, Serialize, StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum Command {
+    /// Fill the configured RCOS API with fake users, semesters, projects,
+    /// meetings, and enrollments for local development.
+    Seed {
+        /// How many users to create.
+        #[structopt(long, default_value = "60")]
+        users: usize,
+        /// How many semesters to fill, counting back from the current one.
+        #[structopt(long, default_value = "2")]
+        semesters: usize,
+        /// Seed for the random generator, to produce the same data every run.
+        #[structopt(long)]
+        random_seed: Option<u64>,
+        /// Seed the API even if it does not look like a local one.
+        #[structopt(long)]
+        force: bool,
+    },
 }
 
 lazy_static! {
@@ -200,11 +867,24 @@ pub fn init() {
     let cfg: &ConcreteConfig = &*CONFIG;
 
     // initialize logger.
-    env_logger::builder().parse_filters(&cfg.log_level).init();
+    logging::init(&cfg.log_level, cfg.log_format);
 
     info!("Starting up...");
     info!("telescope {}", env!("CARGO_PKG_VERSION"));
     trace!("Config: \n{}", serde_json::to_string_pretty(cfg).unwrap());
+
+    if let Some(directory) = cfg.api_fixtures.as_ref() {
+        warn!(
+            "Answering RCOS API queries from fixtures in {} instead of {}.",
+            directory.display(),
+            cfg.api_url
+        );
+    }
+}
+
+/// Get the command given on the command line, if any.
+pub fn command() -> Option<Command> {
+    CommandLine::from_args().command
 }
 
 /// Get the global configuration.
@@ -220,7 +900,16 @@ fn cli() -> ConcreteConfig {
     dotenv::dotenv().ok();
 
     // Get the command line args.
+    #[cfg(not(test))]
     let commandline: CommandLine = CommandLine::from_args();
+    // Tests run against the example config and its fixtures, since the test
+    // harness has its own arguments.
+    #[cfg(test)]
+    let commandline: CommandLine = CommandLine {
+        config_file: "config_example.toml".into(),
+        profile: Some("offline".into()),
+        command: None,
+    };
 
     // Read the config file into a string.
     let mut confing_file_string = String::new();
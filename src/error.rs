@@ -2,6 +2,8 @@
 
 use crate::templates::page::Page;
 use crate::templates::{jumbotron, Template};
+use crate::web::deadline;
+use crate::web::services::error_reports;
 use actix_web::dev::HttpResponseBuilder;
 use actix_web::error::Error as ActixError;
 use actix_web::http::header::CONTENT_TYPE;
@@ -90,6 +92,13 @@ pub enum TelescopeError {
     /// generated by the server. This should be reported as a bad request.
     CsrfTokenMismatch,
 
+    #[display(fmt = "Form submitted without a valid CSRF token")]
+    /// A form was submitted without the token for the browser's session, or
+    /// with one for another session. See
+    /// [the CSRF middleware](crate::web::middlewares::csrf). This should be
+    /// reported as forbidden.
+    CsrfFailure,
+
     #[error(ignore)]
     #[display(fmt = "Error interacting with RCOS API: {}", _0)]
     /// Error interacting with RCOS central API.
@@ -102,6 +111,12 @@ pub enum TelescopeError {
     /// report as an ISE.
     GitHubApiError(String),
 
+    #[error(ignore)]
+    #[display(fmt = "Error interacting with GitLab API: {}", _0)]
+    /// Error interacting with a GitLab instance's REST API. This should
+    /// generally report as an ISE.
+    GitLabApiError(String),
+
     #[error(ignore)]
     #[display(fmt = "Error interacting with Discord API: {}", _0)]
     /// Error interacting with the Discord API via Serenity. This should report
@@ -136,6 +151,12 @@ pub enum TelescopeError {
     /// sufficient permissions to access.
     Forbidden,
 
+    #[error(ignore)]
+    #[display(fmt = "{} did not respond before the request deadline", _0)]
+    /// A request ran out of time waiting on an upstream service. This should
+    /// report as a gateway timeout.
+    DeadlineExceeded(String),
+
     #[error(ignore)]
     #[display(fmt = "RPI CAS error: {}", _0)]
     /// Error sending to or receiving from the RPI CAS system.
@@ -157,18 +178,46 @@ impl TelescopeError {
         Self::InternalServerError(message.into())
     }
 
+    /// Construct the error for an upstream service that did not respond
+    /// before the request deadline.
+    pub fn deadline_exceeded(upstream: impl Into<String>) -> Self {
+        let upstream: String = upstream.into();
+        warn!("Request deadline passed waiting on {}.", upstream);
+        Self::DeadlineExceeded(upstream)
+    }
+
+    /// Check if a reqwest error is a timeout at the request deadline.
+    pub fn timed_out(err: &ReqwestError) -> bool {
+        err.is_timeout() && deadline::current().is_some()
+    }
+
     /// Convert a reqwest error from the RCOS API into a telescope error.
     pub fn rcos_api_error(err: ReqwestError) -> Self {
+        if Self::timed_out(&err) {
+            return Self::deadline_exceeded("The RCOS API");
+        }
         error!("Error querying RCOS API: {}", err);
         Self::RcosApiError(err.to_string())
     }
 
     /// Convert a reqwest error from the GitHub API into a telescope error.
     pub fn github_api_error(err: ReqwestError) -> Self {
+        if Self::timed_out(&err) {
+            return Self::deadline_exceeded("GitHub");
+        }
         error!("Error querying GitHub API: {}", err);
         Self::GitHubApiError(err.to_string())
     }
 
+    /// Convert a reqwest error from a GitLab API into a telescope error.
+    pub fn gitlab_api_error(err: ReqwestError) -> Self {
+        if Self::timed_out(&err) {
+            return Self::deadline_exceeded("GitLab");
+        }
+        error!("Error querying GitLab API: {}", err);
+        Self::GitLabApiError(err.to_string())
+    }
+
     /// Convert a Serenity error into a Telescope error.
     pub fn serenity_error(err: serenity::Error) -> Self {
         error!("Serenity Error: {}", err);
@@ -192,7 +241,7 @@ impl TelescopeError {
             .unwrap_or("Unknown Error");
 
         // Create an inner template depending on the error.
-        let inner_template: Template = match self {
+        let mut inner_template: Template = match self {
             TelescopeError::PageNotFound => jumbotron::new(
                 format!("{} - Page Not Found", status_code),
                 "We could not find the page you are looking for. If you think this is in \
@@ -260,6 +309,13 @@ impl TelescopeError {
                 this is in error, please contact a coordinator and file a GitHub issue.",
             ),
 
+            TelescopeError::CsrfFailure => jumbotron::new(
+                format!("{} - Form Expired", status_code),
+                "This form could not be checked as coming from Telescope. This usually \
+                means the page was open for too long or your browser was restarted. Please \
+                go back, reload the page, and try again.",
+            ),
+
             TelescopeError::RcosApiError(err) => jumbotron::new(
                 format!("{} - Internal API Query Error", status_code),
                 format!(
@@ -274,6 +330,13 @@ impl TelescopeError {
                     err),
             ),
 
+            TelescopeError::GitLabApiError(err) => jumbotron::new(
+                format!("{} - GitLab API Error", status_code),
+                format!("Could not query the GitLab API. Please contact a coordinator and \
+                    file a GitHub issue on the Telescope repository. Internal error description: {}",
+                    err),
+            ),
+
             TelescopeError::SerenityError(err) => jumbotron::new(
                 format!("{} - Discord Error", status_code),
                 format!("Error interacting with the Discord API. Please contact a \
@@ -281,6 +344,13 @@ impl TelescopeError {
                     description: {}", err)
             ),
 
+            TelescopeError::DeadlineExceeded(upstream) => jumbotron::new(
+                format!("{} - Took Too Long", status_code),
+                format!("{} did not respond in time, so Telescope stopped waiting. Please try \
+                again in a moment. If this keeps happening, please let a coordinator know.",
+                    upstream)
+            ),
+
             TelescopeError::RpiCasError(err) => jumbotron::new(
                 format!("{} - RPI CAS Error", status_code),
                 format!("Issue communicating with the RPI CAS service. Please try again. \
@@ -330,6 +400,11 @@ impl TelescopeError {
             ),
         };
 
+        // Let users report server errors, since those are bugs in Telescope.
+        if self.status_code().is_server_error() {
+            inner_template.set_field("report", error_reports::report_fields(req, status_code))?;
+        }
+
         // Put jumbotron in a page and return the content.
         return Page::new(req, "RCOS - Error", inner_template)
             .await
@@ -366,11 +441,13 @@ impl ResponseError for TelescopeError {
             TelescopeError::NotImplemented => StatusCode::NOT_IMPLEMENTED,
             TelescopeError::CsrfTokenNotFound => StatusCode::NOT_FOUND,
             TelescopeError::CsrfTokenMismatch => StatusCode::BAD_REQUEST,
+            TelescopeError::CsrfFailure => StatusCode::FORBIDDEN,
             TelescopeError::InvalidForm(_) => StatusCode::BAD_REQUEST,
             TelescopeError::NotAuthenticated => StatusCode::UNAUTHORIZED,
             TelescopeError::Forbidden => StatusCode::FORBIDDEN,
             TelescopeError::RpiCasError(_) => StatusCode::BAD_GATEWAY,
             TelescopeError::GatewayError { .. } => StatusCode::BAD_GATEWAY,
+            TelescopeError::DeadlineExceeded(_) => StatusCode::GATEWAY_TIMEOUT,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
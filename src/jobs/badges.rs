@@ -0,0 +1,69 @@
+//! Scheduled job that awards automatic badges.
+//!
+//! The `first-commit` badge goes to members with a commit tallied by the
+//! [contribution tracker](crate::jobs::contributions), so it only reaches
+//! members with a linked account on their project's repository host.
+//! Coordinators can still award it by hand to anyone else.
+
+use crate::api::rcos::badges::get::BadgeEligibility;
+use crate::api::rcos::badges::mutations::AwardBadge;
+use crate::templates::cache::{self, Dependency};
+use actix::fut::wrap_future;
+use actix::{Actor, AsyncContext, Context};
+use std::time::Duration as StdDuration;
+
+/// A zero sized struct to act as an actor and periodically award badges to
+/// users who have become eligible for them.
+pub struct BadgeAwarder;
+
+impl BadgeAwarder {
+    /// Check every badge rule and award any badges users now qualify for.
+    async fn call() {
+        // Get the list of awards to make.
+        let eligible = match BadgeEligibility::get().await {
+            Ok(eligible) => eligible,
+            Err(e) => {
+                error!("Could not check badge eligibility: {}", e);
+                return;
+            }
+        };
+
+        // Award each one, counting the new awards.
+        let mut awarded: usize = 0;
+        for (user_id, badge_id) in eligible {
+            match AwardBadge::execute(user_id, badge_id.to_string(), None).await {
+                Ok(true) => awarded += 1,
+                Ok(false) => {}
+                Err(e) => warn!(
+                    "Could not award badge {} to user {}: {}",
+                    badge_id, user_id, e
+                ),
+            }
+        }
+
+        info!("Badge Awarder awarded {} badges.", awarded);
+        if awarded > 0 {
+            cache::invalidate(Dependency::Badges);
+        }
+    }
+}
+
+impl Actor for BadgeAwarder {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("Badge Awarder Started");
+
+        // Check once at startup too, so a restart does not hold awards back
+        // for an hour.
+        ctx.spawn(wrap_future(BadgeAwarder::call()));
+
+        // Check badge rules once an hour.
+        let interval: StdDuration = StdDuration::new(60 * 60, 0);
+
+        ctx.run_interval(interval, |_, ctx| {
+            info!("Calling Badge Awarder.");
+            ctx.spawn(wrap_future(BadgeAwarder::call()));
+        });
+    }
+}
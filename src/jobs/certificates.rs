@@ -0,0 +1,111 @@
+//! Job that watches the TLS certificate served by Telescope.
+//!
+//! The certificate and key files are checked every half minute and reloaded
+//! when they change, so renewing a certificate only means replacing the files.
+//! Once a day the expiry of the served certificate is checked, and sysadmins
+//! with a linked Discord account are messaged when it is two weeks or less
+//! away.
+
+use crate::api::discord;
+use crate::api::rcos::users::sysadmins::SysadminDiscordAccounts;
+use crate::discord_bot::embeds;
+use crate::error::TelescopeError;
+use crate::templates::Template;
+use crate::web::tls;
+use actix::fut::wrap_future;
+use actix::{Actor, AsyncContext, Context};
+use chrono::{DateTime, Duration as ChronoDuration, Local, Utc};
+use serenity::builder::CreateEmbed;
+use std::time::Duration;
+
+/// How often the certificate files are checked for changes.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often the certificate expiry is checked.
+const EXPIRY_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How close to expiring the certificate has to be before sysadmins are told.
+const WARN_DAYS: i64 = 14;
+
+/// Handlebars file for the expiry warning sent to sysadmins.
+const WARNING_TEMPLATE: &'static str = "discord/certificate_expiry";
+
+/// A zero sized struct to act as an actor and watch the TLS certificate.
+pub struct CertificateWatcher;
+
+impl CertificateWatcher {
+    /// Render the warning embed for a certificate that expires soon.
+    fn render_warning(
+        expires: DateTime<Utc>,
+        remaining: ChronoDuration,
+    ) -> Result<CreateEmbed, TelescopeError> {
+        let mut template = Template::new(WARNING_TEMPLATE);
+        template
+            .set_field(
+                "expires",
+                expires
+                    .with_timezone(&Local)
+                    .format("%A, %B %-d at %-I:%M %P")
+                    .to_string(),
+            )?
+            .set_field("days", remaining.num_days().max(0))?
+            .set_field("expired", remaining <= ChronoDuration::zero())?;
+        return embeds::render(&template);
+    }
+
+    /// Warn sysadmins if the served certificate expires soon.
+    async fn check_expiry() {
+        let expires: DateTime<Utc> = match tls::expires() {
+            Some(expires) => expires,
+            None => return,
+        };
+
+        let remaining: ChronoDuration = expires - Utc::now();
+        if remaining > ChronoDuration::days(WARN_DAYS) {
+            return;
+        }
+        error!(
+            "The TLS certificate expires at {} ({} days from now).",
+            expires,
+            remaining.num_days()
+        );
+
+        let embed: CreateEmbed = match Self::render_warning(expires, remaining) {
+            Ok(embed) => embed,
+            Err(e) => {
+                error!("Could not render certificate expiry warning: {}", e);
+                return;
+            }
+        };
+
+        let sysadmins: Vec<u64> = match SysadminDiscordAccounts::get().await {
+            Ok(sysadmins) => sysadmins,
+            Err(e) => {
+                error!(
+                    "Could not get sysadmins to warn about certificate expiry: {}",
+                    e
+                );
+                return;
+            }
+        };
+
+        for discord_id in sysadmins {
+            discord::send_dm(discord_id, &embed).await;
+        }
+    }
+}
+
+impl Actor for CertificateWatcher {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("Certificate Watcher Started");
+
+        ctx.run_interval(RELOAD_INTERVAL, |_, _| tls::reload_if_changed());
+
+        ctx.spawn(wrap_future(CertificateWatcher::check_expiry()));
+        ctx.run_interval(EXPIRY_INTERVAL, |_, ctx| {
+            ctx.spawn(wrap_future(CertificateWatcher::check_expiry()));
+        });
+    }
+}
@@ -0,0 +1,157 @@
+//! Scheduled job that tallies commits and merged pull requests by project
+//! members from their projects' GitHub and GitLab repositories.
+//!
+//! Each run re-tallies the last few weeks for every project with members in an
+//! ongoing semester. Contributions are matched to members through their linked
+//! account on the repository's host, so work by members without one is not
+//! counted.
+
+use crate::api::rcos::contributions::get::{
+    tracked_projects::TrackedProjectsProjects, TrackedProjects,
+};
+use crate::api::rcos::contributions::mutations::SaveContributionWeek;
+use crate::api::rcos::contributions::week_start;
+use crate::api::rcos::users::UserAccountType;
+use crate::api::repository_hosts;
+use crate::error::TelescopeError;
+use actix::fut::wrap_future;
+use actix::{Actor, AsyncContext, Context};
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use std::collections::HashMap;
+use std::time::Duration as StdDuration;
+use uuid::Uuid;
+
+/// The number of weeks, including the current one, re-tallied on each run.
+const WINDOW_WEEKS: i64 = 4;
+
+/// A zero sized struct to act as an actor and periodically tally contributions.
+pub struct ContributionTracker;
+
+/// Get the week a timestamp falls in, in local time.
+fn week_of(time: &DateTime<Utc>) -> NaiveDate {
+    week_start(time.with_timezone(&Local).date().naive_local())
+}
+
+impl ContributionTracker {
+    /// Tally and save the contributions to a project's repositories. Return
+    /// the number of weekly tallies saved.
+    async fn track_project(project: &TrackedProjectsProjects) -> Result<usize, TelescopeError> {
+        // Map the repository host account IDs of members to their user IDs.
+        let members: HashMap<(UserAccountType, &str), Uuid> = project
+            .enrollments
+            .iter()
+            .flat_map(|enrollment| {
+                enrollment.user.accounts.iter().map(move |account| {
+                    (
+                        (account.type_, account.account_id.as_str()),
+                        enrollment.user_id,
+                    )
+                })
+            })
+            .collect();
+
+        if members.is_empty() {
+            return Ok(0);
+        }
+
+        // Start every member at zero for each week in the window, so that a
+        // week's tally is replaced even if it has dropped.
+        let first_week: NaiveDate =
+            week_start(Local::today().naive_local()) - Duration::weeks(WINDOW_WEEKS - 1);
+        let since: DateTime<Utc> = DateTime::from_utc(first_week.and_hms(0, 0, 0), Utc);
+        let mut tallies: HashMap<(Uuid, NaiveDate), (i64, i64)> = HashMap::new();
+        for user_id in members.values() {
+            for offset in 0..WINDOW_WEEKS {
+                tallies.insert((*user_id, first_week + Duration::weeks(offset)), (0, 0));
+            }
+        }
+
+        for (host, repository) in project
+            .repository_urls
+            .iter()
+            .filter_map(repository_hosts::resolve)
+        {
+            let account_type: UserAccountType = host.account_type();
+
+            let commits = host.commits(&repository, since).await?;
+            for (account_id, time) in commits {
+                if let Some(user_id) = members.get(&(account_type, account_id.as_str())) {
+                    if let Some(tally) = tallies.get_mut(&(*user_id, week_of(&time))) {
+                        tally.0 += 1;
+                    }
+                }
+            }
+
+            let pulls = host.merged_pull_requests(&repository, since).await?;
+            for (account_id, time) in pulls {
+                if let Some(user_id) = members.get(&(account_type, account_id.as_str())) {
+                    if let Some(tally) = tallies.get_mut(&(*user_id, week_of(&time))) {
+                        tally.1 += 1;
+                    }
+                }
+            }
+        }
+
+        let saved: usize = tallies.len();
+        for ((user_id, week), (commits, pull_requests)) in tallies {
+            SaveContributionWeek::execute(
+                user_id,
+                project.project_id,
+                week,
+                commits,
+                pull_requests,
+            )
+            .await?;
+        }
+        return Ok(saved);
+    }
+
+    /// Tally contributions for every tracked project.
+    async fn call() {
+        let projects = match TrackedProjects::get().await {
+            Ok(projects) => projects,
+            Err(e) => {
+                error!("Could not get projects to track contributions for: {}", e);
+                return;
+            }
+        };
+
+        let mut saved: usize = 0;
+        for project in projects.iter() {
+            match Self::track_project(project).await {
+                Ok(count) => saved += count,
+                Err(e) => warn!(
+                    "Could not track contributions for project {}: {}",
+                    project.project_id, e
+                ),
+            }
+        }
+
+        info!("Contribution Tracker saved {} weekly tallies.", saved);
+    }
+}
+
+impl Actor for ContributionTracker {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        if !repository_hosts::any_configured() {
+            info!("No repository hosts configured. Contribution tracking is disabled.");
+            return;
+        }
+
+        info!("Contribution Tracker Started");
+
+        // Tally right away, rather than leaving profiles without sparklines
+        // for hours after a restart.
+        ctx.spawn(wrap_future(ContributionTracker::call()));
+
+        // Tally contributions every 6 hours.
+        let interval: StdDuration = StdDuration::new(6 * 60 * 60, 0);
+
+        ctx.run_interval(interval, |_, ctx| {
+            info!("Calling Contribution Tracker.");
+            ctx.spawn(wrap_future(ContributionTracker::call()));
+        });
+    }
+}
@@ -0,0 +1,228 @@
+//! Scheduled job that checks links stored on meetings and projects and records
+//! the ones that are broken.
+//!
+//! A link is broken if it responds with 404 or 410, redirects in a loop, or
+//! its host cannot be reached. Other errors (timeouts, server errors, login
+//! walls) are treated as transient and not flagged. Checks are made one at a
+//! time and spaced out per host so that no site sees more than one request
+//! every few seconds from telescope.
+
+use crate::api::http::{self, Integration};
+use crate::api::rcos::link_health::get::LinkSources;
+use crate::api::rcos::link_health::mutations::{ClearBrokenLinks, SaveBrokenLink};
+use crate::web::telescope_ua;
+use actix::clock::delay_for;
+use actix::fut::wrap_future;
+use actix::{Actor, AsyncContext, Context};
+use chrono::{DateTime, Utc};
+use reqwest::redirect::Policy;
+use reqwest::{Client, Method, StatusCode};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// The least time between two requests to the same host.
+const HOST_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The least time between any two requests.
+const REQUEST_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long to wait for a response before giving up on a link.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Redirects to follow before a link is considered to be in a redirect loop.
+const MAX_REDIRECTS: usize = 10;
+
+/// A link to check and where it appears.
+struct Link {
+    url: String,
+    /// Telescope path of the page the link is on.
+    source_path: String,
+    /// Which link on the page this is.
+    label: &'static str,
+}
+
+/// Why a link is broken.
+#[derive(Clone, Debug)]
+struct Failure {
+    status_code: Option<i64>,
+    error: String,
+}
+
+/// A zero sized struct to act as an actor and periodically check links.
+pub struct LinkChecker;
+
+impl LinkChecker {
+    /// Collect every stored link.
+    async fn links() -> Option<Vec<Link>> {
+        let sources = LinkSources::get()
+            .await
+            .map_err(|e| error!("Could not get links to check: {}", e))
+            .ok()?;
+
+        let mut links: Vec<Link> = Vec::new();
+        let mut push = |url: Option<String>, source_path: &str, label: &'static str| {
+            if let Some(url) = url.filter(|url| !url.trim().is_empty()) {
+                links.push(Link {
+                    url,
+                    source_path: source_path.to_string(),
+                    label,
+                });
+            }
+        };
+
+        for meeting in sources.meetings {
+            let path = format!("/meeting/{}", meeting.meeting_id);
+            push(meeting.meeting_url, &path, "Meeting URL");
+            push(meeting.recording_url, &path, "Recording");
+            push(meeting.external_presentation_url, &path, "Slides");
+        }
+
+        for project in sources.projects {
+            let path = format!("/project/{}", project.project_id);
+            push(project.homepage_url, &path, "Homepage");
+            for url in project.repository_urls {
+                push(Some(url.to_string()), &path, "Repository");
+            }
+        }
+
+        return Some(links);
+    }
+
+    /// Check a link. Return why it is broken, or `None` if it is not.
+    async fn check(client: &Client, url: &Url) -> Option<Failure> {
+        // Some servers do not implement HEAD. Fall back to GET for them.
+        let mut response = client.request(Method::HEAD, url.clone()).send().await;
+        if let Ok(r) = response.as_ref() {
+            if r.status() == StatusCode::METHOD_NOT_ALLOWED
+                || r.status() == StatusCode::NOT_IMPLEMENTED
+            {
+                response = client.get(url.clone()).send().await;
+            }
+        }
+
+        match response {
+            Ok(r) if r.status() == StatusCode::NOT_FOUND || r.status() == StatusCode::GONE => {
+                Some(Failure {
+                    status_code: Some(r.status().as_u16() as i64),
+                    error: format!("The page responded with {}.", r.status()),
+                })
+            }
+            Ok(_) => None,
+            Err(e) if e.is_redirect() => Some(Failure {
+                status_code: None,
+                error: "The page redirects in a loop.".into(),
+            }),
+            Err(e) if e.is_connect() => Some(Failure {
+                status_code: None,
+                error: "The host could not be reached.".into(),
+            }),
+            Err(_) => None,
+        }
+    }
+
+    /// Check every stored link and record the broken ones.
+    async fn call() {
+        let started: DateTime<Utc> = Utc::now();
+        let links: Vec<Link> = match Self::links().await {
+            Some(links) => links,
+            None => return,
+        };
+
+        let client: Client = match http::client_builder(Integration::LinkHealth)
+            .redirect(Policy::limited(MAX_REDIRECTS))
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(telescope_ua())
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                error!("Could not build link checker HTTP client: {}", e);
+                return;
+            }
+        };
+
+        // Check each URL once, even if it appears on several pages.
+        let mut results: HashMap<String, Option<Failure>> = HashMap::new();
+        let mut last_request: Option<Instant> = None;
+        let mut last_by_host: HashMap<String, Instant> = HashMap::new();
+        let mut broken: usize = 0;
+
+        for link in links.iter() {
+            if !results.contains_key(&link.url) {
+                let result: Option<Failure> = match Url::parse(link.url.trim()) {
+                    // Only check web links.
+                    Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {
+                        // Wait for the rate limits before sending anything.
+                        let host: String = url.host_str().unwrap_or_default().to_string();
+                        let mut wait: Duration = last_request
+                            .map(|t| REQUEST_INTERVAL.saturating_sub(t.elapsed()))
+                            .unwrap_or_default();
+                        if let Some(t) = last_by_host.get(&host) {
+                            wait = wait.max(HOST_INTERVAL.saturating_sub(t.elapsed()));
+                        }
+                        if wait > Duration::from_secs(0) {
+                            delay_for(wait).await;
+                        }
+
+                        let result = Self::check(&client, &url).await;
+                        last_request = Some(Instant::now());
+                        last_by_host.insert(host, Instant::now());
+                        result
+                    }
+                    Ok(_) => None,
+                    Err(e) => Some(Failure {
+                        status_code: None,
+                        error: format!("The link is not a valid URL: {}", e),
+                    }),
+                };
+                results.insert(link.url.clone(), result);
+            }
+
+            if let Some(failure) = results[&link.url].clone() {
+                broken += 1;
+                let saved = SaveBrokenLink::execute(
+                    link.url.clone(),
+                    link.source_path.clone(),
+                    link.label.to_string(),
+                    failure.status_code,
+                    Some(failure.error),
+                    Utc::now(),
+                )
+                .await;
+
+                if let Err(e) = saved {
+                    error!("Could not save broken link {}: {}", link.url, e);
+                }
+            }
+        }
+
+        // Links not re-recorded in this run are no longer broken (or no longer
+        // stored).
+        if let Err(e) = ClearBrokenLinks::execute(started).await {
+            error!("Could not clear fixed links: {}", e);
+        }
+
+        info!(
+            "Link Checker checked {} links and found {} broken.",
+            results.len(),
+            broken
+        );
+    }
+}
+
+impl Actor for LinkChecker {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("Link Checker Started");
+
+        // Check links once a day.
+        let interval: Duration = Duration::new(24 * 60 * 60, 0);
+
+        ctx.run_interval(interval, |_, ctx| {
+            info!("Calling Link Checker.");
+            ctx.spawn(wrap_future(LinkChecker::call()));
+        });
+    }
+}
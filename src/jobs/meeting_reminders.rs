@@ -0,0 +1,149 @@
+//! Scheduled job that reminds the members of a small group of meetings that
+//! are only for their group, over Discord direct message. Meetings open to
+//! everyone are announced in the announcement channels instead.
+
+use crate::api::discord;
+use crate::api::rcos::meetings::small_groups::upcoming_group_meetings::UpcomingGroupMeetingsMeetings;
+use crate::api::rcos::meetings::small_groups::{MarkGroupRemindersSent, UpcomingGroupMeetings};
+use crate::discord_bot::embeds;
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use crate::templates::Template;
+use actix::fut::wrap_future;
+use actix::{Actor, AsyncContext, Context};
+use chrono::{Duration, Local};
+use serenity::builder::CreateEmbed;
+use std::collections::HashSet;
+use std::time::Duration as StdDuration;
+
+/// How far ahead of a meeting to remind its small group.
+const REMINDER_HOURS: i64 = 24;
+
+/// The embed template for reminders.
+const REMINDER_TEMPLATE: &'static str = "discord/meeting_reminder";
+
+/// A zero sized struct to act as an actor and send small group meeting
+/// reminders.
+pub struct MeetingReminders;
+
+impl MeetingReminders {
+    /// Render the reminder embed for a small group meeting.
+    fn render_reminder(
+        meeting: &UpcomingGroupMeetingsMeetings,
+    ) -> Result<CreateEmbed, TelescopeError> {
+        let start = meeting.start_date_time.with_timezone(&Local);
+        let mut template = Template::new(REMINDER_TEMPLATE);
+        template
+            .set_field(
+                "title",
+                meeting
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| format!("RCOS {}", meeting.type_)),
+            )?
+            .set_field(
+                "url",
+                format!(
+                    "{}/meeting/{}",
+                    global_config().telescope_url,
+                    meeting.meeting_id
+                ),
+            )?
+            .set_field(
+                "group",
+                meeting.small_group.as_ref().map(|group| &group.title),
+            )?
+            .set_field("date", start.format("%A, %B %-d").to_string())?
+            .set_field("time", start.format("%-I:%M %P").to_string())?
+            .set_field(
+                "location",
+                meeting
+                    .location
+                    .clone()
+                    .or_else(|| meeting.is_remote.then(|| "Remote".to_string())),
+            )?;
+        return embeds::render(&template);
+    }
+
+    /// Remind the members of every small group with a meeting starting soon.
+    async fn call() {
+        let meetings = match UpcomingGroupMeetings::get(Duration::hours(REMINDER_HOURS)).await {
+            Ok(meetings) => meetings,
+            Err(e) => {
+                error!("Could not get upcoming small group meetings: {}", e);
+                return;
+            }
+        };
+
+        let mut reminded: Vec<i64> = Vec::with_capacity(meetings.len());
+        for meeting in meetings {
+            let group = match &meeting.small_group {
+                Some(group) => group,
+                None => continue,
+            };
+
+            let embed: CreateEmbed = match Self::render_reminder(&meeting) {
+                Ok(embed) => embed,
+                Err(e) => {
+                    error!("Could not render small group meeting reminder: {}", e);
+                    return;
+                }
+            };
+
+            // Message the group's mentors and every member of its projects this
+            // semester with a linked Discord. Mentors may also be on a project,
+            // so only message each account once.
+            let mentors = group
+                .small_group_mentors
+                .iter()
+                .filter_map(|mentor| mentor.user.discord.first());
+
+            let members = group
+                .small_group_projects
+                .iter()
+                .flat_map(|group_project| group_project.project.enrollments.iter())
+                .filter(|enrollment| enrollment.semester_id == meeting.semester_id)
+                .filter_map(|enrollment| enrollment.user.discord.first());
+
+            let discord_ids: HashSet<u64> = mentors
+                .map(|account| account.account_id.as_str())
+                .chain(members.map(|account| account.account_id.as_str()))
+                .filter_map(|account_id| account_id.parse::<u64>().ok())
+                .collect();
+
+            for discord_id in discord_ids {
+                discord::send_dm(discord_id, &embed).await;
+            }
+
+            reminded.push(meeting.meeting_id);
+        }
+
+        info!(
+            "Sent reminders for {} small group meetings.",
+            reminded.len()
+        );
+        if !reminded.is_empty() {
+            if let Err(e) = MarkGroupRemindersSent::execute(reminded).await {
+                error!(
+                    "Could not mark small group meeting reminders as sent: {}",
+                    e
+                );
+            }
+        }
+    }
+}
+
+impl Actor for MeetingReminders {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("Meeting Reminders Started");
+
+        // Check for upcoming meetings every 15 minutes.
+        let interval: StdDuration = StdDuration::new(15 * 60, 0);
+
+        ctx.run_interval(interval, |_, ctx| {
+            ctx.spawn(wrap_future(MeetingReminders::call()));
+        });
+    }
+}
@@ -0,0 +1,15 @@
+//! Background jobs that run on a schedule for the lifetime of the server.
+//! Each job is an actix actor started from `main`.
+
+pub mod badges;
+pub mod certificates;
+pub mod contributions;
+pub mod link_health;
+pub mod meeting_reminders;
+pub mod presentation_reminders;
+pub mod publishing;
+pub mod recordings;
+pub mod retention;
+pub mod search_index;
+pub mod tasks;
+pub mod webhooks;
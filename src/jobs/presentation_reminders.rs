@@ -0,0 +1,106 @@
+//! Scheduled job that reminds presenters of their upcoming presentation slot
+//! over Discord direct message.
+
+use crate::api::discord;
+use crate::api::rcos::presentations::get::upcoming_presentations::UpcomingPresentationsPresentationSlots;
+use crate::api::rcos::presentations::get::UpcomingPresentations;
+use crate::api::rcos::presentations::mutations::MarkPresentationRemindersSent;
+use crate::discord_bot::embeds;
+use crate::error::TelescopeError;
+use crate::templates::Template;
+use actix::fut::wrap_future;
+use actix::{Actor, AsyncContext, Context};
+use chrono::{Duration, Local};
+use serenity::builder::CreateEmbed;
+use std::time::Duration as StdDuration;
+
+/// How far ahead of a presentation to remind presenters.
+const REMINDER_HOURS: i64 = 24;
+
+/// The embed template for reminders.
+const REMINDER_TEMPLATE: &'static str = "discord/presentation_reminder";
+
+/// A zero sized struct to act as an actor and send presentation reminders.
+pub struct PresentationReminders;
+
+impl PresentationReminders {
+    /// Render the reminder embed for a presentation slot.
+    fn render_reminder(
+        slot: &UpcomingPresentationsPresentationSlots,
+    ) -> Result<CreateEmbed, TelescopeError> {
+        let start = slot.start_date_time.with_timezone(&Local);
+        let mut template = Template::new(REMINDER_TEMPLATE);
+        template
+            .set_field(
+                "project",
+                slot.project.as_ref().map(|project| &project.title),
+            )?
+            .set_field("date", start.format("%A, %B %-d").to_string())?
+            .set_field("time", start.format("%-I:%M %P").to_string())?
+            .set_field("location", &slot.location)?;
+        return embeds::render(&template);
+    }
+
+    /// Remind the presenters of every claimed slot starting soon.
+    async fn call() {
+        let slots = match UpcomingPresentations::get(Duration::hours(REMINDER_HOURS)).await {
+            Ok(slots) => slots,
+            Err(e) => {
+                error!("Could not get upcoming presentations: {}", e);
+                return;
+            }
+        };
+
+        let mut reminded: Vec<i64> = Vec::with_capacity(slots.len());
+        for slot in slots {
+            let project = match &slot.project {
+                Some(project) => project,
+                None => continue,
+            };
+
+            let embed: CreateEmbed = match Self::render_reminder(&slot) {
+                Ok(embed) => embed,
+                Err(e) => {
+                    error!("Could not render presentation reminder: {}", e);
+                    return;
+                }
+            };
+
+            // Message every member of the project this semester with a linked Discord.
+            let discord_ids = project
+                .enrollments
+                .iter()
+                .filter(|enrollment| enrollment.semester_id == slot.semester_id)
+                .filter_map(|enrollment| enrollment.user.discord.first())
+                .filter_map(|account| account.account_id.parse::<u64>().ok());
+
+            for discord_id in discord_ids {
+                discord::send_dm(discord_id, &embed).await;
+            }
+
+            reminded.push(slot.slot_id);
+        }
+
+        info!("Sent reminders for {} presentation slots.", reminded.len());
+        if !reminded.is_empty() {
+            if let Err(e) = MarkPresentationRemindersSent::execute(reminded).await {
+                error!("Could not mark presentation reminders as sent: {}", e);
+            }
+        }
+    }
+}
+
+impl Actor for PresentationReminders {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("Presentation Reminders Started");
+
+        // Check for upcoming presentations every 15 minutes.
+        let interval: StdDuration = StdDuration::new(15 * 60, 0);
+
+        ctx.run_interval(interval, |_, ctx| {
+            ctx.spawn(wrap_future(PresentationReminders::call()));
+        });
+    }
+}
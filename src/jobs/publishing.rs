@@ -0,0 +1,78 @@
+//! Scheduled job that publishes drafts at the time their hosts picked, and
+//! announces the ones that asked for it on Discord like newly created
+//! meetings are. Webhooks are told about every published draft.
+
+use crate::api::rcos::meetings::publishing::{DueDrafts, PublishDrafts};
+use crate::api::rcos::prelude::MeetingId;
+use crate::discord_bot::{self, DiscordMessage};
+use crate::web::webhooks::{self, WebhookEvent};
+use actix::fut::wrap_future;
+use actix::{Actor, AsyncContext, Context};
+use std::time::Duration;
+
+/// A zero sized struct to act as an actor and publish scheduled drafts.
+pub struct DraftPublisher;
+
+impl DraftPublisher {
+    /// Publish every draft that is due, and announce the ones set to be
+    /// announced.
+    async fn call() {
+        let due: Vec<i64> = match DueDrafts::get().await {
+            Ok(due) => due,
+            Err(e) => {
+                error!("Could not get drafts due to be published: {}", e);
+                return;
+            }
+        };
+
+        if due.is_empty() {
+            return;
+        }
+
+        let published = match PublishDrafts::execute(due).await {
+            Ok(published) => published,
+            Err(e) => {
+                error!("Could not publish scheduled drafts: {}", e);
+                return;
+            }
+        };
+
+        info!("Published {} scheduled drafts.", published.len());
+        for meeting in published {
+            let meeting_id = MeetingId(meeting.meeting_id);
+            webhooks::emit(WebhookEvent::MeetingPublished {
+                meeting_id,
+                title: meeting.title,
+                start: meeting.start_date_time,
+                end: meeting.end_date_time,
+            })
+            .await;
+
+            if !meeting.announce_on_publish {
+                continue;
+            }
+            if let Err(e) = discord_bot::send(DiscordMessage::AnnounceMeeting { meeting_id }) {
+                warn!(
+                    "Could not announce published meeting {} on Discord: {}",
+                    meeting_id, e
+                );
+            }
+        }
+    }
+}
+
+impl Actor for DraftPublisher {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("Draft Publisher Started");
+
+        // Check for due drafts every minute, so they go out close to when
+        // they were scheduled.
+        let interval: Duration = Duration::new(60, 0);
+
+        ctx.run_interval(interval, |_, ctx| {
+            ctx.spawn(wrap_future(DraftPublisher::call()));
+        });
+    }
+}
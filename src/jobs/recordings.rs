@@ -0,0 +1,83 @@
+//! Scheduled job that picks up new meeting recordings from the configured
+//! YouTube playlist and attaches them to their meetings.
+
+use crate::api::rcos::meetings::recordings::{self, Outcome, Recording, RecordingSource};
+use crate::api::youtube;
+use crate::env::global_config;
+use actix::fut::wrap_future;
+use actix::{Actor, AsyncContext, Context};
+use std::time::Duration as StdDuration;
+
+/// A zero sized struct to act as an actor and watch the recordings playlist.
+pub struct RecordingWatcher;
+
+impl RecordingWatcher {
+    /// Match every video in the playlist that was not picked up before.
+    async fn call() {
+        let config = global_config();
+        let (api_key, playlist_id) = match config
+            .recording_ingest
+            .as_ref()
+            .and_then(|ingest| ingest.youtube())
+        {
+            Some(youtube) => youtube,
+            None => return,
+        };
+
+        let videos = match youtube::playlist_videos(api_key, playlist_id).await {
+            Ok(videos) => videos,
+            Err(e) => {
+                error!("Could not get recordings from YouTube: {}", e);
+                return;
+            }
+        };
+
+        let (mut attached, mut queued) = (0, 0);
+        for video in videos {
+            let recording = Recording {
+                url: video.url,
+                title: video.title,
+                published_at: video.published_at,
+                source: RecordingSource::YouTube,
+            };
+
+            match recordings::ingest(recording).await {
+                Ok(Outcome::Attached(_)) => attached += 1,
+                Ok(Outcome::Queued) => queued += 1,
+                Ok(_) => {}
+                Err(e) => error!("Could not match YouTube recording: {}", e),
+            }
+        }
+
+        info!(
+            "Attached {} new recordings from YouTube, queued {} for coordinators.",
+            attached, queued
+        );
+    }
+}
+
+impl Actor for RecordingWatcher {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        let configured: bool = global_config()
+            .recording_ingest
+            .as_ref()
+            .and_then(|ingest| ingest.youtube())
+            .is_some();
+
+        if !configured {
+            info!("No YouTube playlist configured. Recording watching is disabled.");
+            return;
+        }
+
+        info!("Recording Watcher Started");
+
+        // Check the playlist every hour.
+        let interval: StdDuration = StdDuration::new(60 * 60, 0);
+
+        ctx.run_interval(interval, |_, ctx| {
+            ctx.spawn(wrap_future(RecordingWatcher::call()));
+        });
+    }
+}
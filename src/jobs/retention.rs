@@ -0,0 +1,81 @@
+//! Scheduled job that enforces the data retention rules from the config.
+//!
+//! In dry-run mode, which is the default, the job only logs what it would
+//! remove. The same plan is shown on the admin panel.
+
+use crate::api::rcos::retention::{AnonymizeUser, DeleteExpiredAttendance, RetentionPlan};
+use crate::env::{global_config, RetentionConfig};
+use crate::web::images;
+use actix::fut::wrap_future;
+use actix::{Actor, AsyncContext, Context};
+use std::time::Duration as StdDuration;
+
+/// A zero sized struct to act as an actor and periodically remove personal
+/// data the retention rules say is no longer needed.
+pub struct RetentionEnforcer;
+
+impl RetentionEnforcer {
+    /// Work out what the rules would remove, and remove it unless this is a
+    /// dry run.
+    async fn call() {
+        let rules: &RetentionConfig = &global_config().retention;
+        let plan: RetentionPlan = match RetentionPlan::get(rules).await {
+            Ok(plan) => plan,
+            Err(e) => {
+                error!("Could not plan data retention: {}", e);
+                return;
+            }
+        };
+
+        if rules.dry_run {
+            info!(
+                "Retention dry run: would delete {} attendance records and {} excused absences, \
+                and anonymize {} withdrawn accounts.",
+                plan.attendance_records,
+                plan.excused_absences,
+                plan.to_anonymize.len()
+            );
+            return;
+        }
+
+        if let Some(cutoff) = plan.attendance_cutoff {
+            match DeleteExpiredAttendance::execute(cutoff).await {
+                Ok((attendances, absences)) => info!(
+                    "Retention deleted {} attendance records and {} excused absences.",
+                    attendances, absences
+                ),
+                Err(e) => error!("Could not delete expired attendance: {}", e),
+            }
+        }
+
+        let mut anonymized: usize = 0;
+        for account in plan.to_anonymize {
+            match AnonymizeUser::execute(account.id).await {
+                Ok(()) => {
+                    anonymized += 1;
+                    if let Some(avatar) = account.avatar_image_id {
+                        images::delete(avatar);
+                    }
+                }
+                Err(e) => error!("Could not anonymize user {}: {}", account.id, e),
+            }
+        }
+        info!("Retention anonymized {} withdrawn accounts.", anonymized);
+    }
+}
+
+impl Actor for RetentionEnforcer {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("Retention Enforcer Started");
+
+        // Enforce retention rules once a day.
+        let interval: StdDuration = StdDuration::new(24 * 60 * 60, 0);
+
+        ctx.run_interval(interval, |_, ctx| {
+            info!("Calling Retention Enforcer.");
+            ctx.spawn(wrap_future(RetentionEnforcer::call()));
+        });
+    }
+}
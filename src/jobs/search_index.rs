@@ -0,0 +1,52 @@
+//! Scheduled job that rebuilds the full-text search index.
+//!
+//! There is no way to hear about changes made to the RCOS database, so the
+//! whole index is rebuilt every few minutes. Search results can be that far
+//! behind.
+
+use crate::api::rcos::search_index::SearchDocuments;
+use crate::web::search;
+use actix::fut::wrap_future;
+use actix::{Actor, AsyncContext, Context};
+use std::time::Duration;
+
+/// How often the index is rebuilt.
+const INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// A zero sized struct to act as an actor and periodically rebuild the
+/// search index.
+pub struct SearchIndexer;
+
+impl SearchIndexer {
+    /// Fetch everything searchable and rebuild the index with it.
+    async fn call() {
+        let documents = match SearchDocuments::get().await {
+            Ok(documents) => documents,
+            Err(e) => {
+                error!("Could not get documents to index for search: {}", e);
+                return;
+            }
+        };
+
+        match search::rebuild(documents).await {
+            Ok(count) => info!("Search Indexer indexed {} documents.", count),
+            Err(e) => error!("Could not rebuild the search index: {}", e),
+        }
+    }
+}
+
+impl Actor for SearchIndexer {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("Search Indexer Started");
+
+        // Build the index right away so search works soon after a restart.
+        ctx.spawn(wrap_future(SearchIndexer::call()));
+
+        ctx.run_interval(INTERVAL, |_, ctx| {
+            info!("Calling Search Indexer.");
+            ctx.spawn(wrap_future(SearchIndexer::call()));
+        });
+    }
+}
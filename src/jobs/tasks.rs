@@ -0,0 +1,159 @@
+//! Scheduled job that adds tasks to the coordinator task board for events that
+//! need a coordinator to act, like a mentor application being submitted.
+//!
+//! Submissions are made outside of telescope, so this checks for ones that are
+//! waiting on review rather than reacting to them directly. Each submission
+//! gets at most one task, even if the task is finished before the submission
+//! is reviewed.
+
+use crate::api::rcos::tasks::get::PendingReviews;
+use crate::api::rcos::tasks::mutations::CreateEventTask;
+use crate::api::rcos::users::display::UserDisplay;
+use actix::fut::wrap_future;
+use actix::{Actor, AsyncContext, Context};
+use std::time::Duration as StdDuration;
+
+/// A task to create for an event.
+struct EventTask {
+    title: String,
+    description: Option<String>,
+    /// The kind of event.
+    source: &'static str,
+    /// Identifies the event so it only gets one task.
+    source_key: String,
+}
+
+/// A zero sized struct to act as an actor and periodically create tasks for
+/// submissions that are waiting on review.
+pub struct TaskGenerator;
+
+impl TaskGenerator {
+    /// Get the tasks that should exist for submissions waiting on review.
+    async fn event_tasks() -> Option<Vec<EventTask>> {
+        let pending = PendingReviews::get()
+            .await
+            .map_err(|e| error!("Could not get submissions waiting on review: {}", e))
+            .ok()?;
+
+        let mut tasks: Vec<EventTask> = Vec::new();
+
+        for proposal in pending.mentor_proposals {
+            let user = proposal.user;
+            let name = UserDisplay::new(
+                user.first_name,
+                user.last_name,
+                user.preferred_name,
+                user.pronouns,
+            )
+            .name();
+
+            tasks.push(EventTask {
+                title: format!("Review mentor application from {}", name),
+                description: Some(format!(
+                    "Submitted for {}. Applicant: /user/{}",
+                    proposal.semester_id, user.id
+                )),
+                source: "mentor_proposal",
+                source_key: format!("mentor_proposal:{}:{}", proposal.semester_id, user.id),
+            });
+        }
+
+        for pitch in pending.project_pitches {
+            let user = pitch.user;
+            let name = UserDisplay::new(
+                user.first_name,
+                user.last_name,
+                user.preferred_name,
+                user.pronouns,
+            )
+            .name();
+
+            tasks.push(EventTask {
+                title: format!(
+                    "Review project pitch \"{}\" from {}",
+                    pitch.proposed_title, name
+                ),
+                description: Some(format!(
+                    "Submitted for {}. Pitched by: /user/{}",
+                    pitch.semester_id, user.id
+                )),
+                source: "project_pitch",
+                source_key: format!("project_pitch:{}:{}", pitch.semester_id, user.id),
+            });
+        }
+
+        for proposal in pending.workshop_proposals {
+            tasks.push(EventTask {
+                title: format!("Review workshop proposal \"{}\"", proposal.title),
+                description: Some(format!("Submitted for {}.", proposal.semester_id)),
+                source: "workshop_proposal",
+                source_key: format!("workshop_proposal:{}", proposal.workshop_proposal_id),
+            });
+        }
+
+        for request in pending.pay_requests {
+            let user = request.user;
+            let name = UserDisplay::new(
+                user.first_name,
+                user.last_name,
+                user.preferred_name,
+                user.pronouns,
+            )
+            .name();
+
+            tasks.push(EventTask {
+                title: format!("Review pay request from {}", name),
+                description: Some(format!(
+                    "Submitted for {}. Requested by: /user/{}",
+                    request.semester_id, user.id
+                )),
+                source: "pay_request",
+                source_key: format!("pay_request:{}:{}", request.semester_id, user.id),
+            });
+        }
+
+        return Some(tasks);
+    }
+
+    /// Create any tasks that do not exist yet.
+    async fn call() {
+        let tasks = match Self::event_tasks().await {
+            Some(tasks) => tasks,
+            None => return,
+        };
+
+        let mut created: usize = 0;
+        for task in tasks {
+            match CreateEventTask::execute(
+                task.title,
+                task.description,
+                task.source,
+                task.source_key.clone(),
+            )
+            .await
+            {
+                Ok(true) => created += 1,
+                Ok(false) => {}
+                Err(e) => warn!("Could not create task for {}: {}", task.source_key, e),
+            }
+        }
+
+        info!("Task Generator created {} tasks.", created);
+    }
+}
+
+impl Actor for TaskGenerator {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("Task Generator Started");
+
+        // Check for new submissions every 15 minutes.
+        let interval: StdDuration = StdDuration::new(15 * 60, 0);
+
+        ctx.run_interval(interval, |_, ctx| {
+            info!("Calling Task Generator.");
+            ctx.spawn(wrap_future(TaskGenerator::call()));
+        });
+    }
+}
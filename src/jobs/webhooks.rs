@@ -0,0 +1,162 @@
+//! Scheduled job that posts events in the webhook outbox to their endpoints.
+//!
+//! Deliveries that fail are retried with exponential backoff, starting a
+//! minute after the first attempt, until they have been tried
+//! [`MAX_ATTEMPTS`] times. Every attempt is logged for the delivery log in
+//! the admin panel.
+
+use crate::api::http::{self, Integration};
+use crate::api::rcos::webhooks::{
+    due_webhook_deliveries::DueWebhookDeliveriesWebhookDeliveries as Delivery,
+    record_webhook_attempt, DueWebhookDeliveries, RecordWebhookAttempt,
+};
+use crate::web::telescope_ua;
+use crate::web::webhooks::{
+    self, DELIVERY_HEADER, EVENT_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+};
+use actix::fut::wrap_future;
+use actix::{Actor, AsyncContext, Context};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use std::time::{Duration, Instant};
+
+/// How many times to try a delivery before giving up on it.
+pub const MAX_ATTEMPTS: i64 = 8;
+
+/// How long to wait before the first retry, in seconds. Each retry after
+/// waits twice as long as the one before.
+const RETRY_BASE_SECONDS: i64 = 60;
+
+/// How long to wait for an endpoint to respond.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The most deliveries to try in one run.
+const BATCH_SIZE: i64 = 50;
+
+/// A zero sized struct to act as an actor and deliver webhooks.
+pub struct WebhookDelivery;
+
+impl WebhookDelivery {
+    /// Post a delivery to its endpoint and log the attempt.
+    async fn deliver(client: &Client, delivery: Delivery) {
+        let attempted_at: DateTime<Utc> = Utc::now();
+        let timestamp: i64 = attempted_at.timestamp();
+        let signature: String =
+            webhooks::sign(&delivery.endpoint.secret, timestamp, &delivery.payload);
+
+        let started = Instant::now();
+        let response = client
+            .post(delivery.endpoint.url.as_str())
+            .header(CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, delivery.event.as_str())
+            .header(DELIVERY_HEADER, delivery.delivery_id.to_string())
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, signature)
+            .body(delivery.payload.clone())
+            .send()
+            .await;
+        let duration_ms: i64 = started.elapsed().as_millis() as i64;
+
+        let (status_code, error): (Option<i64>, Option<String>) = match response {
+            Ok(r) if r.status().is_success() => (Some(r.status().as_u16() as i64), None),
+            Ok(r) => (
+                Some(r.status().as_u16() as i64),
+                Some(format!("The endpoint responded with {}.", r.status())),
+            ),
+            Err(e) if e.is_timeout() => {
+                (None, Some("The endpoint did not respond in time.".into()))
+            }
+            Err(e) => (None, Some(e.to_string())),
+        };
+
+        let attempts: i64 = delivery.attempts + 1;
+        let next_attempt_at: Option<DateTime<Utc>> = match error {
+            Some(_) if attempts < MAX_ATTEMPTS => {
+                let backoff: i64 = RETRY_BASE_SECONDS << (attempts - 1);
+                Some(attempted_at + ChronoDuration::seconds(backoff))
+            }
+            _ => None,
+        };
+
+        if next_attempt_at.is_none() && error.is_some() {
+            warn!(
+                "Giving up on webhook delivery {} to {} after {} attempts.",
+                delivery.delivery_id, delivery.endpoint.url, attempts
+            );
+        }
+
+        let logged = RecordWebhookAttempt::execute(record_webhook_attempt::Variables {
+            delivery_id: delivery.delivery_id,
+            attempts,
+            next_attempt_at,
+            delivered_at: error.is_none().then(|| attempted_at),
+            attempted_at,
+            status_code,
+            error,
+            duration_ms,
+        })
+        .await;
+
+        if let Err(e) = logged {
+            error!(
+                "Could not log attempt of webhook delivery {}: {}",
+                delivery.delivery_id, e
+            );
+        }
+    }
+
+    /// Try every delivery that is due.
+    async fn call() {
+        let deliveries: Vec<Delivery> = match DueWebhookDeliveries::get(BATCH_SIZE).await {
+            Ok(deliveries) => deliveries,
+            Err(e) => {
+                error!("Could not get webhook deliveries: {}", e);
+                return;
+            }
+        };
+
+        if deliveries.is_empty() {
+            return;
+        }
+
+        let client: Client = match http::client_builder(Integration::Webhooks)
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(telescope_ua())
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                error!("Could not build webhook HTTP client: {}", e);
+                return;
+            }
+        };
+
+        // Send them all at once, so that the run is over well before the next
+        // one starts and picks up the same deliveries.
+        let count: usize = deliveries.len();
+        futures::future::join_all(
+            deliveries
+                .into_iter()
+                .map(|delivery| Self::deliver(&client, delivery)),
+        )
+        .await;
+        info!("Tried {} webhook deliveries.", count);
+    }
+}
+
+impl Actor for WebhookDelivery {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("Webhook Delivery Started");
+
+        // Check the outbox every half minute, so events go out soon after
+        // they happen.
+        let interval: Duration = Duration::new(30, 0);
+
+        ctx.run_interval(interval, |_, ctx| {
+            ctx.spawn(wrap_future(WebhookDelivery::call()));
+        });
+    }
+}
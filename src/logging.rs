@@ -0,0 +1,238 @@
+//! Logging setup.
+//!
+//! Log lines are written to standard error in the env_logger text format or as
+//! one JSON object per line, depending on the `log_format` config option.
+//! Which lines are written follows the `log_level` filter, except for modules
+//! given their own level at runtime from the admin panel. Those overrides are
+//! kept in memory and broadcast to every replica; they are lost on restart.
+//!
+//! Lines logged while handling a request carry that request's ID, method,
+//! and path. The [request ID middleware](crate::web::middlewares::request_id)
+//! sets these for the handler with [`scope`].
+//!
+//! Panics are logged as errors too, with the request they happened in. The
+//! backtrace is printed after the log line by the default panic hook.
+
+use crate::env::LogFormat;
+use chrono::{SecondsFormat, Utc};
+use env_logger::filter::{Builder as FilterBuilder, Filter};
+use log::{LevelFilter, Log, Metadata, Record};
+use std::cmp::max;
+use std::env;
+use std::future::Future;
+use std::io::{self, Write};
+use std::panic::{self, PanicInfo};
+use std::str::FromStr;
+use std::sync::RwLock;
+
+tokio::task_local! {
+    /// The request this task is handling.
+    static SPAN: RequestSpan;
+}
+
+lazy_static! {
+    /// Levels set at runtime, most specific module first.
+    static ref OVERRIDES: RwLock<Vec<(String, LevelFilter)>> = RwLock::new(Vec::new());
+
+    /// The most verbose level the `log_level` filter lets through.
+    static ref BASE_MAX_LEVEL: RwLock<LevelFilter> = RwLock::new(LevelFilter::Off);
+}
+
+/// The request a log line was written while handling.
+#[derive(Clone, Debug, Serialize)]
+pub struct RequestSpan {
+    pub request_id: String,
+    pub method: String,
+    pub path: String,
+}
+
+/// Run a future with the request it handles set, so log lines written by it
+/// carry the request's fields.
+pub async fn scope<F: Future>(span: RequestSpan, future: F) -> F::Output {
+    SPAN.scope(span, future).await
+}
+
+/// The request being handled, if any.
+pub fn current_span() -> Option<RequestSpan> {
+    SPAN.try_with(|span| span.clone()).ok()
+}
+
+/// A log level set at runtime for a module and the modules under it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LevelOverride {
+    /// Module path, like `telescope::discord_bot` or `actix_web`.
+    pub module: String,
+    /// Level name, like `debug` or `off`.
+    pub level: String,
+}
+
+impl LevelOverride {
+    /// Check the module and level of an override. Returns the level if both
+    /// are valid.
+    pub fn parse(&self) -> Option<LevelFilter> {
+        let module: &str = self.module.as_str();
+        if module.is_empty() || module.contains(char::is_whitespace) || module.contains(',') {
+            return None;
+        }
+        return LevelFilter::from_str(self.level.as_str()).ok();
+    }
+}
+
+/// The logger installed by [`init`].
+struct TelescopeLogger {
+    format: LogFormat,
+    /// The `log_level` filter.
+    base: Filter,
+    /// Writes the text format. It lets everything through; filtering is done
+    /// before lines are passed to it.
+    text: env_logger::Logger,
+}
+
+impl Log for TelescopeLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        let overrides = OVERRIDES.read().expect("Log level overrides lock poisoned");
+        // Modules match by prefix, like in the `log_level` filter.
+        match overrides
+            .iter()
+            .find(|(module, _)| metadata.target().starts_with(module.as_str()))
+        {
+            Some((_, level)) => metadata.level() <= *level,
+            None => self.base.enabled(metadata),
+        }
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        match self.format {
+            LogFormat::Text => self.text.log(record),
+            LogFormat::Json => write_json(record),
+        }
+    }
+
+    fn flush(&self) {
+        self.text.flush();
+    }
+}
+
+/// A log line written as a JSON object.
+#[derive(Serialize)]
+struct JsonLine<'a> {
+    timestamp: String,
+    level: &'static str,
+    target: &'a str,
+    message: String,
+    module: Option<&'a str>,
+    file: Option<&'a str>,
+    line: Option<u32>,
+    /// The fields of the request the line was written while handling, if
+    /// any.
+    #[serde(flatten)]
+    span: Option<RequestSpan>,
+}
+
+/// Write a log line as a JSON object.
+fn write_json(record: &Record) {
+    let line = JsonLine {
+        timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        level: record.level().as_str(),
+        target: record.target(),
+        message: record.args().to_string(),
+        module: record.module_path(),
+        file: record.file(),
+        line: record.line(),
+        span: current_span(),
+    };
+
+    // Nowhere to report a failure to write a log line.
+    if let Ok(line) = serde_json::to_string(&line) {
+        let stderr = io::stderr();
+        let _ = writeln!(stderr.lock(), "{}", line);
+    }
+}
+
+/// Let through the most verbose level any filter or override asks for.
+fn update_max_level() {
+    let base: LevelFilter = *BASE_MAX_LEVEL.read().expect("Log level lock poisoned");
+    let most_verbose: LevelFilter = OVERRIDES
+        .read()
+        .expect("Log level overrides lock poisoned")
+        .iter()
+        .map(|(_, level)| *level)
+        .fold(base, max);
+    log::set_max_level(most_verbose);
+}
+
+/// Install the logger. `filters` uses the env_logger syntax.
+pub fn init(filters: &str, format: LogFormat) {
+    let base: Filter = FilterBuilder::new().parse(filters).build();
+    *BASE_MAX_LEVEL.write().expect("Log level lock poisoned") = base.filter();
+
+    let logger = TelescopeLogger {
+        format,
+        base,
+        text: env_logger::Builder::new()
+            .filter_level(LevelFilter::Trace)
+            .build(),
+    };
+    log::set_boxed_logger(Box::new(logger)).expect("Could not install the logger.");
+    update_max_level();
+    install_panic_hook();
+}
+
+/// Log panics before the default hook prints them. Backtraces are turned on
+/// unless `RUST_BACKTRACE` says otherwise, so there is one to go with every
+/// logged panic.
+fn install_panic_hook() {
+    if env::var_os("RUST_BACKTRACE").is_none() {
+        env::set_var("RUST_BACKTRACE", "1");
+    }
+
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info: &PanicInfo| {
+        match current_span() {
+            Some(span) => error!(
+                "Request {} ({} {}) panicked: {}",
+                span.request_id, span.method, span.path, info
+            ),
+            None => error!("{}", info),
+        }
+        default_hook(info);
+    }));
+}
+
+/// The levels set at runtime, most specific module first.
+pub fn overrides() -> Vec<LevelOverride> {
+    OVERRIDES
+        .read()
+        .expect("Log level overrides lock poisoned")
+        .iter()
+        .map(|(module, level)| LevelOverride {
+            module: module.clone(),
+            level: level.as_str().to_lowercase(),
+        })
+        .collect()
+}
+
+/// Replace the levels set at runtime. Invalid overrides are skipped.
+pub fn set_overrides(overrides: Vec<LevelOverride>) {
+    let mut parsed: Vec<(String, LevelFilter)> = overrides
+        .iter()
+        .filter_map(|o| o.parse().map(|level| (o.module.clone(), level)))
+        .collect();
+    let skipped: usize = overrides.len() - parsed.len();
+
+    // Longer modules are more specific, so they are checked first.
+    parsed.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then(a.0.cmp(&b.0)));
+    *OVERRIDES
+        .write()
+        .expect("Log level overrides lock poisoned") = parsed;
+    update_max_level();
+
+    // Only log once the lock is released, since logging takes it too.
+    if skipped > 0 {
+        warn!("Skipped {} invalid log level overrides.", skipped);
+    }
+}
@@ -19,48 +19,107 @@ extern crate derive_more;
 #[macro_use]
 extern crate graphql_client;
 
-use crate::discord_bot::DiscordBot;
-use crate::templates::static_pages::sponsors::SponsorsPage;
-use crate::templates::static_pages::StaticPage;
-use crate::web::csrf::CsrfJanitor;
+use crate::jobs::badges::BadgeAwarder;
+use crate::jobs::certificates::CertificateWatcher;
+use crate::jobs::contributions::ContributionTracker;
+use crate::jobs::link_health::LinkChecker;
+use crate::jobs::meeting_reminders::MeetingReminders;
+use crate::jobs::presentation_reminders::PresentationReminders;
+use crate::jobs::publishing::DraftPublisher;
+use crate::jobs::recordings::RecordingWatcher;
+use crate::jobs::retention::RetentionEnforcer;
+use crate::jobs::search_index::SearchIndexer;
+use crate::jobs::tasks::TaskGenerator;
+use crate::jobs::webhooks::WebhookDelivery;
 use crate::web::middlewares;
+use crate::web::sessions::SessionJanitor;
 use actix::prelude::*;
 use actix_files as afs;
 use actix_identity::{CookieIdentityPolicy, IdentityService};
 use actix_web::cookie::SameSite;
-use actix_web::{middleware, web as aweb, web::get, App, HttpServer};
+use actix_web::{middleware, web as aweb, App, HttpServer};
 use chrono::Offset;
-use rand::rngs::OsRng;
-use rand::Rng;
 
 pub mod api;
 mod app_data;
 mod discord_bot;
 mod env;
 mod error;
+mod jobs;
+mod logging;
+mod seed;
 mod templates;
 mod web;
 
+/// Start the scheduled jobs and the Discord bot, which must only run once
+/// across all replicas.
+fn start_singletons() {
+    BadgeAwarder.start();
+    PresentationReminders.start();
+    MeetingReminders.start();
+    DraftPublisher.start();
+    ContributionTracker.start();
+    LinkChecker.start();
+    RecordingWatcher.start();
+    TaskGenerator.start();
+    RetentionEnforcer.start();
+    WebhookDelivery.start();
+
+    // Start the discord bot on its own thread. It reconnects by itself if
+    // it crashes.
+    discord_bot::start();
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     // set up logger and global web server configuration.
     env::init();
+    // Pass outbound proxies on to libraries that make their own HTTP clients.
+    api::http::init();
+
+    // Run one-off commands instead of the server.
+    if let Some(command) = env::command() {
+        let result = match command {
+            env::Command::Seed {
+                users,
+                semesters,
+                random_seed,
+                force,
+            } => seed::run(users, semesters, random_seed, force).await,
+        };
+        if let Err(e) = result {
+            error!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     // Log the server timezone
     info!("Server timezone: {}", chrono::Local::now().offset().fix());
 
-    // Start global CSRF token janitor.
-    CsrfJanitor.start();
+    // Connect the session store, and clean up session state kept in memory.
+    web::sessions::init();
+    SessionJanitor.start();
 
-    // Create and start the discord bot under a Supervisor that will
-    // restart it if it crashes.
-    Supervisor::start(|_| DiscordBot);
+    // Check cluster mode, and listen for changes broadcast by other replicas.
+    web::cluster::init();
+    actix_web::rt::spawn(web::cluster::listen());
+
+    // Start scheduled jobs and the Discord bot. In cluster mode these only run
+    // on the elected leader.
+    web::cluster::start_singletons(start_singletons);
+    // Every replica keeps its own search index.
+    SearchIndexer.start();
 
     // Setup identity middleware.
-    // Create secure random sequence to encrypt cookie identities.
-    let cookie_key: [u8; 32] = OsRng::default().gen::<[u8; 32]>();
+    // Get the key to encrypt cookie identities with. This is shared between
+    // replicas through the session store.
+    let cookie_key: Vec<u8> = web::services::auth::identity::cookie_key()
+        .await
+        .expect("Could not get the identity cookie key.");
 
-    // Construct and start main server instance.
-    let web_server = HttpServer::new(move || {
+    // Construct the main server instance.
+    let mut web_server = HttpServer::new(move || {
         // Create cookie policy.
         let cookie_policy = CookieIdentityPolicy::new(&cookie_key)
             // Transmit cookies over HTTPS only.
@@ -72,14 +131,45 @@ async fn main() -> std::io::Result<()> {
             .max_age_time(time::Duration::days(1));
 
         App::new()
+            // Answer with an error page when a handler panics. This is inside
+            // the error renderer, which renders the page.
+            .wrap(middlewares::catch_panic::CatchPanic)
+            // Give each request a deadline for its upstream calls. This is
+            // inside the error renderer so error pages are not cut short.
+            .wrap(middlewares::deadline::RequestDeadline)
+            // Keep the admin panel on the admin listener, if there is one.
+            .wrap(middlewares::admin_listener::AdminListener)
             // Middleware to render telescope errors into pages
             .wrap(middlewares::error_rendering::TelescopeErrorHandler)
+            // Check form tokens and fill them in to pages. This is outside the
+            // error renderer so the forms on error pages get tokens too.
+            .wrap(middlewares::csrf::CsrfProtection)
+            // Send members to accept the latest policies. This needs the identity cookie,
+            // so it has to be wrapped inside the identity middleware.
+            .wrap(middlewares::policy_gate::PolicyGate)
             // Cookie Identity middleware.
             .wrap(IdentityService::new(cookie_policy))
-            // Logger middleware
-            .wrap(middleware::Logger::default())
+            // Check rendered pages for accessibility problems in development.
+            .wrap(middleware::Condition::new(
+                env::global_config().a11y_audit,
+                middlewares::a11y_audit::A11yAudit,
+            ))
+            // Time requests and log the slow ones. This is inside the
+            // concurrency limiter so only requests that are let in count.
+            .wrap(middlewares::timing::RequestTiming)
+            // Turn requests away when too many are in flight.
+            .wrap(middlewares::concurrency::ConcurrencyLimiter)
+            // Logger middleware. Same as the default format, with the request ID.
+            .wrap(middleware::Logger::new(
+                r#"%a "%r" %s %b "%{Referer}i" "%{User-Agent}i" %T %{x-request-id}i"#,
+            ))
+            // Give each request an ID for the logs. This sets the request
+            // header the logger reads, so it has to wrap the logger.
+            .wrap(middlewares::request_id::RequestId)
             // Register Services
             .configure(web::services::register)
+            // Live update sockets
+            .configure(web::live::register)
             // static files service
             .service(
                 afs::Files::new("/static", "static")
@@ -88,15 +178,35 @@ async fn main() -> std::io::Result<()> {
                     // Show listings of directories
                     .show_files_listing(),
             )
-            .route("/sponsors", get().to(SponsorsPage::page))
             .default_service(aweb::to(web::services::not_found::not_found))
-    })
-    // Bind to 80 (this gets reversed proxied by Caddy later)
-    .bind("0.0.0.0:80")
-    .expect("Could not bind http://localhost:80")
+    });
+
+    // Load the TLS certificate if Telescope serves HTTPS itself, and watch it
+    // for renewals.
+    let tls_config = web::tls::server_config();
+    if tls_config.is_some() {
+        CertificateWatcher.start();
+    }
+
+    // Listen on every configured address. By default this is just port 80,
+    // which gets reverse proxied by Caddy.
+    for listener in web::listeners::open() {
+        web_server = match (listener, tls_config.as_ref()) {
+            (web::listeners::Listener::Tcp(listener), Some(tls)) => {
+                web_server.listen_rustls(listener, tls.clone())?
+            }
+            (web::listeners::Listener::Tcp(listener), None) => web_server.listen(listener)?,
+            #[cfg(unix)]
+            (web::listeners::Listener::Unix(listener), _) => web_server.listen_uds(listener)?,
+        };
+    }
+
     // Start the server running.
-    .run();
+    let server = web_server.run();
+    // Tell systemd when Telescope is ready, if it is running under systemd.
+    #[cfg(unix)]
+    actix_web::rt::spawn(web::systemd::notify_ready());
 
     // Wait on server to produce an error.
-    return web_server.await;
+    return server.await;
 }
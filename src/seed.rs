@@ -0,0 +1,488 @@
+//! The `seed` command, which fills an RCOS API with fake but realistic data so
+//! that Telescope can be worked on locally without a copy of production data.
+//!
+//! Everything is created through the same API wrappers the web server uses,
+//! so seeded data goes through the same paths real data does. The generated
+//! data is random, but the seed is logged so a run can be repeated against a
+//! fresh database with `--random-seed`.
+
+use crate::api::rcos::attendance::mutations::RecordAttendance;
+use crate::api::rcos::batch::{batch_execute, DEFAULT_CONCURRENCY};
+use crate::api::rcos::meetings::creation::create::CreateMeeting;
+use crate::api::rcos::meetings::MeetingType;
+use crate::api::rcos::prelude::{MeetingId, SemesterId};
+use crate::api::rcos::seed::{
+    SeedEnrollment, SeedProject, SeedSmallGroup, SeedSmallGroupMentor, SeedSmallGroupProject,
+};
+use crate::api::rcos::semesters::get_by_id::Semester;
+use crate::api::rcos::semesters::mutations::create::CreateSemester;
+use crate::api::rcos::users::create::CreateOneUser;
+use crate::api::rcos::users::{UserAccountType, UserRole};
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone, Utc, Weekday};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::process::exit;
+use url::Url;
+use uuid::Uuid;
+
+const FIRST_NAMES: &[&str] = &[
+    "Avery", "Jordan", "Priya", "Mateo", "Chen", "Fatima", "Liam", "Sofia", "Kwame", "Hana",
+    "Diego", "Amara", "Noah", "Yuki", "Omar", "Elena", "Ravi", "Grace", "Tariq", "Mei", "Samuel",
+    "Aisha", "Lucas", "Ingrid", "Jamal", "Nadia", "Ethan", "Leila", "Kai", "Rosa",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "Nguyen",
+    "Garcia",
+    "Patel",
+    "Kim",
+    "Okafor",
+    "Rossi",
+    "Schmidt",
+    "Haddad",
+    "Silva",
+    "Tanaka",
+    "Johnson",
+    "Kowalski",
+    "Mensah",
+    "Ivanova",
+    "Lopez",
+    "Chen",
+    "Murphy",
+    "Singh",
+    "Dubois",
+    "Andersson",
+    "Cohen",
+    "Ali",
+    "Bianchi",
+    "Yamamoto",
+    "Walker",
+    "Reyes",
+];
+
+const PROJECT_ADJECTIVES: &[&str] = &[
+    "Open", "Tiny", "Rapid", "Quiet", "Bright", "Shared", "Local", "Civic", "Secure", "Smart",
+];
+
+const PROJECT_NOUNS: &[&str] = &[
+    "Scheduler",
+    "Notebook",
+    "Tracker",
+    "Compiler",
+    "Garden",
+    "Map",
+    "Marketplace",
+    "Library",
+    "Dashboard",
+    "Synth",
+    "Tutor",
+    "Forum",
+    "Telemetry",
+    "Pantry",
+    "Transit",
+];
+
+const PROJECT_PURPOSES: &[&str] = &[
+    "helps students find study groups on campus",
+    "tracks local air quality with cheap sensors",
+    "makes course schedules easier to plan",
+    "teaches introductory programming through puzzles",
+    "connects volunteers with community organizations",
+    "visualizes public transit data in real time",
+    "keeps a shared inventory for student clubs",
+    "generates music from simple text patterns",
+];
+
+const STACKS: &[&str] = &[
+    "Rust",
+    "TypeScript",
+    "React",
+    "Python",
+    "Django",
+    "Flutter",
+    "Go",
+    "PostgreSQL",
+    "C++",
+    "Vue",
+];
+
+const ROOMS: &[&str] = &["DCC 308", "DCC 318", "Sage 3101", "Lally 102", "AE 217"];
+
+const WORKSHOP_TOPICS: &[&str] = &[
+    "Git and GitHub",
+    "Writing Good Issues",
+    "Intro to Docker",
+    "Testing Your Code",
+    "Giving a Good Presentation",
+];
+
+/// What is known about an enrolled user while seeding a semester.
+struct Enrollee {
+    user_id: Uuid,
+    project_id: Option<i64>,
+}
+
+/// Check that the configured API looks like a local development one: a
+/// loopback address or a single label host like the `hasura` docker service.
+fn api_is_local() -> bool {
+    Url::parse(global_config().api_url.as_str())
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .map(|host| {
+            host == "localhost" || host == "127.0.0.1" || host == "[::1]" || !host.contains('.')
+        })
+        .unwrap_or(false)
+}
+
+/// The spring or fall semester a date belongs to, as its ID, title, start, and
+/// end. Summers count towards the spring semester since few people enroll in
+/// them. Semesters are named by year and starting month like the registrar does.
+fn semester_of(date: NaiveDate) -> (String, String, NaiveDate, NaiveDate) {
+    let year = date.year();
+    let (month, title, start, end) = match date.month() {
+        1..=7 => (1, "Spring", (1, 10), (5, 1)),
+        _ => (9, "Fall", (9, 1), (12, 15)),
+    };
+    return (
+        format!("{}{:02}", year, month),
+        format!("{} {}", title, year),
+        NaiveDate::from_ymd(year, start.0, start.1),
+        NaiveDate::from_ymd(year, end.0, end.1),
+    );
+}
+
+/// A local time on a date, converted to UTC.
+fn local_time(date: NaiveDate, hour: u32) -> DateTime<Utc> {
+    Local
+        .from_local_datetime(&date.and_hms(hour, 0, 0))
+        .earliest()
+        .unwrap_or_else(|| Local.from_utc_datetime(&date.and_hms(hour, 0, 0)))
+        .with_timezone(&Utc)
+}
+
+/// The first date on or after `date` that falls on `weekday`.
+fn next_weekday(mut date: NaiveDate, weekday: Weekday) -> NaiveDate {
+    while date.weekday() != weekday {
+        date = date.succ();
+    }
+    return date;
+}
+
+/// Record attendance for a meeting. Failures are logged rather than stopping
+/// the seed, since a missing attendance record is harmless.
+async fn record_attendance(meeting_id: MeetingId, attendees: Vec<Uuid>) {
+    let calls = attendees.into_iter().map(|user_id| {
+        (
+            user_id.to_string(),
+            RecordAttendance::execute(meeting_id, user_id),
+        )
+    });
+    let report = batch_execute(calls, DEFAULT_CONCURRENCY).await;
+    if !report.is_complete() {
+        warn!(
+            "Could not record {} of {} attendances for meeting {}.",
+            report.failed,
+            report.outcomes.len(),
+            meeting_id
+        );
+    }
+}
+
+/// Create fake users. Users whose account ID is already taken are skipped.
+async fn seed_users(rng: &mut StdRng, count: usize) -> Result<Vec<Uuid>, TelescopeError> {
+    let mut users: Vec<Uuid> = Vec::with_capacity(count);
+    for i in 0..count {
+        let first_name = *FIRST_NAMES.choose(rng).unwrap();
+        let last_name = *LAST_NAMES.choose(rng).unwrap();
+        let role = match i {
+            0 => UserRole::FacultyAdvisor,
+            _ if rng.gen_bool(0.05) => UserRole::ExternalMentor,
+            _ if rng.gen_bool(0.05) => UserRole::Alum,
+            _ => UserRole::Student,
+        };
+
+        // RCS IDs are the first letters of the last name, the first initial,
+        // and a number.
+        let rcs_id = format!(
+            "{}{}{}",
+            last_name.chars().take(5).collect::<String>().to_lowercase(),
+            first_name.chars().next().unwrap().to_ascii_lowercase(),
+            rng.gen_range(1..1000)
+        );
+
+        match CreateOneUser::execute(
+            first_name.to_string(),
+            last_name.to_string(),
+            role,
+            UserAccountType::Rpi,
+            rcs_id.clone(),
+        )
+        .await
+        {
+            Ok(Some(id)) => users.push(id),
+            Ok(None) => warn!("User {} was not created.", rcs_id),
+            Err(e) => warn!("Skipping user {}: {}", rcs_id, e),
+        }
+    }
+
+    info!("Created {} users.", users.len());
+    return Ok(users);
+}
+
+/// Create fake projects.
+async fn seed_projects(rng: &mut StdRng, count: usize) -> Result<Vec<i64>, TelescopeError> {
+    let mut projects: Vec<i64> = Vec::with_capacity(count);
+    for _ in 0..count {
+        let title = format!(
+            "{} {}",
+            PROJECT_ADJECTIVES.choose(rng).unwrap(),
+            PROJECT_NOUNS.choose(rng).unwrap()
+        );
+        let description = format!(
+            "{} is an open source project that {}.",
+            title,
+            PROJECT_PURPOSES.choose(rng).unwrap()
+        );
+        let stack_size: usize = rng.gen_range(1..4);
+        let stack: Vec<String> = STACKS
+            .choose_multiple(rng, stack_size)
+            .map(|s| s.to_string())
+            .collect();
+        let repository = Url::parse(&format!(
+            "https://github.com/rcos/{}",
+            title.to_lowercase().replace(' ', "-")
+        ))
+        .map_err(|e| TelescopeError::ise(format!("Invalid repository URL: {}", e)))?;
+
+        projects.push(SeedProject::execute(title, description, stack, vec![repository]).await?);
+    }
+
+    info!("Created {} projects.", projects.len());
+    return Ok(projects);
+}
+
+/// Fill a semester with enrollments, small groups, meetings, and attendance.
+async fn seed_semester(
+    rng: &mut StdRng,
+    (semester_id, title, start, end): (String, String, NaiveDate, NaiveDate),
+    users: &[Uuid],
+    projects: &[i64],
+) -> Result<(), TelescopeError> {
+    // Use the semester if it already exists so seeding works on top of real
+    // semesters created through the admin panel.
+    if Semester::get_by_id(SemesterId(semester_id.clone()))
+        .await?
+        .is_none()
+    {
+        CreateSemester::execute(semester_id.clone(), title.clone(), start, end).await?;
+        info!("Created semester {}.", title);
+    }
+
+    // Enroll most users. The first few enrollees coordinate, and the first
+    // enrollee on each project leads it.
+    let mut enrollees: Vec<Enrollee> = Vec::new();
+    let mut led: Vec<i64> = Vec::new();
+    for user_id in users {
+        if !rng.gen_bool(0.8) {
+            continue;
+        }
+
+        let is_coordinator = enrollees.len() < 2;
+        let project_id: Option<i64> = if is_coordinator || rng.gen_bool(0.1) {
+            None
+        } else {
+            projects.choose(rng).copied()
+        };
+        let is_project_lead = project_id.map(|p| !led.contains(&p)).unwrap_or(false);
+        if is_project_lead {
+            led.extend(project_id);
+        }
+
+        let credits = *[0, 1, 2, 4].choose(rng).unwrap();
+        SeedEnrollment::execute(
+            semester_id.clone(),
+            *user_id,
+            project_id,
+            credits,
+            is_project_lead,
+            is_coordinator,
+        )
+        .await?;
+
+        enrollees.push(Enrollee {
+            user_id: *user_id,
+            project_id,
+        });
+    }
+
+    if enrollees.is_empty() {
+        warn!("Nobody was enrolled in {}.", title);
+        return Ok(());
+    }
+
+    // Split the projects into small groups of about three, each with a mentor.
+    let mut small_groups: Vec<(i64, Vec<i64>)> = Vec::new();
+    let mut shuffled: Vec<i64> = projects.to_vec();
+    shuffled.shuffle(rng);
+    for (i, chunk) in shuffled.chunks(3).enumerate() {
+        let room: Option<String> = ROOMS.choose(rng).map(|r| r.to_string());
+        let group_id =
+            SeedSmallGroup::execute(semester_id.clone(), format!("Group {}", i + 1), room).await?;
+        for project_id in chunk {
+            SeedSmallGroupProject::execute(group_id, *project_id).await?;
+        }
+        let mentor = enrollees.choose(rng).unwrap().user_id;
+        SeedSmallGroupMentor::execute(group_id, mentor).await?;
+        small_groups.push((group_id, chunk.to_vec()));
+    }
+
+    // Weekly meetings, up to two weeks from now so there is something upcoming.
+    let today: NaiveDate = Local::today().naive_local();
+    let last_meeting: NaiveDate = end.min(today + Duration::weeks(2));
+    let coordinators: Vec<Uuid> = enrollees.iter().take(2).map(|e| e.user_id).collect();
+    let mut meeting_count: usize = 0;
+
+    let mut week: NaiveDate = next_weekday(start, Weekday::Tue);
+    let mut week_number: usize = 1;
+    while week <= last_meeting {
+        // Tuesday large group meeting for everyone.
+        let large_group = CreateMeeting::execute(
+            coordinators.choose(rng).copied(),
+            Some(format!("Week {} Large Group", week_number)),
+            local_time(week, 16),
+            local_time(week, 18),
+            "Announcements and project updates.".into(),
+            false,
+            false,
+            Some("DCC 308".into()),
+            None,
+            None,
+            None,
+            None,
+            semester_id.clone(),
+            MeetingType::LargeGroup.into(),
+        )
+        .await?;
+        meeting_count += 1;
+        if let Some(meeting_id) = large_group.filter(|_| week < today) {
+            let attendees: Vec<Uuid> = enrollees
+                .iter()
+                .filter(|_| rng.gen_bool(0.75))
+                .map(|e| e.user_id)
+                .collect();
+            record_attendance(meeting_id, attendees).await;
+        }
+
+        // Friday small group meetings for each group's members.
+        let friday: NaiveDate = next_weekday(week, Weekday::Fri);
+        for (i, (_, group_projects)) in small_groups.iter().enumerate() {
+            let meeting = CreateMeeting::execute(
+                None,
+                Some(format!("Group {} Week {}", i + 1, week_number)),
+                local_time(friday, 16),
+                local_time(friday, 18),
+                "Weekly small group check in.".into(),
+                false,
+                rng.gen_bool(0.2),
+                ROOMS.choose(rng).map(|r| r.to_string()),
+                None,
+                None,
+                None,
+                None,
+                semester_id.clone(),
+                MeetingType::SmallGroup.into(),
+            )
+            .await?;
+            meeting_count += 1;
+            if let Some(meeting_id) = meeting.filter(|_| friday < today) {
+                let attendees: Vec<Uuid> = enrollees
+                    .iter()
+                    .filter(|e| {
+                        e.project_id
+                            .map(|p| group_projects.contains(&p))
+                            .unwrap_or(false)
+                    })
+                    .filter(|_| rng.gen_bool(0.8))
+                    .map(|e| e.user_id)
+                    .collect();
+                record_attendance(meeting_id, attendees).await;
+            }
+        }
+
+        // A workshop every few weeks.
+        if week_number % 4 == 2 {
+            let thursday: NaiveDate = next_weekday(week, Weekday::Thu);
+            CreateMeeting::execute(
+                enrollees.choose(rng).map(|e| e.user_id),
+                WORKSHOP_TOPICS.choose(rng).map(|t| t.to_string()),
+                local_time(thursday, 18),
+                local_time(thursday, 19),
+                "A bonus workshop open to everyone.".into(),
+                false,
+                true,
+                None,
+                Some("https://example.com/rcos-workshop".into()),
+                None,
+                None,
+                None,
+                semester_id.clone(),
+                MeetingType::BonusSession.into(),
+            )
+            .await?;
+            meeting_count += 1;
+        }
+
+        week = week + Duration::weeks(1);
+        week_number += 1;
+    }
+
+    info!(
+        "Seeded {} with {} enrollments, {} small groups, and {} meetings.",
+        title,
+        enrollees.len(),
+        small_groups.len(),
+        meeting_count
+    );
+    return Ok(());
+}
+
+/// Fill the configured RCOS API with fake data.
+pub async fn run(
+    users: usize,
+    semesters: usize,
+    random_seed: Option<u64>,
+    force: bool,
+) -> Result<(), TelescopeError> {
+    if !force && !api_is_local() {
+        error!(
+            "{} does not look like a local API. Use --force to seed it anyway.",
+            global_config().api_url
+        );
+        exit(1);
+    }
+
+    let random_seed: u64 = random_seed.unwrap_or_else(|| rand::thread_rng().gen());
+    info!("Seeding with random seed {}.", random_seed);
+    let mut rng = StdRng::seed_from_u64(random_seed);
+
+    let users: Vec<Uuid> = seed_users(&mut rng, users).await?;
+    let projects: Vec<i64> = seed_projects(&mut rng, (users.len() / 6).max(1)).await?;
+
+    // Walk back from the current semester.
+    let mut date: NaiveDate = Local::today().naive_local();
+    for _ in 0..semesters {
+        seed_semester(&mut rng, semester_of(date), &users, &projects).await?;
+        // Step into the middle of the previous semester.
+        date = if date.month() <= 7 {
+            NaiveDate::from_ymd(date.year() - 1, 10, 1)
+        } else {
+            NaiveDate::from_ymd(date.year(), 3, 1)
+        };
+    }
+
+    info!("Done seeding.");
+    return Ok(());
+}
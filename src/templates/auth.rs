@@ -1,5 +1,6 @@
 //! Templates for users to login and register with.
 
+use crate::error::TelescopeError;
 use crate::templates::Template;
 use crate::web::services::auth::oauth2_providers::{discord::DiscordOAuth, github::GitHubOauth};
 use crate::web::services::auth::rpi_cas::RpiCas;
@@ -57,7 +58,7 @@ fn item(
 }
 
 /// Create a template to offer the user options to login.
-pub fn login() -> Template {
+pub fn login() -> Result<Template, TelescopeError> {
     // Make list of identity providers in login configuration.
     let items: Vec<Map<String, Value>> = vec![
         item(
@@ -79,13 +80,14 @@ pub fn login() -> Template {
 
     // Create and return template.
     let mut template = empty();
-    template[HEADER] = json!("Sign In");
-    template[ITEMS] = json!(items);
-    return template;
+    template
+        .set_field(HEADER, "Sign In")?
+        .set_field(ITEMS, items)?;
+    return Ok(template);
 }
 
 /// Create a template to offer the users options to register a new account.
-pub fn register() -> Template {
+pub fn register() -> Result<Template, TelescopeError> {
     // Make list of identity providers in account creation configuration.
     let items: Vec<Map<String, Value>> = vec![
         //         item(
@@ -112,7 +114,8 @@ pub fn register() -> Template {
 
     // Create and return template.
     let mut template = empty();
-    template[HEADER] = json!("Create Account");
-    template[ITEMS] = json!(items);
-    return template;
+    template
+        .set_field(HEADER, "Create Account")?
+        .set_field(ITEMS, items)?;
+    return Ok(template);
 }
@@ -0,0 +1,88 @@
+//! Site-wide banner for meetings that are in progress or about to start.
+//!
+//! Every page shows the banner, so the meetings it could show are fetched at
+//! most once a minute and shared between requests. Which of them are shown,
+//! and how long until they start, is worked out for each request. Everything
+//! is compared in UTC so the banner does not depend on the server's timezone.
+
+use crate::api::rcos::meetings::happening_now::{
+    happening_now::HappeningNowMeetings, HappeningNow,
+};
+use crate::api::rcos::meetings::MeetingType;
+use crate::error::TelescopeError;
+use chrono::{DateTime, Duration, Utc};
+use std::sync::RwLock;
+
+/// Meeting types that get a banner. Bonus sessions are the workshops.
+const BANNER_TYPES: [MeetingType; 2] = [MeetingType::LargeGroup, MeetingType::BonusSession];
+
+/// How long before a meeting starts the banner shows it.
+const LEAD_MINUTES: i64 = 30;
+
+/// How long fetched meetings are reused.
+const REFRESH_SECONDS: i64 = 60;
+
+lazy_static! {
+    /// The meetings fetched last, and when they were fetched.
+    static ref CACHE: RwLock<Option<(DateTime<Utc>, Vec<HappeningNowMeetings>)>> =
+        RwLock::new(None);
+}
+
+/// A meeting shown in the banner.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct BannerMeeting {
+    meeting_id: i64,
+    /// The meeting title, or its type if it has none.
+    title: String,
+    /// Has the meeting started?
+    started: bool,
+    /// Minutes until the meeting starts, if it has not started.
+    minutes_until: i64,
+}
+
+/// Get the meetings that could be shown in the banner any time before the
+/// next refresh.
+async fn candidates(now: DateTime<Utc>) -> Result<Vec<HappeningNowMeetings>, TelescopeError> {
+    if let Some((fetched, meetings)) = CACHE.read().unwrap().as_ref() {
+        if now - *fetched < Duration::seconds(REFRESH_SECONDS) {
+            return Ok(meetings.clone());
+        }
+    }
+
+    // Look far enough ahead to cover meetings that come into the banner
+    // before the next refresh.
+    let cutoff = now + Duration::minutes(LEAD_MINUTES) + Duration::seconds(REFRESH_SECONDS);
+    let meetings = HappeningNow::get(now, cutoff, BANNER_TYPES.to_vec()).await?;
+    *CACHE.write().unwrap() = Some((now, meetings.clone()));
+    return Ok(meetings);
+}
+
+/// Get the meetings to show in the banner right now. Error pages are rendered
+/// with the banner too, so API errors are logged and no banner is shown
+/// instead.
+pub async fn happening_now() -> Vec<BannerMeeting> {
+    let now: DateTime<Utc> = Utc::now();
+    let meetings = match candidates(now).await {
+        Ok(meetings) => meetings,
+        Err(err) => {
+            warn!("Could not get meetings for the page banner: {}", err);
+            return Vec::new();
+        }
+    };
+
+    let lead = Duration::minutes(LEAD_MINUTES);
+    return meetings
+        .into_iter()
+        .filter(|meeting| meeting.end_date_time > now && meeting.start_date_time - lead <= now)
+        .map(|meeting| BannerMeeting {
+            meeting_id: meeting.meeting_id,
+            title: meeting
+                .title
+                .clone()
+                .unwrap_or_else(|| format!("RCOS {}", meeting.type_)),
+            started: meeting.start_date_time <= now,
+            // Round up, so a meeting a few seconds away is "in 1 minute".
+            minutes_until: ((meeting.start_date_time - now).num_seconds().max(0) + 59) / 60,
+        })
+        .collect();
+}
@@ -0,0 +1,134 @@
+//! Cache of rendered page content.
+//!
+//! Some pages render long lists -- the developers catalogue renders a card per
+//! user -- and rendering them takes longer than fetching their data. A page
+//! can opt in with [`Page::cached`], after which its content is rendered once
+//! per template, set of fields, and viewer role, and reused for repeat views.
+//!
+//! Since the fields are part of the key, a render is never reused for
+//! different data. The cache is still dropped when the data it was built from
+//! changes, so that stale renders do not pile up: each page lists the
+//! [`Dependency`]s of its content, and mutations call [`invalidate`] with the
+//! ones they touch. Entries also expire after a few minutes.
+//!
+//! [`Page::cached`]: crate::templates::page::Page::cached
+
+use crate::api::rcos::users::UserRole;
+use crate::error::TelescopeError;
+use crate::templates::Template;
+use crate::web::cluster::{self, Broadcast};
+use dashmap::DashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+/// How long a render is reused.
+const LIFETIME: Duration = Duration::from_secs(10 * 60);
+
+/// The most renders kept at once. Expired renders are dropped when this is
+/// reached, and everything is dropped if that is not enough.
+const MAX_ENTRIES: usize = 1000;
+
+lazy_static! {
+    /// Rendered content and what it was built from.
+    static ref CACHE: DashMap<Key, Entry> = DashMap::new();
+}
+
+/// Data that rendered content can be built from.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Dependency {
+    /// User names, roles, usernames, and whether users are current members.
+    Users,
+    /// Badges awarded to users.
+    Badges,
+}
+
+/// How a page's content should be cached.
+#[derive(Copy, Clone, Debug)]
+pub struct CachePolicy {
+    /// The role of the viewer, or `None` for visitors who are not logged in.
+    pub role: Option<UserRole>,
+    /// What the content is built from.
+    pub dependencies: &'static [Dependency],
+}
+
+/// Identifies a render.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+struct Key {
+    template: String,
+    /// Hash of the template fields.
+    fields: u64,
+    role: Option<UserRole>,
+}
+
+/// A cached render.
+struct Entry {
+    html: String,
+    dependencies: &'static [Dependency],
+    rendered: Instant,
+}
+
+/// Hash the fields of a template.
+fn fingerprint(template: &Template) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    // JSON values are not hashable themselves, but serialize the same way
+    // every time.
+    template.fields.to_string().hash(&mut hasher);
+    return hasher.finish();
+}
+
+/// Make room for another render.
+fn make_room() {
+    if CACHE.len() < MAX_ENTRIES {
+        return;
+    }
+    CACHE.retain(|_, entry| entry.rendered.elapsed() < LIFETIME);
+    if CACHE.len() >= MAX_ENTRIES {
+        warn!("Render cache is full. Dropping every cached render.");
+        CACHE.clear();
+    }
+}
+
+/// Render a template, reusing an earlier render if there is one.
+pub fn render(template: &Template, policy: &CachePolicy) -> Result<String, TelescopeError> {
+    let key = Key {
+        template: template.handlebars_file.clone(),
+        fields: fingerprint(template),
+        role: policy.role,
+    };
+
+    if let Some(entry) = CACHE.get(&key) {
+        if entry.rendered.elapsed() < LIFETIME {
+            return Ok(entry.html.clone());
+        }
+    }
+
+    let html: String = template.render()?;
+    make_room();
+    CACHE.insert(
+        key,
+        Entry {
+            html: html.clone(),
+            dependencies: policy.dependencies,
+            rendered: Instant::now(),
+        },
+    );
+    return Ok(html);
+}
+
+/// Drop renders built from some data on every replica. Call this after
+/// changing it.
+pub fn invalidate(dependency: Dependency) {
+    cluster::broadcast(Broadcast::RenderCacheInvalidated { dependency });
+}
+
+/// Drop the renders of this replica that were built from some data.
+pub(crate) fn clear(dependency: Dependency) {
+    CACHE.retain(|_, entry| !entry.dependencies.contains(&dependency));
+}
+
+/// Drop every render of this replica.
+pub(crate) fn clear_all() {
+    CACHE.clear();
+}
@@ -0,0 +1,179 @@
+//! Form schemas.
+//!
+//! A [`FormSchema`] lists the fields of a form with their labels, input types,
+//! and constraints. Handlers put the schema in the form template as `schema`,
+//! where the `forms/field` partial renders each field from its entry, and
+//! check submissions against the same schema with [`FormSchema::validate`].
+//! Rules that involve more than one field, like an end date after a start
+//! date, are still checked by the handler.
+
+use crate::templates::Template;
+use chrono::{NaiveDate, NaiveTime};
+use serde::ser::{Serialize, Serializer};
+use serde_json::Value;
+
+/// How a field is entered.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+    Text,
+    /// Multiple lines of text.
+    TextArea,
+    /// A date, submitted as `YYYY-MM-DD`.
+    Date,
+    /// A time of day, submitted as `HH:MM`.
+    Time,
+    Url,
+    /// A checkbox, submitted as `true` when checked.
+    Checkbox,
+}
+
+/// One field of a form.
+#[derive(Copy, Clone, Debug)]
+pub struct Field {
+    /// The name the field is submitted under.
+    pub name: &'static str,
+    pub label: &'static str,
+    pub kind: FieldKind,
+    /// Must the field be filled in? Fields that are only required for some
+    /// values of other fields are not marked here.
+    pub required: bool,
+    /// The most characters the field can have.
+    pub max_length: Option<usize>,
+    /// Text shown under the field. This is not escaped, so it can contain
+    /// links.
+    pub help: Option<&'static str>,
+}
+
+impl Field {
+    /// A field with no constraints or help text.
+    pub const fn new(name: &'static str, label: &'static str, kind: FieldKind) -> Self {
+        Field {
+            name,
+            label,
+            kind,
+            required: false,
+            max_length: None,
+            help: None,
+        }
+    }
+
+    /// Require this field to be filled in.
+    pub const fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Limit the length of this field.
+    pub const fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Show help text under this field.
+    pub const fn help(mut self, help: &'static str) -> Self {
+        self.help = Some(help);
+        self
+    }
+
+    /// The HTML ID of the input, like `start-date-input`.
+    fn id(&self) -> String {
+        let suffix: &str = match self.kind {
+            FieldKind::Checkbox => "check",
+            _ => "input",
+        };
+        return format!("{}-{}", self.name.replace('_', "-"), suffix);
+    }
+
+    /// Check a submitted value of this field, and return the issue to show
+    /// under it if there is one.
+    fn check(&self, value: &Value) -> Option<String> {
+        let text: &str = match value {
+            Value::String(text) => text.trim(),
+            // Checkboxes are deserialized as booleans, and other fields that
+            // were left out are null.
+            _ => "",
+        };
+
+        if text.is_empty() {
+            return (self.required && self.kind != FieldKind::Checkbox)
+                .then(|| "This field is required.".to_string());
+        }
+
+        if let Some(max_length) = self.max_length {
+            if text.chars().count() > max_length {
+                return Some(format!(
+                    "This cannot be longer than {} characters.",
+                    max_length
+                ));
+            }
+        }
+
+        match self.kind {
+            FieldKind::Date if NaiveDate::parse_from_str(text, "%Y-%m-%d").is_err() => {
+                Some("This is not a valid date.".to_string())
+            }
+            FieldKind::Time if NaiveTime::parse_from_str(text, "%H:%M").is_err() => {
+                Some("This is not a valid time.".to_string())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The template fields for one field of a schema.
+#[derive(Serialize)]
+struct FieldView<'a> {
+    name: &'a str,
+    id: String,
+    label: &'a str,
+    kind: FieldKind,
+    required: bool,
+    max_length: Option<usize>,
+    help: Option<&'a str>,
+}
+
+/// The fields of a form.
+#[derive(Copy, Clone, Debug)]
+pub struct FormSchema {
+    pub fields: &'static [Field],
+}
+
+impl FormSchema {
+    /// Check submitted values against the schema. The values are the form's
+    /// selections, keyed by field name. Issues are added to the form under
+    /// `issues`, and fields that already have an issue are skipped. Return
+    /// true if no issues were added.
+    pub fn validate(&self, form: &mut Template, values: &Value) -> bool {
+        let mut valid: bool = true;
+        for field in self.fields {
+            if !form["issues"][field.name].is_null() {
+                continue;
+            }
+            if let Some(issue) = field.check(&values[field.name]) {
+                form.set_issue(field.name, issue);
+                valid = false;
+            }
+        }
+        return valid;
+    }
+}
+
+/// Schemas are rendered as an object of fields by name, so templates can
+/// place each one with `{{> forms/field field=schema.title}}`.
+impl Serialize for FormSchema {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.fields.iter().map(|field| {
+            let view = FieldView {
+                name: field.name,
+                id: field.id(),
+                label: field.label,
+                kind: field.kind,
+                required: field.required,
+                max_length: field.max_length,
+                help: field.help,
+            };
+            (field.name, view)
+        }))
+    }
+}
@@ -1,12 +1,14 @@
 //! Handlebars helpers.
 
 use crate::api::rcos::meetings::MeetingType;
+use crate::api::rcos::users::display::UserDisplay;
 use crate::api::rcos::users::UserRole;
+use crate::templates::markdown;
+use crate::web::middlewares::csrf;
 use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime};
 use handlebars::{
     Context, Handlebars, Helper, HelperDef, HelperResult, Output, RenderContext, RenderError,
 };
-use pulldown_cmark::{Options as MarkdownOptions, Parser as MarkdownParser};
 use serde_json::Value;
 use std::collections::HashMap;
 use url::Url;
@@ -20,9 +22,13 @@ pub fn register_helpers(registry: &mut Handlebars) {
         wrap_helper(format_meeting_type_helper),
     );
     registry.register_helper("format_user_role", wrap_helper(format_user_role));
+    registry.register_helper("display_name", wrap_helper(display_name_helper));
     registry.register_helper("domain_of", wrap_helper(domain_of_helper));
     registry.register_helper("url_encode", wrap_helper(url_encode_helper));
     registry.register_helper("render_markdown", wrap_helper(markdown_renderer_helper));
+    registry.register_helper("sparkline", wrap_helper(sparkline_helper));
+    registry.register_helper("bar_chart", wrap_helper(bar_chart_helper));
+    registry.register_helper("csrf_field", wrap_helper(csrf_field_helper));
 }
 
 /// Wrap a two-argument helper function into a helper object to add to the
@@ -188,6 +194,21 @@ fn format_user_role(h: &Helper<'_, '_>, out: &mut dyn Output) -> HelperResult {
     Ok(())
 }
 
+/// Helper to show a user by name and pronouns. The parameter is any user object
+/// with the fields of [`UserDisplay`].
+fn display_name_helper(h: &Helper<'_, '_>, out: &mut dyn Output) -> HelperResult {
+    let user: UserDisplay = h
+        .param(0)
+        .and_then(|param| UserDisplay::from_json(param.value()))
+        .ok_or(RenderError::new(
+            "display_name expects a user with a first and last name",
+        ))?;
+
+    // Names are user input, so escape them like any other rendered value.
+    out.write(handlebars::html_escape(user.to_string().as_str()).as_str())?;
+    Ok(())
+}
+
 /// Helper to urlencode a query string.
 /// This accepts a series of hash arguments and encodes all of them.
 fn url_encode_helper(h: &Helper<'_, '_>, out: &mut dyn Output) -> HelperResult {
@@ -219,15 +240,136 @@ fn markdown_renderer_helper(h: &Helper<'_, '_>, out: &mut dyn Output) -> HelperR
             .ok_or(RenderError::new(
                 "render_markdown expects a markdown string parameter.",
             ))?;
-    // Escape all the HTML in the markdown source to avoid cross-site-scripting issues.
-    let escaped: String = v_htmlescape::escape(markdown_source).to_string();
-    // Make a new parser with all options enabled.
-    let parser = MarkdownParser::new_ext(escaped.as_str(), MarkdownOptions::all());
-    // Make and write an HTML buffer with the rendered markdown.
-    // Set the initial capacity at at least the length of the escaped markdown.
-    let mut buffer = String::with_capacity(escaped.len());
-    pulldown_cmark::html::push_html(&mut buffer, parser);
+    // Render the markdown, removing any unsafe HTML written in it.
+    let buffer: String = markdown::render(markdown_source);
     // Write the rendered HTML to the handlebars output.
     out.write(buffer.as_str())?;
     return Ok(());
 }
+
+/// Helper to render a list of numbers as an inline SVG sparkline.
+fn sparkline_helper(h: &Helper<'_, '_>, out: &mut dyn Output) -> HelperResult {
+    // Expect one parameter with an array of numbers.
+    let values: Vec<f64> = h
+        .param(0)
+        .and_then(|param| param.value().as_array())
+        .map(|array| array.iter().filter_map(Value::as_f64).collect())
+        .ok_or(RenderError::new(
+            "sparkline expects an array of numbers parameter.",
+        ))?;
+
+    // Size of the SVG viewbox.
+    const WIDTH: f64 = 120.0;
+    const HEIGHT: f64 = 24.0;
+
+    // Scale to the largest value, leaving a pixel of room for the stroke.
+    let max: f64 = values.iter().cloned().fold(1.0, f64::max);
+    let step: f64 = WIDTH / (values.len().max(2) - 1) as f64;
+    let points: Vec<String> = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let x = i as f64 * step;
+            let y = HEIGHT - 1.0 - (value / max) * (HEIGHT - 2.0);
+            format!("{:.1},{:.1}", x, y)
+        })
+        .collect();
+
+    let svg = format!(
+        "<svg class=\"sparkline\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" role=\"img\" aria-label=\"Weekly totals: {label}\">\
+         <polyline fill=\"none\" stroke=\"currentColor\" stroke-width=\"2\" points=\"{points}\"/></svg>",
+        w = WIDTH,
+        h = HEIGHT,
+        label = values
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+        points = points.join(" ")
+    );
+    out.write(svg.as_str())?;
+    return Ok(());
+}
+
+/// Helper to render a list of numbers as an inline SVG bar chart. Takes the
+/// numbers, then the label under each bar, then a description of the chart for
+/// screen readers.
+fn bar_chart_helper(h: &Helper<'_, '_>, out: &mut dyn Output) -> HelperResult {
+    let values: Vec<f64> = h
+        .param(0)
+        .and_then(|param| param.value().as_array())
+        .map(|array| array.iter().filter_map(Value::as_f64).collect())
+        .ok_or(RenderError::new(
+            "bar_chart expects an array of numbers as its first parameter.",
+        ))?;
+    let labels: Vec<&str> = h
+        .param(1)
+        .and_then(|param| param.value().as_array())
+        .map(|array| array.iter().filter_map(Value::as_str).collect())
+        .ok_or(RenderError::new(
+            "bar_chart expects an array of labels as its second parameter.",
+        ))?;
+    let description: &str = h
+        .param(2)
+        .and_then(|param| param.value().as_str())
+        .unwrap_or("Bar chart");
+
+    // Width of the space for each bar, and heights of the plot and of the
+    // room for the values above and labels below it.
+    const SLOT: f64 = 64.0;
+    const PLOT: f64 = 160.0;
+    const TOP: f64 = 16.0;
+    const BOTTOM: f64 = 20.0;
+
+    let width: f64 = SLOT * values.len().max(1) as f64;
+    let height: f64 = TOP + PLOT + BOTTOM;
+    let max: f64 = values.iter().cloned().fold(1.0, f64::max);
+
+    let mut bars = String::new();
+    for (i, value) in values.iter().enumerate() {
+        let label: String = handlebars::html_escape(labels.get(i).copied().unwrap_or(""));
+        let bar: f64 = value / max * PLOT;
+        let x: f64 = i as f64 * SLOT;
+        let center: f64 = x + SLOT / 2.0;
+        bars.push_str(&format!(
+            "<g><title>{label}: {value}</title>\
+             <rect x=\"{x:.1}\" y=\"{y:.1}\" width=\"{w:.1}\" height=\"{bar:.1}\" fill=\"currentColor\"/>\
+             <text x=\"{c:.1}\" y=\"{vy:.1}\" text-anchor=\"middle\" font-size=\"11\" fill=\"currentColor\">{value}</text>\
+             <text x=\"{c:.1}\" y=\"{ly:.1}\" text-anchor=\"middle\" font-size=\"10\" fill=\"currentColor\">{label}</text></g>",
+            label = label,
+            value = value,
+            x = x + SLOT * 0.15,
+            y = TOP + PLOT - bar,
+            w = SLOT * 0.7,
+            bar = bar,
+            c = center,
+            vy = TOP + PLOT - bar - 4.0,
+            ly = height - 6.0,
+        ));
+    }
+
+    let svg = format!(
+        "<svg class=\"bar-chart\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" role=\"img\" aria-label=\"{description}\">{bars}</svg>",
+        w = width,
+        h = height,
+        description = handlebars::html_escape(description),
+        bars = bars
+    );
+    out.write(svg.as_str())?;
+    return Ok(());
+}
+
+/// Helper to write the hidden CSRF token field for a form. The token itself
+/// is filled in by the [CSRF middleware](crate::web::middlewares::csrf), since
+/// rendered content may be shared between viewers.
+fn csrf_field_helper(_: &Helper<'_, '_>, out: &mut dyn Output) -> HelperResult {
+    out.write(
+        format!(
+            "<input type=\"hidden\" name=\"{}\" value=\"{}\">",
+            csrf::FIELD_NAME,
+            csrf::PLACEHOLDER
+        )
+        .as_str(),
+    )?;
+    return Ok(());
+}
@@ -0,0 +1,316 @@
+//! Rendering user written markdown to HTML that is safe to put in a page.
+//!
+//! Meeting descriptions, project descriptions, status updates, and custom
+//! pages are all stored as markdown and rendered with the `render_markdown`
+//! helper, which uses [`render`]. HTML written in the markdown is limited to a
+//! few formatting tags without attributes. Other tags are removed, and
+//! scripts and styles are removed along with their contents. Links and images
+//! can only use web and email URLs.
+
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+
+/// Tags that can be written as HTML in markdown. They cannot have attributes.
+const ALLOWED_TAGS: &[&'static str] = &[
+    "br", "kbd", "sub", "sup", "del", "ins", "mark", "details", "summary",
+];
+
+/// Tags removed along with everything in them.
+const DROPPED_TAGS: &[&'static str] = &["script", "style", "iframe", "object", "embed", "template"];
+
+/// URL schemes links and images can use. URLs without a scheme are relative
+/// to the page, and are allowed.
+const ALLOWED_SCHEMES: &[&'static str] = &["http", "https", "mailto"];
+
+/// Where unsafe links and images point instead.
+const BLOCKED_URL: &'static str = "#";
+
+/// Check if a link or image URL is safe to render. Browsers skip whitespace
+/// and control characters in schemes, so those are skipped here too.
+fn safe_url(url: &str) -> bool {
+    let cleaned: String = url
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect();
+
+    // A colon before any slash, query, or fragment ends a scheme.
+    let scheme_end: Option<usize> = cleaned.find(|c| matches!(c, ':' | '/' | '?' | '#'));
+    return match scheme_end {
+        Some(end) if cleaned[end..].starts_with(':') => ALLOWED_SCHEMES
+            .iter()
+            .any(|scheme| cleaned[..end].eq_ignore_ascii_case(scheme)),
+        _ => true,
+    };
+}
+
+/// Sanitize a piece of HTML from the markdown source. `dropping` is the tag
+/// whose contents are being removed, which can span several pieces.
+fn sanitize_html(source: &str, dropping: &mut Option<String>) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut rest: &str = source;
+
+    while !rest.is_empty() {
+        let (text, tag): (&str, Option<&str>) = match rest.find('<') {
+            Some(start) => match rest[start..].find('>') {
+                Some(end) => (&rest[..start], Some(&rest[start..start + end + 1])),
+                // A lone `<` is text.
+                None => (rest, None),
+            },
+            None => (rest, None),
+        };
+        rest = &rest[text.len() + tag.map_or(0, str::len)..];
+
+        if dropping.is_none() {
+            output.push_str(&v_htmlescape::escape(text).to_string());
+        }
+
+        let tag: &str = match tag {
+            Some(tag) => tag,
+            None => continue,
+        };
+
+        let inner: &str = tag[1..tag.len() - 1].trim();
+        let closing: bool = inner.starts_with('/');
+        let inner: &str = inner.trim_start_matches('/');
+        let name_end: usize = inner
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(inner.len());
+        let name: String = inner[..name_end].to_ascii_lowercase();
+        let has_attributes: bool = !inner[name_end..].trim().trim_end_matches('/').is_empty();
+
+        if let Some(dropped) = dropping.as_deref() {
+            if closing && name == dropped {
+                *dropping = None;
+            }
+            continue;
+        }
+
+        if DROPPED_TAGS.contains(&name.as_str()) {
+            if !closing && !inner.ends_with('/') {
+                *dropping = Some(name);
+            }
+        } else if ALLOWED_TAGS.contains(&name.as_str()) && !has_attributes {
+            output.push_str(if closing { "</" } else { "<" });
+            output.push_str(name.as_str());
+            output.push('>');
+        }
+        // Any other tag is removed, but what is in it is kept.
+    }
+
+    return output;
+}
+
+/// Render markdown to sanitized HTML.
+pub fn render(source: &str) -> String {
+    let options: Options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+
+    let mut dropping: Option<String> = None;
+    let mut events: Vec<Event> = Vec::new();
+    for event in Parser::new_ext(source, options) {
+        let event: Event = match event {
+            Event::Html(piece) => Event::Html(sanitize_html(&piece, &mut dropping).into()),
+            // The text between the tags of a dropped element is removed.
+            // Other events are kept so that the rest of the HTML is balanced.
+            Event::Text(_)
+            | Event::Code(_)
+            | Event::SoftBreak
+            | Event::HardBreak
+            | Event::FootnoteReference(_)
+            | Event::TaskListMarker(_)
+                if dropping.is_some() =>
+            {
+                continue
+            }
+            Event::Start(Tag::Link(kind, url, title)) if !safe_url(&url) => {
+                Event::Start(Tag::Link(kind, CowStr::Borrowed(BLOCKED_URL), title))
+            }
+            Event::Start(Tag::Image(kind, url, title)) if !safe_url(&url) => {
+                Event::Start(Tag::Image(kind, CowStr::Borrowed(BLOCKED_URL), title))
+            }
+            event => event,
+        };
+        events.push(event);
+    }
+
+    let mut buffer = String::with_capacity(source.len());
+    html::push_html(&mut buffer, events.into_iter());
+    return buffer;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render, safe_url};
+
+    /// The tags in some rendered HTML.
+    fn tags(html: &str) -> Vec<&str> {
+        html.match_indices('<')
+            .filter_map(|(start, _)| {
+                html[start..]
+                    .find('>')
+                    .map(|end| &html[start..=start + end])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn script_urls_are_blocked() {
+        for url in &[
+            "javascript:alert(1)",
+            "JavaScript:alert(1)",
+            "  javascript:alert(1)",
+            "java\tscript:alert(1)",
+            "java\nscript:alert(1)",
+            "java\u{0}script:alert(1)",
+            "\u{1}javascript:alert(1)",
+            "javascript :alert(1)",
+            "data:text/html;base64,PHNjcmlwdD4=",
+            "vbscript:msgbox(1)",
+        ] {
+            assert!(!safe_url(url), "{:?} is not safe", url);
+        }
+
+        for url in &[
+            "https://rcos.io",
+            "HTTP://rcos.io",
+            "mailto:rcos@rpi.edu",
+            "/projects",
+            "projects?search=a:b",
+            "#top",
+        ] {
+            assert!(safe_url(url), "{:?} is safe", url);
+        }
+    }
+
+    #[test]
+    fn script_links_and_images_point_nowhere() {
+        for source in &[
+            "[click](javascript:alert(1))",
+            "[click](JaVaScRiPt:alert(1))",
+            "[click](<java\tscript:alert(1)>)",
+            "[click](java&#9;script:alert(1))",
+            "[click](&#x6A;avascript:alert(1))",
+            "[click][ref]\n\n[ref]: javascript:alert(1)",
+            "<javascript:alert(1)>",
+        ] {
+            let html: String = render(source);
+            assert_eq!(tags(&html)[1], "<a href=\"#\">", "{:?}", source);
+        }
+
+        let html: String = render("![cat](javascript:alert(1))");
+        assert!(html.contains("<img src=\"#\""), "{}", html);
+        let html: String = render("![cat](<Java\u{b}Script:alert(1)>)");
+        assert!(html.contains("<img src=\"#\""), "{}", html);
+        assert_eq!(
+            render("[rcos](https://rcos.io)"),
+            "<p><a href=\"https://rcos.io\">rcos</a></p>\n"
+        );
+    }
+
+    #[test]
+    fn dropped_tags_lose_their_contents() {
+        // The contents are split across several events by the blank lines
+        // and markdown in them.
+        let html: String =
+            render("before\n\n<script>\nalert(1)\n\n**still script**\n\n</script>\n\nafter");
+        assert!(!html.contains("alert"), "{}", html);
+        assert!(!html.contains("still script"), "{}", html);
+        assert!(
+            html.contains("before") && html.contains("after"),
+            "{}",
+            html
+        );
+
+        let html: String = render("a <iframe src=\"https://evil.example\">*framed*</iframe> b");
+        assert!(
+            !html.contains("iframe") && !html.contains("framed"),
+            "{}",
+            html
+        );
+        assert!(html.contains("a ") && html.contains(" b"), "{}", html);
+
+        let html: String = render("<SCRIPT>alert(1)</SCRIPT>\n\nafter");
+        assert!(
+            !html.contains("alert") && html.contains("after"),
+            "{}",
+            html
+        );
+
+        // An unclosed script removes the rest of the document.
+        let html: String = render("before\n\n<script>\nalert(1)\n\n# heading\n\n- item");
+        assert!(html.contains("before"), "{}", html);
+        assert!(
+            !html.contains("alert") && !html.contains("heading"),
+            "{}",
+            html
+        );
+        assert!(
+            !html.contains("item") && !html.contains("<script"),
+            "{}",
+            html
+        );
+    }
+
+    #[test]
+    fn allowed_tags_lose_their_attributes() {
+        assert_eq!(render("a<br>b"), "<p>a<br>b</p>\n");
+        assert_eq!(render("<kbd>Ctrl</kbd>"), "<p><kbd>Ctrl</kbd></p>\n");
+
+        for source in &[
+            "a<br onload=alert(1)>b",
+            "a<br/onload=alert(1)>b",
+            "a<BR ONLOAD=\"alert(1)\">b",
+            "<kbd style=\"color: red\">x</kbd>",
+            "<details open ontoggle=alert(1)>x</details>",
+        ] {
+            let html: String = render(source);
+            for tag in tags(&html) {
+                assert!(
+                    ["<p>", "</p>", "</kbd>", "</details>"].contains(&tag),
+                    "{:?} rendered {:?}",
+                    source,
+                    html
+                );
+            }
+        }
+
+        // Other tags are removed but keep their contents.
+        assert_eq!(
+            render("<span onclick=\"alert(1)\">hi</span>"),
+            "<p>hi</p>\n"
+        );
+    }
+
+    #[test]
+    fn malformed_tags_are_not_html() {
+        // A stray `<` before a tag is text, and the tag is still read.
+        for source in &[
+            "<<script>alert(1)</script>",
+            "<<script>>alert(1)<</script>",
+            "<</script><script>alert(1)</script>",
+            "<scr<script>ipt>alert(1)</script>",
+        ] {
+            let html: String = render(source);
+            assert!(!html.contains("alert"), "{:?} rendered {:?}", source, html);
+            assert_eq!(tags(&html), vec!["<p>", "</p>"], "{:?}", source);
+        }
+
+        // Tags that are not finished or not tags at all are escaped.
+        for source in &[
+            "<script\n>alert(1)</script>",
+            "<img src=x onerror=alert(1)//",
+            "< script>alert(1)</script>",
+        ] {
+            let html: String = render(source);
+            for tag in tags(&html) {
+                assert!(
+                    ["<p>", "</p>"].contains(&tag),
+                    "{:?} rendered {:?}",
+                    source,
+                    html
+                );
+            }
+        }
+    }
+}
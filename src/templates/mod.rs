@@ -1,19 +1,27 @@
 use crate::app_data::AppData;
 use crate::error::TelescopeError;
 use crate::templates::page::Page;
+use crate::templates::strict::{self, MissingField};
+use crate::web::timing;
 use actix_web::{HttpRequest, HttpResponse, Responder};
 use futures::future::{ready, Ready};
+use handlebars::RenderError;
 use serde::Serialize;
 use serde_json::Value;
 use std::ops::{Index, IndexMut};
 
 pub mod auth;
+pub mod banner;
+pub mod cache;
+pub mod forms;
 pub mod helpers;
 pub mod jumbotron;
+pub mod markdown;
 pub mod navbar;
 pub mod page;
 pub mod pagination;
-pub mod static_pages;
+pub mod pending_actions;
+pub mod strict;
 pub mod tags;
 
 /// A template that can be rendered using the handlebars template registry.
@@ -36,15 +44,120 @@ impl Template {
         }
     }
 
+    /// Set a field of this template. Unlike assigning `json!(value)` to an
+    /// index, this returns an error instead of panicking if the value cannot be
+    /// serialized, like a map with keys that are not strings.
+    pub fn set_field(
+        &mut self,
+        key: &str,
+        value: impl Serialize,
+    ) -> Result<&mut Self, TelescopeError> {
+        self.set_path(&[key], value)
+    }
+
+    /// Set a field nested in objects of this template, like `["data", "title"]`.
+    /// Missing or null objects along the path are made empty objects first.
+    /// Numbers index into arrays, which must already be long enough.
+    /// See [`Template::set_field`].
+    pub fn set_path(
+        &mut self,
+        path: &[&str],
+        value: impl Serialize,
+    ) -> Result<&mut Self, TelescopeError> {
+        let file: &str = self.handlebars_file.as_str();
+        let value: Value = serde_json::to_value(value).map_err(|e| {
+            TelescopeError::ise(format!(
+                "Could not serialize field {} of template {}: {}",
+                path.join("."),
+                file,
+                e
+            ))
+        })?;
+
+        let mut field: &mut Value = &mut self.fields;
+        for (depth, key) in path.iter().enumerate() {
+            if field.is_null() {
+                *field = json!({});
+            }
+            let next: Option<&mut Value> = match field {
+                Value::Object(object) => Some(object.entry(key.to_string()).or_insert(Value::Null)),
+                Value::Array(array) => match key.parse::<usize>() {
+                    Ok(index) => array.get_mut(index),
+                    Err(_) => None,
+                },
+                _ => None,
+            };
+            field = next.ok_or_else(|| {
+                TelescopeError::ise(format!(
+                    "Could not set field {} of template {}: {} has no {}.",
+                    path.join("."),
+                    file,
+                    path[..depth].join("."),
+                    key
+                ))
+            })?;
+        }
+        *field = value;
+        return Ok(self);
+    }
+
+    /// Note an issue with a field of the form this template renders, which the
+    /// field shows under its input. Messages are always strings, so unlike
+    /// [`Template::set_field`] this cannot fail.
+    pub fn set_issue(&mut self, field: &str, issue: impl Into<String>) -> &mut Self {
+        let fields: &mut Value = &mut self.fields;
+        if !fields.is_object() {
+            *fields = json!({});
+        }
+        let issues: &mut Value = &mut fields["issues"];
+        if !issues.is_object() {
+            *issues = json!({});
+        }
+        issues[field] = Value::String(issue.into());
+        return self;
+    }
+
+    /// Set a field of this template and return it, for building templates in
+    /// one expression. See [`Template::set_field`].
+    pub fn try_field(mut self, key: &str, value: impl Serialize) -> Result<Self, TelescopeError> {
+        self.set_field(key, value)?;
+        return Ok(self);
+    }
+
     /// Render this template using the global handlebars registry.
     pub fn render(&self) -> Result<String, TelescopeError> {
-        AppData::global()
-            // Get the global handlebars registry
-            .get_handlebars_registry()
-            // Render this template's file with this template's data
-            .render(self.handlebars_file.as_str(), &self.fields)
-            // Convert any rendering errors that occur.
-            .map_err(TelescopeError::RenderingError)
+        timing::render(|| {
+            let app_data = AppData::global();
+            let file: &str = self.handlebars_file.as_str();
+            let err: RenderError = match app_data
+                // Get the global handlebars registry
+                .get_handlebars_registry()
+                // Render this template's file with this template's data
+                .render(file, &self.fields)
+            {
+                Ok(rendered) => return Ok(rendered),
+                Err(err) => err,
+            };
+
+            // Fields missing in strict mode fail in release builds. Debug
+            // builds note them for the page and render without strict mode.
+            let missing: MissingField = match MissingField::from_error(&err, file) {
+                Some(missing) => missing,
+                None => return Err(TelescopeError::RenderingError(err)),
+            };
+            if !cfg!(debug_assertions) {
+                return Err(TelescopeError::RenderingError(missing.into_error()));
+            }
+            warn!(
+                "Template {} uses {}, which was not set.",
+                missing.template, missing.path
+            );
+            strict::note(missing);
+            return app_data
+                .get_lenient_registry()
+                .render(file, &self.fields)
+                .map_err(TelescopeError::RenderingError);
+        })
     }
 
     /// Render this template as the content of a page.
@@ -100,3 +213,44 @@ impl Responder for Template {
         return ready(result);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Template;
+
+    #[test]
+    fn set_path() {
+        let mut template = Template::new("test");
+        template
+            .set_path(&["data", "title"], "Kickoff")
+            .unwrap()
+            .set_path(&["data", "is_draft"], true)
+            .unwrap();
+        assert_eq!(
+            template.fields,
+            json!({ "data": { "title": "Kickoff", "is_draft": true } })
+        );
+
+        // Fields that are not objects are not replaced.
+        template.set_field("count", 1).unwrap();
+        assert!(template.set_path(&["count", "value"], 2).is_err());
+        assert_eq!(template["count"], json!(1));
+
+        // Arrays are indexed, but not extended.
+        template.set_field("list", json!([{}, {}])).unwrap();
+        template.set_path(&["list", "1", "checked"], true).unwrap();
+        assert_eq!(template["list"], json!([{}, { "checked": true }]));
+        assert!(template.set_path(&["list", "2", "checked"], true).is_err());
+    }
+
+    #[test]
+    fn set_issue() {
+        let mut template = Template::new("test");
+        template.set_issue("title", "Cannot be empty.");
+        template.set_issue("body", format!("Must be at most {} characters.", 10));
+        assert_eq!(
+            template["issues"],
+            json!({ "title": "Cannot be empty.", "body": "Must be at most 10 characters." })
+        );
+    }
+}
@@ -1,12 +1,32 @@
 //! Navbar template constants and functions.
 
+use crate::api::rcos::custom_pages::get::NavigationPages;
 use crate::api::rcos::users::navbar_auth::Authentication;
 use crate::error::TelescopeError;
+use crate::templates::pending_actions::{self, PendingBadge};
 use crate::web::services::auth::identity::{AuthenticationCookie, Identity};
 use actix_web::FromRequest;
 use actix_web::HttpRequest;
+use chrono::{DateTime, Duration, Utc};
+use std::sync::RwLock;
 use uuid::Uuid;
 
+/// How long fetched custom page links are reused.
+const REFRESH_SECONDS: i64 = 60;
+
+lazy_static! {
+    /// The custom page links fetched last, and when they were fetched. Every
+    /// page has a navbar, so this saves a query on each one.
+    static ref CUSTOM_PAGES: RwLock<Option<(DateTime<Utc>, Vec<NavbarLink>)>> =
+        RwLock::new(None);
+}
+
+/// Forget the cached custom page links, so that changes to them show up in
+/// the navbar right away.
+pub fn invalidate_custom_pages() {
+    *CUSTOM_PAGES.write().unwrap() = None;
+}
+
 /// The values used for rendering the navbar template at the top of every page.
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct Navbar {
@@ -20,10 +40,25 @@ pub struct Navbar {
     is_student: bool,
     /// The user ID of the currently signed in user.
     user_id: Option<Uuid>,
+    /// The username of the currently signed in user, if they have set one.
+    username: Option<String>,
     /// If the viewer is creating an account.
     creating_account: bool,
     /// The path of the request to mark a navbar item as active or not.
     req_path: String,
+    /// Coordinator managed pages linked in the navbar.
+    custom_pages: Vec<NavbarLink>,
+    /// Items waiting on coordinators, if the signed in user is one.
+    pending: Option<PendingBadge>,
+}
+
+/// A link to a coordinator managed page in the navbar.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+struct NavbarLink {
+    /// The text of the link.
+    title: String,
+    /// The path the link goes to.
+    path: String,
 }
 
 impl Navbar {
@@ -35,15 +70,49 @@ impl Navbar {
             is_mentor: false,
             is_student: false,
             user_id: None,
+            username: None,
             creating_account: false,
             req_path: "".to_string(),
+            custom_pages: Vec::new(),
+            pending: None,
+        }
+    }
+
+    /// Get the links to coordinator managed pages. This is used while rendering error pages
+    /// too, so failures are logged and an empty list is used rather than returning an error.
+    async fn custom_page_links() -> Vec<NavbarLink> {
+        let now: DateTime<Utc> = Utc::now();
+        if let Some((fetched, links)) = CUSTOM_PAGES.read().unwrap().as_ref() {
+            if now - *fetched < Duration::seconds(REFRESH_SECONDS) {
+                return links.clone();
+            }
+        }
+
+        match NavigationPages::get().await {
+            Ok(pages) => {
+                let links: Vec<NavbarLink> = pages
+                    .into_iter()
+                    .map(|page| NavbarLink {
+                        title: page.title,
+                        path: format!("/p/{}", page.slug),
+                    })
+                    .collect();
+                *CUSTOM_PAGES.write().unwrap() = Some((now, links.clone()));
+                links
+            }
+
+            Err(err) => {
+                warn!("Could not get custom pages for navbar: {}", err);
+                Vec::new()
+            }
         }
     }
 
     /// Create a navbar for a viewer without an account. This is the default navbar.
-    fn userless(request: &HttpRequest) -> Self {
+    async fn userless(request: &HttpRequest) -> Self {
         Navbar {
             req_path: request.path().to_string(),
+            custom_pages: Self::custom_page_links().await,
             // Fill remaining fields from empty navbar.
             ..Self::empty()
         }
@@ -58,7 +127,7 @@ impl Navbar {
         // If the user is authenticated.
         if let Some(authenticated) = identity {
             // Create a navbar instance to modify and return.
-            let mut navbar = Self::userless(request);
+            let mut navbar = Self::userless(request).await;
 
             // Check if there is an authenticated RCOS account
             if let Some(user_id) = authenticated.get_user_id().await? {
@@ -71,6 +140,10 @@ impl Navbar {
                 navbar.is_coordinator = navbar_auth.is_coordinating();
                 navbar.is_mentor = navbar_auth.is_mentoring();
                 navbar.is_student = navbar_auth.is_student();
+                navbar.username = navbar_auth.users_by_pk.and_then(|user| user.username);
+                if navbar.is_coordinator || navbar.is_admin {
+                    navbar.pending = pending_actions::pending_badge().await;
+                }
                 // Return modified navbar.
                 return Ok(navbar);
             } else {
@@ -80,7 +153,7 @@ impl Navbar {
             }
         } else {
             // If the user is not authenticated or there is no user ID, return a default navbar.
-            return Ok(Self::userless(request));
+            return Ok(Self::userless(request).await);
         }
     }
 }
@@ -1,9 +1,25 @@
+use crate::api::rcos::sponsors::get::{current_sponsors::CurrentSponsorsSponsors, CurrentSponsors};
 use crate::error::TelescopeError;
+use crate::templates::banner::{self, BannerMeeting};
+use crate::templates::cache::{self, CachePolicy};
 use crate::templates::navbar::Navbar;
+use crate::templates::strict;
 use crate::templates::tags::Tags;
 use crate::templates::Template;
 use actix_web::{HttpRequest, HttpResponse, Responder};
+use chrono::{DateTime, Duration, Utc};
 use futures::future::{ready, Ready};
+use std::sync::RwLock;
+
+/// How long fetched footer sponsors are reused.
+const SPONSORS_REFRESH_SECONDS: i64 = 60;
+
+lazy_static! {
+    /// The footer sponsors fetched last, and when they were fetched. Every
+    /// page has the footer, so this saves a query on each one.
+    static ref SPONSORS: RwLock<Option<(DateTime<Utc>, Vec<CurrentSponsorsSponsors>)>> =
+        RwLock::new(None);
+}
 
 /// The template for a page shown to the user.
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -23,6 +39,16 @@ pub struct Page {
 
     /// Open Graph Protocol tags.
     pub ogp_tags: Tags,
+
+    /// Currently active sponsors to rotate through in the footer.
+    footer_sponsors: Vec<CurrentSponsorsSponsors>,
+
+    /// Large group meetings and workshops in progress or starting soon.
+    happening_now: Vec<BannerMeeting>,
+
+    /// How to cache the rendered content, if it should be cached.
+    #[serde(skip)]
+    cache: Option<CachePolicy>,
 }
 
 impl Page {
@@ -41,26 +67,74 @@ impl Page {
             content,
             version: env!("CARGO_PKG_VERSION").to_string(),
             ogp_tags: Tags::for_request(request),
+            footer_sponsors: Self::footer_sponsors().await,
+            happening_now: banner::happening_now().await,
+            cache: None,
         })
     }
 
+    /// Get the sponsors to show in the footer. Error pages are rendered using this
+    /// too, so API errors are logged and no sponsors are shown instead.
+    async fn footer_sponsors() -> Vec<CurrentSponsorsSponsors> {
+        let now: DateTime<Utc> = Utc::now();
+        if let Some((fetched, sponsors)) = SPONSORS.read().unwrap().as_ref() {
+            if now - *fetched < Duration::seconds(SPONSORS_REFRESH_SECONDS) {
+                return sponsors.clone();
+            }
+        }
+
+        match CurrentSponsors::get().await {
+            Ok(sponsors) => {
+                *SPONSORS.write().unwrap() = Some((now, sponsors.clone()));
+                sponsors
+            }
+            Err(err) => {
+                warn!("Could not get sponsors for page footer: {}", err);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Reuse the rendered content of an earlier view with the same content
+    /// fields and viewer role. See [`cache`](crate::templates::cache).
+    pub fn cached(mut self, policy: CachePolicy) -> Self {
+        self.cache = Some(policy);
+        return self;
+    }
+
     /// Render the page content and turn the page object into a template object.
     pub fn as_template(&self) -> Result<Template, TelescopeError> {
         // Render the page content.
-        let content_rendered: String = self.content.render()?;
+        let content_rendered: String = match &self.cache {
+            Some(policy) => cache::render(&self.content, policy)?,
+            None => self.content.render()?,
+        };
         // Turn this object into a JSON value.
         let mut template = Template::new(Self::TEMPLATE_PATH);
         // Set the fields of the template to this object.
-        template.fields = json!(&self);
+        template.fields = serde_json::to_value(self).map_err(|e| {
+            TelescopeError::ise(format!("Could not serialize page {}: {}", self.title, e))
+        })?;
         // Replace the content field with the rendered content.
-        template["content"] = json!(content_rendered);
+        template.set_field("content", content_rendered)?;
         // Return the template.
         return Ok(template);
     }
 
     /// Render this page into a string using the handlebars template registry.
+    /// Fields missing from strict templates are listed at the bottom of the
+    /// page in debug builds.
     pub fn render(&self) -> Result<String, TelescopeError> {
-        self.as_template()?.render()
+        let (rendered, missing) = strict::collect(|| self.as_template()?.render());
+        let mut html: String = rendered?;
+        if !missing.is_empty() {
+            let note: String = strict::overlay(&missing);
+            match html.rfind("</body>") {
+                Some(index) => html.insert_str(index, note.as_str()),
+                None => html.push_str(note.as_str()),
+            }
+        }
+        return Ok(html);
     }
 }
 
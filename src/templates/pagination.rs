@@ -1,67 +1,120 @@
 //! Template model for pagination bar.
+//!
+//! Listings read the page number from their path into a
+//! [`PaginationParameter`], query the API for that page, and pass the total
+//! count to [`PaginationInfo::new`] for the fields the
+//! `pagination/pagination_bar` partial renders.
 
-use std::num::NonZeroU64;
+/// How many numbered pages to link on each side of the current page.
+const WINDOW: u64 = 2;
 
-/// Pagination template fields.
+/// Which slice of a listing a page shows.
 #[derive(Serialize, Copy, Clone, Debug)]
-pub struct PaginationInfo {
-    // first field excluded, first page is always page 1
-    /// Flag to include left side ellipsis/separator.
-    left_sep: bool,
+pub struct PaginationParameter {
+    /// How many items come before this page.
+    pub offset: u64,
+    /// How many items are on each page.
+    pub page_size: u64,
+}
 
-    /// Previous page number (current - 1).
-    prev: u64,
+impl PaginationParameter {
+    /// The page with the given number, counting from 1. Missing page numbers
+    /// and 0 are the first page.
+    /// ## Panics:
+    /// - If the `page_size` is 0.
+    pub fn from_page(page: Option<u32>, page_size: u32) -> Self {
+        assert!(page_size > 0, "page_size > 0");
+        let index: u64 = page.unwrap_or(1).max(1) as u64 - 1;
+        PaginationParameter {
+            offset: index * page_size as u64,
+            page_size: page_size as u64,
+        }
+    }
 
-    /// Current page number.
-    current: NonZeroU64,
+    /// The page number counting from 0, for API queries that take one.
+    pub fn page_index(&self) -> u32 {
+        (self.offset / self.page_size) as u32
+    }
 
-    /// Next page number (current + 1).
-    next: u64,
+    /// The page number counting from 1, as shown to users.
+    pub fn page_number(&self) -> u64 {
+        self.offset / self.page_size + 1
+    }
+}
 
-    /// Flag to include right side ellipsis/separator
-    right_sep: bool,
+/// A numbered link in the pagination bar.
+#[derive(Serialize, Clone, Debug)]
+struct PageLink {
+    number: u64,
+    href: String,
+    /// Is this the page being shown?
+    current: bool,
+    /// Are there pages skipped between this link and the one before it?
+    gap_before: bool,
+}
 
-    /// Last page number.
-    last: u64,
+/// Pagination template fields.
+#[derive(Serialize, Clone, Debug)]
+pub struct PaginationInfo {
+    /// Link to the previous page, unless this is the first.
+    prev: Option<String>,
+    /// Link to the next page, unless this is the last.
+    next: Option<String>,
+    /// The first page, the pages around the current one, and the last page.
+    pages: Vec<PageLink>,
 }
 
 impl PaginationInfo {
-    /// Create a new pagination info object if there are enough results to
-    /// require multiple pages.
-    fn new_inner(items: u64, per_page: u64, current_page: NonZeroU64) -> Option<Self> {
-        // Calculate the number of pages required.
-        let pages_required: u64 = items / per_page + 1;
-
-        if pages_required <= 1 {
-            // No pagination if there is only one page.
-            None
-        } else {
-            Some(PaginationInfo {
-                // include left separator if there are pages between
-                left_sep: (current_page.get() - 1) > 2,
-
-                // If we're currently on the first page this will be hidden.
-                prev: current_page.get() - 1,
-
-                current: current_page,
+    /// Create the pagination bar for a listing of `total` items. Page links
+    /// are `prefix` followed by the page number, and then `query_string` after
+    /// a `?` if it is not empty. Return [`Option::None`] if there is only one
+    /// page.
+    pub fn new(
+        total: u64,
+        parameter: PaginationParameter,
+        prefix: &str,
+        query_string: &str,
+    ) -> Option<Self> {
+        // Round up, so a partly filled last page counts.
+        let last: u64 = (total + parameter.page_size - 1) / parameter.page_size;
+        if last <= 1 {
+            return None;
+        }
 
-                // If we're currently on the last page this will be hidden.
-                next: current_page.get() + 1,
+        let current: u64 = parameter.page_number();
+        let href = |number: u64| {
+            if query_string.is_empty() {
+                format!("{}{}", prefix, number)
+            } else {
+                format!("{}{}?{}", prefix, number, query_string)
+            }
+        };
 
-                // similar with right separator,
-                right_sep: current_page.get() + 1 < pages_required - 1,
+        // The first and last pages are always linked, along with the ones
+        // near the current page.
+        let window_start: u64 = current.saturating_sub(WINDOW).max(2);
+        let window_end: u64 = (current + WINDOW).min(last - 1);
+        let numbers = std::iter::once(1)
+            .chain(window_start..=window_end)
+            .chain(std::iter::once(last));
 
-                last: pages_required,
-            })
+        let mut pages: Vec<PageLink> = Vec::new();
+        let mut previous: u64 = 0;
+        for number in numbers {
+            pages.push(PageLink {
+                number,
+                href: href(number),
+                current: number == current,
+                gap_before: number > previous + 1,
+            });
+            previous = number;
         }
-    }
 
-    /// Create a new pagination object with 1 indexed page numbers.
-    /// Return [`Option::None`] if there is only one page.
-    /// ## Panics:
-    /// - If the `current_page` is 0.
-    pub fn new(items: u64, per_page: u64, current_page: u64) -> Option<Self> {
-        let converted = NonZeroU64::new(current_page).expect("current_page > 0");
-        Self::new_inner(items, per_page, converted)
+        return Some(PaginationInfo {
+            // Pages past the end go back to the last one.
+            prev: (current > 1).then(|| href((current - 1).min(last))),
+            next: (current < last).then(|| href(current + 1)),
+            pages,
+        });
     }
 }
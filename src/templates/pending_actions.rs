@@ -0,0 +1,56 @@
+//! Navbar badge of items waiting on coordinators.
+//!
+//! The counts are the same for every coordinator, and every page they load
+//! shows them, so they are fetched at most every half minute and shared
+//! between requests.
+
+use crate::api::rcos::pending_actions::{PendingActions, PendingCounts};
+use chrono::{DateTime, Duration, Utc};
+use std::sync::RwLock;
+
+/// How long fetched counts are reused.
+const REFRESH_SECONDS: i64 = 30;
+
+lazy_static! {
+    /// The counts fetched last, and when they were fetched.
+    static ref CACHE: RwLock<Option<(DateTime<Utc>, PendingCounts)>> = RwLock::new(None);
+}
+
+/// The counts shown in the navbar.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct PendingBadge {
+    #[serde(flatten)]
+    counts: PendingCounts,
+    total: i64,
+}
+
+/// Get the items waiting on coordinators. Error pages are rendered with the
+/// navbar too, so API errors are logged and no badge is shown instead.
+pub async fn pending_badge() -> Option<PendingBadge> {
+    let now: DateTime<Utc> = Utc::now();
+    let cached: Option<PendingCounts> = CACHE
+        .read()
+        .unwrap()
+        .as_ref()
+        .filter(|(fetched, _)| now - *fetched < Duration::seconds(REFRESH_SECONDS))
+        .map(|(_, counts)| *counts);
+
+    let counts: PendingCounts = match cached {
+        Some(counts) => counts,
+        None => match PendingActions::get().await {
+            Ok(counts) => {
+                *CACHE.write().unwrap() = Some((now, counts));
+                counts
+            }
+            Err(err) => {
+                warn!("Could not count pending coordinator actions: {}", err);
+                return None;
+            }
+        },
+    };
+
+    return Some(PendingBadge {
+        total: counts.total(),
+        counts,
+    });
+}
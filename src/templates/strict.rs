@@ -0,0 +1,113 @@
+//! Strict template rendering.
+//!
+//! With `strict_templates` set in the config, a template that uses a field
+//! its handler did not set fails to render instead of printing nothing. In
+//! release builds the page fails with an error naming the template, line, and
+//! field. Debug builds render the template leniently instead, and list what
+//! was missing in a box at the bottom of the page, so one forgotten field does
+//! not stop work on the rest of it.
+//!
+//! Handlebars stops at the first missing field, so only the first one in each
+//! template render is listed.
+
+use handlebars::RenderError;
+use std::cell::RefCell;
+
+/// The end of the messages handlebars uses for missing fields in strict mode.
+const STRICT_SUFFIX: &'static str = "in strict mode";
+
+thread_local! {
+    /// Missing fields noted while rendering a page, if a page is being
+    /// rendered on this thread.
+    static NOTED: RefCell<Option<Vec<MissingField>>> = RefCell::new(None);
+}
+
+/// A field a template used that was not set.
+#[derive(Clone, Debug, Serialize)]
+pub struct MissingField {
+    /// The template the field was used in.
+    pub template: String,
+    pub line: Option<usize>,
+    /// The path of the field, like `project.title`, relative to the block it
+    /// was used in.
+    pub path: String,
+}
+
+impl MissingField {
+    /// Check if a rendering error is from a missing field in strict mode.
+    pub fn from_error(err: &RenderError, template: &str) -> Option<Self> {
+        if !err.desc.ends_with(STRICT_SUFFIX) {
+            return None;
+        }
+
+        // The path is quoted in the message, when handlebars knows it.
+        let path: String = match (err.desc.find('"'), err.desc.rfind('"')) {
+            (Some(start), Some(end)) if start < end => err.desc[start + 1..end].to_string(),
+            _ => "(unknown)".to_string(),
+        };
+
+        return Some(MissingField {
+            template: err
+                .template_name
+                .clone()
+                .unwrap_or_else(|| template.to_string()),
+            line: err.line_no,
+            path,
+        });
+    }
+
+    /// A rendering error that says which field is missing and where.
+    pub fn into_error(self) -> RenderError {
+        let mut err = RenderError::new(format!(
+            "Template `{}` uses `{}`, which was not set",
+            self.template, self.path
+        ));
+        err.template_name = Some(self.template);
+        err.line_no = self.line;
+        return err;
+    }
+}
+
+/// Note a missing field for the page being rendered, if any.
+pub fn note(field: MissingField) {
+    NOTED.with(|noted| {
+        if let Some(list) = noted.borrow_mut().as_mut() {
+            list.push(field);
+        }
+    });
+}
+
+/// Render a page, and return the missing fields noted while rendering it.
+pub fn collect<T>(render: impl FnOnce() -> T) -> (T, Vec<MissingField>) {
+    // Pages are not rendered inside each other, but keep any outer list just
+    // in case.
+    let outer = NOTED.with(|noted| noted.replace(Some(Vec::new())));
+    let output: T = render();
+    let missing = NOTED.with(|noted| noted.replace(outer)).unwrap_or_default();
+    return (output, missing);
+}
+
+/// Render missing fields as a box to put at the end of the page.
+pub fn overlay(missing: &[MissingField]) -> String {
+    let items: String = missing
+        .iter()
+        .map(|field| {
+            format!(
+                "<li><code>{}</code> in <code>{}</code>{}</li>",
+                v_htmlescape::escape(field.path.as_str()),
+                v_htmlescape::escape(field.template.as_str()),
+                field
+                    .line
+                    .map(|line| format!(" line {}", line))
+                    .unwrap_or_default()
+            )
+        })
+        .collect();
+
+    format!(
+        "<aside class=\"alert alert-danger m-3\" aria-label=\"Missing template fields\">\
+         <strong>Strict templates: {} missing fields</strong><ul class=\"mb-0\">{}</ul></aside>",
+        missing.len(),
+        items
+    )
+}
@@ -0,0 +1,293 @@
+//! Automated accessibility checks on rendered HTML.
+//!
+//! This catches the mistakes that are easy to make in handlebars templates
+//! and easy to spot mechanically:
+//!
+//! - images without an `alt` attribute,
+//! - headings that skip a level on the way down (an `h2` followed by an `h4`),
+//! - form controls without a label (a `<label for>`, a wrapping `<label>`,
+//!   `aria-label`, `aria-labelledby`, or `title`).
+//!
+//! It is not a full audit, and it reads HTML with regular expressions rather
+//! than a parser, so it only understands markup as Telescope writes it.
+//! Violations are reported by the [audit middleware] in development, and the
+//! tests check the core templates against sample data in `fixtures/templates`.
+//!
+//! [audit middleware]: crate::web::middlewares::a11y_audit
+
+use regex::{Captures, Regex};
+use std::collections::{HashMap, HashSet};
+
+lazy_static! {
+    /// Script and style elements, which are skipped.
+    static ref SCRIPT_REGEX: Regex = Regex::new(r"(?is)<(script|style)\b.*?</(script|style)\s*>").unwrap();
+    /// HTML comments, which are skipped.
+    static ref COMMENT_REGEX: Regex = Regex::new(r"(?s)<!--.*?-->").unwrap();
+    /// An opening or closing tag.
+    static ref TAG_REGEX: Regex = Regex::new(r"<(/?)([a-zA-Z][a-zA-Z0-9]*)((?:[^>'\x22]|'[^']*'|\x22[^\x22]*\x22)*)>").unwrap();
+    /// An attribute in a tag.
+    static ref ATTRIBUTE_REGEX: Regex = Regex::new(r#"([a-zA-Z_:][-a-zA-Z0-9_:.]*)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'=<>`]+)))?"#).unwrap();
+}
+
+/// A problem found in a page.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Short name of the rule, like "img-alt".
+    pub rule: &'static str,
+    /// What is wrong.
+    pub message: String,
+    /// The line of the rendered HTML it was found on, counting from 1.
+    pub line: usize,
+}
+
+/// A tag read from the HTML.
+struct Tag<'a> {
+    closing: bool,
+    name: String,
+    attributes: HashMap<String, &'a str>,
+    line: usize,
+}
+
+impl Tag<'_> {
+    /// Check if the tag has an attribute with a non-blank value.
+    fn has(&self, attribute: &str) -> bool {
+        self.attributes
+            .get(attribute)
+            .map_or(false, |value| !value.trim().is_empty())
+    }
+}
+
+/// Blank out skipped sections while keeping line numbers.
+fn blank(html: &str, regex: &Regex) -> String {
+    regex
+        .replace_all(html, |captures: &Captures| {
+            "\n".repeat(captures[0].matches('\n').count())
+        })
+        .into_owned()
+}
+
+/// Read every tag in a page.
+fn tags(html: &str) -> Vec<Tag<'_>> {
+    let mut line: usize = 1;
+    let mut last_end: usize = 0;
+
+    TAG_REGEX
+        .captures_iter(html)
+        .map(|captures| {
+            let whole = captures.get(0).unwrap();
+            line += html[last_end..whole.start()].matches('\n').count();
+            last_end = whole.start();
+
+            let attributes = ATTRIBUTE_REGEX
+                .captures_iter(captures.get(3).unwrap().as_str())
+                .map(|attribute| {
+                    let value = attribute
+                        .get(2)
+                        .or(attribute.get(3))
+                        .or(attribute.get(4))
+                        .map_or("", |value| value.as_str());
+                    (attribute[1].to_ascii_lowercase(), value)
+                })
+                .collect();
+
+            Tag {
+                closing: !captures[1].is_empty(),
+                name: captures[2].to_ascii_lowercase(),
+                attributes,
+                line,
+            }
+        })
+        .collect()
+}
+
+/// Check a rendered page.
+pub fn audit(html: &str) -> Vec<Violation> {
+    let html: String = blank(&blank(html, &SCRIPT_REGEX), &COMMENT_REGEX);
+    let tags: Vec<Tag> = tags(html.as_str());
+
+    // Labels can come after the controls they label, so collect them first.
+    let labelled: HashSet<&str> = tags
+        .iter()
+        .filter(|tag| !tag.closing && tag.name == "label")
+        .filter_map(|tag| tag.attributes.get("for").copied())
+        .collect();
+
+    let mut violations: Vec<Violation> = Vec::new();
+    let mut label_depth: usize = 0;
+    let mut last_heading: Option<usize> = None;
+
+    for tag in tags.iter() {
+        match (tag.closing, tag.name.as_str()) {
+            (false, "label") => label_depth += 1,
+            (true, "label") => label_depth = label_depth.saturating_sub(1),
+
+            (false, "img") if !tag.attributes.contains_key("alt") => {
+                violations.push(Violation {
+                    rule: "img-alt",
+                    message: format!(
+                        "Image {} has no alt attribute. Use alt=\"\" for decorative images.",
+                        tag.attributes.get("src").copied().unwrap_or("(no src)")
+                    ),
+                    line: tag.line,
+                });
+            }
+
+            (false, name)
+                if name.len() == 2
+                    && name.starts_with('h')
+                    && matches!(name.as_bytes()[1], b'1'..=b'6') =>
+            {
+                let level: usize = (name.as_bytes()[1] - b'0') as usize;
+                if let Some(previous) = last_heading {
+                    if level > previous + 1 {
+                        violations.push(Violation {
+                            rule: "heading-order",
+                            message: format!(
+                                "Heading h{} follows h{}, skipping a level.",
+                                level, previous
+                            ),
+                            line: tag.line,
+                        });
+                    }
+                }
+                last_heading = Some(level);
+            }
+
+            (false, "input") | (false, "select") | (false, "textarea") => {
+                let input_type: &str = tag.attributes.get("type").copied().unwrap_or("text");
+                let needs_label: bool = tag.name != "input"
+                    || !["hidden", "submit", "button", "reset", "image"]
+                        .contains(&input_type.to_ascii_lowercase().as_str());
+                let has_label: bool = label_depth > 0
+                    || tag.has("aria-label")
+                    || tag.has("aria-labelledby")
+                    || tag.has("title")
+                    || tag
+                        .attributes
+                        .get("id")
+                        .map_or(false, |id| labelled.contains(id));
+
+                if needs_label && !has_label {
+                    violations.push(Violation {
+                        rule: "form-label",
+                        message: format!(
+                            "Form control {} has no label.",
+                            tag.attributes
+                                .get("name")
+                                .or(tag.attributes.get("id"))
+                                .copied()
+                                .unwrap_or(tag.name.as_str())
+                        ),
+                        line: tag.line,
+                    });
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    return violations;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{audit, Violation};
+    use crate::templates::Template;
+    use std::fs;
+    use std::path::PathBuf;
+
+    /// The directory of template samples.
+    const SAMPLES_DIR: &'static str = "fixtures/templates";
+
+    /// Sample data for a template, read from the samples directory.
+    #[derive(Deserialize, Debug)]
+    struct Sample {
+        /// The template to render, from the templates directory.
+        template: String,
+        /// The fields to render it with.
+        fields: serde_json::Value,
+    }
+
+    /// The rules broken by a snippet of HTML.
+    fn rules(html: &str) -> Vec<&'static str> {
+        audit(html)
+            .into_iter()
+            .map(|violation| violation.rule)
+            .collect()
+    }
+
+    #[test]
+    fn image_alt_text() {
+        assert_eq!(rules(r#"<img src="logo.png">"#), vec!["img-alt"]);
+        assert!(rules(r#"<img src="logo.png" alt="">"#).is_empty());
+    }
+
+    #[test]
+    fn heading_order() {
+        assert_eq!(rules("<h2>A</h2>\n<h4>B</h4>"), vec!["heading-order"]);
+        assert!(rules("<h2>A</h2><h3>B</h3><h2>C</h2>").is_empty());
+    }
+
+    #[test]
+    fn form_labels() {
+        assert_eq!(rules(r#"<input name="q">"#), vec!["form-label"]);
+        assert!(rules(r#"<label for="q">Search</label><input id="q">"#).is_empty());
+        assert!(rules(r#"<label>Search <input name="q"></label>"#).is_empty());
+        assert!(rules(r#"<select aria-label="Kind"></select>"#).is_empty());
+        assert!(rules(r#"<input type="hidden" name="csrf_token">"#).is_empty());
+    }
+
+    #[test]
+    fn skips_scripts_and_comments() {
+        assert!(rules("<script>let x = '<img>';</script><!-- <img> -->").is_empty());
+    }
+
+    #[test]
+    fn reports_lines() {
+        let violations: Vec<Violation> = audit("<p>\n</p>\n<img src=\"a.png\">");
+        assert_eq!(violations[0].line, 3);
+    }
+
+    /// Render every template in the samples directory and check it.
+    #[test]
+    fn core_templates() {
+        let mut paths: Vec<PathBuf> = fs::read_dir(SAMPLES_DIR)
+            .expect("Could not read the template samples")
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().map_or(false, |ext| ext == "json"))
+            .collect();
+        paths.sort();
+        assert!(!paths.is_empty(), "No template samples in {}", SAMPLES_DIR);
+
+        let mut problems: Vec<String> = Vec::new();
+        for path in paths.iter() {
+            let contents: String = fs::read_to_string(path).expect("Could not read sample");
+            let sample: Sample = serde_json::from_str(contents.as_str())
+                .unwrap_or_else(|e| panic!("Malformed sample {}: {}", path.display(), e));
+            let rendered: String = Template {
+                handlebars_file: sample.template.clone(),
+                fields: sample.fields,
+            }
+            .render()
+            .unwrap_or_else(|e| panic!("Could not render {}: {}", sample.template, e));
+
+            for violation in audit(rendered.as_str()) {
+                problems.push(format!(
+                    "{} (line {} rendered from {}): [{}] {}",
+                    sample.template,
+                    violation.line,
+                    path.display(),
+                    violation.rule,
+                    violation.message
+                ));
+            }
+        }
+
+        assert!(
+            problems.is_empty(),
+            "Accessibility violations:\n{}",
+            problems.join("\n")
+        );
+    }
+}
@@ -0,0 +1,371 @@
+//! Keys and rate limits for the public JSON API.
+//!
+//! Callers without a key are limited by IP address at the anonymous tier.
+//! That is the address of the connection, unless it is one of the
+//! `trusted_proxies`, which can say who they forwarded the request for.
+//! Key holders send their key in the `X-API-Key` header and are limited per
+//! key, at the tier coordinators gave the key on the API keys admin page.
+//! Requests are counted in one minute windows in the
+//! [session store](crate::web::sessions), so with a shared store every replica
+//! sees the same counts.
+
+use crate::api::rcos::api_keys::ApiKeyByHash;
+use crate::env::{global_config, ApiRateLimits};
+use crate::error::TelescopeError;
+use crate::web::sessions;
+use actix_web::dev::ServiceRequest;
+use actix_web::http::header::{HeaderName, FORWARDED};
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use ipnet::IpNet;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use std::net::{IpAddr, SocketAddr};
+
+/// The header key holders send their key in.
+pub const HEADER_NAME: &'static str = "X-API-Key";
+
+/// Every key starts with this, so leaked keys are easy to search for.
+const KEY_PREFIX: &'static str = "tsk_";
+
+/// How many random characters follow the prefix of a key.
+const KEY_LENGTH: usize = 40;
+
+/// How many characters of a key are stored to tell keys apart.
+const SHOWN_LENGTH: usize = 10;
+
+/// How many seconds each count of requests covers.
+const WINDOW_SECONDS: i64 = 60;
+
+/// How many seconds looked up keys are reused before being looked up again.
+/// Revoking a key or changing its tier clears this on the replica that made
+/// the change.
+const CACHE_SECONDS: i64 = 60;
+
+lazy_static! {
+    /// Recently looked up keys by hash, with their ID and tier.
+    static ref KEY_CACHE: DashMap<String, (DateTime<Utc>, i64, RateTier)> = DashMap::new();
+}
+
+/// How many requests a caller can make.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateTier {
+    /// Callers without a key.
+    Anonymous,
+    /// The tier new keys are given.
+    Standard,
+    /// Keys coordinators have granted more requests.
+    Elevated,
+}
+
+impl RateTier {
+    /// The tiers keys can be given.
+    pub const KEY_TIERS: [RateTier; 2] = [RateTier::Standard, RateTier::Elevated];
+
+    /// The name of this tier, as stored with keys.
+    pub fn name(self) -> &'static str {
+        match self {
+            RateTier::Anonymous => "anonymous",
+            RateTier::Standard => "standard",
+            RateTier::Elevated => "elevated",
+        }
+    }
+
+    /// Parse the name of a tier keys can be given.
+    pub fn parse_key_tier(name: &str) -> Option<Self> {
+        RateTier::KEY_TIERS
+            .iter()
+            .copied()
+            .find(|tier| tier.name() == name)
+    }
+
+    /// How many requests per minute this tier allows.
+    pub fn limit(self, limits: &ApiRateLimits) -> u64 {
+        match self {
+            RateTier::Anonymous => limits.anonymous,
+            RateTier::Standard => limits.standard,
+            RateTier::Elevated => limits.elevated,
+        }
+    }
+}
+
+/// Who is calling the API.
+#[derive(Clone, Debug)]
+pub enum Caller {
+    /// A caller without a key, by IP address.
+    Anonymous(String),
+    /// A key holder.
+    Key { key_id: i64, tier: RateTier },
+}
+
+impl Caller {
+    /// The tier of this caller.
+    pub fn tier(&self) -> RateTier {
+        match self {
+            Caller::Anonymous(_) => RateTier::Anonymous,
+            Caller::Key { tier, .. } => *tier,
+        }
+    }
+
+    /// What this caller's requests are counted under.
+    fn bucket(&self) -> String {
+        match self {
+            Caller::Anonymous(ip) => format!("ip:{}", ip),
+            Caller::Key { key_id, .. } => format!("key:{}", key_id),
+        }
+    }
+}
+
+/// Read an address from a `Forwarded` or `X-Forwarded-For` header. These may
+/// have a port, and IPv6 addresses may be in brackets.
+fn parse_hop(hop: &str) -> Option<IpAddr> {
+    let hop: &str = hop.trim().trim_matches('"');
+    hop.parse::<IpAddr>()
+        .or_else(|_| hop.parse::<SocketAddr>().map(|addr| addr.ip()))
+        .or_else(|_| hop.trim_start_matches('[').trim_end_matches(']').parse())
+        .ok()
+}
+
+/// The addresses a request was forwarded for, from the client to the last
+/// proxy. The `Forwarded` header is used if there is one.
+fn forwarded_hops(req: &ServiceRequest) -> Vec<String> {
+    let headers = req.headers();
+    let values = |name: &HeaderName| -> Vec<String> {
+        headers
+            .get_all(name)
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::to_string)
+            .collect()
+    };
+
+    let forwarded: Vec<String> = values(&FORWARDED);
+    if forwarded.is_empty() {
+        return values(&HeaderName::from_static("x-forwarded-for"));
+    }
+    return forwarded
+        .iter()
+        .map(|element| {
+            element
+                .split(';')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("for"))
+                .map(|(_, value)| value.to_string())
+                // Keep elements without an address so they are not skipped.
+                .unwrap_or_default()
+        })
+        .collect();
+}
+
+/// Find the client from the address of the connection and the addresses it
+/// says the request was forwarded for. Forwarded addresses are read from the
+/// last one back only while the request is still coming from a trusted proxy,
+/// so a client cannot choose its own address by sending the headers itself.
+/// Connections on the Unix socket (with no peer address) come from a proxy on
+/// the same machine and are trusted.
+fn resolve_client(peer: Option<IpAddr>, hops: &[String], trusted: &[IpNet]) -> Option<IpAddr> {
+    let mut client: Option<IpAddr> = peer;
+    for hop in hops.iter().rev() {
+        if let Some(ip) = client {
+            if !trusted.iter().any(|net| net.contains(&ip)) {
+                break;
+            }
+        }
+        match parse_hop(hop) {
+            Some(ip) => client = Some(ip),
+            None => break,
+        }
+    }
+    return client;
+}
+
+/// The IP address anonymous requests are counted under.
+pub fn client_ip(req: &ServiceRequest) -> String {
+    let peer: Option<IpAddr> = req.peer_addr().map(|addr| addr.ip());
+    let hops: Vec<String> = forwarded_hops(req);
+    resolve_client(peer, hops.as_slice(), &global_config().trusted_proxies)
+        .map(|ip| ip.to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Generate a new key.
+pub fn new_key() -> String {
+    let random: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(KEY_LENGTH)
+        .map(char::from)
+        .collect();
+    return format!("{}{}", KEY_PREFIX, random);
+}
+
+/// The start of a key, which is stored to tell keys apart.
+pub fn shown_prefix(key: &str) -> String {
+    key.chars().take(SHOWN_LENGTH).collect()
+}
+
+/// The hash of a key, which is stored instead of the key.
+pub fn hash(key: &str) -> String {
+    Sha256::digest(key.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Look up the ID and tier of a key. Return `None` if the key is unknown or
+/// revoked.
+pub async fn lookup(key: &str) -> Result<Option<(i64, RateTier)>, TelescopeError> {
+    let key_hash: String = hash(key);
+    if let Some(cached) = KEY_CACHE.get(&key_hash) {
+        let (looked_up, key_id, tier) = *cached.value();
+        if Utc::now() - looked_up < Duration::seconds(CACHE_SECONDS) {
+            return Ok(Some((key_id, tier)));
+        }
+    }
+
+    // Only keys that exist are cached, so made up keys cannot fill the cache.
+    let found: Option<(i64, RateTier)> = match ApiKeyByHash::get(key_hash.clone()).await? {
+        Some((key_id, tier)) => {
+            let tier: RateTier = RateTier::parse_key_tier(tier.as_str()).ok_or_else(|| {
+                TelescopeError::ise(format!("API key {} has unknown tier {}.", key_id, tier))
+            })?;
+            Some((key_id, tier))
+        }
+        None => None,
+    };
+
+    match found {
+        Some((key_id, tier)) => {
+            KEY_CACHE.insert(key_hash, (Utc::now(), key_id, tier));
+        }
+        None => {
+            KEY_CACHE.remove(&key_hash);
+        }
+    }
+    return Ok(found);
+}
+
+/// Forget looked up keys, after one is revoked or changes tier.
+pub fn forget_keys() {
+    KEY_CACHE.clear();
+}
+
+/// A caller's count of requests in the current window.
+#[derive(Copy, Clone, Debug)]
+pub struct RateCount {
+    /// How many requests the caller can make in a window.
+    pub limit: u64,
+    /// How many requests the caller has made in this window, including the
+    /// one being counted.
+    pub count: u64,
+    /// Seconds until the next window starts.
+    pub reset_after: i64,
+}
+
+impl RateCount {
+    /// Is the request within the caller's limit?
+    pub fn allowed(&self) -> bool {
+        self.count <= self.limit
+    }
+
+    /// How many more requests the caller can make in this window.
+    pub fn remaining(&self) -> u64 {
+        self.limit.saturating_sub(self.count)
+    }
+}
+
+/// Count a request from a caller.
+pub async fn count(caller: &Caller) -> Result<RateCount, TelescopeError> {
+    let now: i64 = Utc::now().timestamp();
+    let window: i64 = now / WINDOW_SECONDS;
+    let store_key: String = format!("ratelimit:{}:{}", caller.bucket(), window);
+    let count: u64 = sessions::store()
+        .increment(store_key.as_str(), Duration::seconds(WINDOW_SECONDS))
+        .await?;
+
+    return Ok(RateCount {
+        limit: caller.tier().limit(&global_config().api_rate_limits),
+        count,
+        reset_after: (window + 1) * WINDOW_SECONDS - now,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hash, new_key, resolve_client, shown_prefix, RateTier};
+    use ipnet::IpNet;
+    use std::net::IpAddr;
+
+    #[test]
+    fn keys_are_hashed() {
+        let key: String = new_key();
+        assert!(key.starts_with("tsk_"));
+        assert_eq!(hash(key.as_str()), hash(key.as_str()));
+        assert_ne!(hash(key.as_str()), hash(new_key().as_str()));
+        // Only the start of the key is kept.
+        assert!(key.starts_with(shown_prefix(key.as_str()).as_str()));
+        assert!(shown_prefix(key.as_str()).len() < key.len() / 2);
+    }
+
+    #[test]
+    fn key_tiers() {
+        assert_eq!(
+            RateTier::parse_key_tier("standard"),
+            Some(RateTier::Standard)
+        );
+        assert_eq!(
+            RateTier::parse_key_tier("elevated"),
+            Some(RateTier::Elevated)
+        );
+        // Keys cannot be anonymous.
+        assert_eq!(RateTier::parse_key_tier("anonymous"), None);
+    }
+
+    #[test]
+    fn forwarded_addresses_need_a_trusted_proxy() {
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+        let hops = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let trusted: Vec<IpNet> = vec!["10.0.0.0/8".parse().unwrap()];
+        let proxy: Option<IpAddr> = Some(ip("10.1.2.3"));
+
+        // Clients connecting directly cannot pick their address.
+        assert_eq!(
+            resolve_client(Some(ip("203.0.113.9")), &hops(&["198.51.100.1"]), &trusted),
+            Some(ip("203.0.113.9"))
+        );
+        assert_eq!(
+            resolve_client(Some(ip("203.0.113.9")), &hops(&["198.51.100.1"]), &[]),
+            Some(ip("203.0.113.9"))
+        );
+        // A trusted proxy says who it forwarded for.
+        assert_eq!(
+            resolve_client(proxy, &hops(&["203.0.113.9"]), &trusted),
+            Some(ip("203.0.113.9"))
+        );
+        // Addresses the client added before the proxy's are ignored.
+        assert_eq!(
+            resolve_client(proxy, &hops(&["198.51.100.1", " 203.0.113.9"]), &trusted),
+            Some(ip("203.0.113.9"))
+        );
+        // Chains of trusted proxies are followed, with ports and brackets.
+        assert_eq!(
+            resolve_client(
+                proxy,
+                &hops(&["\"[2001:db8::1]:4711\"", "10.9.9.9:80"]),
+                &trusted
+            ),
+            Some(ip("2001:db8::1"))
+        );
+        // Unreadable addresses stop the search at the last known one.
+        assert_eq!(
+            resolve_client(proxy, &hops(&["203.0.113.9", "unknown"]), &trusted),
+            proxy
+        );
+        // The Unix socket is trusted.
+        assert_eq!(
+            resolve_client(None, &hops(&["203.0.113.9"]), &[]),
+            Some(ip("203.0.113.9"))
+        );
+    }
+}
@@ -0,0 +1,327 @@
+//! Cluster mode, for running several replicas of Telescope behind a load
+//! balancer.
+//!
+//! Most state is already outside the process: the RCOS database holds
+//! everything durable, and short-lived session state goes through the
+//! [session store](crate::web::sessions). What is left is handled here:
+//!
+//! - Changes other replicas need to hear about (live page events, cache
+//!   invalidations, and work for the Discord bot) are [broadcast] over an
+//!   [`EventBus`]. Outside cluster mode they are delivered straight to this
+//!   process; in cluster mode they go through Redis pub/sub to every replica,
+//!   including this one.
+//! - Scheduled jobs and the Discord bot must only run once. In cluster mode
+//!   the replicas elect a leader with a lease in Redis, and only the leader
+//!   starts them. A leader that loses its lease exits so that its jobs cannot
+//!   run twice; the process manager restarts it as a follower.
+//!
+//! Cluster mode is turned on with `cluster_mode = true` in the config, and
+//! needs the Redis session store. Replicas also need to share the uploads
+//! directories (for example over a network file system).
+
+use crate::discord_bot::{self, DiscordMessage};
+use crate::env::{global_config, SessionStoreConfig};
+use crate::error::TelescopeError;
+use crate::logging::{self, LevelOverride};
+use crate::templates::cache::{self as render_cache, Dependency};
+use crate::web::live;
+use crate::web::policies;
+use crate::web::services::leaderboard;
+use actix::clock::delay_for;
+use actix::fut::wrap_future;
+use actix::{Actor, ActorFuture, AsyncContext, Context};
+use futures::StreamExt;
+use redis::{Client, RedisError, Script};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// How long the leader's lease lasts without being renewed.
+const LEASE: Duration = Duration::from_secs(30);
+
+/// How often replicas try to take or renew the lease.
+const LEASE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long to wait before subscribing to the event bus again after the
+/// connection drops.
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+
+/// Takes the lease if nobody holds it, or renews it if this replica does.
+/// Returns 1 if this replica holds the lease afterwards.
+const LEASE_SCRIPT: &'static str = r"
+local holder = redis.call('GET', KEYS[1])
+if holder == false then
+    redis.call('SET', KEYS[1], ARGV[1], 'PX', ARGV[2])
+    return 1
+end
+if holder == ARGV[1] then
+    redis.call('PEXPIRE', KEYS[1], ARGV[2])
+    return 1
+end
+return 0
+";
+
+lazy_static! {
+    /// Identifies this replica in the leader lease.
+    static ref REPLICA_ID: String = Uuid::new_v4().to_string();
+
+    /// The configured event bus.
+    static ref BUS: Box<dyn EventBus> = match redis_config() {
+        Some((client, prefix)) => Box::new(RedisBus {
+            client,
+            channel: format!("{}events", prefix),
+        }),
+        None => Box::new(LocalBus),
+    };
+}
+
+/// A change that every replica needs to hear about.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Broadcast {
+    /// An event for browsers subscribed to a live topic.
+    Live { topic: String, event: String },
+    /// Leaderboard standings need to be recomputed.
+    LeaderboardChanged,
+    /// A new policy version was published.
+    PoliciesChanged,
+    /// Data that cached page content was rendered from changed.
+    RenderCacheInvalidated { dependency: Dependency },
+    /// Work for the Discord bot, which only runs on the leader.
+    Discord(DiscordMessage),
+    /// Log levels set for modules at runtime changed.
+    LogLevelsChanged { overrides: Vec<LevelOverride> },
+}
+
+impl Broadcast {
+    /// Act on a broadcast in this process.
+    fn deliver(self) {
+        match self {
+            Broadcast::Live { topic, event } => live::deliver(topic, event),
+            Broadcast::LeaderboardChanged => leaderboard::clear_cache(),
+            Broadcast::PoliciesChanged => policies::clear(),
+            Broadcast::RenderCacheInvalidated { dependency } => render_cache::clear(dependency),
+            Broadcast::Discord(message) => discord_bot::receive(message),
+            Broadcast::LogLevelsChanged { overrides } => logging::set_overrides(overrides),
+        }
+    }
+}
+
+/// Carries broadcasts to every replica.
+#[async_trait::async_trait]
+pub trait EventBus: Send + Sync {
+    /// Send a broadcast to every replica.
+    async fn publish(&self, message: Broadcast) -> Result<(), TelescopeError>;
+
+    /// Receive broadcasts from other replicas and deliver them here. Runs
+    /// until the server stops.
+    async fn listen(&self);
+}
+
+/// The event bus when there is only one replica.
+struct LocalBus;
+
+#[async_trait::async_trait]
+impl EventBus for LocalBus {
+    async fn publish(&self, message: Broadcast) -> Result<(), TelescopeError> {
+        message.deliver();
+        return Ok(());
+    }
+
+    async fn listen(&self) {}
+}
+
+/// An event bus over Redis pub/sub.
+struct RedisBus {
+    client: Client,
+    /// The channel broadcasts are published on.
+    channel: String,
+}
+
+/// Convert a Redis error into a Telescope error.
+fn redis_error(err: RedisError) -> TelescopeError {
+    TelescopeError::ise(format!("Could not reach Redis: {}", err))
+}
+
+impl RedisBus {
+    /// Subscribe to the channel and deliver broadcasts until the connection
+    /// drops.
+    async fn subscribe(&self) -> Result<(), RedisError> {
+        let mut pubsub = self.client.get_async_connection().await?.into_pubsub();
+        pubsub.subscribe(self.channel.as_str()).await?;
+
+        // Broadcasts may have been missed while disconnected, so drop the
+        // caches they would have invalidated.
+        leaderboard::clear_cache();
+        policies::clear();
+        render_cache::clear_all();
+
+        let mut messages = Box::pin(pubsub.on_message());
+        while let Some(message) = messages.next().await {
+            let payload: String = message.get_payload()?;
+            match serde_json::from_str::<Broadcast>(payload.as_str()) {
+                Ok(broadcast) => broadcast.deliver(),
+                Err(e) => warn!("Ignoring malformed broadcast {:?}: {}", payload, e),
+            }
+        }
+        return Ok(());
+    }
+}
+
+#[async_trait::async_trait]
+impl EventBus for RedisBus {
+    async fn publish(&self, message: Broadcast) -> Result<(), TelescopeError> {
+        let payload: String = serde_json::to_string(&message)
+            .map_err(|e| TelescopeError::ise(format!("Could not serialize broadcast: {}", e)))?;
+        let mut connection = self
+            .client
+            .get_async_connection()
+            .await
+            .map_err(redis_error)?;
+
+        redis::cmd("PUBLISH")
+            .arg(self.channel.as_str())
+            .arg(payload)
+            .query_async::<_, i64>(&mut connection)
+            .await
+            .map_err(redis_error)?;
+        return Ok(());
+    }
+
+    async fn listen(&self) {
+        loop {
+            match self.subscribe().await {
+                Ok(()) => warn!("Event bus connection closed. Subscribing again."),
+                Err(e) => error!("Event bus connection failed: {}", e),
+            }
+            delay_for(RESUBSCRIBE_DELAY).await;
+        }
+    }
+}
+
+/// The Redis client and key prefix to coordinate replicas with, in cluster
+/// mode.
+fn redis_config() -> Option<(Client, String)> {
+    let config = global_config();
+    if !config.cluster_mode {
+        return None;
+    }
+
+    match &config.session_store {
+        SessionStoreConfig::Redis { url, prefix } => {
+            let client: Client = Client::open(url.as_str())
+                .unwrap_or_else(|e| panic!("Invalid session store URL: {}", e));
+            Some((client, prefix.clone()))
+        }
+        SessionStoreConfig::Memory => {
+            panic!("Cluster mode needs a shared session store. Set [session_store] to Redis.")
+        }
+    }
+}
+
+/// Check the cluster config. Panics if cluster mode is on without a shared
+/// session store.
+pub fn init() {
+    if redis_config().is_some() {
+        info!("Running in cluster mode as replica {}.", *REPLICA_ID);
+    }
+}
+
+/// Tell every replica about a change. Delivery happens in the background.
+pub fn broadcast(message: Broadcast) {
+    actix_web::rt::spawn(async move {
+        if let Err(e) = BUS.publish(message).await {
+            error!("Could not broadcast a change to other replicas: {}", e);
+        }
+    });
+}
+
+/// Receive broadcasts from other replicas until the server stops.
+pub async fn listen() {
+    BUS.listen().await;
+}
+
+/// Start the jobs that must only run on one replica. Outside cluster mode
+/// they start now; in cluster mode they start once this replica is elected
+/// leader.
+pub fn start_singletons(start: fn()) {
+    match redis_config() {
+        Some((client, prefix)) => {
+            Leadership {
+                client,
+                key: format!("{}leader", prefix),
+                start,
+                renewed: None,
+            }
+            .start();
+        }
+        None => start(),
+    }
+}
+
+/// Actor that takes and keeps the leader lease.
+struct Leadership {
+    client: Client,
+    /// The Redis key of the lease.
+    key: String,
+    /// Starts the singleton jobs once elected.
+    start: fn(),
+    /// When the lease was last renewed, if this replica is the leader.
+    renewed: Option<Instant>,
+}
+
+impl Leadership {
+    /// Try to take or renew the lease. Return whether this replica holds it.
+    async fn claim(client: Client, key: String) -> Result<bool, RedisError> {
+        let mut connection = client.get_async_connection().await?;
+        let held: i64 = Script::new(LEASE_SCRIPT)
+            .key(key)
+            .arg(REPLICA_ID.as_str())
+            .arg(LEASE.as_millis() as u64)
+            .invoke_async(&mut connection)
+            .await?;
+        return Ok(held == 1);
+    }
+
+    /// Claim the lease in the background.
+    fn try_claim(&self, ctx: &mut Context<Self>) {
+        let claim = Leadership::claim(self.client.clone(), self.key.clone());
+        ctx.spawn(
+            wrap_future::<_, Self>(claim).map(|result, leadership, _| leadership.claimed(result)),
+        );
+    }
+
+    /// Act on the result of a claim.
+    fn claimed(&mut self, result: Result<bool, RedisError>) {
+        match (result, self.renewed) {
+            (Ok(true), Some(_)) => self.renewed = Some(Instant::now()),
+            (Ok(true), None) => {
+                info!("Replica {} elected leader.", *REPLICA_ID);
+                self.renewed = Some(Instant::now());
+                (self.start)();
+            }
+            (Ok(false), Some(_)) => {
+                error!("Another replica took the leader lease. Exiting so jobs do not run twice.");
+                std::process::exit(1);
+            }
+            (Ok(false), None) => {}
+            (Err(e), renewed) => {
+                error!("Could not claim the leader lease: {}", e);
+                if renewed.map_or(false, |renewed| renewed.elapsed() >= LEASE) {
+                    error!("The leader lease expired. Exiting so jobs do not run twice.");
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+}
+
+impl Actor for Leadership {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("Leader Election Started");
+
+        self.try_claim(ctx);
+        ctx.run_interval(LEASE_INTERVAL, |leadership, ctx| leadership.try_claim(ctx));
+    }
+}
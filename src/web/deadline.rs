@@ -0,0 +1,61 @@
+//! Deadlines for requests, passed down to the calls they make to upstream
+//! services.
+//!
+//! The [deadline middleware] gives each request a deadline by its route class
+//! and runs the handler with it set. Clients from [`http::client`] time out
+//! when it passes, and Discord calls go through [`within`]. Either way the
+//! request fails with a 504 page once its time is up, so one slow upstream
+//! cannot hold a worker for long. Work outside of a request, like scheduled
+//! jobs and the Discord bot, has no deadline.
+//!
+//! [deadline middleware]: crate::web::middlewares::deadline
+//! [`http::client`]: crate::api::http::client
+
+use crate::error::TelescopeError;
+use crate::web::timing;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tokio::time::timeout;
+
+tokio::task_local! {
+    /// The deadline of the request this task is handling.
+    static DEADLINE: Instant;
+}
+
+/// When a request has to be done with its upstream calls. This is also put in
+/// the request extensions.
+#[derive(Copy, Clone, Debug)]
+pub struct Deadline(pub Instant);
+
+impl Deadline {
+    /// The time left before the deadline. Zero once it has passed.
+    pub fn remaining(&self) -> Duration {
+        self.0.saturating_duration_since(Instant::now())
+    }
+
+    /// Run a future with this deadline set.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        DEADLINE.scope(self.0, future).await
+    }
+}
+
+/// The deadline of the request being handled, if there is one.
+pub fn current() -> Option<Deadline> {
+    DEADLINE.try_with(|deadline| Deadline(*deadline)).ok()
+}
+
+/// Run a call to an upstream service with only the time left before the
+/// deadline. Calls outside of a request are not limited.
+pub async fn within<F: Future>(upstream: &str, call: F) -> Result<F::Output, TelescopeError> {
+    let remaining: Duration = match current() {
+        Some(deadline) => deadline.remaining(),
+        None => return Ok(call.await),
+    };
+
+    if remaining == Duration::ZERO {
+        return Err(TelescopeError::deadline_exceeded(upstream));
+    }
+    return timing::upstream(timeout(remaining, call))
+        .await
+        .map_err(|_| TelescopeError::deadline_exceeded(upstream));
+}
@@ -0,0 +1,124 @@
+//! Embedded video players for meeting recordings.
+//!
+//! Recording links are parsed into a provider and video ID on the server so
+//! that only known players are ever embedded. Players use each provider's
+//! privacy-enhanced mode, so no tracking cookies are set until the viewer
+//! starts playback. Links to other hosts are shown as plain links instead.
+
+use regex::Regex;
+use url::Url;
+
+lazy_static! {
+    /// YouTube video IDs are 11 URL-safe base64 characters.
+    static ref YOUTUBE_ID: Regex = Regex::new(r"^[A-Za-z0-9_-]{11}$").unwrap();
+
+    /// Vimeo video IDs are numeric.
+    static ref VIMEO_ID: Regex = Regex::new(r"^[0-9]+$").unwrap();
+
+    /// Vimeo privacy hashes for unlisted videos are hexadecimal.
+    static ref VIMEO_HASH: Regex = Regex::new(r"^[0-9a-f]+$").unwrap();
+}
+
+/// Video hosts with embeddable players.
+#[derive(Copy, Clone, Debug, Serialize, Eq, PartialEq)]
+pub enum Provider {
+    YouTube,
+    Vimeo,
+}
+
+/// An embeddable video player.
+#[derive(Clone, Debug, Serialize)]
+pub struct Embed {
+    /// The video host.
+    pub provider: Provider,
+    /// The ID of the video on the host.
+    pub video_id: String,
+    /// The URL to use as the player iframe source.
+    pub player_url: String,
+}
+
+impl Embed {
+    /// Parse a video link into an embeddable player. Return `None` if the link
+    /// is not to a video on a supported host.
+    pub fn parse(link: &str) -> Option<Self> {
+        let url: Url = Url::parse(link.trim()).ok()?;
+        let host: String = url.host_str()?.trim_start_matches("www.").to_lowercase();
+        match host.as_str() {
+            "youtube.com" | "m.youtube.com" | "youtu.be" | "youtube-nocookie.com" => {
+                Self::youtube(&url)
+            }
+            "vimeo.com" | "player.vimeo.com" => Self::vimeo(&url),
+            _ => None,
+        }
+    }
+
+    /// Parse a YouTube link. Handles watch pages, short links, live streams,
+    /// shorts, and existing embed links.
+    fn youtube(url: &Url) -> Option<Self> {
+        let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+        let video_id: String = match segments.as_slice() {
+            ["watch"] => url
+                .query_pairs()
+                .find(|(key, _)| key == "v")
+                .map(|(_, value)| value.into_owned())?,
+            [id] if url.host_str()? == "youtu.be" => id.to_string(),
+            ["embed", id, ..] | ["live", id, ..] | ["shorts", id, ..] | ["v", id, ..] => {
+                id.to_string()
+            }
+            _ => return None,
+        };
+
+        if !YOUTUBE_ID.is_match(video_id.as_str()) {
+            return None;
+        }
+
+        // Keep the start time if the link has one.
+        let start: Option<u64> = url
+            .query_pairs()
+            .find(|(key, _)| key == "t" || key == "start")
+            .and_then(|(_, value)| value.trim_end_matches('s').parse().ok());
+
+        let mut player_url = format!("https://www.youtube-nocookie.com/embed/{}", video_id);
+        if let Some(start) = start {
+            player_url.push_str(&format!("?start={}", start));
+        }
+
+        return Some(Embed {
+            provider: Provider::YouTube,
+            video_id,
+            player_url,
+        });
+    }
+
+    /// Parse a Vimeo link, including unlisted links with a privacy hash.
+    fn vimeo(url: &Url) -> Option<Self> {
+        let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+        let (video_id, hash): (&str, Option<String>) = match segments.as_slice() {
+            ["video", id] => (
+                *id,
+                url.query_pairs()
+                    .find(|(key, _)| key == "h")
+                    .map(|(_, value)| value.into_owned()),
+            ),
+            [id] => (*id, None),
+            [id, hash] => (*id, Some(hash.to_string())),
+            _ => return None,
+        };
+
+        if !VIMEO_ID.is_match(video_id) {
+            return None;
+        }
+
+        // "Do not track" mode.
+        let mut player_url = format!("https://player.vimeo.com/video/{}?dnt=1", video_id);
+        if let Some(hash) = hash.filter(|hash| VIMEO_HASH.is_match(hash)) {
+            player_url.push_str(&format!("&h={}", hash));
+        }
+
+        return Some(Embed {
+            provider: Provider::Vimeo,
+            video_id: video_id.to_string(),
+            player_url,
+        });
+    }
+}
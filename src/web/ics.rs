@@ -0,0 +1,139 @@
+//! Minimal iCalendar (RFC 5545) document builder for calendar exports.
+
+use crate::env::global_config;
+use actix_web::http::header::{self, ContentDisposition, DispositionParam, DispositionType};
+use actix_web::HttpResponse;
+use chrono::{DateTime, Utc};
+use url::Url;
+
+/// The maximum length of a content line in octets, not counting the line break.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// A single event in a calendar.
+#[derive(Clone, Debug)]
+pub struct Event {
+    /// Globally unique identifier for this event.
+    pub uid: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub summary: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub url: Option<String>,
+}
+
+/// An iCalendar document.
+#[derive(Clone, Debug)]
+pub struct Calendar {
+    /// The calendar's display name.
+    name: String,
+    events: Vec<Event>,
+}
+
+/// Make a globally unique event identifier for a telescope record, using the
+/// host telescope is served from.
+pub fn event_uid(kind: &str, id: impl std::fmt::Display) -> String {
+    let host: String = Url::parse(global_config().telescope_url.as_str())
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or("telescope".into());
+    return format!("{}-{}@{}", kind, id, host);
+}
+
+/// Format a timestamp in the iCalendar UTC format.
+fn format_timestamp(time: &DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a text value.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Write a content line to the output, folding it to the maximum line length.
+fn push_line(output: &mut String, line: String) {
+    let mut octets: usize = 0;
+    for c in line.chars() {
+        // Folded lines start with a space, which counts toward their length.
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            output.push_str("\r\n ");
+            octets = 1;
+        }
+        output.push(c);
+        octets += c.len_utf8();
+    }
+    output.push_str("\r\n");
+}
+
+impl Calendar {
+    /// Create an empty calendar with a display name.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Add an event to this calendar.
+    pub fn push(&mut self, event: Event) {
+        self.events.push(event);
+    }
+
+    /// Render this calendar to an iCalendar document.
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        let now: String = format_timestamp(&Utc::now());
+
+        push_line(&mut output, "BEGIN:VCALENDAR".into());
+        push_line(&mut output, "VERSION:2.0".into());
+        push_line(&mut output, "PRODID:-//RCOS//Telescope//EN".into());
+        push_line(&mut output, "CALSCALE:GREGORIAN".into());
+        push_line(&mut output, format!("X-WR-CALNAME:{}", escape(&self.name)));
+
+        for event in self.events.iter() {
+            push_line(&mut output, "BEGIN:VEVENT".into());
+            push_line(&mut output, format!("UID:{}", escape(&event.uid)));
+            push_line(&mut output, format!("DTSTAMP:{}", now));
+            push_line(
+                &mut output,
+                format!("DTSTART:{}", format_timestamp(&event.start)),
+            );
+            push_line(
+                &mut output,
+                format!("DTEND:{}", format_timestamp(&event.end)),
+            );
+            push_line(&mut output, format!("SUMMARY:{}", escape(&event.summary)));
+            if let Some(description) = event.description.as_ref() {
+                push_line(&mut output, format!("DESCRIPTION:{}", escape(description)));
+            }
+            if let Some(location) = event.location.as_ref() {
+                push_line(&mut output, format!("LOCATION:{}", escape(location)));
+            }
+            if let Some(url) = event.url.as_ref() {
+                push_line(&mut output, format!("URL:{}", url));
+            }
+            push_line(&mut output, "END:VEVENT".into());
+        }
+
+        push_line(&mut output, "END:VCALENDAR".into());
+        return output;
+    }
+
+    /// Render this calendar into a file download response.
+    pub fn into_response(self, filename: impl Into<String>) -> HttpResponse {
+        HttpResponse::Ok()
+            .set_header(header::CONTENT_TYPE, "text/calendar; charset=utf-8")
+            .set_header(
+                header::CONTENT_DISPOSITION,
+                ContentDisposition {
+                    disposition: DispositionType::Attachment,
+                    parameters: vec![DispositionParam::Filename(filename.into())],
+                },
+            )
+            .body(self.render())
+    }
+}
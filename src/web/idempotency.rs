@@ -0,0 +1,104 @@
+//! Idempotency keys, so that submitting a form twice does not repeat its
+//! mutation.
+//!
+//! Forms embed a fresh key from [`new_key`] in a hidden `idempotency_key`
+//! field. The submission handler runs through [`once`], which records where
+//! the first successful submission with a key redirected to. Later submissions
+//! with the same key are sent to the same place instead of running again.
+//! Keys are kept in the [session store](crate::web::sessions), so with the
+//! in-memory store they are forgotten on restart.
+
+use crate::error::TelescopeError;
+use crate::web::sessions;
+use actix_web::http::header::LOCATION;
+use actix_web::HttpResponse;
+use chrono::Duration;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use std::future::Future;
+
+/// How long a completed key is remembered, in minutes.
+const KEY_LIFETIME_MINUTES: i64 = 60;
+
+/// How many characters are in a key.
+const KEY_LENGTH: usize = 32;
+
+/// Stored for a key while its first submission is still running. Finished
+/// submissions store where they redirected to.
+const IN_PROGRESS: &'static str = "";
+
+/// The session store key for an idempotency key.
+fn store_key(key: &str) -> String {
+    format!("idempotency:{}", key)
+}
+
+/// Generate a key to embed in a form.
+pub fn new_key() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(KEY_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+/// Run a form submission unless one with the same key has already run.
+///
+/// If the key has already completed, redirect to where that submission
+/// redirected. The submission is only remembered if it succeeds with a
+/// redirect; if it fails (for example with an invalid form), the key can be
+/// submitted again. Submissions without a key always run.
+pub async fn once<F>(key: Option<String>, submission: F) -> Result<HttpResponse, TelescopeError>
+where
+    F: Future<Output = Result<HttpResponse, TelescopeError>>,
+{
+    let key: String = match key.filter(|key| !key.is_empty()) {
+        Some(key) => key,
+        None => return submission.await,
+    };
+
+    let store = sessions::store();
+    let lifetime: Duration = Duration::minutes(KEY_LIFETIME_MINUTES);
+    if !store
+        .set_if_absent(
+            store_key(&key).as_str(),
+            IN_PROGRESS.to_string(),
+            Some(lifetime),
+        )
+        .await?
+    {
+        return match store.get(store_key(&key).as_str()).await? {
+            Some(location) if location != IN_PROGRESS => Ok(HttpResponse::Found()
+                .header(LOCATION, location.as_str())
+                .finish()),
+            // Still running, or expired just now.
+            _ => Err(TelescopeError::BadRequest {
+                header: "Already Submitted".into(),
+                message: "This form is still being submitted. Please wait a moment \
+                and then reload the page."
+                    .into(),
+                show_status_code: false,
+            }),
+        };
+    }
+
+    let result = submission.await;
+    let location: Option<String> = result
+        .as_ref()
+        .ok()
+        .and_then(|response| response.headers().get(LOCATION))
+        .and_then(|location| location.to_str().ok())
+        .map(str::to_string);
+
+    match location {
+        Some(location) => {
+            store
+                .set(store_key(&key).as_str(), location, Some(lifetime))
+                .await?;
+        }
+        None => {
+            store.take(store_key(&key).as_str()).await?;
+        }
+    }
+
+    return result;
+}
@@ -0,0 +1,180 @@
+//! Resized and WebP copies of uploaded images.
+//!
+//! Profile pictures and sponsor logos are processed once, when they are
+//! uploaded. Every image gets a directory under the images directory from the
+//! config, holding each [`Variant`] twice: as WebP, and in a fallback format
+//! for browsers without WebP support. Images are served from
+//! `/images/{id}/{variant}`, picking the format from the `Accept` header.
+
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use crate::web::uploads::sniff::FileType;
+use crate::web::uploads::CheckedUpload;
+use actix_web::web::block;
+use image::codecs::webp::{WebPEncoder, WebPQuality};
+use image::{ColorType, DynamicImage, ImageOutputFormat};
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// The quality of WebP copies, out of 100.
+const WEBP_QUALITY: u8 = 80;
+
+/// The quality of JPEG fallbacks, out of 100.
+const JPEG_QUALITY: u8 = 85;
+
+/// The sizes an image is stored in.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Variant {
+    /// Fits in 128 pixels square, for lists and the navbar.
+    Thumbnail,
+    /// Fits in 512 pixels square, for profiles and the sponsors page.
+    Medium,
+    /// The size it was uploaded at.
+    Original,
+}
+
+impl Variant {
+    /// Every variant.
+    const ALL: [Variant; 3] = [Variant::Thumbnail, Variant::Medium, Variant::Original];
+
+    /// The name of the variant, used in URLs and file names.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Variant::Thumbnail => "thumbnail",
+            Variant::Medium => "medium",
+            Variant::Original => "original",
+        }
+    }
+
+    /// Get a variant by name.
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|v| v.name() == name)
+    }
+
+    /// The largest width or height of this variant in pixels, if it is
+    /// limited.
+    fn max_size(&self) -> Option<u32> {
+        match self {
+            Variant::Thumbnail => Some(128),
+            Variant::Medium => Some(512),
+            Variant::Original => None,
+        }
+    }
+}
+
+/// The formats fallback copies can be stored in.
+const FALLBACK_TYPES: [FileType; 3] = [FileType::Png, FileType::Jpeg, FileType::Gif];
+
+/// The directory an image's files are stored in.
+fn image_dir(id: Uuid) -> PathBuf {
+    global_config().uploads.images_dir.join(id.to_string())
+}
+
+/// Encode an image as WebP.
+fn encode_webp(image: &DynamicImage) -> image::ImageResult<Vec<u8>> {
+    let rgba = image.to_rgba8();
+    let mut bytes: Vec<u8> = Vec::new();
+    WebPEncoder::new_with_quality(&mut bytes, WebPQuality::lossy(WEBP_QUALITY)).encode(
+        rgba.as_raw(),
+        rgba.width(),
+        rgba.height(),
+        ColorType::Rgba8,
+    )?;
+    return Ok(bytes);
+}
+
+/// Encode an image for browsers without WebP. Photos uploaded as JPEG stay
+/// JPEG, and everything else becomes PNG.
+fn encode_fallback(
+    image: &DynamicImage,
+    uploaded_as: FileType,
+) -> image::ImageResult<(FileType, Vec<u8>)> {
+    let (file_type, format) = match uploaded_as {
+        FileType::Jpeg => (FileType::Jpeg, ImageOutputFormat::Jpeg(JPEG_QUALITY)),
+        _ => (FileType::Png, ImageOutputFormat::Png),
+    };
+
+    let mut bytes: Vec<u8> = Vec::new();
+    image.write_to(&mut Cursor::new(&mut bytes), format)?;
+    return Ok((file_type, bytes));
+}
+
+/// Make and save every variant of an image. This is slow, so it is run on
+/// the blocking thread pool.
+fn process(id: Uuid, upload: &CheckedUpload) -> Result<(), String> {
+    let image: DynamicImage = image::load_from_memory(&upload.bytes)
+        .map_err(|e| format!("Could not read the image: {}", e))?;
+
+    let dir: PathBuf = image_dir(id);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    for variant in Variant::ALL {
+        let needs_resize: bool = variant
+            .max_size()
+            .map_or(false, |max| image.width() > max || image.height() > max);
+
+        let resized: DynamicImage = match variant.max_size() {
+            Some(max) if needs_resize => image.thumbnail(max, max),
+            _ => image.clone(),
+        };
+
+        // Images that are already small enough keep their uploaded file as
+        // the fallback, so animated GIFs keep moving for those browsers.
+        let (fallback_type, fallback) = if needs_resize || upload.file_type == FileType::Webp {
+            encode_fallback(&resized, upload.file_type).map_err(|e| e.to_string())?
+        } else {
+            (upload.file_type, upload.bytes.clone())
+        };
+
+        let webp: Vec<u8> = encode_webp(&resized).map_err(|e| e.to_string())?;
+
+        let fallback_name = format!("{}.{}", variant.name(), fallback_type.extensions()[0]);
+        fs::write(dir.join(fallback_name), fallback).map_err(|e| e.to_string())?;
+        fs::write(dir.join(format!("{}.webp", variant.name())), webp).map_err(|e| e.to_string())?;
+    }
+
+    return Ok(());
+}
+
+/// Process and store an uploaded image. Return the ID it is served under.
+pub async fn store(upload: CheckedUpload) -> Result<Uuid, TelescopeError> {
+    let id: Uuid = Uuid::new_v4();
+    block(move || process(id, &upload))
+        .await
+        .map_err(|e| TelescopeError::BadRequest {
+            header: "Could Not Process Image".into(),
+            message: e.to_string(),
+            show_status_code: false,
+        })?;
+    return Ok(id);
+}
+
+/// Delete every variant of a stored image.
+pub fn delete(id: Uuid) {
+    if let Err(e) = fs::remove_dir_all(image_dir(id)) {
+        warn!("Could not delete image {}: {}", id, e);
+    }
+}
+
+/// The URL a variant of a stored image is served at.
+pub fn url(id: Uuid, variant: Variant) -> String {
+    format!("/images/{}/{}", id, variant.name())
+}
+
+/// Find the file to serve for a variant of an image, with its type. WebP is
+/// preferred if the browser accepts it.
+pub fn find(id: Uuid, variant: Variant, accepts_webp: bool) -> Option<(PathBuf, FileType)> {
+    let dir: PathBuf = image_dir(id);
+
+    let webp: PathBuf = dir.join(format!("{}.webp", variant.name()));
+    if accepts_webp && webp.exists() {
+        return Some((webp, FileType::Webp));
+    }
+
+    return FALLBACK_TYPES.iter().find_map(|file_type| {
+        let path = dir.join(format!("{}.{}", variant.name(), file_type.extensions()[0]));
+        path.exists().then(|| (path, *file_type))
+    });
+}
@@ -0,0 +1,206 @@
+//! The sockets Telescope listens on.
+//!
+//! Telescope listens on every address in `bind_to`, and on `admin_bind_to`
+//! if it is set. IPv6 sockets are made IPv6-only, so that `0.0.0.0:443` and
+//! `[::]:443` can be bound side by side for dual-stack hosting.
+//!
+//! When there is an admin address, the admin panel is only served there.
+//!
+//! On Unix, Telescope can also listen on a Unix domain socket (`unix_socket`)
+//! for a reverse proxy on the same host. Under systemd socket activation, the
+//! sockets systemd passes in are used instead of any of these.
+
+use crate::env::global_config;
+use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+use std::io;
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs};
+use std::sync::RwLock;
+
+#[cfg(unix)]
+use crate::env::UnixSocketConfig;
+#[cfg(unix)]
+use crate::web::systemd;
+#[cfg(unix)]
+use std::fs;
+#[cfg(unix)]
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use std::path::Path;
+
+/// Connections waiting to be accepted on each socket. Matches actix-web's default.
+const BACKLOG: i32 = 1024;
+
+lazy_static! {
+    /// The addresses listened on, once they are bound. Unix sockets are
+    /// listed by path, prefixed with "unix:".
+    static ref LISTENING: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+    /// The addresses of the admin listener, if there is one.
+    static ref ADMIN_ADDRESSES: Vec<SocketAddr> = global_config()
+        .admin_bind_to
+        .as_ref()
+        .map(|address| resolve(address).unwrap_or_default())
+        .unwrap_or_default();
+}
+
+/// A socket to serve on.
+pub enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+/// Open the sockets to serve on. Panics if any cannot be opened, since
+/// Telescope would be unreachable where it is expected.
+pub fn open() -> Vec<Listener> {
+    #[cfg(unix)]
+    {
+        let activated: Vec<Listener> = systemd::listen_fds();
+        if !activated.is_empty() {
+            for listener in activated.iter() {
+                let description: String = match listener {
+                    Listener::Tcp(l) => l.local_addr().map(|a| a.to_string()),
+                    Listener::Unix(l) => l.local_addr().map(|a| match a.as_pathname() {
+                        Some(path) => format!("unix:{}", path.display()),
+                        None => "unix:(unnamed)".into(),
+                    }),
+                }
+                .unwrap_or_else(|_| "unknown address".into());
+
+                info!("Listening on {} (from systemd)", description);
+                LISTENING.write().unwrap().push(description);
+            }
+            return activated;
+        }
+    }
+
+    let mut listeners: Vec<Listener> = bind_all().into_iter().map(Listener::Tcp).collect();
+    #[cfg(unix)]
+    listeners.extend(bind_unix_socket().map(Listener::Unix));
+    return listeners;
+}
+
+/// Look up the socket addresses of a configured address, like "[::]:443" or
+/// "localhost:8081".
+fn resolve(address: &str) -> io::Result<Vec<SocketAddr>> {
+    address
+        .to_socket_addrs()
+        .map(|addresses| addresses.collect())
+}
+
+/// Make a listening socket.
+fn listen(address: SocketAddr) -> io::Result<TcpListener> {
+    let domain: Domain = match address {
+        SocketAddr::V4(_) => Domain::ipv4(),
+        SocketAddr::V6(_) => Domain::ipv6(),
+    };
+
+    let socket = Socket::new(domain, Type::stream(), Some(Protocol::tcp()))?;
+    if address.is_ipv6() {
+        // Leave IPv4 to its own socket.
+        socket.set_only_v6(true)?;
+    }
+    socket.set_reuse_address(true)?;
+    socket.bind(&SockAddr::from(address))?;
+    socket.listen(BACKLOG)?;
+    return Ok(socket.into_tcp_listener());
+}
+
+/// Bind every configured address.
+fn bind_all() -> Vec<TcpListener> {
+    let config = global_config();
+    let scheme: &str = if config.tls.is_some() {
+        "https"
+    } else {
+        "http"
+    };
+    let mut listeners: Vec<TcpListener> = Vec::new();
+
+    for address in config.bind_to.iter().chain(config.admin_bind_to.iter()) {
+        let resolved: Vec<SocketAddr> = resolve(address)
+            .unwrap_or_else(|e| panic!("Could not resolve bind address {}: {}", address, e));
+
+        for socket_address in resolved {
+            let listener: TcpListener = listen(socket_address)
+                .unwrap_or_else(|e| panic!("Could not bind {}: {}", socket_address, e));
+            let local: SocketAddr = listener.local_addr().unwrap_or(socket_address);
+
+            if ADMIN_ADDRESSES.contains(&socket_address) {
+                info!("Listening on {}://{} (admin panel)", scheme, local);
+            } else {
+                info!("Listening on {}://{}", scheme, local);
+            }
+
+            LISTENING.write().unwrap().push(local.to_string());
+            listeners.push(listener);
+        }
+    }
+
+    return listeners;
+}
+
+/// The addresses Telescope is listening on.
+pub fn listening() -> Vec<String> {
+    LISTENING.read().unwrap().clone()
+}
+
+/// Check if the admin panel can be served on the listener a request came in on.
+/// Always true when there is no admin address.
+pub fn serves_admin(local_address: SocketAddr) -> bool {
+    ADMIN_ADDRESSES.is_empty() || ADMIN_ADDRESSES.contains(&local_address)
+}
+
+/// Remove a socket file left behind by an earlier run. Fails if the path is
+/// not a socket, or if something is still accepting connections on it.
+#[cfg(unix)]
+fn remove_stale_socket(path: &Path) -> io::Result<()> {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+
+    if !metadata.file_type().is_socket() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "a file that is not a socket is in the way",
+        ));
+    }
+    if UnixStream::connect(path).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            "another server is listening on it",
+        ));
+    }
+
+    info!("Removing stale socket {}", path.display());
+    return fs::remove_file(path);
+}
+
+/// Bind the configured Unix domain socket, if there is one.
+#[cfg(unix)]
+fn bind_unix_socket() -> Option<UnixListener> {
+    let config = global_config();
+    let socket: &UnixSocketConfig = config.unix_socket.as_ref()?;
+    let path: &Path = socket.path.as_path();
+
+    let bind = || -> io::Result<UnixListener> {
+        remove_stale_socket(path)?;
+        let listener = UnixListener::bind(path)?;
+        if let Some(mode) = socket.mode {
+            fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
+        }
+        return Ok(listener);
+    };
+
+    let listener: UnixListener =
+        bind().unwrap_or_else(|e| panic!("Could not bind {}: {}", path.display(), e));
+    info!("Listening on unix:{}", path.display());
+    LISTENING
+        .write()
+        .unwrap()
+        .push(format!("unix:{}", path.display()));
+    return Some(listener);
+}
@@ -0,0 +1,222 @@
+//! WebSocket notification layer for pages that update live.
+//!
+//! Browsers open a socket to `/live/{topic}` and are sent a short event name
+//! whenever something on that topic changes. Events carry no data -- pages
+//! fetch whatever changed through normal (authenticated) requests -- so any
+//! visitor can subscribe to any topic.
+//!
+//! Server code publishes events with [`publish`]. Topics are plain strings,
+//! conventionally the path of the page they are for (e.g.
+//! `meeting/12/questions`).
+
+use crate::web::cluster::{self, Broadcast};
+use actix::prelude::*;
+use actix_web::web::{Path, Payload, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use actix_web_actors::ws;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How often sessions ping the browser.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long a session waits to hear from the browser before closing.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Register the live update socket.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(socket);
+}
+
+/// Publish an event to everyone subscribed to a topic, on every replica.
+pub fn publish(topic: impl Into<String>, event: &'static str) {
+    cluster::broadcast(Broadcast::Live {
+        topic: topic.into(),
+        event: event.into(),
+    });
+}
+
+/// Send an event to the sessions subscribed to a topic on this replica.
+pub(crate) fn deliver(topic: String, event: String) {
+    LiveHub::from_registry().do_send(Publish { topic, event });
+}
+
+/// An event sent to a session.
+#[derive(Message, Clone)]
+#[rtype(result = "()")]
+struct Event(String);
+
+/// Subscribe a session to a topic. Returns the session's ID.
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct Subscribe {
+    topic: String,
+    session: Recipient<Event>,
+}
+
+/// Remove a session from a topic.
+#[derive(Message)]
+#[rtype(result = "()")]
+struct Unsubscribe {
+    topic: String,
+    id: usize,
+}
+
+/// Send an event to a topic.
+#[derive(Message)]
+#[rtype(result = "()")]
+struct Publish {
+    topic: String,
+    event: String,
+}
+
+/// Actor tracking which sessions are subscribed to which topics. There is one
+/// per actix system, found through the system registry.
+#[derive(Default)]
+struct LiveHub {
+    /// The sessions subscribed to each topic, by session ID.
+    topics: HashMap<String, HashMap<usize, Recipient<Event>>>,
+    /// The ID to give the next session.
+    next_id: usize,
+}
+
+impl Actor for LiveHub {
+    type Context = Context<Self>;
+}
+
+impl Supervised for LiveHub {}
+
+impl SystemService for LiveHub {}
+
+impl Handler<Subscribe> for LiveHub {
+    type Result = usize;
+
+    fn handle(&mut self, msg: Subscribe, _: &mut Self::Context) -> Self::Result {
+        let id: usize = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        self.topics
+            .entry(msg.topic)
+            .or_default()
+            .insert(id, msg.session);
+        return id;
+    }
+}
+
+impl Handler<Unsubscribe> for LiveHub {
+    type Result = ();
+
+    fn handle(&mut self, msg: Unsubscribe, _: &mut Self::Context) {
+        if let Some(sessions) = self.topics.get_mut(&msg.topic) {
+            sessions.remove(&msg.id);
+            // Drop topics nobody is listening to.
+            if sessions.is_empty() {
+                self.topics.remove(&msg.topic);
+            }
+        }
+    }
+}
+
+impl Handler<Publish> for LiveHub {
+    type Result = ();
+
+    fn handle(&mut self, msg: Publish, _: &mut Self::Context) {
+        if let Some(sessions) = self.topics.get(&msg.topic) {
+            for session in sessions.values() {
+                // Sessions that have closed unsubscribe themselves, so a failed
+                // send here is not worth reporting.
+                let _ = session.do_send(Event(msg.event.clone()));
+            }
+        }
+    }
+}
+
+/// A browser's connection to one topic.
+struct LiveSession {
+    topic: String,
+    /// The ID the hub gave this session once subscribed.
+    id: Option<usize>,
+    /// The last time the browser was heard from.
+    last_heartbeat: Instant,
+}
+
+impl Actor for LiveSession {
+    type Context = ws::WebsocketContext<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        // Close the socket if the browser stops responding.
+        ctx.run_interval(HEARTBEAT_INTERVAL, |session, ctx| {
+            if Instant::now().duration_since(session.last_heartbeat) > CLIENT_TIMEOUT {
+                ctx.stop();
+            } else {
+                ctx.ping(b"");
+            }
+        });
+
+        LiveHub::from_registry()
+            .send(Subscribe {
+                topic: self.topic.clone(),
+                session: ctx.address().recipient(),
+            })
+            .into_actor(self)
+            .then(|result, session, ctx| {
+                match result {
+                    Ok(id) => session.id = Some(id),
+                    Err(_) => ctx.stop(),
+                }
+                fut::ready(())
+            })
+            .wait(ctx);
+    }
+
+    fn stopping(&mut self, _: &mut Self::Context) -> Running {
+        if let Some(id) = self.id {
+            LiveHub::from_registry().do_send(Unsubscribe {
+                topic: self.topic.clone(),
+                id,
+            });
+        }
+        Running::Stop
+    }
+}
+
+impl Handler<Event> for LiveSession {
+    type Result = ();
+
+    fn handle(&mut self, msg: Event, ctx: &mut Self::Context) {
+        ctx.text(msg.0);
+    }
+}
+
+impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for LiveSession {
+    fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        match msg {
+            Ok(ws::Message::Ping(bytes)) => {
+                self.last_heartbeat = Instant::now();
+                ctx.pong(&bytes);
+            }
+            Ok(ws::Message::Pong(_)) => self.last_heartbeat = Instant::now(),
+            Ok(ws::Message::Close(reason)) => {
+                ctx.close(reason);
+                ctx.stop();
+            }
+            // Browsers only listen on these sockets. Ignore anything they send.
+            Ok(_) => self.last_heartbeat = Instant::now(),
+            Err(_) => ctx.stop(),
+        }
+    }
+}
+
+/// Open a live update socket for a topic.
+#[get("/live/{topic:.*}")]
+async fn socket(
+    req: HttpRequest,
+    stream: Payload,
+    Path(topic): Path<String>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let session = LiveSession {
+        topic,
+        id: None,
+        last_heartbeat: Instant::now(),
+    };
+    ws::start(session, &req, stream)
+}
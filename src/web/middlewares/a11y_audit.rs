@@ -0,0 +1,123 @@
+//! Development middleware that checks rendered pages for accessibility
+//! problems.
+//!
+//! Enabled by `a11y_audit` in the config. Every HTML response is run through
+//! [`a11y::audit`](crate::web::a11y::audit). Violations are logged as
+//! warnings and listed in a box at the bottom of the page. This buffers and
+//! re-scans every page, so it should stay off in production.
+
+use crate::web::a11y::{self, Violation};
+use actix_web::body::{Body, ResponseBody};
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::Error as ActixError;
+use actix_web::http::header::CONTENT_TYPE;
+use futures::future::{ok, Ready};
+use futures::task::{Context, Poll};
+use futures::TryStreamExt;
+use std::future::Future;
+use std::pin::Pin;
+
+/// The factory for the accessibility audit middleware.
+pub struct A11yAudit;
+
+/// Middleware to check HTML responses for accessibility problems.
+pub struct A11yAuditMiddleware<S> {
+    /// The next service in the chain.
+    service: S,
+}
+
+impl<S> Transform<S> for A11yAudit
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError>,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Transform = A11yAuditMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(A11yAuditMiddleware { service })
+    }
+}
+
+/// Render violations as a box to put at the end of the page.
+fn annotation(violations: &[Violation]) -> String {
+    let items: String = violations
+        .iter()
+        .map(|v| {
+            format!(
+                "<li><code>{}</code> line {}: {}</li>",
+                v.rule,
+                v.line,
+                v_htmlescape::escape(v.message.as_str())
+            )
+        })
+        .collect();
+
+    format!(
+        "<aside class=\"alert alert-warning m-3\" aria-label=\"Accessibility audit\">\
+         <strong>Accessibility audit: {} problems</strong><ul class=\"mb-0\">{}</ul></aside>",
+        violations.len(),
+        items
+    )
+}
+
+impl<S> Service for A11yAuditMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError>,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&mut self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&mut self, req: Self::Request) -> Self::Future {
+        let response_future = self.service.call(req);
+
+        Box::pin(async move {
+            let mut response: ServiceResponse = response_future.await?;
+
+            let is_html: bool = response
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map_or(false, |value| value.starts_with("text/html"));
+            if !is_html {
+                return Ok(response);
+            }
+
+            let body: ResponseBody<Body> = response.response_mut().take_body();
+            let mut html: String = body
+                .map_ok(|bytes| String::from_utf8_lossy(bytes.as_ref()).to_string())
+                .try_collect::<String>()
+                .await?;
+
+            let violations: Vec<Violation> = a11y::audit(html.as_str());
+            if !violations.is_empty() {
+                let path: String = response.request().path().to_string();
+                for violation in violations.iter() {
+                    warn!(
+                        "Accessibility: {} line {}: [{}] {}",
+                        path, violation.line, violation.rule, violation.message
+                    );
+                }
+
+                let note: String = annotation(&violations);
+                match html.rfind("</body>") {
+                    Some(index) => html.insert_str(index, note.as_str()),
+                    None => html.push_str(note.as_str()),
+                }
+            }
+
+            return Ok(response.map_body(|_, _| ResponseBody::Body(Body::from(html))));
+        })
+    }
+}
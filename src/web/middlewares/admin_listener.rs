@@ -0,0 +1,66 @@
+//! Middleware that keeps the admin panel on the admin listener.
+//!
+//! When `admin_bind_to` is set, requests for the admin panel that come in on
+//! any other address are answered as if the page did not exist. See
+//! [`listeners`](crate::web::listeners).
+
+use crate::error::TelescopeError;
+use crate::web::listeners;
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::Error as ActixError;
+use futures::future::{ok, Either, Ready};
+use futures::task::{Context, Poll};
+
+/// Check if a path is part of the admin panel.
+fn is_admin_path(path: &str) -> bool {
+    path == "/admin" || path.starts_with("/admin/")
+}
+
+/// The factory for the admin listener middleware.
+pub struct AdminListener;
+
+/// Middleware to hide the admin panel from public listeners.
+pub struct AdminListenerMiddleware<S> {
+    /// The next service in the chain.
+    service: S,
+}
+
+impl<S> Transform<S> for AdminListener
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError>,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Transform = AdminListenerMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(AdminListenerMiddleware { service })
+    }
+}
+
+impl<S> Service for AdminListenerMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError>,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Future = Either<S::Future, Ready<Result<Self::Response, Self::Error>>>;
+
+    fn poll_ready(&mut self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&mut self, req: Self::Request) -> Self::Future {
+        let local_address = req.app_config().local_addr();
+        if is_admin_path(req.path()) && !listeners::serves_admin(local_address) {
+            return Either::Right(ok(req.error_response(TelescopeError::PageNotFound)));
+        }
+        return Either::Left(self.service.call(req));
+    }
+}
@@ -0,0 +1,144 @@
+//! Middleware to rate limit the public JSON API by caller tier.
+//!
+//! Callers are identified and counted by [`api_keys`](crate::web::api_keys).
+//! Every response says how many requests the caller has left in the
+//! `X-RateLimit-Limit` and `X-RateLimit-Remaining` headers. Callers over
+//! their limit get a 429 with a `Retry-After` header, and callers with a key
+//! that is unknown or revoked get a 401. Both are counted against their IP
+//! address, so guessing keys is limited too.
+
+use crate::web::api_keys::{self, Caller, RateCount, HEADER_NAME};
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::Error as ActixError;
+use actix_web::http::header::{HeaderName, HeaderValue, RETRY_AFTER};
+use actix_web::http::StatusCode;
+use actix_web::HttpResponse;
+use futures::future::{ok, Ready};
+use futures::task::{Context, Poll};
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+/// The header with how many requests the caller can make per minute.
+const LIMIT_HEADER: &'static str = "x-ratelimit-limit";
+
+/// The header with how many more requests the caller can make this minute.
+const REMAINING_HEADER: &'static str = "x-ratelimit-remaining";
+
+/// The factory for the API rate limiting middleware.
+pub struct ApiRateLimit;
+
+/// Middleware to count public API requests and turn away callers over their
+/// limit.
+pub struct ApiRateLimitMiddleware<S: 'static> {
+    /// The next service in the chain. Shared with response futures, which
+    /// call it after counting the request.
+    service: Rc<RefCell<S>>,
+}
+
+impl<S> Transform<S> for ApiRateLimit
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError> + 'static,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Transform = ApiRateLimitMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(ApiRateLimitMiddleware {
+            service: Rc::new(RefCell::new(service)),
+        })
+    }
+}
+
+/// A JSON error response.
+fn error_response(status: StatusCode, message: &str) -> HttpResponse {
+    HttpResponse::build(status).json(json!({ "error": message }))
+}
+
+/// Add the rate limit headers to a response.
+fn add_headers(response: &mut HttpResponse, count: &RateCount) {
+    let headers = response.headers_mut();
+    headers.insert(HeaderName::from_static(LIMIT_HEADER), count.limit.into());
+    headers.insert(
+        HeaderName::from_static(REMAINING_HEADER),
+        count.remaining().into(),
+    );
+}
+
+/// The response for a caller over their limit.
+fn too_many_requests(count: &RateCount) -> HttpResponse {
+    let mut response = error_response(
+        StatusCode::TOO_MANY_REQUESTS,
+        "Too many requests. Please wait before trying again.",
+    );
+    add_headers(&mut response, count);
+    response
+        .headers_mut()
+        .insert(RETRY_AFTER, HeaderValue::from(count.reset_after.max(1)));
+    return response;
+}
+
+impl<S> Service for ApiRateLimitMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError> + 'static,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&mut self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&mut self, req: Self::Request) -> Self::Future {
+        let mut service: Rc<RefCell<S>> = self.service.clone();
+
+        Box::pin(async move {
+            let ip: String = api_keys::client_ip(&req);
+            let key: Option<String> = req
+                .headers()
+                .get(HEADER_NAME)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.trim().to_string());
+
+            let caller: Caller = match key {
+                None => Caller::Anonymous(ip),
+                Some(key) => match api_keys::lookup(key.as_str()).await? {
+                    Some((key_id, tier)) => Caller::Key { key_id, tier },
+                    None => {
+                        let count: RateCount = api_keys::count(&Caller::Anonymous(ip)).await?;
+                        let response: HttpResponse = if count.allowed() {
+                            error_response(StatusCode::UNAUTHORIZED, "Unknown or revoked API key.")
+                        } else {
+                            too_many_requests(&count)
+                        };
+                        return Ok(req.into_response(response));
+                    }
+                },
+            };
+
+            let count: RateCount = api_keys::count(&caller).await?;
+            if !count.allowed() {
+                warn!(
+                    "Turning away {} -- {:?} is over the {} tier limit.",
+                    req.path(),
+                    caller,
+                    caller.tier().name()
+                );
+                return Ok(req.into_response(too_many_requests(&count)));
+            }
+
+            let mut response: ServiceResponse = service.call(req).await?;
+            add_headers(response.response_mut(), &count);
+            return Ok(response);
+        })
+    }
+}
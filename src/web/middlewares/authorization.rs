@@ -124,7 +124,7 @@ where
 }
 
 /// Extract the RCOS user ID authenticated with a request or error.
-async fn extract_user_id(req: &ServiceRequest) -> Result<Uuid, TelescopeError> {
+pub async fn extract_user_id(req: &ServiceRequest) -> Result<Uuid, TelescopeError> {
     req
         // Get the identity of the service request -- this should be a json encoded authentication
         // cookie if it exists.
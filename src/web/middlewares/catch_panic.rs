@@ -0,0 +1,93 @@
+//! Middleware that turns panics in handlers into error pages.
+//!
+//! Without it, a panic drops the connection and the user sees it reset. The
+//! panic is [logged](crate::logging) with the request ID when it happens; this
+//! middleware answers with an internal server error instead, which the error
+//! rendering middleware turns into the usual error page.
+
+use crate::error::TelescopeError;
+use crate::web::middlewares::request_id::REQUEST_ID_HEADER;
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::Error as ActixError;
+use actix_web::{HttpRequest, ResponseError};
+use futures::future::{ok, FutureExt, Ready};
+use futures::task::{Context, Poll};
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+
+/// The factory for the panic catching middleware.
+pub struct CatchPanic;
+
+/// Middleware to catch panics in the services it wraps.
+pub struct CatchPanicMiddleware<S> {
+    /// The next service in the chain.
+    service: S,
+}
+
+impl<S> Transform<S> for CatchPanic
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError>,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Transform = CatchPanicMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(CatchPanicMiddleware { service })
+    }
+}
+
+/// The error shown for a request that panicked.
+fn panic_error(req: &HttpRequest) -> TelescopeError {
+    let request_id: &str = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("unknown");
+    TelescopeError::ise(format!(
+        "Telescope crashed while handling this request. Please include the request ID {} \
+        in the issue.",
+        request_id
+    ))
+}
+
+impl<S> Service for CatchPanicMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError>,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&mut self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&mut self, req: Self::Request) -> Self::Future {
+        // Keep the request to answer with if the service panics.
+        let http_req: HttpRequest = req.request().clone();
+
+        // Handlers run when the service future is polled, so that is where
+        // panics are caught.
+        let service_response_future = self.service.call(req);
+        Box::pin(async move {
+            match AssertUnwindSafe(service_response_future)
+                .catch_unwind()
+                .await
+            {
+                Ok(result) => result,
+                Err(_) => {
+                    let response = panic_error(&http_req).error_response();
+                    Ok(ServiceResponse::new(http_req, response))
+                }
+            }
+        })
+    }
+}
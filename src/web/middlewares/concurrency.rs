@@ -0,0 +1,209 @@
+//! Middleware to limit how many requests are handled at once.
+//!
+//! Requests are sorted into classes by path, and each class has its own limit
+//! (see [`ConcurrencyLimits`]). A request that would go over its class's limit
+//! is turned away right away with a 503 and a `Retry-After` header rather than
+//! queued, so a burst of slow exports cannot hold up logins.
+
+use crate::env::{global_config, ConcurrencyLimits};
+use actix_web::body::{Body, BodySize, MessageBody, ResponseBody};
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::Error as ActixError;
+use actix_web::http::header::RETRY_AFTER;
+use actix_web::web::Bytes;
+use actix_web::HttpResponse;
+use futures::future::{ok, Ready};
+use futures::task::{Context, Poll};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Paths that do little work and should stay available when pages are busy.
+const LIGHT_PREFIXES: &[&str] = &[
+    "/static/",
+    "/images/",
+    "/login",
+    "/logout",
+    "/register",
+    "/auth/",
+    "/health",
+    "/metrics",
+];
+
+/// Paths of generated downloads.
+const EXPORT_PREFIXES: &[&str] = &["/admin/download/", "/admin/research/csv"];
+
+/// Prefix of the attestation reports, which are downloaded at `{key}/csv`
+/// under it.
+const ATTESTATIONS_PREFIX: &str = "/admin/attestations/";
+
+/// Classes of routes with separate limits.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum RouteClass {
+    Light,
+    Pages,
+    Exports,
+}
+
+/// Requests in flight, indexed by route class.
+static IN_FLIGHT: [AtomicUsize; 3] = [
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+];
+
+impl RouteClass {
+    /// Classify a request path.
+    pub(crate) fn of(path: &str) -> Self {
+        if LIGHT_PREFIXES.iter().any(|prefix| path.starts_with(prefix)) {
+            RouteClass::Light
+        } else if EXPORT_PREFIXES
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+            || (path.starts_with(ATTESTATIONS_PREFIX) && path.ends_with("/csv"))
+            || path.contains("/certificate/")
+            || path.ends_with(".ics")
+        {
+            RouteClass::Exports
+        } else {
+            RouteClass::Pages
+        }
+    }
+
+    /// The limit for this class.
+    fn limit(self, limits: &ConcurrencyLimits) -> usize {
+        match self {
+            RouteClass::Light => limits.light,
+            RouteClass::Pages => limits.pages,
+            RouteClass::Exports => limits.exports,
+        }
+    }
+
+    /// The in flight counter for this class.
+    fn counter(self) -> &'static AtomicUsize {
+        &IN_FLIGHT[self as usize]
+    }
+}
+
+/// A slot held by a request in flight. The slot is released when this is
+/// dropped, even if the request errors or is cancelled.
+struct Permit(RouteClass);
+
+impl Permit {
+    /// Take a slot in a class if one is free.
+    fn acquire(class: RouteClass, limit: usize) -> Option<Self> {
+        class
+            .counter()
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (count < limit).then(|| count + 1)
+            })
+            .ok()
+            .map(|_| Permit(class))
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.0.counter().fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// A response body that holds its request's slot until it has been sent, so
+/// slow downloads count against their class while they stream.
+struct PermitBody {
+    /// The response body.
+    body: ResponseBody<Body>,
+    /// The slot, released once the body ends.
+    permit: Option<Permit>,
+}
+
+impl MessageBody for PermitBody {
+    fn size(&self) -> BodySize {
+        self.body.size()
+    }
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, ActixError>>> {
+        let poll = Pin::new(&mut self.body).poll_next(cx);
+        if let Poll::Ready(None) | Poll::Ready(Some(Err(_))) = poll {
+            self.permit = None;
+        }
+        return poll;
+    }
+}
+
+/// The factory to create concurrency limiting middleware.
+pub struct ConcurrencyLimiter;
+
+/// Middleware to turn away requests over their class's concurrency limit.
+pub struct ConcurrencyLimiterMiddleware<S> {
+    /// The next service in the chain.
+    service: S,
+}
+
+impl<S> Transform<S> for ConcurrencyLimiter
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError>,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Transform = ConcurrencyLimiterMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(ConcurrencyLimiterMiddleware { service })
+    }
+}
+
+impl<S> Service for ConcurrencyLimiterMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError>,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&mut self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&mut self, req: Self::Request) -> Self::Future {
+        let limits: ConcurrencyLimits = global_config().concurrency_limits;
+        let class = RouteClass::of(req.path());
+
+        let permit = match Permit::acquire(class, class.limit(&limits)) {
+            Some(permit) => permit,
+            None => {
+                warn!(
+                    "Turning away {} -- too many {:?} requests.",
+                    req.path(),
+                    class
+                );
+                let response = HttpResponse::ServiceUnavailable()
+                    .header(RETRY_AFTER, limits.retry_after.to_string())
+                    .body("Telescope is busy right now. Please try again in a few seconds.");
+                return Box::pin(ok(req.into_response(response)));
+            }
+        };
+
+        let service_response_future = self.service.call(req);
+        Box::pin(async move {
+            // Hold the slot until the body has been sent. If the handler
+            // errors, the slot is released here.
+            let response: ServiceResponse = service_response_future.await?;
+            return Ok(response.map_body(|_, body| {
+                ResponseBody::Body(Body::from_message(PermitBody {
+                    body,
+                    permit: Some(permit),
+                }))
+            }));
+        })
+    }
+}
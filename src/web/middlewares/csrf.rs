@@ -0,0 +1,290 @@
+//! Middleware protecting form submissions from Cross Site Request Forgery.
+//!
+//! Every browser gets a random session ID in the `telescope_csrf` cookie. The
+//! form token for a session is an HMAC of that ID keyed with the JWT secret,
+//! so it can be checked without keeping anything on the server. Templates
+//! put the token in their forms with the `{{csrf_field}}` helper, which
+//! writes a [placeholder](PLACEHOLDER) that is swapped for the real token in
+//! every HTML response on the way out. The placeholder is the same for every
+//! viewer, so rendered content can still be [cached](crate::templates::cache).
+//!
+//! POST, PUT, PATCH, and DELETE requests have to send the token back, either
+//! in the `X-CSRF-Token` header or in a `csrf_token` form field. Multipart
+//! forms should put the field first, since only the start of the body is
+//! read to find it. JSON requests are left alone, because browsers will not
+//! send them across sites without the server's permission.
+//!
+//! This is separate from the [OAuth2 CSRF tokens](crate::web::csrf), which
+//! protect logins with identity providers.
+
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use crate::web::tokens_match;
+use actix_web::body::{Body, ResponseBody};
+use actix_web::cookie::{Cookie, SameSite};
+use actix_web::dev::{
+    HttpResponseBuilder, Payload, Service, ServiceRequest, ServiceResponse, Transform,
+};
+use actix_web::error::Error as ActixError;
+use actix_web::http::header::CONTENT_TYPE;
+use actix_web::http::Method;
+use actix_web::web::{Bytes, BytesMut};
+use actix_web::{HttpMessage, HttpResponse, ResponseError};
+use futures::future::{ok, ready, Ready};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use futures::task::{Context, Poll};
+use hmac::{Hmac, Mac, NewMac};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use sha2::Sha256;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+/// The cookie holding the CSRF session ID.
+const COOKIE_NAME: &'static str = "telescope_csrf";
+
+/// The length of CSRF session IDs.
+const SESSION_ID_LENGTH: usize = 32;
+
+/// The header scripts can send the token in.
+pub const HEADER_NAME: &'static str = "X-CSRF-Token";
+
+/// The form field the token is sent in.
+pub const FIELD_NAME: &'static str = "csrf_token";
+
+/// Written by the `csrf_field` helper in place of the token.
+pub const PLACEHOLDER: &'static str = "__telescope_csrf_token__";
+
+/// The most of a request body to read looking for the token.
+const BODY_LIMIT: usize = 256 * 1024;
+
+/// The factory for the CSRF protection middleware.
+pub struct CsrfProtection;
+
+/// Middleware to check form tokens and fill them in to pages.
+pub struct CsrfProtectionMiddleware<S: 'static> {
+    /// The next service in the chain. Shared with response futures, which
+    /// call it after checking the token.
+    service: Rc<RefCell<S>>,
+}
+
+impl<S> Transform<S> for CsrfProtection
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError> + 'static,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Transform = CsrfProtectionMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(CsrfProtectionMiddleware {
+            service: Rc::new(RefCell::new(service)),
+        })
+    }
+}
+
+/// Make a new random session ID.
+fn new_session_id() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(SESSION_ID_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+/// The form token for a session ID.
+fn token_for(session_id: &str) -> String {
+    // HMAC takes keys of any length.
+    let mut mac = Hmac::<Sha256>::new_varkey(global_config().jwt_secret.as_bytes())
+        .expect("Invalid HMAC key length");
+    mac.update(b"csrf:");
+    mac.update(session_id.as_bytes());
+
+    return mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+}
+
+/// Check if a request has to carry a token.
+fn needs_token(req: &ServiceRequest) -> bool {
+    let unsafe_method: bool = matches!(
+        *req.method(),
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    );
+    return unsafe_method && req.content_type() != "application/json";
+}
+
+/// Find the first position of `needle` in `haystack` at or after `from`.
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    if from > haystack.len() {
+        return None;
+    }
+    haystack[from..]
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|index| index + from)
+}
+
+/// Get the token field out of the start of a multipart body.
+fn multipart_token(body: &[u8]) -> Option<String> {
+    let name: String = format!("name=\"{}\"", FIELD_NAME);
+    let header: usize = find(body, name.as_bytes(), 0)?;
+    let start: usize = find(body, b"\r\n\r\n", header)? + 4;
+    let end: usize = find(body, b"\r\n", start)?;
+    return String::from_utf8(body[start..end].to_vec()).ok();
+}
+
+/// Get the token field out of the start of a URL encoded body.
+fn urlencoded_token(body: &[u8]) -> Option<String> {
+    serde_urlencoded::from_bytes::<Vec<(String, String)>>(body)
+        .ok()?
+        .into_iter()
+        .find(|(key, _)| key == FIELD_NAME)
+        .map(|(_, value)| value)
+}
+
+/// Get the token a request was sent with. Reads the start of the body, and
+/// puts it back for the handler.
+async fn submitted_token(req: &mut ServiceRequest) -> Result<Option<String>, ActixError> {
+    if let Some(token) = req
+        .headers()
+        .get(HEADER_NAME)
+        .and_then(|value| value.to_str().ok())
+    {
+        return Ok(Some(token.to_string()));
+    }
+
+    let is_multipart: bool = req.content_type() == "multipart/form-data";
+    let mut payload = req.take_payload();
+    let mut prefix = BytesMut::new();
+    let mut token: Option<String> = None;
+    while prefix.len() < BODY_LIMIT {
+        match payload.next().await {
+            Some(chunk) => prefix.extend_from_slice(chunk?.as_ref()),
+            None => break,
+        }
+
+        // The field is at the start of multipart forms, so stop reading once
+        // it has been found. URL encoded bodies are small enough to read
+        // whole.
+        if is_multipart {
+            token = multipart_token(prefix.as_ref());
+            if token.is_some() {
+                break;
+            }
+        }
+    }
+
+    if !is_multipart {
+        token = urlencoded_token(prefix.as_ref());
+    }
+
+    // Put the body back together for the handler.
+    let prefix: Bytes = prefix.freeze();
+    req.set_payload(Payload::Stream(Box::pin(
+        stream::once(ready(Ok(prefix))).chain(payload),
+    )));
+    return Ok(token);
+}
+
+/// Fill the session's token in to an HTML response, and set the session
+/// cookie if it is new.
+async fn finish(
+    mut response: ServiceResponse,
+    session_id: String,
+    is_new: bool,
+) -> Result<ServiceResponse, ActixError> {
+    if is_new {
+        let cookie = Cookie::build(COOKIE_NAME, session_id.clone())
+            .path("/")
+            .secure(true)
+            .http_only(true)
+            .same_site(SameSite::Lax)
+            .finish();
+        response.response_mut().add_cookie(&cookie)?;
+    }
+
+    let is_html: bool = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| value.starts_with("text/html"));
+    if !is_html {
+        return Ok(response);
+    }
+
+    let body: ResponseBody<Body> = response.response_mut().take_body();
+    let html: String = body
+        .map_ok(|bytes| String::from_utf8_lossy(bytes.as_ref()).to_string())
+        .try_collect::<String>()
+        .await?;
+    let filled: String = html.replace(PLACEHOLDER, token_for(&session_id).as_str());
+    return Ok(response.map_body(|_, _| ResponseBody::Body(Body::from(filled))));
+}
+
+impl<S> Service for CsrfProtectionMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError> + 'static,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&mut self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&mut self, mut req: Self::Request) -> Self::Future {
+        let mut service: Rc<RefCell<S>> = self.service.clone();
+
+        Box::pin(async move {
+            let existing: Option<String> = req
+                .cookie(COOKIE_NAME)
+                .map(|cookie| cookie.value().to_string())
+                .filter(|id| {
+                    id.len() == SESSION_ID_LENGTH && id.chars().all(|c| c.is_ascii_alphanumeric())
+                });
+            let is_new: bool = existing.is_none();
+            let session_id: String = existing.unwrap_or_else(new_session_id);
+
+            if needs_token(&req) {
+                let given: Option<String> = submitted_token(&mut req).await?;
+                let valid: bool = !is_new
+                    && given.map_or(false, |given| {
+                        tokens_match(given.as_str(), token_for(&session_id).as_str())
+                    });
+
+                if !valid {
+                    warn!(
+                        "Rejected {} {} with a missing or invalid CSRF token",
+                        req.method(),
+                        req.path()
+                    );
+
+                    // This is outside the error renderer, so render the
+                    // error page here.
+                    let err = TelescopeError::CsrfFailure;
+                    let rendered: String = err.render_error_page(req.request()).await?;
+                    let page: HttpResponse = HttpResponseBuilder::new(err.status_code())
+                        .header(CONTENT_TYPE, "text/html;charset=UTF-8")
+                        .body(rendered);
+                    return finish(req.into_response(page), session_id, is_new).await;
+                }
+            }
+
+            let response: ServiceResponse = service.call(req).await?;
+            return finish(response, session_id, is_new).await;
+        })
+    }
+}
@@ -0,0 +1,79 @@
+//! Middleware that gives each request a [deadline](crate::web::deadline).
+//!
+//! The deadline depends on the route class (see [`RequestDeadlines`]). It is
+//! stored in the request extensions, and set for the handler so that upstream
+//! calls can find it.
+
+use crate::env::{global_config, RequestDeadlines};
+use crate::web::deadline::Deadline;
+use crate::web::middlewares::concurrency::RouteClass;
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::Error as ActixError;
+use actix_web::HttpMessage;
+use futures::future::{ok, Ready};
+use futures::task::{Context, Poll};
+use std::future::Future;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+/// The factory for the request deadline middleware.
+pub struct RequestDeadline;
+
+/// Middleware to set the deadline of each request.
+pub struct RequestDeadlineMiddleware<S> {
+    /// The next service in the chain.
+    service: S,
+}
+
+impl<S> Transform<S> for RequestDeadline
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError>,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Transform = RequestDeadlineMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(RequestDeadlineMiddleware { service })
+    }
+}
+
+/// The time requests of a class get.
+fn budget(class: RouteClass, deadlines: &RequestDeadlines) -> Duration {
+    let seconds: u64 = match class {
+        RouteClass::Light => deadlines.light,
+        RouteClass::Pages => deadlines.pages,
+        RouteClass::Exports => deadlines.exports,
+    };
+    return Duration::from_secs(seconds);
+}
+
+impl<S> Service for RequestDeadlineMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError>,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&mut self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&mut self, req: Self::Request) -> Self::Future {
+        let class = RouteClass::of(req.path());
+        let deadline = Deadline(Instant::now() + budget(class, &global_config().request_deadlines));
+        req.extensions_mut().insert(deadline);
+
+        // The handler runs when the service future is polled, so the deadline
+        // is set around that.
+        let service_response_future = self.service.call(req);
+        Box::pin(deadline.scope(service_response_future))
+    }
+}
@@ -1,4 +1,14 @@
 //! Telescope's middlewares.
 
+pub mod a11y_audit;
+pub mod admin_listener;
+pub mod api_rate_limit;
 pub mod authorization;
+pub mod catch_panic;
+pub mod concurrency;
+pub mod csrf;
+pub mod deadline;
 pub mod error_rendering;
+pub mod policy_gate;
+pub mod request_id;
+pub mod timing;
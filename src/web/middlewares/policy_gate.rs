@@ -0,0 +1,121 @@
+//! Middleware that sends logged-in users to accept the latest policies.
+//!
+//! Users who have not accepted the latest version of every policy are
+//! redirected to `/policies/accept`, which sends them back where they were
+//! going afterwards. Signing in and out, the policies themselves, account
+//! deletion, static files, and JSON endpoints are left open. Visitors who are
+//! not logged in are never redirected.
+
+use crate::web::middlewares::authorization::extract_user_id;
+use crate::web::policies;
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::Error as ActixError;
+use actix_web::http::header::LOCATION;
+use actix_web::HttpResponse;
+use futures::future::{ok, Ready};
+use futures::task::{Context, Poll};
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+/// Paths that can be used without accepting the latest policies.
+const OPEN_PREFIXES: &[&str] = &[
+    "/policies",
+    "/static/",
+    "/images/",
+    "/login",
+    "/logout",
+    "/register",
+    "/link/",
+    "/unlink/",
+    "/auth/",
+    "/profile_delete",
+    "/api/",
+    "/live/",
+];
+
+/// The factory for the policy gate middleware.
+pub struct PolicyGate;
+
+/// Middleware to redirect users who have not accepted the latest policies.
+pub struct PolicyGateMiddleware<S: 'static> {
+    /// The next service in the chain. Shared with response futures, which
+    /// call it after checking the user.
+    service: Rc<RefCell<S>>,
+}
+
+impl<S> Transform<S> for PolicyGate
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError> + 'static,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Transform = PolicyGateMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(PolicyGateMiddleware {
+            service: Rc::new(RefCell::new(service)),
+        })
+    }
+}
+
+impl<S> Service for PolicyGateMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError> + 'static,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&mut self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&mut self, req: Self::Request) -> Self::Future {
+        let mut service: Rc<RefCell<S>> = self.service.clone();
+
+        Box::pin(async move {
+            let path: &str = req.path();
+            if OPEN_PREFIXES.iter().any(|prefix| path.starts_with(prefix)) {
+                return service.call(req).await;
+            }
+
+            // Visitors who are not logged in are let through.
+            let user_id = match extract_user_id(&req).await {
+                Ok(user_id) => user_id,
+                Err(_) => return service.call(req).await,
+            };
+
+            match policies::pending(user_id).await {
+                Ok(pending) if !pending.is_empty() => {
+                    let next: String = match req.query_string() {
+                        "" => path.to_string(),
+                        query => format!("{}?{}", path, query),
+                    };
+                    let location: String = format!(
+                        "/policies/accept?{}",
+                        serde_urlencoded::to_string(&[("next", next)]).unwrap_or_default()
+                    );
+
+                    return Ok(req
+                        .into_response(HttpResponse::Found().header(LOCATION, location).finish()));
+                }
+
+                Ok(_) => {}
+
+                // Don't lock everyone out if the API is having trouble. The
+                // page itself will most likely fail too.
+                Err(e) => error!("Could not check policy acceptance for {}: {}", user_id, e),
+            }
+
+            return service.call(req).await;
+        })
+    }
+}
@@ -0,0 +1,104 @@
+//! Middleware that gives each request an ID.
+//!
+//! The ID is taken from the `X-Request-Id` header if a proxy in front of
+//! Telescope already set one, and made up otherwise. It is set on the request
+//! for the access log, on the response so clients can report it, and as the
+//! [logging span](crate::logging::scope) of the handler.
+
+use crate::logging::{self, RequestSpan};
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::Error as ActixError;
+use actix_web::http::header::{HeaderName, HeaderValue};
+use futures::future::{ok, Ready};
+use futures::task::{Context, Poll};
+use std::future::Future;
+use std::pin::Pin;
+use uuid::Uuid;
+
+/// The header the request ID is read from and sent back in.
+pub const REQUEST_ID_HEADER: &'static str = "x-request-id";
+
+/// The longest request ID taken from a proxy.
+pub const MAX_ID_LENGTH: usize = 64;
+
+/// The factory for the request ID middleware.
+pub struct RequestId;
+
+/// Middleware to give each request an ID.
+pub struct RequestIdMiddleware<S> {
+    /// The next service in the chain.
+    service: S,
+}
+
+impl<S> Transform<S> for RequestId
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError>,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Transform = RequestIdMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(RequestIdMiddleware { service })
+    }
+}
+
+/// The ID a proxy gave a request, if it looks safe to log.
+fn forwarded_id(req: &ServiceRequest) -> Option<String> {
+    req.headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= MAX_ID_LENGTH
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+        .map(str::to_string)
+}
+
+impl<S> Service for RequestIdMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError>,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&mut self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&mut self, mut req: Self::Request) -> Self::Future {
+        let request_id: String =
+            forwarded_id(&req).unwrap_or_else(|| Uuid::new_v4().to_simple().to_string());
+        // Only letters, digits, dashes, and underscores, so this can't fail.
+        let header_value =
+            HeaderValue::from_str(&request_id).expect("Request ID is not a valid header");
+        let header_name = HeaderName::from_static(REQUEST_ID_HEADER);
+        req.headers_mut()
+            .insert(header_name.clone(), header_value.clone());
+
+        let span = RequestSpan {
+            request_id,
+            method: req.method().to_string(),
+            path: req.path().to_string(),
+        };
+
+        // The handler runs when the service future is polled, so the span is
+        // set around that.
+        let service_response_future = self.service.call(req);
+        Box::pin(async move {
+            let mut res: ServiceResponse = logging::scope(span, service_response_future).await?;
+            res.headers_mut().insert(header_name, header_value);
+            return Ok(res);
+        })
+    }
+}
@@ -0,0 +1,107 @@
+//! Middleware that times each request and logs the ones that run over their
+//! [load budget](crate::env::LoadBudgets).
+//!
+//! Time is split into upstream calls and rendering by the
+//! [request timer](crate::web::timing), which is set for the handler here.
+
+use crate::env::{global_config, LoadBudgets};
+use crate::web::middlewares::concurrency::RouteClass;
+use crate::web::timing::{self, RequestTimer};
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::Error as ActixError;
+use futures::future::{ok, Ready};
+use futures::task::{Context, Poll};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// The route name used for requests that did not match any route, so that
+/// made up paths do not each get their own totals.
+const UNMATCHED: &'static str = "unmatched";
+
+/// The factory for the request timing middleware.
+pub struct RequestTiming;
+
+/// Middleware to time each request.
+pub struct RequestTimingMiddleware<S> {
+    /// The next service in the chain.
+    service: S,
+}
+
+impl<S> Transform<S> for RequestTiming
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError>,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Transform = RequestTimingMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(RequestTimingMiddleware { service })
+    }
+}
+
+/// The time requests of a class should take.
+fn budget(class: RouteClass, budgets: &LoadBudgets) -> Duration {
+    let millis: u64 = match class {
+        RouteClass::Light => budgets.light,
+        RouteClass::Pages => budgets.pages,
+        RouteClass::Exports => budgets.exports,
+    };
+    return Duration::from_millis(millis);
+}
+
+impl<S> Service for RequestTimingMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse, Error = ActixError>,
+    S::Future: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse;
+    type Error = ActixError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&mut self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&mut self, req: Self::Request) -> Self::Future {
+        let budget = budget(RouteClass::of(req.path()), &global_config().load_budgets);
+        let method = req.method().to_string();
+        let timer = Rc::new(RequestTimer::default());
+        let start = Instant::now();
+
+        let service_response_future = self.service.call(req);
+        Box::pin(async move {
+            let result = timer.clone().scope(service_response_future).await;
+            let wall: Duration = start.elapsed();
+
+            // Errors that were not turned into responses have no request to
+            // find the route of, and are counted as unmatched.
+            let pattern: Option<String> = result
+                .as_ref()
+                .ok()
+                .and_then(|res| res.request().match_pattern());
+            let route: String = format!("{} {}", method, pattern.as_deref().unwrap_or(UNMATCHED));
+
+            let over_budget: bool = wall > budget;
+            if over_budget {
+                warn!(
+                    "{} took {} ms, over its budget of {} ms ({} ms upstream, {} ms rendering).",
+                    route,
+                    wall.as_millis(),
+                    budget.as_millis(),
+                    timer.upstream().as_millis(),
+                    timer.render().as_millis()
+                );
+            }
+            timing::record(route, wall, &timer, over_budget);
+            return result;
+        })
+    }
+}
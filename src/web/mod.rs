@@ -2,9 +2,31 @@
 
 use reqwest::header::HeaderValue;
 
+pub mod a11y;
+pub mod api_keys;
+pub mod cluster;
 pub mod csrf;
+pub mod deadline;
+pub mod embed;
+pub mod ics;
+pub mod idempotency;
+pub mod images;
+pub mod listeners;
+pub mod live;
 pub mod middlewares;
+pub mod pdf;
+pub mod policies;
+pub mod search;
 pub mod services;
+pub mod sessions;
+#[cfg(unix)]
+pub mod systemd;
+pub mod timing;
+pub mod tls;
+pub mod uploads;
+pub mod validation;
+pub mod webhooks;
+pub mod xlsx;
 
 lazy_static! {
     static ref TELESCOPE_USER_AGENT: String =
@@ -16,3 +38,15 @@ pub fn telescope_ua() -> HeaderValue {
     HeaderValue::from_str(TELESCOPE_USER_AGENT.as_str())
         .expect("Could not make Telescope User-Agent")
 }
+
+/// Compare a given token or secret to the expected one without stopping at
+/// the first difference, so how much of it matched cannot be learned from
+/// how long the comparison takes.
+pub fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
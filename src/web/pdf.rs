@@ -0,0 +1,238 @@
+//! Minimal PDF layout for certificates and reports.
+//!
+//! Documents are laid out top to bottom with a cursor, using the PDF built-in
+//! Helvetica and Times fonts so that no font files need to be shipped. Built-in
+//! fonts only cover Windows-1252 characters; anything else is dropped.
+
+use crate::error::TelescopeError;
+use actix_web::http::header::{self, ContentDisposition, DispositionParam, DispositionType};
+use actix_web::HttpResponse;
+use printpdf::{
+    BuiltinFont, Color, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference,
+    PdfLayerReference, Point, Rgb,
+};
+use std::io::BufWriter;
+
+/// US Letter width in millimeters.
+pub const LETTER_WIDTH: f64 = 215.9;
+
+/// US Letter height in millimeters.
+pub const LETTER_HEIGHT: f64 = 279.4;
+
+/// Page margin in millimeters.
+const MARGIN: f64 = 18.0;
+
+/// Millimeters per point.
+const MM_PER_PT: f64 = 0.352_778;
+
+/// Rough average glyph width of the built-in fonts as a fraction of the font
+/// size. Built-in fonts have no metrics available here, so this is only used
+/// to center short lines like names and titles.
+const AVERAGE_GLYPH_WIDTH: f64 = 0.5;
+
+/// Line height as a multiple of the font size.
+const LINE_HEIGHT: f64 = 1.4;
+
+/// Font styles available to documents.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Style {
+    Regular,
+    Bold,
+    /// Serif display font, used for certificates.
+    Serif,
+    SerifBold,
+}
+
+/// A PDF document being laid out.
+pub struct PdfBuilder {
+    document: PdfDocumentReference,
+    layer: PdfLayerReference,
+    fonts: [IndirectFontRef; 4],
+    width: f64,
+    height: f64,
+    /// Distance of the cursor from the bottom of the page in millimeters.
+    cursor: f64,
+}
+
+/// Convert a printpdf error into a telescope error.
+fn pdf_error(e: printpdf::Error) -> TelescopeError {
+    TelescopeError::ise(format!("Could not generate PDF: {:?}", e))
+}
+
+impl PdfBuilder {
+    /// Create a document with one empty page of the given size in millimeters.
+    pub fn new(title: &str, width: f64, height: f64) -> Result<Self, TelescopeError> {
+        let (document, page, layer) = PdfDocument::new(title, Mm(width), Mm(height), "Content");
+        let fonts = [
+            document
+                .add_builtin_font(BuiltinFont::Helvetica)
+                .map_err(pdf_error)?,
+            document
+                .add_builtin_font(BuiltinFont::HelveticaBold)
+                .map_err(pdf_error)?,
+            document
+                .add_builtin_font(BuiltinFont::TimesRoman)
+                .map_err(pdf_error)?,
+            document
+                .add_builtin_font(BuiltinFont::TimesBold)
+                .map_err(pdf_error)?,
+        ];
+        let layer = document.get_page(page).get_layer(layer);
+
+        return Ok(Self {
+            document,
+            layer,
+            fonts,
+            width,
+            height,
+            cursor: height - MARGIN,
+        });
+    }
+
+    /// The font for a style.
+    fn font(&self, style: Style) -> &IndirectFontRef {
+        match style {
+            Style::Regular => &self.fonts[0],
+            Style::Bold => &self.fonts[1],
+            Style::Serif => &self.fonts[2],
+            Style::SerifBold => &self.fonts[3],
+        }
+    }
+
+    /// Start a new page and move the cursor to its top.
+    pub fn new_page(&mut self) {
+        let (page, layer) = self
+            .document
+            .add_page(Mm(self.width), Mm(self.height), "Content");
+        self.layer = self.document.get_page(page).get_layer(layer);
+        self.cursor = self.height - MARGIN;
+    }
+
+    /// Start a new page if less than `needed` millimeters are left on this one.
+    fn ensure_space(&mut self, needed: f64) {
+        if self.cursor - needed < MARGIN {
+            self.new_page();
+        }
+    }
+
+    /// Move the cursor down.
+    pub fn space(&mut self, mm: f64) {
+        self.cursor -= mm;
+    }
+
+    /// Move the cursor to a fraction of the page height from the top.
+    pub fn move_to(&mut self, fraction_from_top: f64) {
+        self.cursor = self.height * (1.0 - fraction_from_top);
+    }
+
+    /// Write a line of text at the left margin.
+    pub fn line(&mut self, text: &str, size: f64, style: Style) {
+        let line_height = size * MM_PER_PT * LINE_HEIGHT;
+        self.ensure_space(line_height);
+        self.cursor -= line_height;
+        self.layer
+            .use_text(text, size, Mm(MARGIN), Mm(self.cursor), self.font(style));
+    }
+
+    /// Write a line of text centered on the page.
+    pub fn centered(&mut self, text: &str, size: f64, style: Style) {
+        let line_height = size * MM_PER_PT * LINE_HEIGHT;
+        self.ensure_space(line_height);
+        self.cursor -= line_height;
+        let width = text.chars().count() as f64 * size * AVERAGE_GLYPH_WIDTH * MM_PER_PT;
+        let x = ((self.width - width) / 2.0).max(MARGIN);
+        self.layer
+            .use_text(text, size, Mm(x), Mm(self.cursor), self.font(style));
+    }
+
+    /// Write a row of cells. Each cell is given as its text and its width in
+    /// millimeters. Text that does not fit is cut off with an ellipsis.
+    pub fn row(&mut self, cells: &[(&str, f64)], size: f64, style: Style) {
+        let line_height = size * MM_PER_PT * LINE_HEIGHT;
+        self.ensure_space(line_height);
+        self.cursor -= line_height;
+
+        let mut x = MARGIN;
+        for (text, width) in cells {
+            let fits = (width / (size * AVERAGE_GLYPH_WIDTH * MM_PER_PT)).floor() as usize;
+            let text: String = if text.chars().count() > fits && fits > 1 {
+                let mut cut: String = text.chars().take(fits - 1).collect();
+                cut.push('…');
+                cut
+            } else {
+                text.to_string()
+            };
+            self.layer
+                .use_text(text, size, Mm(x), Mm(self.cursor), self.font(style));
+            x += width;
+        }
+    }
+
+    /// Draw a horizontal rule across the content width.
+    pub fn rule(&mut self) {
+        self.ensure_space(2.0);
+        self.cursor -= 1.0;
+        self.layer
+            .set_outline_color(Color::Rgb(Rgb::new(0.6, 0.6, 0.6, None)));
+        self.layer.set_outline_thickness(0.5);
+        self.layer.add_shape(Line {
+            points: vec![
+                (Point::new(Mm(MARGIN), Mm(self.cursor)), false),
+                (Point::new(Mm(self.width - MARGIN), Mm(self.cursor)), false),
+            ],
+            is_closed: false,
+            has_fill: false,
+            has_stroke: true,
+            is_clipping_path: false,
+        });
+        self.cursor -= 1.0;
+    }
+
+    /// Draw a border inset from the page edges, for certificates.
+    pub fn border(&mut self, inset: f64, thickness: f64) {
+        let (left, bottom) = (inset, inset);
+        let (right, top) = (self.width - inset, self.height - inset);
+        self.layer
+            .set_outline_color(Color::Rgb(Rgb::new(0.2, 0.2, 0.4, None)));
+        self.layer.set_outline_thickness(thickness);
+        self.layer.add_shape(Line {
+            points: vec![
+                (Point::new(Mm(left), Mm(bottom)), false),
+                (Point::new(Mm(right), Mm(bottom)), false),
+                (Point::new(Mm(right), Mm(top)), false),
+                (Point::new(Mm(left), Mm(top)), false),
+            ],
+            is_closed: true,
+            has_fill: false,
+            has_stroke: true,
+            is_clipping_path: false,
+        });
+    }
+
+    /// Serialize the document.
+    pub fn finish(self) -> Result<Vec<u8>, TelescopeError> {
+        let mut writer = BufWriter::new(Vec::new());
+        self.document.save(&mut writer).map_err(pdf_error)?;
+        writer
+            .into_inner()
+            .map_err(|e| TelescopeError::ise(format!("Could not write PDF: {}", e)))
+    }
+
+    /// Finish the document into a file download response.
+    pub fn into_response(
+        self,
+        filename: impl Into<String>,
+    ) -> Result<HttpResponse, TelescopeError> {
+        let body: Vec<u8> = self.finish()?;
+        return Ok(HttpResponse::Ok()
+            .set_header(header::CONTENT_TYPE, "application/pdf")
+            .set_header(
+                header::CONTENT_DISPOSITION,
+                ContentDisposition {
+                    disposition: DispositionType::Attachment,
+                    parameters: vec![DispositionParam::Filename(filename.into())],
+                },
+            )
+            .body(body));
+    }
+}
@@ -0,0 +1,91 @@
+//! Policy acceptance tracking.
+//!
+//! Logged-in members have to accept the latest version of every policy (the
+//! terms of service and the code of conduct) before using the site. The
+//! [policy gate] sends them to the acceptance page until they have.
+//!
+//! The gate runs on every request, so the latest versions are cached for a
+//! minute, and users are remembered in memory once they have accepted them.
+//! Publishing a new version invalidates the cache, which makes everyone accept
+//! it the next time they load a page.
+//!
+//! [policy gate]: crate::web::middlewares::policy_gate
+
+use crate::api::rcos::policies::get::{
+    latest_policies::PolicyInfo, LatestPolicies, PolicyAcceptances,
+};
+use crate::api::rcos::policies::mutations::AcceptPolicy;
+use crate::error::TelescopeError;
+use crate::web::cluster::{self, Broadcast};
+use dashmap::DashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// How long the latest policy versions are cached.
+const LATEST_TTL: Duration = Duration::from_secs(60);
+
+lazy_static! {
+    /// The latest version of every policy, and when they were fetched.
+    static ref LATEST: RwLock<Option<(Instant, Vec<PolicyInfo>)>> = RwLock::new(None);
+
+    /// Users known to have accepted every policy, with the policy versions
+    /// they were checked against.
+    static ref ACCEPTED: Arc<DashMap<Uuid, Vec<i64>>> = Arc::new(DashMap::new());
+}
+
+/// Get the latest version of every policy.
+pub async fn latest() -> Result<Vec<PolicyInfo>, TelescopeError> {
+    if let Some((fetched, policies)) = LATEST.read().unwrap().as_ref() {
+        if fetched.elapsed() < LATEST_TTL {
+            return Ok(policies.clone());
+        }
+    }
+
+    let policies: Vec<PolicyInfo> = LatestPolicies::get().await?;
+    *LATEST.write().unwrap() = Some((Instant::now(), policies.clone()));
+    return Ok(policies);
+}
+
+/// Forget the cached policy versions on every replica, after a new one is
+/// published.
+pub fn invalidate() {
+    cluster::broadcast(Broadcast::PoliciesChanged);
+}
+
+/// Forget the cached policy versions of this replica.
+pub(crate) fn clear() {
+    *LATEST.write().unwrap() = None;
+}
+
+/// Get the latest policy versions a user has not accepted yet.
+pub async fn pending(user_id: Uuid) -> Result<Vec<PolicyInfo>, TelescopeError> {
+    let latest: Vec<PolicyInfo> = latest().await?;
+    let latest_ids: Vec<i64> = latest.iter().map(|policy| policy.policy_id).collect();
+
+    if ACCEPTED
+        .get(&user_id)
+        .map_or(false, |accepted| *accepted == latest_ids)
+    {
+        return Ok(Vec::new());
+    }
+
+    let accepted: Vec<i64> = PolicyAcceptances::get(user_id, latest_ids.clone()).await?;
+    let pending: Vec<PolicyInfo> = latest
+        .into_iter()
+        .filter(|policy| !accepted.contains(&policy.policy_id))
+        .collect();
+
+    if pending.is_empty() {
+        ACCEPTED.insert(user_id, latest_ids);
+    }
+    return Ok(pending);
+}
+
+/// Record that a user accepted some policy versions.
+pub async fn accept(user_id: Uuid, policy_ids: Vec<i64>) -> Result<(), TelescopeError> {
+    AcceptPolicy::execute(user_id, policy_ids).await?;
+    // Checked again on the next request.
+    ACCEPTED.remove(&user_id);
+    return Ok(());
+}
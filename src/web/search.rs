@@ -0,0 +1,360 @@
+//! Full-text search over meetings and status updates.
+//!
+//! The index is an embedded tantivy index kept in the directory set by
+//! `search_index_dir` in the config. It is rebuilt from the RCOS API by the
+//! [search indexer job](crate::jobs::search_index), so it can be deleted at
+//! any time. Every indexed document stores enough to check who may see it, and
+//! results are filtered for the viewer after searching.
+
+use crate::api::rcos::meetings::authorization_for::UserMeetingAuthorization;
+use crate::api::rcos::meetings::MeetingType;
+use crate::api::rcos::moderation::ContentRef;
+use crate::api::rcos::search_index::search_documents::ResponseData;
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use actix_web::web::block;
+use std::fs;
+use std::sync::Mutex;
+use tantivy::collector::TopDocs;
+use tantivy::directory::MmapDirectory;
+use tantivy::query::QueryParser;
+use tantivy::schema::{Field, Schema, STORED, STRING, TEXT};
+use tantivy::{doc, Document, Index, IndexReader, IndexWriter, ReloadPolicy, SnippetGenerator};
+
+/// Memory given to the index writer, in bytes.
+const WRITER_HEAP_SIZE: usize = 32 * 1024 * 1024;
+
+/// How many documents to look at before filtering out the ones the viewer
+/// cannot see.
+const CANDIDATE_LIMIT: usize = 200;
+
+/// The most results shown for a search.
+const RESULT_LIMIT: usize = 25;
+
+/// The most characters in a highlighted snippet.
+const SNIPPET_LENGTH: usize = 240;
+
+/// Document kind of meetings.
+const MEETING: &'static str = "meeting";
+
+/// Document kind of status update submissions.
+const STATUS_UPDATE: &'static str = "status_update";
+
+/// The fields of an indexed document.
+struct Fields {
+    /// Either [`MEETING`] or [`STATUS_UPDATE`].
+    kind: Field,
+    title: Field,
+    body: Field,
+    /// Path of the page the result links to.
+    url: Field,
+    /// RFC 3339 timestamp shown with the result.
+    date: Field,
+    /// Meeting type as named in the API. Empty for status updates.
+    meeting_type: Field,
+    /// "true" for draft meetings.
+    draft: Field,
+    /// Author of a status update. Empty for meetings.
+    owner: Field,
+    /// Key to report a status update with. Empty for meetings, which are not
+    /// user-generated.
+    report_key: Field,
+}
+
+/// The open search index.
+struct SearchIndex {
+    index: Index,
+    reader: IndexReader,
+    writer: Mutex<IndexWriter>,
+    fields: Fields,
+}
+
+lazy_static! {
+    /// The global search index. `None` if it could not be opened, in which
+    /// case search is unavailable but the rest of the site works.
+    static ref SEARCH_INDEX: Option<SearchIndex> = SearchIndex::open()
+        .map_err(|e| error!("Could not open the search index: {}", e))
+        .ok();
+}
+
+/// A search result.
+#[derive(Serialize, Debug, Clone)]
+pub struct SearchHit {
+    /// Either "meeting" or "status_update".
+    kind: String,
+    /// The title with matching terms highlighted. Escaped HTML.
+    title_html: String,
+    /// An excerpt of the body with matching terms highlighted. Escaped HTML.
+    snippet_html: String,
+    url: String,
+    date: String,
+    /// Key to report the result with, if it can be reported.
+    report_key: String,
+}
+
+impl SearchIndex {
+    /// Build the schema of the index.
+    fn schema() -> (Schema, Fields) {
+        let mut builder = Schema::builder();
+        let fields = Fields {
+            kind: builder.add_text_field("kind", STRING | STORED),
+            title: builder.add_text_field("title", TEXT | STORED),
+            body: builder.add_text_field("body", TEXT | STORED),
+            url: builder.add_text_field("url", STORED),
+            date: builder.add_text_field("date", STORED),
+            meeting_type: builder.add_text_field("meeting_type", STORED),
+            draft: builder.add_text_field("draft", STORED),
+            owner: builder.add_text_field("owner", STORED),
+            report_key: builder.add_text_field("report_key", STORED),
+        };
+        return (builder.build(), fields);
+    }
+
+    /// Open the index on disk, or create it. An index with a different schema
+    /// is thrown away, since it is rebuilt in the background anyway.
+    fn open() -> tantivy::Result<Self> {
+        let dir = global_config().search_index_dir.clone();
+        let (schema, fields) = Self::schema();
+
+        fs::create_dir_all(&dir)?;
+        let index = match Index::open_or_create(MmapDirectory::open(&dir)?, schema.clone()) {
+            Ok(index) => index,
+            Err(e) => {
+                warn!("Recreating search index at {}: {}", dir.display(), e);
+                fs::remove_dir_all(&dir)?;
+                fs::create_dir_all(&dir)?;
+                Index::create_in_dir(&dir, schema)?
+            }
+        };
+
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        let writer = index.writer(WRITER_HEAP_SIZE)?;
+
+        return Ok(SearchIndex {
+            index,
+            reader,
+            writer: Mutex::new(writer),
+            fields,
+        });
+    }
+
+    /// Turn API data into index documents.
+    fn documents(&self, data: ResponseData) -> Vec<Document> {
+        let f = &self.fields;
+        let mut documents: Vec<Document> = Vec::new();
+
+        for meeting in data.meetings {
+            let title: String = meeting.title.unwrap_or_else(|| {
+                format!(
+                    "RCOS {} - {}",
+                    meeting.type_,
+                    meeting.start_date_time.format("%B %_d, %Y")
+                )
+            });
+
+            documents.push(doc!(
+                f.kind => MEETING,
+                f.title => title,
+                f.body => meeting.description,
+                f.url => format!("/meeting/{}", meeting.meeting_id),
+                f.date => meeting.start_date_time.to_rfc3339(),
+                f.meeting_type => type_name(meeting.type_),
+                f.draft => meeting.is_draft.to_string(),
+                f.owner => "",
+                f.report_key => "",
+            ));
+        }
+
+        for submission in data.status_update_submissions {
+            let update = submission.status_update;
+            let title: String = format!(
+                "{} {}: {} ({})",
+                submission.user.first_name,
+                submission.user.last_name,
+                update.title.unwrap_or("Status Update".into()),
+                update.semester.title
+            );
+
+            let mut body: String = submission.this_week;
+            for part in [submission.next_week, submission.blockers] {
+                body.push_str("\n\n");
+                body.push_str(part.as_str());
+            }
+
+            let report_key: String = ContentRef::StatusUpdate {
+                status_update_id: submission.status_update_id,
+                user_id: submission.user_id,
+            }
+            .key();
+
+            documents.push(doc!(
+                f.kind => STATUS_UPDATE,
+                f.title => title,
+                f.body => body,
+                f.url => format!("/user/{}", submission.user_id),
+                f.date => submission.created_at.to_rfc3339(),
+                f.meeting_type => "",
+                f.draft => "false",
+                f.owner => submission.user_id.to_string(),
+                f.report_key => report_key,
+            ));
+        }
+
+        return documents;
+    }
+
+    /// Replace every document in the index.
+    fn replace_all(&self, documents: Vec<Document>) -> tantivy::Result<usize> {
+        let count: usize = documents.len();
+        let mut writer = self.writer.lock().unwrap();
+        writer.delete_all_documents()?;
+        for document in documents {
+            writer.add_document(document)?;
+        }
+        writer.commit()?;
+        self.reader.reload()?;
+        return Ok(count);
+    }
+
+    /// Check if a viewer may see an indexed document.
+    fn can_see(&self, document: &Document, viewer: &UserMeetingAuthorization) -> bool {
+        let text = |field: Field| {
+            document
+                .get_first(field)
+                .and_then(|value| value.as_text())
+                .unwrap_or_default()
+        };
+
+        match text(self.fields.kind) {
+            MEETING => {
+                let visible_type: bool = parse_type(text(self.fields.meeting_type))
+                    .map_or(false, |ty| viewer.can_view(ty));
+                let is_draft: bool = text(self.fields.draft) == "true";
+                visible_type && (!is_draft || viewer.can_view_drafts())
+            }
+            // Status updates are seen by their author and coordinators.
+            STATUS_UPDATE => {
+                viewer.can_view_drafts()
+                    || viewer
+                        .user_id
+                        .map_or(false, |id| id.to_string() == text(self.fields.owner))
+            }
+            _ => false,
+        }
+    }
+
+    /// Search the index.
+    fn search(
+        &self,
+        query: &str,
+        viewer: &UserMeetingAuthorization,
+    ) -> Result<Vec<SearchHit>, TelescopeError> {
+        let f = &self.fields;
+        let mut parser = QueryParser::for_index(&self.index, vec![f.title, f.body]);
+        parser.set_conjunction_by_default();
+        let query = parser
+            .parse_query(query)
+            .map_err(|e| TelescopeError::BadRequest {
+                header: "Invalid Search".into(),
+                message: format!("Could not understand this search: {}", e),
+                show_status_code: false,
+            })?;
+
+        let searcher = self.reader.searcher();
+        let candidates = searcher
+            .search(&query, &TopDocs::with_limit(CANDIDATE_LIMIT))
+            .map_err(search_error)?;
+
+        let title_snippets =
+            SnippetGenerator::create(&searcher, &*query, f.title).map_err(search_error)?;
+        let mut body_snippets =
+            SnippetGenerator::create(&searcher, &*query, f.body).map_err(search_error)?;
+        body_snippets.set_max_num_chars(SNIPPET_LENGTH);
+
+        let mut hits: Vec<SearchHit> = Vec::new();
+        for (_score, address) in candidates {
+            let document: Document = searcher.doc(address).map_err(search_error)?;
+            if !self.can_see(&document, viewer) {
+                continue;
+            }
+
+            let text = |field: Field| {
+                document
+                    .get_first(field)
+                    .and_then(|value| value.as_text())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+
+            // Titles without a match are shown whole.
+            let title_snippet = title_snippets.snippet_from_doc(&document);
+            let title_html: String = if title_snippet.highlighted().is_empty() {
+                v_htmlescape::escape(text(f.title).as_str()).to_string()
+            } else {
+                title_snippet.to_html()
+            };
+
+            hits.push(SearchHit {
+                kind: text(f.kind),
+                title_html,
+                snippet_html: body_snippets.snippet_from_doc(&document).to_html(),
+                url: text(f.url),
+                date: text(f.date),
+                report_key: text(f.report_key),
+            });
+
+            if hits.len() == RESULT_LIMIT {
+                break;
+            }
+        }
+
+        return Ok(hits);
+    }
+}
+
+/// The name of a meeting type in the API, which is what gets indexed.
+fn type_name(meeting_type: MeetingType) -> String {
+    json!(meeting_type).as_str().unwrap_or_default().to_string()
+}
+
+/// Read an indexed meeting type.
+fn parse_type(name: &str) -> Option<MeetingType> {
+    serde_json::from_value(json!(name)).ok()
+}
+
+/// Convert an error from the index.
+fn search_error(e: tantivy::TantivyError) -> TelescopeError {
+    TelescopeError::ise(format!("Search index error: {}", e))
+}
+
+/// Get the search index, or an error if it could not be opened.
+fn search_index() -> Result<&'static SearchIndex, TelescopeError> {
+    SEARCH_INDEX
+        .as_ref()
+        .ok_or(TelescopeError::ise("The search index is not available."))
+}
+
+/// Replace the contents of the index with fresh data from the API. Return how
+/// many documents were indexed.
+pub async fn rebuild(data: ResponseData) -> Result<usize, TelescopeError> {
+    let count = block(move || {
+        let index = search_index()?;
+        let documents: Vec<Document> = index.documents(data);
+        index.replace_all(documents).map_err(search_error)
+    })
+    .await?;
+    return Ok(count);
+}
+
+/// Search meetings and status updates, returning only what the viewer can
+/// see.
+pub async fn search(
+    query: String,
+    viewer: UserMeetingAuthorization,
+) -> Result<Vec<SearchHit>, TelescopeError> {
+    let hits = block(move || search_index()?.search(query.as_str(), &viewer)).await?;
+    return Ok(hits);
+}
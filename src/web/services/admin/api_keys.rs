@@ -0,0 +1,163 @@
+//! Coordinator management of public API keys and their rate limit tiers.
+//! These are registered in the `/admin/api-keys` scope.
+
+use crate::api::rcos::api_keys::{ApiKeys, CreateApiKey, RevokeApiKey, SetApiKeyTier};
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::api_keys::{self, RateTier};
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+
+/// The path to the key list from the templates directory.
+const TEMPLATE_PATH: &'static str = "admin/api_keys";
+
+/// The most characters a key's name can have.
+const MAX_NAME_LENGTH: usize = 200;
+
+/// Register API key services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(index)
+        .service(create)
+        .service(set_tier)
+        .service(revoke);
+}
+
+/// Form submitted to make a key.
+#[derive(Serialize, Deserialize, Debug)]
+struct KeyForm {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    tier: String,
+}
+
+/// Form submitted to change the tier of a key.
+#[derive(Deserialize, Debug)]
+struct TierForm {
+    tier: String,
+}
+
+/// Redirect to the key list.
+fn to_index() -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, "/admin/api-keys")
+        .finish()
+}
+
+/// Error for a key that does not exist.
+fn no_such_key() -> TelescopeError {
+    TelescopeError::resource_not_found(
+        "API Key Not Found",
+        "Could not find an API key with this ID.",
+    )
+}
+
+/// Error for a tier keys cannot be given.
+fn bad_tier() -> TelescopeError {
+    TelescopeError::BadRequest {
+        header: "Unknown Tier".into(),
+        message: "API keys can only be given the standard or elevated tier.".into(),
+        show_status_code: false,
+    }
+}
+
+/// The key list with the form to make another.
+async fn make_index() -> Result<Template, TelescopeError> {
+    let limits = global_config().api_rate_limits;
+    let tiers: Vec<_> = RateTier::KEY_TIERS
+        .iter()
+        .map(|tier| json!({ "name": tier.name(), "limit": tier.limit(&limits) }))
+        .collect();
+
+    let mut template = Template::new(TEMPLATE_PATH);
+    template
+        .set_field("keys", ApiKeys::get().await?)?
+        .set_field("tiers", tiers)?
+        .set_field("anonymous_limit", limits.anonymous)?
+        .set_field("max_name_length", MAX_NAME_LENGTH)?;
+    return Ok(template);
+}
+
+/// Every key, and the form to make another.
+#[get("")]
+async fn index(req: HttpRequest) -> Result<Page, TelescopeError> {
+    make_index().await?.in_page(&req, "API Keys").await
+}
+
+/// Make a key. The key is only shown on the page this returns, since only its
+/// hash is stored.
+#[post("")]
+async fn create(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Form(submitted): Form<KeyForm>,
+) -> Result<Page, TelescopeError> {
+    let mut form = make_index().await?;
+    form.set_field("selections", &submitted)?;
+    let name: &str = submitted.name.trim();
+    if name.is_empty() {
+        form.set_issue("name", "Name cannot be empty.");
+    } else if name.chars().count() > MAX_NAME_LENGTH {
+        form.set_issue(
+            "name",
+            format!("Names can be at most {} characters long.", MAX_NAME_LENGTH),
+        );
+    }
+
+    let tier: Option<RateTier> = RateTier::parse_key_tier(submitted.tier.as_str());
+    if tier.is_none() {
+        form.set_issue("tier", "Pick a tier for the key.");
+    }
+
+    let tier: RateTier = match tier {
+        Some(tier) if form["issues"] == json!(null) => tier,
+        _ => {
+            let page = form.in_page(&req, "API Keys").await?;
+            return Err(TelescopeError::InvalidForm(page));
+        }
+    };
+
+    let key: String = api_keys::new_key();
+    CreateApiKey::execute(
+        name.to_string(),
+        api_keys::shown_prefix(key.as_str()),
+        api_keys::hash(key.as_str()),
+        tier.name().to_string(),
+        auth.get_user_id_or_error().await?,
+    )
+    .await?;
+
+    // Show the new key, and an empty form for the next one.
+    let mut template = make_index().await?;
+    template.set_field("created_key", key)?;
+    return template.in_page(&req, "API Keys").await;
+}
+
+/// Change the tier of a key.
+#[post("/{key_id}/tier")]
+async fn set_tier(
+    Path(key_id): Path<i64>,
+    Form(form): Form<TierForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let tier: RateTier = RateTier::parse_key_tier(form.tier.as_str()).ok_or_else(bad_tier)?;
+    if !SetApiKeyTier::execute(key_id, tier.name().to_string()).await? {
+        return Err(no_such_key());
+    }
+    api_keys::forget_keys();
+    return Ok(to_index());
+}
+
+/// Stop accepting a key.
+#[post("/{key_id}/revoke")]
+async fn revoke(Path(key_id): Path<i64>) -> Result<HttpResponse, TelescopeError> {
+    if !RevokeApiKey::execute(key_id).await? {
+        return Err(no_such_key());
+    }
+    api_keys::forget_keys();
+    return Ok(to_index());
+}
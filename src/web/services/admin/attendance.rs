@@ -0,0 +1,177 @@
+//! Attendance policy and report for coordinators.
+//!
+//! Open to current coordinators as well as admins. Each semester's page sets
+//! its attendance policy, lists the members who do not meet it yet, and has
+//! the excuse requests waiting for a decision.
+
+use crate::api::rcos::attendance::policy::{
+    attendance_report::AttendanceReportEnrollmentsUser, AttendanceReport, DecideExcuse,
+    ExcuseStatus, Policy, SetAttendancePolicy, Standing,
+};
+use crate::api::rcos::prelude::{MeetingId, SemesterId};
+use crate::api::rcos::semesters::current::info::CurrentSemesters;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use uuid::Uuid;
+
+/// The path to the attendance report template from the templates directory.
+const TEMPLATE_PATH: &'static str = "admin/attendance";
+
+/// Register attendance report services. These are registered in the
+/// `/admin/attendance` scope.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(current)
+        .service(report)
+        .service(set_policy)
+        .service(decide_excuse);
+}
+
+/// Form submitted to set a semester's attendance policy.
+#[derive(Deserialize, Debug)]
+struct PolicyForm {
+    required_count: i64,
+    max_excused: i64,
+}
+
+/// Form submitted to decide on an excuse request.
+#[derive(Deserialize, Debug)]
+struct DecisionForm {
+    meeting_id: MeetingId,
+    user_id: Uuid,
+    status: ExcuseStatus,
+}
+
+/// A member who does not meet the attendance policy yet.
+#[derive(Serialize, Debug)]
+struct BelowThreshold {
+    user: AttendanceReportEnrollmentsUser,
+    standing: Standing,
+}
+
+/// Redirect to a semester's attendance report.
+fn to_report(semester_id: &SemesterId) -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, format!("/admin/attendance/{}", semester_id))
+        .finish()
+}
+
+/// The attendance report of the current semester.
+#[get("")]
+async fn current() -> Result<HttpResponse, TelescopeError> {
+    let semester = CurrentSemesters::get()
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            TelescopeError::resource_not_found(
+                "No Current Semester",
+                "There is no semester in progress. Pick one from the semester records.",
+            )
+        })?;
+    return Ok(to_report(&SemesterId(semester.semester_id)));
+}
+
+/// A semester's attendance policy, members below it, and pending excuse
+/// requests.
+#[get("/{semester_id}")]
+async fn report(
+    req: HttpRequest,
+    Path(semester_id): Path<SemesterId>,
+) -> Result<Page, TelescopeError> {
+    let data = AttendanceReport::get(semester_id).await?;
+    let semester = data.semester.ok_or_else(|| {
+        TelescopeError::resource_not_found(
+            "Semester Not Found",
+            "Could not find a semester by this ID.",
+        )
+    })?;
+
+    let policy: Option<Policy> = semester.attendance_policy.as_ref().map(|policy| Policy {
+        required_count: policy.required_count,
+        max_excused: policy.max_excused,
+    });
+
+    // Most behind first.
+    let mut below: Vec<BelowThreshold> = data
+        .enrollments
+        .into_iter()
+        .map(|enrollment| {
+            let user = enrollment.user;
+            let attended = user.attended.aggregate.as_ref().map_or(0, |a| a.count);
+            let excused = user.excused.aggregate.as_ref().map_or(0, |a| a.count);
+            BelowThreshold {
+                standing: Standing::new(policy, attended, excused),
+                user,
+            }
+        })
+        .filter(|member| !member.standing.meets_requirement)
+        .collect();
+    below.sort_by_key(|member| -member.standing.remaining);
+
+    let mut template = Template::new(TEMPLATE_PATH);
+    template
+        .set_field("semester_id", &semester.semester_id)?
+        .set_field("title", &semester.title)?
+        .set_field("policy", policy)?
+        .set_field("held", semester.held.aggregate.map_or(0, |a| a.count))?
+        .set_field("below", below)?
+        .set_field("pending", &data.pending)?;
+    return template.in_page(&req, "Attendance").await;
+}
+
+/// Set a semester's attendance policy.
+#[post("/{semester_id}/policy")]
+async fn set_policy(
+    auth: AuthenticationCookie,
+    Path(semester_id): Path<SemesterId>,
+    Form(form): Form<PolicyForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let coordinator: Uuid = auth.get_user_id_or_error().await?;
+    if form.required_count < 0 || form.max_excused < 0 {
+        return Err(TelescopeError::BadRequest {
+            header: "Invalid Policy".into(),
+            message: "Required attendance and excused absences cannot be negative.".into(),
+            show_status_code: false,
+        });
+    }
+
+    let policy = Policy {
+        required_count: form.required_count,
+        max_excused: form.max_excused,
+    };
+    SetAttendancePolicy::execute(semester_id.clone(), policy, coordinator).await?;
+    return Ok(to_report(&semester_id));
+}
+
+/// Approve or deny an excuse request.
+#[post("/{semester_id}/excuses")]
+async fn decide_excuse(
+    auth: AuthenticationCookie,
+    Path(semester_id): Path<SemesterId>,
+    Form(form): Form<DecisionForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let coordinator: Uuid = auth.get_user_id_or_error().await?;
+    if form.status == ExcuseStatus::Pending {
+        return Err(TelescopeError::BadRequest {
+            header: "Invalid Decision".into(),
+            message: "Excuse requests can be approved or denied.".into(),
+            show_status_code: false,
+        });
+    }
+
+    let found: bool =
+        DecideExcuse::execute(form.meeting_id, form.user_id, form.status, coordinator).await?;
+    if !found {
+        return Err(TelescopeError::resource_not_found(
+            "Excuse Request Not Found",
+            "Could not find this excuse request.",
+        ));
+    }
+    return Ok(to_report(&semester_id));
+}
@@ -0,0 +1,153 @@
+//! Faculty report of the attestations users answered at registration.
+//!
+//! Open to faculty as well as admins, since faculty are usually the ones asked
+//! for this. Only attestations in the config are listed, but answers are kept
+//! for ones that have since been removed and can still be reached by key.
+
+use crate::api::rcos::users::attestations::{
+    attestation_report::AttestationReportUserAttestations, AttestationAnswers, AttestationReport,
+};
+use crate::api::rcos::users::role_lookup::RoleLookup;
+use crate::api::rcos::users::UserRole;
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::middlewares::authorization::AuthorizationResult;
+use actix_web::http::header::{
+    ContentDisposition, DispositionParam, DispositionType, CONTENT_DISPOSITION, CONTENT_TYPE,
+};
+use actix_web::web::{Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use csv::WriterBuilder;
+use futures::future::LocalBoxFuture;
+use uuid::Uuid;
+
+/// The path to the attestation list template from the templates directory.
+const INDEX_TEMPLATE: &'static str = "admin/attestations/index";
+
+/// The path to the attestation report template from the templates directory.
+const REPORT_TEMPLATE: &'static str = "admin/attestations/report";
+
+/// Check that a user is faculty or an admin.
+pub fn faculty_authorization(user_id: Uuid) -> LocalBoxFuture<'static, AuthorizationResult> {
+    Box::pin(async move {
+        let role: UserRole = RoleLookup::get(user_id)
+            .await?
+            .expect("Viewer's account does not exist.");
+
+        if role.is_admin() || role == UserRole::Faculty {
+            Ok(())
+        } else {
+            Err(TelescopeError::Forbidden)
+        }
+    })
+}
+
+/// Register attestation report services. These are registered in the
+/// `/admin/attestations` scope.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(index).service(download).service(report);
+}
+
+/// The configured attestations, with how many users checked and left unchecked
+/// each one.
+#[get("")]
+async fn index(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let answers: Vec<(String, bool)> = AttestationAnswers::get().await?;
+
+    let attestations: Vec<_> = global_config()
+        .attestations
+        .iter()
+        .map(|attestation| {
+            let answered = answers.iter().filter(|(key, _)| *key == attestation.key);
+            let accepted: usize = answered.clone().filter(|(_, accepted)| *accepted).count();
+            json!({
+                "key": attestation.key,
+                "label": attestation.label,
+                "required": attestation.required,
+                "accepted": accepted,
+                "declined": answered.count() - accepted,
+            })
+        })
+        .collect();
+
+    let mut template = Template::new(INDEX_TEMPLATE);
+    template.set_field("attestations", attestations)?;
+    return template.in_page(&req, "Attestations").await;
+}
+
+/// Who answered an attestation, and how.
+#[get("/{key}")]
+async fn report(req: HttpRequest, Path(key): Path<String>) -> Result<Page, TelescopeError> {
+    let answers: Vec<AttestationReportUserAttestations> =
+        AttestationReport::get(key.clone()).await?;
+
+    // Use the configured label if there is one. Otherwise use the label the
+    // latest answer was given to.
+    let label: Option<String> = global_config()
+        .attestations
+        .iter()
+        .find(|attestation| attestation.key == key)
+        .map(|attestation| attestation.label.clone())
+        .or(answers.last().map(|answer| answer.label.clone()));
+
+    let mut template = Template::new(REPORT_TEMPLATE);
+    template
+        .set_field("key", key)?
+        .set_field("label", label)?
+        .set_field("answers", answers)?;
+    return template.in_page(&req, "Attestation Report").await;
+}
+
+/// A row of the attestation report spreadsheet.
+#[derive(Serialize, Debug)]
+struct ReportRow {
+    first_name: String,
+    last_name: String,
+    rcs_id: Option<String>,
+    accepted: bool,
+    answered_at: String,
+}
+
+/// Download who answered an attestation as a spreadsheet.
+#[get("/{key}/csv")]
+async fn download(Path(key): Path<String>) -> Result<HttpResponse, TelescopeError> {
+    let answers: Vec<AttestationReportUserAttestations> =
+        AttestationReport::get(key.clone()).await?;
+
+    let mut buffer: Vec<u8> = Vec::new();
+    // Scope the writer so it is dropped before the buffer is used.
+    {
+        let mut writer = WriterBuilder::new().from_writer(&mut buffer);
+        for answer in answers {
+            let user = answer.user;
+            writer
+                .serialize(ReportRow {
+                    first_name: user.first_name,
+                    last_name: user.last_name,
+                    rcs_id: user.rcs_id.into_iter().next().map(|a| a.account_id),
+                    accepted: answer.accepted,
+                    answered_at: answer.answered_at.to_rfc3339(),
+                })
+                .map_err(|e| TelescopeError::ise(format!("Could not write CSV row: {}", e)))?;
+        }
+        writer
+            .flush()
+            .map_err(|e| TelescopeError::ise(format!("Could not write CSV: {}", e)))?;
+    }
+
+    return Ok(HttpResponse::Ok()
+        .set_header(CONTENT_TYPE, "text/csv")
+        .set_header(
+            CONTENT_DISPOSITION,
+            ContentDisposition {
+                disposition: DispositionType::Attachment,
+                parameters: vec![DispositionParam::Filename(format!(
+                    "attestation-{}.csv",
+                    key
+                ))],
+            },
+        )
+        .body(buffer));
+}
@@ -0,0 +1,410 @@
+//! Admin services to check on the Discord bot and manage its per-server
+//! settings.
+
+use crate::api::rcos::discord_guilds::get::{AllDiscordGuilds, DiscordGuild};
+use crate::api::rcos::discord_guilds::mutations::{
+    DeleteDiscordGuild, DiscordGuildFields, SaveDiscordGuild,
+};
+use crate::discord_bot::channels::{self as bot_channels, ChannelAction, ChannelChange};
+use crate::discord_bot::{self, guilds as bot_guilds, status, DiscordMessage};
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use serde_json::Value;
+
+/// The path to the form template from the templates directory.
+const FORM_TEMPLATE: &'static str = "admin/discord/guild_form";
+
+/// Register Discord status and server settings services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(index)
+        .service(register_commands)
+        .service(sync_roles)
+        .service(test_announcement)
+        .service(channels)
+        .service(generate_channels)
+        .service(stop)
+        .service(restart)
+        .service(guilds)
+        .service(new_guild)
+        .service(submit_new_guild)
+        .service(edit_guild)
+        .service(submit_edit_guild)
+        .service(delete_guild);
+}
+
+/// Form submitted to save a server's settings.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GuildForm {
+    /// Only submitted when adding a server -- the ID is in the path when editing.
+    #[serde(default)]
+    guild_id: String,
+    name: String,
+    announcements_channel_id: String,
+    member_role_id: String,
+    student_role_id: String,
+    mentor_role_id: String,
+    coordinator_role_id: String,
+    faculty_role_id: String,
+    commands_enabled: Option<bool>,
+    announcements_enabled: Option<bool>,
+}
+
+impl GuildForm {
+    /// Create a form pre-filled with a server's saved settings.
+    fn from_fields(fields: DiscordGuildFields) -> Self {
+        GuildForm {
+            guild_id: fields.guild_id,
+            name: fields.name.unwrap_or_default(),
+            announcements_channel_id: fields.announcements_channel_id.unwrap_or_default(),
+            member_role_id: fields.member_role_id.unwrap_or_default(),
+            student_role_id: fields.student_role_id.unwrap_or_default(),
+            mentor_role_id: fields.mentor_role_id.unwrap_or_default(),
+            coordinator_role_id: fields.coordinator_role_id.unwrap_or_default(),
+            faculty_role_id: fields.faculty_role_id.unwrap_or_default(),
+            commands_enabled: Some(fields.commands_enabled),
+            announcements_enabled: Some(fields.announcements_enabled),
+        }
+    }
+}
+
+/// Trim a string and convert it to none if it is empty.
+fn non_empty(string: &str) -> Option<String> {
+    let trimmed = string.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+/// Check that an optional Discord ID is a number.
+fn validate_id(form: &mut Template, field: &str, value: &str) -> Option<String> {
+    let id: String = non_empty(value)?;
+    if id.parse::<u64>().is_err() {
+        form.set_issue(
+            field,
+            "Discord IDs are numbers. Copy them with developer mode on.",
+        );
+    }
+    return Some(id);
+}
+
+/// Make the server settings form. If `editing` is some, the form edits that
+/// server's settings.
+fn make_form(editing: Option<&str>, selections: Option<&GuildForm>) -> Template {
+    let mut form = Template::new(FORM_TEMPLATE);
+    form.fields = json!({
+        "editing": editing,
+        "selections": selections,
+    });
+    return form;
+}
+
+/// Validate a submitted form. On success, return the settings to save.
+/// Otherwise, return the form template with issues marked.
+fn validate(editing: Option<&str>, input: &GuildForm) -> Result<DiscordGuildFields, Template> {
+    let mut form: Template = make_form(editing, Some(input));
+
+    let guild_id: String = match editing {
+        Some(guild_id) => guild_id.to_string(),
+        None => match validate_id(&mut form, "guild_id", input.guild_id.as_str()) {
+            Some(id) => id,
+            None => {
+                form.set_issue("guild_id", "A server ID is required.");
+                String::new()
+            }
+        },
+    };
+
+    let announcements_channel_id: Option<String> = validate_id(
+        &mut form,
+        "announcements_channel_id",
+        input.announcements_channel_id.as_str(),
+    );
+    let member_role_id: Option<String> =
+        validate_id(&mut form, "member_role_id", input.member_role_id.as_str());
+    let student_role_id: Option<String> =
+        validate_id(&mut form, "student_role_id", input.student_role_id.as_str());
+    let mentor_role_id: Option<String> =
+        validate_id(&mut form, "mentor_role_id", input.mentor_role_id.as_str());
+    let coordinator_role_id: Option<String> = validate_id(
+        &mut form,
+        "coordinator_role_id",
+        input.coordinator_role_id.as_str(),
+    );
+    let faculty_role_id: Option<String> =
+        validate_id(&mut form, "faculty_role_id", input.faculty_role_id.as_str());
+
+    let announcements_enabled: bool = input.announcements_enabled.unwrap_or(false);
+    if announcements_enabled && announcements_channel_id.is_none() {
+        form.set_issue(
+            "announcements_channel_id",
+            "Set a channel to turn on announcements.",
+        );
+    }
+
+    if form["issues"] != json!(null) {
+        return Err(form);
+    }
+
+    return Ok(DiscordGuildFields {
+        guild_id,
+        name: non_empty(input.name.as_str()),
+        announcements_channel_id,
+        member_role_id,
+        student_role_id,
+        mentor_role_id,
+        coordinator_role_id,
+        faculty_role_id,
+        commands_enabled: input.commands_enabled.unwrap_or(false),
+        announcements_enabled,
+    });
+}
+
+/// Tell the bot to pick up changed settings.
+fn reload_bot() {
+    if let Err(e) = discord_bot::send(DiscordMessage::ReloadGuilds) {
+        warn!("Could not reload Discord server settings: {}", e);
+    }
+}
+
+/// Redirect to the list of servers.
+fn to_guilds() -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, "/admin/discord/guilds")
+        .finish()
+}
+
+/// The bot's connection state, registered commands, and recent actions.
+#[get("/discord")]
+async fn index(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let bot_status = status::get();
+
+    // Pair each server with the commands registered on it.
+    let guilds: Vec<Value> = bot_guilds::all()
+        .into_iter()
+        .map(|guild| {
+            let commands = bot_status.commands.get(&guild.guild_id.to_string());
+            json!({
+                "settings": guild,
+                "commands": commands,
+            })
+        })
+        .collect();
+
+    let mut template = Template::new("admin/discord/index");
+    template
+        .set_field("status", bot_status)?
+        .set_field("guilds", guilds)?;
+    return template.in_page(&req, "Discord").await;
+}
+
+/// Hand an action to the bot and go back to the status page. Actions run in
+/// the background; their outcome shows up on the status page when done.
+fn dispatch(message: DiscordMessage) -> Result<HttpResponse, TelescopeError> {
+    discord_bot::send(message)?;
+    return Ok(HttpResponse::Found()
+        .header(LOCATION, "/admin/discord")
+        .finish());
+}
+
+/// Register slash commands again on every server with commands enabled, and
+/// globally if turned on.
+#[post("/discord/register-commands")]
+async fn register_commands() -> Result<HttpResponse, TelescopeError> {
+    dispatch(DiscordMessage::RegisterCommands)
+}
+
+/// Give the member role to linked members of the RCOS server missing it, and
+/// reconcile enrollment roles.
+#[post("/discord/sync-roles")]
+async fn sync_roles() -> Result<HttpResponse, TelescopeError> {
+    dispatch(DiscordMessage::SyncRoles)
+}
+
+/// Post a test message in every announcements channel.
+#[post("/discord/test-announcement")]
+async fn test_announcement() -> Result<HttpResponse, TelescopeError> {
+    dispatch(DiscordMessage::TestAnnouncement)
+}
+
+/// What generating small group and project channels would do, without doing
+/// it.
+#[get("/discord/channels")]
+async fn channels(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let changes: Vec<ChannelChange> = bot_channels::generate(true).await?;
+    let pending: usize = changes
+        .iter()
+        .filter(|change| change.action != ChannelAction::Keep)
+        .count();
+
+    let mut template = Template::new("admin/discord/channels");
+    template
+        .set_field("changes", changes)?
+        .set_field("pending", pending)?;
+    return template.in_page(&req, "Discord Channels").await;
+}
+
+/// Make the missing small group and project channels.
+#[post("/discord/channels")]
+async fn generate_channels() -> Result<HttpResponse, TelescopeError> {
+    dispatch(DiscordMessage::GenerateChannels)
+}
+
+/// Disconnect the bot from Discord until it is restarted.
+#[post("/discord/stop")]
+async fn stop() -> Result<HttpResponse, TelescopeError> {
+    dispatch(DiscordMessage::Stop)
+}
+
+/// Reconnect the bot to Discord.
+#[post("/discord/restart")]
+async fn restart() -> Result<HttpResponse, TelescopeError> {
+    dispatch(DiscordMessage::Restart)
+}
+
+/// List the servers with saved settings.
+#[get("/discord/guilds")]
+async fn guilds(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let guilds = AllDiscordGuilds::get().await?;
+    let rcos_guild_id: String = global_config().discord_config.rcos_guild_id.clone();
+
+    let mut template = Template::new("admin/discord/guilds");
+    template
+        .set_field("rcos_guild_id", rcos_guild_id.as_str())?
+        .set_field(
+            "rcos_guild_saved",
+            guilds.iter().any(|guild| guild.guild_id == rcos_guild_id),
+        )?
+        .set_field("guilds", guilds)?;
+    return template.in_page(&req, "Discord Servers").await;
+}
+
+/// Form to add settings for a server.
+#[get("/discord/guilds/create")]
+async fn new_guild(req: HttpRequest) -> Result<Page, TelescopeError> {
+    // New servers get commands but not announcements by default.
+    let defaults = GuildForm {
+        guild_id: String::new(),
+        name: String::new(),
+        announcements_channel_id: String::new(),
+        member_role_id: String::new(),
+        student_role_id: String::new(),
+        mentor_role_id: String::new(),
+        coordinator_role_id: String::new(),
+        faculty_role_id: String::new(),
+        commands_enabled: Some(true),
+        announcements_enabled: Some(false),
+    };
+
+    make_form(None, Some(&defaults))
+        .in_page(&req, "Add Discord Server")
+        .await
+}
+
+/// Submit settings for a new server.
+#[post("/discord/guilds/create")]
+async fn submit_new_guild(
+    req: HttpRequest,
+    Form(input): Form<GuildForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let fields: DiscordGuildFields = match validate(None, &input) {
+        Ok(fields) => fields,
+        Err(form) => {
+            let page = form.in_page(&req, "Add Discord Server").await?;
+            return Err(TelescopeError::InvalidForm(page));
+        }
+    };
+
+    SaveDiscordGuild::execute(fields).await?;
+    reload_bot();
+    return Ok(to_guilds());
+}
+
+/// Form to edit a server's settings.
+#[get("/discord/guilds/edit/{guild_id}")]
+async fn edit_guild(
+    req: HttpRequest,
+    Path(guild_id): Path<String>,
+) -> Result<Page, TelescopeError> {
+    // The RCOS server can be edited before it has saved settings. It starts
+    // with the settings from the config file.
+    let discord_conf = &global_config().discord_config;
+    let selections: GuildForm = match DiscordGuild::get(guild_id.clone()).await? {
+        Some(saved) => GuildForm::from_fields(DiscordGuildFields {
+            guild_id: saved.guild_id,
+            name: saved.name,
+            announcements_channel_id: saved.announcements_channel_id,
+            member_role_id: saved.member_role_id,
+            student_role_id: saved.student_role_id,
+            mentor_role_id: saved.mentor_role_id,
+            coordinator_role_id: saved.coordinator_role_id,
+            faculty_role_id: saved.faculty_role_id,
+            commands_enabled: saved.commands_enabled,
+            announcements_enabled: saved.announcements_enabled,
+        }),
+
+        None if guild_id == discord_conf.rcos_guild_id => GuildForm {
+            guild_id: guild_id.clone(),
+            name: "RCOS".into(),
+            announcements_channel_id: discord_conf
+                .announcements_channel_id
+                .clone()
+                .unwrap_or_default(),
+            member_role_id: String::new(),
+            student_role_id: String::new(),
+            mentor_role_id: String::new(),
+            coordinator_role_id: String::new(),
+            faculty_role_id: String::new(),
+            commands_enabled: Some(true),
+            announcements_enabled: Some(discord_conf.announcements_channel_id.is_some()),
+        },
+
+        None => {
+            return Err(TelescopeError::resource_not_found(
+                "Server Not Found",
+                "There are no settings saved for a Discord server with this ID.",
+            ))
+        }
+    };
+
+    make_form(Some(guild_id.as_str()), Some(&selections))
+        .in_page(&req, "Edit Discord Server")
+        .await
+}
+
+/// Submit edits to a server's settings.
+#[post("/discord/guilds/edit/{guild_id}")]
+async fn submit_edit_guild(
+    req: HttpRequest,
+    Path(guild_id): Path<String>,
+    Form(input): Form<GuildForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let fields: DiscordGuildFields = match validate(Some(guild_id.as_str()), &input) {
+        Ok(fields) => fields,
+        Err(form) => {
+            let page = form.in_page(&req, "Edit Discord Server").await?;
+            return Err(TelescopeError::InvalidForm(page));
+        }
+    };
+
+    SaveDiscordGuild::execute(fields).await?;
+    reload_bot();
+    return Ok(to_guilds());
+}
+
+/// Delete a server's settings. Uses post to prevent inadvertent deletion.
+#[post("/discord/guilds/delete/{guild_id}")]
+async fn delete_guild(Path(guild_id): Path<String>) -> Result<HttpResponse, TelescopeError> {
+    DeleteDiscordGuild::execute(guild_id)
+        .await?
+        .ok_or(TelescopeError::resource_not_found(
+            "Server Not Found",
+            "There are no settings saved for a Discord server with this ID.",
+        ))?;
+
+    reload_bot();
+    return Ok(to_guilds());
+}
@@ -0,0 +1,113 @@
+//! Queue of reports filed from server error pages.
+//!
+//! Admins can open a GitHub issue from a report if an issue repository is
+//! configured, and close reports once they are dealt with. Issues leave out
+//! who filed the report, since the repository may be public.
+
+use crate::api::github::issues::CreateIssue;
+use crate::api::rcos::error_reports::{
+    CloseErrorReport, ErrorReport, ErrorReportById, ErrorReportQueue, SetErrorReportIssue,
+};
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+
+/// The path to the error report queue template from the templates directory.
+const QUEUE_TEMPLATE: &'static str = "admin/errors";
+
+/// Register error report services. These are registered in the `/admin/` scope.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(queue).service(close).service(open_issue);
+}
+
+/// Redirect back to the error report queue.
+fn to_queue() -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, "/admin/errors")
+        .finish()
+}
+
+/// Get a report or return a not found error.
+async fn find(report_id: i64) -> Result<ErrorReport, TelescopeError> {
+    ErrorReportById::get(report_id).await?.ok_or_else(|| {
+        TelescopeError::resource_not_found(
+            "Error Report Not Found",
+            "Could not find an error report with this ID.",
+        )
+    })
+}
+
+/// The error report queue.
+#[get("/errors")]
+async fn queue(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let data = ErrorReportQueue::get().await?;
+    let mut template = Template::new(QUEUE_TEMPLATE);
+    template
+        .set_field("open", &data.open)?
+        .set_field("closed", &data.closed)?
+        .set_field(
+            "issues_enabled",
+            global_config()
+                .github_credentials
+                .issue_repository()
+                .is_some(),
+        )?;
+    return template.in_page(&req, "Error Reports").await;
+}
+
+/// Close a report.
+#[post("/errors/{report_id}/close")]
+async fn close(Path(report_id): Path<i64>) -> Result<HttpResponse, TelescopeError> {
+    if !CloseErrorReport::execute(report_id).await? {
+        return Err(TelescopeError::resource_not_found(
+            "Error Report Not Found",
+            "Could not find an error report with this ID.",
+        ));
+    }
+    return Ok(to_queue());
+}
+
+/// Open a GitHub issue from a report. Reports that already have one keep it.
+#[post("/errors/{report_id}/issue")]
+async fn open_issue(Path(report_id): Path<i64>) -> Result<HttpResponse, TelescopeError> {
+    let report: ErrorReport = find(report_id).await?;
+    if report.issue_url.is_some() {
+        return Ok(to_queue());
+    }
+
+    let config = global_config();
+    let (token, owner, name) = config
+        .github_credentials
+        .issue_repository()
+        .ok_or_else(|| TelescopeError::BadRequest {
+            header: "Issues Not Set Up".into(),
+            message: "Set an issue repository and API token in the GitHub config to open \
+            issues from error reports."
+                .into(),
+            show_status_code: false,
+        })?;
+
+    let title: String = format!("{} error on {}", report.status, report.route);
+    let body: String = format!(
+        "Reported from an error page.\n\n\
+        - Request ID: `{}`\n\
+        - Route: `{}`\n\
+        - Status: {}\n\
+        - Occurred at: {}\n\n\
+        ### What the user was doing\n\n{}\n",
+        report.request_id,
+        report.route,
+        report.status,
+        report.occurred_at.to_rfc3339(),
+        report.description
+    );
+
+    let issue_url: String = CreateIssue::execute(token, owner, name, title, body).await?;
+    info!("Opened {} from error report {}.", issue_url, report_id);
+    SetErrorReportIssue::execute(report_id, issue_url).await?;
+    return Ok(to_queue());
+}
@@ -0,0 +1,22 @@
+//! Admin report of broken links found by the link checker.
+
+use crate::api::rcos::link_health::get::BrokenLinkReport;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use actix_web::web::ServiceConfig;
+use actix_web::HttpRequest;
+
+/// Register the broken link report.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(index);
+}
+
+/// List every link that failed its last check, with the page it is on.
+#[get("/links")]
+async fn index(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let links = BrokenLinkReport::get().await?;
+    let mut template = Template::new("admin/links");
+    template.set_field("links", links)?;
+    return template.in_page(&req, "Broken Links").await;
+}
@@ -0,0 +1,92 @@
+//! Admin page to change log levels of modules while Telescope runs.
+//!
+//! Levels set here override the `log_level` filter from the config for a
+//! module and the modules under it. They apply to every replica, and are lost
+//! on restart; change the config to keep them.
+
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use crate::logging::{self, LevelOverride};
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::cluster::{self, Broadcast};
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+
+/// The levels offered on the form, least verbose first.
+const LEVELS: [&'static str; 6] = ["off", "error", "warn", "info", "debug", "trace"];
+
+/// Register log level services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(index)
+        .service(set_level)
+        .service(reset_level);
+}
+
+/// Form submitted to stop overriding a module's level.
+#[derive(Deserialize, Debug)]
+struct ResetForm {
+    module: String,
+}
+
+/// Redirect to the log level page.
+fn to_index() -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, "/admin/logging")
+        .finish()
+}
+
+/// Apply new overrides here and on every other replica.
+fn publish(overrides: Vec<LevelOverride>) {
+    logging::set_overrides(overrides.clone());
+    cluster::broadcast(Broadcast::LogLevelsChanged { overrides });
+}
+
+/// The log format, the configured filter, and the levels set at runtime.
+#[get("/logging")]
+async fn index(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let config = global_config();
+    let mut template = Template::new("admin/logging");
+    template
+        .set_field("format", &config.log_format)?
+        .set_field("filter", config.log_level())?
+        .set_field("overrides", logging::overrides())?
+        .set_field("levels", LEVELS)?;
+    return template.in_page(&req, "Logging").await;
+}
+
+/// Set the level of a module.
+#[post("/logging")]
+async fn set_level(Form(form): Form<LevelOverride>) -> Result<HttpResponse, TelescopeError> {
+    let form = LevelOverride {
+        module: form.module.trim().to_string(),
+        level: form.level.trim().to_lowercase(),
+    };
+    if form.parse().is_none() {
+        return Err(TelescopeError::BadRequest {
+            header: "Invalid Log Level".into(),
+            message: "The module must be a module path without spaces or commas, like \
+                telescope::discord_bot, and the level one of off, error, warn, info, debug, \
+                or trace."
+                .into(),
+            show_status_code: false,
+        });
+    }
+
+    let mut overrides: Vec<LevelOverride> = logging::overrides();
+    overrides.retain(|o| o.module != form.module);
+    overrides.push(form);
+    publish(overrides);
+    return Ok(to_index());
+}
+
+/// Go back to the configured filter for a module.
+#[post("/logging/reset")]
+async fn reset_level(Form(form): Form<ResetForm>) -> Result<HttpResponse, TelescopeError> {
+    let mut overrides: Vec<LevelOverride> = logging::overrides();
+    overrides.retain(|o| o.module != form.module);
+    publish(overrides);
+    return Ok(to_index());
+}
@@ -1,6 +1,27 @@
 //! Services for the admin panel.
 
+mod api_keys;
+mod attendance;
+mod attestations;
+mod discord;
+mod errors;
+mod links;
+mod logging;
+mod moderation;
+mod pages;
+mod performance;
+mod policies;
+mod projects;
+mod quarantine;
+mod recordings;
+mod research;
+mod retention;
 mod semesters;
+mod skills;
+mod sponsors;
+mod tasks;
+mod users;
+mod webhooks;
 
 use crate::api::rcos::users::role_lookup::RoleLookup;
 use crate::api::rcos::users::UserRole;
@@ -8,6 +29,7 @@ use crate::error::TelescopeError;
 use crate::templates::page::Page;
 use crate::templates::Template;
 use crate::web::middlewares::authorization::{Authorization, AuthorizationResult};
+use crate::web::uploads;
 use actix_web::guard;
 use actix_web::web as aweb;
 use actix_web::web::ServiceConfig;
@@ -46,6 +68,77 @@ pub fn register(config: &mut ServiceConfig) {
             .to(index),
     );
 
+    // The task board is also open to coordinators, so it gets its own scope. This
+    // has to be registered before the admin scope, which would otherwise match it.
+    config.service(
+        aweb::scope("/admin/tasks")
+            .wrap(Authorization::new(tasks::coordinator_authorization))
+            .configure(tasks::register),
+    );
+
+    // So is the moderation queue.
+    config.service(
+        aweb::scope("/admin/moderation")
+            .wrap(Authorization::new(tasks::coordinator_authorization))
+            .configure(moderation::register),
+    );
+
+    // And attendance policies and reports.
+    config.service(
+        aweb::scope("/admin/attendance")
+            .wrap(Authorization::new(tasks::coordinator_authorization))
+            .configure(attendance::register),
+    );
+
+    // And the project health overview.
+    config.service(
+        aweb::scope("/admin/projects")
+            .wrap(Authorization::new(tasks::coordinator_authorization))
+            .configure(projects::register),
+    );
+
+    // And the skill taxonomy.
+    config.service(
+        aweb::scope("/admin/skills")
+            .wrap(Authorization::new(tasks::coordinator_authorization))
+            .configure(skills::register),
+    );
+
+    // And recordings waiting to be attached to meetings.
+    config.service(
+        aweb::scope("/admin/recordings")
+            .wrap(Authorization::new(tasks::coordinator_authorization))
+            .configure(recordings::register),
+    );
+
+    // And the policy pages.
+    config.service(
+        aweb::scope("/admin/policies")
+            .wrap(Authorization::new(tasks::coordinator_authorization))
+            .configure(policies::register),
+    );
+
+    // And the anonymized research export.
+    config.service(
+        aweb::scope("/admin/research")
+            .wrap(Authorization::new(tasks::coordinator_authorization))
+            .configure(research::register),
+    );
+
+    // And public API keys.
+    config.service(
+        aweb::scope("/admin/api-keys")
+            .wrap(Authorization::new(tasks::coordinator_authorization))
+            .configure(api_keys::register),
+    );
+
+    // The attestation report is open to faculty instead.
+    config.service(
+        aweb::scope("/admin/attestations")
+            .wrap(Authorization::new(attestations::faculty_authorization))
+            .configure(attestations::register),
+    );
+
     // Route every sub-service through the admin scope.
     config.service(
         // Create the admin scope.
@@ -53,16 +146,38 @@ pub fn register(config: &mut ServiceConfig) {
             // Verify that the viewer has the admin role.
             .wrap(admin_authorization_middleware)
             // Semester services
-            .configure(semesters::register),
+            .configure(semesters::register)
+            // Custom page services
+            .configure(pages::register)
+            // Sponsor services
+            .configure(sponsors::register)
+            // Broken link report
+            .configure(links::register)
+            // Duplicate account merging
+            .configure(users::register)
+            // Data retention rules
+            .configure(retention::register)
+            // Discord bot settings
+            .configure(discord::register)
+            // Log levels
+            .configure(logging::register)
+            // Reports filed from error pages
+            .configure(errors::register)
+            // Slowest pages
+            .configure(performance::register)
+            // Quarantined uploads
+            .configure(quarantine::register)
+            // Outgoing webhooks
+            .configure(webhooks::register),
     );
 }
 
 /// Admin page index.
 async fn index(req: HttpRequest) -> Result<Page, TelescopeError> {
     // Access is pre-checked by the scope this is in.
-    // Return the admin page (currently just a static template).
-    return Template::new("admin/index")
-        // Rendered in a page of course.
-        .in_page(&req, "RCOS Admin")
-        .await;
+    let mut template = Template::new("admin/index");
+    // Flag uploads waiting for review.
+    template.set_field("quarantined", uploads::quarantine::count())?;
+    // Rendered in a page of course.
+    return template.in_page(&req, "RCOS Admin").await;
 }
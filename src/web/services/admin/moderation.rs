@@ -0,0 +1,195 @@
+//! Moderation queue for reported content.
+//!
+//! Like the task board, this is open to current coordinators as well as
+//! admins. Open reports are grouped by the content they are about, and a
+//! decision closes every open report of that content at once. Decisions are
+//! kept in the moderation log, and reporters with a linked Discord account are
+//! told what was decided.
+
+use crate::api::discord;
+use crate::api::rcos::moderation::get::{
+    moderation_queue::ModerationQueueOpen, ModerationQueue, ReportById,
+};
+use crate::api::rcos::moderation::mutations::{self, CloseReports, LogDecision};
+use crate::api::rcos::moderation::{ContentRef, ReportStatus, ReportedContent};
+use crate::discord_bot::embeds;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use uuid::Uuid;
+
+/// The path to the moderation queue template from the templates directory.
+const QUEUE_TEMPLATE: &'static str = "admin/moderation";
+
+/// The embed template for telling reporters about decisions.
+const DECISION_TEMPLATE: &'static str = "discord/report_decision";
+
+/// Register moderation services. These are registered in the `/admin/moderation` scope.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(queue).service(decide);
+}
+
+/// Open reports of one piece of content.
+#[derive(Serialize, Debug)]
+struct ReportGroup {
+    /// The first report, which decisions are posted to.
+    report_id: i64,
+    content_type: ReportedContent,
+    content_url: String,
+    /// The content as of the latest report.
+    content_preview: String,
+    reports: Vec<ModerationQueueOpen>,
+}
+
+/// Group open reports by their content, keeping the order of the first report
+/// of each.
+fn group(open: Vec<ModerationQueueOpen>) -> Vec<ReportGroup> {
+    let mut groups: Vec<ReportGroup> = Vec::new();
+    for report in open {
+        let existing = groups.iter_mut().find(|group| {
+            group.content_type == report.content_type
+                && group.reports[0].content_key == report.content_key
+        });
+
+        match existing {
+            Some(group) => {
+                group.content_preview = report.content_preview.clone();
+                group.reports.push(report);
+            }
+            None => groups.push(ReportGroup {
+                report_id: report.report_id,
+                content_type: report.content_type,
+                content_url: report.content_url.clone(),
+                content_preview: report.content_preview.clone(),
+                reports: vec![report],
+            }),
+        }
+    }
+    return groups;
+}
+
+/// The moderation queue.
+#[get("")]
+async fn queue(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let data = ModerationQueue::get().await?;
+    let mut template = Template::new(QUEUE_TEMPLATE);
+    template
+        .set_field("groups", group(data.open))?
+        .set_field("log", &data.log)?;
+    return template.in_page(&req, "Moderation").await;
+}
+
+/// Form submitted with a decision on reported content.
+#[derive(Deserialize, Debug)]
+struct DecisionForm {
+    action: ReportStatus,
+    #[serde(default)]
+    note: String,
+}
+
+/// Decide what to do about reported content. Closes every open report of the
+/// same content.
+#[post("/{report_id}")]
+async fn decide(
+    auth: AuthenticationCookie,
+    Path(report_id): Path<i64>,
+    Form(form): Form<DecisionForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let moderator: Uuid = auth.get_user_id_or_error().await?;
+
+    let report = ReportById::get(report_id).await?.ok_or_else(|| {
+        TelescopeError::resource_not_found(
+            "Report Not Found",
+            "Could not find a report with this ID.",
+        )
+    })?;
+
+    if form.action == ReportStatus::Open {
+        return Err(TelescopeError::BadRequest {
+            header: "Invalid Decision".into(),
+            message: "Reports can be hidden, removed, or dismissed.".into(),
+            show_status_code: false,
+        });
+    }
+
+    // Someone else decided first.
+    if report.status != ReportStatus::Open {
+        return Ok(to_queue());
+    }
+
+    let content =
+        ContentRef::parse(report.content_type, report.content_key.as_str()).ok_or_else(|| {
+            TelescopeError::ise(format!(
+                "Report {} has a malformed content key: {}",
+                report_id, report.content_key
+            ))
+        })?;
+
+    let exists: bool = match form.action {
+        ReportStatus::Hidden => mutations::hide(content).await?,
+        ReportStatus::Removed => mutations::remove(content).await?,
+        _ => true,
+    };
+    if !exists {
+        warn!(
+            "Reported {} {} no longer exists. Closing its reports anyway.",
+            content.content_type(),
+            content.key()
+        );
+    }
+
+    let reporters: Vec<Option<u64>> = CloseReports::execute(content, form.action).await?;
+    let note: Option<String> = Some(form.note.trim().to_string()).filter(|s| !s.is_empty());
+    LogDecision::execute(
+        content,
+        report.content_url,
+        form.action,
+        note,
+        reporters.len(),
+        moderator,
+    )
+    .await?;
+
+    info!(
+        "Moderator {} marked {} {} {} ({} reports).",
+        moderator,
+        content.content_type(),
+        content.key(),
+        form.action,
+        reporters.len()
+    );
+
+    notify(content.content_type(), form.action, reporters).await;
+    return Ok(to_queue());
+}
+
+/// Tell reporters what was decided about content they reported.
+async fn notify(content_type: ReportedContent, action: ReportStatus, reporters: Vec<Option<u64>>) {
+    let rendered = Template::new(DECISION_TEMPLATE)
+        .try_field("content", content_type.to_string().to_lowercase())
+        .and_then(|template| template.try_field("action", action))
+        .and_then(|template| embeds::render(&template));
+
+    let embed = match rendered {
+        Ok(embed) => embed,
+        Err(e) => {
+            error!("Could not render report decision message: {}", e);
+            return;
+        }
+    };
+
+    for discord_id in reporters.into_iter().flatten() {
+        discord::send_dm(discord_id, &embed).await;
+    }
+}
+
+/// Redirect back to the moderation queue.
+fn to_queue() -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, "/admin/moderation")
+        .finish()
+}
@@ -0,0 +1,240 @@
+//! Admin services to manage coordinator editable markdown pages.
+
+use crate::api::rcos::custom_pages::get::{AllCustomPages, CustomPage, CustomPageRevisions};
+use crate::api::rcos::custom_pages::mutations::{
+    CreateCustomPage, CustomPageContent, DeleteCustomPage, EditCustomPage,
+};
+use crate::error::TelescopeError;
+use crate::templates::navbar;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use regex::Regex;
+
+/// The path to the form template from the templates directory.
+const FORM_TEMPLATE: &'static str = "admin/pages/form";
+
+lazy_static! {
+    static ref SLUG_REGEX: Regex = Regex::new(r"^[a-z0-9][a-z0-9\-]*$").expect("Bad Regex");
+}
+
+/// Register custom page management services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(index)
+        .service(new)
+        .service(submit_new)
+        .service(edit)
+        .service(submit_edit)
+        .service(delete)
+        .service(history);
+}
+
+/// Form submitted to create or edit a custom page.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CustomPageForm {
+    /// The slug of the page. Ignored on edits.
+    #[serde(default)]
+    slug: Option<String>,
+
+    /// The title of the page.
+    title: String,
+
+    /// The markdown body of the page.
+    body: String,
+
+    /// Is the page a draft? Checkboxes are not sent at all when unchecked.
+    #[serde(default)]
+    is_draft: Option<bool>,
+
+    /// Should the page be linked in the navbar?
+    #[serde(default)]
+    show_in_nav: Option<bool>,
+
+    /// The order of the page in the navbar.
+    #[serde(default)]
+    nav_order: Option<i64>,
+}
+
+impl CustomPageForm {
+    /// Convert the submitted form into page content.
+    fn content(&self) -> CustomPageContent {
+        CustomPageContent {
+            title: self.title.trim().to_string(),
+            body: self.body.clone(),
+            is_draft: self.is_draft.unwrap_or(false),
+            show_in_nav: self.show_in_nav.unwrap_or(false),
+            nav_order: self.nav_order.unwrap_or(0),
+        }
+    }
+}
+
+/// Make the form template. If `existing_slug` is some, the form is for editing that page.
+fn make_form(existing_slug: Option<String>, selections: Option<&CustomPageForm>) -> Template {
+    let mut form = Template::new(FORM_TEMPLATE);
+    form.fields = json!({
+        "editing": existing_slug,
+        "selections": selections,
+    });
+    return form;
+}
+
+/// Check a slug for validity. Return a message describing the issue if it is invalid.
+fn slug_issue(slug: &str) -> Option<&'static str> {
+    (!SLUG_REGEX.is_match(slug))
+        .then(|| "Slugs may only contain lowercase letters, digits, and dashes.")
+}
+
+/// List all custom pages.
+#[get("/pages")]
+async fn index(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let pages = AllCustomPages::get().await?;
+    let mut template = Template::new("admin/pages/index");
+    template.set_field("data", pages)?;
+    return template.in_page(&req, "Custom Pages").await;
+}
+
+/// Form to create a new custom page.
+#[get("/pages/create")]
+async fn new(req: HttpRequest) -> Result<Page, TelescopeError> {
+    make_form(None, None).in_page(&req, "Create Page").await
+}
+
+/// Submit a new custom page.
+#[post("/pages/create")]
+async fn submit_new(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Form(input): Form<CustomPageForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    // Get the author of the page.
+    let author = auth.get_user_id_or_error().await?;
+
+    // Validate the form.
+    let mut form: Template = make_form(None, Some(&input));
+    let slug: String = input.slug.clone().unwrap_or_default().trim().to_string();
+    if let Some(issue) = slug_issue(slug.as_str()) {
+        form.set_issue("slug", issue);
+    } else if CustomPage::get(slug.clone()).await?.is_some() {
+        form.set_issue("slug", "There is already a page with this slug.");
+    }
+
+    if input.title.trim().is_empty() {
+        form.set_issue("title", "Title cannot be empty.");
+    }
+
+    // Return the form with issues if there are any.
+    if form["issues"] != json!(null) {
+        let page = form.in_page(&req, "Create Page").await?;
+        return Err(TelescopeError::InvalidForm(page));
+    }
+
+    // Create the page.
+    let created = CreateCustomPage::execute(slug, input.content(), author)
+        .await?
+        .ok_or(TelescopeError::ise("Page creation did not return slug."))?;
+    navbar::invalidate_custom_pages();
+
+    // Redirect to the new page.
+    Ok(HttpResponse::Found()
+        .header(LOCATION, format!("/p/{}", created))
+        .finish())
+}
+
+/// Form to edit an existing custom page.
+#[get("/pages/edit/{slug}")]
+async fn edit(req: HttpRequest, Path(slug): Path<String>) -> Result<Page, TelescopeError> {
+    // Lookup the existing page.
+    let existing =
+        CustomPage::get(slug.clone())
+            .await?
+            .ok_or(TelescopeError::resource_not_found(
+                "Page Not Found",
+                "Could not find a custom page with this slug.",
+            ))?;
+
+    // Pre-fill the form with the existing content.
+    let selections = CustomPageForm {
+        slug: Some(existing.slug),
+        title: existing.title,
+        body: existing.body,
+        is_draft: Some(existing.is_draft),
+        show_in_nav: Some(existing.show_in_nav),
+        nav_order: Some(existing.nav_order),
+    };
+
+    make_form(Some(slug), Some(&selections))
+        .in_page(&req, "Edit Page")
+        .await
+}
+
+/// Submit edits to a custom page.
+#[post("/pages/edit/{slug}")]
+async fn submit_edit(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Path(slug): Path<String>,
+    Form(input): Form<CustomPageForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    // Get the editor.
+    let author = auth.get_user_id_or_error().await?;
+
+    // Validate the title.
+    if input.title.trim().is_empty() {
+        let mut form: Template = make_form(Some(slug), Some(&input));
+        form.set_issue("title", "Title cannot be empty.");
+        let page = form.in_page(&req, "Edit Page").await?;
+        return Err(TelescopeError::InvalidForm(page));
+    }
+
+    // Save the edits and a revision.
+    EditCustomPage::execute(slug.clone(), input.content(), author)
+        .await?
+        .ok_or(TelescopeError::resource_not_found(
+            "Page Not Found",
+            "Could not find a custom page with this slug.",
+        ))?;
+    navbar::invalidate_custom_pages();
+
+    // Redirect to the edited page.
+    Ok(HttpResponse::Found()
+        .header(LOCATION, format!("/p/{}", slug))
+        .finish())
+}
+
+/// Delete a custom page. Uses post to prevent inadvertent deletion.
+#[post("/pages/delete/{slug}")]
+async fn delete(Path(slug): Path<String>) -> Result<HttpResponse, TelescopeError> {
+    DeleteCustomPage::execute(slug)
+        .await?
+        .ok_or(TelescopeError::resource_not_found(
+            "Page Not Found",
+            "Could not find a custom page with this slug.",
+        ))?;
+    navbar::invalidate_custom_pages();
+
+    Ok(HttpResponse::Found()
+        .header(LOCATION, "/admin/pages")
+        .finish())
+}
+
+/// View the revision history of a custom page.
+#[get("/pages/history/{slug}")]
+async fn history(req: HttpRequest, Path(slug): Path<String>) -> Result<Page, TelescopeError> {
+    let revisions = CustomPageRevisions::get(slug).await?;
+
+    // Make sure the page exists.
+    if revisions.custom_pages_by_pk.is_none() {
+        return Err(TelescopeError::resource_not_found(
+            "Page Not Found",
+            "Could not find a custom page with this slug.",
+        ));
+    }
+
+    let mut template = Template::new("admin/pages/history");
+    template.set_field("data", revisions)?;
+    return template.in_page(&req, "Page History").await;
+}
@@ -0,0 +1,52 @@
+//! Admin report of the slowest pages.
+//!
+//! Timings are kept by each replica since it started, so this only shows the
+//! requests the replica serving the report has handled. `/metrics` has the
+//! same numbers for collecting across replicas.
+
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::timing::{self, RouteTiming};
+use actix_web::web::ServiceConfig;
+use actix_web::HttpRequest;
+
+/// Register the slow page report.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(index);
+}
+
+/// Milliseconds in a number of seconds, rounded.
+fn millis(seconds: f64) -> u64 {
+    return (seconds * 1000.0).round() as u64;
+}
+
+/// Every route this replica has handled, slowest on average first.
+#[get("/performance")]
+async fn index(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let routes: Vec<RouteTiming> = timing::routes();
+
+    let mut template = Template::new("admin/performance");
+    template
+        .set_field("budgets", global_config().load_budgets)?
+        .set_field(
+            "routes",
+            routes
+                .iter()
+                .map(|route| {
+                    let count: f64 = route.count.max(1) as f64;
+                    json!({
+                        "route": route.route,
+                        "count": route.count,
+                        "over_budget": route.over_budget,
+                        "average": millis(route.wall_seconds / count),
+                        "upstream": millis(route.upstream_seconds / count),
+                        "render": millis(route.render_seconds / count),
+                        "max": millis(route.max_wall_seconds),
+                    })
+                })
+                .collect::<Vec<_>>(),
+        )?;
+    return template.in_page(&req, "Slowest Pages").await;
+}
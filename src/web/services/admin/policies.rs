@@ -0,0 +1,125 @@
+//! Publishing policy versions and checking who accepted them.
+//!
+//! Open to coordinators as well as admins. Published versions cannot be
+//! edited. Changing a policy means publishing a new version, which every
+//! member then has to accept.
+
+use crate::api::rcos::policies::get::{policy_versions, PolicyAcceptanceReport, PolicyVersions};
+use crate::api::rcos::policies::mutations::PublishPolicy;
+use crate::api::rcos::policies::PolicyKind;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::policies;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use serde_json::Value;
+
+/// The path to the policy list template from the templates directory.
+const INDEX_TEMPLATE: &'static str = "admin/policies/index";
+
+/// The path to the acceptance report template from the templates directory.
+const REPORT_TEMPLATE: &'static str = "admin/policies/report";
+
+/// Register policy services. These are registered in the `/admin/policies` scope.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(index).service(publish).service(report);
+}
+
+/// Form submitted to publish a policy version.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct PublishForm {
+    kind: PolicyKind,
+    title: String,
+    body: String,
+}
+
+/// Make the policy list, with the publish form.
+fn make_index(data: &policy_versions::ResponseData) -> Result<Template, TelescopeError> {
+    let kinds: Vec<Value> = PolicyKind::ALL
+        .iter()
+        .map(|kind| json!({ "value": kind, "name": kind.to_string() }))
+        .collect();
+
+    let mut template = Template::new(INDEX_TEMPLATE);
+    template
+        .set_field("data", data)?
+        .set_field("kinds", kinds)?;
+    return Ok(template);
+}
+
+/// Every version of every policy.
+#[get("")]
+async fn index(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let data = PolicyVersions::get().await?;
+    return make_index(&data)?.in_page(&req, "Policies").await;
+}
+
+/// Publish a new version of a policy.
+#[post("")]
+async fn publish(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Form(form): Form<PublishForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let publisher = auth.get_user_id_or_error().await?;
+    let versions = PolicyVersions::get().await?;
+
+    let mut template = make_index(&versions)?;
+    template.set_field("form", &form)?;
+    let title: String = form.title.trim().to_string();
+    let body: String = form.body.trim().to_string();
+    if title.is_empty() {
+        template.set_issue("title", "Cannot be empty.");
+    }
+    if body.is_empty() {
+        template.set_issue("body", "Cannot be empty.");
+    }
+    if template["issues"] != json!(null) {
+        let page = template.in_page(&req, "Policies").await?;
+        return Err(TelescopeError::InvalidForm(page));
+    }
+
+    let version: i64 = versions
+        .policies
+        .iter()
+        .map(|policy| &policy.policy_info)
+        .filter(|policy| policy.kind == form.kind.as_str())
+        .map(|policy| policy.version)
+        .max()
+        .unwrap_or(0)
+        + 1;
+
+    let policy_id: i64 = PublishPolicy::execute(form.kind, version, title, body, publisher).await?;
+    policies::invalidate();
+    info!(
+        "User {} published {} version {} (policy {}).",
+        publisher, form.kind, version, policy_id
+    );
+
+    return Ok(HttpResponse::Found()
+        .header(LOCATION, "/admin/policies")
+        .finish());
+}
+
+/// Who accepted a policy version, and when.
+#[get("/{policy_id}")]
+async fn report(req: HttpRequest, Path(policy_id): Path<i64>) -> Result<Page, TelescopeError> {
+    let data = PolicyAcceptanceReport::get(policy_id).await?;
+    let policy = data.policy.ok_or_else(|| {
+        TelescopeError::resource_not_found(
+            "Policy Not Found",
+            "Could not find a policy version with this ID.",
+        )
+    })?;
+
+    let title: String = format!(
+        "{} Version {}",
+        policy.policy_info.title, policy.policy_info.version
+    );
+    let mut template = Template::new(REPORT_TEMPLATE);
+    template.set_field("policy", policy)?;
+    return template.in_page(&req, title).await;
+}
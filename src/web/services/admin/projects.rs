@@ -0,0 +1,161 @@
+//! Project health overview for coordinators.
+//!
+//! Open to current coordinators as well as admins. Lists the projects with
+//! members this semester, how far along they are through their milestones,
+//! and whether their teams break the semester's team constraints. Projects
+//! that need attention are listed first. The team constraints are set here
+//! too.
+
+use crate::api::rcos::audit_log::{AuditAction, RecentAudits};
+use crate::api::rcos::prelude::SemesterId;
+use crate::api::rcos::projects::constraints::{SetTeamConstraints, TeamConstraints};
+use crate::api::rcos::projects::milestones::{
+    project_health::ProjectHealthProjects, MilestoneProgress, MilestoneStatus, ProjectHealth,
+};
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+
+/// The path to the project health template from the templates directory.
+const TEMPLATE_PATH: &'static str = "admin/projects";
+
+/// How many constraint overrides to list.
+const OVERRIDES_SHOWN: i64 = 20;
+
+/// Register project health services. These are registered in the
+/// `/admin/projects` scope.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(health).service(set_constraints);
+}
+
+/// A project, its progress through its milestones, and how its team breaks
+/// the team constraints.
+#[derive(Serialize, Debug)]
+struct ProjectProgress {
+    project: ProjectHealthProjects,
+    members: i64,
+    leads: i64,
+    progress: MilestoneProgress,
+    /// The next milestone that is not done.
+    next: Option<String>,
+    violations: Vec<String>,
+}
+
+/// Form submitted to set the team constraints of a semester. Empty fields
+/// remove the limit.
+#[derive(Deserialize, Debug)]
+struct ConstraintsForm {
+    semester_id: SemesterId,
+    #[serde(default)]
+    max_team_size: String,
+    #[serde(default)]
+    members_per_mentor: String,
+}
+
+/// Parse an optional positive limit from a form field.
+fn parse_limit(field: &str) -> Result<Option<i64>, TelescopeError> {
+    let field: &str = field.trim();
+    if field.is_empty() {
+        return Ok(None);
+    }
+
+    return field
+        .parse::<i64>()
+        .ok()
+        .filter(|limit| *limit > 0)
+        .map(Some)
+        .ok_or(TelescopeError::BadRequest {
+            header: "Invalid Team Constraints".into(),
+            message: "Limits have to be positive whole numbers, or empty for no limit.".into(),
+            show_status_code: false,
+        });
+}
+
+/// Current projects, their milestone progress, and team constraint
+/// violations.
+#[get("")]
+async fn health(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let data = ProjectHealth::get().await?;
+    let semester = data.semesters.into_iter().next();
+    let constraints: TeamConstraints = semester
+        .as_ref()
+        .and_then(|semester| semester.team_constraints.as_ref())
+        .map(|c| TeamConstraints {
+            max_team_size: c.max_team_size,
+            members_per_mentor: c.members_per_mentor,
+        })
+        .unwrap_or_default();
+
+    let done: String = MilestoneStatus::Done.to_string();
+    let mut projects: Vec<ProjectProgress> = data
+        .projects
+        .into_iter()
+        .map(|project| {
+            let progress = MilestoneProgress::new(
+                project
+                    .milestones
+                    .iter()
+                    .map(|milestone| (milestone.due_date, milestone.status.as_str())),
+            );
+            let next: Option<String> = project
+                .milestones
+                .iter()
+                .find(|milestone| milestone.status != done)
+                .map(|milestone| milestone.title.clone());
+            let members: i64 = project.members.aggregate.as_ref().map_or(0, |a| a.count);
+            let leads: i64 = project.leads.aggregate.as_ref().map_or(0, |a| a.count);
+
+            ProjectProgress {
+                project,
+                members,
+                leads,
+                progress,
+                next,
+                violations: constraints.violations(members, leads),
+            }
+        })
+        .collect();
+
+    // Broken constraints first, then most overdue milestones, then projects
+    // without any milestones.
+    projects.sort_by_key(|p| {
+        (
+            p.violations.is_empty(),
+            -(p.progress.overdue as i64),
+            p.progress.total > 0,
+        )
+    });
+
+    let mut template = Template::new(TEMPLATE_PATH);
+    template
+        .set_field("semester", semester)?
+        .set_field("constraints", constraints)?
+        .set_field("projects", projects)?
+        .set_field(
+            "overrides",
+            RecentAudits::get(AuditAction::JoinRequestOverride, OVERRIDES_SHOWN).await?,
+        )?;
+    return template.in_page(&req, "Project Health").await;
+}
+
+/// Set the team constraints of a semester.
+#[post("/constraints")]
+async fn set_constraints(
+    auth: AuthenticationCookie,
+    Form(form): Form<ConstraintsForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let coordinator = auth.get_user_id_or_error().await?;
+    let constraints = TeamConstraints {
+        max_team_size: parse_limit(&form.max_team_size)?,
+        members_per_mentor: parse_limit(&form.members_per_mentor)?,
+    };
+
+    SetTeamConstraints::execute(form.semester_id, constraints, coordinator).await?;
+    return Ok(HttpResponse::Found()
+        .header(LOCATION, "/admin/projects")
+        .finish());
+}
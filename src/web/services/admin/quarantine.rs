@@ -0,0 +1,39 @@
+//! Admin review of uploads quarantined after failing a malware scan.
+
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::uploads::quarantine;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use uuid::Uuid;
+
+/// Register quarantine services.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(index).service(delete);
+}
+
+/// List quarantined uploads, newest first.
+#[get("/quarantine")]
+async fn index(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let mut template = Template::new("admin/quarantine");
+    template.set_field("files", quarantine::list()?)?;
+    return template.in_page(&req, "Quarantined Uploads").await;
+}
+
+/// Delete a quarantined upload once it has been looked into. Uses post to
+/// prevent inadvertent deletion.
+#[post("/quarantine/delete/{id}")]
+async fn delete(Path(id): Path<Uuid>) -> Result<HttpResponse, TelescopeError> {
+    if !quarantine::delete(id)? {
+        return Err(TelescopeError::resource_not_found(
+            "File Not Found",
+            "There is no quarantined upload with this ID.",
+        ));
+    }
+
+    return Ok(HttpResponse::Found()
+        .header(LOCATION, "/admin/quarantine")
+        .finish());
+}
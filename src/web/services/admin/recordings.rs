@@ -0,0 +1,79 @@
+//! Queue of recordings that could not be matched to a meeting on their own.
+//!
+//! Open to current coordinators as well as admins. Each recording lists the
+//! meetings it could be of, best match first. Confirming one attaches the
+//! recording to it, and dismissing leaves the recording off every meeting.
+
+use crate::api::rcos::meetings::recordings::{ConfirmRecording, DismissRecording, RecordingQueue};
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use uuid::Uuid;
+
+/// The path to the recording queue template from the templates directory.
+const QUEUE_TEMPLATE: &'static str = "admin/recordings";
+
+/// Register recording queue services. These are registered in the
+/// `/admin/recordings` scope.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(queue).service(confirm).service(dismiss);
+}
+
+/// The recording queue.
+#[get("")]
+async fn queue(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let mut template = Template::new(QUEUE_TEMPLATE);
+    template.set_field("recordings", RecordingQueue::get().await?)?;
+    return template.in_page(&req, "Recordings").await;
+}
+
+/// Form submitted to attach a queued recording to a meeting.
+#[derive(Deserialize, Debug)]
+struct ConfirmForm {
+    url: String,
+    meeting_id: i64,
+}
+
+/// Attach a queued recording to the meeting a coordinator picked.
+#[post("/confirm")]
+async fn confirm(
+    auth: AuthenticationCookie,
+    Form(form): Form<ConfirmForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let coordinator: Uuid = auth.get_user_id_or_error().await?;
+    ConfirmRecording::execute(form.url.clone(), form.meeting_id, coordinator).await?;
+    info!(
+        "{} attached recording {} to meeting {}.",
+        coordinator, form.url, form.meeting_id
+    );
+    return Ok(to_queue());
+}
+
+/// Form submitted to dismiss a queued recording.
+#[derive(Deserialize, Debug)]
+struct DismissForm {
+    url: String,
+}
+
+/// Leave a queued recording off every meeting.
+#[post("/dismiss")]
+async fn dismiss(
+    auth: AuthenticationCookie,
+    Form(form): Form<DismissForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let coordinator: Uuid = auth.get_user_id_or_error().await?;
+    DismissRecording::execute(form.url.clone(), coordinator).await?;
+    info!("{} dismissed recording {}.", coordinator, form.url);
+    return Ok(to_queue());
+}
+
+/// Redirect back to the recording queue.
+fn to_queue() -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, "/admin/recordings")
+        .finish()
+}
@@ -0,0 +1,71 @@
+//! Anonymized dataset export for program-outcome research.
+//!
+//! Open to current coordinators as well as admins. The dataset has one row per
+//! enrollment with participation counts; the anonymization rules are in
+//! [`crate::api::rcos::research::anonymize`].
+
+use crate::api::rcos::research::anonymize::{Anonymizer, MIN_CELL_SIZE};
+use crate::api::rcos::research::{ResearchDataset, ResearchRow};
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use actix_web::http::header::{
+    ContentDisposition, DispositionParam, DispositionType, CONTENT_DISPOSITION, CONTENT_TYPE,
+};
+use actix_web::web::ServiceConfig;
+use actix_web::{HttpRequest, HttpResponse};
+use chrono::Utc;
+use csv::WriterBuilder;
+
+/// The path to the research export template from the templates directory.
+const TEMPLATE_PATH: &'static str = "admin/research";
+
+/// Register research export services. These are registered in the
+/// `/admin/research` scope.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(index).service(download);
+}
+
+/// What is in the research dataset, and a button to download it.
+#[get("")]
+async fn index(req: HttpRequest) -> Result<Page, TelescopeError> {
+    return Template::new(TEMPLATE_PATH)
+        .try_field("min_cell_size", MIN_CELL_SIZE)?
+        .in_page(&req, "Research Export")
+        .await;
+}
+
+/// Download the research dataset as a spreadsheet. Every download is
+/// anonymized with a new key, so pseudonyms differ between downloads.
+#[get("/csv")]
+async fn download() -> Result<HttpResponse, TelescopeError> {
+    let rows: Vec<ResearchRow> = ResearchDataset::get(&Anonymizer::new()).await?;
+
+    let mut buffer: Vec<u8> = Vec::new();
+    // Scope the writer so it is dropped before the buffer is used.
+    {
+        let mut writer = WriterBuilder::new().from_writer(&mut buffer);
+        for row in rows {
+            writer
+                .serialize(row)
+                .map_err(|e| TelescopeError::ise(format!("Could not write CSV row: {}", e)))?;
+        }
+        writer
+            .flush()
+            .map_err(|e| TelescopeError::ise(format!("Could not write CSV: {}", e)))?;
+    }
+
+    return Ok(HttpResponse::Ok()
+        .set_header(CONTENT_TYPE, "text/csv")
+        .set_header(
+            CONTENT_DISPOSITION,
+            ContentDisposition {
+                disposition: DispositionType::Attachment,
+                parameters: vec![DispositionParam::Filename(format!(
+                    "rcos-research-{}.csv",
+                    Utc::today().format("%Y-%m-%d")
+                ))],
+            },
+        )
+        .body(buffer));
+}
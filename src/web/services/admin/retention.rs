@@ -0,0 +1,82 @@
+//! Admin summary of the data retention rules.
+//!
+//! Shows the rules from the config, whether the retention job is in dry-run
+//! mode, and what its next run would remove. Accounts are marked withdrawn
+//! here, which starts the wait before they are anonymized.
+
+use crate::api::rcos::retention::{MarkWithdrawn, RetentionPlan, UnmarkWithdrawn};
+use crate::api::rcos::users::username::{self, UsernameLookup, UsernameTarget};
+use crate::env::{global_config, RetentionConfig};
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use uuid::Uuid;
+
+/// Register data retention services.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(index).service(withdraw).service(unwithdraw);
+}
+
+/// Form submitted to mark an account withdrawn.
+#[derive(Deserialize, Debug)]
+struct WithdrawForm {
+    /// The user's ID or username.
+    user: String,
+}
+
+/// Redirect to the retention summary.
+fn to_index() -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, "/admin/retention")
+        .finish()
+}
+
+/// Error for a user that could not be found or was already anonymized.
+fn no_such_account() -> TelescopeError {
+    TelescopeError::resource_not_found(
+        "User Not Found",
+        "Could not find an account by this ID or username that has not been anonymized.",
+    )
+}
+
+/// The retention rules and what the next run of the retention job would remove.
+#[get("/retention")]
+async fn index(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let rules: &RetentionConfig = &global_config().retention;
+    let mut template = Template::new("admin/retention/index");
+    template
+        .set_field("rules", rules)?
+        .set_field("plan", RetentionPlan::get(rules).await?)?;
+    return template.in_page(&req, "Data Retention").await;
+}
+
+/// Mark an account withdrawn.
+#[post("/retention/withdraw")]
+async fn withdraw(Form(form): Form<WithdrawForm>) -> Result<HttpResponse, TelescopeError> {
+    let input: &str = form.user.trim();
+    let user_id: Uuid = match input.parse::<Uuid>() {
+        Ok(id) => id,
+        Err(_) => match UsernameLookup::get(username::normalize(input)).await? {
+            Some(UsernameTarget::Current(id)) => id,
+            Some(UsernameTarget::Redirect { user_id, .. }) => user_id,
+            None => return Err(no_such_account()),
+        },
+    };
+
+    if !MarkWithdrawn::execute(user_id).await? {
+        return Err(no_such_account());
+    }
+    return Ok(to_index());
+}
+
+/// Take back the withdrawal of an account that has not been anonymized yet.
+#[post("/retention/{user_id}/restore")]
+async fn unwithdraw(Path(user_id): Path<Uuid>) -> Result<HttpResponse, TelescopeError> {
+    if !UnmarkWithdrawn::execute(user_id).await? {
+        return Err(no_such_account());
+    }
+    return Ok(to_index());
+}
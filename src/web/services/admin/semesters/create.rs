@@ -1,5 +1,7 @@
 //! Semester record creation.
 
+use crate::api::rcos::prelude::SemesterId;
+use crate::api::rcos::semesters::get_by_id::Semester;
 use crate::api::rcos::semesters::mutations::create::CreateSemester;
 use crate::error::TelescopeError;
 use crate::templates::Template;
@@ -99,6 +101,26 @@ pub async fn submit_new(
         return Err(TelescopeError::InvalidForm(page));
     }
 
+    // Check that the ID is not taken. Creating it again would fail in the API
+    // with a less helpful error.
+    if Semester::get_by_id(SemesterId(id.clone())).await?.is_some() {
+        let mut return_form_template: Template = new_semester_form_empty();
+        return_form_template.fields = json!({
+            "id": {
+                "value": id,
+                "issue": "There is already a semester with this ID."
+            },
+            "title": {"value": title},
+            "start": {"value": start},
+            "end": {"value": end}
+        });
+
+        let page = return_form_template
+            .in_page(&req, "Create Semester")
+            .await?;
+        return Err(TelescopeError::InvalidForm(page));
+    }
+
     // Everything is valid -- create the semester.
     CreateSemester::execute(id, title, start, end).await?;
 
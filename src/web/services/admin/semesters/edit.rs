@@ -1,5 +1,6 @@
 //! Semester Edit services.
 
+use crate::api::rcos::prelude::SemesterId;
 use crate::api::rcos::semesters::get_by_id::{semester::SemesterSemestersByPk, Semester};
 use crate::api::rcos::semesters::mutations::edit::EditSemester;
 use crate::error::TelescopeError;
@@ -39,7 +40,7 @@ pub async fn edit(
     Path(semester_id): Path<String>,
 ) -> Result<Page, TelescopeError> {
     // First lookup the semester.
-    let semester_data = Semester::get_by_id(semester_id).await?;
+    let semester_data = Semester::get_by_id(SemesterId(semester_id)).await?;
 
     // Make sure it exists.
     if semester_data.is_none() {
@@ -76,7 +77,7 @@ pub async fn submit_edit(
     // Validate title
     if title.trim().is_empty() {
         let mut return_form_template: Template = make_edit_form(semester_id, title, start, end);
-        return_form_template.fields["title"]["issue"] = json!("Title cannot be empty.");
+        return_form_template.set_path(&["title", "issue"], "Title cannot be empty.")?;
         let page = return_form_template.in_page(&req, "Edit Semester").await?;
         return Err(TelescopeError::InvalidForm(page));
     }
@@ -84,8 +85,8 @@ pub async fn submit_edit(
     // Validate dates.
     if start >= end {
         let mut return_form_template: Template = make_edit_form(semester_id, title, start, end);
-        return_form_template.fields["start"]["issue"] =
-            json!("Start date must be before end date.");
+        return_form_template
+            .set_path(&["start", "issue"], "Start date must be before end date.")?;
         let page = return_form_template.in_page(&req, "Edit Semester").await?;
         return Err(TelescopeError::InvalidForm(page));
     }
@@ -8,7 +8,7 @@ use regex::Regex;
 use crate::api::rcos::semesters::get::{Semesters, PER_PAGE};
 use crate::error::TelescopeError;
 use crate::templates::page::Page;
-use crate::templates::pagination::PaginationInfo;
+use crate::templates::pagination::{PaginationInfo, PaginationParameter};
 use crate::templates::Template;
 
 mod create;
@@ -25,17 +25,19 @@ pub fn register(config: &mut ServiceConfig) {
         .service(edit::edit)
         .service(edit::submit_edit)
         .service(view_enrollments::export_to_csv)
+        .service(view_enrollments::export_to_xlsx)
+        .service(view_enrollments::export_to_pdf)
         .route("/semesters", aweb::get().to(index))
         .route("/semesters/{page}", aweb::get().to(index));
 }
 
 /// Page to display previous semesters and allow edits.
 async fn index(req: HttpRequest, page_num: Option<Path<u32>>) -> Result<Page, TelescopeError> {
-    // Resolve the page number. Default to Page 1.
-    let page_num: u32 = page_num.map(|path| path.0).unwrap_or(1);
+    // Resolve the page. Default to Page 1.
+    let page = PaginationParameter::from_page(page_num.map(|path| path.0), PER_PAGE);
 
     // Send the API query to get semester data.
-    let semester_data = Semesters::get(page_num - 1).await?;
+    let semester_data = Semesters::get(page).await?;
 
     // Extract the semester count if available.
     let semester_count = semester_data
@@ -46,7 +48,7 @@ async fn index(req: HttpRequest, page_num: Option<Path<u32>>) -> Result<Page, Te
     // Render template and send back to user.
     let mut template = Template::new("admin/semesters/index");
     template.fields = json!({
-        "pagination": PaginationInfo::new(semester_count, PER_PAGE as u64, page_num as u64),
+        "pagination": PaginationInfo::new(semester_count, page, "/admin/semesters/", ""),
         "data": semester_data
     });
     return template.in_page(&req, "Semester Records").await;
@@ -5,22 +5,27 @@ use actix_web::http::header::{
 };
 use actix_web::web::{self as aweb, Path, Query, ServiceConfig};
 use actix_web::{HttpRequest, HttpResponse};
-use chrono::Utc;
+use chrono::{Local, Utc};
 use csv::WriterBuilder;
 use serde::Serialize;
 use serde_json::Value;
 use uuid::Uuid;
 
+use crate::api::rcos::attendance::policy::Standing;
+use crate::api::rcos::prelude::SemesterId;
 use crate::api::rcos::semesters::get_by_id::semester::SemesterSemestersByPk;
 use crate::api::rcos::semesters::get_by_id::Semester;
+use crate::api::rcos::semesters::grading_report::{GradingReport, GroupedReport, ReportEnrollment};
 use crate::api::rcos::users::enrollments::enrollments_lookup::EnrollmentsLookup;
 use crate::api::rcos::users::enrollments::user_enrollment_lookup::UserEnrollmentLookup;
 use crate::error::TelescopeError;
 use crate::templates::page::Page;
-use crate::templates::pagination::PaginationInfo;
+use crate::templates::pagination::{PaginationInfo, PaginationParameter};
 use crate::templates::Template;
+use crate::web::pdf::{PdfBuilder, Style, LETTER_HEIGHT, LETTER_WIDTH};
 use crate::web::services::admin::semesters::PER_PAGE;
 use crate::web::services::auth::identity::Identity;
+use crate::web::xlsx::{Cell, Spreadsheet};
 
 const TEMPLATE_PATH: &'static str = "admin/semesters/enrollments";
 /// The query parameters passed to the developers page indicating pagination
@@ -59,8 +64,12 @@ pub fn register_services(conf: &mut ServiceConfig) {
 }
 
 /// Try to get the pagination bar to use based on the api data.
-/// Panics if `current_page` is 0.
-fn get_page_numbers(api_response: &Value, current_page: u64) -> Option<PaginationInfo> {
+fn get_page_numbers(
+    api_response: &Value,
+    page: PaginationParameter,
+    prefix: &str,
+    query_string: &str,
+) -> Option<PaginationInfo> {
     api_response
         // Check for the JSON field user_count
         .get("user_count")?
@@ -71,7 +80,7 @@ fn get_page_numbers(api_response: &Value, current_page: u64) -> Option<Paginatio
         // As an unsigned integer
         .as_u64()
         // Convert to pagination info
-        .and_then(|count| PaginationInfo::new(count, PER_PAGE as u64, current_page))
+        .and_then(|count| PaginationInfo::new(count, page, prefix, query_string))
 }
 
 // download page for enrollments csv file.
@@ -117,60 +126,314 @@ pub async fn export_to_csv(
     Ok(resp)
 }
 
+/// Columns of the per small group sheets of the grading report.
+const GRADING_COLUMNS: &[&str] = &[
+    "Last Name",
+    "First Name",
+    "Preferred Name",
+    "RCS ID",
+    "Project",
+    "Project Lead",
+    "Coordinator",
+    "Credits",
+    "For Pay",
+    "Attended",
+    "Excused",
+    "Attendance Met",
+    "Mid-Year Grade",
+    "Final Grade",
+];
+
+/// Columns of the summary sheet of the grading report.
+const SUMMARY_COLUMNS: &[&str] = &[
+    "Small Group",
+    "Mentors",
+    "Projects",
+    "Students",
+    "For Credit",
+    "For Pay",
+    "Below Attendance",
+    "Mid-Year Grades",
+    "Final Grades",
+];
+
+/// Make a summary row for a group of enrollments.
+fn summary_row<'a>(
+    report: &GroupedReport,
+    title: &str,
+    mentors: String,
+    projects: usize,
+    enrollments: impl Iterator<Item = &'a ReportEnrollment> + Clone,
+) -> Vec<Cell> {
+    let count = |f: fn(&ReportEnrollment) -> bool| -> i64 {
+        enrollments.clone().filter(|e| f(e)).count() as i64
+    };
+    vec![
+        title.into(),
+        mentors.into(),
+        (projects as i64).into(),
+        count(|_| true).into(),
+        count(|e| e.credits > 0).into(),
+        count(|e| e.is_for_pay).into(),
+        (enrollments
+            .clone()
+            .filter(|e| !report.standing(e).meets_requirement)
+            .count() as i64)
+            .into(),
+        count(|e| e.mid_year_grade.is_some()).into(),
+        count(|e| e.final_grade.is_some()).into(),
+    ]
+}
+
+/// Get an enrollment's RCS ID if the student has linked one.
+fn rcs_id(enrollment: &ReportEnrollment) -> Option<String> {
+    enrollment
+        .user
+        .rcs_id
+        .first()
+        .map(|account| account.account_id.clone())
+}
+
+/// Get the title of an enrollment's project if there is one.
+fn project_title(enrollment: &ReportEnrollment) -> Option<String> {
+    enrollment
+        .project
+        .as_ref()
+        .map(|project| project.title.clone())
+}
+
+/// Make a grading row for an enrollment. Whether attendance is met is left
+/// blank if the semester has no attendance policy.
+fn grading_row(report: &GroupedReport, enrollment: &ReportEnrollment) -> Vec<Cell> {
+    let user = &enrollment.user;
+    let standing = report.standing(enrollment);
+    vec![
+        user.last_name.clone().into(),
+        user.first_name.clone().into(),
+        user.preferred_name.clone().into(),
+        rcs_id(enrollment).into(),
+        project_title(enrollment).into(),
+        enrollment.is_project_lead.into(),
+        enrollment.is_coordinator.into(),
+        enrollment.credits.into(),
+        enrollment.is_for_pay.into(),
+        standing.attended.into(),
+        standing.excused.into(),
+        standing.required.map(|_| standing.meets_requirement).into(),
+        enrollment.mid_year_grade.into(),
+        enrollment.final_grade.into(),
+    ]
+}
+
+/// Get a semester's grading report or a not found error.
+async fn grading_report(semester_id: SemesterId) -> Result<GroupedReport, TelescopeError> {
+    GradingReport::grouped(semester_id).await?.ok_or_else(|| {
+        TelescopeError::resource_not_found(
+            "Semester Not Found",
+            "Could not find a semester by this ID.",
+        )
+    })
+}
+
+/// Download a semester's grading report as a spreadsheet, with a summary sheet
+/// and a sheet of enrollments for each small group.
+#[get("/download/enrollments/{semester_id}/xlsx")]
+pub async fn export_to_xlsx(
+    Path(semester_id): Path<SemesterId>,
+) -> Result<HttpResponse, TelescopeError> {
+    let report = grading_report(semester_id).await?;
+
+    let mut summary: Vec<Vec<Cell>> = report
+        .groups
+        .iter()
+        .map(|group| {
+            summary_row(
+                &report,
+                group.title.as_str(),
+                group.mentors.join(", "),
+                group.project_count,
+                group.enrollments.iter(),
+            )
+        })
+        .collect();
+    summary.push(summary_row(
+        &report,
+        "Unassigned",
+        String::new(),
+        0,
+        report.unassigned.iter(),
+    ));
+    summary.push(summary_row(
+        &report,
+        "Total",
+        String::new(),
+        report.groups.iter().map(|group| group.project_count).sum(),
+        report.all(),
+    ));
+
+    let mut spreadsheet = Spreadsheet::new();
+    spreadsheet.add_sheet("Summary", SUMMARY_COLUMNS, summary)?;
+    for group in report.groups.iter() {
+        spreadsheet.add_sheet(
+            group.title.as_str(),
+            GRADING_COLUMNS,
+            group
+                .enrollments
+                .iter()
+                .map(|enrollment| grading_row(&report, enrollment)),
+        )?;
+    }
+    if !report.unassigned.is_empty() {
+        spreadsheet.add_sheet(
+            "Unassigned",
+            GRADING_COLUMNS,
+            report
+                .unassigned
+                .iter()
+                .map(|enrollment| grading_row(&report, enrollment)),
+        )?;
+    }
+
+    return spreadsheet.into_response(format!("grading-{}.xlsx", report.semester_id));
+}
+
+/// Column widths of the grading report PDF in millimeters. These add up to the
+/// content width of a letter page.
+const PDF_COLUMNS: [(&str, f64); 7] = [
+    ("Name", 46.0),
+    ("RCS ID", 22.0),
+    ("Project", 46.0),
+    ("Credits", 14.0),
+    ("Attended", 18.0),
+    ("Mid-Year", 17.0),
+    ("Final", 16.9),
+];
+
+/// Format an optional grade for the PDF report.
+fn format_grade(grade: Option<f64>) -> String {
+    grade.map(|grade| format!("{}", grade)).unwrap_or_default()
+}
+
+/// Format a student's attendance for the PDF report, marking students below
+/// the attendance policy.
+fn format_attendance(standing: Standing) -> String {
+    let attended = standing.attended + standing.counted_excused;
+    match standing.required {
+        Some(required) if !standing.meets_requirement => format!("{}/{} *", attended, required),
+        Some(required) => format!("{}/{}", attended, required),
+        None => attended.to_string(),
+    }
+}
+
+/// Write a table of enrollments to the grading report PDF.
+fn pdf_table<'a>(
+    pdf: &mut PdfBuilder,
+    report: &GroupedReport,
+    enrollments: impl Iterator<Item = &'a ReportEnrollment>,
+) {
+    let header: Vec<(&str, f64)> = PDF_COLUMNS.iter().copied().collect();
+    pdf.row(&header, 9.0, Style::Bold);
+    pdf.rule();
+
+    for enrollment in enrollments {
+        let user = &enrollment.user;
+        let name = format!(
+            "{}, {}",
+            user.last_name,
+            user.preferred_name
+                .as_deref()
+                .filter(|name| !name.is_empty())
+                .unwrap_or(user.first_name.as_str())
+        );
+        let rcs_id = rcs_id(enrollment).unwrap_or_default();
+        let project = project_title(enrollment).unwrap_or_default();
+        let credits = enrollment.credits.to_string();
+        let attended = format_attendance(report.standing(enrollment));
+        let mid_year = format_grade(enrollment.mid_year_grade);
+        let final_grade = format_grade(enrollment.final_grade);
+        let values = [
+            name.as_str(),
+            rcs_id.as_str(),
+            project.as_str(),
+            credits.as_str(),
+            attended.as_str(),
+            mid_year.as_str(),
+            final_grade.as_str(),
+        ];
+
+        let cells: Vec<(&str, f64)> = values
+            .iter()
+            .zip(PDF_COLUMNS.iter())
+            .map(|(value, (_, width))| (*value, *width))
+            .collect();
+        pdf.row(&cells, 9.0, Style::Regular);
+    }
+}
+
+/// Download a semester's grading report as a PDF for faculty, with a section
+/// for each small group.
+#[get("/download/enrollments/{semester_id}/pdf")]
+pub async fn export_to_pdf(
+    Path(semester_id): Path<SemesterId>,
+) -> Result<HttpResponse, TelescopeError> {
+    let report = grading_report(semester_id).await?;
+    let title = format!("RCOS Grading Report - {}", report.semester_title);
+
+    let mut pdf = PdfBuilder::new(title.as_str(), LETTER_WIDTH, LETTER_HEIGHT)?;
+    pdf.line(title.as_str(), 16.0, Style::Bold);
+    pdf.line(
+        format!(
+            "{} students, generated {}",
+            report.all().count(),
+            Local::today().format("%B %-d, %Y")
+        )
+        .as_str(),
+        10.0,
+        Style::Regular,
+    );
+    if let Some(policy) = report.policy {
+        pdf.line(
+            format!(
+                "Attendance counts up to {} excused absences. * marks students below the {} \
+                required meetings.",
+                policy.max_excused, policy.required_count
+            )
+            .as_str(),
+            9.0,
+            Style::Regular,
+        );
+    }
+
+    for group in report.groups.iter() {
+        pdf.space(6.0);
+        pdf.line(group.title.as_str(), 13.0, Style::Bold);
+        if !group.mentors.is_empty() {
+            pdf.line(
+                format!("Mentors: {}", group.mentors.join(", ")).as_str(),
+                9.0,
+                Style::Regular,
+            );
+        }
+        pdf_table(&mut pdf, &report, group.enrollments.iter());
+    }
+
+    if !report.unassigned.is_empty() {
+        pdf.space(6.0);
+        pdf.line("Not in a small group", 13.0, Style::Bold);
+        pdf_table(&mut pdf, &report, report.unassigned.iter());
+    }
+
+    return pdf.into_response(format!("grading-{}.pdf", report.semester_id));
+}
+
 pub async fn enrollments_page_index(
     req: HttpRequest,
     identity: Identity,
     Path((semester_id, page)): Path<(String, u32)>,
     Query(query): Query<EnrollmentPageQuery>,
 ) -> Result<Page, TelescopeError> {
-    // Resolve the page number from the request
-    let mut page_num = page;
-    if page_num >= 1 {
-        page_num -= 1;
-    } else {
-        page_num = 0;
-    }
-
-    // Get the API data by sending one of the enrollment page queries.
-    let semester = Semester::get_by_id(semester_id.clone())
-        .await?
-        .unwrap_or_else(|| SemesterSemestersByPk {
-            semester_id: semester_id.clone(),
-            title: "".to_string(),
-            start_date: Utc::today().naive_utc(),
-            end_date: Utc::today().naive_utc(),
-        });
-    let query_response =
-        UserEnrollmentLookup::get_by_id(page_num, query.search.clone(), semester_id.clone())
-            .await?;
-    let enrollments = query_response.enrollments.clone();
-    let enrollment_data = serde_json::to_value(enrollments).map_err(|e| {
-        TelescopeError::ise(format!(
-            "Could not serialize enrollments data to JSON format: {}",
-            e
-        ))
-    })?;
-    let api_data = serde_json::to_value(query_response).map_err(|e| {
-        TelescopeError::ise(format!(
-            "Could not serialize API data to JSON format: {}",
-            e
-        ))
-    })?;
-
-    // Get the viewers user ID
-    let viewer: Option<Uuid> = identity.get_user_id().await?;
-    let prefix = "/admin/semesters/enrollments/".to_owned() + &semester_id + "/";
-    let mut template = Template::new(TEMPLATE_PATH);
-    template.fields = json!({
-        "pagination": get_page_numbers(&api_data, page_num as u64 + 1),
-        "title": semester.title,
-        "data": enrollment_data,
-        "id": semester_id,
-        "identity": viewer,
-        "prefix": prefix,
-        "preserved_query_string": req.query_string(),
-    });
-    return template.in_page(&req, "Enrollments").await;
+    let page = PaginationParameter::from_page(Some(page), PER_PAGE);
+    return render_enrollments(req, identity, semester_id, page, query).await;
 }
 
 pub async fn enrollments_page(
@@ -178,9 +441,21 @@ pub async fn enrollments_page(
     identity: Identity,
     Path(semester_id): Path<String>,
     Query(query): Query<EnrollmentPageQuery>,
+) -> Result<Page, TelescopeError> {
+    let page = PaginationParameter::from_page(None, PER_PAGE);
+    return render_enrollments(req, identity, semester_id, page, query).await;
+}
+
+/// Render a page of a semester's enrollments.
+async fn render_enrollments(
+    req: HttpRequest,
+    identity: Identity,
+    semester_id: String,
+    page: PaginationParameter,
+    query: EnrollmentPageQuery,
 ) -> Result<Page, TelescopeError> {
     // Get the API data by sending one of the enrollment page queries.
-    let semester = Semester::get_by_id(semester_id.clone())
+    let semester = Semester::get_by_id(SemesterId(semester_id.clone()))
         .await?
         .unwrap_or_else(|| SemesterSemestersByPk {
             semester_id: semester_id.clone(),
@@ -189,7 +464,7 @@ pub async fn enrollments_page(
             end_date: Utc::today().naive_utc(),
         });
     let query_response =
-        UserEnrollmentLookup::get_by_id(0, query.search.clone(), semester_id.clone()).await?;
+        UserEnrollmentLookup::get_by_id(page, query.search.clone(), semester_id.clone()).await?;
     let enrollments = query_response.enrollments.clone();
     let enrollment_data = serde_json::to_value(enrollments).map_err(|e| {
         TelescopeError::ise(format!(
@@ -206,16 +481,14 @@ pub async fn enrollments_page(
 
     // Get the viewers user ID
     let viewer: Option<Uuid> = identity.get_user_id().await?;
-    let prefix = "/admin/semesters/enrollments/".to_owned() + &semester_id + "/";
+    let prefix = format!("/admin/semesters/enrollments/{}/", semester_id);
     let mut template = Template::new(TEMPLATE_PATH);
     template.fields = json!({
+        "pagination": get_page_numbers(&api_data, page, prefix.as_str(), req.query_string()),
         "title": semester.title,
-        "pagination": get_page_numbers(&api_data, 1),
         "data": enrollment_data,
         "id": semester_id,
         "identity": viewer,
-        "prefix": prefix,
-        "preserved_query_string": req.query_string(),
     });
     return template.in_page(&req, "Enrollments").await;
 }
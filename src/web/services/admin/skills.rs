@@ -0,0 +1,99 @@
+//! Skill taxonomy management for coordinators.
+//!
+//! Open to current coordinators as well as admins. Lists every skill members
+//! and projects can be tagged with, and how often each is used. Skills are
+//! added and removed here.
+
+use crate::api::rcos::skills::{AllSkills, CreateSkill, DeleteSkill, SkillCategory};
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+
+/// The path to the skill taxonomy template from the templates directory.
+const TEMPLATE_PATH: &'static str = "admin/skills";
+
+/// The longest skill name.
+const MAX_NAME_LENGTH: usize = 50;
+
+/// Register skill taxonomy services. These are registered in the
+/// `/admin/skills` scope.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(index)
+        .service(create_skill)
+        .service(delete_skill);
+}
+
+/// Form submitted to add a skill.
+#[derive(Deserialize, Debug)]
+struct SkillForm {
+    name: String,
+    category: SkillCategory,
+}
+
+/// Redirect to the skill taxonomy.
+fn to_index() -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, "/admin/skills")
+        .finish()
+}
+
+/// Every skill, by category.
+#[get("")]
+async fn index(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let mut template = Template::new(TEMPLATE_PATH);
+    template
+        .set_field("skills", AllSkills::get().await?)?
+        .set_field(
+            "categories",
+            SkillCategory::ALL
+                .iter()
+                .map(|category| json!({"name": category, "label": category.label()}))
+                .collect::<Vec<_>>(),
+        )?;
+    return template.in_page(&req, "Skills").await;
+}
+
+/// Add a skill.
+#[post("")]
+async fn create_skill(Form(form): Form<SkillForm>) -> Result<HttpResponse, TelescopeError> {
+    let name: &str = form.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Err(TelescopeError::BadRequest {
+            header: "Invalid Skill".into(),
+            message: format!(
+                "Skills need a name of at most {} characters.",
+                MAX_NAME_LENGTH
+            ),
+            show_status_code: false,
+        });
+    }
+
+    if CreateSkill::execute(name.to_string(), form.category)
+        .await?
+        .is_none()
+    {
+        return Err(TelescopeError::BadRequest {
+            header: "Skill Already Exists".into(),
+            message: format!("There is already a skill named {}.", name),
+            show_status_code: false,
+        });
+    }
+    return Ok(to_index());
+}
+
+/// Remove a skill, untagging it from every member and project. Uses post to
+/// prevent inadvertent removal.
+#[post("/{skill_id}/delete")]
+async fn delete_skill(Path(skill_id): Path<i64>) -> Result<HttpResponse, TelescopeError> {
+    if !DeleteSkill::execute(skill_id).await? {
+        return Err(TelescopeError::resource_not_found(
+            "Skill Not Found",
+            "Could not find a skill by this ID.",
+        ));
+    }
+    return Ok(to_index());
+}
@@ -0,0 +1,269 @@
+//! Admin services to manage sponsor records.
+
+use crate::api::rcos::sponsors::get::{AllSponsors, Sponsor};
+use crate::api::rcos::sponsors::mutations::{
+    CreateSponsor, DeleteSponsor, EditSponsor, SetSponsorLogo, SponsorFields,
+};
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::images::{self, Variant};
+use crate::web::uploads::{self, UploadKind};
+use crate::web::validation::validate_url;
+use actix_multipart::Multipart;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use chrono::NaiveDate;
+
+/// The path to the form template from the templates directory.
+const FORM_TEMPLATE: &'static str = "admin/sponsors/form";
+
+/// Register sponsor management services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(index)
+        .service(new)
+        .service(submit_new)
+        .service(edit)
+        .service(submit_edit)
+        .service(upload_logo)
+        .service(delete);
+}
+
+/// Form submitted to create or edit a sponsor.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SponsorForm {
+    name: String,
+    description: String,
+    logo_url: String,
+    website_url: String,
+    tier: i64,
+    start_date: NaiveDate,
+    /// Empty if the sponsorship has no end.
+    end_date: String,
+}
+
+impl SponsorForm {
+    /// Create a form pre-filled with an existing sponsor's fields.
+    fn from_fields(fields: SponsorFields) -> Self {
+        SponsorForm {
+            name: fields.name,
+            description: fields.description.unwrap_or_default(),
+            logo_url: fields.logo_url,
+            website_url: fields.website_url.unwrap_or_default(),
+            tier: fields.tier,
+            start_date: fields.start_date,
+            end_date: fields
+                .end_date
+                .map(|date| date.to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Trim a string and convert it to none if it is empty.
+fn non_empty(string: &str) -> Option<String> {
+    let trimmed = string.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+/// Make the sponsor form template. If `editing` is some, the form edits that sponsor.
+fn make_form(editing: Option<i64>, selections: Option<&SponsorForm>) -> Template {
+    let mut form = Template::new(FORM_TEMPLATE);
+    form.fields = json!({
+        "editing": editing,
+        "selections": selections,
+    });
+    return form;
+}
+
+/// Validate a submitted sponsor form. On success, return the fields to save.
+/// Otherwise, return the form template with issues marked.
+fn validate(editing: Option<i64>, input: &SponsorForm) -> Result<SponsorFields, Template> {
+    let mut form: Template = make_form(editing, Some(input));
+
+    if input.name.trim().is_empty() {
+        form.set_issue("name", "Name cannot be empty.");
+    }
+
+    // Logos can be local paths, so they are not validated as URLs.
+    if input.logo_url.trim().is_empty() {
+        form.set_issue("logo_url", "A logo is required.");
+    }
+
+    let website_url: Option<String> = validate_url(Some(input.website_url.as_str()), &[])
+        .unwrap_or_else(|issue| {
+            form.set_issue("website_url", issue);
+            None
+        });
+
+    // Parse the end date if there is one.
+    let end_date: Option<NaiveDate> = match non_empty(input.end_date.as_str()) {
+        None => None,
+        Some(string) => match string.parse::<NaiveDate>() {
+            Ok(date) if date < input.start_date => {
+                form.set_issue("end_date", "End date cannot be before the start date.");
+                None
+            }
+            Ok(date) => Some(date),
+            Err(_) => {
+                form.set_issue("end_date", "Invalid date.");
+                None
+            }
+        },
+    };
+
+    if form["issues"] != json!(null) {
+        return Err(form);
+    }
+
+    return Ok(SponsorFields {
+        name: input.name.trim().to_string(),
+        description: non_empty(input.description.as_str()),
+        logo_url: input.logo_url.trim().to_string(),
+        website_url,
+        tier: input.tier,
+        start_date: input.start_date,
+        end_date,
+    });
+}
+
+/// List all sponsor records.
+#[get("/sponsors")]
+async fn index(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let sponsors = AllSponsors::get().await?;
+    let mut template = Template::new("admin/sponsors/index");
+    template.set_field("sponsors", sponsors)?;
+    return template.in_page(&req, "Sponsors").await;
+}
+
+/// Form to create a sponsor record.
+#[get("/sponsors/create")]
+async fn new(req: HttpRequest) -> Result<Page, TelescopeError> {
+    make_form(None, None).in_page(&req, "Add Sponsor").await
+}
+
+/// Submit a new sponsor record.
+#[post("/sponsors/create")]
+async fn submit_new(
+    req: HttpRequest,
+    Form(input): Form<SponsorForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    // Validate the form.
+    let fields: SponsorFields = match validate(None, &input) {
+        Ok(fields) => fields,
+        Err(form) => {
+            let page = form.in_page(&req, "Add Sponsor").await?;
+            return Err(TelescopeError::InvalidForm(page));
+        }
+    };
+
+    // Create the sponsor.
+    CreateSponsor::execute(fields).await?;
+    Ok(HttpResponse::Found()
+        .header(LOCATION, "/admin/sponsors")
+        .finish())
+}
+
+/// Form to edit a sponsor record.
+#[get("/sponsors/edit/{sponsor_id}")]
+async fn edit(req: HttpRequest, Path(sponsor_id): Path<i64>) -> Result<Page, TelescopeError> {
+    // Lookup the sponsor.
+    let sponsor = Sponsor::get(sponsor_id)
+        .await?
+        .ok_or(TelescopeError::resource_not_found(
+            "Sponsor Not Found",
+            "Could not find a sponsor with this ID.",
+        ))?;
+
+    // Pre-fill the form.
+    let selections = SponsorForm::from_fields(SponsorFields {
+        name: sponsor.name,
+        description: sponsor.description,
+        logo_url: sponsor.logo_url,
+        website_url: sponsor.website_url,
+        tier: sponsor.tier,
+        start_date: sponsor.start_date,
+        end_date: sponsor.end_date,
+    });
+
+    make_form(Some(sponsor_id), Some(&selections))
+        .in_page(&req, "Edit Sponsor")
+        .await
+}
+
+/// Submit edits to a sponsor record.
+#[post("/sponsors/edit/{sponsor_id}")]
+async fn submit_edit(
+    req: HttpRequest,
+    Path(sponsor_id): Path<i64>,
+    Form(input): Form<SponsorForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    // Validate the form.
+    let fields: SponsorFields = match validate(Some(sponsor_id), &input) {
+        Ok(fields) => fields,
+        Err(form) => {
+            let page = form.in_page(&req, "Edit Sponsor").await?;
+            return Err(TelescopeError::InvalidForm(page));
+        }
+    };
+
+    // Save the edits.
+    EditSponsor::execute(sponsor_id, fields)
+        .await?
+        .ok_or(TelescopeError::resource_not_found(
+            "Sponsor Not Found",
+            "Could not find a sponsor with this ID.",
+        ))?;
+
+    Ok(HttpResponse::Found()
+        .header(LOCATION, "/admin/sponsors")
+        .finish())
+}
+
+/// Upload a logo for a sponsor, replacing its logo URL.
+#[post("/sponsors/logo/{sponsor_id}")]
+async fn upload_logo(
+    Path(sponsor_id): Path<i64>,
+    payload: Multipart,
+) -> Result<HttpResponse, TelescopeError> {
+    let file =
+        uploads::read(payload)
+            .await?
+            .take_file("logo")
+            .ok_or(TelescopeError::BadRequest {
+                header: "No Logo Selected".into(),
+                message: "Choose a logo to upload.".into(),
+                show_status_code: false,
+            })?;
+
+    let upload = uploads::check(UploadKind::Image, file, None).await?;
+    let image_id = images::store(upload).await?;
+
+    SetSponsorLogo::execute(sponsor_id, images::url(image_id, Variant::Medium))
+        .await?
+        .ok_or(TelescopeError::resource_not_found(
+            "Sponsor Not Found",
+            "Could not find a sponsor with this ID.",
+        ))?;
+
+    Ok(HttpResponse::Found()
+        .header(LOCATION, format!("/admin/sponsors/edit/{}", sponsor_id))
+        .finish())
+}
+
+/// Delete a sponsor record. Uses post to prevent inadvertent deletion.
+#[post("/sponsors/delete/{sponsor_id}")]
+async fn delete(Path(sponsor_id): Path<i64>) -> Result<HttpResponse, TelescopeError> {
+    DeleteSponsor::execute(sponsor_id)
+        .await?
+        .ok_or(TelescopeError::resource_not_found(
+            "Sponsor Not Found",
+            "Could not find a sponsor with this ID.",
+        ))?;
+
+    Ok(HttpResponse::Found()
+        .header(LOCATION, "/admin/sponsors")
+        .finish())
+}
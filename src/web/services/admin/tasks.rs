@@ -0,0 +1,252 @@
+//! Coordinator task board.
+//!
+//! Unlike the rest of the admin panel, the task board is open to current
+//! coordinators as well as admins, since most of the work tracked here is
+//! theirs. Tasks are created by hand or by the task generator job when
+//! something needs review.
+
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::api::rcos::tasks::get::{TaskBoard, TaskById};
+use crate::api::rcos::tasks::mutations::{CreateTask, EditTask, SetTaskStatus, TaskFields};
+use crate::api::rcos::tasks::TaskStatus;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::middlewares::authorization::AuthorizationResult;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, Query, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use chrono::NaiveDate;
+use futures::future::LocalBoxFuture;
+use uuid::Uuid;
+
+/// The path to the task board template from the templates directory.
+const BOARD_TEMPLATE: &'static str = "admin/tasks/board";
+
+/// The path to the task edit form from the templates directory.
+const EDIT_TEMPLATE: &'static str = "admin/tasks/edit";
+
+/// Check that a user can use the task board.
+pub fn coordinator_authorization(user_id: Uuid) -> LocalBoxFuture<'static, AuthorizationResult> {
+    Box::pin(async move {
+        // Coordinators, faculty advisors, and sysadmins can use the task board.
+        if AuthorizationFor::get(Some(user_id))
+            .await?
+            .can_view_drafts()
+        {
+            Ok(())
+        } else {
+            Err(TelescopeError::Forbidden)
+        }
+    })
+}
+
+/// Register task board services. These are registered in the `/admin/tasks` scope.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(board)
+        .service(create)
+        .service(edit_form)
+        .service(save_edits)
+        .service(set_status);
+}
+
+/// Filter for the task board.
+#[derive(Deserialize, Debug)]
+struct BoardQuery {
+    /// Only show tasks assigned to the viewer.
+    #[serde(default)]
+    mine: bool,
+}
+
+/// Task fields submitted through the create and edit forms.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct TaskForm {
+    title: String,
+    #[serde(default)]
+    description: String,
+    /// User ID of the assignee, or empty for nobody.
+    #[serde(default)]
+    assignee_id: String,
+    /// Due date (YYYY-MM-DD), or empty for none.
+    #[serde(default)]
+    due_date: String,
+}
+
+/// Form to move a task to another column.
+#[derive(Deserialize, Debug)]
+struct StatusForm {
+    status: TaskStatus,
+}
+
+/// Redirect back to the task board.
+fn to_board() -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, "/admin/tasks")
+        .finish()
+}
+
+/// Check the submitted task fields, setting any issues on the form template.
+fn validate(form: &mut Template, submitted: &TaskForm) -> Result<TaskFields, TelescopeError> {
+    form.set_field("task", submitted)?;
+
+    let title: String = submitted.title.trim().to_string();
+    if title.is_empty() {
+        form.set_issue("title", "Cannot be empty.");
+    }
+
+    let description: Option<String> =
+        Some(submitted.description.trim().to_string()).filter(|s| !s.is_empty());
+
+    let assignee_id: Option<Uuid> = match submitted.assignee_id.trim() {
+        "" => None,
+        id => match id.parse::<Uuid>() {
+            Ok(id) => Some(id),
+            Err(_) => {
+                form.set_issue("assignee_id", "Invalid assignee.");
+                None
+            }
+        },
+    };
+
+    let due_date: Option<NaiveDate> = match submitted.due_date.trim() {
+        "" => None,
+        date => match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            Ok(date) => Some(date),
+            Err(_) => {
+                form.set_issue("due_date", "Invalid date.");
+                None
+            }
+        },
+    };
+
+    return Ok(TaskFields {
+        title,
+        description,
+        assignee_id,
+        due_date,
+    });
+}
+
+/// Make the task board template.
+async fn make_board(assignee: Option<Uuid>) -> Result<Template, TelescopeError> {
+    let mut template = Template::new(BOARD_TEMPLATE);
+    template
+        .set_field("board", TaskBoard::get(assignee).await?)?
+        .set_field("mine", assignee.is_some())?;
+    return Ok(template);
+}
+
+/// The task board.
+#[get("")]
+async fn board(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    query: Option<Query<BoardQuery>>,
+) -> Result<Page, TelescopeError> {
+    let assignee: Option<Uuid> = match query {
+        Some(Query(BoardQuery { mine: true })) => Some(auth.get_user_id_or_error().await?),
+        _ => None,
+    };
+
+    return make_board(assignee)
+        .await?
+        .in_page(&req, "Task Board")
+        .await;
+}
+
+/// Create a task.
+#[post("")]
+async fn create(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Form(submitted): Form<TaskForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer: Uuid = auth.get_user_id_or_error().await?;
+
+    let mut form = Template::new(BOARD_TEMPLATE);
+    let fields = validate(&mut form, &submitted)?;
+    if form["issues"] != json!(null) {
+        // Show the board again with the submitted fields filled in.
+        form.set_field("board", TaskBoard::get(None).await?)?;
+        return Err(TelescopeError::InvalidForm(
+            form.in_page(&req, "Task Board").await?,
+        ));
+    }
+
+    CreateTask::execute(fields, viewer).await?;
+    return Ok(to_board());
+}
+
+/// Get a task or a not found error.
+async fn get_task(task_id: i64) -> Result<serde_json::Value, TelescopeError> {
+    let task = TaskById::get(task_id).await?.task.ok_or_else(|| {
+        TelescopeError::resource_not_found("Task Not Found", "Could not find a task with this ID.")
+    })?;
+    return Ok(json!(task));
+}
+
+/// Make the edit form for a task.
+async fn make_edit_form(task_id: i64) -> Result<Template, TelescopeError> {
+    let mut form = Template::new(EDIT_TEMPLATE);
+    let task = get_task(task_id).await?;
+    form.set_field(
+        "task",
+        json!({
+            "task_id": task_id,
+            "title": task["title"],
+            "description": task["description"],
+            "assignee_id": task["assignee"]["id"],
+            "due_date": task["due_date"],
+        }),
+    )?
+    .set_field("assignable", TaskBoard::get(None).await?.assignable)?;
+    return Ok(form);
+}
+
+/// Form to edit a task.
+#[get("/{task_id}")]
+async fn edit_form(req: HttpRequest, Path(task_id): Path<i64>) -> Result<Page, TelescopeError> {
+    return make_edit_form(task_id)
+        .await?
+        .in_page(&req, "Edit Task")
+        .await;
+}
+
+/// Save edits to a task.
+#[post("/{task_id}")]
+async fn save_edits(
+    req: HttpRequest,
+    Path(task_id): Path<i64>,
+    Form(submitted): Form<TaskForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let mut form = make_edit_form(task_id).await?;
+    let fields = validate(&mut form, &submitted)?;
+    form.set_path(&["task", "task_id"], task_id)?;
+    if form["issues"] != json!(null) {
+        return Err(TelescopeError::InvalidForm(
+            form.in_page(&req, "Edit Task").await?,
+        ));
+    }
+
+    EditTask::execute(task_id, fields).await?;
+    return Ok(to_board());
+}
+
+/// Move a task to another column.
+#[post("/{task_id}/status")]
+async fn set_status(
+    Path(task_id): Path<i64>,
+    Form(form): Form<StatusForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    SetTaskStatus::execute(task_id, form.status)
+        .await?
+        .ok_or_else(|| {
+            TelescopeError::resource_not_found(
+                "Task Not Found",
+                "Could not find a task with this ID.",
+            )
+        })?;
+    return Ok(to_board());
+}
@@ -0,0 +1,97 @@
+//! Admin tools for finding and merging duplicate user accounts.
+//!
+//! People sometimes end up with more than one account (for example, by
+//! signing in with Discord before linking their RCS ID). Merging moves the
+//! duplicate's records to the account being kept and then deletes it.
+
+use crate::api::rcos::users::merge::{DuplicateCandidates, MergeDiff, MergePreview, MergeUsers};
+use crate::error::TelescopeError;
+use crate::templates::cache::{self, Dependency};
+use crate::templates::page::Page;
+use crate::templates::Template;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Query, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use uuid::Uuid;
+
+/// Register user management services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(duplicates)
+        .service(merge_preview)
+        .service(merge);
+}
+
+/// The two users to merge.
+#[derive(Deserialize, Debug)]
+struct MergeTarget {
+    /// The user to keep.
+    keep: Uuid,
+    /// The user to merge into the kept one and delete.
+    remove: Uuid,
+}
+
+/// Get the dry-run of a merge, or an error if it cannot be done.
+async fn get_diff(target: &MergeTarget) -> Result<MergeDiff, TelescopeError> {
+    if target.keep == target.remove {
+        return Err(TelescopeError::BadRequest {
+            header: "Cannot Merge".into(),
+            message: "A user cannot be merged into themselves.".into(),
+            show_status_code: false,
+        });
+    }
+
+    return MergePreview::get(target.keep, target.remove)
+        .await?
+        .ok_or_else(|| {
+            TelescopeError::resource_not_found(
+                "User Not Found",
+                "Could not find one of the users to merge. They may have already been merged.",
+            )
+        });
+}
+
+/// List likely duplicate accounts.
+#[get("/users/duplicates")]
+async fn duplicates(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let pairs = DuplicateCandidates::get().await?;
+    let mut template = Template::new("admin/users/duplicates");
+    template.set_field("pairs", pairs)?;
+    return template.in_page(&req, "Duplicate Accounts").await;
+}
+
+/// Show what a merge would change without making it.
+#[get("/users/merge")]
+async fn merge_preview(
+    req: HttpRequest,
+    Query(target): Query<MergeTarget>,
+) -> Result<Page, TelescopeError> {
+    let diff = get_diff(&target).await?;
+    let mut template = Template::new("admin/users/merge");
+    template
+        .set_field("mergeable", diff.is_mergeable())?
+        .set_field("diff", diff)?;
+    return template.in_page(&req, "Merge Accounts").await;
+}
+
+/// Merge two users after checking that nothing blocks it.
+#[post("/users/merge")]
+async fn merge(Form(target): Form<MergeTarget>) -> Result<HttpResponse, TelescopeError> {
+    let diff = get_diff(&target).await?;
+    if !diff.is_mergeable() {
+        return Err(TelescopeError::BadRequest {
+            header: "Cannot Merge".into(),
+            message: "The account being removed has records that have to be moved or \
+                removed by hand first. See the merge preview for details."
+                .into(),
+            show_status_code: false,
+        });
+    }
+
+    MergeUsers::execute(target.keep, target.remove, diff.discarded_accounts).await?;
+    cache::invalidate(Dependency::Users);
+
+    return Ok(HttpResponse::Found()
+        .header(LOCATION, format!("/user/{}", target.keep))
+        .finish());
+}
@@ -0,0 +1,234 @@
+//! Admin management of outgoing webhook endpoints, and the log of attempts to
+//! deliver events to them.
+
+use crate::api::rcos::webhooks::{
+    CreateWebhookEndpoint, DeleteWebhookEndpoint, RecentWebhookAttempts, RetryWebhookDelivery,
+    SetWebhookEndpointEnabled, WebhookEndpoints,
+};
+use crate::error::TelescopeError;
+use crate::jobs::webhooks::MAX_ATTEMPTS;
+use crate::templates::forms::{Field, FieldKind, FormSchema};
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::validation::{validate_url, MAX_URL_LENGTH};
+use crate::web::webhooks::EVENTS;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// The path to the endpoint list from the templates directory.
+const INDEX_TEMPLATE: &'static str = "admin/webhooks/index";
+
+/// The path to the delivery log from the templates directory.
+const LOG_TEMPLATE: &'static str = "admin/webhooks/deliveries";
+
+/// How many attempts the delivery log shows.
+const LOG_LENGTH: i64 = 200;
+
+/// How many characters are in a generated secret.
+const SECRET_LENGTH: usize = 40;
+
+/// The shortest secret an admin can pick.
+const MIN_SECRET_LENGTH: usize = 16;
+
+/// The fields of the form to register an endpoint. The events are checkboxes
+/// rendered from [`EVENTS`].
+const ENDPOINT_FORM: FormSchema = FormSchema {
+    fields: &[
+        Field::new("url", "URL", FieldKind::Url)
+            .required()
+            .max_length(MAX_URL_LENGTH),
+        Field::new("description", "Description (optional)", FieldKind::Text)
+            .max_length(200)
+            .help("What the integration is, so other admins know what it is for."),
+        Field::new("secret", "Secret (optional)", FieldKind::Text)
+            .max_length(200)
+            .help("Deliveries are signed with this. Leave it blank to generate one."),
+    ],
+};
+
+/// Register webhook services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(index)
+        .service(create)
+        .service(set_enabled)
+        .service(delete)
+        .service(deliveries)
+        .service(retry);
+}
+
+/// Form submitted to register an endpoint. The events it subscribes to are
+/// submitted as checkboxes named after them.
+#[derive(Serialize, Deserialize, Debug)]
+struct EndpointForm {
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    secret: String,
+    #[serde(flatten)]
+    checked: HashMap<String, String>,
+}
+
+/// Form submitted to enable or disable an endpoint.
+#[derive(Deserialize, Debug)]
+struct EnabledForm {
+    enabled: bool,
+}
+
+/// Redirect to the endpoint list.
+fn to_index() -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, "/admin/webhooks")
+        .finish()
+}
+
+/// Error for an endpoint that does not exist.
+fn no_such_endpoint() -> TelescopeError {
+    TelescopeError::resource_not_found(
+        "Webhook Endpoint Not Found",
+        "Could not find a webhook endpoint with this ID.",
+    )
+}
+
+/// The endpoint list with the registration form, with the events checked in
+/// `checked` selected.
+async fn make_index(checked: &[&str]) -> Result<Template, TelescopeError> {
+    let events: Vec<_> = EVENTS
+        .iter()
+        .map(|(name, description)| {
+            json!({
+                "name": name,
+                "description": description,
+                "checked": checked.contains(name),
+            })
+        })
+        .collect();
+
+    let mut template = Template::new(INDEX_TEMPLATE);
+    template
+        .set_field("endpoints", WebhookEndpoints::get().await?)?
+        .set_field("events", events)?
+        .set_field("schema", ENDPOINT_FORM)?;
+    return Ok(template);
+}
+
+/// Every registered endpoint, and the form to register another.
+#[get("/webhooks")]
+async fn index(req: HttpRequest) -> Result<Page, TelescopeError> {
+    make_index(&[]).await?.in_page(&req, "Webhooks").await
+}
+
+/// Register an endpoint.
+#[post("/webhooks")]
+async fn create(
+    req: HttpRequest,
+    Form(submitted): Form<EndpointForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let events: Vec<&'static str> = EVENTS
+        .iter()
+        .map(|(name, _)| *name)
+        .filter(|name| submitted.checked.contains_key(*name))
+        .collect();
+
+    let mut form = make_index(&events).await?;
+    form.set_field("selections", &submitted)?;
+
+    let url: Option<String> = match validate_url(Some(submitted.url.as_str()), &[]) {
+        Ok(url) => url,
+        Err(issue) => {
+            form.set_issue("url", issue);
+            None
+        }
+    };
+    let secret: &str = submitted.secret.trim();
+    if !secret.is_empty() && secret.chars().count() < MIN_SECRET_LENGTH {
+        form.set_issue(
+            "secret",
+            format!(
+                "Secrets must be at least {} characters long.",
+                MIN_SECRET_LENGTH
+            ),
+        );
+    }
+    if events.is_empty() {
+        form.set_issue("events", "Pick at least one event to send.");
+    }
+    // The schema checks the rest, and a URL is required.
+    ENDPOINT_FORM.validate(&mut form, &json!(&submitted));
+
+    let url: String = match url {
+        Some(url) if form["issues"] == json!(null) => url,
+        _ => {
+            let page = form.in_page(&req, "Webhooks").await?;
+            return Err(TelescopeError::InvalidForm(page));
+        }
+    };
+
+    let description: Option<String> = Some(submitted.description.trim())
+        .filter(|description| !description.is_empty())
+        .map(String::from);
+    let secret: String = if secret.is_empty() {
+        rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(SECRET_LENGTH)
+            .map(char::from)
+            .collect()
+    } else {
+        secret.to_string()
+    };
+
+    CreateWebhookEndpoint::execute(url, description, secret, events).await?;
+    return Ok(to_index());
+}
+
+/// Stop or resume sending new events to an endpoint.
+#[post("/webhooks/{endpoint_id}/enabled")]
+async fn set_enabled(
+    Path(endpoint_id): Path<i64>,
+    Form(form): Form<EnabledForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    if !SetWebhookEndpointEnabled::execute(endpoint_id, form.enabled).await? {
+        return Err(no_such_endpoint());
+    }
+    return Ok(to_index());
+}
+
+/// Remove an endpoint and its delivery log.
+#[post("/webhooks/{endpoint_id}/delete")]
+async fn delete(Path(endpoint_id): Path<i64>) -> Result<HttpResponse, TelescopeError> {
+    if !DeleteWebhookEndpoint::execute(endpoint_id).await? {
+        return Err(no_such_endpoint());
+    }
+    return Ok(to_index());
+}
+
+/// The latest attempts to deliver events to every endpoint.
+#[get("/webhooks/deliveries")]
+async fn deliveries(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let mut template = Template::new(LOG_TEMPLATE);
+    template
+        .set_field("attempts", RecentWebhookAttempts::get(LOG_LENGTH).await?)?
+        .set_field("max_attempts", MAX_ATTEMPTS)?;
+    return template.in_page(&req, "Webhook Deliveries").await;
+}
+
+/// Try a delivery that was given up on again.
+#[post("/webhooks/deliveries/{delivery_id}/retry")]
+async fn retry(Path(delivery_id): Path<i64>) -> Result<HttpResponse, TelescopeError> {
+    if !RetryWebhookDelivery::execute(delivery_id).await? {
+        return Err(TelescopeError::BadRequest {
+            header: "Cannot Retry Delivery".into(),
+            message: "This delivery does not exist or was already delivered.".into(),
+            show_status_code: false,
+        });
+    }
+    return Ok(HttpResponse::Found()
+        .header(LOCATION, "/admin/webhooks/deliveries")
+        .finish());
+}
@@ -7,13 +7,44 @@ use crate::web::services::auth::oauth2_providers::{
     discord::DiscordIdentity, github::GitHubIdentity,
 };
 use crate::web::services::auth::rpi_cas::RpiCasIdentity;
+use crate::web::sessions;
 use actix_identity::Identity as ActixIdentity;
 use actix_web::dev::{Payload, PayloadStream};
 use actix_web::{FromRequest, HttpRequest};
 use futures::future::{ready, LocalBoxFuture, Ready};
+use rand::distributions::Alphanumeric;
+use rand::rngs::OsRng;
+use rand::Rng;
 use serde::Serialize;
 use uuid::Uuid;
 
+/// The session store key of the key that signs identity cookies.
+const COOKIE_KEY: &'static str = "identity:cookie_key";
+
+/// Get the key that signs and encrypts identity cookies.
+///
+/// The key is kept in the session store, so that every replica sharing a
+/// store accepts cookies made by the others. With the in-memory store each
+/// start makes a new key, which logs everyone out on restart.
+pub async fn cookie_key() -> Result<Vec<u8>, TelescopeError> {
+    let store = sessions::store();
+    let new_key: String = OsRng
+        .sample_iter(&Alphanumeric)
+        .take(64)
+        .map(char::from)
+        .collect();
+    // Only the first replica to start sets the key.
+    store.set_if_absent(COOKIE_KEY, new_key, None).await?;
+
+    return store
+        .get(COOKIE_KEY)
+        .await?
+        .map(String::into_bytes)
+        .ok_or(TelescopeError::ise(
+            "The identity cookie key was not saved.",
+        ));
+}
+
 /// The root identity that this user is authenticated with.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RootIdentity {
@@ -5,6 +5,7 @@ use crate::api::rcos::users::UserAccountType;
 use crate::env::global_config;
 use crate::error::TelescopeError;
 
+use crate::web::deadline;
 use crate::web::services::auth::identity::{AuthenticationCookie, Identity};
 use crate::web::services::auth::oauth2_providers::discord::DiscordOAuth;
 use crate::web::services::auth::rpi_cas::RpiCas;
@@ -241,10 +242,12 @@ pub trait IdentityProvider: 'static {
                         let rcos_discord = global_config().discord_config.rcos_guild_id();
 
                         // Kick user from RCOS Discord.
-                        global_discord_client()
-                            .kick_member(rcos_discord, discord_id)
-                            .await
-                            .map_err(TelescopeError::serenity_error)?
+                        deadline::within(
+                            "Discord",
+                            global_discord_client().kick_member(rcos_discord, discord_id),
+                        )
+                        .await?
+                        .map_err(TelescopeError::serenity_error)?
                     }
                 }
 
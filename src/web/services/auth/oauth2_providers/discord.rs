@@ -1,5 +1,6 @@
 //! Discord OAuth2 flow.
 
+use crate::api::http::{self, Integration};
 use crate::api::rcos::users::accounts::reverse_lookup::ReverseLookup;
 use crate::api::rcos::users::UserAccountType;
 use crate::env::global_config;
@@ -166,13 +167,16 @@ impl DiscordIdentity {
     /// Get the currently authenticated discord user associated with this access token.
     pub async fn get_authenticated_user(&self) -> Result<CurrentUser, TelescopeError> {
         // Send the GET request to the discord API.
-        return reqwest::Client::new()
+        return http::client(Integration::Discord)
             .get(format!("{}/users/@me", DISCORD_API_ENDPOINT).as_str())
             .bearer_auth(self.access_token.secret())
             .header(ACCEPT, "application/json")
             .send()
             .await
             .map_err(|e| {
+                if TelescopeError::timed_out(&e) {
+                    return TelescopeError::deadline_exceeded("Discord");
+                }
                 TelescopeError::ise(format!(
                     "Could not send identification query to Discord \
             API. Internal error: {}",
@@ -213,7 +217,7 @@ impl DiscordIdentity {
         });
 
         // Send Discord request.
-        let response = reqwest::Client::new()
+        let response = http::client(Integration::Discord)
             .put(url.as_str())
             .json(&body)
             .header(
@@ -223,6 +227,9 @@ impl DiscordIdentity {
             .send()
             .await
             .map_err(|err| {
+                if TelescopeError::timed_out(&err) {
+                    return TelescopeError::deadline_exceeded("Discord");
+                }
                 error!("Could not add user to RCOS Discord. Reqwest error: {}", err);
                 TelescopeError::ise(format!(
                     "Could not join RCOS Discord. Internal Error: {}",
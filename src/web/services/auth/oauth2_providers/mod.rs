@@ -4,6 +4,7 @@ use crate::api::rcos::users::accounts::link::LinkUserAccount;
 use crate::api::rcos::users::accounts::reverse_lookup::ReverseLookup;
 use crate::api::rcos::users::accounts::unlink::UnlinkUserAccount;
 use crate::api::rcos::users::UserAccountType;
+use crate::discord_bot::{self, DiscordMessage};
 use crate::error::TelescopeError;
 use crate::web::csrf;
 use crate::web::services::auth::identity::{AuthenticationCookie, Identity, RootIdentity};
@@ -18,6 +19,7 @@ use oauth2::{AuthorizationCode, AuthorizationRequest, CsrfToken, RedirectUrl, Sc
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::Arc;
+use uuid::Uuid;
 
 pub mod discord;
 pub mod github;
@@ -52,7 +54,7 @@ pub trait Oauth2Identity {
 
 /// Special trait specifically for OAuth2 Identity providers that implements
 /// certain methods in the IdentityProvider trait automatically.
-pub trait Oauth2IdentityProvider {
+pub trait Oauth2IdentityProvider: 'static {
     /// The type of identity produced by this provider.
     type IdentityType: Oauth2Identity;
 
@@ -71,7 +73,7 @@ pub trait Oauth2IdentityProvider {
     fn auth_response(
         redir_url: RedirectUrl,
         http_req: &HttpRequest,
-    ) -> Result<HttpResponse, TelescopeError> {
+    ) -> LocalBoxFuture<'static, Result<HttpResponse, TelescopeError>> {
         // Get the client configuration and build out the authentication request parameters.
         let client: Arc<BasicClient> = Self::get_client();
         let mut auth_req: AuthorizationRequest = client
@@ -87,13 +89,16 @@ pub trait Oauth2IdentityProvider {
         }
         let (url, csrf_token) = auth_req.url();
 
-        // Save CSRF token.
-        csrf::save(Self::SERVICE_NAME, http_req, csrf_token)?;
+        let http_req: HttpRequest = http_req.clone();
+        return Box::pin(async move {
+            // Save CSRF token.
+            csrf::save(Self::SERVICE_NAME, &http_req, csrf_token).await?;
 
-        // Return the URL in an HTTP redirect response.
-        return Ok(HttpResponse::Found()
-            .header(LOCATION, url.as_str())
-            .finish());
+            // Return the URL in an HTTP redirect response.
+            return Ok(HttpResponse::Found()
+                .header(LOCATION, url.as_str())
+                .finish());
+        });
     }
 
     /// Extract the response parameters from the callback request invoked
@@ -101,52 +106,55 @@ pub trait Oauth2IdentityProvider {
     fn token_exchange(
         redirect_uri: RedirectUrl,
         req: &HttpRequest,
-    ) -> Result<BasicTokenResponse, TelescopeError> {
-        // Extract the parameters from the request.
-        let params: Query<AuthResponse> = Query::extract(req)
-            // Extract the value out of the immediately ready future.
-            .into_inner()
-            // Propagate any errors that occur.
-            .map_err(|err: actix_web::Error| {
-                // Map all errors getting the query from the request into a bad
-                // request error.
-                TelescopeError::BadRequest {
-                    header: "Bad Authentication Request".into(),
-                    message: format!(
-                        "Could not get authentication parameters from request URL. \
-                    Actix-web error: {}",
-                        err
-                    ),
-                    show_status_code: true,
-                }
-            })?;
-
-        // Destructure the parameters.
-        let AuthResponse { code, state } = params.0;
-        // Verify the CSRF token. Propagate any errors including a mismatch
-        // (we expect to verify without issue most of the time).
-        csrf::verify(Self::SERVICE_NAME, req, state)?;
-
-        // Get the OAuth2 client to exchange the auth code for an access token.
-        let oauth_client: Arc<BasicClient> = Self::get_client();
-
-        // Send the exchange request and wait for a response. This happens
-        // synchronously so take care where you call this function from.
-        // Return the response to the calling function.
-        return oauth_client
-            .exchange_code(code)
-            .add_extra_param("redirect_uri", redirect_uri.as_str())
-            // Send request and wait for response synchronously.
-            .request(oauth2::reqwest::http_client)
-            // Any errors that occur should be reported as internal server errors.
-            .map_err(|e| {
-                TelescopeError::ise(format!(
-                    "OAuth2 token exchange error. If this \
-                persists, please contact a coordinator and file a GitHub issue. Internal error \
-                description: {:?}",
-                    e
-                ))
-            });
+    ) -> LocalBoxFuture<'static, Result<BasicTokenResponse, TelescopeError>> {
+        let req: HttpRequest = req.clone();
+        return Box::pin(async move {
+            // Extract the parameters from the request.
+            let params: Query<AuthResponse> = Query::extract(&req)
+                // Extract the value out of the immediately ready future.
+                .into_inner()
+                // Propagate any errors that occur.
+                .map_err(|err: actix_web::Error| {
+                    // Map all errors getting the query from the request into a bad
+                    // request error.
+                    TelescopeError::BadRequest {
+                        header: "Bad Authentication Request".into(),
+                        message: format!(
+                            "Could not get authentication parameters from request URL. \
+                        Actix-web error: {}",
+                            err
+                        ),
+                        show_status_code: true,
+                    }
+                })?;
+
+            // Destructure the parameters.
+            let AuthResponse { code, state } = params.0;
+            // Verify the CSRF token. Propagate any errors including a mismatch
+            // (we expect to verify without issue most of the time).
+            csrf::verify(Self::SERVICE_NAME, &req, state).await?;
+
+            // Get the OAuth2 client to exchange the auth code for an access token.
+            let oauth_client: Arc<BasicClient> = Self::get_client();
+
+            // Send the exchange request and wait for a response. This happens
+            // synchronously so take care where you call this function from.
+            // Return the response to the calling function.
+            return oauth_client
+                .exchange_code(code)
+                .add_extra_param("redirect_uri", redirect_uri.as_str())
+                // Send request and wait for response synchronously.
+                .request(oauth2::reqwest::http_client)
+                // Any errors that occur should be reported as internal server errors.
+                .map_err(|e| {
+                    TelescopeError::ise(format!(
+                        "OAuth2 token exchange error. If this \
+                    persists, please contact a coordinator and file a GitHub issue. Internal error \
+                    description: {:?}",
+                        e
+                    ))
+                });
+        });
     }
 }
 
@@ -176,7 +184,7 @@ where
             // Get the redirect URL.
             let redir_url: RedirectUrl = make_redirect_url(&req, Self::login_redirect_path());
             // Redirect the user.
-            return Self::auth_response(redir_url, &req);
+            return Self::auth_response(redir_url, &req).await;
         });
     }
 
@@ -186,7 +194,7 @@ where
             let redir_url: RedirectUrl =
                 make_redirect_url(&req, Self::registration_redirect_path());
             // Redirect the user.
-            return Self::auth_response(redir_url, &req);
+            return Self::auth_response(redir_url, &req).await;
         });
     }
 
@@ -196,7 +204,7 @@ where
             if ident.identity().await.is_some() {
                 // If so, make the redirect url and send the user there.
                 let redir_url: RedirectUrl = make_redirect_url(&req, Self::link_redirect_path());
-                return Self::auth_response(redir_url, &req);
+                return Self::auth_response(redir_url, &req).await;
             } else {
                 // If not, respond with a NotAuthenticated error.
                 return Err(TelescopeError::NotAuthenticated);
@@ -211,7 +219,7 @@ where
             // Get the redirect URL.
             let redir_uri: RedirectUrl = make_redirect_url(&req, Self::login_redirect_path());
             // Get the API access token.
-            let token_response: BasicTokenResponse = Self::token_exchange(redir_uri, &req)?;
+            let token_response: BasicTokenResponse = Self::token_exchange(redir_uri, &req).await?;
             // Into the platform identity.
             let platform_identity: T::IdentityType =
                 T::IdentityType::from_basic_token(&token_response);
@@ -248,7 +256,7 @@ where
                 make_redirect_url(&req, Self::registration_redirect_path());
 
             // Get the object to store in the user's cookie.
-            let token_response: BasicTokenResponse = Self::token_exchange(redir_uri, &req)?;
+            let token_response: BasicTokenResponse = Self::token_exchange(redir_uri, &req).await?;
             let platform_identity: T::IdentityType =
                 T::IdentityType::from_basic_token(&token_response);
             let root: RootIdentity = platform_identity.into_root();
@@ -272,7 +280,7 @@ where
             // Get the redirect url.
             let redir_url: RedirectUrl = make_redirect_url(&req, Self::link_redirect_path());
             // Token exchange.
-            let token: BasicTokenResponse = Self::token_exchange(redir_url, &req)?;
+            let token: BasicTokenResponse = Self::token_exchange(redir_url, &req).await?;
 
             // Extract the auth cookie from the identity.
             let mut cookie: AuthenticationCookie = ident
@@ -297,6 +305,23 @@ where
                 user_id
             );
 
+            // Make sure this account is not linked to someone else. Each platform
+            // account can only belong to one user, since commands like /whois and
+            // role syncing look users up by it.
+            let owner: Option<Uuid> =
+                ReverseLookup::execute(Self::USER_ACCOUNT_TY, platform_id.clone()).await?;
+            if owner.map_or(false, |owner| owner != user_id) {
+                return Err(TelescopeError::BadRequest {
+                    header: format!("{} Account Already Linked", Self::USER_ACCOUNT_TY),
+                    message: format!(
+                        "This {} account is linked to another Telescope user. Unlink it \
+                        from that user first.",
+                        Self::USER_ACCOUNT_TY
+                    ),
+                    show_status_code: false,
+                });
+            }
+
             // Check if there is already an account of this type linked.
             // Lookup all linked accounts.
             let linked_accounts = UserAccounts::send(user_id)
@@ -340,6 +365,16 @@ where
             // Send the link mutation.
             LinkUserAccount::send(user_id, Self::USER_ACCOUNT_TY, platform_id).await?;
 
+            // Sync the roles a newly linked Discord account gets from enrollment.
+            if Self::USER_ACCOUNT_TY == UserAccountType::Discord {
+                if let Err(e) = discord_bot::send(DiscordMessage::SyncEnrollmentRoles { user_id }) {
+                    warn!(
+                        "Could not sync the enrollment roles of user {}: {}",
+                        user_id, e
+                    );
+                }
+            }
+
             // Add identity to auth cookie.
             platform_identity.add_to_cookie(&mut cookie);
             ident.save(&cookie);
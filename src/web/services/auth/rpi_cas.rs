@@ -2,6 +2,7 @@
 //! [here](https://apereo.github.io/cas/4.2.x/protocol/CAS-Protocol.html)
 //! and work from RPI students who came before me.
 
+use crate::api::http::{self, Integration};
 use crate::api::rcos::users::accounts::link::LinkUserAccount;
 use crate::api::rcos::users::accounts::lookup::AccountLookup;
 use crate::api::rcos::users::accounts::reverse_lookup::ReverseLookup;
@@ -126,7 +127,9 @@ async fn cas_authenticated(
         format!("{}/serviceValidate?{}", RPI_CAS_ENDPOINT, validation_query);
 
     // Send request to CAS service and wait for response.
-    let cas_xml: String = reqwest::get(validation_url.as_str())
+    let cas_xml: String = http::client(Integration::RpiCas)
+        .get(validation_url.as_str())
+        .send()
         .await
         .map_err(TelescopeError::rpi_cas_error)?
         .text()
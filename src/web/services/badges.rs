@@ -0,0 +1,78 @@
+//! Badge catalog and manual badge awards.
+
+use crate::api::rcos::badges::get::BadgeCatalog;
+use crate::api::rcos::badges::mutations::{AwardBadge, RevokeBadge};
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::error::TelescopeError;
+use crate::templates::cache::{self, Dependency};
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use uuid::Uuid;
+
+/// The path to the badge catalog template from the templates directory.
+const TEMPLATE_PATH: &'static str = "badges/catalog";
+
+/// Register badge services.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(catalog).service(award).service(revoke);
+}
+
+/// Form submitted by coordinators to award a badge.
+#[derive(Deserialize, Debug)]
+struct AwardForm {
+    /// The badge to award.
+    badge_id: String,
+}
+
+/// Make sure the viewer is allowed to award and revoke badges by hand.
+/// Return their user ID if so.
+async fn authorize(auth: &AuthenticationCookie) -> Result<Uuid, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    // Coordinators, faculty advisors, and sysadmins can manage badges.
+    if !AuthorizationFor::get(Some(viewer)).await?.can_view_drafts() {
+        return Err(TelescopeError::Forbidden);
+    }
+    return Ok(viewer);
+}
+
+/// Page listing every badge.
+#[get("/badges")]
+async fn catalog(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let badges = BadgeCatalog::get().await?;
+    let mut template = Template::new(TEMPLATE_PATH);
+    template.set_field("badges", badges)?;
+    return template.in_page(&req, "Badges").await;
+}
+
+/// Award a badge to a user by hand.
+#[post("/user/{id}/badges")]
+async fn award(
+    auth: AuthenticationCookie,
+    Path(user_id): Path<Uuid>,
+    Form(form): Form<AwardForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = authorize(&auth).await?;
+    AwardBadge::execute(user_id, form.badge_id, Some(viewer)).await?;
+    cache::invalidate(Dependency::Badges);
+    return Ok(HttpResponse::Found()
+        .header(LOCATION, format!("/user/{}", user_id))
+        .finish());
+}
+
+/// Revoke a badge from a user.
+#[post("/user/{id}/badges/{badge_id}/revoke")]
+async fn revoke(
+    auth: AuthenticationCookie,
+    Path((user_id, badge_id)): Path<(Uuid, String)>,
+) -> Result<HttpResponse, TelescopeError> {
+    authorize(&auth).await?;
+    RevokeBadge::execute(user_id, badge_id).await?;
+    cache::invalidate(Dependency::Badges);
+    return Ok(HttpResponse::Found()
+        .header(LOCATION, format!("/user/{}", user_id))
+        .finish());
+}
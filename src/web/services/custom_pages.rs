@@ -0,0 +1,49 @@
+//! Coordinator managed markdown pages (e.g. `/p/faq`). They live under their
+//! own prefix so that they never shadow a built-in route.
+
+use crate::api::rcos::custom_pages::get::{custom_page::CustomPageCustomPagesByPk, CustomPage};
+use crate::api::rcos::users::role_lookup::RoleLookup;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::Identity;
+use actix_web::web::Path;
+use actix_web::HttpRequest;
+
+/// The path to the custom page template from the templates directory.
+const TEMPLATE_PATH: &'static str = "custom_pages/page";
+
+/// Service to render a custom page by its slug.
+#[get("/p/{slug}")]
+pub async fn view(
+    req: HttpRequest,
+    identity: Identity,
+    Path(slug): Path<String>,
+) -> Result<Page, TelescopeError> {
+    // Lookup the page.
+    let page: CustomPageCustomPagesByPk = CustomPage::get(slug)
+        .await?
+        .ok_or(TelescopeError::PageNotFound)?;
+
+    // Drafts are only visible to admins. Everyone else gets the normal not found page.
+    if page.is_draft {
+        // Get the viewer's role if there is one.
+        let viewer_is_admin: bool = match identity.get_user_id().await? {
+            Some(user_id) => RoleLookup::get(user_id)
+                .await?
+                .map(|role| role.is_admin())
+                .unwrap_or(false),
+            None => false,
+        };
+
+        if !viewer_is_admin {
+            return Err(TelescopeError::PageNotFound);
+        }
+    }
+
+    // Render the page.
+    let title: String = page.title.clone();
+    let mut template = Template::new(TEMPLATE_PATH);
+    template.set_field("page", page)?;
+    return template.in_page(&req, title).await;
+}
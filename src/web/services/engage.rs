@@ -0,0 +1,98 @@
+//! Student engagement panel, where members see how their attendance this
+//! semester measures up to the attendance policy and ask to be excused from
+//! meetings they missed.
+
+use crate::api::rcos::attendance::policy::{ExcuseStatus, MyAttendance, RequestExcuse};
+use crate::api::rcos::prelude::MeetingId;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+
+/// The path to the engagement panel template from the templates directory.
+const TEMPLATE_PATH: &'static str = "engage";
+
+/// The longest reason for an excuse request.
+const MAX_REASON_LENGTH: usize = 500;
+
+/// Register engagement panel services.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(engage).service(request_excuse);
+}
+
+/// Form submitted to ask to be excused from a meeting.
+#[derive(Deserialize, Debug)]
+struct ExcuseForm {
+    meeting_id: MeetingId,
+    reason: String,
+}
+
+/// Error for an excuse request that cannot be made.
+fn invalid_excuse(message: &str) -> TelescopeError {
+    TelescopeError::BadRequest {
+        header: "Cannot Request Excuse".into(),
+        message: message.into(),
+        show_status_code: false,
+    }
+}
+
+/// The viewer's attendance this semester.
+#[get("/engage")]
+async fn engage(req: HttpRequest, auth: AuthenticationCookie) -> Result<Page, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    let mut template = Template::new(TEMPLATE_PATH);
+    if let Some(attendance) = MyAttendance::current(viewer).await? {
+        template
+            .set_field("standing", attendance.standing())?
+            .set_field("attendance", attendance)?;
+    }
+    return template.in_page(&req, "Engage").await;
+}
+
+/// Ask to be excused from a meeting this semester that the viewer missed.
+#[post("/engage/excuse")]
+async fn request_excuse(
+    auth: AuthenticationCookie,
+    Form(form): Form<ExcuseForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+
+    let reason: &str = form.reason.trim();
+    if reason.is_empty() {
+        return Err(invalid_excuse("Say why you could not attend."));
+    }
+    if reason.chars().count() > MAX_REASON_LENGTH {
+        return Err(invalid_excuse(
+            "Reasons can be at most 500 characters long.",
+        ));
+    }
+
+    // Only meetings that count this semester can be excused, and only if they
+    // were missed.
+    let attendance = MyAttendance::current(viewer)
+        .await?
+        .ok_or_else(|| invalid_excuse("There is no semester in progress."))?;
+    let meeting = attendance
+        .meetings
+        .iter()
+        .find(|meeting| MeetingId(meeting.meeting_id) == form.meeting_id)
+        .ok_or_else(|| invalid_excuse("This meeting does not count towards attendance."))?;
+    if !meeting.attended.is_empty() {
+        return Err(invalid_excuse("You already attended this meeting."));
+    }
+    if meeting
+        .excuse
+        .iter()
+        .any(|excuse| excuse.status != ExcuseStatus::Pending.to_string())
+    {
+        return Err(invalid_excuse(
+            "A coordinator already decided on your request for this meeting.",
+        ));
+    }
+
+    RequestExcuse::execute(form.meeting_id, viewer, reason.to_string()).await?;
+    return Ok(HttpResponse::Found().header(LOCATION, "/engage").finish());
+}
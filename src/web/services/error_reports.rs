@@ -0,0 +1,125 @@
+//! Reporting server errors from the error page.
+//!
+//! Pages for 5xx errors carry a form with the request ID, route, and time of
+//! the error filled in. The user adds what they were doing, and the report goes
+//! to the error report queue in the admin panel. Reports can be filed without
+//! signing in, since signing in may be what failed.
+
+use crate::api::rcos::error_reports::{FileErrorReport, NewErrorReport};
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::middlewares::request_id::{MAX_ID_LENGTH, REQUEST_ID_HEADER};
+use crate::web::services::auth::identity::Identity;
+use actix_web::web::{Form, ServiceConfig};
+use actix_web::HttpRequest;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// The path to the report form from the templates directory.
+const FORM_TEMPLATE: &'static str = "error_reports/form";
+
+/// The longest description that can be given with a report.
+const MAX_DESCRIPTION_LENGTH: usize = 2000;
+
+/// The longest route kept with a report.
+const MAX_ROUTE_LENGTH: usize = 500;
+
+/// Register error report services.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(submit);
+}
+
+/// Form submitted to report an error.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ErrorReportForm {
+    request_id: String,
+    route: String,
+    status: u16,
+    occurred_at: DateTime<Utc>,
+    description: String,
+}
+
+/// The fields of the report form on the error page for a request.
+pub fn report_fields(req: &HttpRequest, status: u16) -> Value {
+    let request_id: &str = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+
+    json!({
+        "request_id": request_id,
+        "route": req.path(),
+        "status": status,
+        "occurred_at": Utc::now(),
+        "max_length": MAX_DESCRIPTION_LENGTH,
+    })
+}
+
+/// Make the report form for a submitted report, on its own page.
+fn make_form(form: &ErrorReportForm) -> Result<Template, TelescopeError> {
+    let mut template = Template::new(FORM_TEMPLATE);
+    template.fields = json!(form);
+    template.set_field("max_length", MAX_DESCRIPTION_LENGTH)?;
+    return Ok(template);
+}
+
+/// File an error report.
+#[post("/error_report")]
+async fn submit(
+    req: HttpRequest,
+    identity: Identity,
+    Form(mut form): Form<ErrorReportForm>,
+) -> Result<Page, TelescopeError> {
+    // These came from the error page, so anything off means the form was not
+    // submitted from there.
+    if !(500..600).contains(&form.status)
+        || form.request_id.len() > MAX_ID_LENGTH
+        || form.route.chars().count() > MAX_ROUTE_LENGTH
+    {
+        return Err(TelescopeError::BadRequest {
+            header: "Invalid Error Report".into(),
+            message: "This error report does not match an error page.".into(),
+            show_status_code: false,
+        });
+    }
+
+    form.description = form.description.trim().to_string();
+    let mut template = make_form(&form)?;
+    if form.description.is_empty() {
+        template.set_issue("description", "Please say what you were doing.");
+    } else if form.description.chars().count() > MAX_DESCRIPTION_LENGTH {
+        template.set_issue(
+            "description",
+            format!(
+                "Cannot be longer than {} characters.",
+                MAX_DESCRIPTION_LENGTH
+            ),
+        );
+    }
+
+    if template["issues"] != json!(null) {
+        let page = template.in_page(&req, "Report Error").await?;
+        return Err(TelescopeError::InvalidForm(page));
+    }
+
+    let reporter_id: Option<Uuid> = identity.get_user_id().await?;
+    let report_id: i64 = FileErrorReport::execute(NewErrorReport {
+        request_id: form.request_id.clone(),
+        route: form.route.clone(),
+        status: form.status as i64,
+        occurred_at: form.occurred_at,
+        description: form.description.clone(),
+        reporter_id,
+    })
+    .await?;
+    info!(
+        "Error report {} filed for request {} to {}.",
+        report_id, form.request_id, form.route
+    );
+
+    template.set_field("filed", true)?;
+    return template.in_page(&req, "Error Reported").await;
+}
@@ -0,0 +1,18 @@
+//! Health check for load balancers and uptime monitors.
+
+use crate::web::listeners;
+use actix_web::http::header::CACHE_CONTROL;
+use actix_web::HttpResponse;
+
+/// Report that Telescope is up, with its version and the addresses it is
+/// listening on. This does not check the RCOS API or Discord.
+#[get("/health")]
+pub async fn health() -> HttpResponse {
+    return HttpResponse::Ok()
+        .header(CACHE_CONTROL, "no-store")
+        .json(json!({
+            "status": "ok",
+            "version": env!("CARGO_PKG_VERSION"),
+            "listening": listeners::listening(),
+        }));
+}
@@ -0,0 +1,41 @@
+//! Service for uploaded images.
+
+use crate::error::TelescopeError;
+use crate::web::images::{self, Variant};
+use actix_web::http::header::{ACCEPT, CACHE_CONTROL, VARY};
+use actix_web::web::{block, Path};
+use actix_web::{HttpRequest, HttpResponse};
+use uuid::Uuid;
+
+/// Error for an image or variant that does not exist.
+fn image_not_found() -> TelescopeError {
+    TelescopeError::resource_not_found("Image Not Found", "There is no image at this address.")
+}
+
+/// Serve a variant of an uploaded image, as WebP if the browser accepts it.
+/// Uploads get a new ID every time, so images can be cached forever.
+#[get("/images/{id}/{variant}")]
+pub async fn image(
+    req: HttpRequest,
+    Path((id, variant)): Path<(Uuid, String)>,
+) -> Result<HttpResponse, TelescopeError> {
+    let variant: Variant = Variant::parse(variant.as_str()).ok_or_else(image_not_found)?;
+
+    let accepts_webp: bool = req
+        .headers()
+        .get(ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .map_or(false, |accept| accept.contains("image/webp"));
+
+    let (path, file_type) = images::find(id, variant, accepts_webp).ok_or_else(image_not_found)?;
+    let bytes: Vec<u8> = block(move || std::fs::read(path))
+        .await
+        .map_err(|e| TelescopeError::ise(format!("Could not read image {}: {}", id, e)))?;
+
+    return Ok(HttpResponse::Ok()
+        .content_type(file_type.mime())
+        // The response depends on whether the browser accepts WebP.
+        .header(VARY, "Accept")
+        .header(CACHE_CONTROL, "public, max-age=31536000, immutable")
+        .body(bytes));
+}
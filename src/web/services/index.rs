@@ -1,21 +1,33 @@
 //! Module for serving the RCOS homepage.
 
 use crate::api::rcos::landing_page_stats::LandingPageStatistics;
+use crate::api::rcos::skills::Matchmaking;
 use crate::error::TelescopeError;
 use crate::templates::page::Page;
 use crate::templates::Template;
+use crate::web::services::auth::identity::Identity;
 use actix_web::HttpRequest;
 
 /// Path to the Handlebars file from the templates directory.
 const TEMPLATE_PATH: &'static str = "index";
 
+/// How many projects to suggest to logged in users.
+const SUGGESTIONS_SHOWN: usize = 3;
+
 /// Service that serves the telescope homepage.
 #[get("/")]
-pub async fn index(req: HttpRequest) -> Result<Page, TelescopeError> {
-    // Get the statistics.
+pub async fn index(req: HttpRequest, identity: Identity) -> Result<Page, TelescopeError> {
+    // Get the statistics across all organizations.
     let stats = LandingPageStatistics::get().await?;
     // Make and return a template with the statistics.
     let mut template = Template::new(TEMPLATE_PATH);
-    template["stats"] = json!(stats);
+    template.set_field("stats", stats)?;
+
+    // Suggest projects that need the viewer's skills.
+    if let Some(viewer) = identity.get_user_id().await? {
+        let mut matches = Matchmaking::get(viewer).await?;
+        matches.truncate(SUGGESTIONS_SHOWN);
+        template.set_field("suggested", json!({ "matches": matches }))?;
+    }
     return template.in_page(&req, "RCOS").await;
 }
@@ -0,0 +1,227 @@
+//! Attendance streak and participation leaderboard.
+//!
+//! Only users who opt in from their profile settings are listed. Standings are
+//! expensive to compute, so they are cached per semester for a few minutes.
+
+use crate::api::rcos::leaderboard::LeaderboardData;
+use crate::api::rcos::semesters::current::info::CurrentSemesters;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::cluster::{self, Broadcast};
+use actix_web::web::{Path, ServiceConfig};
+use actix_web::HttpRequest;
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use dashmap::DashMap;
+use std::collections::{BTreeSet, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// The path to the leaderboard template from the templates directory.
+const TEMPLATE_PATH: &'static str = "leaderboard";
+
+/// How many minutes computed standings are reused before being recomputed.
+const CACHE_MINUTES: i64 = 15;
+
+lazy_static! {
+    /// Computed leaderboards keyed by semester ID.
+    static ref LEADERBOARD_CACHE: DashMap<String, Arc<Leaderboard>> = DashMap::new();
+}
+
+/// Register leaderboard services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(current_leaderboard)
+        .service(semester_leaderboard);
+}
+
+/// Drop all cached standings on every replica. Call this when a user changes
+/// whether they appear on the leaderboard.
+pub fn invalidate_cache() {
+    cluster::broadcast(Broadcast::LeaderboardChanged);
+}
+
+/// Drop the cached standings of this replica.
+pub(crate) fn clear_cache() {
+    LEADERBOARD_CACHE.clear();
+}
+
+/// A single user's row on the leaderboard.
+#[derive(Serialize, Debug)]
+struct Standing {
+    /// Position on the leaderboard, starting at 1.
+    rank: usize,
+    user_id: Uuid,
+    first_name: String,
+    last_name: String,
+    /// Number of meetings attended this semester.
+    meetings_attended: usize,
+    /// Consecutive meeting weeks attended, ending with the most recent one.
+    current_streak: usize,
+    /// The longest run of consecutive meeting weeks attended this semester.
+    longest_streak: usize,
+    /// Number of status updates submitted this semester.
+    status_updates_submitted: i64,
+    /// Percent of opened status updates submitted, if any have opened.
+    status_update_rate: Option<i64>,
+}
+
+/// The computed leaderboard for a semester.
+#[derive(Serialize, Debug)]
+struct Leaderboard {
+    semester_id: String,
+    title: String,
+    /// When these standings were computed.
+    computed_at: DateTime<Utc>,
+    standings: Vec<Standing>,
+}
+
+/// Get the week of the semester that a timestamp falls in.
+fn week_of(semester_start: NaiveDate, time: &DateTime<Utc>) -> i64 {
+    let day: NaiveDate = time.with_timezone(&Local).date().naive_local();
+    return (day - semester_start).num_days().div_euclid(7);
+}
+
+/// Compute the leaderboard for a semester. Return `None` if the semester
+/// does not exist.
+async fn compute(semester_id: String) -> Result<Option<Leaderboard>, TelescopeError> {
+    let data = LeaderboardData::get(semester_id).await?;
+
+    // Return early if the semester does not exist.
+    let semester = match data.semesters_by_pk {
+        Some(semester) => semester,
+        None => return Ok(None),
+    };
+
+    // Weeks of the semester that have had at least one meeting so far.
+    let meeting_weeks: BTreeSet<i64> = semester
+        .meetings
+        .iter()
+        .map(|meeting| week_of(semester.start_date, &meeting.start_date_time))
+        .collect();
+
+    // Number of status updates opened so far.
+    let status_updates_open: i64 = semester
+        .status_updates_aggregate
+        .aggregate
+        .as_ref()
+        .map(|agg| agg.count)
+        .unwrap_or(0);
+
+    let mut standings: Vec<Standing> = data
+        .users
+        .into_iter()
+        .map(|user| {
+            // Weeks this user attended a meeting.
+            let attended: HashSet<i64> = user
+                .meeting_attendances
+                .iter()
+                .map(|a| week_of(semester.start_date, &a.meeting.start_date_time))
+                .collect();
+
+            // Walk the meeting weeks in order to find streaks.
+            let mut current_streak: usize = 0;
+            let mut longest_streak: usize = 0;
+            for week in meeting_weeks.iter() {
+                if attended.contains(week) {
+                    current_streak += 1;
+                    longest_streak = longest_streak.max(current_streak);
+                } else {
+                    current_streak = 0;
+                }
+            }
+
+            let status_updates_submitted: i64 = user
+                .status_update_submissions_aggregate
+                .aggregate
+                .as_ref()
+                .map(|agg| agg.count)
+                .unwrap_or(0);
+
+            Standing {
+                rank: 0,
+                user_id: user.id,
+                first_name: user.first_name,
+                last_name: user.last_name,
+                meetings_attended: user.meeting_attendances.len(),
+                current_streak,
+                longest_streak,
+                status_updates_submitted,
+                status_update_rate: (status_updates_open > 0)
+                    .then(|| status_updates_submitted * 100 / status_updates_open),
+            }
+        })
+        .collect();
+
+    // Rank by current streak, then longest streak, then status update consistency.
+    standings.sort_by(|a, b| {
+        b.current_streak
+            .cmp(&a.current_streak)
+            .then(b.longest_streak.cmp(&a.longest_streak))
+            .then(b.status_updates_submitted.cmp(&a.status_updates_submitted))
+    });
+    for (index, standing) in standings.iter_mut().enumerate() {
+        standing.rank = index + 1;
+    }
+
+    return Ok(Some(Leaderboard {
+        semester_id: semester.semester_id,
+        title: semester.title,
+        computed_at: Utc::now(),
+        standings,
+    }));
+}
+
+/// Get the leaderboard for a semester from the cache, recomputing it if it is
+/// missing or stale.
+async fn get_leaderboard(semester_id: String) -> Result<Option<Arc<Leaderboard>>, TelescopeError> {
+    // Check the cache first.
+    if let Some(cached) = LEADERBOARD_CACHE.get(&semester_id) {
+        if Utc::now() - cached.computed_at < Duration::minutes(CACHE_MINUTES) {
+            return Ok(Some(cached.clone()));
+        }
+    }
+
+    // Compute and cache the standings.
+    let computed = compute(semester_id.clone()).await?.map(Arc::new);
+    if let Some(leaderboard) = computed.as_ref() {
+        LEADERBOARD_CACHE.insert(semester_id, leaderboard.clone());
+    }
+    return Ok(computed);
+}
+
+/// Render the leaderboard page. A `None` leaderboard renders a message that
+/// there is no ongoing semester.
+async fn render(
+    req: &HttpRequest,
+    leaderboard: Option<Arc<Leaderboard>>,
+) -> Result<Page, TelescopeError> {
+    let mut template = Template::new(TEMPLATE_PATH);
+    template.set_field("leaderboard", leaderboard.as_deref())?;
+    return template.in_page(req, "Leaderboard").await;
+}
+
+/// Leaderboard for the current semester.
+#[get("/leaderboard")]
+async fn current_leaderboard(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let leaderboard = match CurrentSemesters::get().await?.into_iter().next() {
+        Some(semester) => get_leaderboard(semester.semester_id).await?,
+        None => None,
+    };
+    return render(&req, leaderboard).await;
+}
+
+/// Leaderboard for a specific semester.
+#[get("/leaderboard/{semester_id}")]
+async fn semester_leaderboard(
+    req: HttpRequest,
+    Path(semester_id): Path<String>,
+) -> Result<Page, TelescopeError> {
+    let leaderboard = get_leaderboard(semester_id).await?.ok_or_else(|| {
+        TelescopeError::resource_not_found(
+            "Semester Not Found",
+            "Could not find a semester by this ID.",
+        )
+    })?;
+    return render(&req, Some(leaderboard)).await;
+}
@@ -0,0 +1,77 @@
+//! Matchmaking page, where members find projects that need their skills.
+//!
+//! Also has the skill picker shared by the profile settings and project pages.
+//! Picked skills are submitted as a checkbox named `skill-{id}` for each
+//! skill.
+
+use crate::api::rcos::skills::{AllSkills, Matchmaking, SkillCategory};
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::web::ServiceConfig;
+use actix_web::HttpRequest;
+use std::collections::{HashMap, HashSet};
+
+/// The path to the matchmaking template from the templates directory.
+const TEMPLATE_PATH: &'static str = "matchmaking";
+
+/// Register matchmaking services.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(matchmaking);
+}
+
+/// A skill on the skill picker.
+#[derive(Serialize)]
+struct SkillOption<T> {
+    #[serde(flatten)]
+    skill: T,
+    checked: bool,
+}
+
+/// Fill the skill picker of a form. Skills in `tagged` start checked.
+pub async fn fill_skill_picker(
+    template: &mut Template,
+    tagged: HashSet<i64>,
+) -> Result<(), TelescopeError> {
+    template
+        .set_field(
+            "skill_options",
+            AllSkills::get()
+                .await?
+                .into_iter()
+                .map(|skill| SkillOption {
+                    checked: tagged.contains(&skill.skill_id),
+                    skill,
+                })
+                .collect::<Vec<_>>(),
+        )?
+        .set_field(
+            "skill_categories",
+            SkillCategory::ALL
+                .iter()
+                .map(|category| json!({"name": category, "label": category.label()}))
+                .collect::<Vec<_>>(),
+        )?;
+    return Ok(());
+}
+
+/// Get the IDs of the skills picked in a submitted skill picker. Skills that
+/// do not exist are left out.
+pub async fn picked_skills(form: &HashMap<String, String>) -> Result<Vec<i64>, TelescopeError> {
+    Ok(AllSkills::get()
+        .await?
+        .into_iter()
+        .map(|skill| skill.skill_id)
+        .filter(|id| form.contains_key(&format!("skill-{}", id)))
+        .collect())
+}
+
+/// Projects that need the viewer's skills, best match first.
+#[get("/matchmaking")]
+async fn matchmaking(req: HttpRequest, auth: AuthenticationCookie) -> Result<Page, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    let mut template = Template::new(TEMPLATE_PATH);
+    template.set_field("matches", Matchmaking::get(viewer).await?)?;
+    return template.in_page(&req, "Matchmaking").await;
+}
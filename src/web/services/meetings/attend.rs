@@ -0,0 +1,301 @@
+//! Services for members to check in to meetings with an attendance code, and
+//! for hosts to keep check-in open.
+//!
+//! Codes are only accepted in a window around the meeting, configured per
+//! meeting type. Hosts can extend the window for their meeting when it runs
+//! late or people have trouble checking in.
+//!
+//! Hosts generate a code from the meeting page, which expires a few minutes
+//! later so that it cannot be passed on to people who are not there. Members
+//! who submit too many wrong codes have to wait before trying again.
+
+use crate::api::rcos::attendance::get::MeetingByCode;
+use crate::api::rcos::attendance::mutations::{ExtendCheckIn, RecordAttendance, SetAttendanceCode};
+use crate::api::rcos::attendance::CheckInTimes;
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::api::rcos::meetings::get_by_id::Meeting;
+use crate::api::rcos::prelude::MeetingId;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use crate::web::sessions;
+use crate::web::webhooks::{self, WebhookEvent};
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, Query, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use chrono::{DateTime, Duration, Local, Utc};
+use rand::Rng;
+use uuid::Uuid;
+
+/// The path to the check-in form template from the templates directory.
+const TEMPLATE_PATH: &'static str = "meetings/attend";
+
+/// The longest a host can extend check-in by at once, in minutes.
+const MAX_EXTENSION_MINUTES: i64 = 120;
+
+/// Characters generated codes are made of. Letters and digits that are easy
+/// to mix up when read off a screen are left out.
+const CODE_CHARACTERS: &'static [u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// How many characters are in a generated code.
+const CODE_LENGTH: usize = 6;
+
+/// How long a generated code is accepted for, in minutes.
+const CODE_LIFETIME_MINUTES: i64 = 15;
+
+/// How many wrong codes a member can submit in a window before they have to
+/// wait.
+const MAX_FAILED_ATTEMPTS: u64 = 10;
+
+/// The length of the window wrong codes are counted in, in minutes.
+const ATTEMPT_WINDOW_MINUTES: i64 = 10;
+
+/// Register check-in services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(attend_form)
+        .service(submit_attendance)
+        .service(extend_check_in)
+        .service(generate_code);
+}
+
+/// Attendance code, from the form or a link.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct CodeForm {
+    #[serde(default)]
+    code: String,
+}
+
+/// Form submitted by hosts to extend check-in.
+#[derive(Deserialize, Debug)]
+struct ExtendForm {
+    /// Minutes to keep check-in open past the later of now and when it would
+    /// otherwise close.
+    minutes: i64,
+}
+
+/// Format a timestamp for check-in error messages.
+fn format_time(time: &DateTime<Utc>) -> String {
+    time.with_timezone(&Local)
+        .format("%B %-d at %-I:%M %P")
+        .to_string()
+}
+
+/// Make a new random attendance code.
+fn new_code() -> String {
+    let mut rng = rand::thread_rng();
+    return (0..CODE_LENGTH)
+        .map(|_| CODE_CHARACTERS[rng.gen_range(0..CODE_CHARACTERS.len())] as char)
+        .collect();
+}
+
+/// The session store key counting a member's wrong codes. The count expires
+/// at the end of the window started by the first one.
+fn failed_attempts_key(user_id: Uuid) -> String {
+    format!("checkin-failures:{}", user_id)
+}
+
+/// Check if a member has submitted too many wrong codes recently.
+async fn too_many_attempts(user_id: Uuid) -> Result<bool, TelescopeError> {
+    let count: Option<String> = sessions::store()
+        .get(failed_attempts_key(user_id).as_str())
+        .await?;
+    return Ok(count
+        .and_then(|count| count.parse::<u64>().ok())
+        .map_or(false, |count| count >= MAX_FAILED_ATTEMPTS));
+}
+
+/// Count a wrong code submitted by a member, starting a new window if the
+/// last one is over.
+async fn record_failed_attempt(user_id: Uuid) -> Result<(), TelescopeError> {
+    sessions::store()
+        .increment(
+            failed_attempts_key(user_id).as_str(),
+            Duration::minutes(ATTEMPT_WINDOW_MINUTES),
+        )
+        .await?;
+    return Ok(());
+}
+
+/// Make the check-in form, pre-filled with a code.
+fn make_form(code: &str) -> Result<Template, TelescopeError> {
+    Template::new(TEMPLATE_PATH).try_field("code", code)
+}
+
+/// Form to enter an attendance code. Codes can be pre-filled from the query
+/// string so that they can be shared as links.
+#[get("/attend")]
+async fn attend_form(
+    req: HttpRequest,
+    _auth: AuthenticationCookie,
+    query: Option<Query<CodeForm>>,
+) -> Result<Page, TelescopeError> {
+    let code: String = query.map(|q| q.code.clone()).unwrap_or_default();
+    make_form(code.trim())?.in_page(&req, "Check In").await
+}
+
+/// Record the viewer's attendance at the meeting with the submitted code.
+#[post("/attend")]
+async fn submit_attendance(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Form(form): Form<CodeForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    // Generated codes are upper case, but are often typed in lower case.
+    let code: String = form.code.trim().to_uppercase();
+
+    if too_many_attempts(viewer).await? {
+        return Err(TelescopeError::BadRequest {
+            header: "Too Many Attempts".into(),
+            message: format!(
+                "You have entered too many incorrect attendance codes. Please wait {} minutes \
+                and try again.",
+                ATTEMPT_WINDOW_MINUTES
+            ),
+            show_status_code: false,
+        });
+    }
+
+    let meeting = MeetingByCode::get(code.clone()).await?;
+    // Expired codes are treated the same as wrong ones, but say why.
+    let issue: Option<&str> = match meeting.as_ref() {
+        None => Some("No meeting has this attendance code."),
+        Some(meeting)
+            if meeting
+                .attendance_code_expires_at
+                .map_or(false, |expires| expires < Utc::now()) =>
+        {
+            Some("This attendance code has expired. Ask the host for the current one.")
+        }
+        Some(_) => None,
+    };
+
+    if let Some(issue) = issue {
+        record_failed_attempt(viewer).await?;
+        let mut form = make_form(code.as_str())?;
+        form.set_issue("code", issue);
+        return Err(TelescopeError::InvalidForm(
+            form.in_page(&req, "Check In").await?,
+        ));
+    }
+    // The meeting was found above.
+    let meeting = meeting.unwrap();
+
+    let times = CheckInTimes::for_meeting(
+        meeting.type_,
+        meeting.start_date_time,
+        meeting.end_date_time,
+        meeting.check_in_extended_until,
+    );
+
+    let now: DateTime<Utc> = Utc::now();
+    if now < times.opens {
+        return Err(TelescopeError::BadRequest {
+            header: "Too Early to Check In".into(),
+            message: format!(
+                "Check-in for this meeting opens {}. Please submit the code again then.",
+                format_time(&times.opens)
+            ),
+            show_status_code: false,
+        });
+    }
+
+    if now > times.closes {
+        return Err(TelescopeError::BadRequest {
+            header: "Too Late to Check In".into(),
+            message: format!(
+                "Check-in for this meeting closed {}. If you attended but could not check \
+                in, please ask the meeting host to add you.",
+                format_time(&times.closes)
+            ),
+            show_status_code: false,
+        });
+    }
+
+    let meeting_id = MeetingId(meeting.meeting_id);
+    RecordAttendance::execute(meeting_id, viewer).await?;
+    webhooks::emit(WebhookEvent::AttendanceRecorded {
+        meeting_id,
+        user_id: viewer,
+    })
+    .await;
+    return Ok(HttpResponse::Found()
+        .header(LOCATION, format!("/meeting/{}", meeting_id))
+        .finish());
+}
+
+/// Keep check-in open for a meeting. Only those who can edit the meeting can
+/// extend its check-in.
+#[post("/meeting/{meeting_id}/check_in/extend")]
+async fn extend_check_in(
+    auth: AuthenticationCookie,
+    Path(meeting_id): Path<MeetingId>,
+    Form(form): Form<ExtendForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    if !AuthorizationFor::get(Some(viewer))
+        .await?
+        .can_edit_by_id(meeting_id)
+        .await?
+    {
+        return Err(TelescopeError::Forbidden);
+    }
+
+    if form.minutes < 1 || form.minutes > MAX_EXTENSION_MINUTES {
+        return Err(TelescopeError::BadRequest {
+            header: "Invalid Extension".into(),
+            message: format!(
+                "Check-in can be extended by 1 to {} minutes at a time.",
+                MAX_EXTENSION_MINUTES
+            ),
+            show_status_code: false,
+        });
+    }
+
+    let meeting = Meeting::get(meeting_id).await?.ok_or_else(|| {
+        TelescopeError::resource_not_found(
+            "Meeting Not Found",
+            "Could not find a meeting for this ID.",
+        )
+    })?;
+
+    // Extend from whichever is later: now, or when check-in would close.
+    let times = CheckInTimes::for_meeting(
+        meeting.type_,
+        meeting.start_date_time,
+        meeting.end_date_time,
+        meeting.check_in_extended_until,
+    );
+    let until: DateTime<Utc> = times.closes.max(Utc::now()) + Duration::minutes(form.minutes);
+    ExtendCheckIn::execute(meeting_id, until).await?;
+
+    return Ok(HttpResponse::Found()
+        .header(LOCATION, format!("/meeting/{}", meeting_id))
+        .finish());
+}
+
+/// Generate a new attendance code for a meeting, replacing the old one. Only
+/// those who can edit the meeting can generate codes for it.
+#[post("/meeting/{meeting_id}/check_in/code")]
+async fn generate_code(
+    auth: AuthenticationCookie,
+    Path(meeting_id): Path<MeetingId>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    if !AuthorizationFor::get(Some(viewer))
+        .await?
+        .can_edit_by_id(meeting_id)
+        .await?
+    {
+        return Err(TelescopeError::Forbidden);
+    }
+
+    let expires_at: DateTime<Utc> = Utc::now() + Duration::minutes(CODE_LIFETIME_MINUTES);
+    SetAttendanceCode::execute(meeting_id, new_code(), expires_at).await?;
+
+    return Ok(HttpResponse::Found()
+        .header(LOCATION, format!("/meeting/{}", meeting_id))
+        .finish());
+}
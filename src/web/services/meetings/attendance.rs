@@ -0,0 +1,311 @@
+//! Meeting attendance list and proxy attendance for hosts.
+//!
+//! Hosts can mark someone as attending when they could not check in
+//! themselves (for example, because of technical issues). These records note
+//! who added them and why, and are counted separately on the attendance list.
+//!
+//! Attendance taken outside of Telescope, like on a paper sign-in sheet, can
+//! be imported from a CSV of RCS IDs. Imports are previewed before anything
+//! is saved, and imported records note where they came from.
+
+use crate::api::rcos::attendance::get::MeetingAttendance;
+use crate::api::rcos::attendance::import::{ImportAttendance, ImportLookup, ImportPreview};
+use crate::api::rcos::attendance::mutations::AddAttendance;
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::api::rcos::meetings::creation::host_selection::HostSelection;
+use crate::api::rcos::prelude::MeetingId;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use crate::web::uploads::{self, MultipartForm, UploadKind};
+use actix_multipart::Multipart;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, Query, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use uuid::Uuid;
+
+/// The path to the attendance list template from the templates directory.
+const TEMPLATE_PATH: &'static str = "meetings/attendance";
+
+/// The path to the attendance import template from the templates directory.
+const IMPORT_TEMPLATE: &'static str = "meetings/attendance_import";
+
+/// The most RCS IDs one import can have.
+const MAX_IMPORT_ROWS: usize = 1000;
+
+/// The longest import source label.
+const MAX_SOURCE_LENGTH: usize = 100;
+
+/// Register attendance list services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(attendance)
+        .service(add_attendance)
+        .service(import_form)
+        .service(preview_import)
+        .service(confirm_import);
+}
+
+/// Search for people to add.
+#[derive(Deserialize, Debug)]
+struct SearchQuery {
+    search: String,
+}
+
+/// Form submitted by hosts to add someone's attendance.
+#[derive(Deserialize, Debug)]
+struct AddForm {
+    user_id: Uuid,
+    /// Why the attendance is being added by hand.
+    #[serde(default)]
+    note: String,
+}
+
+/// Form submitted to save a previewed import.
+#[derive(Deserialize, Debug)]
+struct ConfirmImportForm {
+    /// The RCS IDs from the import, one per line.
+    rcs_ids: String,
+    source: String,
+}
+
+/// Make sure the viewer can edit a meeting. Return their user ID if so.
+async fn authorize(
+    auth: &AuthenticationCookie,
+    meeting_id: MeetingId,
+) -> Result<Uuid, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    if !AuthorizationFor::get(Some(viewer))
+        .await?
+        .can_edit_by_id(meeting_id)
+        .await?
+    {
+        return Err(TelescopeError::Forbidden);
+    }
+    return Ok(viewer);
+}
+
+/// Attendance list for a meeting, with a search to add people to it.
+#[get("/meeting/{meeting_id}/attendance")]
+async fn attendance(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Path(meeting_id): Path<MeetingId>,
+    query: Option<Query<SearchQuery>>,
+) -> Result<Page, TelescopeError> {
+    authorize(&auth, meeting_id).await?;
+
+    let meeting = MeetingAttendance::get(meeting_id).await?.ok_or_else(|| {
+        TelescopeError::resource_not_found(
+            "Meeting Not Found",
+            "Could not find a meeting for this ID.",
+        )
+    })?;
+
+    let mut template = Template::new(TEMPLATE_PATH);
+    template
+        .set_field("meeting", &meeting)?
+        .set_field("title", meeting.title())?;
+
+    // Search currently enrolled users the same way host selection does.
+    let search: Option<String> = query
+        .map(|q| q.search.trim().to_string())
+        .filter(|search| !search.is_empty());
+    if let Some(search) = search {
+        let results = HostSelection::get(Some(search.clone())).await?;
+        template
+            .set_field("search", search)?
+            .set_field("results", &results.users)?;
+    }
+
+    return template
+        .in_page(&req, format!("Attendance - {}", meeting.title()))
+        .await;
+}
+
+/// Add someone's attendance to a meeting on their behalf.
+#[post("/meeting/{meeting_id}/attendance/add")]
+async fn add_attendance(
+    auth: AuthenticationCookie,
+    Path(meeting_id): Path<MeetingId>,
+    Form(form): Form<AddForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = authorize(&auth, meeting_id).await?;
+    let note: Option<String> = Some(form.note.trim().to_string()).filter(|s| !s.is_empty());
+    AddAttendance::execute(meeting_id, form.user_id, viewer, note).await?;
+
+    return Ok(HttpResponse::Found()
+        .header(LOCATION, format!("/meeting/{}/attendance", meeting_id))
+        .finish());
+}
+
+/// Error for a meeting that does not exist.
+fn meeting_not_found() -> TelescopeError {
+    TelescopeError::resource_not_found("Meeting Not Found", "Could not find a meeting for this ID.")
+}
+
+/// Error for an import that could not be read.
+fn invalid_import(message: String) -> TelescopeError {
+    TelescopeError::BadRequest {
+        header: "Invalid Import".into(),
+        message,
+        show_status_code: false,
+    }
+}
+
+/// Read the RCS IDs from an attendance spreadsheet. IDs are read from the
+/// column with a header mentioning RCS IDs or emails, or from the first column
+/// if there is no such header. RPI emails are cut down to the RCS ID, and
+/// repeated IDs are only kept once.
+fn read_rcs_ids(bytes: &[u8]) -> Result<Vec<String>, TelescopeError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(bytes);
+
+    let mut rows = reader.records();
+    let first: Option<csv::StringRecord> = rows
+        .next()
+        .transpose()
+        .map_err(|e| invalid_import(format!("Could not read the CSV file: {}", e)))?;
+
+    // Use the header to pick the column, if the first row is one.
+    let header_column: Option<usize> = first.as_ref().and_then(|row| {
+        row.iter().position(|cell| {
+            let cell = cell.to_lowercase();
+            cell.contains("rcs") || cell.contains("email")
+        })
+    });
+    let column: usize = header_column.unwrap_or(0);
+    let data = first
+        .filter(|_| header_column.is_none())
+        .map(Ok)
+        .into_iter()
+        .chain(rows);
+
+    let mut rcs_ids: Vec<String> = Vec::new();
+    for row in data {
+        let row = row.map_err(|e| invalid_import(format!("Could not read the CSV file: {}", e)))?;
+        let cell: String = row.get(column).unwrap_or_default().to_lowercase();
+        let rcs_id: &str = cell.strip_suffix("@rpi.edu").unwrap_or(cell.as_str());
+        if !rcs_id.is_empty() && !rcs_ids.iter().any(|id| id == rcs_id) {
+            rcs_ids.push(rcs_id.to_string());
+        }
+    }
+
+    if rcs_ids.is_empty() {
+        return Err(invalid_import("The file has no RCS IDs.".into()));
+    }
+    if rcs_ids.len() > MAX_IMPORT_ROWS {
+        return Err(invalid_import(format!(
+            "Imports can have at most {} RCS IDs.",
+            MAX_IMPORT_ROWS
+        )));
+    }
+    return Ok(rcs_ids);
+}
+
+/// Make the import page, with a preview if there is one.
+async fn import_page(
+    req: &HttpRequest,
+    meeting_id: MeetingId,
+    preview: Option<(ImportPreview, Vec<String>, String)>,
+) -> Result<Page, TelescopeError> {
+    let mut template = Template::new(IMPORT_TEMPLATE);
+    template.set_field("meeting_id", meeting_id)?;
+    if let Some((preview, rcs_ids, source)) = preview {
+        template
+            .set_field("title", &preview.title)?
+            .set_field("preview", preview)?
+            .set_field("rcs_ids", rcs_ids.join("\n"))?
+            .set_field("source", source)?;
+    }
+    return template.in_page(req, "Import Attendance").await;
+}
+
+/// Form to import attendance from a spreadsheet.
+#[get("/meeting/{meeting_id}/attendance/import")]
+async fn import_form(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Path(meeting_id): Path<MeetingId>,
+) -> Result<Page, TelescopeError> {
+    authorize(&auth, meeting_id).await?;
+    return import_page(&req, meeting_id, None).await;
+}
+
+/// Preview an attendance import. Nothing is saved until the preview is
+/// confirmed.
+#[post("/meeting/{meeting_id}/attendance/import")]
+async fn preview_import(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Path(meeting_id): Path<MeetingId>,
+    payload: Multipart,
+) -> Result<Page, TelescopeError> {
+    let viewer = authorize(&auth, meeting_id).await?;
+
+    let mut form: MultipartForm = uploads::read(payload).await?;
+    let file = form
+        .take_file("file")
+        .ok_or_else(|| invalid_import("Choose a CSV file to import.".into()))?;
+    let upload = uploads::check(UploadKind::Csv, file, Some(viewer)).await?;
+    let rcs_ids: Vec<String> = read_rcs_ids(&upload.bytes)?;
+
+    // Label the import with the file name unless told otherwise.
+    let source: String = form
+        .field("source")
+        .map(str::trim)
+        .filter(|source| !source.is_empty())
+        .unwrap_or(upload.file_name.as_str())
+        .chars()
+        .take(MAX_SOURCE_LENGTH)
+        .collect();
+
+    let preview = ImportLookup::get(meeting_id, rcs_ids.clone())
+        .await?
+        .ok_or_else(meeting_not_found)?;
+    return import_page(&req, meeting_id, Some((preview, rcs_ids, source))).await;
+}
+
+/// Save a previewed import. The RCS IDs are looked up again, so anyone who
+/// checked in since the preview is not added twice.
+#[post("/meeting/{meeting_id}/attendance/import/confirm")]
+async fn confirm_import(
+    auth: AuthenticationCookie,
+    Path(meeting_id): Path<MeetingId>,
+    Form(form): Form<ConfirmImportForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = authorize(&auth, meeting_id).await?;
+
+    let rcs_ids: Vec<String> = form
+        .rcs_ids
+        .lines()
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .take(MAX_IMPORT_ROWS)
+        .map(str::to_string)
+        .collect();
+    let source: String = form.source.trim().chars().take(MAX_SOURCE_LENGTH).collect();
+
+    let preview = ImportLookup::get(meeting_id, rcs_ids)
+        .await?
+        .ok_or_else(meeting_not_found)?;
+
+    let mut added: usize = 0;
+    for imported in preview.to_add {
+        if ImportAttendance::execute(meeting_id, imported.user.id, viewer, source.clone()).await? {
+            added += 1;
+        }
+    }
+    info!(
+        "{} imported {} attendance records for meeting {} from {:?}.",
+        viewer, added, meeting_id, source
+    );
+
+    return Ok(HttpResponse::Found()
+        .header(LOCATION, format!("/meeting/{}/attendance", meeting_id))
+        .finish());
+}
@@ -0,0 +1,479 @@
+//! Calendar of a semester's meetings, and its export as an iCalendar file.
+//!
+//! The calendar grid is built here rather than in the browser, so that the
+//! page works without scripts and prints the same way it looks.
+//!
+//! Upcoming meetings are also served as an iCalendar feed to subscribe to
+//! from calendar apps. Calendar apps fetch the feed without the viewer's
+//! cookies, so it never has drafts in it.
+
+use crate::api::rcos::meetings::authorization_for::{AuthorizationFor, UserMeetingAuthorization};
+use crate::api::rcos::meetings::get::{meetings::MeetingsMeetings, Meetings};
+use crate::api::rcos::meetings::small_groups::ViewerSmallGroups;
+use crate::api::rcos::meetings::types::{MeetingKind, MeetingTypeOption, MeetingTypes};
+use crate::api::rcos::semesters::list::{semester_list::SemesterListSemesters, SemesterList};
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::Identity;
+use actix_web::http::header::{
+    ContentDisposition, DispositionParam, DispositionType, CONTENT_DISPOSITION, CONTENT_TYPE,
+};
+use actix_web::web::{Query, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The path to the calendar template from the templates directory.
+const TEMPLATE_PATH: &'static str = "meetings/calendar";
+
+/// Column headings of the calendar grid. Weeks start on Sunday.
+const WEEKDAYS: [&'static str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// The longest line in an iCalendar file, in bytes, not counting the line
+/// break.
+const ICS_LINE_LIMIT: usize = 75;
+
+/// How many days of past meetings the feed keeps, so that calendar apps do
+/// not drop meetings as soon as they start.
+const FEED_HISTORY_DAYS: i64 = 7;
+
+/// How many days ahead the feed looks when it is not for one semester.
+const FEED_AHEAD_DAYS: i64 = 180;
+
+/// Register the calendar page and export.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(calendar_page)
+        .service(calendar_export)
+        .service(meetings_feed);
+}
+
+/// How the calendar is laid out.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum CalendarView {
+    /// A grid per month of the semester.
+    Month,
+    /// A row per week of the semester, with meeting times and locations.
+    Week,
+}
+
+impl Default for CalendarView {
+    fn default() -> Self {
+        CalendarView::Month
+    }
+}
+
+/// Query parameters of the calendar page and export.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct CalendarQuery {
+    /// The ID of the semester to show. Defaults to the current semester.
+    semester: Option<String>,
+    #[serde(default)]
+    view: CalendarView,
+}
+
+/// Query parameters of the meetings feed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct FeedQuery {
+    /// The ID of a semester to limit the feed to.
+    semester: Option<String>,
+    /// Meeting types to limit the feed to, separated by commas, as picked in
+    /// the meeting forms.
+    #[serde(rename = "type")]
+    types: Option<String>,
+}
+
+/// A semester to show and the meetings in it that the viewer can see.
+struct SemesterMeetings {
+    semesters: Vec<SemesterListSemesters>,
+    /// Index of the semester being shown.
+    index: usize,
+    meetings: Vec<MeetingsMeetings>,
+    authorization: UserMeetingAuthorization,
+}
+
+impl SemesterMeetings {
+    /// Get the semester picked in the query and its meetings.
+    async fn get(query: &CalendarQuery, identity: &Identity) -> Result<Self, TelescopeError> {
+        let viewer = identity.get_user_id().await?;
+        let semesters: Vec<SemesterListSemesters> = SemesterList::get().await?;
+        let today: NaiveDate = Local::today().naive_local();
+        let index: usize = SemesterList::pick(&semesters, query.semester.as_deref(), today).ok_or(
+            TelescopeError::resource_not_found(
+                "Semester Not Found",
+                "Could not find a semester to show meetings from.",
+            ),
+        )?;
+
+        // Semester dates are in local time.
+        let semester = &semesters[index];
+        let start: DateTime<Utc> = local_midnight(semester.start_date);
+        let end: DateTime<Utc> = local_midnight(semester.end_date + Duration::days(1));
+
+        let authorization: UserMeetingAuthorization = AuthorizationFor::get(viewer).await?;
+        let mut meetings: Vec<MeetingsMeetings> = Meetings::get(
+            start,
+            end,
+            authorization.can_view_drafts(),
+            authorization.viewable_types(),
+        )
+        .await?;
+
+        // Like the meetings list, leave out other small groups' meetings
+        // except for coordinators.
+        if !authorization.can_view_drafts() {
+            let my_groups: Vec<i64> = ViewerSmallGroups::get(viewer).await?;
+            meetings.retain(|meeting| meeting.listed_for(my_groups.as_slice()));
+        }
+
+        return Ok(SemesterMeetings {
+            semesters,
+            index,
+            meetings,
+            authorization,
+        });
+    }
+
+    /// The semester being shown.
+    fn semester(&self) -> &SemesterListSemesters {
+        &self.semesters[self.index]
+    }
+}
+
+/// Get the start of a day in local time.
+fn local_midnight(date: NaiveDate) -> DateTime<Utc> {
+    Local
+        .from_local_date(&date)
+        // Midnight may be ambiguous on days the clocks change.
+        .earliest()
+        .map(|date| date.and_hms(0, 0, 0).with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_date(&date).and_hms(0, 0, 0))
+}
+
+/// Get the Sunday a week starts on.
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_sunday() as i64)
+}
+
+/// Get the first day of the next month.
+fn next_month(date: NaiveDate) -> NaiveDate {
+    match date.month() {
+        12 => NaiveDate::from_ymd(date.year() + 1, 1, 1),
+        month => NaiveDate::from_ymd(date.year(), month + 1, 1),
+    }
+}
+
+/// Build the week starting on the given Sunday. `in_range` marks the days
+/// that belong to the month or semester being shown.
+fn make_week(
+    sunday: NaiveDate,
+    today: NaiveDate,
+    meetings: &HashMap<NaiveDate, Vec<&MeetingsMeetings>>,
+    in_range: impl Fn(NaiveDate) -> bool,
+) -> Vec<Value> {
+    (0..7)
+        .map(|offset| {
+            let date: NaiveDate = sunday + Duration::days(offset);
+            json!({
+                "date": date,
+                "day": date.day(),
+                "in_range": in_range(date),
+                "is_today": date == today,
+                "meetings": meetings.get(&date).cloned().unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Build the calendar grid of a semester.
+fn make_grid(
+    semester: &SemesterListSemesters,
+    view: CalendarView,
+    meetings: &[MeetingsMeetings],
+) -> Vec<Value> {
+    let today: NaiveDate = Local::today().naive_local();
+
+    // Group meetings by the local day they start on.
+    let mut by_day: HashMap<NaiveDate, Vec<&MeetingsMeetings>> = HashMap::new();
+    for meeting in meetings {
+        let day: NaiveDate = meeting
+            .start_date_time
+            .with_timezone(&Local)
+            .naive_local()
+            .date();
+        by_day.entry(day).or_default().push(meeting);
+    }
+
+    let mut sections: Vec<Value> = Vec::new();
+    match view {
+        CalendarView::Month => {
+            let mut first: NaiveDate =
+                NaiveDate::from_ymd(semester.start_date.year(), semester.start_date.month(), 1);
+            while first <= semester.end_date {
+                let last: NaiveDate = next_month(first).pred();
+                let mut weeks: Vec<Vec<Value>> = Vec::new();
+                let mut sunday: NaiveDate = week_start(first);
+                while sunday <= last {
+                    weeks.push(make_week(sunday, today, &by_day, |date| {
+                        date.month() == first.month()
+                    }));
+                    sunday = sunday + Duration::weeks(1);
+                }
+
+                sections.push(json!({
+                    "title": first.format("%B %Y").to_string(),
+                    "weeks": weeks,
+                }));
+                first = next_month(first);
+            }
+        }
+
+        CalendarView::Week => {
+            let mut sunday: NaiveDate = week_start(semester.start_date);
+            while sunday <= semester.end_date {
+                let week = make_week(sunday, today, &by_day, |date| {
+                    semester.start_date <= date && date <= semester.end_date
+                });
+                sections.push(json!({
+                    "title": format!("Week of {}", sunday.format("%B %_d")),
+                    "weeks": [week],
+                }));
+                sunday = sunday + Duration::weeks(1);
+            }
+        }
+    }
+
+    return sections;
+}
+
+/// Calendar of a semester's meetings, colored by type.
+#[get("/schedule/calendar")]
+async fn calendar_page(
+    req: HttpRequest,
+    Query(query): Query<CalendarQuery>,
+    identity: Identity,
+) -> Result<Page, TelescopeError> {
+    let data: SemesterMeetings = SemesterMeetings::get(&query, &identity).await?;
+    let semester = data.semester();
+
+    // The legend lists the types this viewer can see.
+    let meeting_types: Vec<MeetingTypeOption> = MeetingTypes::options()
+        .await?
+        .into_iter()
+        .filter(|option| data.authorization.can_view(option.kind.meeting_type()))
+        .collect();
+
+    let mut template = Template::new(TEMPLATE_PATH);
+    template.fields = json!({
+        "semester": semester,
+        "semesters": data.semesters,
+        "previous": data.index.checked_sub(1).map(|i| &data.semesters[i]),
+        "next": data.semesters.get(data.index + 1),
+        "view": query.view,
+        "weekdays": WEEKDAYS,
+        "sections": make_grid(semester, query.view, &data.meetings),
+        "meeting_types": meeting_types,
+        "feed_url": format!("{}/meetings/export.ics", global_config().telescope_url),
+    });
+
+    let title = format!("{} Calendar", semester.title);
+    return template.in_page(&req, title).await;
+}
+
+/// Escape text for an iCalendar property value.
+fn ics_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+        .replace('\r', "")
+}
+
+/// Format a timestamp for iCalendar, in UTC.
+fn ics_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Add a line to an iCalendar file, folding it if it is too long.
+fn push_ics_line(out: &mut String, line: &str) {
+    let mut width: usize = 0;
+    for c in line.chars() {
+        // Continuation lines start with a space, which counts toward their
+        // length.
+        if width + c.len_utf8() > ICS_LINE_LIMIT {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+/// Build an iCalendar file of meetings.
+fn make_ics(name: &str, meetings: &[MeetingsMeetings]) -> String {
+    let telescope_url: &str = global_config().telescope_url.as_str();
+    let now: String = ics_timestamp(Utc::now());
+
+    let mut out = String::new();
+    push_ics_line(&mut out, "BEGIN:VCALENDAR");
+    push_ics_line(&mut out, "VERSION:2.0");
+    push_ics_line(&mut out, "PRODID:-//RCOS//Telescope//EN");
+    push_ics_line(&mut out, "CALSCALE:GREGORIAN");
+    push_ics_line(
+        &mut out,
+        format!("X-WR-CALNAME:{}", ics_escape(name)).as_str(),
+    );
+
+    for meeting in meetings {
+        let url: String = format!("{}/meeting/{}", telescope_url, meeting.meeting_id);
+        let location: Option<&str> = meeting
+            .location
+            .as_deref()
+            .or(meeting.meeting_url.as_deref());
+
+        push_ics_line(&mut out, "BEGIN:VEVENT");
+        push_ics_line(&mut out, format!("UID:{}", url).as_str());
+        push_ics_line(&mut out, format!("DTSTAMP:{}", now).as_str());
+        push_ics_line(
+            &mut out,
+            format!("DTSTART:{}", ics_timestamp(meeting.start_date_time)).as_str(),
+        );
+        push_ics_line(
+            &mut out,
+            format!("DTEND:{}", ics_timestamp(meeting.end_date_time)).as_str(),
+        );
+        push_ics_line(
+            &mut out,
+            format!("SUMMARY:{}", ics_escape(&meeting.title())).as_str(),
+        );
+        push_ics_line(
+            &mut out,
+            format!("CATEGORIES:{}", ics_escape(&meeting.type_name())).as_str(),
+        );
+        if let Some(location) = location {
+            push_ics_line(
+                &mut out,
+                format!("LOCATION:{}", ics_escape(location)).as_str(),
+            );
+        }
+        if !meeting.description.trim().is_empty() {
+            push_ics_line(
+                &mut out,
+                format!("DESCRIPTION:{}", ics_escape(&meeting.description)).as_str(),
+            );
+        }
+        push_ics_line(&mut out, format!("URL:{}", url).as_str());
+        if meeting.is_draft {
+            push_ics_line(&mut out, "STATUS:TENTATIVE");
+        }
+        push_ics_line(&mut out, "END:VEVENT");
+    }
+
+    push_ics_line(&mut out, "END:VCALENDAR");
+    return out;
+}
+
+/// Download a semester's meetings as an iCalendar file.
+#[get("/schedule/calendar.ics")]
+async fn calendar_export(
+    Query(query): Query<CalendarQuery>,
+    identity: Identity,
+) -> Result<HttpResponse, TelescopeError> {
+    let data: SemesterMeetings = SemesterMeetings::get(&query, &identity).await?;
+    let semester = data.semester();
+
+    return Ok(HttpResponse::Ok()
+        .set_header(CONTENT_TYPE, "text/calendar; charset=utf-8")
+        .set_header(
+            CONTENT_DISPOSITION,
+            ContentDisposition {
+                disposition: DispositionType::Attachment,
+                parameters: vec![DispositionParam::Filename(format!(
+                    "rcos-{}.ics",
+                    semester.semester_id
+                ))],
+            },
+        )
+        .body(make_ics(
+            format!("RCOS {}", semester.title).as_str(),
+            &data.meetings,
+        )));
+}
+
+/// Feed of upcoming meetings to subscribe to from calendar apps, optionally
+/// limited to a semester and to some meeting types.
+#[get("/meetings/export.ics")]
+async fn meetings_feed(
+    Query(query): Query<FeedQuery>,
+    identity: Identity,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = identity.get_user_id().await?;
+
+    let kinds: Option<Vec<MeetingKind>> = match query.types.as_deref() {
+        Some(types) => Some(
+            types
+                .split(',')
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(|value| {
+                    MeetingKind::parse(value).ok_or_else(|| TelescopeError::BadRequest {
+                        header: "Unknown Meeting Type".into(),
+                        message: format!("There is no meeting type {:?}.", value),
+                        show_status_code: true,
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        None => None,
+    };
+
+    let now: DateTime<Utc> = Utc::now();
+    let mut start: DateTime<Utc> = now - Duration::days(FEED_HISTORY_DAYS);
+    let mut end: DateTime<Utc> = now + Duration::days(FEED_AHEAD_DAYS);
+    let mut name: String = "RCOS Meetings".into();
+
+    if let Some(semester_id) = query.semester.as_deref() {
+        let semesters: Vec<SemesterListSemesters> = SemesterList::get().await?;
+        let semester = semesters
+            .iter()
+            .find(|semester| semester.semester_id == semester_id)
+            .ok_or_else(|| {
+                TelescopeError::resource_not_found(
+                    "Semester Not Found",
+                    "Could not find a semester by this ID.",
+                )
+            })?;
+
+        // Semester dates are in local time.
+        start = start.max(local_midnight(semester.start_date));
+        end = local_midnight(semester.end_date + Duration::days(1));
+        name = format!("RCOS {}", semester.title);
+    }
+
+    let authorization: UserMeetingAuthorization = AuthorizationFor::get(viewer).await?;
+    let my_groups: Vec<i64> = ViewerSmallGroups::get(viewer).await?;
+    let meetings: Vec<MeetingsMeetings> = if start < end {
+        Meetings::get(start, end, false, authorization.viewable_types()).await?
+    } else {
+        Vec::new()
+    }
+    .into_iter()
+    .filter(|meeting| meeting.listed_for(my_groups.as_slice()))
+    .filter(|meeting| {
+        kinds.as_ref().map_or(true, |kinds| {
+            let custom_type_id = meeting.custom_type.as_ref().map(|c| c.meeting_type_id);
+            kinds.contains(&MeetingKind::of(meeting.type_, custom_type_id))
+        })
+    })
+    .collect();
+
+    return Ok(HttpResponse::Ok()
+        .set_header(CONTENT_TYPE, "text/calendar; charset=utf-8")
+        .body(make_ics(name.as_str(), &meetings)));
+}
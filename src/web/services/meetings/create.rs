@@ -5,21 +5,32 @@
 //! all users. Once the meeting creator has made a decision, they are directed to a form
 //! to finish meeting creation.
 
+use crate::api::rcos::batch::{batch_execute, BatchOutcome, BatchReport, DEFAULT_CONCURRENCY};
 use crate::api::rcos::meetings::authorization_for::UserMeetingAuthorization;
 use crate::api::rcos::meetings::creation::context::CreationContext;
 use crate::api::rcos::meetings::creation::create::CreateMeeting;
 use crate::api::rcos::meetings::creation::host_selection::HostSelection;
-use crate::api::rcos::meetings::{MeetingType, ALL_MEETING_TYPES};
+use crate::api::rcos::meetings::types::{MeetingKind, MeetingTypes};
+use crate::api::rcos::prelude::MeetingId;
+use crate::api::video::{self, Call, CallRepeat, CallRequest};
+use crate::discord_bot::{self, guilds, DiscordMessage};
+use crate::env::global_config;
 use crate::error::TelescopeError;
+use crate::templates::forms::{Field, FieldKind, FormSchema};
 use crate::templates::page::Page;
 use crate::templates::Template;
+use crate::web::idempotency;
+use crate::web::services::auth::identity::AuthenticationCookie;
 use crate::web::services::meetings::make_meeting_auth_middleware;
+use crate::web::sessions;
+use crate::web::validation::validate_url;
+use crate::web::webhooks::{self, WebhookEvent};
 use actix_web::http::header::LOCATION;
 use actix_web::web as aweb;
-use actix_web::web::{Form, Query, ServiceConfig};
+use actix_web::web::{Form, Path, Query, ServiceConfig};
 use actix_web::HttpRequest;
 use actix_web::HttpResponse;
-use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 use serde_json::Value;
 use uuid::Uuid;
 
@@ -29,6 +40,62 @@ const HOST_SELECTION_TEMPLATE: &'static str = "meetings/creation/host_selection"
 /// The handlebars template to finish the meeting creation process.
 const FINISH_CREATION_TEMPLATE: &'static str = "meetings/creation/finish";
 
+/// The handlebars template for a series of meetings that was only partly
+/// created.
+const SERIES_TEMPLATE: &'static str = "meetings/creation/series";
+
+/// How long a partly created series is kept so its failed meetings can be
+/// tried again, in hours.
+const SERIES_LIFETIME_HOURS: i64 = 24;
+
+/// The fields on the form to finish meeting creation that are rendered and
+/// checked from a schema. The semester, type, and small group are picked from
+/// options in the creation context instead.
+pub const MEETING_FORM: FormSchema = FormSchema {
+    fields: &[
+        Field::new("title", "Title (optional)", FieldKind::Text)
+            .max_length(100)
+            .help(
+                "If left out, a title will be auto-generated from the meeting type and date. It is \
+                 strongly recommended to include a title for workshops, since there are sometimes \
+                 several daily and they focus on different topics.",
+            ),
+        Field::new("start_date", "Start date", FieldKind::Date).required(),
+        Field::new("start_time", "Start time", FieldKind::Time).required(),
+        Field::new("end_date", "End date", FieldKind::Date).required(),
+        Field::new("end_time", "End time", FieldKind::Time).required(),
+        Field::new("repeat_until", "Repeat until", FieldKind::Date).help(
+            "Repeating meetings are created up to and including this date, and must all be during \
+             the semester.",
+        ),
+        Field::new("description", "Description", FieldKind::TextArea).help(
+            "Descriptions are rendered as markdown. Supported features and syntax are described \
+             <a href=\"https://github.com/raphlinus/pulldown-cmark\" target=\"_blank\" \
+             rel=\"noopener noreferrer\">here.</a>",
+        ),
+        Field::new("is_remote", "Remote meeting", FieldKind::Checkbox),
+        Field::new("meeting_url", "Meeting URL", FieldKind::Url),
+        Field::new("location", "Location", FieldKind::Text).max_length(200),
+        Field::new("recording_url", "Recording URL", FieldKind::Url),
+        Field::new("external_slides_url", "Slides URL", FieldKind::Url).help(
+            "Natively hosted slides using \
+             <a href=\"https://revealjs.com/\" rel=\"noopener noreferrer\" target=\"_blank\">revealjs</a> \
+             will be supported in a future release.",
+        ),
+        Field::new("is_draft", "Draft (not public)", FieldKind::Checkbox),
+        Field::new(
+            "announce_on_discord",
+            "Announce on Discord (members can RSVP with a reaction)",
+            FieldKind::Checkbox,
+        ),
+        Field::new(
+            "add_hosts",
+            "Add co-hosts or guest speakers next",
+            FieldKind::Checkbox,
+        ),
+    ],
+};
+
 /// Register meeting creation services.
 pub fn register(config: &mut ServiceConfig) {
     // Create meeting creation auth middleware.
@@ -40,7 +107,10 @@ pub fn register(config: &mut ServiceConfig) {
             .wrap(authorization)
             .service(host_selection_page)
             .service(finish)
-            .service(submit_meeting),
+            .service(form_options)
+            .service(submit_meeting)
+            .service(series_page)
+            .service(retry_series),
     );
 }
 
@@ -89,7 +159,17 @@ async fn finish_form(host: Option<Uuid>) -> Result<Template, TelescopeError> {
     // Add context to form.
     form.fields = json!({
         "context": context,
-        "meeting_types": &ALL_MEETING_TYPES
+        "meeting_types": MeetingTypes::options().await?,
+        // Remote meetings don't need a link if one can be generated.
+        "video_provider": video::provider().map(|provider| provider.name()),
+        "video_note": video::provider().map(|provider| format!(
+            "Leave this empty on a remote meeting to create a {} call for it.",
+            provider.name()
+        )),
+        "schema": MEETING_FORM,
+        // Identifies this copy of the form so it is not submitted twice.
+        "idempotency_key": idempotency::new_key(),
+        "can_announce_on_discord": !guilds::announcement_channels().is_empty()
     });
 
     // Return form with context.
@@ -111,6 +191,85 @@ async fn finish(
         .await
 }
 
+/// A small group that can be picked on the meeting form.
+#[derive(Serialize, Clone, Debug)]
+struct SmallGroupOption {
+    small_group_id: i64,
+    title: String,
+}
+
+/// A semester that can be picked on the meeting form, with the options that
+/// depend on it.
+#[derive(Serialize, Clone, Debug)]
+struct SemesterOption {
+    semester_id: String,
+    title: String,
+    /// Meeting dates must be on or after this.
+    start_date: NaiveDate,
+    /// Meeting dates must be on or before this.
+    end_date: NaiveDate,
+    small_groups: Vec<SmallGroupOption>,
+}
+
+/// Options for the fields of the meeting form that depend on the selected
+/// semester, so the form can limit dates and small groups before it is
+/// submitted. These come from the same context the form is made from.
+#[get("/options")]
+async fn form_options() -> Result<HttpResponse, TelescopeError> {
+    let context = CreationContext::execute(None, Vec::new()).await?;
+
+    let semesters: Vec<SemesterOption> = context
+        .available_semesters
+        .into_iter()
+        .map(|semester| SemesterOption {
+            small_groups: context
+                .small_groups
+                .iter()
+                .filter(|group| group.semester_id == semester.semester_id)
+                .map(|group| SmallGroupOption {
+                    small_group_id: group.small_group_id,
+                    title: group.title.clone(),
+                })
+                .collect(),
+            semester_id: semester.semester_id,
+            title: semester.title,
+            start_date: semester.start_date,
+            end_date: semester.end_date,
+        })
+        .collect();
+
+    return Ok(HttpResponse::Ok().json(json!({ "semesters": semesters })));
+}
+
+/// How often a meeting created from the form repeats.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Recurrence {
+    /// Create a single meeting.
+    Once,
+    /// Every week.
+    Weekly,
+    /// Every other week.
+    Biweekly,
+}
+
+impl Default for Recurrence {
+    fn default() -> Self {
+        Recurrence::Once
+    }
+}
+
+impl Recurrence {
+    /// The number of days between meetings, if the meeting repeats.
+    fn interval_days(self) -> Option<i64> {
+        match self {
+            Recurrence::Once => None,
+            Recurrence::Weekly => Some(7),
+            Recurrence::Biweekly => Some(14),
+        }
+    }
+}
+
 /// Form submitted by users to create meeting.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct FinishForm {
@@ -118,18 +277,21 @@ pub struct FinishForm {
     pub semester: String,
 
     /// What type of meeting is being created.
-    pub kind: MeetingType,
+    pub kind: MeetingKind,
 
     /// The optional meeting title. Default empty.
     #[serde(default)]
     pub title: String,
 
-    pub start_date: NaiveDate,
+    /// Read as text so that a malformed date is marked on the form instead
+    /// of failing the whole request.
+    pub start_date: String,
 
     /// Cannot be a [`chrono::NaiveTime`], since seconds are not included.
     pub start_time: String,
 
-    pub end_date: NaiveDate,
+    /// Read as text, like the start date.
+    pub end_date: String,
 
     /// Cannot be a [`chrono::NaiveTime`], since seconds are not included.
     pub end_time: String,
@@ -155,6 +317,89 @@ pub struct FinishForm {
 
     #[serde(default)]
     pub is_draft: Option<bool>,
+
+    /// The ID of the small group this meeting is only for. Empty for
+    /// meetings open to everyone.
+    #[serde(default)]
+    pub small_group: Option<String>,
+
+    /// Whether to announce the meeting on Discord once it is created. Drafts
+    /// are not announced. Not used when editing.
+    #[serde(default)]
+    pub announce_on_discord: Option<bool>,
+
+    /// Whether to go to the co-host page once the meeting is created, rather
+    /// than the meeting page. Not used when editing.
+    #[serde(default)]
+    pub add_hosts: Option<bool>,
+
+    /// Identifies a submission of the creation form, so that submitting it
+    /// twice does not create two meetings. Not used when editing.
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+
+    /// How often to repeat the meeting. Not used when editing.
+    #[serde(default)]
+    pub repeat: Recurrence,
+
+    /// The last date a repeating meeting may start on. Empty unless the
+    /// meeting repeats. Not used when editing.
+    #[serde(default)]
+    pub repeat_until: Option<String>,
+}
+
+/// The fields shared by every meeting created from one submission of the
+/// creation form.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct NewMeeting {
+    host: Option<Uuid>,
+    title: Option<String>,
+    /// The name of the meeting type, used to name video calls.
+    kind_name: String,
+    description: String,
+    is_draft: bool,
+    is_remote: bool,
+    location: Option<String>,
+    meeting_url: Option<String>,
+    /// The host key of the call made for the meetings, if there is one.
+    host_key: Option<String>,
+    recording_url: Option<String>,
+    external_slides_url: Option<String>,
+    semester: String,
+    kind: MeetingKind,
+    small_group_id: Option<i64>,
+    announce_on_discord: bool,
+}
+
+/// One meeting of a series.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Occurrence {
+    /// The date of the meeting, shown to the user.
+    label: String,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    /// The meeting, once it has been created.
+    meeting_id: Option<MeetingId>,
+    /// Why the meeting could not be created the last time it was tried.
+    error: Option<String>,
+}
+
+/// A series of repeating meetings created from one submission of the
+/// creation form. Series that are only partly created are kept in the session
+/// store, so the meetings that failed can be tried again without creating the
+/// others twice.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Series {
+    /// The user who submitted the form. Only they can see the series.
+    creator: Uuid,
+    meeting: NewMeeting,
+    /// The meetings of the series, in order.
+    occurrences: Vec<Occurrence>,
+    /// Whether a meeting of the series has been announced on Discord. Only
+    /// the first one created is.
+    announced: bool,
+    /// Whether to go to the co-host page once the first meeting is created.
+    add_hosts: bool,
 }
 
 /// Endpoint that users submit meeting creation forms to.
@@ -162,15 +407,34 @@ pub struct FinishForm {
 async fn submit_meeting(
     req: HttpRequest,
     query: Option<Query<FinishQuery>>,
+    auth: AuthenticationCookie,
     Form(form): Form<FinishForm>,
 ) -> Result<HttpResponse, TelescopeError> {
     // Resolve host user ID.
     let host = query.map(|q| q.host.clone());
+    let viewer: Uuid = auth.get_user_id_or_error().await?;
+    // Send repeat submissions of the form to the meeting it already created.
+    let key: Option<String> = form.idempotency_key.clone();
+    return idempotency::once(key, create_meeting(req, host, viewer, form)).await;
+}
 
+/// Validate a submitted meeting creation form and create the meeting. The
+/// viewer is the user submitting the form.
+async fn create_meeting(
+    req: HttpRequest,
+    host: Option<Uuid>,
+    viewer: Uuid,
+    form: FinishForm,
+) -> Result<HttpResponse, TelescopeError> {
     // Create a form instance to send back to the user if the one they submitted was invalid.
     let mut return_form: Template = finish_form(host.clone()).await?;
     // Add previously selected fields to the form.
-    return_form["selections"] = json!(&form);
+    return_form.set_field("selections", &form)?;
+
+    // Check the fields against the schema first. The rest of the checks add
+    // their own issues, and all of them are shown together.
+    let selections: Value = return_form["selections"].clone();
+    MEETING_FORM.validate(&mut return_form, &selections);
 
     // Validate form fields.
     // Start by destructuring form:
@@ -189,6 +453,12 @@ async fn submit_meeting(
         recording_url,
         external_slides_url,
         is_draft,
+        small_group,
+        announce_on_discord,
+        add_hosts,
+        idempotency_key: _,
+        repeat,
+        repeat_until,
     } = form;
 
     // We assume that semester_id is valid, since it includes only options from the creation
@@ -207,7 +477,12 @@ async fn submit_meeting(
     // they know if they put in all whitespace. This also decreases form resubmission
     // and template complexity.
     let title: Option<String> = (!title.trim().is_empty()).then(|| title);
-    return_form["selections"]["title"] = json!(&title);
+    return_form.set_path(&["selections", "title"], &title)?;
+
+    // The schema marks dates that could not be read, so the checks that need
+    // them are skipped for those.
+    let start_date: Option<NaiveDate> = start_date.trim().parse::<NaiveDate>().ok();
+    let end_date: Option<NaiveDate> = end_date.trim().parse::<NaiveDate>().ok();
 
     // Check that the start date and end dates are during the semester selected.
     let selected_semester: &Value = return_form["context"]["available_semesters"]
@@ -227,31 +502,65 @@ async fn submit_meeting(
     // Get the semester bounds.
     let (semester_start, semester_end) = get_semester_bounds(selected_semester);
 
-    // If meeting starts before semester, save to issues and return form.
-    if start_date < semester_start {
-        return_form["issues"]["start_date"] = json!("Start date is before the semester starts.");
-    }
-    // Same if meeting starts after the end of the semester.
-    else if start_date > semester_end {
-        return_form["issues"]["start_date"] = json!("Start date is after the semester ends.");
+    if let Some(start_date) = start_date {
+        // If meeting starts before semester, save to issues and return form.
+        if start_date < semester_start {
+            return_form.set_issue("start_date", "Start date is before the semester starts.");
+        }
+        // Same if meeting starts after the end of the semester.
+        else if start_date > semester_end {
+            return_form.set_issue("start_date", "Start date is after the semester ends.");
+        }
     }
 
     // Same with end date.
-    if end_date < semester_start {
-        return_form["issues"]["end_date"] = json!("End date is before the semester starts.");
-    } else if end_date > semester_end {
-        return_form["issues"]["end_date"] = json!("End date is after the semester ends.");
-    }
-    // Also check if the end is before the start.
-    else if end_date < start_date {
-        return_form["issues"]["end_date"] = json!("End date is before start date.");
+    if let Some(end_date) = end_date {
+        if end_date < semester_start {
+            return_form.set_issue("end_date", "End date is before the semester starts.");
+        } else if end_date > semester_end {
+            return_form.set_issue("end_date", "End date is after the semester ends.");
+        }
+        // Also check if the end is before the start.
+        else if start_date.map_or(false, |start_date| end_date < start_date) {
+            return_form.set_issue("end_date", "End date is before start date.");
+        }
     }
 
-    // Check for errors and return form if necessary.
-    if return_form["issues"] != json!(null) {
-        let page = return_form.in_page(&req, "Create Meeting").await?;
-        return Err(TelescopeError::InvalidForm(page));
-    }
+    // Check the small group is one of the selected semester's.
+    let small_group_id: Option<i64> =
+        validate_small_group(&mut return_form, small_group.as_deref(), semester.as_str());
+    return_form.set_path(&["selections", "small_group"], small_group_id)?;
+
+    // Validate and normalize the links.
+    let (meeting_url, recording_url, external_slides_url) = validate_links(
+        &mut return_form,
+        meeting_url,
+        recording_url,
+        external_slides_url,
+    );
+
+    // Work out which days after the first the meeting repeats on.
+    let offsets: Vec<i64> = match start_date.zip(end_date) {
+        Some((start_date, end_date)) => expand_recurrence(
+            &mut return_form,
+            repeat,
+            repeat_until.as_deref(),
+            start_date,
+            end_date,
+            semester_end,
+        ),
+        None => vec![0],
+    };
+
+    // Check for errors and return form if necessary. Dates that could not be
+    // read are among them.
+    let (start_date, end_date): (NaiveDate, NaiveDate) = match start_date.zip(end_date) {
+        Some(dates) if return_form["issues"] == json!(null) => dates,
+        _ => {
+            let page = return_form.in_page(&req, "Create Meeting").await?;
+            return Err(TelescopeError::InvalidForm(page));
+        }
+    };
 
     // Dates are validated, let's check the times. Start by converting the times from strings.
     let start_time: NaiveTime = format!("{}:00", start_time)
@@ -276,71 +585,385 @@ async fn submit_meeting(
 
     // Check the ordering.
     if start > end {
-        return_form["issues"]["end_time"] = json!("End time is before start time.");
+        return_form.set_issue("end_time", "End time is before start time.");
         let page = return_form.in_page(&req, "Create Meeting").await?;
         return Err(TelescopeError::InvalidForm(page));
     }
 
-    // Ascribe local timezone.
-    let start: DateTime<Local> = Local
-        .from_local_datetime(&start)
-        // Expect that there is only one valid local time for this.
-        .single()
-        .ok_or(TelescopeError::BadRequest {
-            header: "Malformed Meeting Creation Form".into(),
-            message: "Could not ascribe local timezone to start timestamp.".into(),
-            show_status_code: false,
-        })?;
-
-    let end: DateTime<Local> = Local
-        .from_local_datetime(&end)
-        // Expect that there is only one valid local time for this.
-        .single()
-        .ok_or(TelescopeError::BadRequest {
-            header: "Malformed Meeting Creation Form".into(),
-            message: "Could not ascribe local timezone to end timestamp.".into(),
-            show_status_code: false,
-        })?;
+    // The name of the selected meeting type, from the options on the form.
+    let kind_name: String = return_form["meeting_types"]
+        .as_array()
+        .and_then(|options| options.iter().find(|option| option["kind"] == kind.value()))
+        .and_then(|option| option["name"].as_str())
+        .unwrap_or("Meeting")
+        .to_string();
 
     // The rest of the fields are managed pretty tersely in the API call and do not need validation
     // or feedback.
-    let created_meeting_id: i64 = CreateMeeting::execute(
+    let is_draft: bool = is_draft.unwrap_or(false);
+    let mut new_meeting = NewMeeting {
         host,
         title,
-        start.with_timezone(&Utc),
-        end.with_timezone(&Utc),
-        description.trim().to_string(),
-        is_draft.unwrap_or(false),
-        is_remote.unwrap_or(false),
-        location.and_then(|string| (!string.trim().is_empty()).then(|| string.trim().to_string())),
+        kind_name,
+        description: description.trim().to_string(),
+        is_draft,
+        is_remote: is_remote.unwrap_or(false),
+        location: location
+            .and_then(|string| (!string.trim().is_empty()).then(|| string.trim().to_string())),
         meeting_url,
         recording_url,
         external_slides_url,
         semester,
         kind,
-    )
-    .await?
-    .ok_or(TelescopeError::ise(
-        "Meeting creation call did not return ID.",
-    ))?;
-
-    // Redirect the user to the page for the meeting they created.
-    return Ok(HttpResponse::Found()
-        .header(LOCATION, format!("/meeting/{}", created_meeting_id))
-        .finish());
+        small_group_id,
+        // Drafts are not announced.
+        announce_on_discord: announce_on_discord.unwrap_or(false) && !is_draft,
+        host_key: None,
+    };
+    let add_hosts: bool = add_hosts.unwrap_or(false);
+
+    // The meetings of a series share one call, which repeats with them.
+    let last_offset: Duration = Duration::days(*offsets.last().unwrap_or(&0));
+    let call_repeat: Option<CallRepeat> = match repeat.interval_days() {
+        Some(interval_days) if offsets.len() > 1 => Some(CallRepeat {
+            interval_days,
+            last_start: local_time(&(start + last_offset), "start")?.with_timezone(&Utc),
+        }),
+        _ => None,
+    };
+    new_meeting.create_call(start, end, call_repeat).await?;
+
+    // A single meeting reports errors straight to the user.
+    let created_meeting_id: MeetingId = if offsets.len() == 1 {
+        let created_meeting_id: MeetingId = new_meeting.create(start, end).await?;
+        if new_meeting.announce_on_discord {
+            announce(created_meeting_id);
+        }
+        created_meeting_id
+    } else {
+        let occurrences: Vec<Occurrence> = offsets
+            .into_iter()
+            .map(|offset| {
+                let offset: Duration = Duration::days(offset);
+                Occurrence {
+                    label: (start_date + offset).format("%B %-d, %Y").to_string(),
+                    start: start + offset,
+                    end: end + offset,
+                    meeting_id: None,
+                    error: None,
+                }
+            })
+            .collect();
+        let mut series = Series {
+            creator: viewer,
+            meeting: new_meeting,
+            occurrences,
+            announced: false,
+            add_hosts,
+        };
+        series.create_missing().await;
+
+        // Keep a series with meetings that failed, and send the user to it.
+        // The redirect is recorded with the form's idempotency key, so
+        // submitting the form again comes back here instead of creating the
+        // other meetings twice.
+        if !series.is_complete() {
+            let series_id: String = idempotency::new_key();
+            series.save(series_id.as_str()).await?;
+            return Ok(HttpResponse::Found()
+                .header(LOCATION, format!("/meeting/create/series/{}", series_id))
+                .finish());
+        }
+
+        series
+            .first_meeting()
+            .expect("Every meeting in the series was created.")
+    };
+
+    return Ok(redirect_to_created(created_meeting_id, add_hosts));
+}
+
+/// Redirect the user to the page for the (first) meeting they created, or to
+/// add its co-hosts if they asked to.
+fn redirect_to_created(meeting_id: MeetingId, add_hosts: bool) -> HttpResponse {
+    let redirect: String = if add_hosts {
+        format!("/meeting/{}/hosts", meeting_id)
+    } else {
+        format!("/meeting/{}", meeting_id)
+    };
+    return HttpResponse::Found().header(LOCATION, redirect).finish();
 }
 
-/// Get the start and end dates of a selected semester object from the meeting creation context.
-pub fn get_semester_bounds(selected_semester: &Value) -> (NaiveDate, NaiveDate) {
-    let semester_start = selected_semester["start_date"]
-        .as_str()
-        .and_then(|string| string.parse::<NaiveDate>().ok())
-        .expect("Semester from context has good start date.");
+/// Ascribe the local timezone to a time from the creation form. `which` is
+/// "start" or "end".
+fn local_time(time: &NaiveDateTime, which: &str) -> Result<DateTime<Local>, TelescopeError> {
+    Local
+        .from_local_datetime(time)
+        // Expect that there is only one valid local time for this.
+        .single()
+        .ok_or(TelescopeError::BadRequest {
+            header: "Malformed Meeting Creation Form".into(),
+            message: format!("Could not ascribe local timezone to {} timestamp.", which),
+            show_status_code: false,
+        })
+}
+
+/// Announce a created meeting on Discord. The bot fetches the meeting and
+/// posts it in the background.
+fn announce(meeting_id: MeetingId) {
+    if let Err(e) = discord_bot::send(DiscordMessage::AnnounceMeeting { meeting_id }) {
+        warn!(
+            "Could not announce meeting {} on Discord: {}",
+            meeting_id, e
+        );
+    }
+}
+
+impl NewMeeting {
+    /// Create a call for remote meetings without a link if a video provider
+    /// is configured, and use its link for every meeting made from this.
+    /// `repeat` is set for a series, so one call covers all of it.
+    async fn create_call(
+        &mut self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        repeat: Option<CallRepeat>,
+    ) -> Result<(), TelescopeError> {
+        let provider = match (self.is_remote, self.meeting_url.as_ref(), video::provider()) {
+            (true, None, Some(provider)) => provider,
+            _ => return Ok(()),
+        };
+
+        let call: Call = provider
+            .create_call(&CallRequest {
+                topic: self
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| format!("RCOS {}", self.kind_name)),
+                start: local_time(&start, "start")?.with_timezone(&Utc),
+                end: local_time(&end, "end")?.with_timezone(&Utc),
+                repeat,
+            })
+            .await?;
+        info!("Created {} call for new meeting.", provider.name());
+        self.meeting_url = Some(call.join_url);
+        self.host_key = call.host_key;
+        return Ok(());
+    }
 
-    let semester_end = selected_semester["end_date"]
-        .as_str()
-        .and_then(|string| string.parse::<NaiveDate>().ok())
-        .expect("Semester from context has good end date.");
+    /// Create a meeting at the given local start and end times. Webhooks are
+    /// told about meetings that are not drafts.
+    async fn create(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<MeetingId, TelescopeError> {
+        let start: DateTime<Local> = local_time(&start, "start")?;
+        let end: DateTime<Local> = local_time(&end, "end")?;
+
+        let created_meeting_id: MeetingId = CreateMeeting::execute(
+            self.host,
+            self.title.clone(),
+            start.with_timezone(&Utc),
+            end.with_timezone(&Utc),
+            self.description.clone(),
+            self.is_draft,
+            self.is_remote,
+            self.location.clone(),
+            self.meeting_url.clone(),
+            self.host_key.clone(),
+            self.recording_url.clone(),
+            self.external_slides_url.clone(),
+            self.semester.clone(),
+            self.kind,
+            self.small_group_id,
+        )
+        .await?
+        .ok_or(TelescopeError::ise(
+            "Meeting creation call did not return ID.",
+        ))?;
+
+        if !self.is_draft {
+            webhooks::emit(WebhookEvent::MeetingPublished {
+                meeting_id: created_meeting_id,
+                title: self.title.clone(),
+                start: start.with_timezone(&Utc),
+                end: end.with_timezone(&Utc),
+            })
+            .await;
+        }
+
+        return Ok(created_meeting_id);
+    }
+}
 
-    return (semester_start, semester_end);
+impl Series {
+    /// The session store key of a partly created series.
+    fn store_key(series_id: &str) -> String {
+        format!("meeting-series:{}", series_id)
+    }
+
+    /// Create the meetings of the series that have not been created yet,
+    /// and announce the first one created if the series has not been
+    /// announced.
+    async fn create_missing(&mut self) {
+        let pending: Vec<usize> = (0..self.occurrences.len())
+            .filter(|index| self.occurrences[*index].meeting_id.is_none())
+            .collect();
+
+        // Create every meeting, even if some fail.
+        let meeting: &NewMeeting = &self.meeting;
+        let occurrences: &[Occurrence] = self.occurrences.as_slice();
+        let calls = pending.iter().map(|index| {
+            let occurrence: &Occurrence = &occurrences[*index];
+            (
+                occurrence.label.clone(),
+                meeting.create(occurrence.start, occurrence.end),
+            )
+        });
+        let report: BatchReport<MeetingId> = batch_execute(calls, DEFAULT_CONCURRENCY).await;
+
+        for (index, outcome) in pending.into_iter().zip(report.outcomes) {
+            self.occurrences[index].meeting_id = outcome.value;
+            self.occurrences[index].error = outcome.error;
+        }
+
+        if self.meeting.announce_on_discord && !self.announced {
+            if let Some(meeting_id) = self.first_meeting() {
+                announce(meeting_id);
+                self.announced = true;
+            }
+        }
+    }
+
+    /// The first meeting of the series that has been created.
+    fn first_meeting(&self) -> Option<MeetingId> {
+        self.occurrences
+            .iter()
+            .find_map(|occurrence| occurrence.meeting_id)
+    }
+
+    /// Whether every meeting of the series has been created.
+    fn is_complete(&self) -> bool {
+        self.occurrences
+            .iter()
+            .all(|occurrence| occurrence.meeting_id.is_some())
+    }
+
+    /// Which meetings of the series were created, for the `batch/report`
+    /// template partial.
+    fn report(&self) -> BatchReport<MeetingId> {
+        let outcomes: Vec<BatchOutcome<MeetingId>> = self
+            .occurrences
+            .iter()
+            .map(|occurrence| BatchOutcome {
+                label: occurrence.label.clone(),
+                value: occurrence.meeting_id,
+                error: occurrence.error.clone(),
+            })
+            .collect();
+        let succeeded: usize = outcomes
+            .iter()
+            .filter(|outcome| outcome.value.is_some())
+            .count();
+        return BatchReport {
+            failed: outcomes.len() - succeeded,
+            succeeded,
+            outcomes,
+        };
+    }
+
+    /// Keep the series in the session store.
+    async fn save(&self, series_id: &str) -> Result<(), TelescopeError> {
+        let value: String = serde_json::to_string(self)
+            .map_err(|e| TelescopeError::ise(format!("Could not save meeting series: {}", e)))?;
+        return sessions::store()
+            .set(
+                Series::store_key(series_id).as_str(),
+                value,
+                Some(Duration::hours(SERIES_LIFETIME_HOURS)),
+            )
+            .await;
+    }
+
+    /// Get a series created by the viewer from the session store.
+    async fn load(series_id: &str, viewer: Uuid) -> Result<Series, TelescopeError> {
+        let not_found = || {
+            TelescopeError::resource_not_found(
+                "Series Not Found",
+                "Could not find this series of meetings. Series are only kept for a day after \
+                they are created. Check the meetings page for the ones that were created.",
+            )
+        };
+
+        let value: String = sessions::store()
+            .get(Series::store_key(series_id).as_str())
+            .await?
+            .ok_or_else(not_found)?;
+        let series: Series = serde_json::from_str(value.as_str())
+            .map_err(|e| TelescopeError::ise(format!("Could not read meeting series: {}", e)))?;
+
+        // Other users cannot tell a series they did not create exists.
+        if series.creator != viewer {
+            return Err(not_found());
+        }
+        return Ok(series);
+    }
+}
+
+/// Form submitted to try the failed meetings of a series again.
+#[derive(Clone, Debug, Deserialize)]
+struct RetryForm {
+    /// Identifies this copy of the form so it is not submitted twice.
+    #[serde(default)]
+    idempotency_key: Option<String>,
+}
+
+/// Page listing the meetings of a series that was only partly created, with
+/// a form to try the ones that failed again.
+#[get("/series/{series_id}")]
+async fn series_page(
+    req: HttpRequest,
+    Path(series_id): Path<String>,
+    auth: AuthenticationCookie,
+) -> Result<Page, TelescopeError> {
+    let viewer: Uuid = auth.get_user_id_or_error().await?;
+    let series: Series = Series::load(series_id.as_str(), viewer).await?;
+
+    let mut template = Template::new(SERIES_TEMPLATE);
+    template
+        .set_field("report", series.report())?
+        .set_field("series", &series)?
+        .set_field("idempotency_key", idempotency::new_key())?;
+    return template.in_page(&req, "Create Meeting").await;
+}
+
+/// Try the meetings of a series that failed again.
+#[post("/series/{series_id}")]
+async fn retry_series(
+    Path(series_id): Path<String>,
+    auth: AuthenticationCookie,
+    Form(form): Form<RetryForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer: Uuid = auth.get_user_id_or_error().await?;
+    let retry = retry_failed_meetings(series_id, viewer);
+    return idempotency::once(form.idempotency_key, retry).await;
+}
+
+/// Create the meetings of a series that failed. Go back to the series if
+/// some still fail, or to the meetings otherwise.
+async fn retry_failed_meetings(
+    series_id: String,
+    viewer: Uuid,
+) -> Result<HttpResponse, TelescopeError> {
+    let mut series: Series = Series::load(series_id.as_str(), viewer).await?;
+    series.create_missing().await;
+    series.save(series_id.as_str()).await?;
+
+    return Ok(match series.first_meeting() {
+        Some(first) if series.is_complete() => redirect_to_created(first, series.add_hosts),
+        _ => HttpResponse::Found()
+            .header(LOCATION, format!("/meeting/create/series/{}", series_id))
+            .finish(),
+    });
 }
@@ -2,6 +2,7 @@
 
 use crate::api::rcos::meetings::authorization_for::{AuthorizationFor, UserMeetingAuthorization};
 use crate::api::rcos::meetings::delete::DeleteMeeting;
+use crate::api::rcos::prelude::MeetingId;
 use crate::error::TelescopeError;
 use crate::web::services::auth::identity::AuthenticationCookie;
 use actix_web::http::header::LOCATION;
@@ -17,7 +18,7 @@ pub fn register(config: &mut ServiceConfig) {
 #[post("/meeting/{meeting_id}/delete")]
 async fn delete_meeting(
     auth: AuthenticationCookie,
-    Path(meeting_id): Path<i64>,
+    Path(meeting_id): Path<MeetingId>,
 ) -> Result<HttpResponse, TelescopeError> {
     // Require that there is a user authenticated.
     let user_id = auth.get_user_id_or_error().await?;
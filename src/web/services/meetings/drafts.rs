@@ -0,0 +1,122 @@
+//! Page listing upcoming drafts, and scheduling drafts to be published.
+//!
+//! Hosts pick a time on the meeting page, and the
+//! [draft publisher](crate::jobs::publishing) publishes the draft then.
+
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::api::rcos::meetings::get_by_id::Meeting;
+use crate::api::rcos::meetings::publishing::{SchedulePublishing, UpcomingDrafts};
+use crate::api::rcos::prelude::MeetingId;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+
+/// The path to the drafts page template from the templates directory.
+const TEMPLATE_PATH: &'static str = "meetings/drafts";
+
+/// Register draft services.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(drafts_page).service(schedule);
+}
+
+/// Form submitted from the meeting page to schedule a draft.
+#[derive(Deserialize, Debug)]
+struct ScheduleForm {
+    /// The date to publish on. Empty to stop publishing automatically.
+    #[serde(default)]
+    publish_date: String,
+    #[serde(default)]
+    publish_time: String,
+    /// Whether to announce the meeting on Discord once it is published.
+    #[serde(default)]
+    announce: Option<bool>,
+}
+
+/// Upcoming drafts the viewer hosts, or every upcoming draft for those who
+/// can view drafts, with when each is published.
+#[get("/meetings/drafts")]
+async fn drafts_page(req: HttpRequest, auth: AuthenticationCookie) -> Result<Page, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    let include_all: bool = AuthorizationFor::get(Some(viewer)).await?.can_view_drafts();
+
+    let mut template = Template::new(TEMPLATE_PATH);
+    template
+        .set_field("drafts", UpcomingDrafts::get(viewer, include_all).await?)?
+        .set_field("include_all", include_all)?;
+    return template.in_page(&req, "Drafts").await;
+}
+
+/// Schedule a draft to be published, or stop it from being published
+/// automatically. Only those who can edit the meeting can schedule it.
+#[post("/meeting/{meeting_id}/schedule")]
+async fn schedule(
+    auth: AuthenticationCookie,
+    Path(meeting_id): Path<MeetingId>,
+    Form(form): Form<ScheduleForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    if !AuthorizationFor::get(Some(viewer))
+        .await?
+        .can_edit_by_id(meeting_id)
+        .await?
+    {
+        return Err(TelescopeError::Forbidden);
+    }
+
+    let meeting = Meeting::get(meeting_id).await?.ok_or_else(|| {
+        TelescopeError::resource_not_found(
+            "Meeting Not Found",
+            "Could not find a meeting for this ID.",
+        )
+    })?;
+
+    if !meeting.is_draft {
+        return Err(TelescopeError::BadRequest {
+            header: "Meeting Already Published".into(),
+            message: "Only drafts can be scheduled to be published.".into(),
+            show_status_code: false,
+        });
+    }
+
+    let announce: bool = form.announce.unwrap_or(false);
+    let publish_at: Option<DateTime<Utc>> = if form.publish_date.trim().is_empty() {
+        None
+    } else {
+        let invalid = || TelescopeError::BadRequest {
+            header: "Invalid Publish Time".into(),
+            message: "Pick a date and time to publish this meeting at.".into(),
+            show_status_code: false,
+        };
+
+        let date = NaiveDate::parse_from_str(form.publish_date.trim(), "%Y-%m-%d")
+            .map_err(|_| invalid())?;
+        let time =
+            NaiveTime::parse_from_str(form.publish_time.trim(), "%H:%M").map_err(|_| invalid())?;
+        let publish_at: DateTime<Utc> = Local
+            .from_local_datetime(&date.and_time(time))
+            .single()
+            .ok_or_else(invalid)?
+            .with_timezone(&Utc);
+
+        if publish_at < Utc::now() {
+            return Err(TelescopeError::BadRequest {
+                header: "Invalid Publish Time".into(),
+                message: "This time has already passed. To publish the meeting now, edit it \
+                and uncheck the draft box."
+                    .into(),
+                show_status_code: false,
+            });
+        }
+        Some(publish_at)
+    };
+
+    SchedulePublishing::execute(meeting_id, publish_at, announce).await?;
+    return Ok(HttpResponse::Found()
+        .header(LOCATION, format!("/meeting/{}", meeting_id))
+        .finish());
+}
@@ -1,26 +1,30 @@
 //! Services to support meeting edits.
 
-use crate::api::rcos::meetings::creation::create::normalize_url;
 use crate::api::rcos::meetings::edit::EditHostSelection;
-use crate::api::rcos::meetings::ALL_MEETING_TYPES;
+use crate::api::rcos::meetings::types::{MeetingKind, MeetingTypes};
 use crate::api::rcos::meetings::{
     authorization_for::{AuthorizationFor, UserMeetingAuthorization},
     creation::context::CreationContext,
     edit,
     get_by_id::{meeting::MeetingMeeting, Meeting},
 };
+use crate::api::rcos::prelude::MeetingId;
+use crate::discord_bot::{self, DiscordMessage};
 use crate::error::TelescopeError;
 use crate::templates::page::Page;
 use crate::templates::Template;
 use crate::web::services::auth::identity::AuthenticationCookie;
-use crate::web::services::meetings::create::{get_semester_bounds, FinishForm};
+use crate::web::services::meetings::create::{
+    get_semester_bounds, parse_date, validate_links, validate_small_group, FinishForm,
+};
+use crate::web::webhooks::{self, WebhookEvent};
 use actix_web::http::header::LOCATION;
 use actix_web::web::Form;
 use actix_web::{
     web::{Path, Query, ServiceConfig},
     HttpRequest, HttpResponse,
 };
-use chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 use serde_json::Value;
 use uuid::Uuid;
 
@@ -46,7 +50,7 @@ struct HostQuery {
 }
 
 /// Get meeting data or return a resource not found error.
-async fn get_meeting_data(meeting_id: i64) -> Result<MeetingMeeting, TelescopeError> {
+async fn get_meeting_data(meeting_id: MeetingId) -> Result<MeetingMeeting, TelescopeError> {
     // Get the meeting data to check that it exists.
     Meeting::get(meeting_id)
         .await?
@@ -70,17 +74,16 @@ async fn authorization_for_viewer(
 /// Get meeting data and error if the authenticated user cannot edit the meeting.
 async fn meeting_data_checked(
     auth: &AuthenticationCookie,
-    meeting_id: i64,
+    meeting_id: MeetingId,
 ) -> Result<MeetingMeeting, TelescopeError> {
-    // Get meeting data. Extract host's user ID.
+    // Get meeting data.
     let meeting_data = get_meeting_data(meeting_id).await?;
-    let meeting_host: Option<_> = meeting_data.host.as_ref().map(|host| host.id);
 
     // Get user's authorization object.
     let authorization = authorization_for_viewer(auth).await?;
 
     // Check edit access.
-    if !authorization.can_edit(meeting_host) {
+    if !authorization.can_edit(meeting_data.host_ids().as_slice()) {
         return Err(TelescopeError::Forbidden);
     } else {
         return Ok(meeting_data);
@@ -123,7 +126,7 @@ fn make_form() -> Template {
 #[get("/meeting/{meeting_id}/edit")]
 async fn edit_page(
     req: HttpRequest,
-    Path(meeting_id): Path<i64>,
+    Path(meeting_id): Path<MeetingId>,
     auth: AuthenticationCookie,
     set_host: Option<Query<HostQuery>>,
 ) -> Result<Page, TelescopeError> {
@@ -141,20 +144,42 @@ async fn edit_page(
     // Instantiate form with meeting data, context, and meeting types.
     form.fields = json!({
         "data": &meeting_data,
-        "meeting_types": ALL_MEETING_TYPES,
+        "meeting_types": MeetingTypes::options().await?,
         "context": context
     });
+    form.set_path(
+        &["data", "kind"],
+        MeetingKind::of(
+            meeting_data.type_,
+            meeting_data
+                .custom_type
+                .as_ref()
+                .map(|custom| custom.meeting_type_id),
+        ),
+    )?;
 
     // Add fields to the template converting the timestamps in the meeting data to the HTML versions.
     let meeting_start: &DateTime<Utc> = &meeting_data.start_date_time;
     let meeting_start_local: DateTime<Local> = meeting_start.with_timezone(&Local);
-    form.fields["data"]["start_date"] = json!(meeting_start_local.format("%Y-%m-%d").to_string());
-    form.fields["data"]["start_time"] = json!(meeting_start_local.format("%H:%M").to_string());
+    form.set_path(
+        &["data", "start_date"],
+        meeting_start_local.format("%Y-%m-%d").to_string(),
+    )?
+    .set_path(
+        &["data", "start_time"],
+        meeting_start_local.format("%H:%M").to_string(),
+    )?;
 
     let meeting_end: &DateTime<Utc> = &meeting_data.end_date_time;
     let meeting_end_local: DateTime<Local> = meeting_end.with_timezone(&Local);
-    form.fields["data"]["end_date"] = json!(meeting_end_local.format("%Y-%m-%d").to_string());
-    form.fields["data"]["end_time"] = json!(meeting_end_local.format("%H:%M").to_string());
+    form.set_path(
+        &["data", "end_date"],
+        meeting_end_local.format("%Y-%m-%d").to_string(),
+    )?
+    .set_path(
+        &["data", "end_time"],
+        meeting_end_local.format("%H:%M").to_string(),
+    )?;
 
     form.in_page(
         &req,
@@ -166,7 +191,7 @@ async fn edit_page(
 #[post("/meeting/{meeting_id}/edit")]
 async fn submit_meeting_edits(
     req: HttpRequest,
-    Path(meeting_id): Path<i64>,
+    Path(meeting_id): Path<MeetingId>,
     auth: AuthenticationCookie,
     set_host: Option<Query<HostQuery>>,
     // Use the same structure as is used for creation since the
@@ -186,7 +211,7 @@ async fn submit_meeting_edits(
     let mut form: Template = make_form();
     // Instantiate form with meeting types, context and data.
     form.fields = json!({
-        "meeting_types": ALL_MEETING_TYPES,
+        "meeting_types": MeetingTypes::options().await?,
         "context": &context,
         "data": &meeting_data
     });
@@ -207,43 +232,59 @@ async fn submit_meeting_edits(
         location,
         kind,
         title,
+        small_group,
+        announce_on_discord: _,
+        add_hosts: _,
+        idempotency_key: _,
+        repeat: _,
+        repeat_until: _,
     } = form_data;
 
     // Like the creation system, semester ID, meeting kind, and host ID are not validated.
 
     // Add submitted data to return form.
-    form["data"]["semester"] = json!({ "semester_id": &semester });
-    form["data"]["type"] = json!(kind);
-    form["data"]["description"] = json!(&description);
+    form.set_path(&["data", "semester"], json!({ "semester_id": &semester }))?
+        .set_path(&["data", "kind"], &kind)?
+        .set_path(&["data", "description"], &description)?;
 
-    form["data"]["start_date"] = json!(&start_date);
-    form["data"]["end_date"] = json!(&end_date);
-    form["data"]["start_time"] = json!(&start_time);
-    form["data"]["end_time"] = json!(&end_time);
+    form.set_path(&["data", "start_date"], &start_date)?
+        .set_path(&["data", "end_date"], &end_date)?
+        .set_path(&["data", "start_time"], &start_time)?
+        .set_path(&["data", "end_time"], &end_time)?;
 
     // Handle meeting title -- just whitespace and default to None if empty.
     let title: Option<String> = (!title.trim().is_empty()).then(|| title.trim().to_string());
-    form["data"]["title"] = json!(&title);
+    form.set_path(&["data", "title"], &title)?;
 
     // Same with location.
     let location: Option<String> =
         location.and_then(|string| (!string.trim().is_empty()).then(|| string.trim().to_string()));
-    form["data"]["location"] = json!(&location);
+    form.set_path(&["data", "location"], &location)?;
 
     // Trim description.
     let description: String = description.trim().to_string();
-    form["data"]["description"] = json!(&description);
-
-    // Don't bother trimming URLs, since the GraphQL mutation will normalize them.
-    form["data"]["meeting_url"] = json!(&meeting_url);
-    form["data"]["recording_url"] = json!(&recording_url);
-    form["data"]["external_presentation_url"] = json!(&external_slides_url);
+    form.set_path(&["data", "description"], &description)?;
+
+    // Check the small group is one of the selected semester's.
+    let small_group_id: Option<i64> =
+        validate_small_group(&mut form, small_group.as_deref(), semester.as_str());
+    form.set_path(
+        &["data", "small_group"],
+        json!({ "small_group_id": small_group_id }),
+    )?;
+
+    // Validate and normalize the links.
+    let (meeting_url, recording_url, external_slides_url) =
+        validate_links(&mut form, meeting_url, recording_url, external_slides_url);
+    form.set_path(&["data", "meeting_url"], &meeting_url)?
+        .set_path(&["data", "recording_url"], &recording_url)?
+        .set_path(&["data", "external_presentation_url"], &external_slides_url)?;
 
     // Handle flags.
     let is_remote: bool = is_remote.unwrap_or(false);
     let is_draft: bool = is_draft.unwrap_or(false);
-    form["data"]["is_remote"] = json!(is_remote);
-    form["data"]["is_draft"] = json!(is_draft);
+    form.set_path(&["data", "is_remote"], is_remote)?
+        .set_path(&["data", "is_draft"], is_draft)?;
 
     // Validate dates and set an issue in the form if there is one.
     // Get the selected semester info from the context object.
@@ -261,16 +302,20 @@ async fn submit_meeting_edits(
     // Get the semester bounds.
     let (semester_start, semester_end) = get_semester_bounds(selected_semester);
 
-    if end_date < start_date {
-        form["issues"]["end_date"] = json!("End date is before start date.");
-    } else if start_date > semester_end {
-        form["issues"]["start_date"] = json!("Start date is after end of semester.");
-    } else if end_date > semester_end {
-        form["issues"]["end_date"] = json!("End date is after end of semester.");
-    } else if start_date < semester_start {
-        form["issues"]["start_date"] = json!("Start date is before semester starts.");
-    } else if end_date < semester_start {
-        form["issues"]["end_date"] = json!("End date is before semester starts.");
+    let start_date: Option<NaiveDate> = parse_date(&mut form, "start_date", start_date.as_str());
+    let end_date: Option<NaiveDate> = parse_date(&mut form, "end_date", end_date.as_str());
+    if let Some((start_date, end_date)) = start_date.zip(end_date) {
+        if end_date < start_date {
+            form.set_issue("end_date", "End date is before start date.");
+        } else if start_date > semester_end {
+            form.set_issue("start_date", "Start date is after end of semester.");
+        } else if end_date > semester_end {
+            form.set_issue("end_date", "End date is after end of semester.");
+        } else if start_date < semester_start {
+            form.set_issue("start_date", "Start date is before semester starts.");
+        } else if end_date < semester_start {
+            form.set_issue("end_date", "End date is before semester starts.");
+        }
     }
 
     // Parse times
@@ -288,26 +333,34 @@ async fn submit_meeting_edits(
         show_status_code: false,
     })?;
 
-    // Add times to dates.
-    let start: NaiveDateTime = start_date.and_time(start_time);
-    let end: NaiveDateTime = end_date.and_time(end_time);
+    // Add times to dates, if both dates could be read.
+    let times: Option<(NaiveDateTime, NaiveDateTime)> =
+        start_date.zip(end_date).map(|(start_date, end_date)| {
+            (start_date.and_time(start_time), end_date.and_time(end_time))
+        });
 
     // Make sure meeting starts before it ends.
-    if start > end {
-        form["issues"]["end_time"] = json!("End time is before start time.");
+    if let Some((start, end)) = times {
+        if start > end {
+            form.set_issue("end_time", "End time is before start time.");
+        }
     }
 
-    // If there was an issue, return the form as invalid.
-    if form["issues"] != json!(null) {
-        // Render page.
-        let page = form
-            .in_page(
-                &req,
-                format!("Edit {}", resolve_meeting_title(&meeting_data)),
-            )
-            .await?;
-        return Err(TelescopeError::InvalidForm(page));
-    }
+    // If there was an issue, return the form as invalid. Dates that could not
+    // be read are among them.
+    let (start, end): (NaiveDateTime, NaiveDateTime) = match times {
+        Some(times) if form["issues"] == json!(null) => times,
+        _ => {
+            // Render page.
+            let page = form
+                .in_page(
+                    &req,
+                    format!("Edit {}", resolve_meeting_title(&meeting_data)),
+                )
+                .await?;
+            return Err(TelescopeError::InvalidForm(page));
+        }
+    };
 
     // Add timestamps.
     let timezone_adder = |timestamp: &NaiveDateTime| Local.from_local_datetime(timestamp).single();
@@ -324,21 +377,34 @@ async fn submit_meeting_edits(
         show_status_code: false,
     })?;
 
+    // Slides added in this edit get posted in the meeting's Discord threads.
+    let has_slides: bool = external_slides_url.is_some();
+    // Publishing a draft is the same as creating a meeting for webhooks.
+    let published: Option<WebhookEvent> =
+        (meeting_data.is_draft && !is_draft).then(|| WebhookEvent::MeetingPublished {
+            meeting_id,
+            title: title.clone(),
+            start: start.with_timezone(&Utc),
+            end: end.with_timezone(&Utc),
+        });
+
     // Create variables for mutation.
     let edit_mutation_variables = edit::edit_meeting::Variables {
-        meeting_id,
+        meeting_id: meeting_id.into(),
         title,
         start: start.with_timezone(&Utc),
         end: end.with_timezone(&Utc),
         semester_id: semester,
-        kind,
+        kind: kind.meeting_type(),
+        custom_type_id: kind.custom_type_id(),
+        small_group_id,
         description,
         is_remote,
         is_draft,
-        meeting_url: normalize_url(meeting_url),
+        meeting_url,
         location,
-        external_slides_url: normalize_url(external_slides_url),
-        recording_url: normalize_url(recording_url),
+        external_slides_url,
+        recording_url,
         // Extract the host from context object.
         host: form["context"]["host"][0]["id"]
             .as_str()
@@ -346,10 +412,23 @@ async fn submit_meeting_edits(
     };
 
     // The returned meeting ID should match the existing one but we don't check.
-    let meeting_id: i64 = edit::EditMeeting::execute(edit_mutation_variables)
+    let meeting_id: MeetingId = edit::EditMeeting::execute(edit_mutation_variables)
         .await?
         .unwrap_or(meeting_id);
 
+    if let Some(event) = published {
+        webhooks::emit(event).await;
+    }
+
+    if has_slides {
+        if let Err(e) = discord_bot::send(DiscordMessage::MeetingChanged { meeting_id }) {
+            warn!(
+                "Could not update Discord threads of meeting {}: {}",
+                meeting_id, e
+            );
+        }
+    }
+
     // Redirect the user back to the meeting they edited.
     return Ok(HttpResponse::Found()
         .header(LOCATION, format!("/meeting/{}", meeting_id))
@@ -359,7 +438,7 @@ async fn submit_meeting_edits(
 /// Host selection page.
 #[get("/meeting/{meeting_id}/edit/select_host")]
 async fn host_selection(
-    Path(meeting_id): Path<i64>,
+    Path(meeting_id): Path<MeetingId>,
     auth: AuthenticationCookie,
     req: HttpRequest,
 ) -> Result<Page, TelescopeError> {
@@ -378,6 +457,6 @@ async fn host_selection(
 
     // Create host selection page template.
     let mut template: Template = Template::new(HOST_SELECTION_TEMPLATE);
-    template["data"] = json!(data);
+    template.set_field("data", data)?;
     return template.in_page(&req, "Select Host").await;
 }
@@ -0,0 +1,157 @@
+//! Page for managing the co-hosts and speakers of a meeting.
+//!
+//! The primary host is still picked on the meeting's creation and edit
+//! forms. Co-hosts and guest speakers added here can edit the meeting, see
+//! its attendance, and moderate its questions, the same as the primary host.
+//! Speakers without Telescope accounts are added on this page too; see
+//! [`speakers`].
+//!
+//! [`speakers`]: super::speakers
+
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::api::rcos::meetings::creation::host_selection::HostSelection;
+use crate::api::rcos::meetings::get_by_id::{meeting::MeetingMeeting, Meeting};
+use crate::api::rcos::meetings::hosts::{AddMeetingHost, HostRole, RemoveMeetingHost};
+use crate::api::rcos::meetings::speakers::ExternalSpeakers;
+use crate::api::rcos::prelude::MeetingId;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, Query, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use uuid::Uuid;
+
+/// The path to the hosts page template from the templates directory.
+const TEMPLATE_PATH: &'static str = "meetings/hosts";
+
+/// Register meeting host services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(hosts_page)
+        .service(add_host)
+        .service(remove_host);
+}
+
+/// Search for people to add.
+#[derive(Deserialize, Debug)]
+struct SearchQuery {
+    search: String,
+}
+
+/// Form submitted to add someone to a meeting's hosts.
+#[derive(Deserialize, Debug)]
+struct AddForm {
+    user_id: Uuid,
+    role: HostRole,
+}
+
+/// Form submitted to remove someone from a meeting's hosts.
+#[derive(Deserialize, Debug)]
+struct RemoveForm {
+    user_id: Uuid,
+}
+
+/// Get a meeting and make sure the viewer can edit it.
+pub(super) async fn authorize(
+    auth: &AuthenticationCookie,
+    meeting_id: MeetingId,
+) -> Result<MeetingMeeting, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    let meeting: MeetingMeeting = Meeting::get(meeting_id).await?.ok_or_else(|| {
+        TelescopeError::resource_not_found(
+            "Meeting Not Found",
+            "Could not find a meeting for this ID.",
+        )
+    })?;
+
+    if !AuthorizationFor::get(Some(viewer))
+        .await?
+        .can_edit(meeting.host_ids().as_slice())
+    {
+        return Err(TelescopeError::Forbidden);
+    }
+    return Ok(meeting);
+}
+
+/// Redirect to the hosts page of a meeting.
+pub(super) fn to_hosts(meeting_id: MeetingId) -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, format!("/meeting/{}/hosts", meeting_id))
+        .finish()
+}
+
+/// List a meeting's hosts, with a search to add more.
+#[get("/meeting/{meeting_id}/hosts")]
+async fn hosts_page(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Path(meeting_id): Path<MeetingId>,
+    query: Option<Query<SearchQuery>>,
+) -> Result<Page, TelescopeError> {
+    let meeting: MeetingMeeting = authorize(&auth, meeting_id).await?;
+
+    let roles: Vec<_> = HostRole::ALL
+        .iter()
+        .map(|role| json!({"name": role.name(), "label": role.to_string()}))
+        .collect();
+
+    let mut template = Template::new(TEMPLATE_PATH);
+    template
+        .set_field("meeting", &meeting)?
+        .set_field("title", meeting.title())?
+        .set_field("roles", roles)?;
+    // Past speakers, to add again without entering their details.
+    template.set_field("past_speakers", ExternalSpeakers::get().await?)?;
+
+    // Search the same users that can be picked as the primary host.
+    let search: Option<String> = query
+        .map(|q| q.search.trim().to_string())
+        .filter(|search| !search.is_empty());
+    if let Some(search) = search {
+        let results = HostSelection::get(Some(search.clone())).await?;
+        template
+            .set_field("search", search)?
+            .set_field("results", &results.users)?;
+    }
+
+    return template
+        .in_page(&req, format!("Hosts - {}", meeting.title()))
+        .await;
+}
+
+/// Add someone to a meeting's hosts, or change their role.
+#[post("/meeting/{meeting_id}/hosts/add")]
+async fn add_host(
+    auth: AuthenticationCookie,
+    Path(meeting_id): Path<MeetingId>,
+    Form(form): Form<AddForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let meeting: MeetingMeeting = authorize(&auth, meeting_id).await?;
+
+    // The primary host is changed on the edit form instead.
+    if meeting.host.as_ref().map(|host| host.id) == Some(form.user_id) {
+        return Err(TelescopeError::BadRequest {
+            header: "Already Hosting".into(),
+            message: "This person is already the meeting's primary host.".into(),
+            show_status_code: false,
+        });
+    }
+
+    AddMeetingHost::execute(meeting_id, form.user_id, form.role).await?;
+    return Ok(to_hosts(meeting_id));
+}
+
+/// Remove someone from a meeting's hosts. Uses post to prevent inadvertent
+/// removal.
+#[post("/meeting/{meeting_id}/hosts/remove")]
+async fn remove_host(
+    auth: AuthenticationCookie,
+    Path(meeting_id): Path<MeetingId>,
+    Form(form): Form<RemoveForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    authorize(&auth, meeting_id).await?;
+    RemoveMeetingHost::execute(meeting_id, form.user_id).await?;
+    return Ok(to_hosts(meeting_id));
+}
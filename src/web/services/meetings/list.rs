@@ -2,13 +2,16 @@
 
 use crate::api::rcos::meetings::authorization_for::{AuthorizationFor, UserMeetingAuthorization};
 use crate::api::rcos::meetings::get::Meetings;
+use crate::api::rcos::meetings::small_groups::ViewerSmallGroups;
+use crate::api::rcos::meetings::types::{MeetingKind, MeetingTypeOption, MeetingTypes};
 use crate::api::rcos::meetings::MeetingType;
 use crate::error::TelescopeError;
 use crate::templates::page::Page;
 use crate::templates::Template;
 use crate::web::services::auth::identity::Identity;
+use crate::web::services::user::saved_searches::{self, SearchPage};
 use actix_web::web::{Query, ServiceConfig};
-use actix_web::HttpRequest;
+use actix_web::{HttpRequest, HttpResponse, Responder};
 use chrono::{Date, DateTime, Duration, Local, NaiveDate, TimeZone, Utc};
 
 /// Register the meetings page.
@@ -20,12 +23,27 @@ pub fn register(c: &mut ServiceConfig) -> &mut ServiceConfig {
 const TEMPLATE_PATH: &'static str = "meetings/list";
 
 /// Query parameters submitted via the form on the meetings page.
-#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct MeetingsQuery {
     /// The start time to get events from.
     pub start: NaiveDate,
     /// The end time to get events from.
     pub end: NaiveDate,
+    /// Only show meetings of this type. Empty for all types.
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// Show meetings for every small group, not just the viewer's own.
+    #[serde(default)]
+    pub all_groups: Option<bool>,
+}
+
+/// A meeting on the meetings page, marked if it is for one of the viewer's
+/// small groups.
+#[derive(Serialize)]
+struct MeetingView<'a, T> {
+    #[serde(flatten)]
+    meeting: &'a T,
+    for_my_group: bool,
 }
 
 /// Meetings page
@@ -34,7 +52,16 @@ async fn meetings_list(
     req: HttpRequest,
     params: Option<Query<MeetingsQuery>>,
     identity: Identity,
-) -> Result<Page, TelescopeError> {
+) -> Result<HttpResponse, TelescopeError> {
+    // Is there an RCOS user authenticated?
+    let viewer: Option<_> = identity.get_user_id().await?;
+
+    // Open the viewer's default filters if they have some and didn't pick any.
+    let saved = saved_searches::get(viewer, SearchPage::Meetings).await?;
+    if let Some(redirect) = saved_searches::default_redirect(&req, SearchPage::Meetings, &saved) {
+        return Ok(redirect);
+    }
+
     // Resolve parameters to API query variables
     let start: DateTime<Utc> = params
         .as_ref()
@@ -69,15 +96,50 @@ async fn meetings_list(
         // Convert timezone to UTC.
         .with_timezone(&Utc);
 
-    // Is there an RCOS user authenticated?
-    let viewer: Option<_> = identity.get_user_id().await?;
     // Check if that user can view drafts / certain meeting types.
     let authorization: UserMeetingAuthorization = AuthorizationFor::get(viewer).await?;
     let include_drafts: bool = authorization.can_view_drafts();
     let visible_meeting_types: Vec<MeetingType> = authorization.viewable_types();
 
     // Query the RCOS API to get meeting data.
-    let events: Vec<_> = Meetings::get(start, end, include_drafts, visible_meeting_types).await?;
+    let mut events: Vec<_> =
+        Meetings::get(start, end, include_drafts, visible_meeting_types).await?;
+
+    // Filter to the selected type.
+    let kind: Option<MeetingKind> = params
+        .as_ref()
+        .and_then(|p| p.kind.as_deref())
+        .and_then(MeetingKind::parse);
+    if let Some(kind) = kind {
+        events.retain(|meeting| {
+            let custom_type_id = meeting.custom_type.as_ref().map(|c| c.meeting_type_id);
+            MeetingKind::of(meeting.type_, custom_type_id) == kind
+        });
+    }
+
+    // Meetings for one small group are left out for viewers outside it, unless
+    // they asked for every group. Coordinators see them all.
+    let my_groups: Vec<i64> = ViewerSmallGroups::get(viewer).await?;
+    let all_groups: bool = params.as_ref().and_then(|p| p.all_groups).unwrap_or(false);
+    if !all_groups && !include_drafts {
+        events.retain(|meeting| meeting.listed_for(my_groups.as_slice()));
+    }
+
+    // Mark the meetings for the viewer's own small groups.
+    let meetings: Vec<MeetingView<_>> = events
+        .iter()
+        .map(|meeting| MeetingView {
+            meeting,
+            for_my_group: meeting.small_group.is_some() && meeting.listed_for(my_groups.as_slice()),
+        })
+        .collect();
+
+    // The types to filter by are the ones this viewer can see.
+    let meeting_types: Vec<MeetingTypeOption> = MeetingTypes::options()
+        .await?
+        .into_iter()
+        .filter(|option| authorization.can_view(option.kind.meeting_type()))
+        .collect();
 
     // Get the values to pre-fill in the filters.
     let query = params
@@ -87,14 +149,19 @@ async fn meetings_list(
         .unwrap_or(MeetingsQuery {
             start: start.naive_local().date(),
             end: end.naive_local().date(),
+            kind: None,
+            all_groups: None,
         });
 
     let mut template = Template::new(TEMPLATE_PATH);
     template.fields = json!({
-        "meetings": events,
+        "meetings": meetings,
         "query": query,
+        "meeting_types": meeting_types,
         "authorization": authorization,
+        "saved_searches": saved_searches::template_fields(&req, SearchPage::Meetings, &saved),
     });
 
-    return template.in_page(&req, "RCOS Meetings").await;
+    let page: Page = template.in_page(&req, "RCOS Meetings").await?;
+    return page.respond_to(&req).await;
 }
@@ -6,10 +6,19 @@ use crate::web::middlewares::authorization::Authorization;
 use actix_web::web::ServiceConfig;
 use uuid::Uuid;
 
+mod attend;
+mod attendance;
+mod calendar;
 mod create;
 mod delete;
+mod drafts;
 mod edit;
+mod hosts;
 mod list;
+mod questions;
+mod recordings;
+mod speakers;
+mod types;
 mod view;
 
 /// Register calendar related services.
@@ -26,6 +35,33 @@ pub fn register(config: &mut ServiceConfig) {
     // Meeting destruction services.
     delete::register(config);
 
+    // Co-hosts and guest speakers.
+    hosts::register(config);
+
+    // Speakers without Telescope accounts.
+    speakers::register(config);
+
+    // Upcoming drafts and scheduled publishing.
+    drafts::register(config);
+
+    // Check-in services.
+    attend::register(config);
+
+    // Attendance list and proxy attendance.
+    attendance::register(config);
+
+    // Semester calendar and its export.
+    calendar::register(config);
+
+    // Live question queue.
+    questions::register(config);
+
+    // Recordings posted by other services.
+    recordings::register(config);
+
+    // Custom meeting type management.
+    types::register(config);
+
     config
         // The meeting viewing endpoint must be registered after the meeting creation endpoint,
         // so that the ID path doesn't match the create path.
@@ -0,0 +1,249 @@
+//! Live question queue for large group meetings.
+//!
+//! Members ask and upvote questions while the meeting is running, and the
+//! host (or anyone else who can edit the meeting) marks them answered. Every
+//! change is published on the meeting's live topic so open pages refresh the
+//! queue without reloading. Each meeting has its own queue.
+
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::api::rcos::meetings::questions::{
+    AskQuestion, MeetingQuestions, SetQuestionAnswered, UpvoteQuestion,
+};
+use crate::api::rcos::meetings::MeetingType;
+use crate::api::rcos::prelude::MeetingId;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::live;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+/// The path to the question queue page from the templates directory.
+const PAGE_TEMPLATE: &'static str = "meetings/questions/page";
+
+/// The path to the question list (refreshed live) from the templates directory.
+const LIST_TEMPLATE: &'static str = "meetings/questions/list";
+
+/// The longest question that can be asked.
+const MAX_QUESTION_LENGTH: usize = 500;
+
+/// How long after a meeting ends questions can still be asked.
+const GRACE_PERIOD_MINUTES: i64 = 15;
+
+/// The event published when a meeting's questions change.
+const QUESTIONS_CHANGED: &'static str = "questions";
+
+/// Register question queue services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(page)
+        .service(list)
+        .service(ask)
+        .service(vote)
+        .service(mark_answered);
+}
+
+/// The live topic for a meeting's questions.
+fn topic(meeting_id: MeetingId) -> String {
+    format!("meeting/{}/questions", meeting_id)
+}
+
+/// Redirect back to the question queue.
+fn to_queue(meeting_id: MeetingId) -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, format!("/meeting/{}/questions", meeting_id))
+        .finish()
+}
+
+/// Form to ask a question.
+#[derive(Deserialize, Debug)]
+struct QuestionForm {
+    body: String,
+}
+
+/// Form to upvote a question or take the vote back.
+#[derive(Deserialize, Debug)]
+struct VoteForm {
+    vote: bool,
+}
+
+/// Form to mark a question answered or reopen it.
+#[derive(Deserialize, Debug)]
+struct AnsweredForm {
+    answered: bool,
+}
+
+/// Get the question queue of a meeting for a viewer as a template with the
+/// given path. Errors if the viewer cannot see the queue.
+async fn load(
+    path: &'static str,
+    meeting_id: MeetingId,
+    viewer: Uuid,
+) -> Result<Template, TelescopeError> {
+    let meeting = MeetingQuestions::get(meeting_id, viewer)
+        .await?
+        .meeting
+        .ok_or_else(|| {
+            TelescopeError::resource_not_found(
+                "Meeting Not Found",
+                "Could not find a meeting for this ID.",
+            )
+        })?;
+
+    let authorization = AuthorizationFor::get(Some(viewer)).await?;
+    let hosts: Vec<Uuid> = meeting
+        .host
+        .iter()
+        .map(|host| host.id)
+        .chain(meeting.hosts.iter().map(|host| host.user_id))
+        .chain(
+            meeting
+                .small_group
+                .iter()
+                .flat_map(|group| group.small_group_mentors.iter())
+                .map(|mentor| mentor.user_id),
+        )
+        .collect();
+    let can_moderate: bool = authorization.can_edit(hosts.as_slice());
+
+    if meeting.is_draft && !can_moderate && !authorization.can_view_drafts() {
+        return Err(TelescopeError::resource_not_found(
+            "Meeting Not Found",
+            "Could not find a meeting for this ID.",
+        ));
+    }
+
+    if meeting.type_ != MeetingType::LargeGroup {
+        return Err(TelescopeError::BadRequest {
+            header: "No Questions".into(),
+            message: "Questions can only be asked during large group meetings.".into(),
+            show_status_code: false,
+        });
+    }
+
+    let is_open: bool =
+        Utc::now() <= meeting.end_date_time + Duration::minutes(GRACE_PERIOD_MINUTES);
+
+    let mut template = Template::new(path);
+    template
+        .set_field("meeting", meeting)?
+        .set_field("viewer", viewer)?
+        .set_field("can_moderate", can_moderate)?
+        .set_field("is_open", is_open)?
+        .set_field("topic", topic(meeting_id))?
+        .set_field("max_length", MAX_QUESTION_LENGTH)?;
+    return Ok(template);
+}
+
+/// The question queue page.
+#[get("/meeting/{meeting_id}/questions")]
+async fn page(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Path(meeting_id): Path<MeetingId>,
+) -> Result<Page, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    let template = load(PAGE_TEMPLATE, meeting_id, viewer).await?;
+    let title = format!(
+        "Questions - {}",
+        template["meeting"]["title"].as_str().unwrap_or("Meeting")
+    );
+    return template.in_page(&req, title).await;
+}
+
+/// Just the question lists, for pages to refresh when the queue changes.
+#[get("/meeting/{meeting_id}/questions/list")]
+async fn list(
+    auth: AuthenticationCookie,
+    Path(meeting_id): Path<MeetingId>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    let rendered: String = load(LIST_TEMPLATE, meeting_id, viewer).await?.render()?;
+    return Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(rendered));
+}
+
+/// Ask a question.
+#[post("/meeting/{meeting_id}/questions")]
+async fn ask(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Path(meeting_id): Path<MeetingId>,
+    Form(form): Form<QuestionForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    let mut template = load(PAGE_TEMPLATE, meeting_id, viewer).await?;
+
+    if template["is_open"] != json!(true) {
+        return Err(TelescopeError::BadRequest {
+            header: "Questions Closed".into(),
+            message: "This meeting has ended and is no longer taking questions.".into(),
+            show_status_code: false,
+        });
+    }
+
+    let body: String = form.body.trim().to_string();
+    template.set_field("body", &body)?;
+    if body.is_empty() {
+        template.set_issue("body", "Cannot be empty.");
+    } else if body.chars().count() > MAX_QUESTION_LENGTH {
+        template.set_issue(
+            "body",
+            format!("Cannot be longer than {} characters.", MAX_QUESTION_LENGTH),
+        );
+    }
+
+    if template["issues"] != json!(null) {
+        let page = template.in_page(&req, "Questions").await?;
+        return Err(TelescopeError::InvalidForm(page));
+    }
+
+    AskQuestion::execute(meeting_id, viewer, body).await?;
+    live::publish(topic(meeting_id), QUESTIONS_CHANGED);
+    return Ok(to_queue(meeting_id));
+}
+
+/// Upvote a question or take the vote back.
+#[post("/meeting/{meeting_id}/questions/{question_id}/vote")]
+async fn vote(
+    auth: AuthenticationCookie,
+    Path((meeting_id, question_id)): Path<(MeetingId, i64)>,
+    Form(form): Form<VoteForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    // Make sure the viewer can see this meeting's queue.
+    load(LIST_TEMPLATE, meeting_id, viewer).await?;
+
+    UpvoteQuestion::execute(question_id, viewer, form.vote).await?;
+    live::publish(topic(meeting_id), QUESTIONS_CHANGED);
+    return Ok(to_queue(meeting_id));
+}
+
+/// Mark a question answered or reopen it. Only the meeting's moderators can do this.
+#[post("/meeting/{meeting_id}/questions/{question_id}/answered")]
+async fn mark_answered(
+    auth: AuthenticationCookie,
+    Path((meeting_id, question_id)): Path<(MeetingId, i64)>,
+    Form(form): Form<AnsweredForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    let template = load(LIST_TEMPLATE, meeting_id, viewer).await?;
+    if template["can_moderate"] != json!(true) {
+        return Err(TelescopeError::Forbidden);
+    }
+
+    if !SetQuestionAnswered::execute(meeting_id, question_id, form.answered).await? {
+        return Err(TelescopeError::resource_not_found(
+            "Question Not Found",
+            "Could not find this question on this meeting.",
+        ));
+    }
+
+    live::publish(topic(meeting_id), QUESTIONS_CHANGED);
+    return Ok(to_queue(meeting_id));
+}
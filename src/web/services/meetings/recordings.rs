@@ -0,0 +1,82 @@
+//! Webhook for other services to post new meeting recordings to.
+//!
+//! Recordings are matched to meetings the same way as the ones picked up from
+//! YouTube. Requests need the configured webhook secret as a bearer token.
+//! The webhook is off if no secret is configured.
+
+use crate::api::rcos::meetings::recordings::{self, Outcome, Recording, RecordingSource};
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use crate::web::tokens_match;
+use crate::web::validation::validate_url;
+use actix_web::http::header::AUTHORIZATION;
+use actix_web::web::{Json, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+
+/// Register the recording webhook.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(webhook);
+}
+
+/// A recording posted to the webhook.
+#[derive(Deserialize, Debug)]
+struct RecordingPost {
+    url: String,
+    title: String,
+    /// When the recording was published. Defaults to now.
+    #[serde(default)]
+    published_at: Option<DateTime<Utc>>,
+}
+
+/// Match a posted recording to its meeting. Responds with what happened to it.
+#[post("/api/recordings")]
+async fn webhook(
+    req: HttpRequest,
+    Json(post): Json<RecordingPost>,
+) -> Result<HttpResponse, TelescopeError> {
+    let config = global_config();
+    let secret: &str = match config
+        .recording_ingest
+        .as_ref()
+        .and_then(|ingest| ingest.webhook_secret.as_deref())
+    {
+        Some(secret) => secret,
+        None => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let authorized: bool = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map_or(false, |token| tokens_match(token, secret));
+    if !authorized {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    // Hold posted links to the same rules as the meeting forms.
+    let url: String = match validate_url(Some(post.url.as_str()), &config.recording_domains) {
+        Ok(Some(url)) => url,
+        Ok(None) => {
+            return Ok(HttpResponse::BadRequest().json(json!({ "error": "A URL is required." })))
+        }
+        Err(issue) => return Ok(HttpResponse::BadRequest().json(json!({ "error": issue }))),
+    };
+
+    let outcome: Outcome = recordings::ingest(Recording {
+        url,
+        title: post.title,
+        published_at: post.published_at.unwrap_or_else(Utc::now),
+        source: RecordingSource::Webhook,
+    })
+    .await?;
+
+    let body = match outcome {
+        Outcome::Known => json!({ "status": "known" }),
+        Outcome::Attached(meeting_id) => json!({ "status": "attached", "meeting_id": meeting_id }),
+        Outcome::Queued => json!({ "status": "queued" }),
+        Outcome::Unmatched => json!({ "status": "unmatched" }),
+    };
+    return Ok(HttpResponse::Ok().json(body));
+}
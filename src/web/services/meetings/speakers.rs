@@ -0,0 +1,133 @@
+//! Services to add and remove the external speakers of a meeting. Speakers
+//! are managed on the same page as co-hosts.
+
+use crate::api::rcos::meetings::speakers::{
+    AddMeetingSpeaker, CreateExternalSpeaker, RemoveMeetingSpeaker, SpeakerFields,
+};
+use crate::api::rcos::prelude::MeetingId;
+use crate::error::TelescopeError;
+use crate::web::images::{self, Variant};
+use crate::web::services::auth::identity::AuthenticationCookie;
+use crate::web::services::meetings::hosts::{authorize, to_hosts};
+use crate::web::uploads::{self, MultipartForm, UploadKind};
+use actix_multipart::Multipart;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::HttpResponse;
+
+/// The longest a speaker's name or affiliation can be.
+const MAX_NAME_LENGTH: usize = 100;
+
+/// The longest a speaker's bio can be.
+const MAX_BIO_LENGTH: usize = 2000;
+
+/// Register external speaker services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(create_speaker)
+        .service(add_speaker)
+        .service(remove_speaker);
+}
+
+/// Form submitted to add or remove an existing speaker.
+#[derive(Deserialize, Debug)]
+struct SpeakerForm {
+    speaker_id: i64,
+}
+
+/// Make the error for a speaker form with a problem.
+fn invalid(message: String) -> TelescopeError {
+    TelescopeError::BadRequest {
+        header: "Invalid Speaker".into(),
+        message,
+        show_status_code: false,
+    }
+}
+
+/// Get a trimmed text field of the speaker form, or none if it is empty.
+/// Errors if it is longer than `max` characters.
+fn text_field(
+    form: &MultipartForm,
+    name: &str,
+    label: &str,
+    max: usize,
+) -> Result<Option<String>, TelescopeError> {
+    let value: Option<String> = form
+        .field(name)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string);
+
+    if value
+        .as_ref()
+        .map_or(false, |value| value.chars().count() > max)
+    {
+        return Err(invalid(format!(
+            "{} can be at most {} characters long.",
+            label, max
+        )));
+    }
+    return Ok(value);
+}
+
+/// Create an external speaker, with an optional photo, and add them to a
+/// meeting.
+#[post("/meeting/{meeting_id}/speakers/create")]
+async fn create_speaker(
+    auth: AuthenticationCookie,
+    Path(meeting_id): Path<MeetingId>,
+    payload: Multipart,
+) -> Result<HttpResponse, TelescopeError> {
+    authorize(&auth, meeting_id).await?;
+    let viewer = auth.get_user_id_or_error().await?;
+
+    let mut form: MultipartForm = uploads::read(payload).await?;
+    let name: String = text_field(&form, "name", "Names", MAX_NAME_LENGTH)?
+        .ok_or_else(|| invalid("Speakers need a name.".into()))?;
+    let affiliation = text_field(&form, "affiliation", "Affiliations", MAX_NAME_LENGTH)?;
+    let bio = text_field(&form, "bio", "Bios", MAX_BIO_LENGTH)?;
+
+    let photo_url: Option<String> = match form.take_file("photo") {
+        Some(file) => {
+            let upload = uploads::check(UploadKind::Image, file, Some(viewer)).await?;
+            let image_id = images::store(upload).await?;
+            Some(images::url(image_id, Variant::Medium))
+        }
+        None => None,
+    };
+
+    let fields = SpeakerFields {
+        name,
+        affiliation,
+        bio,
+        photo_url,
+    };
+    CreateExternalSpeaker::execute(meeting_id, fields, viewer)
+        .await?
+        .ok_or(TelescopeError::ise("Speaker was not created."))?;
+    return Ok(to_hosts(meeting_id));
+}
+
+/// Add an existing external speaker to a meeting.
+#[post("/meeting/{meeting_id}/speakers/add")]
+async fn add_speaker(
+    auth: AuthenticationCookie,
+    Path(meeting_id): Path<MeetingId>,
+    Form(form): Form<SpeakerForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    authorize(&auth, meeting_id).await?;
+    AddMeetingSpeaker::execute(meeting_id, form.speaker_id).await?;
+    return Ok(to_hosts(meeting_id));
+}
+
+/// Remove an external speaker from a meeting. Uses post to prevent
+/// inadvertent removal.
+#[post("/meeting/{meeting_id}/speakers/remove")]
+async fn remove_speaker(
+    auth: AuthenticationCookie,
+    Path(meeting_id): Path<MeetingId>,
+    Form(form): Form<SpeakerForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    authorize(&auth, meeting_id).await?;
+    RemoveMeetingSpeaker::execute(meeting_id, form.speaker_id).await?;
+    return Ok(to_hosts(meeting_id));
+}
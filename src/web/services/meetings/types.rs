@@ -0,0 +1,233 @@
+//! Services for coordinators to manage custom meeting types.
+
+use crate::api::rcos::meetings::authorization_for::UserMeetingAuthorization;
+use crate::api::rcos::meetings::types::{CreateMeetingType, EditMeetingType, MeetingTypes};
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::meetings::make_meeting_auth_middleware;
+use actix_web::http::header::LOCATION;
+use actix_web::web as aweb;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+
+/// The path to the form template from the templates directory.
+const FORM_TEMPLATE: &'static str = "meetings/types/form";
+
+/// The longest name a meeting type can have.
+const MAX_NAME_LENGTH: usize = 50;
+
+/// The longest default duration of a meeting type, in minutes.
+const MAX_DURATION_MINUTES: i64 = 12 * 60;
+
+/// Register meeting type services.
+pub fn register(config: &mut ServiceConfig) {
+    let authorization =
+        make_meeting_auth_middleware(&UserMeetingAuthorization::can_create_meetings);
+
+    config.service(
+        aweb::scope("/meeting_types")
+            .wrap(authorization)
+            .service(index)
+            .service(new_type)
+            .service(submit_new_type)
+            .service(edit_type)
+            .service(submit_edit_type),
+    );
+}
+
+/// Form submitted to save a meeting type.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct MeetingTypeForm {
+    name: String,
+    color: String,
+    default_duration_minutes: String,
+    counts_for_attendance: Option<bool>,
+}
+
+/// A validated meeting type.
+struct MeetingTypeFields {
+    name: String,
+    color: String,
+    default_duration_minutes: i64,
+    counts_for_attendance: bool,
+}
+
+/// Make the meeting type form. If `editing` is some, the form edits the
+/// meeting type with that ID.
+fn make_form(editing: Option<i64>, selections: &MeetingTypeForm) -> Template {
+    let mut form = Template::new(FORM_TEMPLATE);
+    form.fields = json!({
+        "editing": editing,
+        "selections": selections,
+    });
+    return form;
+}
+
+/// Check if a color is a hex color like `#1a2b3c`. Colors are put in inline
+/// styles, so nothing else is allowed.
+fn is_hex_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Validate a submitted form. On success, return the meeting type to save.
+/// Otherwise, return the form template with issues marked.
+fn validate(editing: Option<i64>, input: &MeetingTypeForm) -> Result<MeetingTypeFields, Template> {
+    let mut form: Template = make_form(editing, input);
+
+    let name: &str = input.name.trim();
+    if name.is_empty() {
+        form.set_issue("name", "A name is required.");
+    } else if name.chars().count() > MAX_NAME_LENGTH {
+        form.set_issue(
+            "name",
+            format!("Names can be at most {} characters long.", MAX_NAME_LENGTH),
+        );
+    }
+
+    let color: String = input.color.trim().to_lowercase();
+    if !is_hex_color(color.as_str()) {
+        form.set_issue("color", "Pick a color in the form #rrggbb.");
+    }
+
+    let default_duration_minutes: i64 = input
+        .default_duration_minutes
+        .trim()
+        .parse::<i64>()
+        .unwrap_or(0);
+    if default_duration_minutes < 1 || default_duration_minutes > MAX_DURATION_MINUTES {
+        form.set_issue(
+            "default_duration_minutes",
+            format!(
+                "Enter a number of minutes between 1 and {}.",
+                MAX_DURATION_MINUTES
+            ),
+        );
+    }
+
+    if form["issues"] != json!(null) {
+        return Err(form);
+    }
+
+    return Ok(MeetingTypeFields {
+        name: name.to_string(),
+        color,
+        default_duration_minutes,
+        counts_for_attendance: input.counts_for_attendance.unwrap_or(false),
+    });
+}
+
+/// Redirect to the list of meeting types.
+fn to_types() -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, "/meeting_types")
+        .finish()
+}
+
+/// Error for a meeting type that does not exist.
+fn type_not_found() -> TelescopeError {
+    TelescopeError::resource_not_found(
+        "Meeting Type Not Found",
+        "Could not find a meeting type with this ID.",
+    )
+}
+
+/// List the custom meeting types.
+#[get("")]
+async fn index(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let mut template = Template::new("meetings/types/list");
+    template.set_field("meeting_types", MeetingTypes::get().await?)?;
+    return template.in_page(&req, "Meeting Types").await;
+}
+
+/// Form to add a meeting type.
+#[get("/create")]
+async fn new_type(req: HttpRequest) -> Result<Page, TelescopeError> {
+    // New types count for attendance and last an hour by default.
+    let defaults = MeetingTypeForm {
+        name: String::new(),
+        color: "#6c757d".into(),
+        default_duration_minutes: "60".into(),
+        counts_for_attendance: Some(true),
+    };
+
+    make_form(None, &defaults)
+        .in_page(&req, "Add Meeting Type")
+        .await
+}
+
+/// Submit a new meeting type.
+#[post("/create")]
+async fn submit_new_type(
+    req: HttpRequest,
+    Form(input): Form<MeetingTypeForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let fields: MeetingTypeFields = match validate(None, &input) {
+        Ok(fields) => fields,
+        Err(form) => {
+            let page = form.in_page(&req, "Add Meeting Type").await?;
+            return Err(TelescopeError::InvalidForm(page));
+        }
+    };
+
+    CreateMeetingType::execute(
+        fields.name,
+        fields.color,
+        fields.default_duration_minutes,
+        fields.counts_for_attendance,
+    )
+    .await?
+    .ok_or(TelescopeError::ise("Meeting type was not created."))?;
+    return Ok(to_types());
+}
+
+/// Form to edit a meeting type.
+#[get("/edit/{meeting_type_id}")]
+async fn edit_type(
+    req: HttpRequest,
+    Path(meeting_type_id): Path<i64>,
+) -> Result<Page, TelescopeError> {
+    let saved = MeetingTypes::get()
+        .await?
+        .into_iter()
+        .find(|meeting_type| meeting_type.meeting_type_id == meeting_type_id)
+        .ok_or_else(type_not_found)?;
+
+    let selections = MeetingTypeForm {
+        name: saved.name,
+        color: saved.color,
+        default_duration_minutes: saved.default_duration_minutes.to_string(),
+        counts_for_attendance: Some(saved.counts_for_attendance),
+    };
+
+    make_form(Some(meeting_type_id), &selections)
+        .in_page(&req, "Edit Meeting Type")
+        .await
+}
+
+/// Submit edits to a meeting type.
+#[post("/edit/{meeting_type_id}")]
+async fn submit_edit_type(
+    req: HttpRequest,
+    Path(meeting_type_id): Path<i64>,
+    Form(input): Form<MeetingTypeForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let fields: MeetingTypeFields = match validate(Some(meeting_type_id), &input) {
+        Ok(fields) => fields,
+        Err(form) => {
+            let page = form.in_page(&req, "Edit Meeting Type").await?;
+            return Err(TelescopeError::InvalidForm(page));
+        }
+    };
+
+    EditMeetingType::execute(
+        meeting_type_id,
+        fields.name,
+        fields.color,
+        fields.default_duration_minutes,
+        fields.counts_for_attendance,
+    )
+    .await?
+    .ok_or_else(type_not_found)?;
+    return Ok(to_types());
+}
@@ -1,11 +1,15 @@
 //! Service to view a meeting's details.
 
+use crate::api::rcos::attendance::CheckInTimes;
+use crate::api::rcos::link_health::get::BrokenLinks;
 use crate::api::rcos::meetings::authorization_for::{AuthorizationFor, UserMeetingAuthorization};
 use crate::api::rcos::meetings::get_by_id::{meeting::MeetingMeeting, Meeting};
+use crate::api::rcos::prelude::MeetingId;
 use crate::error::TelescopeError;
 use crate::templates::page::Page;
 use crate::templates::tags::Tags;
 use crate::templates::Template;
+use crate::web::embed::Embed;
 use crate::web::services::auth::identity::Identity;
 use actix_web::web::Path;
 use actix_web::HttpRequest;
@@ -18,7 +22,7 @@ const TEMPLATE_PATH: &'static str = "meetings/page";
 #[get("/meeting/{meeting_id}")]
 pub async fn meeting(
     req: HttpRequest,
-    Path(meeting_id): Path<i64>,
+    Path(meeting_id): Path<MeetingId>,
     identity: Identity,
 ) -> Result<Page, TelescopeError> {
     // Get the viewer's user ID.
@@ -36,11 +40,10 @@ pub async fn meeting(
     }
 
     // Unwrap the meeting object.
-    let meeting: MeetingMeeting = meeting.unwrap();
+    let mut meeting: MeetingMeeting = meeting.unwrap();
     // Make sure that the meeting is visible to the user.
     // First check for draft status.
-    let meeting_host: Option<_> = meeting.host.as_ref().map(|host| host.id);
-    let can_edit: bool = authorization.can_edit(meeting_host);
+    let can_edit: bool = authorization.can_edit(meeting.host_ids().as_slice());
     if !can_edit && meeting.is_draft && !authorization.can_view_drafts() {
         return Err(TelescopeError::BadRequest {
             header: "Meeting Not Visible".into(),
@@ -52,6 +55,14 @@ pub async fn meeting(
         });
     }
 
+    // Only hosts and those who can edit the meeting see the call host key
+    // and attendance code.
+    if !can_edit {
+        meeting.host_key = None;
+        meeting.attendance_code = None;
+        meeting.attendance_code_expires_at = None;
+    }
+
     // Then check the meeting variant.
     if !authorization.can_view(meeting.type_) {
         return Err(TelescopeError::BadRequest {
@@ -110,7 +121,7 @@ pub async fn meeting(
     description.push_str("\n");
 
     // Add meeting type.
-    description.push_str(format!("{}", meeting.type_).as_str());
+    description.push_str(meeting.type_name().as_str());
 
     // Add the host if possible.
     if meeting.host.is_some() {
@@ -126,13 +137,42 @@ pub async fn meeting(
     // Add description to OGP tags.
     tags.description = description;
 
+    // Look up which of the meeting's links failed their last check.
+    let links: Vec<String> = vec![
+        meeting.meeting_url.clone(),
+        meeting.recording_url.clone(),
+        meeting.external_presentation_url.clone(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let broken_links = BrokenLinks::get(links).await?;
+
     // Build meeting template.
     let mut template = Template::new(TEMPLATE_PATH);
     template.fields = json!({
         "meeting": &meeting,
-        "auth": authorization
+        "auth": authorization,
+        "broken_links": broken_links,
+        // Hosts, co-hosts, guest speakers, and coordinators can edit the meeting.
+        "can_edit": can_edit,
+        // Embedded player for recordings on supported video hosts.
+        "recording_embed": meeting.recording_url.as_deref().and_then(Embed::parse)
     });
 
+    // Show hosts when check-in is open so they can extend it.
+    if can_edit {
+        template.set_field(
+            "check_in",
+            CheckInTimes::for_meeting(
+                meeting.type_,
+                meeting.start_date_time,
+                meeting.end_date_time,
+                meeting.check_in_extended_until,
+            ),
+        )?;
+    }
+
     // Build page around meeting template.
     let mut page = template.in_page(&req, meeting.title()).await?;
     // Replace default page tags with meeting specific ones.
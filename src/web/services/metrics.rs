@@ -0,0 +1,90 @@
+//! Request timings in the Prometheus text format.
+
+use crate::web::timing::{self, RouteTiming};
+use actix_web::http::header::{CACHE_CONTROL, CONTENT_TYPE};
+use actix_web::HttpResponse;
+use std::fmt::Write;
+
+/// The content type of the Prometheus text format.
+const CONTENT_TYPE_TEXT: &'static str = "text/plain; version=0.0.4";
+
+/// The metrics reported for each route: name, type, help text, and value.
+const METRICS: [(
+    &'static str,
+    &'static str,
+    &'static str,
+    fn(&RouteTiming) -> f64,
+); 6] = [
+    (
+        "telescope_requests_total",
+        "counter",
+        "Requests handled.",
+        |route| route.count as f64,
+    ),
+    (
+        "telescope_requests_over_budget_total",
+        "counter",
+        "Requests that took longer than the load budget of their route class.",
+        |route| route.over_budget as f64,
+    ),
+    (
+        "telescope_request_seconds_total",
+        "counter",
+        "Wall time spent handling requests.",
+        |route| route.wall_seconds,
+    ),
+    (
+        "telescope_request_upstream_seconds_total",
+        "counter",
+        "Time spent waiting on upstream services. Calls made at once are each counted.",
+        |route| route.upstream_seconds,
+    ),
+    (
+        "telescope_request_render_seconds_total",
+        "counter",
+        "Time spent rendering templates.",
+        |route| route.render_seconds,
+    ),
+    (
+        "telescope_request_max_seconds",
+        "gauge",
+        "Longest wall time of a single request.",
+        |route| route.max_wall_seconds,
+    ),
+];
+
+/// Escape a label value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Report the request timings of this replica, by route. Totals start over
+/// when it restarts.
+#[get("/metrics")]
+pub async fn metrics() -> HttpResponse {
+    let routes: Vec<RouteTiming> = timing::routes();
+
+    let mut body = String::new();
+    for (name, kind, help, value) in METRICS.iter() {
+        // Writing to a string cannot fail.
+        let _ = writeln!(body, "# HELP {} {}", name, help);
+        let _ = writeln!(body, "# TYPE {} {}", name, kind);
+        for route in routes.iter() {
+            let _ = writeln!(
+                body,
+                "{}{{route=\"{}\"}} {}",
+                name,
+                escape(route.route.as_str()),
+                value(route)
+            );
+        }
+    }
+
+    return HttpResponse::Ok()
+        .header(CACHE_CONTROL, "no-store")
+        .header(CONTENT_TYPE, CONTENT_TYPE_TEXT)
+        .body(body);
+}
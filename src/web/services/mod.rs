@@ -4,12 +4,35 @@ use actix_web::web::ServiceConfig;
 
 mod admin;
 pub mod auth;
+mod badges;
+mod custom_pages;
+mod engage;
+pub mod error_reports;
+mod health;
+mod images;
 mod index;
+pub mod leaderboard;
+mod matchmaking;
+mod metrics;
 pub mod meetings;
 pub mod not_found;
+mod organizations;
+mod policies;
+mod polls;
+mod presentations;
 mod projects;
+mod public_api;
+mod quick_nav;
+mod reports;
+mod search;
+mod sponsors;
+mod stats;
+mod status_updates;
 pub mod user;
 
+#[cfg(test)]
+mod tests;
+
 /// Register all of the routes to the actix app.
 pub fn register(config: &mut ServiceConfig) {
     // Register authentication related services
@@ -27,7 +50,60 @@ pub fn register(config: &mut ServiceConfig) {
     // Admin panel services.
     admin::register(config);
 
+    // Organization scoped services.
+    organizations::register(config);
+
+    // Badge catalog and awards.
+    badges::register(config);
+
+    // Attendance leaderboard.
+    leaderboard::register(config);
+
+    // Student engagement panel.
+    engage::register(config);
+
+    // Projects suggested by skill.
+    matchmaking::register(config);
+
+    // Presentation day scheduler.
+    presentations::register(config);
+
+    // Polls.
+    polls::register(config);
+
+    // Weekly status updates.
+    status_updates::register(config);
+
+    // Content reports.
+    reports::register(config);
+
+    // Reports filed from error pages.
+    error_reports::register(config);
+
+    // Terms of service and code of conduct.
+    policies::register(config);
+
+    // Public statistics.
+    stats::register(config);
+
+    // Public JSON API.
+    public_api::register(config);
+
     config
         // Homepage
-        .service(index::index);
+        .service(index::index)
+        // Coordinator managed pages
+        .service(custom_pages::view)
+        // Sponsors page
+        .service(sponsors::sponsors_page)
+        // Uploaded images
+        .service(images::image)
+        // Global search
+        .service(search::search_page)
+        // Navbar quick switcher data
+        .service(quick_nav::quick_nav)
+        // Health check
+        .service(health::health)
+        // Request timings
+        .service(metrics::metrics);
 }
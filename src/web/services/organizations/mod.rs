@@ -0,0 +1,130 @@
+//! Organization scoped services. Every organization configured in the Telescope
+//! config file gets a set of pages under `/org/{slug}`.
+
+use crate::api::rcos::landing_page_stats::OrganizationStatistics;
+use crate::api::rcos::projects::projects_page::{OrganizationProjects, PER_PAGE};
+use crate::env::{global_config, OrganizationConfig};
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::pagination::{PaginationInfo, PaginationParameter};
+use crate::templates::Template;
+use actix_web::web::{self as aweb, Path, Query, ServiceConfig};
+use actix_web::HttpRequest;
+
+/// The path to the organization landing page template from the templates directory.
+const TEMPLATE_PATH: &'static str = "organizations/index";
+
+/// The path to the organization projects page template.
+const PROJECTS_TEMPLATE_PATH: &'static str = "organizations/projects";
+
+/// Register organization scoped services.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(
+        aweb::scope("/org/{slug}")
+            .route("", aweb::get().to(landing_page))
+            .route("/projects", aweb::get().to(projects_page))
+            .route("/projects/{page}", aweb::get().to(projects_page)),
+    );
+}
+
+/// Query parameters of the organization projects page.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+struct ProjectsQuery {
+    /// Only list projects with this in their title or description.
+    search: Option<String>,
+}
+
+/// Path parameters of the organization projects page.
+#[derive(Deserialize, Debug)]
+struct ProjectsPath {
+    slug: String,
+    page: Option<u32>,
+}
+
+/// Resolve the organization config for a slug from the request path or return
+/// a resource not found error.
+fn resolve_organization(slug: &str) -> Result<OrganizationConfig, TelescopeError> {
+    global_config()
+        .organization(slug)
+        .cloned()
+        .ok_or(TelescopeError::resource_not_found(
+            "Organization Not Found",
+            format!("There is no organization with the ID \"{}\".", slug),
+        ))
+}
+
+/// Put an organization page in a page titled and tagged with the
+/// organization's name and description.
+async fn in_page(
+    req: &HttpRequest,
+    template: Template,
+    organization: &OrganizationConfig,
+    title: String,
+) -> Result<Page, TelescopeError> {
+    let mut page = template.in_page(req, title).await?;
+    page.ogp_tags.title = organization.name.clone();
+    if let Some(description) = organization.description.clone() {
+        page.ogp_tags.description = description;
+    }
+    return Ok(page);
+}
+
+/// Landing page of an organization. This is similar to the Telescope homepage
+/// but with branding and statistics specific to the organization.
+async fn landing_page(req: HttpRequest, Path(slug): Path<String>) -> Result<Page, TelescopeError> {
+    // Lookup the organization.
+    let organization: OrganizationConfig = resolve_organization(slug.as_str())?;
+    // Get statistics for just this organization.
+    let stats = OrganizationStatistics::get(slug.clone()).await?;
+
+    // Build the template.
+    let mut template = Template::new(TEMPLATE_PATH);
+    template.fields = json!({
+        "slug": slug,
+        "organization": organization,
+        "discord_guild_id": organization.discord_guild_id()?,
+        "stats": stats,
+    });
+
+    let title: String = organization.name.clone();
+    return in_page(&req, template, &organization, title).await;
+}
+
+/// Paginated list of an organization's projects.
+async fn projects_page(
+    req: HttpRequest,
+    Path(path): Path<ProjectsPath>,
+    Query(query): Query<ProjectsQuery>,
+) -> Result<Page, TelescopeError> {
+    let organization: OrganizationConfig = resolve_organization(path.slug.as_str())?;
+    let page = PaginationParameter::from_page(path.page, PER_PAGE);
+    let data =
+        OrganizationProjects::get(path.slug.clone(), page.page_index(), query.search.clone())
+            .await?;
+
+    let prefix: String = format!("/org/{}/projects/", path.slug);
+    let pagination: Option<PaginationInfo> =
+        data.projects_aggregate
+            .aggregate
+            .as_ref()
+            .and_then(|aggregate| {
+                PaginationInfo::new(
+                    aggregate.count as u64,
+                    page,
+                    prefix.as_str(),
+                    req.query_string(),
+                )
+            });
+
+    let mut template = Template::new(PROJECTS_TEMPLATE_PATH);
+    template.fields = json!({
+        "slug": path.slug,
+        "organization": organization,
+        "query": query,
+        "pagination": pagination,
+        "projects": data.projects,
+    });
+
+    let title: String = format!("{} Projects", organization.name);
+    return in_page(&req, template, &organization, title).await;
+}
@@ -0,0 +1,133 @@
+//! Pages to read and accept the terms of service and code of conduct.
+
+use crate::api::rcos::policies::get::latest_policies::PolicyInfo;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::policies;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Query, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse, Responder};
+
+/// The path to the policies page from the templates directory.
+const PAGE_TEMPLATE: &'static str = "policies/page";
+
+/// The path to the acceptance form from the templates directory.
+const ACCEPT_TEMPLATE: &'static str = "policies/accept";
+
+/// Register policy services.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(page).service(accept_form).service(accept);
+}
+
+/// Where to go after accepting policies.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct NextQuery {
+    next: Option<String>,
+}
+
+/// Form submitted to accept policies.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct AcceptForm {
+    /// Comma separated IDs of the policy versions shown on the form.
+    policy_ids: String,
+    /// The "I accept" checkbox.
+    agree: Option<String>,
+    next: Option<String>,
+}
+
+/// Only follow local paths after accepting, so the form cannot be used to
+/// send people to other sites.
+fn safe_next(next: Option<&str>) -> String {
+    match next {
+        Some(next) if next.starts_with('/') && !next.starts_with("//") => next.to_string(),
+        _ => "/".to_string(),
+    }
+}
+
+/// The latest version of every policy. Anyone can read these.
+#[get("/policies")]
+async fn page(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let mut template = Template::new(PAGE_TEMPLATE);
+    template.set_field("policies", policies::latest().await?)?;
+    return template.in_page(&req, "Policies").await;
+}
+
+/// Make the acceptance form for some pending policies.
+fn make_form(pending: &[PolicyInfo], next: &str) -> Result<Template, TelescopeError> {
+    let ids: Vec<String> = pending
+        .iter()
+        .map(|policy| policy.policy_id.to_string())
+        .collect();
+
+    let mut template = Template::new(ACCEPT_TEMPLATE);
+    template
+        .set_field("policies", pending)?
+        .set_field("policy_ids", ids.join(","))?
+        .set_field("next", next)?;
+    return Ok(template);
+}
+
+/// Redirect to where the user was going.
+fn to_next(next: String) -> HttpResponse {
+    HttpResponse::Found().header(LOCATION, next).finish()
+}
+
+/// The policies the viewer still has to accept.
+#[get("/policies/accept")]
+async fn accept_form(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    query: Option<Query<NextQuery>>,
+) -> Result<HttpResponse, TelescopeError> {
+    let user_id = auth.get_user_id_or_error().await?;
+    let next: String = safe_next(query.as_ref().and_then(|q| q.next.as_deref()));
+
+    let pending: Vec<PolicyInfo> = policies::pending(user_id).await?;
+    if pending.is_empty() {
+        return Ok(to_next(next));
+    }
+
+    let page = make_form(&pending, next.as_str())?
+        .in_page(&req, "Accept Policies")
+        .await?;
+    return page.respond_to(&req).await;
+}
+
+/// Accept the policies shown on the form.
+#[post("/policies/accept")]
+async fn accept(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Form(form): Form<AcceptForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let user_id = auth.get_user_id_or_error().await?;
+    let next: String = safe_next(form.next.as_deref());
+
+    let pending: Vec<PolicyInfo> = policies::pending(user_id).await?;
+    let shown: Vec<i64> = form
+        .policy_ids
+        .split(',')
+        .filter_map(|id| id.trim().parse::<i64>().ok())
+        .collect();
+
+    // If a new version was published while the form was open, the user has
+    // to read that one too.
+    let all_shown: bool = pending
+        .iter()
+        .all(|policy| shown.contains(&policy.policy_id));
+
+    if form.agree.is_none() || !all_shown {
+        let mut template = make_form(&pending, next.as_str())?;
+        if form.agree.is_none() {
+            template.set_issue("agree", "You must accept these policies to continue.");
+        }
+        let page = template.in_page(&req, "Accept Policies").await?;
+        return Err(TelescopeError::InvalidForm(page));
+    }
+
+    let accepted: Vec<i64> = pending.iter().map(|policy| policy.policy_id).collect();
+    policies::accept(user_id, accepted).await?;
+    return Ok(to_next(next));
+}
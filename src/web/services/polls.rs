@@ -0,0 +1,480 @@
+//! Polls.
+//!
+//! Coordinators create polls, either on their own or attached to a meeting.
+//! Members vote on the poll page and can change their vote until the poll
+//! closes. Results are shown to voters, to coordinators, and to everyone once
+//! the poll has closed, and update live as votes come in. Polls can be
+//! cross-posted to the RCOS Discord when they are created.
+
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::api::rcos::polls::get::{ListPolls, PollById, PollMeetings, PollVoters};
+use crate::api::rcos::polls::mutations::{ClearPollVotes, ClosePoll, CreatePoll, PollFields};
+use crate::api::rcos::polls::PollStatus;
+use crate::discord_bot::{self, guilds, DiscordMessage};
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use crate::web::{idempotency, live};
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, Query, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// The path to the poll list template from the templates directory.
+const LIST_TEMPLATE: &'static str = "polls/list";
+
+/// The path to the poll creation form from the templates directory.
+const CREATE_TEMPLATE: &'static str = "polls/create";
+
+/// The path to the poll page from the templates directory.
+const PAGE_TEMPLATE: &'static str = "polls/page";
+
+/// The path to the poll results (refreshed live) from the templates directory.
+const RESULTS_TEMPLATE: &'static str = "polls/results";
+
+/// The most options a poll can have.
+const MAX_OPTIONS: usize = 10;
+
+/// The longest a poll title or option can be.
+const MAX_TEXT_LENGTH: usize = 200;
+
+/// The format of `datetime-local` inputs.
+const DATETIME_INPUT_FORMAT: &'static str = "%Y-%m-%dT%H:%M";
+
+/// The event published when a poll gets a vote or closes.
+const POLL_CHANGED: &'static str = "poll";
+
+/// Register poll services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(list)
+        // The creation form must be registered before the poll page so that
+        // the ID path doesn't match the create path.
+        .service(create_form)
+        .service(create)
+        .service(page)
+        .service(results)
+        .service(vote)
+        .service(close);
+}
+
+/// The live topic for a poll.
+fn topic(poll_id: i64) -> String {
+    format!("poll/{}", poll_id)
+}
+
+/// Redirect to a poll.
+fn to_poll(poll_id: i64) -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, format!("/polls/{}", poll_id))
+        .finish()
+}
+
+/// Check if a user can create and close polls.
+async fn can_manage(viewer: Uuid) -> Result<bool, TelescopeError> {
+    // Coordinators, faculty advisors, and sysadmins manage polls.
+    Ok(AuthorizationFor::get(Some(viewer)).await?.can_view_drafts())
+}
+
+/// Query parameters for the poll creation form.
+#[derive(Deserialize, Debug)]
+struct CreateQuery {
+    meeting_id: Option<i64>,
+}
+
+/// Form submitted by coordinators to create a poll.
+#[derive(Deserialize, Serialize, Debug)]
+struct CreateForm {
+    title: String,
+    #[serde(default)]
+    description: String,
+    /// One option per line.
+    options: String,
+    #[serde(default)]
+    is_multiple_choice: Option<String>,
+    #[serde(default)]
+    is_anonymous: Option<String>,
+    #[serde(default)]
+    opens_at: String,
+    #[serde(default)]
+    closes_at: String,
+    #[serde(default)]
+    meeting_id: String,
+    #[serde(default)]
+    post_to_discord: Option<String>,
+    #[serde(default)]
+    idempotency_key: Option<String>,
+}
+
+/// Make the poll creation form template.
+async fn create_template() -> Result<Template, TelescopeError> {
+    let mut template = Template::new(CREATE_TEMPLATE);
+    template
+        .set_field("idempotency_key", idempotency::new_key())?
+        .set_field("meetings", PollMeetings::get().await?)?
+        .set_field(
+            "can_post_to_discord",
+            !guilds::announcement_channels().is_empty(),
+        )?;
+    return Ok(template);
+}
+
+/// Parse an optional `datetime-local` input as local time. Sets an issue on the
+/// form if it cannot be parsed.
+fn parse_datetime(form: &mut Template, field: &str, value: &str) -> Option<DateTime<Utc>> {
+    if value.trim().is_empty() {
+        return None;
+    }
+
+    let parsed = NaiveDateTime::parse_from_str(value.trim(), DATETIME_INPUT_FORMAT)
+        .ok()
+        .and_then(|naive| Local.from_local_datetime(&naive).single());
+
+    if parsed.is_none() {
+        form.set_issue(field, "Not a valid date and time.");
+    }
+    return parsed.map(|local| local.with_timezone(&Utc));
+}
+
+/// A poll on the list, with whether it is upcoming, open, or closed.
+#[derive(Serialize)]
+struct PollView<T> {
+    #[serde(flatten)]
+    poll: T,
+    status: PollStatus,
+}
+
+/// The list of polls.
+#[get("/polls")]
+async fn list(req: HttpRequest, auth: AuthenticationCookie) -> Result<Page, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    let can_manage: bool = can_manage(viewer).await?;
+
+    let polls: Vec<PollView<_>> = ListPolls::get()
+        .await?
+        .into_iter()
+        .map(|poll| PollView {
+            status: PollStatus::of(poll.opens_at, poll.closes_at),
+            poll,
+        })
+        // Only managers see polls before they open.
+        .filter(|poll| can_manage || poll.status != PollStatus::Upcoming)
+        .collect();
+
+    let mut template = Template::new(LIST_TEMPLATE);
+    template
+        .set_field("polls", polls)?
+        .set_field("can_manage", can_manage)?;
+    return template.in_page(&req, "Polls").await;
+}
+
+/// The poll creation form.
+#[get("/polls/create")]
+async fn create_form(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    query: Option<Query<CreateQuery>>,
+) -> Result<Page, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    if !can_manage(viewer).await? {
+        return Err(TelescopeError::Forbidden);
+    }
+
+    let mut template = create_template().await?;
+    template.set_path(&["form", "meeting_id"], query.and_then(|q| q.meeting_id))?;
+    return template.in_page(&req, "Create Poll").await;
+}
+
+/// Create a poll.
+#[post("/polls/create")]
+async fn create(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Form(form): Form<CreateForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    if !can_manage(viewer).await? {
+        return Err(TelescopeError::Forbidden);
+    }
+
+    // Send repeat submissions of the form to the poll it already created.
+    let key: Option<String> = form.idempotency_key.clone();
+    return idempotency::once(key, create_poll(req, viewer, form)).await;
+}
+
+/// Validate a submitted poll creation form and create the poll.
+async fn create_poll(
+    req: HttpRequest,
+    viewer: Uuid,
+    form: CreateForm,
+) -> Result<HttpResponse, TelescopeError> {
+    let mut template = create_template().await?;
+    template.set_field("form", &form)?;
+
+    let title: String = form.title.trim().to_string();
+    if title.is_empty() {
+        template.set_issue("title", "Cannot be empty.");
+    } else if title.chars().count() > MAX_TEXT_LENGTH {
+        template.set_issue(
+            "title",
+            format!("Cannot be longer than {} characters.", MAX_TEXT_LENGTH),
+        );
+    }
+
+    let options: Vec<String> = form
+        .options
+        .lines()
+        .map(str::trim)
+        .filter(|option| !option.is_empty())
+        .map(str::to_string)
+        .collect();
+    if options.len() < 2 {
+        template.set_issue("options", "A poll needs at least two options.");
+    } else if options.len() > MAX_OPTIONS {
+        template.set_issue(
+            "options",
+            format!("A poll can have at most {} options.", MAX_OPTIONS),
+        );
+    } else if options
+        .iter()
+        .any(|option| option.chars().count() > MAX_TEXT_LENGTH)
+    {
+        template.set_issue(
+            "options",
+            format!(
+                "Options cannot be longer than {} characters.",
+                MAX_TEXT_LENGTH
+            ),
+        );
+    }
+
+    let opens_at = parse_datetime(&mut template, "opens_at", &form.opens_at);
+    let closes_at = parse_datetime(&mut template, "closes_at", &form.closes_at);
+    if let Some(closes) = closes_at {
+        if closes <= opens_at.unwrap_or_else(Utc::now) {
+            template.set_issue("closes_at", "Must be after the poll opens.");
+        }
+    }
+
+    let meeting_id: Option<i64> = match form.meeting_id.trim() {
+        "" => None,
+        id => Some(id.parse::<i64>().map_err(|_| TelescopeError::BadRequest {
+            header: "Malformed Poll Form".into(),
+            message: "Could not parse meeting ID.".into(),
+            show_status_code: false,
+        })?),
+    };
+    template.set_path(&["form", "meeting_id"], meeting_id)?;
+
+    if template["issues"] != json!(null) {
+        let page = template.in_page(&req, "Create Poll").await?;
+        return Err(TelescopeError::InvalidForm(page));
+    }
+
+    let description: Option<String> =
+        Some(form.description.trim().to_string()).filter(|description| !description.is_empty());
+
+    let poll_id = CreatePoll::execute(
+        PollFields {
+            title: title.clone(),
+            description: description.clone(),
+            is_multiple_choice: form.is_multiple_choice.is_some(),
+            is_anonymous: form.is_anonymous.is_some(),
+            opens_at,
+            closes_at,
+            meeting_id,
+            options,
+        },
+        viewer,
+    )
+    .await?;
+
+    if form.post_to_discord.is_some() {
+        // Announcing is left to the bot thread -- the poll exists either way.
+        let announcement = DiscordMessage::AnnouncePoll {
+            poll_id,
+            title,
+            description,
+        };
+        if let Err(e) = discord_bot::send(announcement) {
+            warn!("Could not announce poll {} on Discord: {}", poll_id, e);
+        }
+    }
+
+    return Ok(to_poll(poll_id));
+}
+
+/// Make the template for a poll as seen by a viewer, with results included
+/// when the viewer can see them.
+async fn poll_template(
+    path: &'static str,
+    poll_id: i64,
+    viewer: Uuid,
+) -> Result<Template, TelescopeError> {
+    let poll = PollById::get(poll_id, viewer).await?;
+    let can_manage: bool = can_manage(viewer).await?;
+
+    let not_found = || {
+        TelescopeError::resource_not_found("Poll Not Found", "Could not find a poll by this ID.")
+    };
+    let poll = poll.ok_or_else(not_found)?;
+    let status = PollStatus::of(poll.opens_at, poll.closes_at);
+    if status == PollStatus::Upcoming && !can_manage {
+        return Err(not_found());
+    }
+
+    let has_voted: bool = poll.options.iter().any(|option| !option.my_vote.is_empty());
+    let show_results: bool = has_voted || can_manage || status == PollStatus::Closed;
+    let voters: i64 = poll
+        .voters
+        .aggregate
+        .as_ref()
+        .map(|aggregate| aggregate.count)
+        .unwrap_or(0);
+
+    let mut template = Template::new(path);
+    template
+        .set_field("poll", &poll)?
+        .set_field("status", status)?
+        .set_field("has_voted", has_voted)?
+        .set_field("show_results", show_results)?
+        .set_field("can_manage", can_manage)?
+        .set_field("topic", topic(poll_id))?;
+
+    if show_results {
+        // Who voted for what, by option.
+        let mut named: HashMap<i64, Value> = HashMap::new();
+        if !poll.is_anonymous {
+            for option in PollVoters::get(poll_id).await? {
+                let users: Vec<_> = option.votes.into_iter().map(|vote| vote.user).collect();
+                named.insert(option.option_id, json!(users));
+            }
+        }
+
+        for (index, option) in poll.options.iter().enumerate() {
+            let count: i64 = option
+                .votes_aggregate
+                .aggregate
+                .as_ref()
+                .map(|aggregate| aggregate.count)
+                .unwrap_or(0);
+            let percent: i64 = if voters > 0 { count * 100 / voters } else { 0 };
+            let index: String = index.to_string();
+            template.set_path(&["poll", "options", index.as_str(), "percent"], percent)?;
+            if let Some(users) = named.remove(&option.option_id) {
+                template.set_path(&["poll", "options", index.as_str(), "voters"], users)?;
+            }
+        }
+    } else {
+        // Don't leak counts to people who haven't voted yet.
+        for index in 0..poll.options.len() {
+            let index: String = index.to_string();
+            template.set_path(
+                &["poll", "options", index.as_str(), "votes_aggregate"],
+                Value::Null,
+            )?;
+        }
+        template.set_path(&["poll", "voters"], Value::Null)?;
+    }
+    return Ok(template);
+}
+
+/// A poll's voting page.
+#[get("/polls/{poll_id}")]
+async fn page(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Path(poll_id): Path<i64>,
+) -> Result<Page, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    let template = poll_template(PAGE_TEMPLATE, poll_id, viewer).await?;
+    let title = format!(
+        "Poll - {}",
+        template["poll"]["title"].as_str().unwrap_or("Poll")
+    );
+    return template.in_page(&req, title).await;
+}
+
+/// Just a poll's results, for pages to refresh when votes come in.
+#[get("/polls/{poll_id}/results")]
+async fn results(
+    auth: AuthenticationCookie,
+    Path(poll_id): Path<i64>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    let rendered: String = poll_template(RESULTS_TEMPLATE, poll_id, viewer)
+        .await?
+        .render()?;
+    return Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(rendered));
+}
+
+/// Vote on a poll, replacing any earlier vote. Single choice polls submit the
+/// picked option ID as `option`. Multiple choice polls submit a checkbox named
+/// `option-{id}` for each picked option.
+#[post("/polls/{poll_id}/vote")]
+async fn vote(
+    auth: AuthenticationCookie,
+    Path(poll_id): Path<i64>,
+    Form(form): Form<HashMap<String, String>>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    let poll = PollById::get(poll_id, viewer).await?.ok_or_else(|| {
+        TelescopeError::resource_not_found("Poll Not Found", "Could not find a poll by this ID.")
+    })?;
+
+    if PollStatus::of(poll.opens_at, poll.closes_at) != PollStatus::Open {
+        return Err(TelescopeError::BadRequest {
+            header: "Poll Not Open".into(),
+            message: "This poll is not taking votes.".into(),
+            show_status_code: false,
+        });
+    }
+
+    let picked: Vec<i64> = poll
+        .options
+        .iter()
+        .map(|option| option.option_id)
+        .filter(|id| {
+            if poll.is_multiple_choice {
+                form.contains_key(&format!("option-{}", id))
+            } else {
+                form.get("option") == Some(&id.to_string())
+            }
+        })
+        .collect();
+
+    if picked.is_empty() {
+        return Err(TelescopeError::BadRequest {
+            header: "No Option Picked".into(),
+            message: "Pick an option to vote.".into(),
+            show_status_code: false,
+        });
+    }
+
+    ClearPollVotes::cast(poll_id, viewer, picked).await?;
+    live::publish(topic(poll_id), POLL_CHANGED);
+    return Ok(to_poll(poll_id));
+}
+
+/// Close a poll now.
+#[post("/polls/{poll_id}/close")]
+async fn close(
+    auth: AuthenticationCookie,
+    Path(poll_id): Path<i64>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    if !can_manage(viewer).await? {
+        return Err(TelescopeError::Forbidden);
+    }
+
+    ClosePoll::execute(poll_id).await?;
+    live::publish(topic(poll_id), POLL_CHANGED);
+    return Ok(to_poll(poll_id));
+}
@@ -0,0 +1,366 @@
+//! End of semester presentation scheduler.
+//!
+//! Coordinators add time slots to a semester, and project leads claim one slot
+//! per project. The schedule is public and can be exported as an iCalendar file.
+
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::api::rcos::presentations::get::{
+    OverlappingSlots, PresentationSchedule, SlotClaimContext,
+};
+use crate::api::rcos::presentations::mutations::{
+    ClaimPresentationSlot, CreatePresentationSlot, DeletePresentationSlot, ReleasePresentationSlot,
+};
+use crate::api::rcos::semesters::current::info::CurrentSemesters;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::ics::{event_uid, Calendar, Event};
+use crate::web::services::auth::identity::{AuthenticationCookie, Identity};
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// The path to the schedule template from the templates directory.
+const TEMPLATE_PATH: &'static str = "presentations/schedule";
+
+/// Register presentation scheduler services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(current_schedule)
+        .service(schedule)
+        .service(export_ics)
+        .service(create_slot)
+        .service(delete_slot)
+        .service(claim_slot)
+        .service(release_slot);
+}
+
+/// Form submitted by coordinators to add a slot.
+#[derive(Deserialize, Serialize, Debug)]
+struct SlotForm {
+    date: NaiveDate,
+    start_time: String,
+    end_time: String,
+    #[serde(default)]
+    location: String,
+}
+
+/// Form submitted by project leads to claim a slot.
+#[derive(Deserialize, Debug)]
+struct ClaimForm {
+    project_id: i64,
+}
+
+/// A presentation slot, marked if the viewer can release it.
+#[derive(Serialize)]
+struct SlotView<'a, T> {
+    #[serde(flatten)]
+    slot: &'a T,
+    can_release: bool,
+}
+
+/// Check if a user can manage presentation slots.
+async fn can_manage(viewer: Option<Uuid>) -> Result<bool, TelescopeError> {
+    // Coordinators, faculty advisors, and sysadmins can manage slots.
+    Ok(viewer.is_some() && AuthorizationFor::get(viewer).await?.can_view_drafts())
+}
+
+/// Redirect to the schedule for a semester.
+fn redirect_to_schedule(semester_id: &str) -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, format!("/presentations/{}", semester_id))
+        .finish()
+}
+
+/// Make the schedule template for a semester.
+async fn schedule_template(
+    semester_id: String,
+    viewer: Option<Uuid>,
+) -> Result<Template, TelescopeError> {
+    let data = PresentationSchedule::get(semester_id, viewer).await?;
+    let semester = data.semesters_by_pk.as_ref().ok_or_else(|| {
+        TelescopeError::resource_not_found(
+            "Semester Not Found",
+            "Could not find a semester by this ID.",
+        )
+    })?;
+
+    // Projects that already have a slot.
+    let scheduled: HashSet<i64> = semester
+        .presentation_slots
+        .iter()
+        .filter_map(|slot| slot.project.as_ref().map(|p| p.project_id))
+        .collect();
+
+    // The viewer's projects that still need a slot.
+    let claimable: Vec<_> = data
+        .led_projects
+        .iter()
+        .filter_map(|enrollment| enrollment.project.as_ref())
+        .filter(|project| !scheduled.contains(&project.project_id))
+        .collect();
+
+    // The viewer's projects, so they can release their own slots.
+    let led: HashSet<i64> = data
+        .led_projects
+        .iter()
+        .filter_map(|enrollment| enrollment.project.as_ref().map(|p| p.project_id))
+        .collect();
+
+    // Mark the slots the viewer can release.
+    let can_manage: bool = can_manage(viewer).await?;
+    let slots: Vec<SlotView<_>> = semester
+        .presentation_slots
+        .iter()
+        .map(|slot| SlotView {
+            slot,
+            can_release: slot
+                .project
+                .as_ref()
+                .map(|p| can_manage || led.contains(&p.project_id))
+                .unwrap_or(false),
+        })
+        .collect();
+
+    let mut template = Template::new(TEMPLATE_PATH);
+    template
+        .set_field(
+            "semester",
+            json!({
+                "semester_id": &semester.semester_id,
+                "title": &semester.title,
+            }),
+        )?
+        .set_field("slots", slots)?
+        .set_field("claimable", claimable)?
+        .set_field("can_manage", can_manage)?;
+    return Ok(template);
+}
+
+/// Presentation schedule for the current semester.
+#[get("/presentations")]
+async fn current_schedule(req: HttpRequest, identity: Identity) -> Result<Page, TelescopeError> {
+    let semester_id = CurrentSemesters::get()
+        .await?
+        .into_iter()
+        .next()
+        .map(|semester| semester.semester_id)
+        .ok_or_else(|| {
+            TelescopeError::resource_not_found(
+                "No Ongoing Semester",
+                "There is no ongoing semester to show presentations for.",
+            )
+        })?;
+
+    let viewer = identity.get_user_id().await?;
+    return schedule_template(semester_id, viewer)
+        .await?
+        .in_page(&req, "Presentations")
+        .await;
+}
+
+/// Presentation schedule for a semester.
+#[get("/presentations/{semester_id}")]
+async fn schedule(
+    req: HttpRequest,
+    identity: Identity,
+    Path(semester_id): Path<String>,
+) -> Result<Page, TelescopeError> {
+    let viewer = identity.get_user_id().await?;
+    return schedule_template(semester_id, viewer)
+        .await?
+        .in_page(&req, "Presentations")
+        .await;
+}
+
+/// Export a semester's presentation schedule as an iCalendar file.
+#[get("/presentations/{semester_id}/schedule.ics")]
+async fn export_ics(Path(semester_id): Path<String>) -> Result<HttpResponse, TelescopeError> {
+    let data = PresentationSchedule::get(semester_id.clone(), None).await?;
+    let semester = data.semesters_by_pk.ok_or_else(|| {
+        TelescopeError::resource_not_found(
+            "Semester Not Found",
+            "Could not find a semester by this ID.",
+        )
+    })?;
+
+    let mut calendar = Calendar::new(format!("RCOS {} Presentations", semester.title));
+    for slot in semester.presentation_slots {
+        calendar.push(Event {
+            uid: event_uid("presentation-slot", slot.slot_id),
+            start: slot.start_date_time,
+            end: slot.end_date_time,
+            summary: slot
+                .project
+                .as_ref()
+                .map(|project| format!("{} Presentation", project.title))
+                .unwrap_or("Open Presentation Slot".into()),
+            description: None,
+            location: slot.location,
+            url: None,
+        });
+    }
+
+    return Ok(calendar.into_response(format!("presentations-{}.ics", semester_id)));
+}
+
+/// Parse an `HH:MM` time from the slot form on a given date into a timestamp.
+fn parse_slot_time(date: NaiveDate, time: &str) -> Option<DateTime<Utc>> {
+    let time: NaiveTime = NaiveTime::parse_from_str(time, "%H:%M").ok()?;
+    return Local
+        .from_local_datetime(&date.and_time(time))
+        .single()
+        .map(|t| t.with_timezone(&Utc));
+}
+
+/// Add a slot to a semester's schedule.
+#[post("/presentations/{semester_id}/slots")]
+async fn create_slot(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Path(semester_id): Path<String>,
+    Form(form): Form<SlotForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    if !can_manage(Some(viewer)).await? {
+        return Err(TelescopeError::Forbidden);
+    }
+
+    // Make the schedule page to return on errors.
+    let mut template = schedule_template(semester_id.clone(), Some(viewer)).await?;
+    template.set_field("form", &form)?;
+
+    let start = parse_slot_time(form.date, form.start_time.as_str());
+    let end = parse_slot_time(form.date, form.end_time.as_str());
+    let location: Option<String> = Some(form.location.trim().to_string()).filter(|l| !l.is_empty());
+
+    match (start, end) {
+        (None, _) => {
+            template.set_issue("start_time", "Invalid start time.");
+        }
+        (_, None) => {
+            template.set_issue("end_time", "Invalid end time.");
+        }
+        (Some(start), Some(end)) if end <= start => {
+            template.set_issue("end_time", "End time must be after start time.");
+        }
+        (Some(start), Some(end)) => {
+            // Slots at the same place cannot overlap.
+            if OverlappingSlots::exists(semester_id.clone(), start, end, location.clone()).await? {
+                template.set_issue(
+                    "start_time",
+                    "This overlaps another slot at the same location.",
+                );
+            }
+        }
+    }
+
+    if template["issues"] != json!(null) {
+        let page = template.in_page(&req, "Presentations").await?;
+        return Err(TelescopeError::InvalidForm(page));
+    }
+
+    CreatePresentationSlot::execute(semester_id.clone(), start.unwrap(), end.unwrap(), location)
+        .await?;
+    return Ok(redirect_to_schedule(semester_id.as_str()));
+}
+
+/// Delete a slot.
+#[post("/presentations/slots/{slot_id}/delete")]
+async fn delete_slot(
+    auth: AuthenticationCookie,
+    Path(slot_id): Path<i64>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    if !can_manage(Some(viewer)).await? {
+        return Err(TelescopeError::Forbidden);
+    }
+
+    let semester_id = DeletePresentationSlot::execute(slot_id)
+        .await?
+        .ok_or_else(|| {
+            TelescopeError::resource_not_found("Slot Not Found", "This slot does not exist.")
+        })?;
+    return Ok(redirect_to_schedule(semester_id.as_str()));
+}
+
+/// Claim a slot for a project the viewer leads.
+#[post("/presentations/slots/{slot_id}/claim")]
+async fn claim_slot(
+    auth: AuthenticationCookie,
+    Path(slot_id): Path<i64>,
+    Form(ClaimForm { project_id }): Form<ClaimForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    let slot = SlotClaimContext::get(slot_id, viewer)
+        .await?
+        .ok_or_else(|| {
+            TelescopeError::resource_not_found("Slot Not Found", "This slot does not exist.")
+        })?;
+
+    // Only project leads can claim slots for their projects.
+    if !slot
+        .semester
+        .led
+        .iter()
+        .any(|e| e.project_id == Some(project_id))
+    {
+        return Err(TelescopeError::Forbidden);
+    }
+
+    // Each project presents once per semester.
+    if slot
+        .semester
+        .claimed
+        .iter()
+        .any(|s| s.project_id == Some(project_id))
+    {
+        return Err(TelescopeError::BadRequest {
+            header: "Already Scheduled".into(),
+            message: "This project already has a presentation slot this semester.".into(),
+            show_status_code: false,
+        });
+    }
+
+    // The claim only succeeds if the slot is still open.
+    if slot.project_id.is_some()
+        || !ClaimPresentationSlot::execute(slot_id, project_id, viewer).await?
+    {
+        return Err(TelescopeError::BadRequest {
+            header: "Slot Taken".into(),
+            message: "Another project has already claimed this slot.".into(),
+            show_status_code: false,
+        });
+    }
+
+    return Ok(redirect_to_schedule(slot.semester_id.as_str()));
+}
+
+/// Give up a claimed slot.
+#[post("/presentations/slots/{slot_id}/release")]
+async fn release_slot(
+    auth: AuthenticationCookie,
+    Path(slot_id): Path<i64>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    let slot = SlotClaimContext::get(slot_id, viewer)
+        .await?
+        .ok_or_else(|| {
+            TelescopeError::resource_not_found("Slot Not Found", "This slot does not exist.")
+        })?;
+
+    // Leads of the presenting project and coordinators can release a slot.
+    let leads_project: bool = slot
+        .project_id
+        .map(|id| slot.semester.led.iter().any(|e| e.project_id == Some(id)))
+        .unwrap_or(false);
+    if !leads_project && !can_manage(Some(viewer)).await? {
+        return Err(TelescopeError::Forbidden);
+    }
+
+    ReleasePresentationSlot::execute(slot_id).await?;
+    return Ok(redirect_to_schedule(slot.semester_id.as_str()));
+}
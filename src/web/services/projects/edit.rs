@@ -0,0 +1,279 @@
+//! Services to create, edit, and archive projects.
+//!
+//! Coordinators and faculty advisors create projects and archive the ones
+//! that have stopped. Anyone who can manage a project (see
+//! [`super::can_manage`]) can edit its details. Archived projects stay
+//! visible, but do not take new members.
+
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::api::rcos::projects::mutations::{
+    CreateProject, EditProject, ProjectFields, SetProjectArchived,
+};
+use crate::api::rcos::projects::project::project::ProjectProjectsByPk;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use crate::web::services::projects::authorize_manager;
+use crate::web::validation::validate_url;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use url::Url;
+
+/// The path to the form template from the templates directory.
+const FORM_TEMPLATE: &'static str = "projects/form";
+
+/// The longest project title.
+const MAX_TITLE_LENGTH: usize = 100;
+
+/// The longest name of a technology in a project's stack.
+const MAX_TECHNOLOGY_LENGTH: usize = 50;
+
+/// Register project creation and editing services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(new)
+        .service(submit_new)
+        .service(edit)
+        .service(submit_edit)
+        .service(archive)
+        .service(restore);
+}
+
+/// Form submitted to create or edit a project.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ProjectForm {
+    title: String,
+    description: String,
+    homepage_url: String,
+    /// One link per line.
+    repository_urls: String,
+    /// Separated by commas.
+    stack: String,
+}
+
+impl ProjectForm {
+    /// Create a form pre-filled with an existing project's details.
+    fn from_project(project: &ProjectProjectsByPk) -> Self {
+        ProjectForm {
+            title: project.title.clone(),
+            description: project.description.clone(),
+            homepage_url: project.homepage_url.clone().unwrap_or_default(),
+            repository_urls: project
+                .repository_urls
+                .iter()
+                .map(Url::as_str)
+                .collect::<Vec<&str>>()
+                .join("\n"),
+            stack: project.stack.join(", "),
+        }
+    }
+}
+
+/// Make the project form template. If `editing` is some, the form edits that
+/// project.
+fn make_form(editing: Option<&ProjectProjectsByPk>, selections: Option<&ProjectForm>) -> Template {
+    let mut form = Template::new(FORM_TEMPLATE);
+    form.fields = json!({
+        "editing": editing.map(|project| project.project_id),
+        "archived": editing.map_or(false, |project| project.archived_at.is_some()),
+        "selections": selections,
+    });
+    return form;
+}
+
+/// Validate a submitted project form. On success, return the fields to save.
+/// Otherwise, return the form template with issues marked.
+fn validate(
+    editing: Option<&ProjectProjectsByPk>,
+    input: &ProjectForm,
+) -> Result<ProjectFields, Template> {
+    let mut form: Template = make_form(editing, Some(input));
+
+    let title: &str = input.title.trim();
+    if title.is_empty() || title.chars().count() > MAX_TITLE_LENGTH {
+        form.set_issue(
+            "title",
+            format!(
+                "Titles must be between 1 and {} characters long.",
+                MAX_TITLE_LENGTH
+            ),
+        );
+    }
+
+    if input.description.trim().is_empty() {
+        form.set_issue("description", "Description cannot be empty.");
+    }
+
+    let homepage_url: Option<String> = validate_url(Some(input.homepage_url.as_str()), &[])
+        .unwrap_or_else(|issue| {
+            form.set_issue("homepage_url", issue);
+            None
+        });
+
+    // Check every repository link, and mark the first bad one.
+    let mut repository_urls: Vec<Url> = Vec::new();
+    for line in input.repository_urls.lines() {
+        match validate_url(Some(line), &[]) {
+            Ok(Some(url)) => match Url::parse(url.as_str()) {
+                Ok(url) if !repository_urls.contains(&url) => repository_urls.push(url),
+                _ => {}
+            },
+            Ok(None) => {}
+            Err(issue) => {
+                form.set_issue("repository_urls", format!("{}: {}", line.trim(), issue));
+                break;
+            }
+        }
+    }
+
+    // Technologies are listed in the order given, without repeats.
+    let mut stack: Vec<String> = Vec::new();
+    for technology in input.stack.split(',').map(str::trim) {
+        if technology.chars().count() > MAX_TECHNOLOGY_LENGTH {
+            form.set_issue(
+                "stack",
+                format!(
+                    "Technologies can be at most {} characters long.",
+                    MAX_TECHNOLOGY_LENGTH
+                ),
+            );
+            break;
+        }
+        if !technology.is_empty() && !stack.iter().any(|t| t.eq_ignore_ascii_case(technology)) {
+            stack.push(technology.to_string());
+        }
+    }
+
+    if form["issues"] != json!(null) {
+        return Err(form);
+    }
+
+    return Ok(ProjectFields {
+        title: title.to_string(),
+        description: input.description.trim().to_string(),
+        homepage_url,
+        repository_urls,
+        stack,
+    });
+}
+
+/// Redirect to a project's page.
+fn to_project(project_id: i64) -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, format!("/project/{}", project_id))
+        .finish()
+}
+
+/// Make sure the viewer is a coordinator or faculty advisor.
+async fn authorize_coordinator(auth: &AuthenticationCookie) -> Result<(), TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    if !AuthorizationFor::get(Some(viewer)).await?.can_view_drafts() {
+        return Err(TelescopeError::Forbidden);
+    }
+    return Ok(());
+}
+
+/// Form to create a project.
+#[get("/projects/create")]
+async fn new(req: HttpRequest, auth: AuthenticationCookie) -> Result<Page, TelescopeError> {
+    authorize_coordinator(&auth).await?;
+    make_form(None, None).in_page(&req, "New Project").await
+}
+
+/// Submit a new project.
+#[post("/projects/create")]
+async fn submit_new(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Form(input): Form<ProjectForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    authorize_coordinator(&auth).await?;
+    let fields: ProjectFields = match validate(None, &input) {
+        Ok(fields) => fields,
+        Err(form) => {
+            let page = form.in_page(&req, "New Project").await?;
+            return Err(TelescopeError::InvalidForm(page));
+        }
+    };
+
+    let project_id: i64 = CreateProject::execute(fields)
+        .await?
+        .ok_or(TelescopeError::ise("Project was not created."))?;
+    return Ok(to_project(project_id));
+}
+
+/// Form to edit a project.
+#[get("/project/{project_id}/edit")]
+async fn edit(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Path(project_id): Path<i64>,
+) -> Result<Page, TelescopeError> {
+    let (viewer, project) = authorize_manager(&auth, project_id).await?;
+    let selections = ProjectForm::from_project(&project);
+    let mut form: Template = make_form(Some(&project), Some(&selections));
+    // Only coordinators and faculty advisors can archive projects.
+    form.set_field(
+        "can_archive",
+        AuthorizationFor::get(Some(viewer)).await?.can_view_drafts(),
+    )?;
+    form.in_page(&req, "Edit Project").await
+}
+
+/// Submit edits to a project.
+#[post("/project/{project_id}/edit")]
+async fn submit_edit(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Path(project_id): Path<i64>,
+    Form(input): Form<ProjectForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let (_, project) = authorize_manager(&auth, project_id).await?;
+    let fields: ProjectFields = match validate(Some(&project), &input) {
+        Ok(fields) => fields,
+        Err(form) => {
+            let page = form.in_page(&req, "Edit Project").await?;
+            return Err(TelescopeError::InvalidForm(page));
+        }
+    };
+
+    EditProject::execute(project_id, fields)
+        .await?
+        .ok_or(TelescopeError::ise("Project was not updated."))?;
+    return Ok(to_project(project_id));
+}
+
+/// Archive a project.
+#[post("/project/{project_id}/archive")]
+async fn archive(
+    auth: AuthenticationCookie,
+    Path(project_id): Path<i64>,
+) -> Result<HttpResponse, TelescopeError> {
+    authorize_coordinator(&auth).await?;
+    set_archived(project_id, true).await
+}
+
+/// Bring back an archived project.
+#[post("/project/{project_id}/restore")]
+async fn restore(
+    auth: AuthenticationCookie,
+    Path(project_id): Path<i64>,
+) -> Result<HttpResponse, TelescopeError> {
+    authorize_coordinator(&auth).await?;
+    set_archived(project_id, false).await
+}
+
+/// Archive or restore a project and go back to its page.
+async fn set_archived(project_id: i64, archived: bool) -> Result<HttpResponse, TelescopeError> {
+    SetProjectArchived::execute(project_id, archived)
+        .await?
+        .ok_or_else(|| {
+            TelescopeError::resource_not_found(
+                "Project Not Found",
+                "Could not find a project by this ID.",
+            )
+        })?;
+    return Ok(to_project(project_id));
+}
@@ -0,0 +1,245 @@
+//! Services for members to ask to join a project, and for its leads to decide.
+//!
+//! Members ask to join a project for the current semester from its page. The
+//! project's leads see pending requests on the same page. Approving a request
+//! puts the member on the project and has the Discord bot open the project's
+//! channels to them.
+//!
+//! Requests that would break the semester's team constraints can only be
+//! approved by a coordinator overriding them, which goes in the audit log.
+
+use crate::api::rcos::audit_log::{AuditAction, RecordAudit};
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::api::rcos::prelude::SemesterId;
+use crate::api::rcos::projects::constraints::SemesterTeamConstraints;
+use crate::api::rcos::projects::join_requests::{
+    ApproveJoinRequest, DenyJoinRequest, JoinRequestStatus, JoinRequests, RequestToJoin,
+};
+use crate::api::rcos::projects::project::{project::ProjectProjectsByPk, Project};
+use crate::api::rcos::semesters::current::info::CurrentSemesters;
+use crate::discord_bot::{self, DiscordMessage};
+use crate::error::TelescopeError;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use crate::web::services::projects::authorize_manager;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::HttpResponse;
+use uuid::Uuid;
+
+/// The longest message on a request to join.
+const MAX_MESSAGE_LENGTH: usize = 500;
+
+/// Register join request services.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(request_to_join).service(decide);
+}
+
+/// Form submitted to ask to join a project.
+#[derive(Deserialize, Debug)]
+struct JoinForm {
+    #[serde(default)]
+    message: String,
+}
+
+/// Form submitted to approve or deny a request to join.
+#[derive(Deserialize, Debug)]
+struct DecisionForm {
+    status: JoinRequestStatus,
+    /// Approve the request even if it breaks the team constraints. Only
+    /// coordinators can.
+    #[serde(default)]
+    override_constraints: Option<bool>,
+}
+
+/// Error for a request to join that cannot be made.
+fn cannot_join(message: &str) -> TelescopeError {
+    TelescopeError::BadRequest {
+        header: "Cannot Request to Join".into(),
+        message: message.into(),
+        show_status_code: false,
+    }
+}
+
+/// Get the semester in progress. Requests to join are for this semester.
+pub async fn current_semester() -> Result<Option<SemesterId>, TelescopeError> {
+    return Ok(CurrentSemesters::get()
+        .await?
+        .into_iter()
+        .next()
+        .map(|semester| SemesterId(semester.semester_id)));
+}
+
+/// Describe how the team would break the semester's team constraints if a
+/// member joined. Empty if it would not.
+pub async fn join_violations(
+    project: &ProjectProjectsByPk,
+    semester_id: SemesterId,
+    user_id: Option<Uuid>,
+) -> Result<Vec<String>, TelescopeError> {
+    let constraints = SemesterTeamConstraints::get(semester_id).await?;
+    let members = &project.current_members;
+    let already_member: bool = user_id.map_or(false, |user_id| {
+        members.iter().any(|member| member.user.id == user_id)
+    });
+    let size: i64 = members.len() as i64 + if already_member { 0 } else { 1 };
+    let leads: i64 = members.iter().filter(|m| m.is_project_lead).count() as i64;
+    return Ok(constraints.violations(size, leads));
+}
+
+/// Redirect to a project's page.
+fn to_project(project_id: i64) -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, format!("/project/{}", project_id))
+        .finish()
+}
+
+/// Ask to join a project this semester.
+#[post("/project/{project_id}/join")]
+async fn request_to_join(
+    auth: AuthenticationCookie,
+    Path(project_id): Path<i64>,
+    Form(form): Form<JoinForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer: Uuid = auth.get_user_id_or_error().await?;
+    let project = Project::get(project_id).await?.ok_or_else(|| {
+        TelescopeError::resource_not_found(
+            "Project Not Found",
+            "Could not find a project by this ID.",
+        )
+    })?;
+
+    if project
+        .current_members
+        .iter()
+        .any(|member| member.user.id == viewer)
+    {
+        return Err(cannot_join("You are already on this project."));
+    }
+
+    if project.archived_at.is_some() {
+        return Err(cannot_join(
+            "This project is archived and is not taking new members.",
+        ));
+    }
+
+    let semester_id: SemesterId = current_semester()
+        .await?
+        .ok_or_else(|| cannot_join("There is no semester in progress."))?;
+
+    // One request at a time. Members can ask again after being denied.
+    let requests = JoinRequests::get(project_id, semester_id.clone(), viewer).await?;
+    if requests
+        .mine
+        .iter()
+        .any(|request| request.status == JoinRequestStatus::Pending.to_string())
+    {
+        return Err(cannot_join(
+            "You already asked to join this project. The leads have not decided yet.",
+        ));
+    }
+
+    let message: &str = form.message.trim();
+    if message.chars().count() > MAX_MESSAGE_LENGTH {
+        return Err(cannot_join("Messages can be at most 500 characters long."));
+    }
+    let message: Option<String> = (!message.is_empty()).then(|| message.to_string());
+
+    RequestToJoin::execute(project_id, viewer, semester_id, message)
+        .await?
+        .ok_or(TelescopeError::ise("Request to join was not created."))?;
+    return Ok(to_project(project_id));
+}
+
+/// Approve or deny a pending request to join a project.
+#[post("/project/{project_id}/join_requests/{request_id}")]
+async fn decide(
+    auth: AuthenticationCookie,
+    Path((project_id, request_id)): Path<(i64, i64)>,
+    Form(form): Form<DecisionForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let (viewer, project) = authorize_manager(&auth, project_id).await?;
+    let semester_id: SemesterId =
+        current_semester()
+            .await?
+            .ok_or_else(|| TelescopeError::BadRequest {
+                header: "No Current Semester".into(),
+                message: "Requests to join can only be decided during a semester.".into(),
+                show_status_code: false,
+            })?;
+
+    // Look the request up rather than trusting the form for who made it.
+    let request = JoinRequests::get(project_id, semester_id.clone(), viewer)
+        .await?
+        .pending
+        .into_iter()
+        .find(|request| request.request_id == request_id)
+        .ok_or_else(|| {
+            TelescopeError::resource_not_found(
+                "Request Not Found",
+                "Could not find a pending request to join this project.",
+            )
+        })?;
+
+    match form.status {
+        JoinRequestStatus::Pending => {
+            return Err(TelescopeError::BadRequest {
+                header: "Invalid Decision".into(),
+                message: "Requests to join can be approved or denied.".into(),
+                show_status_code: false,
+            });
+        }
+
+        JoinRequestStatus::Denied => {
+            DenyJoinRequest::execute(request_id, project_id, viewer).await?;
+        }
+
+        JoinRequestStatus::Approved => {
+            let user_id: Uuid = request.user.id;
+            let violations: Vec<String> =
+                join_violations(&project, semester_id.clone(), Some(user_id)).await?;
+            if !violations.is_empty() {
+                let can_override: bool = form.override_constraints.unwrap_or(false)
+                    && AuthorizationFor::get(Some(viewer)).await?.can_view_drafts();
+                if !can_override {
+                    return Err(TelescopeError::BadRequest {
+                        header: "Team Constraints".into(),
+                        message: format!(
+                            "Approving this request would break the semester's team constraints: {} \
+                            A coordinator can approve it anyway.",
+                            violations.join(" ")
+                        ),
+                        show_status_code: false,
+                    });
+                }
+
+                RecordAudit::execute(
+                    viewer,
+                    AuditAction::JoinRequestOverride,
+                    format!("/project/{}", project_id),
+                    Some(violations.join(" ")),
+                )
+                .await?;
+            }
+
+            let approved: bool =
+                ApproveJoinRequest::execute(request_id, project_id, user_id, semester_id, viewer)
+                    .await?;
+
+            if approved {
+                // The bot opens the project's channels in the background.
+                let message = DiscordMessage::GrantProjectRole {
+                    project_id,
+                    user_id,
+                };
+                if let Err(e) = discord_bot::send(message) {
+                    warn!(
+                        "Could not give user {} the Discord role of project {}: {}",
+                        user_id, project_id, e
+                    );
+                }
+            }
+        }
+    }
+
+    return Ok(to_project(project_id));
+}
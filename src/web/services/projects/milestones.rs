@@ -0,0 +1,113 @@
+//! Services for project leads to manage their project's milestones.
+//!
+//! Leads of a project this semester, coordinators, and faculty advisors can
+//! add, change, and remove milestones (see [`super::can_manage`]). Milestones
+//! are shown on the project page.
+
+use crate::api::rcos::projects::milestones::{
+    CreateMilestone, DeleteMilestone, EditMilestone, MilestoneFields, MilestoneStatus,
+};
+use crate::error::TelescopeError;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use crate::web::services::projects::authorize_manager;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::HttpResponse;
+use chrono::NaiveDate;
+
+/// The longest milestone title.
+const MAX_TITLE_LENGTH: usize = 100;
+
+/// Register milestone services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(create_milestone)
+        .service(edit_milestone)
+        .service(delete_milestone);
+}
+
+/// Form submitted to add or change a milestone.
+#[derive(Deserialize, Debug)]
+struct MilestoneForm {
+    title: String,
+    due_date: NaiveDate,
+    status: MilestoneStatus,
+}
+
+/// Validate a submitted milestone.
+fn validate(form: MilestoneForm) -> Result<MilestoneFields, TelescopeError> {
+    let title: &str = form.title.trim();
+    if title.is_empty() || title.chars().count() > MAX_TITLE_LENGTH {
+        return Err(TelescopeError::BadRequest {
+            header: "Invalid Milestone".into(),
+            message: format!(
+                "Milestones need a title of at most {} characters.",
+                MAX_TITLE_LENGTH
+            ),
+            show_status_code: false,
+        });
+    }
+
+    return Ok(MilestoneFields {
+        title: title.to_string(),
+        due_date: form.due_date,
+        status: form.status,
+    });
+}
+
+/// Error for a milestone that does not exist.
+fn milestone_not_found() -> TelescopeError {
+    TelescopeError::resource_not_found(
+        "Milestone Not Found",
+        "Could not find this milestone on this project.",
+    )
+}
+
+/// Redirect to a project's page.
+fn to_project(project_id: i64) -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, format!("/project/{}", project_id))
+        .finish()
+}
+
+/// Add a milestone to a project.
+#[post("/project/{project_id}/milestones")]
+async fn create_milestone(
+    auth: AuthenticationCookie,
+    Path(project_id): Path<i64>,
+    Form(form): Form<MilestoneForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let (viewer, _) = authorize_manager(&auth, project_id).await?;
+    CreateMilestone::execute(project_id, validate(form)?, viewer)
+        .await?
+        .ok_or(TelescopeError::ise("Milestone was not created."))?;
+    return Ok(to_project(project_id));
+}
+
+/// Change a milestone of a project.
+#[post("/project/{project_id}/milestones/{milestone_id}")]
+async fn edit_milestone(
+    auth: AuthenticationCookie,
+    Path((project_id, milestone_id)): Path<(i64, i64)>,
+    Form(form): Form<MilestoneForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    authorize_manager(&auth, project_id).await?;
+    if !EditMilestone::execute(project_id, milestone_id, validate(form)?).await? {
+        return Err(milestone_not_found());
+    }
+    return Ok(to_project(project_id));
+}
+
+/// Remove a milestone from a project. Uses post to prevent inadvertent
+/// removal.
+#[post("/project/{project_id}/milestones/{milestone_id}/delete")]
+async fn delete_milestone(
+    auth: AuthenticationCookie,
+    Path((project_id, milestone_id)): Path<(i64, i64)>,
+) -> Result<HttpResponse, TelescopeError> {
+    authorize_manager(&auth, project_id).await?;
+    if !DeleteMilestone::execute(project_id, milestone_id).await? {
+        return Err(milestone_not_found());
+    }
+    return Ok(to_project(project_id));
+}
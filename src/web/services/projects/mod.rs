@@ -1,10 +1,73 @@
 //! Services related to project management.
 
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::api::rcos::projects::project::{project::ProjectProjectsByPk, Project};
+use crate::error::TelescopeError;
+use crate::web::services::auth::identity::AuthenticationCookie;
 use actix_web::web::ServiceConfig;
+use uuid::Uuid;
 
+mod edit;
+mod join_requests;
+mod milestones;
+mod project;
 mod projects_page;
+mod skills;
 
 /// Register project services.
 pub fn register(conf: &mut ServiceConfig) {
-    conf.service(projects_page::get);
+    conf.service(projects_page::get).service(project::get);
+
+    // Creating, editing, and archiving projects.
+    edit::register(conf);
+
+    // Milestones on project pages.
+    milestones::register(conf);
+
+    // Requests to join projects.
+    join_requests::register(conf);
+
+    // Skills projects need.
+    skills::register(conf);
+}
+
+/// Can a viewer manage a project's milestones and members? Leads of the
+/// project this semester can, as can coordinators and faculty advisors.
+pub async fn can_manage(
+    viewer: Option<Uuid>,
+    project: &ProjectProjectsByPk,
+) -> Result<bool, TelescopeError> {
+    let viewer: Uuid = match viewer {
+        Some(viewer) => viewer,
+        None => return Ok(false),
+    };
+
+    let is_lead: bool = project
+        .current_members
+        .iter()
+        .any(|member| member.is_project_lead && member.user.id == viewer);
+    if is_lead {
+        return Ok(true);
+    }
+    return Ok(AuthorizationFor::get(Some(viewer)).await?.can_view_drafts());
+}
+
+/// Make sure the viewer can manage a project. Return their user ID and the
+/// project if so.
+pub async fn authorize_manager(
+    auth: &AuthenticationCookie,
+    project_id: i64,
+) -> Result<(Uuid, ProjectProjectsByPk), TelescopeError> {
+    let viewer: Uuid = auth.get_user_id_or_error().await?;
+    let project = Project::get(project_id).await?.ok_or_else(|| {
+        TelescopeError::resource_not_found(
+            "Project Not Found",
+            "Could not find a project by this ID.",
+        )
+    })?;
+
+    if !can_manage(Some(viewer), &project).await? {
+        return Err(TelescopeError::Forbidden);
+    }
+    return Ok((viewer, project));
 }
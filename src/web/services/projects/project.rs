@@ -0,0 +1,139 @@
+//! Project page.
+
+use crate::api::rcos::link_health::get::BrokenLinks;
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::api::rcos::projects::join_requests::JoinRequests;
+use crate::api::rcos::projects::milestones::MilestoneProgress;
+use crate::api::rcos::projects::project::Project;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::Identity;
+use crate::web::services::matchmaking;
+use crate::web::services::projects::{can_manage, join_requests};
+use actix_web::web::Path;
+use actix_web::HttpRequest;
+use std::collections::HashSet;
+
+/// The path to the project page template from the templates directory.
+const TEMPLATE_PATH: &'static str = "projects/project";
+
+/// A current member of a project, with their recent contributions.
+#[derive(Serialize)]
+struct MemberView<'a, T> {
+    #[serde(flatten)]
+    member: &'a T,
+    contributions: Vec<i64>,
+}
+
+/// A milestone of a project, marked if it is overdue.
+#[derive(Serialize)]
+struct MilestoneView<'a, T> {
+    #[serde(flatten)]
+    milestone: &'a T,
+    overdue: bool,
+}
+
+/// Page showing a project, its current members, their recent contributions,
+/// and the project's milestones and needed skills. Logged in members can ask to join, and the
+/// project's leads see pending requests.
+#[get("/project/{project_id}")]
+pub async fn get(
+    req: HttpRequest,
+    identity: Identity,
+    Path(project_id): Path<i64>,
+) -> Result<Page, TelescopeError> {
+    let project = Project::get(project_id).await?.ok_or_else(|| {
+        TelescopeError::resource_not_found(
+            "Project Not Found",
+            "Could not find a project by this ID.",
+        )
+    })?;
+
+    // Add a contribution sparkline to each member.
+    let members: Vec<MemberView<_>> = project
+        .current_members
+        .iter()
+        .map(|member| MemberView {
+            member,
+            contributions: project.contribution_series(Some(member.user.id)),
+        })
+        .collect();
+
+    // Flag project links that failed their last check.
+    let links: Vec<String> = project
+        .homepage_url
+        .iter()
+        .cloned()
+        .chain(project.repository_urls.iter().map(|url| url.to_string()))
+        .collect();
+
+    // Mark overdue milestones.
+    let milestone_list: Vec<MilestoneView<_>> = project
+        .milestones
+        .iter()
+        .map(|milestone| MilestoneView {
+            milestone,
+            overdue: MilestoneProgress::is_overdue(milestone.due_date, milestone.status.as_str()),
+        })
+        .collect();
+    let progress = MilestoneProgress::new(
+        project
+            .milestones
+            .iter()
+            .map(|milestone| (milestone.due_date, milestone.status.as_str())),
+    );
+    let viewer = identity.get_user_id().await?;
+    let can_manage: bool = can_manage(viewer, &project).await?;
+
+    let mut template = Template::new(TEMPLATE_PATH);
+    template
+        .set_field("milestones", milestone_list)?
+        .set_field("progress", progress)?
+        .set_field("can_manage", can_manage)?;
+
+    // Let managers change the skills the project needs.
+    if can_manage {
+        let tagged: HashSet<i64> = project
+            .needed_skills
+            .iter()
+            .map(|needed| needed.skill.skill_id)
+            .collect();
+        matchmaking::fill_skill_picker(&mut template, tagged).await?;
+    }
+
+    // Requests to join this semester.
+    if let (Some(viewer), Some(semester_id)) = (viewer, join_requests::current_semester().await?) {
+        let requests = JoinRequests::get(project_id, semester_id.clone(), viewer).await?;
+
+        // Warn leads if approving another member would break the team constraints.
+        let violations: Vec<String> = if can_manage && !requests.pending.is_empty() {
+            join_requests::join_violations(&project, semester_id, None).await?
+        } else {
+            Vec::new()
+        };
+        let can_override: bool =
+            !violations.is_empty() && AuthorizationFor::get(Some(viewer)).await?.can_view_drafts();
+
+        template.set_field(
+            "join",
+            json!({
+                "is_member": project.current_members.iter().any(|member| member.user.id == viewer),
+                "mine": requests.mine.first(),
+                "pending": can_manage.then(|| requests.pending),
+                "violations": violations,
+                "can_override": can_override,
+            }),
+        )?;
+    }
+    template.set_field("broken_links", BrokenLinks::get(links).await?)?;
+    template
+        .set_field("project", &project)?
+        .set_field("members", members)?
+        .set_field("contributions", project.contribution_series(None))?;
+
+    let mut page = template.in_page(&req, project.title.as_str()).await?;
+    page.ogp_tags.title = project.title.clone();
+    page.ogp_tags.description = project.description.clone();
+    return Ok(page);
+}
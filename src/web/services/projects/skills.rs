@@ -0,0 +1,33 @@
+//! Service for project leads to set the skills their project needs.
+//!
+//! Members with these skills are suggested the project on the matchmaking page
+//! and the homepage.
+
+use crate::api::rcos::skills::ClearProjectSkills;
+use crate::error::TelescopeError;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use crate::web::services::matchmaking;
+use crate::web::services::projects::authorize_manager;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::HttpResponse;
+use std::collections::HashMap;
+
+/// Register project skill services.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(save_skills);
+}
+
+/// Set the skills a project needs, replacing the ones it had.
+#[post("/project/{project_id}/skills")]
+async fn save_skills(
+    auth: AuthenticationCookie,
+    Path(project_id): Path<i64>,
+    Form(form): Form<HashMap<String, String>>,
+) -> Result<HttpResponse, TelescopeError> {
+    authorize_manager(&auth, project_id).await?;
+    ClearProjectSkills::set(project_id, matchmaking::picked_skills(&form).await?).await?;
+    return Ok(HttpResponse::Found()
+        .header(LOCATION, format!("/project/{}", project_id))
+        .finish());
+}
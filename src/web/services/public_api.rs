@@ -0,0 +1,60 @@
+//! The public read-only JSON API, under `/api/v1`.
+//!
+//! Every route here is rate limited by [caller tier](crate::web::api_keys).
+//! Keys are handed out on the API keys admin page.
+
+use super::stats::get_statistics;
+use crate::api::rcos::projects::projects_page::{CurrentProjects, PER_PAGE};
+use crate::api::rcos::sponsors::get::CurrentSponsors;
+use crate::error::TelescopeError;
+use crate::web::middlewares::api_rate_limit::ApiRateLimit;
+use actix_web::web as aweb;
+use actix_web::web::{Query, ServiceConfig};
+use actix_web::HttpResponse;
+
+/// Register the public API.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(
+        aweb::scope("/api/v1")
+            .wrap(ApiRateLimit)
+            .service(stats)
+            .service(projects)
+            .service(sponsors),
+    );
+}
+
+/// Query parameters of the project list.
+#[derive(Deserialize, Debug)]
+struct ProjectsQuery {
+    /// The page to get, counting from 1.
+    page: Option<u32>,
+    /// Only list projects with this in their title or description.
+    search: Option<String>,
+}
+
+/// Membership, project, and meeting counts for every semester.
+#[get("/stats")]
+async fn stats() -> Result<HttpResponse, TelescopeError> {
+    let stats = get_statistics().await?;
+    return Ok(HttpResponse::Ok().json(stats.as_ref()));
+}
+
+/// Projects active this semester, a page at a time.
+#[get("/projects")]
+async fn projects(Query(query): Query<ProjectsQuery>) -> Result<HttpResponse, TelescopeError> {
+    let page: u32 = query.page.unwrap_or(1).max(1);
+    let data = CurrentProjects::get(page - 1, query.search).await?;
+    return Ok(HttpResponse::Ok().json(json!({
+        "page": page,
+        "per_page": PER_PAGE,
+        "total": data.projects_aggregate.aggregate.map(|aggregate| aggregate.count),
+        "projects": data.projects,
+    })));
+}
+
+/// The current sponsors, in tier order.
+#[get("/sponsors")]
+async fn sponsors() -> Result<HttpResponse, TelescopeError> {
+    let sponsors = CurrentSponsors::get().await?;
+    return Ok(HttpResponse::Ok().json(sponsors));
+}
@@ -0,0 +1,154 @@
+//! JSON data for the navbar quick switcher.
+//!
+//! The quick switcher (`static/scripts/quick_nav.js`) fetches this once when
+//! it is opened, and filters it as the user types.
+
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::api::rcos::users::quick_nav::{
+    quick_nav::{QuickNavMeeting, QuickNavSmallGroup},
+    QuickNav,
+};
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use crate::web::services::auth::identity::Identity;
+use actix_web::http::header::CACHE_CONTROL;
+use actix_web::HttpResponse;
+use chrono::Local;
+use std::collections::HashSet;
+
+/// Pages every visitor can jump to.
+const PAGES: [(&'static str, &'static str); 6] = [
+    ("Home", "/"),
+    ("Projects", "/projects"),
+    ("Developers", "/developers"),
+    ("Meetings", "/meetings"),
+    ("Sponsors", "/sponsors"),
+    ("Search", "/search"),
+];
+
+/// A place the quick switcher can go.
+#[derive(Serialize, Debug)]
+struct Destination {
+    label: String,
+    url: String,
+    /// The heading the destination is listed under.
+    group: &'static str,
+    /// Extra text shown next to the label, like a meeting's start time.
+    hint: Option<String>,
+}
+
+impl Destination {
+    /// A destination without a hint.
+    fn new(group: &'static str, label: impl Into<String>, url: impl Into<String>) -> Self {
+        Destination {
+            label: label.into(),
+            url: url.into(),
+            group,
+            hint: None,
+        }
+    }
+
+    /// A meeting destination, hinted with its start time.
+    fn meeting(group: &'static str, meeting: QuickNavMeeting) -> Self {
+        let start = meeting.start_date_time.with_timezone(&Local);
+        let type_name: String = meeting
+            .custom_type
+            .map(|custom| custom.name)
+            .unwrap_or_else(|| meeting.type_.to_string());
+        let label: String = meeting
+            .title
+            .unwrap_or_else(|| format!("RCOS {} - {}", type_name, start.format("%B %_d, %Y")));
+
+        Destination {
+            label,
+            url: format!("/meeting/{}", meeting.meeting_id),
+            group,
+            hint: Some(start.format("%a %b %-d, %-I:%M %p").to_string()),
+        }
+    }
+
+    /// A small group destination. Small groups don't have pages, so this
+    /// links to the group's Discord channel. Groups without one are skipped.
+    fn small_group(group: QuickNavSmallGroup) -> Option<Self> {
+        let channel = group.small_group_channels.first()?;
+        let url = format!(
+            "https://discord.com/channels/{}/{}",
+            global_config().discord_config.rcos_guild_id(),
+            channel.channel_id
+        );
+
+        return Some(Destination {
+            hint: Some("Discord".into()),
+            ..Destination::new("Small Groups", group.title, url)
+        });
+    }
+}
+
+/// Get the destinations for the viewer's quick switcher.
+#[get("/api/quick-nav")]
+pub async fn quick_nav(identity: Identity) -> Result<HttpResponse, TelescopeError> {
+    let mut destinations: Vec<Destination> = PAGES
+        .iter()
+        .map(|(label, url)| Destination::new("Pages", *label, *url))
+        .collect();
+
+    if let Some(user_id) = identity.get_user_id().await? {
+        let authorization = AuthorizationFor::get(Some(user_id)).await?;
+        let data = QuickNav::get(user_id, &authorization).await?;
+
+        let profile: String = data
+            .user
+            .and_then(|user| user.username)
+            .unwrap_or_else(|| user_id.to_string());
+        destinations.push(Destination::new(
+            "You",
+            "Profile",
+            format!("/user/{}", profile),
+        ));
+        destinations.push(Destination::new("You", "Settings", "/edit_profile"));
+
+        // Small groups are listed once, even if several of the user's
+        // projects are in the same one.
+        let mut small_groups = Vec::new();
+        for enrollment in data.enrollments {
+            if let Some(project) = enrollment.project {
+                destinations.push(Destination::new(
+                    "Projects",
+                    project.title,
+                    format!("/project/{}", project.project_id),
+                ));
+                small_groups.extend(
+                    project
+                        .small_group_projects
+                        .into_iter()
+                        .map(|sgp| sgp.small_group),
+                );
+            }
+        }
+        small_groups.extend(data.small_group_mentors.into_iter().map(|m| m.small_group));
+
+        let mut seen: HashSet<i64> = HashSet::new();
+        destinations.extend(
+            small_groups
+                .into_iter()
+                .filter(|group| seen.insert(group.small_group_id))
+                .filter_map(Destination::small_group),
+        );
+
+        destinations.extend(
+            data.upcoming
+                .into_iter()
+                .map(|m| Destination::meeting("Upcoming Meetings", m)),
+        );
+        destinations.extend(
+            data.drafts
+                .into_iter()
+                .map(|m| Destination::meeting("Drafts", m)),
+        );
+    }
+
+    return Ok(HttpResponse::Ok()
+        // Destinations depend on who is logged in.
+        .header(CACHE_CONTROL, "private, no-store")
+        .json(json!({ "destinations": destinations })));
+}
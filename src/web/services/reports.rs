@@ -0,0 +1,130 @@
+//! Reporting user-generated content to moderators.
+//!
+//! Logged-in members can report a status update, a profile, or a question
+//! asked during a meeting. Reports keep a copy of the content as it was when
+//! reported and go to the moderation queue for coordinators to decide on.
+
+use crate::api::rcos::moderation::get::{self as moderation, ContentSnapshot};
+use crate::api::rcos::moderation::mutations::FileReport;
+use crate::api::rcos::moderation::{ContentRef, ReportedContent};
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::web::{Form, Query, ServiceConfig};
+use actix_web::HttpRequest;
+
+/// The path to the report form from the templates directory.
+const FORM_TEMPLATE: &'static str = "reports/form";
+
+/// The longest reason that can be given for a report.
+const MAX_REASON_LENGTH: usize = 1000;
+
+/// Register report services.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(form).service(submit);
+}
+
+/// The content a report is about, as given in the query string or form.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ContentQuery {
+    content: ReportedContent,
+    key: String,
+}
+
+/// Form submitted to file a report.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ReportForm {
+    content: ReportedContent,
+    key: String,
+    reason: String,
+}
+
+/// Find the content a report is about, or return a not found error.
+async fn find(
+    content_type: ReportedContent,
+    key: &str,
+) -> Result<(ContentRef, ContentSnapshot), TelescopeError> {
+    let not_found = || {
+        TelescopeError::resource_not_found(
+            "Content Not Found",
+            "Could not find the content to report. It may have been deleted.",
+        )
+    };
+
+    let content: ContentRef = ContentRef::parse(content_type, key).ok_or_else(not_found)?;
+    let snapshot: ContentSnapshot = moderation::snapshot(content).await?.ok_or_else(not_found)?;
+    return Ok((content, snapshot));
+}
+
+/// Make the report form. The content itself is not shown, since the reporter
+/// may be reporting it from somewhere that only shows part of it.
+fn make_form(content: ContentRef, snapshot: &ContentSnapshot) -> Result<Template, TelescopeError> {
+    let mut template = Template::new(FORM_TEMPLATE);
+    template
+        .set_field("content", content.content_type())?
+        .set_field("content_name", content.content_type().to_string())?
+        .set_field(
+            "content_noun",
+            content.content_type().to_string().to_lowercase(),
+        )?
+        .set_field("key", content.key())?
+        .set_field("url", &snapshot.url)?
+        .set_field("max_length", MAX_REASON_LENGTH)?;
+    return Ok(template);
+}
+
+/// The form to report some content.
+#[get("/report")]
+async fn form(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Query(query): Query<ContentQuery>,
+) -> Result<Page, TelescopeError> {
+    auth.get_user_id_or_error().await?;
+    let (content, snapshot) = find(query.content, query.key.as_str()).await?;
+    return make_form(content, &snapshot)?
+        .in_page(&req, "Report Content")
+        .await;
+}
+
+/// File a report.
+#[post("/report")]
+async fn submit(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Form(form): Form<ReportForm>,
+) -> Result<Page, TelescopeError> {
+    let reporter = auth.get_user_id_or_error().await?;
+    let (content, snapshot) = find(form.content, form.key.as_str()).await?;
+
+    let mut template = make_form(content, &snapshot)?;
+    let reason: String = form.reason.trim().to_string();
+    template.set_field("reason", &reason)?;
+    if reason.is_empty() {
+        template.set_issue("reason", "Please say what is wrong with this content.");
+    } else if reason.chars().count() > MAX_REASON_LENGTH {
+        template.set_issue(
+            "reason",
+            format!("Cannot be longer than {} characters.", MAX_REASON_LENGTH),
+        );
+    }
+
+    if template["issues"] != json!(null) {
+        let page = template.in_page(&req, "Report Content").await?;
+        return Err(TelescopeError::InvalidForm(page));
+    }
+
+    let report_id: i64 =
+        FileReport::execute(content, snapshot.url, snapshot.preview, reporter, reason).await?;
+    info!(
+        "User {} reported {} {} (report {}).",
+        reporter,
+        content.content_type(),
+        content.key(),
+        report_id
+    );
+
+    template.set_field("filed", true)?;
+    return template.in_page(&req, "Report Filed").await;
+}
@@ -0,0 +1,43 @@
+//! Global search page.
+
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::search;
+use crate::web::services::auth::identity::Identity;
+use actix_web::web::Query;
+use actix_web::HttpRequest;
+
+/// The path to the search page template from the templates directory.
+const TEMPLATE_PATH: &'static str = "search";
+
+/// Query parameters of the search page.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct SearchQuery {
+    /// The search terms.
+    q: String,
+}
+
+/// Search meetings and status updates.
+#[get("/search")]
+pub async fn search_page(
+    req: HttpRequest,
+    identity: Identity,
+    params: Option<Query<SearchQuery>>,
+) -> Result<Page, TelescopeError> {
+    let query: Option<String> = params
+        .map(|p| p.0.q.trim().to_string())
+        .filter(|q| !q.is_empty());
+
+    let mut template = Template::new(TEMPLATE_PATH);
+    template.set_field("query", &query)?;
+
+    if let Some(query) = query {
+        let viewer = identity.get_user_id().await?;
+        let authorization = AuthorizationFor::get(viewer).await?;
+        template.set_field("results", search::search(query, authorization).await?)?;
+    }
+
+    return template.in_page(&req, "Search").await;
+}
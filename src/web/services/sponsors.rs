@@ -0,0 +1,19 @@
+//! Public sponsors page.
+
+use crate::api::rcos::sponsors::get::CurrentSponsors;
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use actix_web::HttpRequest;
+
+/// The path to the sponsors page template from the templates directory.
+const TEMPLATE_PATH: &'static str = "sponsors/page";
+
+/// Page listing the currently active sponsors in tier order.
+#[get("/sponsors")]
+pub async fn sponsors_page(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let sponsors = CurrentSponsors::get().await?;
+    let mut template = Template::new(TEMPLATE_PATH);
+    template.set_field("sponsors", sponsors)?;
+    return template.in_page(&req, "RCOS Sponsors").await;
+}
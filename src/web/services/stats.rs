@@ -0,0 +1,91 @@
+//! Public organization-wide statistics, for outreach and reports.
+//!
+//! Shows membership, project, and meeting counts for every semester as charts
+//! and tables, and serves the same counts as JSON. The counts only change
+//! slowly, so they are computed at most once a day per replica.
+
+use crate::api::rcos::semester_stats::{SemesterCounts, SemesterStatistics};
+use crate::error::TelescopeError;
+use crate::templates::page::Page;
+use crate::templates::Template;
+use actix_web::web::ServiceConfig;
+use actix_web::{HttpRequest, HttpResponse};
+use chrono::{DateTime, Duration, Utc};
+use std::sync::{Arc, RwLock};
+
+/// The path to the statistics template from the templates directory.
+const TEMPLATE_PATH: &'static str = "stats";
+
+/// How many hours computed statistics are reused before being recomputed.
+const CACHE_HOURS: i64 = 24;
+
+lazy_static! {
+    /// The most recently computed statistics.
+    static ref STATS_CACHE: RwLock<Option<Arc<OrgStatistics>>> = RwLock::new(None);
+}
+
+/// Register statistics services.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(stats_page).service(stats_json);
+}
+
+/// Statistics for every semester so far.
+#[derive(Serialize, Debug)]
+pub(super) struct OrgStatistics {
+    /// When these statistics were computed.
+    computed_at: DateTime<Utc>,
+    semesters: Vec<SemesterCounts>,
+}
+
+/// Get the statistics from the cache, recomputing them if they are missing or
+/// stale.
+pub(super) async fn get_statistics() -> Result<Arc<OrgStatistics>, TelescopeError> {
+    // Check the cache first.
+    if let Some(cached) = STATS_CACHE.read().unwrap().as_ref() {
+        if Utc::now() - cached.computed_at < Duration::hours(CACHE_HOURS) {
+            return Ok(cached.clone());
+        }
+    }
+
+    let computed = Arc::new(OrgStatistics {
+        computed_at: Utc::now(),
+        semesters: SemesterStatistics::get().await?,
+    });
+    *STATS_CACHE.write().unwrap() = Some(computed.clone());
+    return Ok(computed);
+}
+
+/// Statistics page with a chart and table of each count per semester.
+#[get("/stats")]
+async fn stats_page(req: HttpRequest) -> Result<Page, TelescopeError> {
+    let stats = get_statistics().await?;
+    let semesters: &[SemesterCounts] = stats.semesters.as_slice();
+
+    let mut template = Template::new(TEMPLATE_PATH);
+    template
+        .set_field("stats", stats.as_ref())?
+        .set_field(
+            "labels",
+            semesters.iter().map(|s| &s.title).collect::<Vec<_>>(),
+        )?
+        .set_field(
+            "members",
+            semesters.iter().map(|s| &s.members).collect::<Vec<_>>(),
+        )?
+        .set_field(
+            "projects",
+            semesters.iter().map(|s| &s.projects).collect::<Vec<_>>(),
+        )?
+        .set_field(
+            "meetings",
+            semesters.iter().map(|s| &s.meetings).collect::<Vec<_>>(),
+        )?;
+    return template.in_page(&req, "Statistics").await;
+}
+
+/// The statistics as JSON, for building other charts and reports.
+#[get("/stats.json")]
+async fn stats_json() -> Result<HttpResponse, TelescopeError> {
+    let stats = get_statistics().await?;
+    return Ok(HttpResponse::Ok().json(stats.as_ref()));
+}
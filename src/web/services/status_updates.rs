@@ -0,0 +1,285 @@
+//! Weekly status updates.
+//!
+//! Coordinators open a status update for a week of the semester. Members
+//! enrolled in the semester submit what they did that week, what they plan
+//! to do next, and what is blocking them, and can change their submission
+//! until it closes or is graded. Mentors browse the submissions from their
+//! small group's projects. Submissions are markdown, rendered like the rest of
+//! the site's markdown.
+
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::api::rcos::status_updates::get::{
+    SmallGroupStatusUpdates, SmallGroupSubmissions, StatusUpdateOverview, StatusUpdateSubmission,
+};
+use crate::api::rcos::status_updates::mutations::SubmitStatusUpdate;
+use crate::error::TelescopeError;
+use crate::templates::forms::{Field, FieldKind, FormSchema};
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, Query, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use chrono::Utc;
+use uuid::Uuid;
+
+/// The path to the status update list from the templates directory.
+const INDEX_TEMPLATE: &'static str = "status_updates/index";
+
+/// The path to the submission form from the templates directory.
+const FORM_TEMPLATE: &'static str = "status_updates/form";
+
+/// The path to a small group's submissions from the templates directory.
+const FEED_TEMPLATE: &'static str = "status_updates/feed";
+
+/// The longest each part of a submission can be.
+const MAX_SECTION_LENGTH: usize = 5000;
+
+/// The fields of the submission form.
+const SUBMISSION_FORM: FormSchema = FormSchema {
+    fields: &[
+        Field::new(
+            "this_week",
+            "What did you do this week?",
+            FieldKind::TextArea,
+        )
+        .required()
+        .max_length(MAX_SECTION_LENGTH)
+        .help("Link the commits, issues, and pull requests you worked on. Markdown is supported."),
+        Field::new(
+            "next_week",
+            "What will you do next week?",
+            FieldKind::TextArea,
+        )
+        .required()
+        .max_length(MAX_SECTION_LENGTH),
+        Field::new(
+            "blockers",
+            "Is anything blocking you? (optional)",
+            FieldKind::TextArea,
+        )
+        .max_length(MAX_SECTION_LENGTH),
+    ],
+};
+
+/// Register status update services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(index)
+        .service(submission_form)
+        .service(submit)
+        .service(feed);
+}
+
+/// A submitted status update.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct SubmissionForm {
+    #[serde(default)]
+    this_week: String,
+    #[serde(default)]
+    next_week: String,
+    #[serde(default)]
+    blockers: String,
+}
+
+/// Query on the small group feed, to pick a status update other than the
+/// latest.
+#[derive(Deserialize, Debug)]
+struct FeedQuery {
+    status_update: Option<i64>,
+}
+
+/// A status update on the list, marked if it still takes submissions.
+#[derive(Serialize)]
+struct StatusUpdateView<'a, T> {
+    #[serde(flatten)]
+    status_update: &'a T,
+    is_open: bool,
+}
+
+/// This semester's status updates with the viewer's submissions, and the
+/// small groups whose submissions they can browse.
+#[get("/status_updates")]
+async fn index(req: HttpRequest, auth: AuthenticationCookie) -> Result<Page, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    let all_groups: bool = AuthorizationFor::get(Some(viewer)).await?.can_view_drafts();
+
+    let overview = StatusUpdateOverview::get(viewer, all_groups).await?;
+    let now = Utc::now();
+    let status_updates: Vec<StatusUpdateView<_>> = overview
+        .status_updates
+        .iter()
+        .map(|status_update| StatusUpdateView {
+            status_update,
+            is_open: now <= status_update.close_date_time,
+        })
+        .collect();
+
+    let mut template = Template::new(INDEX_TEMPLATE);
+    template
+        .set_field("status_updates", status_updates)?
+        .set_field("is_enrolled", !overview.enrollments.is_empty())?
+        .set_field("small_groups", &overview.small_groups)?;
+    return template.in_page(&req, "Status Updates").await;
+}
+
+/// Get a status update the viewer can submit to, and make the form for it
+/// with their earlier submission filled in.
+async fn make_form(status_update_id: i64, viewer: Uuid) -> Result<Template, TelescopeError> {
+    let status_update = StatusUpdateSubmission::get(status_update_id, viewer)
+        .await?
+        .ok_or_else(|| {
+            TelescopeError::resource_not_found(
+                "Status Update Not Found",
+                "Could not find a status update with this ID.",
+            )
+        })?;
+
+    if status_update.semester.enrollments.is_empty() {
+        return Err(TelescopeError::BadRequest {
+            header: "Not Enrolled".into(),
+            message: format!(
+                "Only members enrolled in {} can submit this status update.",
+                status_update.semester.title
+            ),
+            show_status_code: false,
+        });
+    }
+
+    let now = Utc::now();
+    if now < status_update.open_date_time || now > status_update.close_date_time {
+        return Err(TelescopeError::BadRequest {
+            header: "Status Update Closed".into(),
+            message: "This status update is not open for submissions.".into(),
+            show_status_code: false,
+        });
+    }
+
+    let earlier = status_update.submission.first();
+    if earlier.map_or(false, |submission| submission.grade.is_some()) {
+        return Err(TelescopeError::BadRequest {
+            header: "Status Update Graded".into(),
+            message: "Your submission to this status update has been graded and can no longer \
+            be changed."
+                .into(),
+            show_status_code: false,
+        });
+    }
+
+    let mut form = Template::new(FORM_TEMPLATE);
+    form.set_field("selections", &earlier)?
+        .set_field("resubmission", earlier.is_some())?
+        .set_field("status_update", status_update)?
+        .set_field("schema", SUBMISSION_FORM)?;
+    return Ok(form);
+}
+
+/// Form to submit a status update.
+#[get("/status_updates/{status_update_id}/submit")]
+async fn submission_form(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Path(status_update_id): Path<i64>,
+) -> Result<Page, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    make_form(status_update_id, viewer)
+        .await?
+        .in_page(&req, "Submit Status Update")
+        .await
+}
+
+/// Save a submitted status update.
+#[post("/status_updates/{status_update_id}/submit")]
+async fn submit(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Path(status_update_id): Path<i64>,
+    Form(submission): Form<SubmissionForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    let mut form = make_form(status_update_id, viewer).await?;
+    form.set_field("selections", &submission)?;
+
+    if !SUBMISSION_FORM.validate(&mut form, &json!(&submission)) {
+        let page = form.in_page(&req, "Submit Status Update").await?;
+        return Err(TelescopeError::InvalidForm(page));
+    }
+
+    SubmitStatusUpdate::execute(
+        status_update_id,
+        viewer,
+        submission.this_week.trim().to_string(),
+        submission.next_week.trim().to_string(),
+        submission.blockers.trim().to_string(),
+    )
+    .await?;
+
+    return Ok(HttpResponse::Found()
+        .header(LOCATION, "/status_updates")
+        .finish());
+}
+
+/// Submissions to a status update from a small group. Only the group's
+/// mentors and coordinators can see them.
+#[get("/status_updates/small_group/{small_group_id}")]
+async fn feed(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Path(small_group_id): Path<i64>,
+    query: Query<FeedQuery>,
+) -> Result<Page, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    let group = SmallGroupStatusUpdates::get(small_group_id)
+        .await?
+        .ok_or_else(|| {
+            TelescopeError::resource_not_found(
+                "Small Group Not Found",
+                "Could not find a small group with this ID.",
+            )
+        })?;
+
+    // Coordinators also see submissions hidden by a moderator.
+    let is_coordinator: bool = AuthorizationFor::get(Some(viewer)).await?.can_view_drafts();
+    let is_mentor: bool = group
+        .small_group_mentors
+        .iter()
+        .any(|mentor| mentor.user_id == viewer);
+    if !is_mentor && !is_coordinator {
+        return Err(TelescopeError::Forbidden);
+    }
+
+    // Show the latest status update unless another one of the semester's
+    // was picked.
+    let selected: Option<i64> = query
+        .status_update
+        .filter(|id| {
+            group
+                .semester
+                .status_updates
+                .iter()
+                .any(|status_update| status_update.status_update_id == *id)
+        })
+        .or_else(|| {
+            group
+                .semester
+                .status_updates
+                .first()
+                .map(|status_update| status_update.status_update_id)
+        });
+
+    let submissions = match selected {
+        Some(status_update_id) => {
+            SmallGroupSubmissions::get(small_group_id, status_update_id, is_coordinator).await?
+        }
+        None => Vec::new(),
+    };
+
+    let mut template = Template::new(FEED_TEMPLATE);
+    template
+        .set_field("group", &group)?
+        .set_field("selected", selected)?
+        .set_field("submissions", submissions)?;
+    return template
+        .in_page(&req, format!("{} Status Updates", group.title))
+        .await;
+}
@@ -0,0 +1,96 @@
+//! Render the main pages against the API fixtures in `fixtures/rcos`.
+//!
+//! Tests load `config_example.toml` with the offline profile, so every API
+//! query is answered from a fixture file instead of the RCOS API.
+
+use actix_identity::{CookieIdentityPolicy, IdentityService};
+use actix_web::dev::ServiceResponse;
+use actix_web::http::StatusCode;
+use actix_web::{test, App};
+
+/// Send a GET request as an anonymous viewer.
+async fn send(uri: &str) -> ServiceResponse {
+    let mut app = test::init_service(
+        App::new()
+            .wrap(IdentityService::new(
+                CookieIdentityPolicy::new(&[0; 32]).secure(false),
+            ))
+            .configure(super::register),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri(uri).to_request();
+    return test::call_service(&mut app, req).await;
+}
+
+/// Render a page, check that it worked, and return the body.
+async fn render(uri: &str) -> String {
+    let response: ServiceResponse = send(uri).await;
+    assert_eq!(response.status(), StatusCode::OK, "GET {}", uri);
+
+    let body = test::read_body(response).await;
+    return String::from_utf8(body.to_vec()).expect("Pages are UTF-8");
+}
+
+#[actix_rt::test]
+async fn homepage() {
+    let body: String = render("/").await;
+    // Statistics from the landing page fixture.
+    assert!(body.contains("142"));
+    assert!(body.contains("1893"));
+    // Custom pages are linked in the navbar.
+    assert!(body.contains("/p/handbook"));
+}
+
+#[actix_rt::test]
+async fn sponsors() {
+    render("/sponsors").await;
+}
+
+#[actix_rt::test]
+async fn custom_page() {
+    let body: String = render("/p/handbook").await;
+    assert!(body.contains("How RCOS works."));
+}
+
+#[actix_rt::test]
+async fn missing_custom_page() {
+    let response: ServiceResponse = send("/p/missing").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_rt::test]
+async fn meetings() {
+    let body: String = render("/meetings").await;
+    assert!(body.contains("Kickoff"));
+}
+
+#[actix_rt::test]
+async fn developers() {
+    let body: String = render("/developers").await;
+    assert!(body.contains("Avery"));
+}
+
+#[actix_rt::test]
+async fn badges() {
+    let body: String = render("/badges").await;
+    assert!(body.contains("First Commit"));
+}
+
+#[actix_rt::test]
+async fn statistics() {
+    let body: String = render("/stats").await;
+    assert!(body.contains("Spring 2022"));
+}
+
+#[actix_rt::test]
+async fn organization() {
+    let body: String = render("/org/rcos").await;
+    assert!(body.contains("Rensselaer Center for Open Source"));
+}
+
+#[actix_rt::test]
+async fn organization_projects() {
+    let body: String = render("/org/rcos/projects").await;
+    assert!(body.contains("Telescope"));
+}
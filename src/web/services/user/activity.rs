@@ -0,0 +1,132 @@
+//! Activity tab of user profiles.
+//!
+//! A timeline of a user's attendance, status updates, meetings hosted, and
+//! repository contributions, newest first. Attendance and status updates are
+//! only shown to the user and coordinators. Contributions follow the audience
+//! the user picked for them on their profile.
+
+use crate::api::rcos::users::activity::{ActivityKind, ActivityPage, ActivityTarget};
+use crate::api::rcos::users::display::UserDisplay;
+use crate::api::rcos::users::field_visibility::{Audience, FieldVisibility, ProfileField, Viewer};
+use crate::error::TelescopeError;
+use crate::templates::Template;
+use crate::web::services::auth::identity::Identity;
+use crate::web::services::user::profile::{lookup_address, user_not_found, AddressedUser};
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Path, Query, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse, Responder};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// The path to the activity template from the templates directory.
+const TEMPLATE_PATH: &'static str = "user/activity";
+
+/// How many entries to show on each page of the timeline.
+const PAGE_SIZE: usize = 25;
+
+/// Register activity timeline services.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(activity);
+}
+
+/// Query parameters of the activity timeline.
+#[derive(Deserialize, Debug)]
+struct ActivityQuery {
+    /// Only show one kind of activity.
+    kind: Option<ActivityKind>,
+    /// Only show activity from before this time. Used for pagination.
+    before: Option<DateTime<Utc>>,
+}
+
+/// Can a viewer in an audience see a kind of activity on a user's timeline?
+fn can_see(kind: ActivityKind, audience: Audience, visibility: &FieldVisibility) -> bool {
+    match kind {
+        ActivityKind::Attendance | ActivityKind::StatusUpdate => audience >= Audience::Coordinators,
+        ActivityKind::Hosted => true,
+        ActivityKind::Contribution => visibility.shows(ProfileField::Contributions, audience),
+    }
+}
+
+/// A user's activity timeline. The user's ID or username is in the path.
+#[get("/user/{id}/activity")]
+async fn activity(
+    req: HttpRequest,
+    identity: Identity,
+    Path(id): Path<String>,
+    Query(query): Query<ActivityQuery>,
+) -> Result<HttpResponse, TelescopeError> {
+    let id: Uuid = match lookup_address(&id).await? {
+        AddressedUser::Found(id) => id,
+        AddressedUser::Moved(current) => {
+            return Ok(HttpResponse::MovedPermanently()
+                .header(LOCATION, format!("/user/{}/activity", current))
+                .finish());
+        }
+    };
+
+    let mut target = ActivityTarget::get(id).await?.ok_or_else(user_not_found)?;
+    let viewer = Viewer::get(identity.get_user_id().await?).await?;
+    let audience: Audience = viewer.audience_for(target.id);
+
+    // Hidden profiles are only shown to their owner and coordinators.
+    if target.profile_hidden && audience < Audience::Coordinators {
+        return Err(user_not_found());
+    }
+
+    let visibility = FieldVisibility::from_rows(
+        target
+            .field_visibility
+            .iter()
+            .map(|row| (row.field.as_str(), row.audience.as_str())),
+    );
+    if !visibility.shows(ProfileField::Pronouns, audience) {
+        target.pronouns = None;
+    }
+
+    let visible: Vec<ActivityKind> = ActivityKind::ALL
+        .iter()
+        .copied()
+        .filter(|kind| can_see(*kind, audience, &visibility))
+        .collect();
+    let kinds: Vec<ActivityKind> = visible
+        .iter()
+        .copied()
+        .filter(|kind| query.kind.map_or(true, |only| only == *kind))
+        .collect();
+
+    let page = ActivityPage::get(
+        target.id,
+        &kinds,
+        query.before.unwrap_or_else(Utc::now),
+        PAGE_SIZE,
+        viewer.is_coordinator,
+    )
+    .await?;
+
+    let name: String = UserDisplay::new(
+        target.first_name.as_str(),
+        target.last_name.as_str(),
+        target.preferred_name.clone(),
+        None,
+    )
+    .name();
+
+    let mut template = Template::new(TEMPLATE_PATH);
+    template
+        .set_field("target", target)?
+        .set_field("page", &page)?
+        .set_field("kind", &query.kind)?
+        .set_field("paged", query.before.is_some())?
+        .set_field(
+            "filters",
+            visible
+                .iter()
+                .map(|kind| json!({"name": kind, "label": kind.label()}))
+                .collect::<Vec<_>>(),
+        )?;
+
+    let page = template
+        .in_page(&req, format!("{} - Activity", name))
+        .await?;
+    return page.respond_to(&req).await;
+}
@@ -0,0 +1,118 @@
+//! Marking students as alumni, either by themselves or in bulk by coordinators
+//! at the end of a semester.
+
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::api::rcos::users::alumni::{GraduationCandidates, LatestSemester, MarkAlumni};
+use crate::error::TelescopeError;
+use crate::templates::cache::{self, Dependency};
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Query, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// The path to the graduate marking template from the templates directory.
+const TEMPLATE_PATH: &'static str = "user/alumni";
+
+/// Register alumni services.
+pub fn register(config: &mut ServiceConfig) {
+    config
+        .service(mark_self)
+        .service(graduates)
+        .service(mark_graduates);
+}
+
+/// Semester to list students from.
+#[derive(Deserialize, Debug)]
+struct SemesterQuery {
+    semester_id: String,
+}
+
+/// Make sure the viewer can mark other users as alumni.
+async fn authorize(auth: &AuthenticationCookie) -> Result<(), TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    // Coordinators, faculty advisors, and sysadmins can mark graduates.
+    if !AuthorizationFor::get(Some(viewer)).await?.can_view_drafts() {
+        return Err(TelescopeError::Forbidden);
+    }
+    return Ok(());
+}
+
+/// Mark the viewer as an alum.
+#[post("/profile/alumni")]
+async fn mark_self(auth: AuthenticationCookie) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    MarkAlumni::execute(vec![viewer]).await?;
+    cache::invalidate(Dependency::Users);
+    return Ok(HttpResponse::Found()
+        .header(LOCATION, format!("/user/{}", viewer))
+        .finish());
+}
+
+/// List the students in a semester (by default the latest one) so coordinators
+/// can mark the ones who graduated.
+#[get("/alumni/mark")]
+async fn graduates(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    query: Option<Query<SemesterQuery>>,
+) -> Result<Page, TelescopeError> {
+    authorize(&auth).await?;
+
+    let semester_id: String = match query {
+        Some(Query(query)) => query.semester_id,
+        None => LatestSemester::get().await?.ok_or_else(|| {
+            TelescopeError::resource_not_found(
+                "No Semesters",
+                "There are no semesters to mark graduates from yet.",
+            )
+        })?,
+    };
+
+    let candidates = GraduationCandidates::get(semester_id).await?;
+    if candidates.semester.is_none() {
+        return Err(TelescopeError::resource_not_found(
+            "Semester Not Found",
+            "Could not find a semester with this ID.",
+        ));
+    }
+
+    let mut template = Template::new(TEMPLATE_PATH);
+    template.set_field("data", candidates)?;
+    return template.in_page(&req, "Mark Graduates").await;
+}
+
+/// Mark the selected students as alumni. The form has a checkbox named by
+/// each student's user ID.
+#[post("/alumni/mark")]
+async fn mark_graduates(
+    auth: AuthenticationCookie,
+    Form(form): Form<HashMap<String, String>>,
+) -> Result<HttpResponse, TelescopeError> {
+    authorize(&auth).await?;
+
+    let user_ids: Vec<Uuid> = form
+        .keys()
+        .filter_map(|key| key.parse::<Uuid>().ok())
+        .collect();
+
+    if !user_ids.is_empty() {
+        let marked: i64 = MarkAlumni::execute(user_ids).await?;
+        info!("Marked {} students as alumni", marked);
+        cache::invalidate(Dependency::Users);
+    }
+
+    let location: String = match form.get("semester_id") {
+        Some(semester_id) => format!(
+            "/alumni/mark?{}",
+            serde_urlencoded::to_string(&[("semester_id", semester_id)])
+                .map_err(|e| TelescopeError::ise(format!("Could not encode semester ID: {}", e)))?
+        ),
+        None => "/alumni/mark".to_string(),
+    };
+
+    return Ok(HttpResponse::Found().header(LOCATION, location).finish());
+}
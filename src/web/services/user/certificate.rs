@@ -0,0 +1,123 @@
+//! End of semester participation certificates.
+//!
+//! Members can download a certificate for each semester they were enrolled in
+//! once it has ended. Coordinators can download anyone's.
+
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::api::rcos::prelude::SemesterId;
+use crate::api::rcos::users::certificate::Certificate;
+use crate::api::rcos::users::display::UserDisplay;
+use crate::error::TelescopeError;
+use crate::web::pdf::{PdfBuilder, Style, LETTER_HEIGHT, LETTER_WIDTH};
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::web::{Path, ServiceConfig};
+use actix_web::HttpResponse;
+use chrono::Local;
+use uuid::Uuid;
+
+/// Register certificate services.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(download);
+}
+
+/// Check if a viewer can download a user's certificates.
+async fn can_download(viewer: Uuid, user_id: Uuid) -> Result<bool, TelescopeError> {
+    Ok(viewer == user_id || AuthorizationFor::get(Some(viewer)).await?.can_view_drafts())
+}
+
+/// Download a user's participation certificate for a semester.
+#[get("/user/{user_id}/certificate/{semester_id}")]
+async fn download(
+    auth: AuthenticationCookie,
+    Path((user_id, semester_id)): Path<(Uuid, SemesterId)>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = auth.get_user_id_or_error().await?;
+    if !can_download(viewer, user_id).await? {
+        return Err(TelescopeError::Forbidden);
+    }
+
+    let data = Certificate::get(user_id, semester_id).await?;
+    let not_enrolled = || {
+        TelescopeError::resource_not_found(
+            "No Certificate",
+            "This user was not enrolled in this semester.",
+        )
+    };
+    let semester = data.semester.ok_or_else(not_enrolled)?;
+    let user = data.user.ok_or_else(not_enrolled)?;
+    let enrollment = data.enrollment.ok_or_else(not_enrolled)?;
+
+    if semester.end_date >= Local::today().naive_local() {
+        return Err(TelescopeError::BadRequest {
+            header: "Semester Not Over".into(),
+            message: "Certificates are available once the semester has ended.".into(),
+            show_status_code: false,
+        });
+    }
+
+    let name: String =
+        UserDisplay::new(user.first_name, user.last_name, user.preferred_name, None).name();
+
+    // What the member did this semester, most notable first.
+    let mut roles: Vec<String> = Vec::new();
+    if enrollment.is_coordinator {
+        roles.push("as a Coordinator".into());
+    }
+    for mentoring in data.mentoring.iter() {
+        roles.push(format!("as a mentor of {}", mentoring.small_group.title));
+    }
+    if let Some(project) = enrollment.project.as_ref() {
+        if enrollment.is_project_lead {
+            roles.push(format!("leading {}", project.title));
+        } else {
+            roles.push(format!("contributing to {}", project.title));
+        }
+    }
+
+    let attended: i64 = data
+        .attended
+        .aggregate
+        .map(|aggregate| aggregate.count)
+        .unwrap_or(0);
+
+    // Landscape letter page.
+    let title = format!("RCOS Certificate of Participation - {}", name);
+    let mut pdf = PdfBuilder::new(title.as_str(), LETTER_HEIGHT, LETTER_WIDTH)?;
+    pdf.border(10.0, 1.5);
+    pdf.border(13.0, 0.5);
+
+    pdf.move_to(0.15);
+    pdf.centered("Rensselaer Center for Open Source", 16.0, Style::Serif);
+    pdf.space(6.0);
+    pdf.centered("Certificate of Participation", 32.0, Style::SerifBold);
+    pdf.space(10.0);
+    pdf.centered("This certifies that", 14.0, Style::Serif);
+    pdf.space(4.0);
+    pdf.centered(name.as_str(), 28.0, Style::SerifBold);
+    pdf.space(4.0);
+    pdf.centered(
+        format!("participated in RCOS during {}", semester.title).as_str(),
+        14.0,
+        Style::Serif,
+    );
+    for role in roles.iter() {
+        pdf.centered(role.as_str(), 14.0, Style::Serif);
+    }
+    if attended > 0 {
+        pdf.space(4.0);
+        pdf.centered(
+            format!("and attended {} meetings.", attended).as_str(),
+            12.0,
+            Style::Serif,
+        );
+    }
+
+    pdf.move_to(0.82);
+    pdf.centered(
+        format!("Issued {}", Local::today().format("%B %-d, %Y")).as_str(),
+        11.0,
+        Style::Serif,
+    );
+
+    return pdf.into_response(format!("rcos-certificate-{}.pdf", semester.semester_id));
+}
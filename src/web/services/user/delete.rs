@@ -3,8 +3,10 @@ use crate::api::rcos::users::accounts::lookup::AccountLookup;
 use crate::api::rcos::users::{delete::DeleteUser, profile::Profile, UserAccountType};
 use crate::env::global_config;
 use crate::error::TelescopeError;
+use crate::templates::cache::{self, Dependency};
 use crate::templates::page::Page;
 use crate::templates::{jumbotron, Template};
+use crate::web::deadline;
 use crate::web::services::auth::identity::{AuthenticationCookie, Identity};
 use actix_web::{HttpRequest, Responder};
 
@@ -46,14 +48,17 @@ pub async fn profile_delete(
         let rcos_guild = global_config().discord_config.rcos_guild_id();
 
         // Kick the user from the RCOS guild.
-        global_discord_client()
-            .kick_member(rcos_guild, discord_id)
-            .await
-            .map_err(TelescopeError::serenity_error)?;
+        deadline::within(
+            "Discord",
+            global_discord_client().kick_member(rcos_guild, discord_id),
+        )
+        .await?
+        .map_err(TelescopeError::serenity_error)?;
     }
 
     // Execute the user deletion.
     DeleteUser::execute(user_id).await?;
+    cache::invalidate(Dependency::Users);
 
     // Clear the user's cookies.
     identity.forget();
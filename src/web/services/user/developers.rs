@@ -1,20 +1,28 @@
 //! Developers page services
 
 use actix_web::web::{self as aweb, Path, Query, ServiceConfig};
-use actix_web::HttpRequest;
+use actix_web::{HttpRequest, HttpResponse, Responder};
 use serde_json::Value;
 use uuid::Uuid;
 
 use crate::api::rcos::users::developers_page::{AllDevelopers, CurrentDevelopers, PER_PAGE};
+use crate::api::rcos::users::field_visibility::Viewer;
+use crate::api::rcos::users::role_lookup::RoleLookup;
+use crate::api::rcos::users::UserRole;
 use crate::error::TelescopeError;
+use crate::templates::cache::{CachePolicy, Dependency};
 use crate::templates::page::Page;
-use crate::templates::pagination::PaginationInfo;
+use crate::templates::pagination::{PaginationInfo, PaginationParameter};
 use crate::templates::Template;
 use crate::web::services::auth::identity::Identity;
+use crate::web::services::user::saved_searches::{self, SearchPage};
 
 /// The path to the developers page template from the templates directory.
 const TEMPLATE_PATH: &'static str = "user/developers";
 
+/// What the rendered developers page is built from.
+const DEPENDENCIES: [Dependency; 2] = [Dependency::Users, Dependency::Badges];
+
 /// The query parameters passed to the developers page indicating pagination
 /// data and any filters.
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
@@ -35,8 +43,11 @@ pub fn register_services(conf: &mut ServiceConfig) {
 }
 
 /// Try to get the pagination bar to use based on the api data.
-/// Panics if `current_page` is 0.
-fn get_page_numbers(api_response: &Value, current_page: u64) -> Option<PaginationInfo> {
+fn get_page_numbers(
+    api_response: &Value,
+    page: PaginationParameter,
+    query_string: &str,
+) -> Option<PaginationInfo> {
     api_response
         // Check for the JSON field user_count
         .get("user_count")?
@@ -47,7 +58,7 @@ fn get_page_numbers(api_response: &Value, current_page: u64) -> Option<Paginatio
         // As an unsigned integer
         .as_u64()
         // Convert to pagination info
-        .and_then(|count| PaginationInfo::new(count, PER_PAGE as u64, current_page))
+        .and_then(|count| PaginationInfo::new(count, page, "/developers/", query_string))
 }
 
 /// The developer catalogue. This page displays all of the users in the
@@ -57,45 +68,63 @@ pub async fn developers_page(
     identity: Identity,
     page: Option<Path<u32>>,
     Query(query): Query<DevelopersPageQuery>,
-) -> Result<Page, TelescopeError> {
-    // Resolve the page number from the request
-    let page_num: u32 = page
-        // Extract from path if available.
-        .map(|page_path| page_path.0)
-        // Filter and subtract 1, since the page numbers in the UI index from 1.
-        // Filter first since subtracting first could result in underflow.
-        .filter(|p| *p >= 1)
-        .map(|p| p - 1)
-        // Otherwise default to 0
-        .unwrap_or(0);
+) -> Result<HttpResponse, TelescopeError> {
+    // Get the viewers user ID
+    let viewer: Option<Uuid> = identity.get_user_id().await?;
+
+    // Open the viewer's default filters if they have some and didn't pick any.
+    let saved = saved_searches::get(viewer, SearchPage::Developers).await?;
+    if let Some(redirect) = saved_searches::default_redirect(&req, SearchPage::Developers, &saved) {
+        return Ok(redirect);
+    }
+
+    // Resolve the page from the request.
+    let page = PaginationParameter::from_page(page.map(|path| path.0), PER_PAGE);
+
+    // Fields users have hidden are removed from the API data depending on
+    // who is looking.
+    let field_viewer: Viewer = Viewer::get(viewer).await?;
 
     // Get the API data by sending one of the developer page queries.
     let api_data: Value;
     // Determine which API query to send using the request query.
     if query.include_old {
         // Get all the developers (including ones not active this semester).
-        let query_response = AllDevelopers::get(page_num, query.search.clone()).await?;
+        let query_response = AllDevelopers::get(page, query.search.clone(), &field_viewer).await?;
         // Convert the response into a JSON value.
         // Unwrap because this conversion should never fail.
         api_data = serde_json::to_value(query_response).unwrap();
     } else {
         // Get only the current developers.
-        let query_response = CurrentDevelopers::get(page_num, query.search.clone()).await?;
+        let query_response =
+            CurrentDevelopers::get(page, query.search.clone(), &field_viewer).await?;
         api_data = serde_json::to_value(query_response).unwrap();
     }
 
-    // Get the viewers user ID
-    let viewer: Option<Uuid> = identity.get_user_id().await?;
-
     // Build developers page template.
     let mut template = Template::new(TEMPLATE_PATH);
     template.fields = json!({
-        "pagination": get_page_numbers(&api_data, page_num as u64 + 1),
+        "pagination": get_page_numbers(&api_data, page, req.query_string()),
         "data": api_data,
         "query": query,
-        "identity": viewer,
-        "preserved_query_string": req.query_string()
+        // Only whether the viewer is logged in matters, so renders can be
+        // shared between viewers.
+        "identity": viewer.is_some(),
+        "saved_searches": saved_searches::template_fields(&req, SearchPage::Developers, &saved),
     });
 
-    return template.in_page(&req, "Developers").await;
+    // Renders are shared between viewers with the same role.
+    let role: Option<UserRole> = match viewer {
+        Some(user_id) => RoleLookup::get(user_id).await?,
+        None => None,
+    };
+
+    let page: Page = template
+        .in_page(&req, "Developers")
+        .await?
+        .cached(CachePolicy {
+            role,
+            dependencies: &DEPENDENCIES,
+        });
+    return page.respond_to(&req).await;
 }
@@ -2,9 +2,11 @@
 
 use crate::api::discord::{global_discord_client, rcos_discord_verified_role_id};
 use crate::api::rcos::users::discord_whois::DiscordWhoIs;
+use crate::discord_bot::{self, DiscordMessage};
 use crate::error::TelescopeError;
 
 use crate::env::global_config;
+use crate::web::deadline;
 use crate::web::services::auth::identity::AuthenticationCookie;
 use actix_web::HttpResponse;
 use reqwest::header::LOCATION;
@@ -106,10 +108,24 @@ pub async fn handle(auth: AuthenticationCookie) -> Result<HttpResponse, Telescop
     let rcos_discord_guild = global_config().discord_config.rcos_guild_id();
 
     // Make the call to add the verified role
-    global_discord_client()
-        .add_member_role(rcos_discord_guild, discord_user_id, verified_role.0)
-        .await
-        .map_err(TelescopeError::serenity_error)?;
+    deadline::within(
+        "Discord",
+        global_discord_client().add_member_role(
+            rcos_discord_guild,
+            discord_user_id,
+            verified_role.0,
+        ),
+    )
+    .await?
+    .map_err(TelescopeError::serenity_error)?;
+
+    // Give the user their enrollment roles now that they are in the server.
+    if let Err(e) = discord_bot::send(DiscordMessage::SyncEnrollmentRoles { user_id }) {
+        warn!(
+            "Could not sync the enrollment roles of user {}: {}",
+            user_id, e
+        );
+    }
 
     // On success, redirect user back to their profile.
     Ok(HttpResponse::Found()
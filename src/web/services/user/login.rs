@@ -11,7 +11,7 @@ use actix_web::{HttpRequest, HttpResponse};
 /// Login page. Users go here and are presented options to login with a variety
 /// of identity providers.
 pub async fn login_page(req: HttpRequest) -> Result<Page, TelescopeError> {
-    auth::login().in_page(&req, "RCOS Login").await
+    auth::login()?.in_page(&req, "RCOS Login").await
 }
 
 #[get("/logout")]
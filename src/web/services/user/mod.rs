@@ -2,20 +2,42 @@
 
 use actix_web::web::ServiceConfig;
 
+mod activity;
+mod alumni;
+mod certificate;
 mod delete;
 pub mod developers;
 mod join_discord;
 mod login;
 pub mod profile;
 mod register;
+pub mod saved_searches;
+mod search;
+mod username;
 
 /// Register user related services.
 pub fn register(config: &mut ServiceConfig) {
     // Developers page.
     developers::register_services(config);
 
+    // Saved filters on list pages.
+    saved_searches::register(config);
+
     // User profile and settings.
     profile::register(config);
+    username::register(config);
+
+    // Type-ahead user search.
+    search::register(config);
+
+    // Activity tab of profiles.
+    activity::register(config);
+
+    // Alumni status.
+    alumni::register(config);
+
+    // Participation certificates.
+    certificate::register(config);
 
     // Everything else
     config
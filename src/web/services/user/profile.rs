@@ -1,24 +1,39 @@
 //! Profile services.
 
 use crate::api::discord::{self, global_discord_client};
-use crate::api::rcos::users::edit_profile::{EditProfileContext, SaveProfileEdits};
+use crate::api::rcos::meetings::authorization_for::AuthorizationFor;
+use crate::api::rcos::skills::ClearUserSkills;
+use crate::api::rcos::users::display::UserDisplay;
+use crate::api::rcos::users::edit_profile::{EditProfileContext, SaveProfileEdits, SetAvatar};
+use crate::api::rcos::users::field_visibility::{
+    Audience, FieldVisibility, ProfileField, SaveFieldVisibility,
+};
 use crate::api::rcos::users::profile::{
     profile::{ProfileTarget, ResponseData},
     Profile,
 };
+use crate::api::rcos::users::username::{self, UsernameLookup, UsernameTarget};
 use crate::api::rcos::users::UserRole;
 use crate::env::global_config;
 use crate::error::TelescopeError;
+use crate::templates::cache::{self, Dependency};
 use crate::templates::page::Page;
 use crate::templates::tags::Tags;
 use crate::templates::Template;
+use crate::web::deadline;
+use crate::web::images;
 use crate::web::services::auth::identity::{AuthenticationCookie, Identity};
+use crate::web::services::leaderboard;
+use crate::web::services::matchmaking;
+use crate::web::uploads::{self, UploadKind};
+use actix_multipart::Multipart;
 use actix_web::web::{Form, Path, ServiceConfig};
-use actix_web::{http::header::LOCATION, HttpRequest, HttpResponse};
+use actix_web::{http::header::LOCATION, HttpRequest, HttpResponse, Responder};
 use chrono::{Datelike, Local};
+use serde_json::Value;
 use serenity::model::guild::Member;
 use serenity::model::user::User;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 /// The path from the template directory to the profile template.
@@ -32,15 +47,69 @@ pub fn register(config: &mut ServiceConfig) {
     config
         .service(profile)
         .service(settings)
-        .service(save_changes);
+        .service(save_changes)
+        .service(upload_avatar)
+        .service(remove_avatar)
+        .service(save_skills);
+}
+
+/// Error for a profile path that does not match any user.
+pub(super) fn user_not_found() -> TelescopeError {
+    TelescopeError::resource_not_found(
+        "User Not Found",
+        "Could not find a user by this user ID or username.",
+    )
+}
+
+/// A user addressed by user ID or username in a profile URL.
+pub(super) enum AddressedUser {
+    Found(Uuid),
+    /// The username has changed. Holds what to address the user by now.
+    Moved(String),
+}
+
+/// Look up the user a profile URL addresses. Profiles are addressed by user
+/// ID, or by username if the user has set one.
+pub(super) async fn lookup_address(id: &str) -> Result<AddressedUser, TelescopeError> {
+    if let Ok(id) = id.parse::<Uuid>() {
+        return Ok(AddressedUser::Found(id));
+    }
+
+    match UsernameLookup::get(username::normalize(id)).await? {
+        Some(UsernameTarget::Current(id)) => Ok(AddressedUser::Found(id)),
+        Some(UsernameTarget::Redirect { user_id, username }) => Ok(AddressedUser::Moved(
+            username.unwrap_or_else(|| user_id.to_string()),
+        )),
+        None => Err(user_not_found()),
+    }
 }
 
-/// User profile service. The target's user ID is in the path.
+/// User profile service. The target's user ID or username is in the path.
+/// Usernames the user has changed away from redirect to their current profile URL.
 #[get("/user/{id}")]
 async fn profile(
     req: HttpRequest,
     identity: Identity,
-    Path(id): Path<Uuid>,
+    Path(id): Path<String>,
+) -> Result<HttpResponse, TelescopeError> {
+    let id: Uuid = match lookup_address(&id).await? {
+        AddressedUser::Found(id) => id,
+        AddressedUser::Moved(current) => {
+            return Ok(HttpResponse::MovedPermanently()
+                .header(LOCATION, format!("/user/{}", current))
+                .finish());
+        }
+    };
+
+    let page: Page = profile_page(&req, &identity, id).await?;
+    return page.respond_to(&req).await;
+}
+
+/// Render the profile page for a user.
+async fn profile_page(
+    req: &HttpRequest,
+    identity: &Identity,
+    id: Uuid,
 ) -> Result<Page, TelescopeError> {
     // Get the viewer's user ID.
     let viewer: Option<Uuid> = identity.get_user_id().await?;
@@ -50,20 +119,67 @@ async fn profile(
 
     // Throw an error if there is no user.
     if response.target.is_none() {
-        return Err(TelescopeError::resource_not_found(
-            "User Not Found",
-            "Could not find a user by this user ID.",
-        ));
+        return Err(user_not_found());
+    }
+
+    // Coordinators, faculty advisors, and sysadmins can award and revoke badges.
+    let is_coordinator: bool = match viewer {
+        Some(viewer) => AuthorizationFor::get(Some(viewer)).await?.can_view_drafts(),
+        None => false,
+    };
+
+    // Profiles hidden by a moderator are only shown to their owner and coordinators.
+    let is_hidden: bool = response.target.as_ref().unwrap().profile_hidden;
+    if is_hidden && viewer != Some(id) && !is_coordinator {
+        return Err(user_not_found());
     }
 
     // Create the profile template to send back to the viewer.
     let mut template: Template = Template::new(TEMPLATE_NAME);
-    template["data"] = json!(&response);
+    template
+        .set_field("data", &response)?
+        .set_field("contributions", response.contribution_series())?
+        .set_field(
+            "hidden",
+            response
+                .hidden_fields()
+                .iter()
+                .map(|field| (field.name(), true))
+                .collect::<HashMap<_, _>>(),
+        )?;
+    if viewer.is_some() {
+        template.set_field("can_award_badges", is_coordinator)?;
+    }
+
+    // Members and coordinators can download certificates for semesters that have ended.
+    if viewer == Some(id) || template["can_award_badges"] == json!(true) {
+        let today = Local::today().naive_local();
+        let target = response.target.as_ref().unwrap();
+        for (index, enrollment) in target.enrollments.iter().enumerate() {
+            if enrollment.semester.end_date < today {
+                let index: String = index.to_string();
+                let path = [
+                    "data",
+                    "target",
+                    "enrollments",
+                    index.as_str(),
+                    "certificate",
+                ];
+                template.set_path(&path, true)?;
+            }
+        }
+    }
 
     // Get the target user's info.
     let target_user: &ProfileTarget = response.target.as_ref().unwrap();
     // And use it to make the page title
-    let page_title: String = format!("{} {}", target_user.first_name, target_user.last_name);
+    let page_title: String = UserDisplay::new(
+        target_user.first_name.as_str(),
+        target_user.last_name.as_str(),
+        target_user.preferred_name.clone(),
+        target_user.pronouns.clone(),
+    )
+    .name();
 
     // Get the target user's discord info.
     let target_discord_id: Option<&str> = target_user
@@ -74,8 +190,11 @@ async fn profile(
     // If the discord ID exists, and is properly formatted.
     if let Some(target_discord_id) = target_discord_id.and_then(|s| s.parse::<u64>().ok()) {
         // Get target user info.
-        let target_user: Result<User, serenity::Error> =
-            global_discord_client().get_user(target_discord_id).await;
+        let target_user: Result<User, serenity::Error> = deadline::within(
+            "Discord",
+            global_discord_client().get_user(target_discord_id),
+        )
+        .await?;
 
         // Check to make sure target user info was available.
         match target_user {
@@ -83,24 +202,27 @@ async fn profile(
             Err(e) => {
                 // Log an error and set a flag for the template.
                 warn!("Could not get target user account for Discord user ID {}. Account may have been deleted. Internal error: {}", target_discord_id, e);
-                template["discord"]["target"] = json!({"errored": true});
+                template.set_path(&["discord", "target"], json!({"errored": true}))?;
 
                 // Return early if there was an error.
                 // Otherwise we can go forward and check for the user's membership in the RCOS
                 // Discord server.
-                return template.in_page(&req, page_title).await;
+                return template.in_page(req, page_title).await;
             }
 
             // User returned successfully.
             Ok(u) => {
                 // Add the discord info to the template.
-                template["discord"]["target"] = json!({
-                    "response": &u,
-                    "resolved": {
-                        "face": u.face(),
-                        "tag": u.tag(),
-                    }
-                });
+                template.set_path(
+                    &["discord", "target"],
+                    json!({
+                        "response": &u,
+                        "resolved": {
+                            "face": u.face(),
+                            "tag": u.tag(),
+                        }
+                    }),
+                )?;
             }
         }
 
@@ -109,10 +231,12 @@ async fn profile(
         let rcos_discord: u64 = global_config().discord_config.rcos_guild_id();
 
         // Target user as member of RCOS discord.
-        let membership: Option<Member> = global_discord_client()
-            .get_member(rcos_discord, target_discord_id)
-            .await
-            .ok();
+        let membership: Option<Member> = deadline::within(
+            "Discord",
+            global_discord_client().get_member(rcos_discord, target_discord_id),
+        )
+        .await?
+        .ok();
 
         // Get "Verified" role ID if available.
         let verified_role_id = discord::rcos_discord_verified_role_id()
@@ -133,18 +257,21 @@ async fn profile(
             .is_some();
 
         // Add verified status to template.
-        template["discord"]["target"]["is_verified"] = json!(is_verified);
+        template.set_path(&["discord", "target", "is_verified"], is_verified)?;
 
         // Add Discord authentication status to template.
-        template["discord"]["viewer"]["is_authenticated"] = identity
-            .identity()
-            .await
-            .map(|cookie| json!(cookie.get_discord().is_some()))
-            .unwrap_or(json!(false));
+        template.set_path(
+            &["discord", "viewer", "is_authenticated"],
+            identity
+                .identity()
+                .await
+                .map(|cookie| cookie.get_discord().is_some())
+                .unwrap_or(false),
+        )?;
     }
 
     // Render the profile template and send to user.
-    let mut page = template.in_page(&req, page_title.clone()).await?;
+    let mut page = template.in_page(req, page_title.clone()).await?;
 
     let mut tags = Tags::default();
     tags.title = page_title.clone();
@@ -182,6 +309,14 @@ async fn profile(
     return Ok(page);
 }
 
+/// The longest pronouns string users can set.
+const MAX_PRONOUNS_LENGTH: usize = 40;
+
+/// Trim an optional form field, treating an empty one as unset.
+fn optional(value: String) -> Option<String> {
+    Some(value.trim().to_string()).filter(|s| !s.is_empty())
+}
+
 /// Create a form template for the user settings page.
 fn make_settings_form() -> Template {
     // Create the base form.
@@ -219,9 +354,9 @@ async fn get_context_and_make_form(
     // Create the form to edit the profile.
     let mut form: Template = make_settings_form();
     // Add the context to the form.
-    form["context"] = json!(&context);
+    form.set_field("context", &context)?;
     // Add user id to the form for the cancel button
-    form["user_id"] = json!(&viewer);
+    form.set_field("user_id", viewer)?;
 
     // Add the list of roles (and whether the current role can switch to them).
     let role_list = UserRole::ALL_ROLES
@@ -232,16 +367,54 @@ async fn get_context_and_make_form(
         .collect::<HashMap<_, _>>();
 
     // Add to form.
-    form["roles"] = json!(role_list);
+    form.set_field("roles", role_list)?;
+
+    // Add who can see each optional field, and the audiences to pick from.
+    let visibility = FieldVisibility::from_rows(
+        context
+            .field_visibility
+            .iter()
+            .map(|row| (row.field.as_str(), row.audience.as_str())),
+    );
+    set_visibility_fields(&mut form, &visibility)?;
+
+    // Check the skills the user has tagged themselves with.
+    let tagged: HashSet<i64> = context.skills.iter().map(|skill| skill.skill_id).collect();
+    matchmaking::fill_skill_picker(&mut form, tagged).await?;
 
     // Disable student role if the current role is external and there is no RCS ID in the context.
     if context.role.is_external() && context.rcs_id.first().is_none() {
-        form["roles"]["student"] = json!(false);
+        form.set_path(&["roles", "student"], false)?;
     }
 
     return Ok(form);
 }
 
+/// Fill the field visibility section of the settings form.
+fn set_visibility_fields(
+    form: &mut Template,
+    visibility: &FieldVisibility,
+) -> Result<(), TelescopeError> {
+    let fields: Vec<Value> = ProfileField::ALL
+        .iter()
+        .map(|field| {
+            json!({
+                "name": field.name(),
+                "label": field.label(),
+                "audience": visibility.audience(*field).name(),
+            })
+        })
+        .collect();
+    let audiences: Vec<Value> = Audience::ALL
+        .iter()
+        .map(|audience| json!({"name": audience.name(), "label": audience.label()}))
+        .collect();
+
+    form.set_field("visibility", fields)?
+        .set_field("audiences", audiences)?;
+    return Ok(());
+}
+
 /// User settings form.
 #[get("/edit_profile")]
 async fn settings(req: HttpRequest, auth: AuthenticationCookie) -> Result<Page, TelescopeError> {
@@ -258,9 +431,35 @@ struct ProfileEdits {
     last_name: String,
     role: UserRole,
 
+    /// Preferred first name, shown in place of the first name.
+    #[serde(default)]
+    preferred_name: String,
+
+    /// Pronouns shown next to the user's name.
+    #[serde(default)]
+    pronouns: String,
+
     /// Entry year for RPI students.
     #[serde(default)]
     cohort: String,
+
+    /// Checkbox to appear on the attendance leaderboard.
+    #[serde(default)]
+    leaderboard_opt_in: Option<bool>,
+
+    /// Who can see each optional field. Fields left out keep their default.
+    #[serde(default)]
+    visibility_pronouns: Option<Audience>,
+    #[serde(default)]
+    visibility_cohort: Option<Audience>,
+    #[serde(default)]
+    visibility_email: Option<Audience>,
+    #[serde(default)]
+    visibility_discord: Option<Audience>,
+    #[serde(default)]
+    visibility_github: Option<Audience>,
+    #[serde(default)]
+    visibility_contributions: Option<Audience>,
 }
 
 /// Submission endpoint for the user settings form.
@@ -272,7 +471,16 @@ async fn save_changes(
         first_name,
         last_name,
         role,
+        preferred_name,
+        pronouns,
         cohort,
+        leaderboard_opt_in,
+        visibility_pronouns,
+        visibility_cohort,
+        visibility_email,
+        visibility_discord,
+        visibility_github,
+        visibility_contributions,
     }): Form<ProfileEdits>,
 ) -> Result<HttpResponse, TelescopeError> {
     // Get authenticated user ID. This API call gets duplicated in the context creation unfortunately.
@@ -288,12 +496,12 @@ async fn save_changes(
     // Check if user is allowed to set their cohort and if it is within the valid range.
     if cohort.is_some() {
         if auth.get_rcs_id().await.unwrap().is_none() {
-            form["issues"]["cohort"] = json!("Please link RPI CAS before setting this.");
+            form.set_issue("cohort", "Please link RPI CAS before setting this.");
         }
         let cohort_int = cohort.unwrap();
         let year: i64 = Local::today().year() as i64;
         if cohort_int < 1824 || cohort_int > year {
-            form["issues"]["cohort"] = json!(format!("Year must be between 1824 and {}", year));
+            form.set_issue("cohort", format!("Year must be between 1824 and {}", year));
         }
     }
 
@@ -313,18 +521,45 @@ async fn save_changes(
     }
 
     // Fill the form with the submitted info.
-    form["context"]["first_name"] = json!(&first_name);
-    form["context"]["last_name"] = json!(&last_name);
-    form["context"]["cohort"] = json!(&cohort);
-    form["context"]["role"] = json!(role);
+    form.set_path(&["context", "first_name"], &first_name)?
+        .set_path(&["context", "last_name"], &last_name)?
+        .set_path(&["context", "preferred_name"], &preferred_name)?
+        .set_path(&["context", "pronouns"], &pronouns)?
+        .set_path(&["context", "cohort"], cohort)?
+        .set_path(&["context", "role"], role)?;
+    let leaderboard_opt_in: bool = leaderboard_opt_in.unwrap_or(false);
+    form.set_path(&["context", "leaderboard_opt_in"], leaderboard_opt_in)?;
+
+    let mut visibility = FieldVisibility::default();
+    let chosen = [
+        (ProfileField::Pronouns, visibility_pronouns),
+        (ProfileField::Cohort, visibility_cohort),
+        (ProfileField::Email, visibility_email),
+        (ProfileField::Discord, visibility_discord),
+        (ProfileField::Github, visibility_github),
+        (ProfileField::Contributions, visibility_contributions),
+    ];
+    for (field, audience) in chosen {
+        if let Some(audience) = audience {
+            visibility.set(field, audience);
+        }
+    }
+    set_visibility_fields(&mut form, &visibility)?;
 
     // Error if first or last name is empty.
     if first_name.trim().is_empty() {
-        form["issues"]["first_name"] = json!("Cannot be empty.");
+        form.set_issue("first_name", "Cannot be empty.");
     }
 
     if last_name.trim().is_empty() {
-        form["issues"]["last_name"] = json!("Cannot be empty.");
+        form.set_issue("last_name", "Cannot be empty.");
+    }
+
+    if pronouns.trim().chars().count() > MAX_PRONOUNS_LENGTH {
+        form.set_issue(
+            "pronouns",
+            format!("Cannot be longer than {} characters.", MAX_PRONOUNS_LENGTH),
+        );
     }
 
     if form["issues"] != json!(null) {
@@ -333,14 +568,96 @@ async fn save_changes(
     }
 
     // Execute GraphQL mutation to save changes.
-    let user_id = SaveProfileEdits::execute(user_id, first_name, last_name, cohort, role)
-        .await?
-        .ok_or(TelescopeError::ise(
-            "Could not save changes -- user not found.",
-        ))?;
+    let user_id = SaveProfileEdits::execute(
+        user_id,
+        first_name,
+        last_name,
+        optional(preferred_name),
+        optional(pronouns),
+        cohort,
+        role,
+        leaderboard_opt_in,
+    )
+    .await?
+    .ok_or(TelescopeError::ise(
+        "Could not save changes -- user not found.",
+    ))?;
+    SaveFieldVisibility::execute(user_id, &visibility).await?;
+
+    // Drop cached standings so the leaderboard reflects the privacy setting.
+    leaderboard::invalidate_cache();
+    cache::invalidate(Dependency::Users);
 
     // On success, redirect to user's profile.
     return Ok(HttpResponse::Found()
         .header(LOCATION, format!("/user/{}", user_id))
         .finish());
 }
+
+/// Redirect back to the profile settings form.
+fn to_settings() -> HttpResponse {
+    HttpResponse::Found()
+        .header(LOCATION, "/edit_profile")
+        .finish()
+}
+
+/// Get the profile picture a user has uploaded, if any.
+async fn current_avatar(user_id: Uuid) -> Result<Option<Uuid>, TelescopeError> {
+    Ok(EditProfileContext::get(user_id)
+        .await?
+        .and_then(|context| context.avatar_image_id))
+}
+
+/// Upload a new profile picture, replacing the old one.
+#[post("/edit_profile/avatar")]
+async fn upload_avatar(
+    auth: AuthenticationCookie,
+    payload: Multipart,
+) -> Result<HttpResponse, TelescopeError> {
+    let user_id: Uuid = auth.get_user_id_or_error().await?;
+
+    let file =
+        uploads::read(payload)
+            .await?
+            .take_file("avatar")
+            .ok_or(TelescopeError::BadRequest {
+                header: "No Picture Selected".into(),
+                message: "Choose a picture to upload.".into(),
+                show_status_code: false,
+            })?;
+
+    let upload = uploads::check(UploadKind::Image, file, Some(user_id)).await?;
+    let image_id: Uuid = images::store(upload).await?;
+
+    let previous: Option<Uuid> = current_avatar(user_id).await?;
+    SetAvatar::execute(user_id, Some(image_id)).await?;
+    if let Some(previous) = previous {
+        images::delete(previous);
+    }
+
+    return Ok(to_settings());
+}
+
+/// Remove the user's profile picture.
+#[post("/edit_profile/avatar/remove")]
+async fn remove_avatar(auth: AuthenticationCookie) -> Result<HttpResponse, TelescopeError> {
+    let user_id: Uuid = auth.get_user_id_or_error().await?;
+
+    if let Some(previous) = current_avatar(user_id).await? {
+        SetAvatar::execute(user_id, None).await?;
+        images::delete(previous);
+    }
+
+    return Ok(to_settings());
+}
+
+/// Set the skills the user has, used to suggest projects to them.
+#[post("/edit_profile/skills")]
+async fn save_skills(
+    auth: AuthenticationCookie,
+    Form(form): Form<HashMap<String, String>>,
+) -> Result<HttpResponse, TelescopeError> {
+    let user_id: Uuid = auth.get_user_id_or_error().await?;
+    ClearUserSkills::set(user_id, matchmaking::picked_skills(&form).await?).await?;
+    return Ok(to_settings());
+}
@@ -1,13 +1,19 @@
+use crate::api::rcos::users::attestations::RecordAttestation;
 use crate::api::rcos::users::create::CreateOneUser;
 use crate::api::rcos::users::{UserAccountType, UserRole};
+use crate::env::{global_config, AttestationConfig};
 use crate::error::TelescopeError;
+use crate::templates::cache::{self, Dependency};
 use crate::templates::page::Page;
 use crate::templates::{auth, Template};
 use crate::web::services::auth::identity::{AuthenticationCookie, RootIdentity};
 use crate::web::services::auth::rpi_cas::RpiCasIdentity;
+use crate::web::webhooks::{self, WebhookEvent};
 use actix_web::http::header::LOCATION;
 use actix_web::web::Form;
 use actix_web::{HttpRequest, HttpResponse, Responder};
+use serde_json::Value;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 /// The path from the templates directory to the registration template.
@@ -21,15 +27,52 @@ pub struct RegistrationFormInput {
 
     /// The new user's last name
     last_name: String,
+
+    /// The attestation checkboxes, named `attest_<key>`. Unchecked boxes are
+    /// not submitted.
+    #[serde(flatten)]
+    attestations: HashMap<String, String>,
 }
 
 impl RegistrationFormInput {
-    /// Check that neither the first name or last name is empty.
+    /// Check if the user checked an attestation.
+    fn attested(&self, attestation: &AttestationConfig) -> bool {
+        self.attestations
+            .contains_key(&format!("attest_{}", attestation.key))
+    }
+
+    /// Check that neither the first name or last name is empty, and that every
+    /// required attestation is checked.
     fn is_valid(&self) -> bool {
-        !self.first_name.is_empty() && !self.last_name.is_empty()
+        !self.first_name.is_empty()
+            && !self.last_name.is_empty()
+            && global_config()
+                .attestations
+                .iter()
+                .all(|a| !a.required || self.attested(a))
     }
 }
 
+/// The attestation checkboxes for the registration form. Without input, every
+/// box is unchecked and there are no errors.
+fn attestation_fields(input: Option<&RegistrationFormInput>) -> Value {
+    global_config()
+        .attestations
+        .iter()
+        .map(|attestation| {
+            let checked: bool = input.map_or(false, |input| input.attested(attestation));
+            json!({
+                "key": attestation.key,
+                "label": attestation.label,
+                "required": attestation.required,
+                "checked": checked,
+                "error": (input.is_some() && attestation.required && !checked)
+                    .then(|| "You must agree to this to create an account.")
+            })
+        })
+        .collect()
+}
+
 /// Create an empty registration form.
 async fn empty_registration_form(id: &RootIdentity) -> Result<Template, TelescopeError> {
     // Create the base form
@@ -76,6 +119,7 @@ async fn empty_registration_form(id: &RootIdentity) -> Result<Template, Telescop
         }
     }
 
+    template.set_field("attestations", attestation_fields(None))?;
     return Ok(template);
 }
 
@@ -116,6 +160,8 @@ async fn form_with_input(
         }),
     );
 
+    fields.insert("attestations".into(), attestation_fields(Some(input)));
+
     return Ok(template);
 }
 
@@ -123,7 +169,7 @@ async fn form_with_input(
 /// Service for the registration page. This page allows users to start the
 /// account creation process by signing into an identity provider.
 pub async fn register_page(req: HttpRequest) -> Result<Page, TelescopeError> {
-    auth::register().in_page(&req, "Create RCOS Account").await
+    auth::register()?.in_page(&req, "Create RCOS Account").await
 }
 
 #[get("/register/finish")]
@@ -172,10 +218,18 @@ pub async fn submit_registration(
         return Err(TelescopeError::InvalidForm(invalid_form));
     }
 
+    // Pair every configured attestation with the user's answer.
+    let answers: Vec<(String, String, bool)> = global_config()
+        .attestations
+        .iter()
+        .map(|a| (a.key.clone(), a.label.clone(), form_input.attested(a)))
+        .collect();
+
     // Deconstruct the input.
     let RegistrationFormInput {
         first_name,
         last_name,
+        ..
     } = form_input.0;
 
     // Retrieve the user account platform variant.
@@ -219,6 +273,14 @@ pub async fn submit_registration(
         "Create User mutation did not return user ID",
     ))?;
 
+    // Store the user's attestations with their account.
+    RecordAttestation::execute(created_user_id, answers).await?;
+    cache::invalidate(Dependency::Users);
+    webhooks::emit(WebhookEvent::UserRegistered {
+        user_id: created_user_id,
+    })
+    .await;
+
     // Redirect the user to the account we created for them
     Ok(HttpResponse::Found()
         .header(LOCATION, format!("/user/{}", created_user_id))
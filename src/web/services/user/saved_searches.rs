@@ -0,0 +1,210 @@
+//! Named filter sets that users save on list pages.
+//!
+//! A saved search is the query string of a list page. Users pick one from a
+//! dropdown on the page, and can make one their default for that page. Opening
+//! the page without any filters then redirects to the default. The "Without
+//! filters" link adds `view=all`, which no page reads, to get past it.
+
+use crate::api::rcos::users::saved_searches::{
+    saved_searches::SavedSearchesSavedSearches, DeleteSavedSearch, SaveSearch, SavedSearches,
+    SetDefaultSearch,
+};
+use crate::error::TelescopeError;
+use crate::web::services::auth::identity::Identity;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, Path, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use uuid::Uuid;
+
+/// The most characters in the name of a saved search.
+const NAME_LIMIT: usize = 60;
+
+/// The most characters in a saved query string.
+const QUERY_LIMIT: usize = 1000;
+
+/// The pages that searches can be saved on.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchPage {
+    Meetings,
+    Developers,
+}
+
+impl SearchPage {
+    /// The name stored with saved searches.
+    fn name(&self) -> &'static str {
+        match self {
+            SearchPage::Meetings => "meetings",
+            SearchPage::Developers => "developers",
+        }
+    }
+
+    /// The path of the page.
+    pub fn path(&self) -> &'static str {
+        match self {
+            SearchPage::Meetings => "/meetings",
+            SearchPage::Developers => "/developers",
+        }
+    }
+}
+
+/// Register saved search services.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(save).service(set_default).service(delete);
+}
+
+/// Get the searches a viewer has saved on a page. Empty if nobody is logged in.
+pub async fn get(
+    viewer: Option<Uuid>,
+    page: SearchPage,
+) -> Result<Vec<SavedSearchesSavedSearches>, TelescopeError> {
+    match viewer {
+        Some(user_id) => SavedSearches::get(user_id, page.name().to_string()).await,
+        None => Ok(Vec::new()),
+    }
+}
+
+/// If the page was opened without any filters and the viewer has a default
+/// search for it, redirect to the default.
+pub fn default_redirect(
+    req: &HttpRequest,
+    page: SearchPage,
+    saved: &[SavedSearchesSavedSearches],
+) -> Option<HttpResponse> {
+    if req.path() != page.path() || !req.query_string().is_empty() {
+        return None;
+    }
+
+    let default = saved.iter().find(|search| search.is_default)?;
+    return Some(
+        HttpResponse::Found()
+            .header(LOCATION, format!("{}?{}", page.path(), default.query))
+            .finish(),
+    );
+}
+
+/// Redirect back to a page with a query string after changing saved searches.
+fn back_to(page: SearchPage, query: &str) -> HttpResponse {
+    return HttpResponse::Found()
+        .header(LOCATION, format!("{}?{}", page.path(), query))
+        .finish();
+}
+
+/// Error for a saved search that does not belong to the viewer.
+fn not_found() -> TelescopeError {
+    TelescopeError::resource_not_found(
+        "Saved Search Not Found",
+        "Could not find a saved search with this ID.",
+    )
+}
+
+/// Form submitted to save the filters of a page.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct SaveSearchForm {
+    page: SearchPage,
+    name: String,
+    /// The query string of the page, without the leading `?`.
+    query: String,
+    /// Checkbox to make this the default for the page.
+    is_default: Option<String>,
+}
+
+/// Save the current filters of a page.
+#[post("/saved_searches")]
+async fn save(
+    identity: Identity,
+    Form(form): Form<SaveSearchForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let user_id: Uuid = identity.get_user_id_or_error().await?;
+
+    let name: &str = form.name.trim();
+    let query: &str = form.query.trim().trim_start_matches('?');
+    if name.is_empty() || name.chars().count() > NAME_LIMIT {
+        return Err(TelescopeError::BadRequest {
+            header: "Invalid Name".into(),
+            message: format!(
+                "Saved searches need a name of at most {} characters.",
+                NAME_LIMIT
+            ),
+            show_status_code: false,
+        });
+    }
+    if query.len() > QUERY_LIMIT {
+        return Err(TelescopeError::BadRequest {
+            header: "Search Too Long".into(),
+            message: "These filters are too long to save.".into(),
+            show_status_code: false,
+        });
+    }
+
+    SaveSearch::execute(
+        user_id,
+        form.page.name().to_string(),
+        name.to_string(),
+        query.to_string(),
+        form.is_default.is_some(),
+    )
+    .await?;
+
+    return Ok(back_to(form.page, query));
+}
+
+/// Form submitted to change the default search of a page.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct SetDefaultForm {
+    page: SearchPage,
+    is_default: bool,
+}
+
+/// Make a saved search the default for its page, or stop it being the default.
+#[post("/saved_searches/{id}/default")]
+async fn set_default(
+    identity: Identity,
+    Path(id): Path<i64>,
+    Form(form): Form<SetDefaultForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let user_id: Uuid = identity.get_user_id_or_error().await?;
+    let found: bool =
+        SetDefaultSearch::execute(user_id, form.page.name().to_string(), id, form.is_default)
+            .await?;
+
+    if !found {
+        return Err(not_found());
+    }
+    return Ok(back_to(form.page, "view=all"));
+}
+
+/// Form submitted to delete a saved search.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct DeleteForm {
+    page: SearchPage,
+}
+
+/// Delete a saved search.
+#[post("/saved_searches/{id}/delete")]
+async fn delete(
+    identity: Identity,
+    Path(id): Path<i64>,
+    Form(form): Form<DeleteForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let user_id: Uuid = identity.get_user_id_or_error().await?;
+    if !DeleteSavedSearch::execute(user_id, id).await? {
+        return Err(not_found());
+    }
+    return Ok(back_to(form.page, "view=all"));
+}
+
+/// The fields of the `saved_searches` partial, which renders the saved search
+/// controls on a list page.
+pub fn template_fields(
+    req: &HttpRequest,
+    page: SearchPage,
+    saved: &[SavedSearchesSavedSearches],
+) -> serde_json::Value {
+    json!({
+        "page": page,
+        "path": page.path(),
+        "searches": saved,
+        "query_string": req.query_string(),
+    })
+}
@@ -0,0 +1,100 @@
+//! JSON user search, for type-ahead inputs.
+//!
+//! Signed in users can search by username and name. Profiles hidden by a
+//! moderator only show up for coordinators.
+
+use crate::api::rcos::users::display::UserDisplay;
+use crate::api::rcos::users::field_visibility::Viewer;
+use crate::api::rcos::users::search::UserSearch;
+use crate::api::rcos::users::UserRole;
+use crate::error::TelescopeError;
+use crate::templates::pagination::PaginationParameter;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::web::{Query, ServiceConfig};
+use actix_web::HttpResponse;
+use uuid::Uuid;
+
+/// How many users are returned per page if the query does not say.
+const DEFAULT_LIMIT: u32 = 10;
+
+/// The most users returned per page.
+const MAX_LIMIT: u32 = 50;
+
+/// Register user search services.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(search);
+}
+
+/// Query parameters of a user search.
+#[derive(Deserialize, Debug)]
+struct SearchQuery {
+    /// What to search for.
+    #[serde(default)]
+    q: String,
+    /// The page of results, counting from 1.
+    page: Option<u32>,
+    /// How many results to return per page.
+    limit: Option<u32>,
+}
+
+/// A user in the search results.
+#[derive(Serialize, Debug)]
+struct SearchResult {
+    id: Uuid,
+    username: Option<String>,
+    /// The name the user goes by.
+    name: String,
+    role: UserRole,
+    /// The user's profile page.
+    url: String,
+}
+
+/// The search results.
+#[derive(Serialize, Debug)]
+struct SearchResponse {
+    users: Vec<SearchResult>,
+    /// How many users match in total.
+    total: i64,
+    page: u64,
+    page_size: u64,
+}
+
+/// Search users by username and name.
+#[get("/users/search")]
+async fn search(
+    auth: AuthenticationCookie,
+    Query(query): Query<SearchQuery>,
+) -> Result<HttpResponse, TelescopeError> {
+    let viewer = Viewer::get(Some(auth.get_user_id_or_error().await?)).await?;
+
+    let limit: u32 = query.limit.unwrap_or(DEFAULT_LIMIT).max(1).min(MAX_LIMIT);
+    let page = PaginationParameter::from_page(query.page, limit);
+    let (users, total) = UserSearch::get(query.q.as_str(), page, viewer.is_coordinator).await?;
+
+    let users: Vec<SearchResult> = users
+        .into_iter()
+        .map(|user| SearchResult {
+            name: UserDisplay::new(
+                user.first_name.as_str(),
+                user.last_name.as_str(),
+                user.preferred_name.clone(),
+                None,
+            )
+            .name(),
+            url: format!(
+                "/user/{}",
+                user.username.clone().unwrap_or_else(|| user.id.to_string())
+            ),
+            id: user.id,
+            username: user.username,
+            role: user.role,
+        })
+        .collect();
+
+    return Ok(HttpResponse::Ok().json(SearchResponse {
+        users,
+        total,
+        page: page.page_number(),
+        page_size: page.page_size,
+    }));
+}
@@ -0,0 +1,123 @@
+//! Username change form.
+
+use crate::api::rcos::prelude::Username;
+use crate::api::rcos::users::edit_profile::EditProfileContext;
+use crate::api::rcos::users::username::{
+    self, ChangeUsername, UsernameAvailable, CHANGE_COOLDOWN_DAYS, REDIRECT_GRACE_PERIOD_DAYS,
+};
+use crate::error::TelescopeError;
+use crate::templates::cache::{self, Dependency};
+use crate::templates::page::Page;
+use crate::templates::Template;
+use crate::web::services::auth::identity::AuthenticationCookie;
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Form, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse};
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+/// The path from the templates directory to the username form template.
+const TEMPLATE_PATH: &'static str = "user/username";
+
+/// Register username services.
+pub fn register(config: &mut ServiceConfig) {
+    config.service(username_form).service(change_username);
+}
+
+/// Username submitted through the form.
+#[derive(Deserialize, Debug)]
+struct UsernameForm {
+    username: String,
+}
+
+/// Make the username form for the viewer. Return their user ID, current
+/// username, and the form.
+async fn make_form(
+    auth: &AuthenticationCookie,
+) -> Result<(Uuid, Option<Username>, Template), TelescopeError> {
+    let viewer: Uuid = auth.get_user_id_or_error().await?;
+    let context = EditProfileContext::get(viewer).await?.ok_or_else(|| {
+        TelescopeError::ise(format!(
+            "Could not get edit context for user ID {}.",
+            viewer
+        ))
+    })?;
+
+    let mut form = Template::new(TEMPLATE_PATH);
+    form.set_field("user_id", &viewer)?
+        .set_field("username", &context.username)?
+        .set_field("min_length", username::MIN_LENGTH)?
+        .set_field("max_length", username::MAX_LENGTH)?
+        .set_field("grace_period_days", REDIRECT_GRACE_PERIOD_DAYS)?
+        .set_field("cooldown_days", CHANGE_COOLDOWN_DAYS)?;
+
+    // Users who have changed their username recently have to wait to change it again.
+    if let Some(changed_at) = context.username_changed_at {
+        let next_change = changed_at + Duration::days(CHANGE_COOLDOWN_DAYS);
+        if next_change > Utc::now() {
+            form.set_field("next_change", next_change)?;
+        }
+    }
+
+    return Ok((viewer, context.username.map(Username), form));
+}
+
+/// Username change form.
+#[get("/edit_profile/username")]
+async fn username_form(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+) -> Result<Page, TelescopeError> {
+    let (_, _, form) = make_form(&auth).await?;
+    return form.in_page(&req, "Change Username").await;
+}
+
+/// Username change submission.
+#[post("/edit_profile/username")]
+async fn change_username(
+    req: HttpRequest,
+    auth: AuthenticationCookie,
+    Form(UsernameForm { username }): Form<UsernameForm>,
+) -> Result<HttpResponse, TelescopeError> {
+    let (user_id, current, mut form) = make_form(&auth).await?;
+
+    if form["next_change"] != json!(null) {
+        return Err(TelescopeError::BadRequest {
+            header: "Username Recently Changed".into(),
+            message: format!(
+                "You can only change your username once every {} days.",
+                CHANGE_COOLDOWN_DAYS
+            ),
+            show_status_code: false,
+        });
+    }
+
+    let username: Username = username::normalize(&username);
+    form.set_field("value", &username)?;
+
+    if current.as_ref() == Some(&username) {
+        form.set_issue("username", "This is already your username.");
+    } else if let Err(issue) = username::validate(&username) {
+        form.set_issue("username", issue);
+    } else if !UsernameAvailable::get(username.clone(), user_id).await? {
+        form.set_issue("username", "This username is taken.");
+    }
+
+    if form["issues"] != json!(null) {
+        let page = form.in_page(&req, "Change Username").await?;
+        return Err(TelescopeError::InvalidForm(page));
+    }
+
+    ChangeUsername::execute(user_id, current, username.clone())
+        .await?
+        .ok_or(TelescopeError::ise(
+            "Could not change username -- user not found.",
+        ))?;
+    cache::invalidate(Dependency::Users);
+
+    // The navbar looks up the username on every page, so the profile link
+    // picks up the new one right away.
+    return Ok(HttpResponse::Found()
+        .header(LOCATION, format!("/user/{}", username))
+        .finish());
+}
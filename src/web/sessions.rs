@@ -0,0 +1,330 @@
+//! Storage for short-lived session state.
+//!
+//! OAuth CSRF tokens, form idempotency keys, public API request counts, and
+//! the key that signs identity cookies all live in a [`SessionStore`]. By
+//! default that is the memory of this process, which is fine for a single
+//! server. Deployments with more than one replica behind a load balancer set
+//! `session_store` in the config to a Redis server, so that a login started
+//! on one replica can finish on another.
+
+use crate::env::{global_config, SessionStoreConfig};
+use crate::error::TelescopeError;
+use actix::{Actor, AsyncContext, Context};
+use chrono::{DateTime, Duration, Utc};
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use redis::{aio::Connection, Client, RedisError};
+use std::time::Duration as StdDuration;
+
+/// A key-value store for session state. Values are strings, and can expire.
+#[async_trait::async_trait]
+pub trait SessionStore: Send + Sync {
+    /// The name of the backend, for logs.
+    fn name(&self) -> &'static str;
+
+    /// Get the value of a key, if it is set and has not expired.
+    async fn get(&self, key: &str) -> Result<Option<String>, TelescopeError>;
+
+    /// Set a key, replacing any value it had. Keys without a lifetime are kept
+    /// until they are removed.
+    async fn set(
+        &self,
+        key: &str,
+        value: String,
+        lifetime: Option<Duration>,
+    ) -> Result<(), TelescopeError>;
+
+    /// Set a key only if it is not already set. Return whether it was set.
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: String,
+        lifetime: Option<Duration>,
+    ) -> Result<bool, TelescopeError>;
+
+    /// Remove a key and return the value it had.
+    async fn take(&self, key: &str) -> Result<Option<String>, TelescopeError>;
+
+    /// Add one to a counter and return the new count. A counter that is not
+    /// set starts at zero, and expires after `lifetime`.
+    async fn increment(&self, key: &str, lifetime: Duration) -> Result<u64, TelescopeError>;
+}
+
+/// Session state in the memory of this process.
+#[derive(Default)]
+pub struct MemoryStore {
+    /// Values and when they expire.
+    map: DashMap<String, (String, Option<DateTime<Utc>>)>,
+}
+
+impl MemoryStore {
+    /// Remove expired keys. Return the number removed.
+    fn purge(&self) -> usize {
+        let before: usize = self.map.len();
+        let now: DateTime<Utc> = Utc::now();
+        self.map
+            .retain(|_, (_, expires)| expires.map_or(true, |expires| expires > now));
+        return before.saturating_sub(self.map.len());
+    }
+}
+
+/// Check if an expiry time has passed.
+fn expired(expires: &Option<DateTime<Utc>>) -> bool {
+    expires.map_or(false, |expires| expires <= Utc::now())
+}
+
+#[async_trait::async_trait]
+impl SessionStore for MemoryStore {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<String>, TelescopeError> {
+        return Ok(self
+            .map
+            .get(key)
+            .filter(|record| !expired(&record.value().1))
+            .map(|record| record.value().0.clone()));
+    }
+
+    async fn set(
+        &self,
+        key: &str,
+        value: String,
+        lifetime: Option<Duration>,
+    ) -> Result<(), TelescopeError> {
+        let expires = lifetime.map(|lifetime| Utc::now() + lifetime);
+        self.map.insert(key.to_string(), (value, expires));
+        return Ok(());
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: String,
+        lifetime: Option<Duration>,
+    ) -> Result<bool, TelescopeError> {
+        let expires = lifetime.map(|lifetime| Utc::now() + lifetime);
+        match self.map.entry(key.to_string()) {
+            Entry::Occupied(entry) if !expired(&entry.get().1) => return Ok(false),
+            // Expired keys are treated as absent.
+            Entry::Occupied(mut entry) => {
+                entry.insert((value, expires));
+            }
+            Entry::Vacant(entry) => {
+                entry.insert((value, expires));
+            }
+        }
+        return Ok(true);
+    }
+
+    async fn take(&self, key: &str) -> Result<Option<String>, TelescopeError> {
+        return Ok(self
+            .map
+            .remove(key)
+            .filter(|(_, (_, expires))| !expired(expires))
+            .map(|(_, (value, _))| value));
+    }
+
+    async fn increment(&self, key: &str, lifetime: Duration) -> Result<u64, TelescopeError> {
+        let fresh = || ("0".to_string(), Some(Utc::now() + lifetime));
+        let mut record = self.map.entry(key.to_string()).or_insert_with(fresh);
+        if expired(&record.1) {
+            *record = fresh();
+        }
+
+        let count: u64 = record.0.parse::<u64>().unwrap_or(0) + 1;
+        record.0 = count.to_string();
+        return Ok(count);
+    }
+}
+
+/// Session state in a Redis server.
+///
+/// Each operation opens its own connection. Session state is only touched on
+/// login and form submissions, so this keeps things simple without costing
+/// much.
+pub struct RedisStore {
+    client: Client,
+    /// Prepended to every key.
+    prefix: String,
+}
+
+/// Convert a Redis error into a Telescope error.
+fn redis_error(err: RedisError) -> TelescopeError {
+    TelescopeError::ise(format!("Could not reach the session store: {}", err))
+}
+
+impl RedisStore {
+    /// Connect to the Redis server.
+    async fn connection(&self) -> Result<Connection, TelescopeError> {
+        self.client
+            .get_async_connection()
+            .await
+            .map_err(redis_error)
+    }
+
+    /// Run a `SET` command. Return whether the key was set.
+    async fn set_command(
+        &self,
+        key: &str,
+        value: String,
+        lifetime: Option<Duration>,
+        only_if_absent: bool,
+    ) -> Result<bool, TelescopeError> {
+        let mut command = redis::cmd("SET");
+        command.arg(format!("{}{}", self.prefix, key)).arg(value);
+        if let Some(lifetime) = lifetime {
+            // Redis rejects lifetimes shorter than a second.
+            command.arg("EX").arg(lifetime.num_seconds().max(1));
+        }
+        if only_if_absent {
+            command.arg("NX");
+        }
+
+        // The reply is "OK", or nil if NX stopped the key being set.
+        let reply: Option<String> = command
+            .query_async(&mut self.connection().await?)
+            .await
+            .map_err(redis_error)?;
+        return Ok(reply.is_some());
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionStore for RedisStore {
+    fn name(&self) -> &'static str {
+        "Redis"
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<String>, TelescopeError> {
+        redis::cmd("GET")
+            .arg(format!("{}{}", self.prefix, key))
+            .query_async(&mut self.connection().await?)
+            .await
+            .map_err(redis_error)
+    }
+
+    async fn set(
+        &self,
+        key: &str,
+        value: String,
+        lifetime: Option<Duration>,
+    ) -> Result<(), TelescopeError> {
+        self.set_command(key, value, lifetime, false).await?;
+        return Ok(());
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: String,
+        lifetime: Option<Duration>,
+    ) -> Result<bool, TelescopeError> {
+        self.set_command(key, value, lifetime, true).await
+    }
+
+    async fn take(&self, key: &str) -> Result<Option<String>, TelescopeError> {
+        let key: String = format!("{}{}", self.prefix, key);
+        // GETDEL needs Redis 6.2, so get and delete in a transaction instead.
+        let (value,): (Option<String>,) = redis::pipe()
+            .atomic()
+            .get(key.as_str())
+            .del(key.as_str())
+            .ignore()
+            .query_async(&mut self.connection().await?)
+            .await
+            .map_err(redis_error)?;
+        return Ok(value);
+    }
+
+    async fn increment(&self, key: &str, lifetime: Duration) -> Result<u64, TelescopeError> {
+        let key: String = format!("{}{}", self.prefix, key);
+        // Start the counter with its lifetime if it is not set, then count.
+        let (count,): (u64,) = redis::pipe()
+            .atomic()
+            .cmd("SET")
+            .arg(key.as_str())
+            .arg(0)
+            .arg("EX")
+            .arg(lifetime.num_seconds().max(1))
+            .arg("NX")
+            .ignore()
+            .incr(key.as_str(), 1)
+            .query_async(&mut self.connection().await?)
+            .await
+            .map_err(redis_error)?;
+        return Ok(count);
+    }
+}
+
+lazy_static! {
+    /// Session state kept in memory, when that is the configured backend.
+    static ref MEMORY: MemoryStore = MemoryStore::default();
+
+    /// The configured session store.
+    static ref STORE: &'static dyn SessionStore = match &global_config().session_store {
+        SessionStoreConfig::Memory => &*MEMORY,
+        SessionStoreConfig::Redis { url, prefix } => {
+            let client: Client = Client::open(url.as_str())
+                .unwrap_or_else(|e| panic!("Invalid session store URL: {}", e));
+            Box::leak(Box::new(RedisStore {
+                client,
+                prefix: prefix.clone(),
+            }))
+        }
+    };
+}
+
+/// Get the configured session store.
+pub fn store() -> &'static dyn SessionStore {
+    *STORE
+}
+
+/// Set up the configured session store. Panics if the config is invalid.
+pub fn init() {
+    info!("Keeping session state in {}.", store().name());
+}
+
+/// A zero sized struct to act as an actor and periodically remove expired
+/// session state from memory. Redis expires keys by itself.
+pub struct SessionJanitor;
+
+impl Actor for SessionJanitor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("Session Janitor Started");
+
+        // Clean up every 20 minutes.
+        let interval: StdDuration = StdDuration::new(20 * 60, 0);
+
+        ctx.run_interval(interval, |_, _| {
+            let removed: usize = MEMORY.purge();
+            info!("Session Janitor removed {} expired keys.", removed);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MemoryStore, SessionStore};
+    use chrono::Duration;
+    use futures::executor::block_on;
+
+    #[test]
+    fn counters() {
+        let store = MemoryStore::default();
+        let count =
+            |key: &str, lifetime: Duration| block_on(store.increment(key, lifetime)).unwrap();
+        let lifetime: Duration = Duration::minutes(1);
+        assert_eq!(count("a", lifetime), 1);
+        assert_eq!(count("a", lifetime), 2);
+        assert_eq!(count("b", lifetime), 1);
+
+        // Expired counters start over.
+        let expired: Duration = Duration::seconds(-1);
+        assert_eq!(count("c", expired), 1);
+        assert_eq!(count("c", expired), 1);
+    }
+}
@@ -0,0 +1,100 @@
+//! Running as a systemd service.
+//!
+//! With socket activation, systemd opens the listening sockets and passes
+//! them in, so they stay open (and queue connections) while Telescope
+//! restarts. With `Type=notify`, systemd waits for Telescope to say it is
+//! ready before routing traffic to it or stopping the old instance.
+//!
+//! See `sd_listen_fds(3)` and `sd_notify(3)`. Neither needs libsystemd.
+
+use crate::api::rcos;
+use crate::app_data::AppData;
+use crate::web::listeners::Listener;
+use actix::clock::delay_for;
+use socket2::{Domain, SockAddr, Socket, Type};
+use std::env;
+use std::io;
+use std::os::unix::io::{FromRawFd, RawFd};
+use std::process;
+use std::time::Duration;
+
+/// The first file descriptor passed by systemd.
+const LISTEN_FDS_START: RawFd = 3;
+
+/// How long to wait between checks of the RCOS API before reporting ready.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Take the listening sockets passed in by systemd, if any. The environment
+/// variables are cleared so that child processes don't take them too. This
+/// should only be called once.
+pub fn listen_fds() -> Vec<Listener> {
+    let pid: Option<u32> = env::var("LISTEN_PID").ok().and_then(|v| v.parse().ok());
+    let count: Option<RawFd> = env::var("LISTEN_FDS").ok().and_then(|v| v.parse().ok());
+    env::remove_var("LISTEN_PID");
+    env::remove_var("LISTEN_FDS");
+    env::remove_var("LISTEN_FDNAMES");
+
+    // The sockets may be meant for another process.
+    if pid != Some(process::id()) {
+        return Vec::new();
+    }
+
+    (LISTEN_FDS_START..LISTEN_FDS_START + count.unwrap_or(0))
+        .map(|fd| {
+            // Systemd hands these over for us to own.
+            let socket: Socket = unsafe { Socket::from_raw_fd(fd) };
+            let address: SockAddr = socket
+                .local_addr()
+                .unwrap_or_else(|e| panic!("Socket {} from systemd is unusable: {}", fd, e));
+
+            if address.as_inet().is_some() || address.as_inet6().is_some() {
+                Listener::Tcp(socket.into_tcp_listener())
+            } else {
+                Listener::Unix(socket.into_unix_listener())
+            }
+        })
+        .collect()
+}
+
+/// Send a message to systemd. Does nothing if Telescope was not started by
+/// systemd with `Type=notify`.
+fn notify(message: &str) -> io::Result<()> {
+    let path: String = match env::var("NOTIFY_SOCKET") {
+        Ok(path) => path,
+        Err(_) => return Ok(()),
+    };
+
+    // Socket names starting with '@' are in the abstract namespace.
+    let address: SockAddr = match path.strip_prefix('@') {
+        Some(name) => SockAddr::unix(format!("\0{}", name))?,
+        None => SockAddr::unix(path.as_str())?,
+    };
+
+    let socket = Socket::new(Domain::unix(), Type::dgram(), None)?;
+    socket.send_to(message.as_bytes(), &address)?;
+    return Ok(());
+}
+
+/// Tell systemd that Telescope is ready, once the templates are loaded and the
+/// RCOS API answers. Call this after the server is started.
+pub async fn notify_ready() {
+    if env::var_os("NOTIFY_SOCKET").is_none() {
+        return;
+    }
+
+    // This loads every template, and panics if any are malformed.
+    AppData::global().get_handlebars_registry();
+
+    while let Err(e) = rcos::ping().await {
+        warn!("RCOS API is not ready: {}", e);
+        if let Err(e) = notify("STATUS=Waiting for the RCOS API") {
+            warn!("Could not send status to systemd: {}", e);
+        }
+        delay_for(RETRY_DELAY).await;
+    }
+
+    match notify("READY=1\nSTATUS=Serving requests") {
+        Ok(()) => info!("Told systemd Telescope is ready."),
+        Err(e) => error!("Could not tell systemd Telescope is ready: {}", e),
+    }
+}
@@ -0,0 +1,149 @@
+//! Where the time of each request goes.
+//!
+//! The [timing middleware] runs each handler with a [`RequestTimer`] set.
+//! Calls to upstream services go through [`upstream`] and template rendering
+//! goes through [`render`], which add their time to it. Once the response is
+//! ready, the wall time and both parts are added to the totals for the route,
+//! which are shown on `/metrics` and the admin panel.
+//!
+//! Upstream calls made at the same time are each counted in full, so the
+//! upstream time of a request can be more than its wall time. Totals are kept
+//! in memory by each replica and start over on restart.
+//!
+//! [timing middleware]: crate::web::middlewares::timing
+
+use dashmap::DashMap;
+use std::cell::Cell;
+use std::future::Future;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+tokio::task_local! {
+    /// The timer of the request this task is handling.
+    static TIMER: Rc<RequestTimer>;
+}
+
+lazy_static! {
+    /// Totals for each route, keyed by method and route pattern.
+    static ref ROUTES: DashMap<String, RouteTotals> = DashMap::new();
+}
+
+/// Time spent on parts of one request so far.
+#[derive(Debug, Default)]
+pub struct RequestTimer {
+    upstream: Cell<Duration>,
+    render: Cell<Duration>,
+}
+
+impl RequestTimer {
+    /// Time spent waiting on upstream services.
+    pub fn upstream(&self) -> Duration {
+        self.upstream.get()
+    }
+
+    /// Time spent rendering templates.
+    pub fn render(&self) -> Duration {
+        self.render.get()
+    }
+
+    /// Run a future with this timer set.
+    pub async fn scope<F: Future>(self: Rc<Self>, future: F) -> F::Output {
+        TIMER.scope(self, future).await
+    }
+}
+
+/// Add time to a part of the current request's timer. Does nothing outside
+/// of a request.
+fn add(part: fn(&RequestTimer) -> &Cell<Duration>, elapsed: Duration) {
+    let _ = TIMER.try_with(|timer| {
+        let cell = part(timer);
+        cell.set(cell.get() + elapsed);
+    });
+}
+
+/// Run a call to an upstream service, counting its time as upstream time.
+pub async fn upstream<F: Future>(call: F) -> F::Output {
+    let start = Instant::now();
+    let output = call.await;
+    add(|timer| &timer.upstream, start.elapsed());
+    return output;
+}
+
+/// Render something, counting the time as render time.
+pub fn render<T>(f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let output = f();
+    add(|timer| &timer.render, start.elapsed());
+    return output;
+}
+
+/// Running totals for a route.
+#[derive(Copy, Clone, Debug, Default)]
+struct RouteTotals {
+    count: u64,
+    over_budget: u64,
+    wall: Duration,
+    upstream: Duration,
+    render: Duration,
+    max_wall: Duration,
+}
+
+/// Add a finished request to the totals for its route.
+pub fn record(route: String, wall: Duration, timer: &RequestTimer, over_budget: bool) {
+    let mut totals = ROUTES.entry(route).or_default();
+    totals.count += 1;
+    totals.over_budget += over_budget as u64;
+    totals.wall += wall;
+    totals.upstream += timer.upstream();
+    totals.render += timer.render();
+    totals.max_wall = totals.max_wall.max(wall);
+}
+
+/// The totals for a route, in seconds.
+#[derive(Clone, Debug, Serialize)]
+pub struct RouteTiming {
+    /// Method and route pattern, like `GET /user/{id}`.
+    pub route: String,
+    pub count: u64,
+    /// Requests that took longer than the budget for their route class.
+    pub over_budget: u64,
+    pub wall_seconds: f64,
+    pub upstream_seconds: f64,
+    pub render_seconds: f64,
+    pub max_wall_seconds: f64,
+}
+
+impl RouteTiming {
+    /// Average wall time of a request in milliseconds.
+    pub fn average_wall_millis(&self) -> f64 {
+        return self.wall_seconds * 1000.0 / self.count.max(1) as f64;
+    }
+}
+
+/// The totals for every route seen since this replica started, slowest on
+/// average first.
+pub fn routes() -> Vec<RouteTiming> {
+    let mut routes: Vec<RouteTiming> = ROUTES
+        .iter()
+        .map(|entry| {
+            let totals: &RouteTotals = entry.value();
+            RouteTiming {
+                route: entry.key().clone(),
+                count: totals.count,
+                over_budget: totals.over_budget,
+                wall_seconds: totals.wall.as_secs_f64(),
+                upstream_seconds: totals.upstream.as_secs_f64(),
+                render_seconds: totals.render.as_secs_f64(),
+                max_wall_seconds: totals.max_wall.as_secs_f64(),
+            }
+        })
+        .collect();
+
+    routes.sort_by(|a, b| {
+        b.average_wall_millis()
+            .partial_cmp(&a.average_wall_millis())
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.route.cmp(&b.route))
+    });
+    return routes;
+}
@@ -0,0 +1,232 @@
+//! HTTPS for the TCP listeners, when `tls` is configured.
+//!
+//! The certificate is served through a resolver rather than being fixed in the
+//! server config, so it can be replaced while Telescope runs. The certificate
+//! watcher job calls [`reload_if_changed`] to pick up renewed files. New
+//! connections get the new certificate; open ones are not touched.
+
+use crate::env::{global_config, TlsConfig};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rustls::internal::pemfile;
+use rustls::sign::{self, CertifiedKey};
+use rustls::{ClientHello, NoClientAuth, PrivateKey, ResolvesServerCert, ServerConfig};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+lazy_static! {
+    /// The certificate being served.
+    static ref CURRENT: RwLock<Option<Loaded>> = RwLock::new(None);
+}
+
+/// A certificate and key read from the configured files.
+struct Loaded {
+    key: CertifiedKey,
+    /// When the certificate and key files were last modified.
+    modified: (SystemTime, SystemTime),
+    /// When the certificate expires, if it could be read.
+    expires: Option<DateTime<Utc>>,
+}
+
+/// Serves whichever certificate is current.
+struct Resolver;
+
+impl ResolvesServerCert for Resolver {
+    fn resolve(&self, _: ClientHello) -> Option<CertifiedKey> {
+        CURRENT
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|loaded| loaded.key.clone())
+    }
+}
+
+/// When the certificate and key files were last modified.
+fn modified(config: &TlsConfig) -> Result<(SystemTime, SystemTime), String> {
+    let of = |path: &Path| {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|e| format!("Could not read {}: {}", path.display(), e))
+    };
+    return Ok((of(&config.cert)?, of(&config.key)?));
+}
+
+/// Read the private key file. Keys can be PKCS#8 or PKCS#1 (RSA).
+fn read_key(path: &Path) -> Result<PrivateKey, String> {
+    let read = |parse: fn(&mut dyn std::io::BufRead) -> Result<Vec<PrivateKey>, ()>| {
+        File::open(path)
+            .map_err(|e| format!("Could not open {}: {}", path.display(), e))
+            .map(|file| parse(&mut BufReader::new(file)).unwrap_or_default())
+    };
+
+    let mut keys: Vec<PrivateKey> = read(pemfile::pkcs8_private_keys)?;
+    if keys.is_empty() {
+        keys = read(pemfile::rsa_private_keys)?;
+    }
+    return keys.into_iter().next().ok_or(format!(
+        "{} has no PKCS#8 or RSA private key",
+        path.display()
+    ));
+}
+
+/// Read the configured certificate chain and key.
+fn load(config: &TlsConfig) -> Result<Loaded, String> {
+    // Read the modification times first, so that a change made while the
+    // files are being read is picked up on the next check.
+    let modified = modified(config)?;
+
+    let file = File::open(&config.cert)
+        .map_err(|e| format!("Could not open {}: {}", config.cert.display(), e))?;
+    let certificates = pemfile::certs(&mut BufReader::new(file)).unwrap_or_default();
+    if certificates.is_empty() {
+        return Err(format!("{} has no certificates", config.cert.display()));
+    }
+
+    let key: PrivateKey = read_key(&config.key)?;
+    let signing_key = sign::any_supported_type(&key)
+        .map_err(|_| format!("{} is not a supported kind of key", config.key.display()))?;
+
+    let expires: Option<DateTime<Utc>> = not_after(certificates[0].0.as_slice());
+    return Ok(Loaded {
+        key: CertifiedKey::new(certificates, Arc::new(signing_key)),
+        modified,
+        expires,
+    });
+}
+
+/// Serve a newly loaded certificate.
+fn install(loaded: Loaded) {
+    match loaded.expires {
+        Some(expires) => info!(
+            "Serving TLS certificate that expires {}.",
+            expires.format("%Y-%m-%d %H:%M UTC")
+        ),
+        None => warn!("Serving TLS certificate with an expiry date that could not be read."),
+    }
+    *CURRENT.write().unwrap() = Some(loaded);
+}
+
+/// Load the configured certificate and make the server config for the TCP
+/// listeners. Returns `None` if TLS is not configured. Panics if the
+/// certificate cannot be loaded.
+pub fn server_config() -> Option<ServerConfig> {
+    let config: TlsConfig = global_config().tls.clone()?;
+    let loaded: Loaded =
+        load(&config).unwrap_or_else(|e| panic!("Could not load TLS certificate: {}", e));
+    install(loaded);
+
+    let mut server_config = ServerConfig::new(NoClientAuth::new());
+    server_config.cert_resolver = Arc::new(Resolver);
+    return Some(server_config);
+}
+
+/// Load the certificate again if its files changed. If the new files cannot
+/// be loaded, the old certificate is kept and this tries again next time.
+pub fn reload_if_changed() {
+    let config: TlsConfig = match global_config().tls.clone() {
+        Some(config) => config,
+        None => return,
+    };
+
+    let modified = match modified(&config) {
+        Ok(modified) => modified,
+        Err(e) => {
+            warn!("Could not check TLS certificate for changes: {}", e);
+            return;
+        }
+    };
+    let changed: bool = CURRENT
+        .read()
+        .unwrap()
+        .as_ref()
+        .map_or(true, |loaded| loaded.modified != modified);
+    if !changed {
+        return;
+    }
+
+    match load(&config) {
+        Ok(loaded) => install(loaded),
+        Err(e) => error!(
+            "Could not reload TLS certificate, keeping the old one: {}",
+            e
+        ),
+    }
+}
+
+/// When the certificate being served expires, if TLS is configured and the
+/// expiry could be read.
+pub fn expires() -> Option<DateTime<Utc>> {
+    CURRENT
+        .read()
+        .unwrap()
+        .as_ref()
+        .and_then(|loaded| loaded.expires)
+}
+
+/// Split a DER element into its tag, its contents, and what comes after it.
+fn der_element(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let tag: u8 = *input.first()?;
+    let first: usize = *input.get(1)? as usize;
+    let (length, header): (usize, usize) = if first < 0x80 {
+        (first, 2)
+    } else {
+        let count: usize = first & 0x7f;
+        if count == 0 || count > 4 {
+            return None;
+        }
+        let length = input
+            .get(2..2 + count)?
+            .iter()
+            .fold(0usize, |length, byte| (length << 8) | *byte as usize);
+        (length, 2 + count)
+    };
+
+    let contents = input.get(header..header + length)?;
+    return Some((tag, contents, &input[header + length..]));
+}
+
+/// Read the end of the validity period of a DER encoded X.509 certificate.
+fn not_after(der: &[u8]) -> Option<DateTime<Utc>> {
+    const SEQUENCE: u8 = 0x30;
+    const VERSION: u8 = 0xa0;
+    const UTC_TIME: u8 = 0x17;
+    const GENERALIZED_TIME: u8 = 0x18;
+
+    // Certificate ::= SEQUENCE { tbsCertificate, signatureAlgorithm, signature }
+    let (tag, certificate, _) = der_element(der)?;
+    if tag != SEQUENCE {
+        return None;
+    }
+    // TBSCertificate ::= SEQUENCE { [0] version (optional), serialNumber,
+    // signature, issuer, validity, ... }
+    let (tag, mut fields, _) = der_element(certificate)?;
+    if tag != SEQUENCE {
+        return None;
+    }
+    if der_element(fields)?.0 == VERSION {
+        fields = der_element(fields)?.2;
+    }
+    for _ in 0..3 {
+        fields = der_element(fields)?.2;
+    }
+
+    // Validity ::= SEQUENCE { notBefore Time, notAfter Time }
+    let (tag, validity, _) = der_element(fields)?;
+    if tag != SEQUENCE {
+        return None;
+    }
+    let (_, _, rest) = der_element(validity)?;
+    let (tag, time, _) = der_element(rest)?;
+
+    let time: &str = std::str::from_utf8(time).ok()?;
+    let format: &str = match tag {
+        UTC_TIME => "%y%m%d%H%M%SZ",
+        GENERALIZED_TIME => "%Y%m%d%H%M%SZ",
+        _ => return None,
+    };
+    return NaiveDateTime::parse_from_str(time, format)
+        .ok()
+        .map(|time| DateTime::from_utc(time, Utc));
+}
@@ -0,0 +1,211 @@
+//! Checks for files uploaded through forms.
+//!
+//! Every upload goes through [`check`] before it is used or stored. The type
+//! of the file is sniffed from its contents and has to be one the upload
+//! accepts and match the extension of the file name. If a scanner is
+//! configured the file is scanned too, and files that fail the scan are put
+//! in [quarantine] for admins to review.
+
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use actix_multipart::{Field, Multipart};
+use futures::StreamExt;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub mod quarantine;
+pub mod scan;
+pub mod sniff;
+
+use self::scan::Verdict;
+use self::sniff::FileType;
+
+/// What an upload is for, which decides the types of files it accepts.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum UploadKind {
+    /// Profile pictures and logos.
+    Image,
+    /// Meeting slides.
+    Slides,
+    /// Spreadsheets to import.
+    Csv,
+}
+
+impl UploadKind {
+    /// The types of files accepted for this kind of upload.
+    pub fn accepts(&self) -> &'static [FileType] {
+        match self {
+            UploadKind::Image => &[FileType::Png, FileType::Jpeg, FileType::Gif, FileType::Webp],
+            UploadKind::Slides => &[FileType::Pdf],
+            UploadKind::Csv => &[FileType::Csv],
+        }
+    }
+}
+
+/// A file submitted in a multipart form, before it is checked.
+#[derive(Clone, Debug)]
+pub struct UploadedFile {
+    /// The name of the form field the file was submitted in.
+    pub field: String,
+    /// The name of the file on the uploader's computer.
+    pub file_name: String,
+    /// The contents of the file.
+    pub bytes: Vec<u8>,
+}
+
+/// A file that passed the upload checks.
+#[derive(Clone, Debug)]
+pub struct CheckedUpload {
+    /// The name of the file on the uploader's computer.
+    pub file_name: String,
+    /// The type sniffed from the contents.
+    pub file_type: FileType,
+    /// The contents of the file.
+    pub bytes: Vec<u8>,
+}
+
+/// The text fields and files of a submitted multipart form.
+#[derive(Clone, Debug, Default)]
+pub struct MultipartForm {
+    /// Text fields by name.
+    pub fields: HashMap<String, String>,
+    /// Submitted files. Empty file inputs are left out.
+    pub files: Vec<UploadedFile>,
+}
+
+impl MultipartForm {
+    /// Get a text field.
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(String::as_str)
+    }
+
+    /// Take the file submitted in a field, if there is one.
+    pub fn take_file(&mut self, field: &str) -> Option<UploadedFile> {
+        let index: usize = self.files.iter().position(|file| file.field == field)?;
+        return Some(self.files.remove(index));
+    }
+}
+
+/// Make the error for an upload that is refused.
+fn rejected(message: String) -> TelescopeError {
+    TelescopeError::BadRequest {
+        header: "Upload Rejected".into(),
+        message,
+        show_status_code: false,
+    }
+}
+
+/// Read one part of a multipart form, counting its size against the limit.
+async fn read_field(field: &mut Field, total: &mut usize) -> Result<Vec<u8>, TelescopeError> {
+    let limit: usize = global_config().uploads.max_size;
+    let mut bytes: Vec<u8> = Vec::new();
+    while let Some(chunk) = field.next().await {
+        let chunk = chunk.map_err(|e| rejected(format!("Could not read the upload: {}", e)))?;
+        *total += chunk.len();
+        if *total > limit {
+            return Err(rejected(format!(
+                "Uploads can be at most {} MB.",
+                limit / (1024 * 1024)
+            )));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    return Ok(bytes);
+}
+
+/// Read a submitted multipart form.
+pub async fn read(mut payload: Multipart) -> Result<MultipartForm, TelescopeError> {
+    let mut form = MultipartForm::default();
+    let mut total: usize = 0;
+
+    while let Some(field) = payload.next().await {
+        let mut field: Field =
+            field.map_err(|e| rejected(format!("Could not read the form: {}", e)))?;
+
+        let disposition = field.content_disposition();
+        let name: String = disposition
+            .as_ref()
+            .and_then(|d| d.get_name())
+            .unwrap_or_default()
+            .to_string();
+        let file_name: Option<String> = disposition
+            .as_ref()
+            .and_then(|d| d.get_filename())
+            .map(str::to_string);
+
+        let bytes: Vec<u8> = read_field(&mut field, &mut total).await?;
+        match file_name {
+            // Browsers send empty file inputs as a part with no file name.
+            Some(file_name) if !file_name.is_empty() => form.files.push(UploadedFile {
+                field: name,
+                file_name,
+                bytes,
+            }),
+            Some(_) => {}
+            None => {
+                form.fields
+                    .insert(name, String::from_utf8_lossy(&bytes).into_owned());
+            }
+        }
+    }
+
+    return Ok(form);
+}
+
+/// Check an uploaded file before it is used. Files that fail a scan, or that
+/// could not be scanned, are quarantined and refused.
+pub async fn check(
+    kind: UploadKind,
+    file: UploadedFile,
+    uploaded_by: Option<Uuid>,
+) -> Result<CheckedUpload, TelescopeError> {
+    let accepted: &[FileType] = kind.accepts();
+    let expected: String = accepted
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let file_type: FileType = sniff::sniff(&file.bytes)
+        .filter(|file_type| accepted.contains(file_type))
+        .ok_or_else(|| {
+            rejected(format!(
+                "\"{}\" is not an accepted type of file. Expected: {}.",
+                file.file_name, expected
+            ))
+        })?;
+
+    if !file_type.matches_name(file.file_name.as_str()) {
+        return Err(rejected(format!(
+            "\"{}\" looks like a {}, but its name does not end in .{}.",
+            file.file_name,
+            file_type,
+            file_type.extensions().join(" or .")
+        )));
+    }
+
+    if let Some(scanner) = scan::scanner() {
+        let reason: Option<String> = match scanner.scan(file.bytes.clone()).await {
+            Ok(Verdict::Clean) => None,
+            Ok(Verdict::Infected(signature)) => {
+                Some(format!("{} found {}", scanner.name(), signature))
+            }
+            Err(e) => Some(format!("{} scan failed: {}", scanner.name(), e)),
+        };
+
+        if let Some(reason) = reason {
+            quarantine::quarantine(file.file_name.as_str(), &file.bytes, reason, uploaded_by)?;
+            return Err(rejected(format!(
+                "\"{}\" did not pass the malware scan and was not saved. Contact a \
+                coordinator if you think this is a mistake.",
+                file.file_name
+            )));
+        }
+    }
+
+    return Ok(CheckedUpload {
+        file_name: file.file_name,
+        file_type,
+        bytes: file.bytes,
+    });
+}
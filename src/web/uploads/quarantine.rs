@@ -0,0 +1,115 @@
+//! Quarantine for uploads that failed a scan.
+//!
+//! Quarantined files are kept in the quarantine directory from the config so
+//! that admins can look into them, each next to a JSON file describing what
+//! happened. They are listed on the admin panel until an admin deletes them.
+
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// A quarantined upload.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QuarantinedFile {
+    /// Identifies the file in the quarantine directory.
+    pub id: Uuid,
+    /// The name the file was uploaded with.
+    pub file_name: String,
+    /// The size of the file in bytes.
+    pub size: usize,
+    /// Why the file was quarantined.
+    pub reason: String,
+    /// The user who uploaded the file, if they were logged in.
+    pub uploaded_by: Option<Uuid>,
+    /// When the file was quarantined.
+    pub quarantined_at: DateTime<Utc>,
+}
+
+/// The path of a quarantined file's contents.
+fn contents_path(id: Uuid) -> PathBuf {
+    global_config().uploads.quarantine_dir.join(id.to_string())
+}
+
+/// The path of a quarantined file's description.
+fn metadata_path(id: Uuid) -> PathBuf {
+    global_config()
+        .uploads
+        .quarantine_dir
+        .join(format!("{}.json", id))
+}
+
+/// Convert a file system error into a telescope error.
+fn io_error(e: std::io::Error) -> TelescopeError {
+    TelescopeError::ise(format!("Upload quarantine error: {}", e))
+}
+
+/// Put a file in quarantine.
+pub fn quarantine(
+    file_name: &str,
+    bytes: &[u8],
+    reason: String,
+    uploaded_by: Option<Uuid>,
+) -> Result<QuarantinedFile, TelescopeError> {
+    let record = QuarantinedFile {
+        id: Uuid::new_v4(),
+        file_name: file_name.to_string(),
+        size: bytes.len(),
+        reason,
+        uploaded_by,
+        quarantined_at: Utc::now(),
+    };
+
+    fs::create_dir_all(&global_config().uploads.quarantine_dir).map_err(io_error)?;
+    fs::write(contents_path(record.id), bytes).map_err(io_error)?;
+    let metadata = serde_json::to_vec_pretty(&record)
+        .map_err(|e| TelescopeError::ise(format!("Could not describe quarantined file: {}", e)))?;
+    fs::write(metadata_path(record.id), metadata).map_err(io_error)?;
+
+    error!(
+        "Quarantined upload \"{}\" ({}): {}",
+        record.file_name, record.id, record.reason
+    );
+    return Ok(record);
+}
+
+/// List quarantined files, newest first.
+pub fn list() -> Result<Vec<QuarantinedFile>, TelescopeError> {
+    let dir: &PathBuf = &global_config().uploads.quarantine_dir;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files: Vec<QuarantinedFile> = fs::read_dir(dir)
+        .map_err(io_error)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "json"))
+        .filter_map(|path| fs::read(path).ok())
+        .filter_map(|metadata| serde_json::from_slice(&metadata).ok())
+        .collect();
+
+    files.sort_by(|a, b| b.quarantined_at.cmp(&a.quarantined_at));
+    return Ok(files);
+}
+
+/// Count quarantined files. Errors count as none, since this is only used to
+/// flag the quarantine on the admin panel.
+pub fn count() -> usize {
+    list().map(|files| files.len()).unwrap_or(0)
+}
+
+/// Delete a quarantined file. Return whether it existed.
+pub fn delete(id: Uuid) -> Result<bool, TelescopeError> {
+    let metadata: PathBuf = metadata_path(id);
+    if !metadata.exists() {
+        return Ok(false);
+    }
+
+    fs::remove_file(metadata).map_err(io_error)?;
+    // The contents may already be gone if an admin removed them by hand.
+    let _ = fs::remove_file(contents_path(id));
+    return Ok(true);
+}
@@ -0,0 +1,105 @@
+//! Malware scanning for uploaded files.
+//!
+//! Scanning is optional. A scanner is used if one is configured; otherwise
+//! uploads are only checked for their type.
+
+use crate::env::global_config;
+use crate::error::TelescopeError;
+use actix_web::web::block;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+/// The outcome of scanning a file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Verdict {
+    /// Nothing was found.
+    Clean,
+    /// The scanner found something, described by the string.
+    Infected(String),
+}
+
+/// A service that can scan files for malware.
+#[async_trait::async_trait]
+pub trait Scanner: Send + Sync {
+    /// The name of the scanner, for logs and the admin panel.
+    fn name(&self) -> &'static str;
+
+    /// Scan the contents of a file.
+    async fn scan(&self, bytes: Vec<u8>) -> Result<Verdict, TelescopeError>;
+}
+
+/// A ClamAV daemon listening on TCP.
+struct ClamAv {
+    /// The address of the daemon, such as `127.0.0.1:3310`.
+    address: String,
+}
+
+/// How long to wait on the ClamAV daemon before giving up.
+const CLAMAV_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The size of the chunks files are streamed to ClamAV in.
+const CLAMAV_CHUNK_SIZE: usize = 64 * 1024;
+
+impl ClamAv {
+    /// Stream a file to the daemon with the `INSTREAM` command and read its
+    /// reply. This blocks, so it is run on the blocking thread pool.
+    fn scan_blocking(address: &str, bytes: &[u8]) -> std::io::Result<String> {
+        let mut stream = TcpStream::connect(address)?;
+        stream.set_read_timeout(Some(CLAMAV_TIMEOUT))?;
+        stream.set_write_timeout(Some(CLAMAV_TIMEOUT))?;
+
+        stream.write_all(b"zINSTREAM\0")?;
+        for chunk in bytes.chunks(CLAMAV_CHUNK_SIZE) {
+            stream.write_all(&(chunk.len() as u32).to_be_bytes())?;
+            stream.write_all(chunk)?;
+        }
+        // A zero length chunk ends the stream.
+        stream.write_all(&[0; 4])?;
+
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply)?;
+        return Ok(reply.trim_end_matches('\0').trim().to_string());
+    }
+}
+
+#[async_trait::async_trait]
+impl Scanner for ClamAv {
+    fn name(&self) -> &'static str {
+        "ClamAV"
+    }
+
+    async fn scan(&self, bytes: Vec<u8>) -> Result<Verdict, TelescopeError> {
+        let address: String = self.address.clone();
+        let reply: String = block(move || Self::scan_blocking(address.as_str(), &bytes))
+            .await
+            .map_err(|e| TelescopeError::ise(format!("Could not reach ClamAV: {}", e)))?;
+
+        // Replies look like "stream: OK" or "stream: <signature> FOUND".
+        let result: &str = reply.strip_prefix("stream:").unwrap_or(&reply).trim();
+        if result == "OK" {
+            return Ok(Verdict::Clean);
+        }
+        if let Some(signature) = result.strip_suffix("FOUND") {
+            return Ok(Verdict::Infected(signature.trim().to_string()));
+        }
+        return Err(TelescopeError::ise(format!(
+            "ClamAV could not scan the file: {}",
+            reply
+        )));
+    }
+}
+
+lazy_static! {
+    /// The configured scanner.
+    static ref SCANNER: Option<Box<dyn Scanner>> = global_config()
+        .uploads
+        .clamav
+        .clone()
+        .map(|address| Box::new(ClamAv { address }) as Box<dyn Scanner>);
+}
+
+/// Get the configured scanner, if there is one.
+pub fn scanner() -> Option<&'static dyn Scanner> {
+    SCANNER.as_deref()
+}
@@ -0,0 +1,91 @@
+//! Content type sniffing for uploaded files.
+//!
+//! The type of an upload is read from its first bytes rather than trusted from
+//! the browser, and has to match the extension of the file name.
+
+/// A type of file Telescope accepts as an upload.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Display)]
+#[serde(rename_all = "lowercase")]
+pub enum FileType {
+    #[display(fmt = "PNG image")]
+    Png,
+    #[display(fmt = "JPEG image")]
+    Jpeg,
+    #[display(fmt = "GIF image")]
+    Gif,
+    #[display(fmt = "WebP image")]
+    Webp,
+    #[display(fmt = "PDF document")]
+    Pdf,
+    #[display(fmt = "CSV file")]
+    Csv,
+}
+
+impl FileType {
+    /// The MIME type to serve this type of file as.
+    pub fn mime(&self) -> &'static str {
+        match self {
+            FileType::Png => "image/png",
+            FileType::Jpeg => "image/jpeg",
+            FileType::Gif => "image/gif",
+            FileType::Webp => "image/webp",
+            FileType::Pdf => "application/pdf",
+            FileType::Csv => "text/csv",
+        }
+    }
+
+    /// The file extensions this type of file may have, lowercase and without
+    /// the dot.
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            FileType::Png => &["png"],
+            FileType::Jpeg => &["jpg", "jpeg"],
+            FileType::Gif => &["gif"],
+            FileType::Webp => &["webp"],
+            FileType::Pdf => &["pdf"],
+            FileType::Csv => &["csv"],
+        }
+    }
+
+    /// Check if a file name has an extension of this type of file.
+    pub fn matches_name(&self, file_name: &str) -> bool {
+        file_name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_lowercase())
+            .map_or(false, |extension| {
+                self.extensions().contains(&extension.as_str())
+            })
+    }
+}
+
+/// Work out the type of a file from its contents. Return `None` if it is not
+/// a type Telescope accepts.
+pub fn sniff(bytes: &[u8]) -> Option<FileType> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some(FileType::Png);
+    }
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some(FileType::Jpeg);
+    }
+    if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        return Some(FileType::Gif);
+    }
+    if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return Some(FileType::Webp);
+    }
+    if bytes.starts_with(b"%PDF-") {
+        return Some(FileType::Pdf);
+    }
+
+    // CSVs have no signature. Accept text that is valid UTF-8 without control
+    // characters other than whitespace, which rules out most binary formats.
+    let is_text: bool = std::str::from_utf8(bytes).map_or(false, |text| {
+        text.chars()
+            .all(|c| !c.is_control() || c == '\n' || c == '\r' || c == '\t')
+    });
+    if is_text && !bytes.is_empty() {
+        return Some(FileType::Csv);
+    }
+
+    return None;
+}
@@ -0,0 +1,75 @@
+//! Validation and normalization shared by form handlers.
+//!
+//! Validators return the normalized value on success, or the message to show
+//! under the form field as an issue.
+
+use url::{ParseError, Url};
+
+/// The longest URL accepted in a form field.
+pub const MAX_URL_LENGTH: usize = 2048;
+
+/// URL schemes accepted in form fields.
+const ALLOWED_SCHEMES: [&'static str; 2] = ["https", "http"];
+
+/// Validate and normalize an optional URL form field. Empty input is `None`.
+/// Links without a scheme are assumed to be `https`.
+///
+/// If `allowed_domains` is not empty, the URL host must be one of them or a
+/// subdomain of one of them.
+pub fn validate_url(
+    input: Option<&str>,
+    allowed_domains: &[String],
+) -> Result<Option<String>, String> {
+    let input: &str = match input.map(str::trim) {
+        None | Some("") => return Ok(None),
+        Some(input) => input,
+    };
+
+    if input.len() > MAX_URL_LENGTH {
+        return Err(format!(
+            "Links cannot be longer than {} characters.",
+            MAX_URL_LENGTH
+        ));
+    }
+
+    let url: Url = match Url::parse(input) {
+        Ok(url) => url,
+        // Assume a link like "rcos.io/faq" is missing its scheme.
+        Err(ParseError::RelativeUrlWithoutBase) => Url::parse(&format!("https://{}", input))
+            .map_err(|e| format!("This is not a valid link ({}).", e))?,
+        Err(e) => return Err(format!("This is not a valid link ({}).", e)),
+    };
+
+    if !ALLOWED_SCHEMES.contains(&url.scheme()) {
+        return Err(format!(
+            "Links must start with https:// or http://, not {}:.",
+            url.scheme()
+        ));
+    }
+
+    let host: &str = url
+        .host_str()
+        .filter(|host| !host.is_empty())
+        .ok_or_else(|| "Links must include a domain.".to_string())?;
+
+    if !allowed_domains.is_empty() && !domain_allowed(host, allowed_domains) {
+        return Err(format!(
+            "Links to {} are not allowed here. Use one of: {}.",
+            host,
+            allowed_domains.join(", ")
+        ));
+    }
+
+    return Ok(Some(url.to_string()));
+}
+
+/// Check if a host is one of a list of domains, or a subdomain of one.
+fn domain_allowed(host: &str, allowed_domains: &[String]) -> bool {
+    allowed_domains.iter().any(|domain| {
+        let domain: &str = domain.trim_start_matches('.');
+        host.eq_ignore_ascii_case(domain)
+            || host
+                .to_ascii_lowercase()
+                .ends_with(&format!(".{}", domain.to_ascii_lowercase()))
+    })
+}
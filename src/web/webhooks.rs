@@ -0,0 +1,177 @@
+//! Outgoing webhooks for third-party integrations.
+//!
+//! Admins register endpoints in the admin panel and pick the events each one
+//! receives. When an event happens, [`emit`] adds a delivery for every
+//! subscribed endpoint to an outbox in the database, and the
+//! [webhook delivery job](crate::jobs::webhooks) posts them, retrying ones
+//! that fail. Keeping the outbox in the database means deliveries survive
+//! restarts, and a slow endpoint does not slow down the page that caused the
+//! event.
+//!
+//! Every delivery is a JSON body like
+//! `{"event": "meeting.published", "occurred_at": ..., "data": {...}}`,
+//! posted with these headers:
+//!
+//! - `X-Telescope-Event`: the event name.
+//! - `X-Telescope-Delivery`: the delivery's ID, the same on every retry.
+//! - `X-Telescope-Timestamp`: when the attempt was sent, in seconds since the
+//!   Unix epoch.
+//! - `X-Telescope-Signature`: `sha256=` and the hex HMAC-SHA256 of the
+//!   timestamp, a period, and the body, keyed with the endpoint's secret.
+//!   Receivers should compute the same and check that the timestamp is
+//!   recent.
+
+use crate::api::rcos::prelude::MeetingId;
+use crate::api::rcos::webhooks::{QueueWebhookDelivery, WebhookSubscribers};
+use crate::env::global_config;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac, NewMac};
+use serde_json::Value;
+use sha2::Sha256;
+use uuid::Uuid;
+
+/// Header with the event name.
+pub const EVENT_HEADER: &'static str = "X-Telescope-Event";
+
+/// Header with the delivery ID.
+pub const DELIVERY_HEADER: &'static str = "X-Telescope-Delivery";
+
+/// Header with the time a delivery attempt was sent.
+pub const TIMESTAMP_HEADER: &'static str = "X-Telescope-Timestamp";
+
+/// Header with the signature of a delivery attempt.
+pub const SIGNATURE_HEADER: &'static str = "X-Telescope-Signature";
+
+/// The names of the events endpoints can subscribe to, with a description of
+/// each for the admin panel.
+pub const EVENTS: [(&'static str, &'static str); 3] = [
+    (
+        MEETING_PUBLISHED,
+        "A meeting is created or published from a draft.",
+    ),
+    (USER_REGISTERED, "Someone creates an account."),
+    (
+        ATTENDANCE_RECORDED,
+        "A member checks in to a meeting with its attendance code.",
+    ),
+];
+
+const MEETING_PUBLISHED: &'static str = "meeting.published";
+const USER_REGISTERED: &'static str = "user.registered";
+const ATTENDANCE_RECORDED: &'static str = "attendance.recorded";
+
+/// Something that happened which webhook endpoints can be told about.
+#[derive(Clone, Debug)]
+pub enum WebhookEvent {
+    /// A meeting became visible to members.
+    MeetingPublished {
+        meeting_id: MeetingId,
+        title: Option<String>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+
+    /// An account was created.
+    UserRegistered { user_id: Uuid },
+
+    /// A member checked in to a meeting.
+    AttendanceRecorded {
+        meeting_id: MeetingId,
+        user_id: Uuid,
+    },
+}
+
+impl WebhookEvent {
+    /// The name endpoints subscribe to this event by.
+    pub fn name(&self) -> &'static str {
+        match self {
+            WebhookEvent::MeetingPublished { .. } => MEETING_PUBLISHED,
+            WebhookEvent::UserRegistered { .. } => USER_REGISTERED,
+            WebhookEvent::AttendanceRecorded { .. } => ATTENDANCE_RECORDED,
+        }
+    }
+
+    /// The details of this event sent to endpoints.
+    fn data(&self) -> Value {
+        let telescope_url: &str = global_config().telescope_url.as_str();
+        match self {
+            WebhookEvent::MeetingPublished {
+                meeting_id,
+                title,
+                start,
+                end,
+            } => json!({
+                "meeting_id": meeting_id,
+                "title": title,
+                "start_date_time": start,
+                "end_date_time": end,
+                "url": format!("{}/meeting/{}", telescope_url, meeting_id),
+            }),
+
+            WebhookEvent::UserRegistered { user_id } => json!({
+                "user_id": user_id,
+                "url": format!("{}/user/{}", telescope_url, user_id),
+            }),
+
+            WebhookEvent::AttendanceRecorded {
+                meeting_id,
+                user_id,
+            } => json!({
+                "meeting_id": meeting_id,
+                "user_id": user_id,
+            }),
+        }
+    }
+}
+
+/// Queue an event for every endpoint subscribed to it. Failures are logged
+/// rather than returned, since the event already happened and whatever
+/// caused it should not fail because of a webhook.
+pub async fn emit(event: WebhookEvent) {
+    let name: &str = event.name();
+    let endpoints: Vec<i64> = match WebhookSubscribers::get(name).await {
+        Ok(endpoints) => endpoints,
+        Err(e) => {
+            error!("Could not get webhook endpoints for {} event: {}", name, e);
+            return;
+        }
+    };
+
+    if endpoints.is_empty() {
+        return;
+    }
+
+    let payload: String = json!({
+        "event": name,
+        "occurred_at": Utc::now(),
+        "data": event.data(),
+    })
+    .to_string();
+
+    for endpoint_id in endpoints {
+        if let Err(e) = QueueWebhookDelivery::execute(endpoint_id, name, payload.clone()).await {
+            error!(
+                "Could not queue {} event for webhook endpoint {}: {}",
+                name, endpoint_id, e
+            );
+        }
+    }
+}
+
+/// Sign a delivery body sent at a timestamp, in the form used by the
+/// signature header.
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    // HMAC takes keys of any length.
+    let mut mac = Hmac::<Sha256>::new_varkey(secret.as_bytes()).expect("Invalid HMAC key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+
+    let digest: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    return format!("sha256={}", digest);
+}
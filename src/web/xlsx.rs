@@ -0,0 +1,192 @@
+//! Spreadsheet (XLSX) builder for exports.
+//!
+//! Each sheet is serialized to XML row by row as it is added, so large exports
+//! never hold more than one row of cells at a time. The sheets are only zipped
+//! into a workbook when it is finished.
+
+use crate::error::TelescopeError;
+use actix_web::http::header::{self, ContentDisposition, DispositionParam, DispositionType};
+use actix_web::HttpResponse;
+use simple_excel_writer::{CellValue, Row, Workbook};
+use std::collections::HashSet;
+
+/// The longest sheet name Excel accepts.
+const MAX_SHEET_NAME_CHARS: usize = 31;
+
+/// Characters Excel does not allow in sheet names.
+const INVALID_SHEET_NAME_CHARS: &[char] = &['[', ']', ':', '*', '?', '/', '\\'];
+
+/// The content type of XLSX files.
+const XLSX_CONTENT_TYPE: &'static str =
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+/// A single cell value.
+#[derive(Clone, Debug)]
+pub enum Cell {
+    Text(String),
+    Number(f64),
+    Bool(bool),
+    Empty,
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Cell::Text(text)
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Cell::Text(text.to_string())
+    }
+}
+
+impl From<i64> for Cell {
+    fn from(number: i64) -> Self {
+        Cell::Number(number as f64)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(number: f64) -> Self {
+        Cell::Number(number)
+    }
+}
+
+impl From<bool> for Cell {
+    fn from(value: bool) -> Self {
+        Cell::Bool(value)
+    }
+}
+
+impl<T: Into<Cell>> From<Option<T>> for Cell {
+    fn from(value: Option<T>) -> Self {
+        value.map(Into::into).unwrap_or(Cell::Empty)
+    }
+}
+
+impl Cell {
+    fn into_value(self) -> CellValue {
+        match self {
+            // Always write text as a string, even if it starts with '=', so that
+            // user entered values can never become formulas.
+            Cell::Text(text) => CellValue::String(text),
+            Cell::Number(number) => CellValue::Number(number),
+            Cell::Bool(value) => CellValue::Bool(value),
+            Cell::Empty => CellValue::Blank(1),
+        }
+    }
+}
+
+/// An XLSX workbook being built.
+pub struct Spreadsheet {
+    workbook: Workbook,
+    /// Sheet names used so far, lowercased, since Excel requires them to be unique.
+    sheet_names: HashSet<String>,
+}
+
+impl Spreadsheet {
+    /// Create an empty spreadsheet.
+    pub fn new() -> Self {
+        Self {
+            workbook: Workbook::create_in_memory(),
+            sheet_names: HashSet::new(),
+        }
+    }
+
+    /// Make a sheet name Excel accepts and that is not used yet.
+    fn sheet_name(&mut self, name: &str) -> String {
+        let cleaned: String = name
+            .chars()
+            .map(|c| {
+                if INVALID_SHEET_NAME_CHARS.contains(&c) {
+                    '-'
+                } else {
+                    c
+                }
+            })
+            .collect::<String>()
+            .trim()
+            .to_string();
+        let base: String = if cleaned.is_empty() {
+            "Sheet".to_string()
+        } else {
+            cleaned
+        };
+
+        let mut candidate: String = base.chars().take(MAX_SHEET_NAME_CHARS).collect();
+        let mut suffix: usize = 2;
+        while self.sheet_names.contains(&candidate.to_lowercase()) {
+            let tail = format!(" ({})", suffix);
+            let head: String = base
+                .chars()
+                .take(MAX_SHEET_NAME_CHARS - tail.chars().count())
+                .collect();
+            candidate = format!("{}{}", head, tail);
+            suffix += 1;
+        }
+
+        self.sheet_names.insert(candidate.to_lowercase());
+        return candidate;
+    }
+
+    /// Add a sheet with a header row followed by data rows.
+    pub fn add_sheet<I>(
+        &mut self,
+        name: &str,
+        headers: &[&str],
+        rows: I,
+    ) -> Result<(), TelescopeError>
+    where
+        I: IntoIterator<Item = Vec<Cell>>,
+    {
+        let name: String = self.sheet_name(name);
+        let mut sheet = self.workbook.create_sheet(name.as_str());
+        self.workbook
+            .write_sheet(&mut sheet, |writer| {
+                let mut header_row = Row::new();
+                for header in headers {
+                    header_row.add_cell(CellValue::String(header.to_string()));
+                }
+                writer.append_row(header_row)?;
+
+                for cells in rows {
+                    let mut row = Row::new();
+                    for cell in cells {
+                        row.add_cell(cell.into_value());
+                    }
+                    writer.append_row(row)?;
+                }
+                Ok(())
+            })
+            .map_err(|e| TelescopeError::ise(format!("Could not write sheet {}: {}", name, e)))
+    }
+
+    /// Zip the sheets into an XLSX file.
+    pub fn finish(mut self) -> Result<Vec<u8>, TelescopeError> {
+        self.workbook
+            .close()
+            .map_err(|e| TelescopeError::ise(format!("Could not finish spreadsheet: {}", e)))?
+            .ok_or(TelescopeError::ise(
+                "Spreadsheet was not written to memory.",
+            ))
+    }
+
+    /// Finish this spreadsheet into a file download response.
+    pub fn into_response(
+        self,
+        filename: impl Into<String>,
+    ) -> Result<HttpResponse, TelescopeError> {
+        let body: Vec<u8> = self.finish()?;
+        return Ok(HttpResponse::Ok()
+            .set_header(header::CONTENT_TYPE, XLSX_CONTENT_TYPE)
+            .set_header(
+                header::CONTENT_DISPOSITION,
+                ContentDisposition {
+                    disposition: DispositionType::Attachment,
+                    parameters: vec![DispositionParam::Filename(filename.into())],
+                },
+            )
+            .body(body));
+    }
+}
@@ -0,0 +1,101 @@
+// Navbar quick switcher. Destinations come from /api/quick-nav the first time
+// the dialog opens. Arrow keys move the selection and Enter follows it.
+$(document).ready(function () {
+    let dialog = $("#quick-nav");
+    let input = $("#quick-nav-input");
+    let list = $("#quick-nav-list");
+    let status = $("#quick-nav-status");
+
+    let destinations = null;
+    let shown = [];
+    let selected = 0;
+
+    // Show the destinations matching the filter, grouped by heading.
+    function render() {
+        let filter = input.val().trim().toLowerCase();
+        shown = (destinations || []).filter(function (d) {
+            return (d.label + " " + d.group).toLowerCase().includes(filter);
+        });
+        selected = Math.min(selected, Math.max(shown.length - 1, 0));
+
+        list.empty();
+        let group = null;
+        shown.forEach(function (d, index) {
+            if (d.group !== group) {
+                group = d.group;
+                list.append($("<li>", {class: "list-group-item bg-secondary text-light py-1 small", role: "presentation"}).text(group));
+            }
+            let item = $("<li>", {
+                id: "quick-nav-option-" + index,
+                class: "list-group-item list-group-item-action" + (index === selected ? " active" : ""),
+                role: "option",
+                "aria-selected": index === selected,
+            }).text(d.label);
+            if (d.hint) {
+                item.append($("<small>", {class: "float-right"}).text(d.hint));
+            }
+            item.on("click", function () {
+                window.location.href = d.url;
+            });
+            list.append(item);
+        });
+
+        input.attr("aria-activedescendant", shown.length ? "quick-nav-option-" + selected : null);
+        status.text(destinations === null ? "Loading..." : shown.length + " destinations");
+    }
+
+    function open() {
+        dialog.modal("show");
+    }
+
+    dialog.on("shown.bs.modal", function () {
+        input.val("").trigger("focus");
+        selected = 0;
+        render();
+        if (destinations === null) {
+            fetch("/api/quick-nav", {credentials: "same-origin"})
+                .then(function (response) { return response.json(); })
+                .then(function (data) {
+                    destinations = data.destinations;
+                    render();
+                })
+                .catch(function () {
+                    status.text("Could not load destinations.");
+                });
+        }
+    });
+
+    input.on("input", function () {
+        selected = 0;
+        render();
+    });
+
+    input.on("keydown", function (event) {
+        if (event.key === "ArrowDown") {
+            selected = Math.min(selected + 1, shown.length - 1);
+        } else if (event.key === "ArrowUp") {
+            selected = Math.max(selected - 1, 0);
+        } else if (event.key === "Enter" && shown[selected]) {
+            window.location.href = shown[selected].url;
+            return;
+        } else {
+            return;
+        }
+        event.preventDefault();
+        render();
+        let option = document.getElementById("quick-nav-option-" + selected);
+        if (option) {
+            option.scrollIntoView({block: "nearest"});
+        }
+    });
+
+    $("#quick-nav-open").on("click", open);
+
+    // Ctrl+K (or Cmd+K) opens the switcher from anywhere.
+    $(document).on("keydown", function (event) {
+        if ((event.ctrlKey || event.metaKey) && event.key.toLowerCase() === "k") {
+            event.preventDefault();
+            open();
+        }
+    });
+});
@@ -20,3 +20,19 @@ $(document).ready(function () {
 
 });
 
+// When a meeting type with a default length is picked on the meeting forms,
+// set the end of the meeting that long after the start.
+function meeting_type_changed() {
+    const duration = $('#meeting-type-select option:selected').data('duration');
+    const start_date = $('#start-date-input').val();
+    const start_time = $('#start-time-input').val();
+    if (!duration || !start_date || !start_time) {
+        return;
+    }
+
+    // Do the math in UTC so that the browser's timezone does not matter.
+    const end = new Date(`${start_date}T${start_time}Z`);
+    end.setUTCMinutes(end.getUTCMinutes() + duration);
+    $('#end-date-input').val(end.toISOString().slice(0, 10));
+    $('#end-time-input').val(end.toISOString().slice(11, 16));
+}